@@ -10,6 +10,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use base64::Engine;
+use termihub_core::connection::LineControl;
 use tokio::net::UnixStream;
 use tokio::sync::Mutex;
 use tracing::{debug, error, info, warn};
@@ -17,6 +18,7 @@ use tracing::{debug, error, info, warn};
 use crate::daemon::protocol::{self, *};
 use crate::io::transport::NotificationSender;
 use crate::protocol::messages::JsonRpcNotification;
+use crate::session::recording::SessionRecordings;
 
 /// How long to wait for the daemon socket to appear after spawning.
 const SOCKET_WAIT_TIMEOUT: Duration = Duration::from_secs(5);
@@ -40,6 +42,8 @@ pub struct DaemonClient {
     alive: Arc<AtomicBool>,
     /// Notification channel to the transport loop.
     notification_tx: NotificationSender,
+    /// Recordings that live output is written to.
+    recordings: SessionRecordings,
 }
 
 impl DaemonClient {
@@ -70,9 +74,15 @@ impl DaemonClient {
         session_id: String,
         socket_path: PathBuf,
         notification_tx: NotificationSender,
+        recordings: SessionRecordings,
     ) -> Result<Self, anyhow::Error> {
-        let (writer, reader_task, alive) =
-            connect_and_start_reader(&socket_path, &session_id, notification_tx.clone()).await?;
+        let (writer, reader_task, alive) = connect_and_start_reader(
+            &socket_path,
+            &session_id,
+            notification_tx.clone(),
+            recordings.clone(),
+        )
+        .await?;
 
         Ok(Self {
             session_id,
@@ -81,6 +91,7 @@ impl DaemonClient {
             reader_task: Some(reader_task),
             alive,
             notification_tx,
+            recordings,
         })
     }
 
@@ -107,6 +118,7 @@ impl DaemonClient {
             &self.socket_path,
             &self.session_id,
             self.notification_tx.clone(),
+            self.recordings.clone(),
         )
         .await?;
 
@@ -139,6 +151,19 @@ impl DaemonClient {
         Ok(())
     }
 
+    /// Send a serial line control request to the daemon's connection.
+    ///
+    /// Failures at the connection come back as a `MSG_ERROR` frame.
+    pub async fn line_control(&self, control: LineControl) -> Result<(), anyhow::Error> {
+        let mut guard = self.writer.lock().await;
+        let writer = guard
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("Not connected to daemon"))?;
+        let payload = protocol::encode_line_control(&control);
+        protocol::write_frame_async(writer, MSG_LINE_CONTROL, &payload).await?;
+        Ok(())
+    }
+
     /// Send kill frame and disconnect.
     pub async fn close(&mut self) {
         // Send Kill frame if connected
@@ -244,6 +269,7 @@ async fn connect_and_start_reader(
     socket_path: &Path,
     session_id: &str,
     notification_tx: NotificationSender,
+    recordings: SessionRecordings,
 ) -> Result<
     (
         tokio::net::unix::OwnedWriteHalf,
@@ -274,7 +300,12 @@ async fn connect_and_start_reader(
                 MSG_BUFFER_REPLAY => {
                     // Send as connection.output if non-empty
                     if !frame.payload.is_empty() {
-                        send_output_notification(&notification_tx, session_id, &frame.payload);
+                        send_output_notification(
+                            &notification_tx,
+                            session_id,
+                            &frame.payload,
+                            true,
+                        );
                     }
                 }
                 MSG_READY => {
@@ -312,28 +343,33 @@ async fn connect_and_start_reader(
     let tx = notification_tx.clone();
 
     let reader_task = tokio::spawn(async move {
-        reader_loop(reader, &session_id_owned, &tx, &alive_clone).await;
+        reader_loop(reader, &session_id_owned, &tx, &recordings, &alive_clone).await;
     });
 
     Ok((writer, reader_task, alive))
 }
 
 /// Background task that reads frames from the daemon and sends notifications.
+///
+/// Live output is also written to the session's recording, if one is
+/// running; buffer replays repeat earlier output and are not recorded.
 async fn reader_loop(
     mut reader: tokio::net::unix::OwnedReadHalf,
     session_id: &str,
     notification_tx: &NotificationSender,
+    recordings: &SessionRecordings,
     alive: &AtomicBool,
 ) {
     loop {
         match protocol::read_frame_async(&mut reader).await {
             Ok(Some(frame)) => match frame.msg_type {
                 MSG_OUTPUT => {
-                    send_output_notification(notification_tx, session_id, &frame.payload);
+                    recordings.record_output(session_id, &frame.payload);
+                    send_output_notification(notification_tx, session_id, &frame.payload, false);
                 }
                 MSG_BUFFER_REPLAY => {
                     if !frame.payload.is_empty() {
-                        send_output_notification(notification_tx, session_id, &frame.payload);
+                        send_output_notification(notification_tx, session_id, &frame.payload, true);
                     }
                 }
                 MSG_EXITED => {
@@ -390,17 +426,63 @@ async fn reader_loop(
 /// Send output data as a base64-encoded `connection.output` notification.
 ///
 /// Chunks large payloads to stay under the 1 MiB NDJSON line limit.
-fn send_output_notification(tx: &NotificationSender, session_id: &str, data: &[u8]) {
+/// Buffer replays are marked with `"replay": true` so the client can tell
+/// scrollback from live output.
+fn send_output_notification(tx: &NotificationSender, session_id: &str, data: &[u8], replay: bool) {
     let b64 = base64::engine::general_purpose::STANDARD;
     for chunk in data.chunks(65536) {
-        let encoded = b64.encode(chunk);
-        let notification = JsonRpcNotification::new(
-            "connection.output",
-            serde_json::json!({
-                "session_id": session_id,
-                "data": encoded,
-            }),
-        );
-        let _ = tx.send(notification);
+        let mut params = serde_json::json!({
+            "session_id": session_id,
+            "data": b64.encode(chunk),
+        });
+        if replay {
+            params["replay"] = serde_json::json!(true);
+        }
+        let _ = tx.send(JsonRpcNotification::new("connection.output", params));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sent(data: &[u8], replay: bool) -> Vec<serde_json::Value> {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        send_output_notification(&tx, "s1", data, replay);
+        drop(tx);
+        let mut params = Vec::new();
+        while let Ok(notification) = rx.try_recv() {
+            assert_eq!(notification.method, "connection.output");
+            params.push(notification.params);
+        }
+        params
+    }
+
+    #[test]
+    fn replayed_output_is_marked() {
+        let b64 = base64::engine::general_purpose::STANDARD;
+        let params = sent(b"scrollback", true);
+        assert_eq!(params.len(), 1);
+        assert_eq!(params[0]["replay"], true);
+        assert_eq!(params[0]["data"], b64.encode(b"scrollback"));
+
+        let params = sent(b"live", false);
+        assert!(params[0].get("replay").is_none());
+    }
+
+    #[test]
+    fn large_replay_is_chunked_in_order() {
+        let b64 = base64::engine::general_purpose::STANDARD;
+        let data: Vec<u8> = (0..150_000u32).map(|i| (i % 251) as u8).collect();
+        let params = sent(&data, true);
+        assert_eq!(params.len(), 3);
+        let joined: Vec<u8> = params
+            .iter()
+            .flat_map(|p| {
+                assert_eq!(p["replay"], true);
+                b64.decode(p["data"].as_str().unwrap()).unwrap()
+            })
+            .collect();
+        assert_eq!(joined, data);
     }
 }
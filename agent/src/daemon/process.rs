@@ -6,9 +6,14 @@
 //!
 //! The daemon keeps the connection alive independently of the agent
 //! process. When the agent disconnects and reconnects, the daemon
-//! replays the ring buffer to bring the agent up to date.
+//! replays the newest part of the ring buffer to bring the agent up to
+//! date.
 
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use nix::sys::stat::{utimensat, UtimensatFlags};
+use nix::sys::time::TimeSpec;
 
 use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
 use tokio::net::UnixListener;
@@ -17,11 +22,17 @@ use tracing::{debug, info, warn};
 
 use crate::daemon::protocol::{self, *};
 use termihub_core::buffer::RingBuffer;
-use termihub_core::connection::{ConnectionType, OutputReceiver};
+use termihub_core::connection::{ConnectionType, LineControl, OutputReceiver};
 
 /// Default ring buffer size: 1 MiB.
 const DEFAULT_BUFFER_SIZE: usize = 1_048_576;
 
+/// Default cap on the bytes replayed to a newly connected agent: 256 KiB.
+const DEFAULT_REPLAY_LIMIT: usize = 262_144;
+
+/// Minimum time between two output stamps on the socket file.
+const ACTIVITY_STAMP_INTERVAL: Duration = Duration::from_secs(10);
+
 /// Configuration for the session daemon, read from environment variables.
 #[derive(Debug)]
 struct DaemonConfig {
@@ -30,6 +41,7 @@ struct DaemonConfig {
     type_id: String,
     settings: serde_json::Value,
     buffer_size: usize,
+    replay_limit: usize,
 }
 
 impl DaemonConfig {
@@ -42,6 +54,7 @@ impl DaemonConfig {
     /// Optional env vars:
     /// - `TERMIHUB_SOCKET_PATH` — Unix socket path (default: auto-generated)
     /// - `TERMIHUB_BUFFER_SIZE` — ring buffer size in bytes (default: 1 MiB)
+    /// - `TERMIHUB_REPLAY_LIMIT` — max bytes replayed on connect (default: 256 KiB)
     fn from_env(session_id: &str) -> anyhow::Result<Self> {
         let socket_path = std::env::var("TERMIHUB_SOCKET_PATH")
             .map(PathBuf::from)
@@ -60,12 +73,18 @@ impl DaemonConfig {
             .and_then(|s| s.parse().ok())
             .unwrap_or(DEFAULT_BUFFER_SIZE);
 
+        let replay_limit = std::env::var("TERMIHUB_REPLAY_LIMIT")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(DEFAULT_REPLAY_LIMIT);
+
         Ok(Self {
             session_id: session_id.to_string(),
             socket_path,
             type_id,
             settings,
             buffer_size,
+            replay_limit,
         })
     }
 }
@@ -135,7 +154,15 @@ pub async fn run_daemon(session_id: &str) -> anyhow::Result<()> {
     let output_rx = connection.subscribe_output();
 
     // Run the main event loop
-    let result = daemon_loop(connection, output_rx, &listener, config.buffer_size).await;
+    let result = daemon_loop(
+        connection,
+        output_rx,
+        &listener,
+        ActivityStamp::new(config.socket_path.clone()),
+        config.buffer_size,
+        config.replay_limit,
+    )
+    .await;
 
     // Cleanup socket file
     let _ = std::fs::remove_file(&config.socket_path);
@@ -144,12 +171,49 @@ pub async fn run_daemon(session_id: &str) -> anyhow::Result<()> {
     result
 }
 
+/// Records output on the socket file's modification time.
+///
+/// The agent's idle reaper reads it for detached sessions, whose output
+/// it does not receive, so a session that keeps printing is not reaped.
+struct ActivityStamp {
+    path: PathBuf,
+    last: Option<Instant>,
+}
+
+impl ActivityStamp {
+    fn new(path: PathBuf) -> Self {
+        Self { path, last: None }
+    }
+
+    /// Stamp the current time, at most once per [`ACTIVITY_STAMP_INTERVAL`].
+    fn touch(&mut self) {
+        if self
+            .last
+            .is_some_and(|last| last.elapsed() < ACTIVITY_STAMP_INTERVAL)
+        {
+            return;
+        }
+        self.last = Some(Instant::now());
+        if let Err(e) = utimensat(
+            None,
+            &self.path,
+            &TimeSpec::UTIME_OMIT,
+            &TimeSpec::UTIME_NOW,
+            UtimensatFlags::NoFollowSymlink,
+        ) {
+            debug!("Failed to stamp output activity: {e}");
+        }
+    }
+}
+
 /// Commands sent from the agent reader task to the main loop.
 enum AgentCommand {
     /// Raw input bytes for the connection.
     Input(Vec<u8>),
     /// Resize the terminal.
     Resize(u16, u16),
+    /// Serial line control (break, DTR, RTS).
+    LineControl(LineControl),
     /// Agent requested detach.
     Detach,
     /// Agent requested kill.
@@ -162,11 +226,18 @@ enum AgentCommand {
 ///
 /// Multiplexes between connection output, new agent connections, and
 /// agent commands using `tokio::select!`.
+///
+/// A new agent gets up to `replay_limit` of the newest buffered bytes,
+/// then Ready, then live output. Output produced while the replay is
+/// being sent waits in `output_rx`, so it follows the replay without
+/// gaps or repeats.
 async fn daemon_loop(
     mut connection: Box<dyn ConnectionType>,
     mut output_rx: OutputReceiver,
     listener: &UnixListener,
+    mut activity: ActivityStamp,
     buffer_size: usize,
+    replay_limit: usize,
 ) -> anyhow::Result<()> {
     let mut ring_buffer = RingBuffer::new(buffer_size);
     let mut agent_writer: Option<OwnedWriteHalf> = None;
@@ -182,6 +253,7 @@ async fn daemon_loop(
                 match output {
                     Some(data) => {
                         ring_buffer.write(&data);
+                        activity.touch();
 
                         // Forward to agent if connected
                         if let Some(ref mut writer) = agent_writer {
@@ -217,7 +289,7 @@ async fn daemon_loop(
                         let (read_half, mut write_half) = stream.into_split();
 
                         // Send buffer replay
-                        let buffered = ring_buffer.read_all();
+                        let buffered = ring_buffer.replay_from(replay_limit);
                         if !buffered.is_empty()
                             && protocol::write_frame_async(
                                 &mut write_half,
@@ -267,6 +339,19 @@ async fn daemon_loop(
                             warn!("Connection resize error: {e}");
                         }
                     }
+                    Some(AgentCommand::LineControl(control)) => {
+                        if let Err(e) = connection.line_control(control) {
+                            warn!("Connection line control error: {e}");
+                            if let Some(ref mut writer) = agent_writer {
+                                let _ = protocol::write_frame_async(
+                                    writer,
+                                    MSG_ERROR,
+                                    e.to_string().as_bytes(),
+                                )
+                                .await;
+                            }
+                        }
+                    }
                     Some(AgentCommand::Detach) => {
                         info!("Agent requested detach");
                         agent_writer = None;
@@ -311,6 +396,14 @@ async fn agent_reader_loop(mut reader: OwnedReadHalf, tx: mpsc::Sender<AgentComm
                             continue;
                         }
                     }
+                    MSG_LINE_CONTROL => {
+                        if let Some(control) = protocol::decode_line_control(&frame.payload) {
+                            AgentCommand::LineControl(control)
+                        } else {
+                            debug!("Invalid line control payload from agent");
+                            continue;
+                        }
+                    }
                     MSG_DETACH => AgentCommand::Detach,
                     MSG_KILL => AgentCommand::Kill,
                     other => {
@@ -359,6 +452,23 @@ mod tests {
     /// Env var tests mutate the process environment and must run serially.
     static ENV_LOCK: Mutex<()> = Mutex::new(());
 
+    #[test]
+    fn activity_stamp_updates_mtime_once_per_interval() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let old = std::time::SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        file.as_file().set_modified(old).unwrap();
+        let modified = || std::fs::metadata(file.path()).unwrap().modified().unwrap();
+
+        let mut stamp = ActivityStamp::new(file.path().to_path_buf());
+        stamp.touch();
+        assert!(modified() > old);
+
+        // A second touch within the interval leaves the file alone.
+        file.as_file().set_modified(old).unwrap();
+        stamp.touch();
+        assert_eq!(modified(), old);
+    }
+
     #[test]
     fn socket_dir_contains_user() {
         let dir = socket_dir();
@@ -388,6 +498,7 @@ mod tests {
         std::env::set_var("TERMIHUB_SETTINGS", r#"{"host":"192.168.1.1","port":22}"#);
         std::env::set_var("TERMIHUB_SOCKET_PATH", "/tmp/test-daemon.sock");
         std::env::set_var("TERMIHUB_BUFFER_SIZE", "2097152");
+        std::env::set_var("TERMIHUB_REPLAY_LIMIT", "4096");
 
         let config = DaemonConfig::from_env("test-456").unwrap();
         assert_eq!(config.session_id, "test-456");
@@ -396,12 +507,14 @@ mod tests {
         assert_eq!(config.settings["port"], 22);
         assert_eq!(config.socket_path, PathBuf::from("/tmp/test-daemon.sock"));
         assert_eq!(config.buffer_size, 2097152);
+        assert_eq!(config.replay_limit, 4096);
 
         // Clean up
         std::env::remove_var("TERMIHUB_TYPE_ID");
         std::env::remove_var("TERMIHUB_SETTINGS");
         std::env::remove_var("TERMIHUB_SOCKET_PATH");
         std::env::remove_var("TERMIHUB_BUFFER_SIZE");
+        std::env::remove_var("TERMIHUB_REPLAY_LIMIT");
     }
 
     #[test]
@@ -412,11 +525,13 @@ mod tests {
         std::env::remove_var("TERMIHUB_SETTINGS");
         std::env::remove_var("TERMIHUB_SOCKET_PATH");
         std::env::remove_var("TERMIHUB_BUFFER_SIZE");
+        std::env::remove_var("TERMIHUB_REPLAY_LIMIT");
 
         let config = DaemonConfig::from_env("test-789").unwrap();
         assert_eq!(config.type_id, "local");
         assert_eq!(config.settings, serde_json::json!({}));
         assert_eq!(config.buffer_size, DEFAULT_BUFFER_SIZE);
+        assert_eq!(config.replay_limit, DEFAULT_REPLAY_LIMIT);
         assert!(config
             .socket_path
             .to_string_lossy()
@@ -10,6 +10,7 @@ use std::io;
 #[cfg(test)]
 use std::io::{Read, Write};
 
+use termihub_core::connection::LineControl;
 #[cfg(unix)]
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 #[cfg(unix)]
@@ -25,10 +26,12 @@ pub const MSG_RESIZE: u8 = 0x02;
 pub const MSG_DETACH: u8 = 0x03;
 /// Agent → Daemon: kill shell and exit (empty payload).
 pub const MSG_KILL: u8 = 0x04;
+/// Agent → Daemon: serial line control (payload: JSON `LineControl`).
+pub const MSG_LINE_CONTROL: u8 = 0x05;
 
 /// Daemon → Agent: output bytes from the PTY.
 pub const MSG_OUTPUT: u8 = 0x81;
-/// Daemon → Agent: full ring buffer replay on connect.
+/// Daemon → Agent: replay of the newest buffered output on connect.
 pub const MSG_BUFFER_REPLAY: u8 = 0x82;
 /// Daemon → Agent: shell exited (payload: exit_code i32 BE).
 pub const MSG_EXITED: u8 = 0x83;
@@ -178,6 +181,16 @@ pub fn decode_resize(payload: &[u8]) -> Option<(u16, u16)> {
     Some((cols, rows))
 }
 
+/// Encode a line control request into a JSON payload.
+pub fn encode_line_control(control: &LineControl) -> Vec<u8> {
+    serde_json::to_vec(control).expect("LineControl serializes to JSON")
+}
+
+/// Decode a line control request from a JSON payload.
+pub fn decode_line_control(payload: &[u8]) -> Option<LineControl> {
+    serde_json::from_slice(payload).ok()
+}
+
 /// Encode an exit code into a 4-byte payload.
 pub fn encode_exit_code(code: i32) -> [u8; 4] {
     code.to_be_bytes()
@@ -247,6 +260,19 @@ mod tests {
         assert_eq!(rows, 40);
     }
 
+    #[test]
+    fn round_trip_line_control() {
+        let control = LineControl::Break { duration_ms: 250 };
+        let mut buf = Vec::new();
+        write_frame(&mut buf, MSG_LINE_CONTROL, &encode_line_control(&control)).unwrap();
+
+        let mut cursor = Cursor::new(&buf);
+        let frame = read_frame(&mut cursor).unwrap().unwrap();
+        assert_eq!(frame.msg_type, MSG_LINE_CONTROL);
+        assert_eq!(decode_line_control(&frame.payload), Some(control));
+        assert_eq!(decode_line_control(b"{\"type\":\"reset\"}"), None);
+    }
+
     #[test]
     fn round_trip_exit_code() {
         let payload = encode_exit_code(42);
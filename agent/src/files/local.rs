@@ -4,7 +4,10 @@
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
 
+use termihub_core::files::dir_size::{local_dir_size, DIR_SIZE_MAX_ENTRIES};
+use termihub_core::files::grep::{local_grep, GrepMatch, GrepOptions};
 use termihub_core::files::FileEntry;
+use tokio_util::sync::CancellationToken;
 
 use super::{FileBackend, FileError};
 use termihub_core::files::utils::chrono_from_epoch;
@@ -15,7 +18,7 @@ use termihub_core::files::utils::format_permissions;
 ///
 /// Supports `"~"` (bare tilde) and `"~/..."` (tilde-slash prefix). If the
 /// home directory cannot be determined the path is returned unchanged.
-fn expand_tilde(path: &str) -> String {
+pub(crate) fn expand_tilde(path: &str) -> String {
     if path == "~" || path.starts_with("~/") {
         #[cfg(unix)]
         let home = std::env::var("HOME").ok();
@@ -84,10 +87,18 @@ impl FileBackend for LocalFileBackend {
         .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
 
-    async fn rename(&self, old_path: &str, new_path: &str) -> Result<(), FileError> {
+    async fn rename(
+        &self,
+        old_path: &str,
+        new_path: &str,
+        overwrite: bool,
+    ) -> Result<(), FileError> {
         let old = expand_tilde(old_path);
         let new = expand_tilde(new_path);
         tokio::task::spawn_blocking(move || {
+            if !overwrite && std::fs::symlink_metadata(&new).is_ok() {
+                return Err(FileError::DestinationExists(new));
+            }
             std::fs::rename(&old, &new).map_err(|e| map_io_error(e, &old))
         })
         .await
@@ -101,14 +112,47 @@ impl FileBackend for LocalFileBackend {
             .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
 
-    async fn mkdir(&self, path: &str) -> Result<(), FileError> {
+    async fn mkdir(&self, path: &str, recursive: bool) -> Result<(), FileError> {
         let path = expand_tilde(path);
         tokio::task::spawn_blocking(move || {
-            std::fs::create_dir_all(&path).map_err(|e| map_io_error(e, &path))
+            let created = if recursive {
+                std::fs::create_dir_all(&path)
+            } else {
+                std::fs::create_dir(&path)
+            };
+            created.map_err(|e| map_io_error(e, &path))
         })
         .await
         .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
+
+    async fn dir_size(&self, path: &str, cancel: &CancellationToken) -> Result<u64, FileError> {
+        let path = expand_tilde(path);
+        let cancel = cancel.clone();
+        tokio::task::spawn_blocking(move || {
+            local_dir_size(Path::new(&path), &cancel, DIR_SIZE_MAX_ENTRIES)
+        })
+        .await
+        .map_err(|e| FileError::OperationFailed(e.to_string()))?
+    }
+
+    async fn grep(
+        &self,
+        root: &str,
+        pattern: &str,
+        opts: &GrepOptions,
+    ) -> Result<Vec<GrepMatch>, FileError> {
+        let root = expand_tilde(root);
+        let pattern = pattern.to_string();
+        let opts = opts.clone();
+        tokio::task::spawn_blocking(move || local_grep(Path::new(&root), &pattern, &opts))
+            .await
+            .map_err(|e| FileError::OperationFailed(e.to_string()))?
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
 }
 
 /// Map `std::io::Error` to `FileError` based on error kind.
@@ -301,7 +345,7 @@ mod tests {
 
         let backend = LocalFileBackend::new();
         backend
-            .rename(old.to_str().unwrap(), new.to_str().unwrap())
+            .rename(old.to_str().unwrap(), new.to_str().unwrap(), false)
             .await
             .unwrap();
         assert!(!old.exists());
@@ -309,6 +353,30 @@ mod tests {
         assert_eq!(std::fs::read_to_string(&new).unwrap(), "content");
     }
 
+    #[tokio::test]
+    async fn rename_onto_existing_file_requires_overwrite() {
+        let dir = TempDir::new().unwrap();
+        let old = dir.path().join("old.txt");
+        let new = dir.path().join("new.txt");
+        std::fs::write(&old, "old").unwrap();
+        std::fs::write(&new, "new").unwrap();
+
+        let backend = LocalFileBackend::new();
+        let err = backend
+            .rename(old.to_str().unwrap(), new.to_str().unwrap(), false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, FileError::DestinationExists(_)));
+        assert_eq!(std::fs::read_to_string(&new).unwrap(), "new");
+
+        backend
+            .rename(old.to_str().unwrap(), new.to_str().unwrap(), true)
+            .await
+            .unwrap();
+        assert!(!old.exists());
+        assert_eq!(std::fs::read_to_string(&new).unwrap(), "old");
+    }
+
     #[tokio::test]
     async fn stat_file() {
         let dir = TempDir::new().unwrap();
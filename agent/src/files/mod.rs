@@ -4,6 +4,7 @@
 //! will use `ConnectionType::file_browser()` from `termihub_core`.
 
 pub mod local;
+pub mod watch;
 
 pub use termihub_core::errors::FileError;
 pub use termihub_core::files::FileBackend;
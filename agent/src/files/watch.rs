@@ -0,0 +1,544 @@
+//! Directory watches: live change notifications for the file browser.
+//!
+//! Each watch keeps a snapshot of a directory listing and diffs it against
+//! a fresh listing whenever the directory may have changed, sending the
+//! result as a `connection.files.changed` notification. Local directories
+//! on Linux are re-listed when inotify reports an event; other backends
+//! (SSH, Docker) and other platforms poll at a fixed interval.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+use termihub_core::files::FileEntry;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info, warn};
+
+use super::{FileBackend, FileError};
+use crate::io::transport::NotificationSender;
+use crate::protocol::messages::JsonRpcNotification;
+use crate::protocol::methods::FilesChangedNotification;
+
+/// Maximum number of concurrent watches.
+pub const MAX_WATCHES: usize = 32;
+
+/// Default poll interval in milliseconds.
+const DEFAULT_INTERVAL_MS: u64 = 2000;
+
+/// Minimum allowed poll interval in milliseconds.
+const MIN_INTERVAL_MS: u64 = 500;
+
+/// Time to let a burst of native events settle before re-listing.
+#[cfg(target_os = "linux")]
+const NATIVE_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Entries that changed between two listings of the same directory.
+#[derive(Debug, Default)]
+pub struct FileChanges {
+    pub added: Vec<FileEntry>,
+    pub removed: Vec<FileEntry>,
+    pub modified: Vec<FileEntry>,
+}
+
+impl FileChanges {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// Diff two listings of a directory, matching entries by name.
+///
+/// An entry counts as modified when its type, size, modification time or
+/// permissions differ.
+pub fn diff_entries(old: &[FileEntry], new: &[FileEntry]) -> FileChanges {
+    let old_by_name: HashMap<&str, &FileEntry> = old.iter().map(|e| (e.name.as_str(), e)).collect();
+    let new_by_name: HashMap<&str, &FileEntry> = new.iter().map(|e| (e.name.as_str(), e)).collect();
+
+    let mut changes = FileChanges::default();
+    for entry in new {
+        match old_by_name.get(entry.name.as_str()) {
+            None => changes.added.push(entry.clone()),
+            Some(previous) if entry_changed(previous, entry) => {
+                changes.modified.push(entry.clone())
+            }
+            Some(_) => {}
+        }
+    }
+    changes.removed = old
+        .iter()
+        .filter(|e| !new_by_name.contains_key(e.name.as_str()))
+        .cloned()
+        .collect();
+    changes
+}
+
+fn entry_changed(old: &FileEntry, new: &FileEntry) -> bool {
+    old.is_directory != new.is_directory
+        || old.size != new.size
+        || old.modified != new.modified
+        || old.permissions != new.permissions
+}
+
+/// Errors that can occur when starting a watch.
+#[derive(Debug)]
+pub enum WatchError {
+    /// The maximum number of watches has been reached.
+    LimitReached,
+    /// The directory could not be listed.
+    File(FileError),
+}
+
+impl fmt::Display for WatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LimitReached => write!(f, "Watch limit reached (max {MAX_WATCHES})"),
+            Self::File(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// Manages active directory watches.
+///
+/// Each watch runs a background tokio task until it is unwatched, the
+/// client that created it disconnects, or the agent shuts down.
+pub struct FileWatchManager {
+    watches: Mutex<HashMap<String, Watch>>,
+    notification_tx: NotificationSender,
+}
+
+/// An active directory watch.
+struct Watch {
+    cancel: CancellationToken,
+    join_handle: JoinHandle<()>,
+}
+
+impl FileWatchManager {
+    pub fn new(notification_tx: NotificationSender) -> Self {
+        Self {
+            watches: Mutex::new(HashMap::new()),
+            notification_tx,
+        }
+    }
+
+    /// Start watching the directory at `path` and return the watch ID.
+    ///
+    /// The directory is listed once up front, so a missing or unreadable
+    /// path fails here rather than in the background task.
+    pub async fn watch(
+        &self,
+        backend: Box<dyn FileBackend>,
+        path: &str,
+        interval_ms: Option<u64>,
+    ) -> Result<String, WatchError> {
+        let mut watches = self.watches.lock().await;
+        if watches.len() >= MAX_WATCHES {
+            return Err(WatchError::LimitReached);
+        }
+
+        let snapshot = backend.list(path).await.map_err(WatchError::File)?;
+        let interval = Duration::from_millis(
+            interval_ms
+                .unwrap_or(DEFAULT_INTERVAL_MS)
+                .max(MIN_INTERVAL_MS),
+        );
+        let trigger = Trigger::for_backend(backend.as_ref(), path, interval);
+
+        let watch_id = uuid::Uuid::new_v4().to_string();
+        let cancel = CancellationToken::new();
+        let join_handle = tokio::spawn(watch_task(
+            watch_id.clone(),
+            path.to_string(),
+            backend,
+            snapshot,
+            trigger,
+            self.notification_tx.clone(),
+            cancel.clone(),
+        ));
+
+        info!("Started file watch {watch_id} for '{path}'");
+        watches.insert(
+            watch_id.clone(),
+            Watch {
+                cancel,
+                join_handle,
+            },
+        );
+        Ok(watch_id)
+    }
+
+    /// Stop a watch. Returns `true` if it existed.
+    pub async fn unwatch(&self, watch_id: &str) -> bool {
+        let mut watches = self.watches.lock().await;
+        if let Some(watch) = watches.remove(watch_id) {
+            watch.cancel.cancel();
+            watch.join_handle.abort();
+            info!("Stopped file watch {watch_id}");
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Cancel all active watches (called when the client disconnects and
+    /// during agent shutdown).
+    pub async fn shutdown(&self) {
+        let mut watches = self.watches.lock().await;
+        for (watch_id, watch) in watches.drain() {
+            watch.cancel.cancel();
+            watch.join_handle.abort();
+            debug!("Shutdown: cancelled file watch {watch_id}");
+        }
+    }
+}
+
+/// What wakes a watch task to re-list its directory.
+enum Trigger {
+    Poll(Interval),
+    #[cfg(target_os = "linux")]
+    Native(inotify::DirWatch),
+}
+
+impl Trigger {
+    /// Use native notifications for local directories where available,
+    /// falling back to polling every `interval`.
+    fn for_backend(backend: &dyn FileBackend, path: &str, interval: Duration) -> Self {
+        #[cfg(target_os = "linux")]
+        if backend.is_local() {
+            let local_path = super::local::expand_tilde(path);
+            match inotify::DirWatch::new(std::path::Path::new(&local_path)) {
+                Ok(watch) => return Self::Native(watch),
+                Err(e) => warn!("inotify unavailable for '{path}', polling instead: {e}"),
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = (backend, path);
+
+        let mut interval = tokio::time::interval_at(Instant::now() + interval, interval);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Self::Poll(interval)
+    }
+
+    async fn wait(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Poll(interval) => {
+                interval.tick().await;
+                Ok(())
+            }
+            #[cfg(target_os = "linux")]
+            Self::Native(watch) => {
+                watch.changed().await?;
+                tokio::time::sleep(NATIVE_DEBOUNCE).await;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Background task: re-list the directory on every trigger and report
+/// differences to the previous listing.
+async fn watch_task(
+    watch_id: String,
+    path: String,
+    backend: Box<dyn FileBackend>,
+    mut snapshot: Vec<FileEntry>,
+    mut trigger: Trigger,
+    tx: NotificationSender,
+    cancel: CancellationToken,
+) {
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            result = trigger.wait() => {
+                if let Err(e) = result {
+                    warn!("File watch {watch_id} stopped: {e}");
+                    break;
+                }
+            }
+        }
+
+        let entries = match backend.list(&path).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                debug!("File watch {watch_id}: listing '{path}' failed: {e}");
+                continue;
+            }
+        };
+        let changes = diff_entries(&snapshot, &entries);
+        snapshot = entries;
+        if changes.is_empty() {
+            continue;
+        }
+
+        let notification = FilesChangedNotification {
+            watch_id: watch_id.clone(),
+            path: path.clone(),
+            added: changes.added,
+            removed: changes.removed,
+            modified: changes.modified,
+        };
+        let notif = JsonRpcNotification::new(
+            "connection.files.changed",
+            serde_json::to_value(notification).unwrap(),
+        );
+        if tx.send(notif).is_err() {
+            debug!("Notification channel closed, stopping file watch {watch_id}");
+            break;
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod inotify {
+    use std::io;
+    use std::os::fd::{AsFd, AsRawFd, RawFd};
+    use std::path::Path;
+
+    use nix::errno::Errno;
+    use nix::sys::inotify::{AddWatchFlags, InitFlags, Inotify};
+    use tokio::io::unix::AsyncFd;
+
+    /// An inotify instance watching a single directory.
+    pub struct DirWatch {
+        fd: AsyncFd<InotifyFd>,
+    }
+
+    struct InotifyFd(Inotify);
+
+    impl AsRawFd for InotifyFd {
+        fn as_raw_fd(&self) -> RawFd {
+            self.0.as_fd().as_raw_fd()
+        }
+    }
+
+    impl DirWatch {
+        pub fn new(path: &Path) -> io::Result<Self> {
+            let inotify = Inotify::init(InitFlags::IN_NONBLOCK | InitFlags::IN_CLOEXEC)?;
+            inotify.add_watch(
+                path,
+                AddWatchFlags::IN_CREATE
+                    | AddWatchFlags::IN_DELETE
+                    | AddWatchFlags::IN_MODIFY
+                    | AddWatchFlags::IN_ATTRIB
+                    | AddWatchFlags::IN_MOVED_FROM
+                    | AddWatchFlags::IN_MOVED_TO,
+            )?;
+            Ok(Self {
+                fd: AsyncFd::new(InotifyFd(inotify))?,
+            })
+        }
+
+        /// Wait for at least one event, consuming all queued events.
+        pub async fn changed(&mut self) -> io::Result<()> {
+            loop {
+                let mut guard = self.fd.readable().await?;
+                match guard.get_inner().0.read_events() {
+                    Ok(events) if !events.is_empty() => return Ok(()),
+                    Ok(_) | Err(Errno::EAGAIN) => guard.clear_ready(),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use crate::files::local::LocalFileBackend;
+    use serde_json::Value;
+
+    fn entry(name: &str, size: u64, modified: &str) -> FileEntry {
+        FileEntry {
+            name: name.to_string(),
+            path: format!("/dir/{name}"),
+            is_directory: false,
+            size,
+            modified: modified.to_string(),
+            permissions: Some("rw-r--r--".to_string()),
+        }
+    }
+
+    #[test]
+    fn diff_detects_added_removed_and_modified() {
+        let old = vec![
+            entry("keep.txt", 10, "2026-01-01T00:00:00Z"),
+            entry("gone.txt", 5, "2026-01-01T00:00:00Z"),
+            entry("grown.txt", 1, "2026-01-01T00:00:00Z"),
+            entry("touched.txt", 1, "2026-01-01T00:00:00Z"),
+        ];
+        let new = vec![
+            entry("keep.txt", 10, "2026-01-01T00:00:00Z"),
+            entry("grown.txt", 2, "2026-01-01T00:00:00Z"),
+            entry("touched.txt", 1, "2026-01-02T00:00:00Z"),
+            entry("new.txt", 0, "2026-01-02T00:00:00Z"),
+        ];
+        let changes = diff_entries(&old, &new);
+        let names = |entries: &[FileEntry]| -> Vec<String> {
+            entries.iter().map(|e| e.name.clone()).collect()
+        };
+        assert_eq!(names(&changes.added), vec!["new.txt"]);
+        assert_eq!(names(&changes.removed), vec!["gone.txt"]);
+        assert_eq!(names(&changes.modified), vec!["grown.txt", "touched.txt"]);
+    }
+
+    #[test]
+    fn diff_of_identical_listings_is_empty() {
+        let listing = vec![entry("a", 1, "2026-01-01T00:00:00Z")];
+        assert!(diff_entries(&listing, &listing).is_empty());
+    }
+
+    #[test]
+    fn diff_detects_type_and_permission_changes() {
+        let old = vec![entry("x", 0, "t")];
+        let mut as_dir = entry("x", 0, "t");
+        as_dir.is_directory = true;
+        assert_eq!(diff_entries(&old, &[as_dir]).modified.len(), 1);
+
+        let mut chmod = entry("x", 0, "t");
+        chmod.permissions = Some("rwxr-xr-x".to_string());
+        assert_eq!(diff_entries(&old, &[chmod]).modified.len(), 1);
+    }
+
+    async fn next_change(
+        rx: &mut tokio::sync::mpsc::UnboundedReceiver<JsonRpcNotification>,
+    ) -> Value {
+        let notif = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("no change notification within 5s")
+            .expect("channel closed");
+        assert_eq!(notif.method, "connection.files.changed");
+        notif.params
+    }
+
+    #[tokio::test]
+    async fn local_watch_reports_created_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_string_lossy().into_owned();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let manager = FileWatchManager::new(tx);
+
+        let watch_id = manager
+            .watch(Box::new(LocalFileBackend::new()), &path, Some(500))
+            .await
+            .unwrap();
+        std::fs::write(dir.path().join("created.txt"), b"hello").unwrap();
+
+        let params = next_change(&mut rx).await;
+        assert_eq!(params["watchId"], watch_id.as_str());
+        assert_eq!(params["path"], path.as_str());
+        assert_eq!(params["added"][0]["name"], "created.txt");
+        assert!(params["removed"].as_array().unwrap().is_empty());
+
+        assert!(manager.unwatch(&watch_id).await);
+        assert!(!manager.unwatch(&watch_id).await);
+    }
+
+    /// Remote-style backend whose listing is set by the test.
+    struct StubBackend {
+        listing: Arc<std::sync::Mutex<Vec<FileEntry>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl FileBackend for StubBackend {
+        async fn list(&self, _path: &str) -> Result<Vec<FileEntry>, FileError> {
+            Ok(self.listing.lock().unwrap().clone())
+        }
+        async fn read(&self, _path: &str) -> Result<Vec<u8>, FileError> {
+            Err(FileError::NotSupported)
+        }
+        async fn write(&self, _path: &str, _data: &[u8]) -> Result<(), FileError> {
+            Err(FileError::NotSupported)
+        }
+        async fn delete(&self, _path: &str, _is_directory: bool) -> Result<(), FileError> {
+            Err(FileError::NotSupported)
+        }
+        async fn rename(
+            &self,
+            _old_path: &str,
+            _new_path: &str,
+            _overwrite: bool,
+        ) -> Result<(), FileError> {
+            Err(FileError::NotSupported)
+        }
+        async fn stat(&self, _path: &str) -> Result<FileEntry, FileError> {
+            Err(FileError::NotSupported)
+        }
+        async fn mkdir(&self, _path: &str, _recursive: bool) -> Result<(), FileError> {
+            Err(FileError::NotSupported)
+        }
+    }
+
+    #[tokio::test]
+    async fn polled_watch_reports_listing_changes() {
+        let listing = Arc::new(std::sync::Mutex::new(vec![entry(
+            "old.log",
+            1,
+            "2026-01-01T00:00:00Z",
+        )]));
+        let backend = StubBackend {
+            listing: listing.clone(),
+        };
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let manager = FileWatchManager::new(tx);
+        manager
+            .watch(Box::new(backend), "/dir", Some(500))
+            .await
+            .unwrap();
+
+        *listing.lock().unwrap() = vec![
+            entry("old.log", 2, "2026-01-01T00:00:01Z"),
+            entry("new.log", 0, "2026-01-01T00:00:01Z"),
+        ];
+        let params = next_change(&mut rx).await;
+        assert_eq!(params["added"][0]["name"], "new.log");
+        assert_eq!(params["modified"][0]["name"], "old.log");
+
+        *listing.lock().unwrap() = Vec::new();
+        let params = next_change(&mut rx).await;
+        assert_eq!(params["removed"].as_array().unwrap().len(), 2);
+        manager.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn watch_of_missing_directory_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let missing = dir.path().join("missing").to_string_lossy().into_owned();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let manager = FileWatchManager::new(tx);
+        let result = manager
+            .watch(Box::new(LocalFileBackend::new()), &missing, None)
+            .await;
+        assert!(matches!(result, Err(WatchError::File(_))));
+    }
+
+    #[tokio::test]
+    async fn watch_count_is_bounded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().to_string_lossy().into_owned();
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let manager = FileWatchManager::new(tx);
+        for _ in 0..MAX_WATCHES {
+            manager
+                .watch(Box::new(LocalFileBackend::new()), &path, None)
+                .await
+                .unwrap();
+        }
+        let result = manager
+            .watch(Box::new(LocalFileBackend::new()), &path, None)
+            .await;
+        assert!(matches!(result, Err(WatchError::LimitReached)));
+        manager.shutdown().await;
+
+        // Watches of a client that went away no longer count.
+        manager
+            .watch(Box::new(LocalFileBackend::new()), &path, None)
+            .await
+            .unwrap();
+        manager.shutdown().await;
+    }
+}
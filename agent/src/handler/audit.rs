@@ -0,0 +1,238 @@
+//! Opt-in audit trail of dispatched JSON-RPC requests (`--audit-log`).
+//!
+//! Each request produces one NDJSON line with its method, timestamp,
+//! client, request ID, redacted params, and outcome. Terminal input and
+//! file contents are never written; neither are password-like fields in
+//! connection configs.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{SecondsFormat, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use tracing::warn;
+
+use crate::handler::SECRET_KEYS;
+
+/// Replacement for redacted values.
+pub const REDACTED: &str = "[redacted]";
+
+/// Params fields redacted per method because they carry user data.
+const REDACTED_FIELDS: &[(&str, &[&str])] = &[
+    ("connection.write", &["data"]),
+    ("connection.files.write", &["data"]),
+];
+
+/// How a request ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditOutcome {
+    Success,
+    Error,
+}
+
+/// One audit log line.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry<'a> {
+    pub timestamp: String,
+    pub client: &'a str,
+    pub method: &'a str,
+    pub id: &'a Value,
+    pub params: Value,
+    pub outcome: AuditOutcome,
+    /// JSON-RPC error code when `outcome` is `error`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<i64>,
+}
+
+impl<'a> AuditEntry<'a> {
+    /// Build an entry stamped with the current time, redacting `params`.
+    pub fn new(
+        client: &'a str,
+        method: &'a str,
+        id: &'a Value,
+        params: &Value,
+        error_code: Option<i64>,
+    ) -> Self {
+        Self {
+            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            client,
+            method,
+            id,
+            params: redact_params(method, params),
+            outcome: match error_code {
+                Some(_) => AuditOutcome::Error,
+                None => AuditOutcome::Success,
+            },
+            error_code,
+        }
+    }
+}
+
+/// Append-only audit log writer, shared by all clients of the agent.
+pub struct AuditLog {
+    writer: Mutex<Box<dyn Write + Send>>,
+}
+
+impl AuditLog {
+    /// Open (or create) the audit log at `path` for appending.
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self::from_writer(file))
+    }
+
+    /// Write entries to an arbitrary writer.
+    pub fn from_writer(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Mutex::new(Box::new(writer)),
+        }
+    }
+
+    /// Append one entry. Write failures are logged, never propagated, so
+    /// a full disk cannot stop the agent from serving requests.
+    pub fn record(&self, entry: &AuditEntry<'_>) {
+        let mut line = match serde_json::to_vec(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Failed to serialize audit entry: {e}");
+                return;
+            }
+        };
+        line.push(b'\n');
+        let mut writer = self
+            .writer
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Err(e) = writer.write_all(&line).and_then(|()| writer.flush()) {
+            warn!("Failed to write audit entry: {e}");
+        }
+    }
+}
+
+/// Copy of `params` safe to write to the audit log.
+pub fn redact_params(method: &str, params: &Value) -> Value {
+    let mut params = params.clone();
+    redact_secrets(&mut params);
+    let fields = REDACTED_FIELDS
+        .iter()
+        .find(|(m, _)| *m == method)
+        .map_or(&[][..], |(_, fields)| fields);
+    if let Some(obj) = params.as_object_mut() {
+        for field in fields {
+            if let Some(value) = obj.get_mut(*field) {
+                *value = Value::String(REDACTED.to_string());
+            }
+        }
+    }
+    params
+}
+
+fn redact_secrets(value: &mut Value) {
+    match value {
+        Value::Object(obj) => {
+            for (key, value) in obj.iter_mut() {
+                if SECRET_KEYS.contains(&key.as_str()) {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_secrets(value);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_secrets),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn redacts_input_data() {
+        let params = json!({"session_id": "s1", "data": "bHMK"});
+        assert_eq!(
+            redact_params("connection.write", &params),
+            json!({"session_id": "s1", "data": REDACTED})
+        );
+    }
+
+    #[test]
+    fn redacts_file_write_data_but_keeps_path() {
+        let params = json!({"path": "/etc/motd", "data": "aGk=", "newline": "lf"});
+        assert_eq!(
+            redact_params("connection.files.write", &params),
+            json!({"path": "/etc/motd", "data": REDACTED, "newline": "lf"})
+        );
+    }
+
+    #[test]
+    fn keeps_data_of_other_methods() {
+        let params = json!({"data": "kept"});
+        assert_eq!(redact_params("connection.files.read", &params), params);
+    }
+
+    #[test]
+    fn redacts_nested_secrets() {
+        let params = json!({
+            "type": "ssh",
+            "config": {"host": "h", "password": "hunter2", "jump": [{"passphrase": "x"}]}
+        });
+        assert_eq!(
+            redact_params("connection.create", &params),
+            json!({
+                "type": "ssh",
+                "config": {"host": "h", "password": REDACTED, "jump": [{"passphrase": REDACTED}]}
+            })
+        );
+    }
+
+    #[test]
+    fn redacts_every_secret_key() {
+        let params = json!({
+            "config": {
+                "password": "a",
+                "fallbackPassword": "b",
+                "passphrase": "c",
+                "keyPassphrase": "d",
+                "username": "admin"
+            }
+        });
+        let redacted = redact_params("connection.create", &params);
+        for key in SECRET_KEYS {
+            assert_eq!(redacted["config"][key], REDACTED, "{key}");
+        }
+        assert_eq!(redacted["config"]["username"], "admin");
+    }
+
+    #[test]
+    fn open_appends_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let id = json!(1);
+        for _ in 0..2 {
+            let log = AuditLog::open(&path).unwrap();
+            log.record(&AuditEntry::new(
+                "stdio",
+                "health.check",
+                &id,
+                &Value::Null,
+                None,
+            ));
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let entries: Vec<Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["method"], "health.check");
+        assert_eq!(entries[0]["outcome"], "success");
+        assert!(entries[0].get("errorCode").is_none());
+    }
+}
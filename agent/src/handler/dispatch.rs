@@ -1,6 +1,7 @@
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use serde_json::{json, Value};
 use tracing::{debug, warn};
@@ -8,27 +9,39 @@ use tracing::{debug, warn};
 use base64::Engine;
 
 use crate::files::local::LocalFileBackend;
+use crate::files::watch::{FileWatchManager, WatchError};
 use crate::files::{FileBackend, FileError};
-use crate::monitoring::MonitoringManagerApi;
+use crate::handler::audit::{AuditEntry, AuditLog};
+use crate::handler::SECRET_KEYS;
+use crate::log_capture::SharedLogBuffer;
+use crate::monitoring::process::{read_process_sample, ProcessCpuTracker};
+use crate::monitoring::{MonitoringManagerApi, DOCKER_HOST_PREFIX};
 use crate::network;
 use crate::protocol::errors;
 use crate::protocol::messages::{JsonRpcErrorResponse, JsonRpcRequest, JsonRpcResponse};
 use crate::protocol::methods::{
     AgentSettings, AgentSettingsUpdateParams, AgentShutdownParams, AgentShutdownResult,
     Capabilities, ConnectionCreateParams, ConnectionDeleteParams, ConnectionTypesResult,
-    ConnectionUpdateParams, FilesDeleteParams, FilesListParams, FilesListResult, FilesMkdirParams,
-    FilesReadParams, FilesReadResult, FilesRenameParams, FilesStatParams, FilesWriteParams,
-    FolderCreateParams, FolderDeleteParams, FolderUpdateParams, HealthCheckResult,
-    InitializeParams, InitializeResult, MonitoringSubscribeParams, MonitoringUnsubscribeParams,
+    ConnectionUpdateParams, ConnectionsImportParams, ConnectionsImportResult, FilesDeleteParams,
+    FilesGrepParams, FilesGrepResult, FilesListParams, FilesListResult, FilesMkdirParams,
+    FilesReadParams, FilesReadResult, FilesRenameParams, FilesStatParams, FilesUnwatchParams,
+    FilesWatchParams, FilesWatchResult, FilesWriteParams, FolderCreateParams, FolderDeleteParams,
+    FolderUpdateParams, HealthCheckResult, ImportStrategy, InitializeParams, InitializeResult,
+    LogsTailParams, LogsTailResult, MonitoringSubscribeParams, MonitoringUnsubscribeParams,
     NetworkDnsLookupParams, NetworkPingParams, NetworkPortScanParams, NetworkTracerouteParams,
     NetworkWolParams, SessionAttachParams, SessionCloseParams, SessionCreateParams,
-    SessionCreateResult, SessionDetachParams, SessionInputParams, SessionListEntry,
-    SessionListResult, SessionResizeParams,
+    SessionCreateResult, SessionDetachParams, SessionInputParams, SessionLineControlParams,
+    SessionListEntry, SessionListResult, SessionRecordingStartParams, SessionRecordingStopParams,
+    SessionRecordingStopResult, SessionResizeParams, SessionStatsEntry, SessionStatsResult,
+    SUPPORTED_FEATURES,
 };
 use crate::session::definitions::{Connection, ConnectionStoreApi, Folder};
 use crate::session::manager::{
     SessionCreateError, SessionManager, SessionManagerApi, MAX_SESSIONS,
 };
+use crate::state::persistence;
+use termihub_core::connection::{validate_settings, SettingsSchema};
+use termihub_core::files::utils::normalize_newlines;
 
 /// The agent's protocol version.
 ///
@@ -49,6 +62,22 @@ pub struct Dispatcher<M: SessionManagerApi = SessionManager> {
     start_time: Instant,
     /// Runtime settings received from the desktop on initialize or settingsUpdate.
     agent_settings: AgentSettings,
+    /// Previous CPU samples of session processes, for `connection.stats` deltas.
+    process_cpu: Mutex<ProcessCpuTracker>,
+    /// Directory watches for `connection.files.watch`; `None` when the
+    /// transport has no notification channel for them.
+    file_watcher: Option<Arc<FileWatchManager>>,
+    /// Registry type IDs disabled by `--disable-types`. They are hidden
+    /// from the advertised capabilities and refused by `connection.create`.
+    disabled_types: HashSet<String>,
+    /// Audit trail of every request (`--audit-log`), and the client the
+    /// entries are attributed to.
+    audit: Option<(Arc<AuditLog>, String)>,
+    /// Optional protocol features negotiated in `initialize`.
+    features: HashSet<String>,
+    /// Recent tracing output served by `logs.tail`; `None` when the
+    /// agent's logs are not captured.
+    log_buffer: Option<SharedLogBuffer>,
 }
 
 /// The result of dispatching a request: either a success or error response.
@@ -77,6 +106,65 @@ impl DispatchResult {
     }
 }
 
+/// The result of dispatching a JSON-RPC batch.
+pub struct BatchResult {
+    /// Message to send back: an array of responses, a single error for an
+    /// empty batch, or `None` when the batch held only notifications.
+    pub response: Option<Value>,
+    /// Set when a batch item was `agent.shutdown`; later items are skipped.
+    pub shutdown: bool,
+}
+
+/// The optional feature a method belongs to, if any. Such methods are
+/// refused unless the feature was negotiated in `initialize`.
+fn required_feature(method: &str) -> Option<&'static str> {
+    match method {
+        "connection.files.watch" | "connection.files.unwatch" => Some("watch"),
+        _ => None,
+    }
+}
+
+/// Whether `method` is left to finish past the request timeout.
+///
+/// Abandoning these mid-flight could leave a session nobody knows about
+/// or a half-written, half-renamed or half-deleted file, so they are never
+/// cut off.
+fn runs_to_completion(method: &str) -> bool {
+    matches!(
+        method,
+        "connection.create"
+            | "connection.files.write"
+            | "connection.files.rename"
+            | "connection.files.delete"
+            | "connection.files.mkdir"
+    )
+}
+
+/// Parse one batch item, mapping malformed items to `INVALID_REQUEST`.
+///
+/// Items without an `id` are notifications and are parsed with a `null` id.
+fn parse_batch_item(mut item: Value) -> Result<JsonRpcRequest, JsonRpcErrorResponse> {
+    let id = item.get("id").cloned().unwrap_or(Value::Null);
+    if let Some(obj) = item.as_object_mut() {
+        obj.entry("id").or_insert(Value::Null);
+    }
+    let request: JsonRpcRequest = serde_json::from_value(item).map_err(|e| {
+        JsonRpcErrorResponse::new(
+            id.clone(),
+            errors::INVALID_REQUEST,
+            format!("Invalid request: {e}"),
+        )
+    })?;
+    if request.jsonrpc != "2.0" {
+        return Err(JsonRpcErrorResponse::new(
+            id,
+            errors::INVALID_REQUEST,
+            "Invalid JSON-RPC version (must be \"2.0\")",
+        ));
+    }
+    Ok(request)
+}
+
 impl<M: SessionManagerApi> Dispatcher<M> {
     pub fn new(
         session_manager: Arc<M>,
@@ -90,11 +178,126 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             initialized: false,
             start_time: Instant::now(),
             agent_settings: AgentSettings::default(),
+            process_cpu: Mutex::new(ProcessCpuTracker::new()),
+            file_watcher: None,
+            disabled_types: HashSet::new(),
+            audit: None,
+            features: HashSet::new(),
+            log_buffer: None,
+        }
+    }
+
+    /// Record every dispatched request to `audit_log`, attributed to
+    /// `client_id` (e.g. the TCP peer address).
+    pub fn with_audit_log(
+        mut self,
+        audit_log: Arc<AuditLog>,
+        client_id: impl Into<String>,
+    ) -> Self {
+        self.audit = Some((audit_log, client_id.into()));
+        self
+    }
+
+    /// Disable the given connection types (see [`parse_type_list`]).
+    pub fn with_disabled_types(mut self, disabled_types: HashSet<String>) -> Self {
+        self.disabled_types = disabled_types;
+        self
+    }
+
+    fn is_type_disabled(&self, type_id: &str) -> bool {
+        self.disabled_types.contains(type_id)
+    }
+
+    /// Serve `logs.tail` from the given capture buffer.
+    pub fn with_log_buffer(mut self, log_buffer: SharedLogBuffer) -> Self {
+        self.log_buffer = Some(log_buffer);
+        self
+    }
+
+    /// Enable `connection.files.watch` using the given watch manager.
+    pub fn with_file_watcher(mut self, file_watcher: Arc<FileWatchManager>) -> Self {
+        self.file_watcher = Some(file_watcher);
+        self
+    }
+
+    /// Dispatch a JSON-RPC 2.0 batch (an array of requests) in order.
+    ///
+    /// Each request's response is placed at its position in the returned
+    /// array. Items without an `id` are notifications: they are processed
+    /// but produce no response. Invalid items produce an `INVALID_REQUEST`
+    /// error, and an empty batch is answered with a single error.
+    pub async fn dispatch_batch(&mut self, items: Vec<Value>) -> BatchResult {
+        if self.initialized && !self.features.contains("batch") {
+            let err = JsonRpcErrorResponse::new(
+                Value::Null,
+                errors::FEATURE_NOT_NEGOTIATED,
+                "Feature not negotiated: batch",
+            );
+            return BatchResult {
+                response: Some(serde_json::to_value(err).unwrap()),
+                shutdown: false,
+            };
+        }
+        if items.is_empty() {
+            let err = JsonRpcErrorResponse::new(
+                Value::Null,
+                errors::INVALID_REQUEST,
+                "Invalid request: empty batch",
+            );
+            return BatchResult {
+                response: Some(serde_json::to_value(err).unwrap()),
+                shutdown: false,
+            };
+        }
+
+        let mut responses = Vec::with_capacity(items.len());
+        let mut shutdown = false;
+        for item in items {
+            let is_notification = item.as_object().is_some_and(|o| !o.contains_key("id"));
+            let request = match parse_batch_item(item) {
+                Ok(request) => request,
+                Err(err) => {
+                    responses.push(serde_json::to_value(err).unwrap());
+                    continue;
+                }
+            };
+            let result = self.dispatch(request).await;
+            if !is_notification {
+                responses.push(result.to_json());
+            }
+            if result.is_shutdown() {
+                shutdown = true;
+                break;
+            }
+        }
+
+        BatchResult {
+            response: (!responses.is_empty()).then_some(Value::Array(responses)),
+            shutdown,
         }
     }
 
-    /// Dispatch a parsed JSON-RPC request to the appropriate handler.
+    /// Dispatch a parsed JSON-RPC request to the appropriate handler,
+    /// recording it to the audit log if one is configured.
     pub async fn dispatch(&mut self, request: JsonRpcRequest) -> DispatchResult {
+        let Some((audit_log, client_id)) = self.audit.clone() else {
+            return self.dispatch_request(request).await;
+        };
+        let method = request.method.clone();
+        let id = request.id.clone();
+        let params = request.params.clone();
+        let result = self.dispatch_request(request).await;
+        let error_code = match &result {
+            DispatchResult::Error(resp) => Some(resp.error.code),
+            DispatchResult::Success(_) | DispatchResult::SuccessAndShutdown(_) => None,
+        };
+        audit_log.record(&AuditEntry::new(
+            &client_id, &method, &id, &params, error_code,
+        ));
+        result
+    }
+
+    async fn dispatch_request(&mut self, request: JsonRpcRequest) -> DispatchResult {
         let id = request.id.clone();
         let method = request.method.as_str();
 
@@ -114,6 +317,42 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             ));
         }
 
+        if let Some(feature) = required_feature(method) {
+            if !self.features.contains(feature) {
+                return DispatchResult::Error(JsonRpcErrorResponse::new(
+                    id,
+                    errors::FEATURE_NOT_NEGOTIATED,
+                    format!("Feature not negotiated: {feature} (required by {method})"),
+                ));
+            }
+        }
+
+        let timeout_secs = self.agent_settings.request_timeout_secs;
+        if timeout_secs == 0 || runs_to_completion(method) {
+            return self.route(request).await;
+        }
+
+        // Dropping the handler future on timeout cancels its remaining work;
+        // blocking tasks it already spawned run to completion unobserved.
+        let method = request.method.clone();
+        match tokio::time::timeout(Duration::from_secs(timeout_secs), self.route(request)).await {
+            Ok(result) => result,
+            Err(_) => {
+                warn!("Request {method} timed out after {timeout_secs}s");
+                DispatchResult::Error(JsonRpcErrorResponse::new(
+                    id,
+                    errors::REQUEST_TIMEOUT,
+                    format!("Request timed out after {timeout_secs}s: {method}"),
+                ))
+            }
+        }
+    }
+
+    /// Route an initialized request to its method handler.
+    async fn route(&mut self, request: JsonRpcRequest) -> DispatchResult {
+        let id = request.id.clone();
+        let method = request.method.as_str();
+
         match method {
             // connection.* — active terminal sessions
             "connection.create" => self.handle_session_create(request).await,
@@ -123,6 +362,10 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             "connection.detach" => self.handle_session_detach(request).await,
             "connection.write" => self.handle_session_input(request).await,
             "connection.resize" => self.handle_session_resize(request).await,
+            "connection.line_control" => self.handle_session_line_control(request).await,
+            "connection.recording.start" => self.handle_session_recording_start(request).await,
+            "connection.recording.stop" => self.handle_session_recording_stop(request).await,
+            "connection.stats" => self.handle_session_stats(request).await,
             "connection.types" => self.handle_connection_types(request).await,
 
             // connections.* — saved connection presets
@@ -133,6 +376,8 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             "connections.folders.create" => self.handle_connections_folders_create(request).await,
             "connections.folders.update" => self.handle_connections_folders_update(request).await,
             "connections.folders.delete" => self.handle_connections_folders_delete(request).await,
+            "connections.export" => self.handle_connections_export(request).await,
+            "connections.import" => self.handle_connections_import(request).await,
 
             // connection.files.* — file browsing scoped to connections
             "connection.files.list" => self.handle_files_list(request).await,
@@ -142,6 +387,9 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             "connection.files.rename" => self.handle_files_rename(request).await,
             "connection.files.stat" => self.handle_files_stat(request).await,
             "connection.files.mkdir" => self.handle_files_mkdir(request).await,
+            "connection.files.grep" => self.handle_files_grep(request).await,
+            "connection.files.watch" => self.handle_files_watch(request).await,
+            "connection.files.unwatch" => self.handle_files_unwatch(request).await,
 
             // connection.monitoring.* — system monitoring
             "connection.monitoring.subscribe" => self.handle_monitoring_subscribe(request).await,
@@ -161,6 +409,7 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             "health.check" => self.handle_health_check(request).await,
             "agent.shutdown" => self.handle_agent_shutdown(request).await,
             "agent.settingsUpdate" => self.handle_settings_update(request).await,
+            "logs.tail" => self.handle_logs_tail(request).await,
             _ => {
                 warn!("Unknown method: {}", method);
                 DispatchResult::Error(JsonRpcErrorResponse::new(
@@ -202,6 +451,16 @@ impl<M: SessionManagerApi> Dispatcher<M> {
         }
 
         self.initialized = true;
+        self.features = params
+            .features
+            .iter()
+            .filter(|f| SUPPORTED_FEATURES.contains(&f.as_str()))
+            .cloned()
+            .collect();
+        let mut features: Vec<String> = self.features.iter().cloned().collect();
+        features.sort();
+        self.session_manager
+            .set_daemon_idle_ttl(params.agent_settings.daemon_idle_ttl_secs);
         self.agent_settings = params.agent_settings;
 
         if !params.external_connection_files.is_empty() {
@@ -210,8 +469,15 @@ impl<M: SessionManagerApi> Dispatcher<M> {
                 .await;
         }
 
-        let docker_available = detect_docker_available();
-        let connection_types = self.session_manager.registry().available_types();
+        let connection_types = self
+            .session_manager
+            .registry()
+            .available_types()
+            .into_iter()
+            .filter(|info| !self.is_type_disabled(&info.type_id))
+            .collect();
+        let docker_enabled = !self.is_type_disabled("docker");
+        let docker_available = docker_enabled && detect_docker_available();
 
         let result = InitializeResult {
             protocol_version: AGENT_PROTOCOL_VERSION.to_string(),
@@ -219,12 +485,25 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             capabilities: Capabilities {
                 connection_types,
                 max_sessions: MAX_SESSIONS,
-                available_shells: detect_available_shells(),
-                available_serial_ports: detect_available_serial_ports(),
+                available_shells: if self.is_type_disabled("local") {
+                    Vec::new()
+                } else {
+                    detect_available_shells()
+                },
+                available_serial_ports: if self.is_type_disabled("serial") {
+                    Vec::new()
+                } else {
+                    detect_available_serial_ports()
+                },
                 docker_available,
-                available_docker_images: detect_docker_images(),
+                available_docker_images: if docker_enabled {
+                    detect_docker_images()
+                } else {
+                    Vec::new()
+                },
                 monitoring_supported: detect_monitoring_supported(),
             },
+            features,
         };
 
         DispatchResult::Success(JsonRpcResponse::new(
@@ -251,6 +530,14 @@ impl<M: SessionManagerApi> Dispatcher<M> {
         // (e.g. "shell" → "local").
         let type_id = normalize_type_id(&params.session_type);
 
+        if self.is_type_disabled(type_id) {
+            return DispatchResult::Error(JsonRpcErrorResponse::new(
+                id,
+                errors::SESSION_CREATION_FAILED,
+                format!("Connection type disabled on this agent: {type_id}"),
+            ));
+        }
+
         // Validate that the type exists in the registry.
         if !self.session_manager.registry().has_type(type_id) {
             return DispatchResult::Error(JsonRpcErrorResponse::new(
@@ -329,6 +616,45 @@ impl<M: SessionManagerApi> Dispatcher<M> {
         ))
     }
 
+    async fn handle_session_stats(&self, request: JsonRpcRequest) -> DispatchResult {
+        let sessions = self.session_manager.list().await;
+        let now = Instant::now();
+        let mut tracker = self
+            .process_cpu
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let mut live_pids = Vec::new();
+        let stats = sessions
+            .into_iter()
+            .map(|s| {
+                let entry = match s.process_id {
+                    Some(pid) => {
+                        live_pids.push(pid);
+                        let sample = read_process_sample(pid);
+                        SessionStatsEntry {
+                            pid: Some(pid),
+                            cpu_percent: sample
+                                .map(|p| tracker.update(pid, p.cpu_ticks, now))
+                                .unwrap_or(0.0),
+                            rss_kb: sample.map(|p| p.rss_kb).unwrap_or(0),
+                        }
+                    }
+                    None => SessionStatsEntry::default(),
+                };
+                (s.id, entry)
+            })
+            .collect();
+        tracker.retain(&live_pids);
+
+        let result = SessionStatsResult { sessions: stats };
+
+        DispatchResult::Success(JsonRpcResponse::new(
+            request.id,
+            serde_json::to_value(result).unwrap(),
+        ))
+    }
+
     async fn handle_session_close(&self, request: JsonRpcRequest) -> DispatchResult {
         let id = request.id.clone();
 
@@ -361,6 +687,7 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             status: "ok".to_string(),
             uptime_secs: uptime,
             active_sessions: active,
+            state_dir_free_bytes: persistence::state_dir_free_bytes(),
         };
 
         DispatchResult::Success(JsonRpcResponse::new(
@@ -369,6 +696,50 @@ impl<M: SessionManagerApi> Dispatcher<M> {
         ))
     }
 
+    async fn handle_logs_tail(&self, request: JsonRpcRequest) -> DispatchResult {
+        let id = request.id.clone();
+
+        let params = if request.params.is_null() {
+            json!({})
+        } else {
+            request.params
+        };
+        let params: LogsTailParams = match serde_json::from_value(params) {
+            Ok(p) => p,
+            Err(e) => {
+                return DispatchResult::Error(JsonRpcErrorResponse::new(
+                    id,
+                    errors::INVALID_PARAMS,
+                    format!("Invalid logs.tail params: {e}"),
+                ));
+            }
+        };
+        let min_level = match params.min_level.as_deref() {
+            None => tracing::Level::TRACE,
+            Some(level) => match level.parse() {
+                Ok(level) => level,
+                Err(_) => {
+                    return DispatchResult::Error(JsonRpcErrorResponse::new(
+                        id,
+                        errors::INVALID_PARAMS,
+                        format!("Invalid logs.tail params: unknown level \"{level}\""),
+                    ));
+                }
+            },
+        };
+
+        let entries = self
+            .log_buffer
+            .as_ref()
+            .and_then(|buffer| buffer.lock().ok().map(|b| b.tail(params.lines, min_level)))
+            .unwrap_or_default();
+
+        DispatchResult::Success(JsonRpcResponse::new(
+            id,
+            serde_json::to_value(LogsTailResult { entries }).unwrap(),
+        ))
+    }
+
     async fn handle_settings_update(&mut self, request: JsonRpcRequest) -> DispatchResult {
         let id = request.id.clone();
 
@@ -383,6 +754,8 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             }
         };
 
+        self.session_manager
+            .set_daemon_idle_ttl(params.settings.daemon_idle_ttl_secs);
         self.agent_settings = params.settings;
 
         DispatchResult::Success(JsonRpcResponse::new(id, json!({"applied": true})))
@@ -406,8 +779,11 @@ impl<M: SessionManagerApi> Dispatcher<M> {
         // (left running in daemons) so the next agent instance can recover them.
         let detached = self.session_manager.active_count().await;
 
-        // Stop monitoring subscriptions
+        // Stop monitoring subscriptions and file watches
         self.monitoring_manager.shutdown().await;
+        if let Some(watcher) = &self.file_watcher {
+            watcher.shutdown().await;
+        }
 
         let result = AgentShutdownResult {
             detached_sessions: detached,
@@ -532,6 +908,115 @@ impl<M: SessionManagerApi> Dispatcher<M> {
         }
     }
 
+    async fn handle_session_line_control(&self, request: JsonRpcRequest) -> DispatchResult {
+        let id = request.id.clone();
+
+        let params: SessionLineControlParams = match serde_json::from_value(request.params) {
+            Ok(p) => p,
+            Err(e) => {
+                return DispatchResult::Error(JsonRpcErrorResponse::new(
+                    id,
+                    errors::INVALID_PARAMS,
+                    format!("Invalid connection.line_control params: {e}"),
+                ));
+            }
+        };
+
+        if self
+            .session_manager
+            .get_session_type_id(&params.session_id)
+            .await
+            .is_none()
+        {
+            return DispatchResult::Error(
+                JsonRpcErrorResponse::new(id, errors::SESSION_NOT_FOUND, "Session not found")
+                    .with_data(json!({"session_id": params.session_id})),
+            );
+        }
+
+        match self
+            .session_manager
+            .line_control(&params.session_id, params.control)
+            .await
+        {
+            Ok(()) => DispatchResult::Success(JsonRpcResponse::new(id, json!({}))),
+            Err(msg) => DispatchResult::Error(
+                JsonRpcErrorResponse::new(id, errors::INTERNAL_ERROR, msg)
+                    .with_data(json!({"session_id": params.session_id})),
+            ),
+        }
+    }
+
+    async fn handle_session_recording_start(&self, request: JsonRpcRequest) -> DispatchResult {
+        let id = request.id.clone();
+
+        let params: SessionRecordingStartParams = match serde_json::from_value(request.params) {
+            Ok(p) => p,
+            Err(e) => {
+                return DispatchResult::Error(JsonRpcErrorResponse::new(
+                    id,
+                    errors::INVALID_PARAMS,
+                    format!("Invalid connection.recording.start params: {e}"),
+                ));
+            }
+        };
+
+        if self
+            .session_manager
+            .get_session_type_id(&params.session_id)
+            .await
+            .is_none()
+        {
+            return DispatchResult::Error(
+                JsonRpcErrorResponse::new(id, errors::SESSION_NOT_FOUND, "Session not found")
+                    .with_data(json!({"session_id": params.session_id})),
+            );
+        }
+
+        match self
+            .session_manager
+            .start_recording(
+                &params.session_id,
+                &params.path,
+                params.cols,
+                params.rows,
+                params.record_input,
+            )
+            .await
+        {
+            Ok(()) => DispatchResult::Success(JsonRpcResponse::new(id, json!({}))),
+            Err(msg) => DispatchResult::Error(
+                JsonRpcErrorResponse::new(id, errors::INTERNAL_ERROR, msg)
+                    .with_data(json!({"session_id": params.session_id})),
+            ),
+        }
+    }
+
+    async fn handle_session_recording_stop(&self, request: JsonRpcRequest) -> DispatchResult {
+        let id = request.id.clone();
+
+        let params: SessionRecordingStopParams = match serde_json::from_value(request.params) {
+            Ok(p) => p,
+            Err(e) => {
+                return DispatchResult::Error(JsonRpcErrorResponse::new(
+                    id,
+                    errors::INVALID_PARAMS,
+                    format!("Invalid connection.recording.stop params: {e}"),
+                ));
+            }
+        };
+
+        let stopped = self
+            .session_manager
+            .stop_recording(&params.session_id)
+            .await;
+        let result = SessionRecordingStopResult { stopped };
+        DispatchResult::Success(JsonRpcResponse::new(
+            id,
+            serde_json::to_value(result).unwrap(),
+        ))
+    }
+
     async fn handle_connection_types(&self, request: JsonRpcRequest) -> DispatchResult {
         let monitoring_ok = detect_monitoring_supported();
         // The "local" shell backend declares monitoring: false because the
@@ -543,6 +1028,7 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             .registry()
             .available_types()
             .into_iter()
+            .filter(|info| !self.is_type_disabled(&info.type_id))
             .map(|mut info| {
                 if info.type_id == "local" {
                     info.capabilities.monitoring = monitoring_ok;
@@ -559,6 +1045,38 @@ impl<M: SessionManagerApi> Dispatcher<M> {
 
     // ── connections.* handlers ───────────────────────────────────────
 
+    /// Check a saved connection's config against its type's settings
+    /// schema. Types this agent does not know are stored as-is.
+    fn validate_connection_config(
+        &self,
+        id: &Value,
+        session_type: &str,
+        config: &Value,
+    ) -> Result<(), JsonRpcErrorResponse> {
+        let type_id = normalize_type_id(session_type);
+        let Some(schema) = self.session_manager.registry().settings_schema(type_id) else {
+            return Ok(());
+        };
+        let field_errors = validate_settings(schema, &validation_view(type_id, schema, config));
+        if field_errors.is_empty() {
+            return Ok(());
+        }
+        let summary: Vec<String> = field_errors
+            .iter()
+            .map(|e| format!("{}: {}", e.field, e.message))
+            .collect();
+        let data: Vec<Value> = field_errors
+            .iter()
+            .map(|e| json!({"field": e.field, "message": e.message}))
+            .collect();
+        Err(JsonRpcErrorResponse::new(
+            id.clone(),
+            errors::INVALID_CONFIGURATION,
+            format!("Invalid {type_id} configuration: {}", summary.join("; ")),
+        )
+        .with_data(json!({"errors": data})))
+    }
+
     async fn handle_connections_list(&self, request: JsonRpcRequest) -> DispatchResult {
         let (connections, folders) = self.connection_store.list().await;
         DispatchResult::Success(JsonRpcResponse::new(
@@ -581,6 +1099,10 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             }
         };
 
+        if let Err(e) = self.validate_connection_config(&id, &params.session_type, &params.config) {
+            return DispatchResult::Error(e);
+        }
+
         let conn = Connection {
             id: format!("conn-{}", uuid::Uuid::new_v4()),
             name: params.name,
@@ -613,6 +1135,21 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             }
         };
 
+        // Re-validate when the type or config changes. An unknown ID is
+        // left to the store, which reports it as not found.
+        if params.session_type.is_some() || params.config.is_some() {
+            if let Some(existing) = self.connection_store.get(&params.id).await {
+                let session_type = params
+                    .session_type
+                    .as_deref()
+                    .unwrap_or(&existing.session_type);
+                let config = params.config.as_ref().unwrap_or(&existing.config);
+                if let Err(e) = self.validate_connection_config(&id, session_type, config) {
+                    return DispatchResult::Error(e);
+                }
+            }
+        }
+
         // Convert folder_id: absent → None, null → Some(None), string → Some(Some(s))
         let folder_id = params.folder_id.map(|v| {
             if v.is_null() {
@@ -775,6 +1312,108 @@ impl<M: SessionManagerApi> Dispatcher<M> {
         }
     }
 
+    /// Export the primary connection store. External-file connections and
+    /// secrets are left out.
+    async fn handle_connections_export(&self, request: JsonRpcRequest) -> DispatchResult {
+        let (connections, mut folders) = self.connection_store.list().await;
+        let mut connections: Vec<_> = connections
+            .into_iter()
+            .filter(|c| c.source_file.is_none())
+            .map(|mut c| {
+                strip_secrets(&mut c.config);
+                c
+            })
+            .collect();
+        connections.sort_by(|a, b| a.id.cmp(&b.id));
+        folders.sort_by(|a, b| a.id.cmp(&b.id));
+        DispatchResult::Success(JsonRpcResponse::new(
+            request.id,
+            json!({"connections": connections, "folders": folders}),
+        ))
+    }
+
+    async fn handle_connections_import(&self, request: JsonRpcRequest) -> DispatchResult {
+        let id = request.id.clone();
+
+        let params: ConnectionsImportParams = match serde_json::from_value(request.params) {
+            Ok(p) => p,
+            Err(e) => {
+                return DispatchResult::Error(JsonRpcErrorResponse::new(
+                    id,
+                    errors::INVALID_PARAMS,
+                    format!("Invalid connections.import params: {e}"),
+                ));
+            }
+        };
+
+        let (existing_connections, existing_folders) = self.connection_store.list().await;
+        let existing_folder_ids: HashSet<String> =
+            existing_folders.into_iter().map(|f| f.id).collect();
+        let existing_configs: HashMap<String, Value> = existing_connections
+            .into_iter()
+            .filter(|c| c.source_file.is_none())
+            .map(|c| (c.id, c.config))
+            .collect();
+
+        let overwrite = params.strategy == ImportStrategy::Overwrite;
+        let mut result = ConnectionsImportResult {
+            connections_imported: 0,
+            folders_imported: 0,
+            skipped: 0,
+        };
+
+        for folder in params.folders {
+            if existing_folder_ids.contains(&folder.id) {
+                if !overwrite {
+                    result.skipped += 1;
+                    continue;
+                }
+                self.connection_store
+                    .update_folder(
+                        &folder.id,
+                        Some(folder.name),
+                        Some(folder.parent_id),
+                        Some(folder.is_expanded),
+                    )
+                    .await;
+            } else {
+                self.connection_store.create_folder(folder).await;
+            }
+            result.folders_imported += 1;
+        }
+
+        for mut conn in params.connections {
+            if let Some(existing_config) = existing_configs.get(&conn.id) {
+                if !overwrite {
+                    result.skipped += 1;
+                    continue;
+                }
+                // Exports carry no secrets; keep the ones already stored.
+                carry_over_secrets(existing_config, &mut conn.config);
+                self.connection_store
+                    .update(
+                        &conn.id,
+                        Some(conn.name),
+                        Some(conn.session_type),
+                        Some(conn.config),
+                        Some(conn.persistent),
+                        Some(conn.folder_id),
+                        Some(conn.terminal_options),
+                        Some(conn.icon),
+                    )
+                    .await;
+            } else {
+                self.connection_store.create(conn).await;
+            }
+            result.connections_imported += 1;
+        }
+
+        DispatchResult::Success(JsonRpcResponse::new(
+            id,
+            serde_json::to_value(result).unwrap(),
+        ))
+    }
+
     // ── files.* handlers ───────────────────────────────────────────
 
     async fn handle_files_list(&self, request: JsonRpcRequest) -> DispatchResult {
@@ -879,6 +1518,7 @@ impl<M: SessionManagerApi> Dispatcher<M> {
                 ));
             }
         };
+        let data = normalize_newlines(&data, params.newline);
 
         let backend = match self.resolve_file_backend(params.connection_id).await {
             Ok(b) => b,
@@ -947,7 +1587,10 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             }
         };
 
-        match backend.rename(&params.old_path, &params.new_path).await {
+        match backend
+            .rename(&params.old_path, &params.new_path, params.overwrite)
+            .await
+        {
             Ok(()) => DispatchResult::Success(JsonRpcResponse::new(id, json!({}))),
             Err(e) => {
                 let (code, msg) = map_file_error(e);
@@ -994,15 +1637,21 @@ impl<M: SessionManagerApi> Dispatcher<M> {
     /// Resolve the monitoring host for a given host identifier.
     ///
     /// If `host` is a session ID whose type is "local", returns `"self"` so
-    /// the `MonitoringManager` subscribes to the local machine stats.
-    /// For SSH session IDs and bare hostnames/IPs the value passes through unchanged.
+    /// the `MonitoringManager` subscribes to the local machine stats; for a
+    /// "docker" session it returns `"docker:<session_id>"` so the session's
+    /// container is monitored. For SSH session IDs and bare hostnames/IPs
+    /// the value passes through unchanged.
     async fn resolve_monitoring_host(&self, host: &str) -> String {
-        if let Some(type_id) = self.session_manager.get_session_type_id(host).await {
-            if type_id == "local" {
-                return "self".to_string();
-            }
-        }
-        host.to_string()
+        match self
+            .session_manager
+            .get_session_type_id(host)
+            .await
+            .as_deref()
+        {
+            Some("local") => "self".to_string(),
+            Some("docker") => format!("{DOCKER_HOST_PREFIX}{host}"),
+            _ => host.to_string(),
+        }
     }
 
     async fn handle_monitoring_subscribe(&self, request: JsonRpcRequest) -> DispatchResult {
@@ -1082,7 +1731,7 @@ impl<M: SessionManagerApi> Dispatcher<M> {
             }
         };
 
-        match backend.mkdir(&params.path).await {
+        match backend.mkdir(&params.path, params.recursive).await {
             Ok(()) => DispatchResult::Success(JsonRpcResponse::new(id, json!({}))),
             Err(e) => {
                 let (code, msg) = map_file_error(e);
@@ -1091,6 +1740,114 @@ impl<M: SessionManagerApi> Dispatcher<M> {
         }
     }
 
+    async fn handle_files_grep(&self, request: JsonRpcRequest) -> DispatchResult {
+        let id = request.id.clone();
+
+        let params: FilesGrepParams = match serde_json::from_value(request.params) {
+            Ok(p) => p,
+            Err(e) => {
+                return DispatchResult::Error(JsonRpcErrorResponse::new(
+                    id,
+                    errors::INVALID_PARAMS,
+                    format!("Invalid files.grep params: {e}"),
+                ));
+            }
+        };
+
+        let backend = match self.resolve_file_backend(params.connection_id).await {
+            Ok(b) => b,
+            Err((code, msg)) => {
+                return DispatchResult::Error(JsonRpcErrorResponse::new(id, code, msg))
+            }
+        };
+
+        match backend
+            .grep(&params.path, &params.pattern, &params.options)
+            .await
+        {
+            Ok(matches) => {
+                let result = FilesGrepResult { matches };
+                DispatchResult::Success(JsonRpcResponse::new(
+                    id,
+                    serde_json::to_value(result).unwrap(),
+                ))
+            }
+            Err(e) => {
+                let (code, msg) = map_file_error(e);
+                DispatchResult::Error(JsonRpcErrorResponse::new(id, code, msg))
+            }
+        }
+    }
+
+    async fn handle_files_watch(&self, request: JsonRpcRequest) -> DispatchResult {
+        let id = request.id.clone();
+
+        let params: FilesWatchParams = match serde_json::from_value(request.params) {
+            Ok(p) => p,
+            Err(e) => {
+                return DispatchResult::Error(JsonRpcErrorResponse::new(
+                    id,
+                    errors::INVALID_PARAMS,
+                    format!("Invalid files.watch params: {e}"),
+                ));
+            }
+        };
+
+        let Some(watcher) = &self.file_watcher else {
+            return DispatchResult::Error(JsonRpcErrorResponse::new(
+                id,
+                errors::FILE_BROWSING_NOT_SUPPORTED,
+                "File watching is not available on this transport",
+            ));
+        };
+
+        let backend = match self.resolve_file_backend(params.connection_id).await {
+            Ok(b) => b,
+            Err((code, msg)) => {
+                return DispatchResult::Error(JsonRpcErrorResponse::new(id, code, msg))
+            }
+        };
+
+        match watcher
+            .watch(backend, &params.path, params.interval_ms)
+            .await
+        {
+            Ok(watch_id) => DispatchResult::Success(JsonRpcResponse::new(
+                id,
+                serde_json::to_value(FilesWatchResult { watch_id }).unwrap(),
+            )),
+            Err(e @ WatchError::LimitReached) => DispatchResult::Error(JsonRpcErrorResponse::new(
+                id,
+                errors::WATCH_LIMIT_REACHED,
+                e.to_string(),
+            )),
+            Err(WatchError::File(e)) => {
+                let (code, msg) = map_file_error(e);
+                DispatchResult::Error(JsonRpcErrorResponse::new(id, code, msg))
+            }
+        }
+    }
+
+    async fn handle_files_unwatch(&self, request: JsonRpcRequest) -> DispatchResult {
+        let id = request.id.clone();
+
+        let params: FilesUnwatchParams = match serde_json::from_value(request.params) {
+            Ok(p) => p,
+            Err(e) => {
+                return DispatchResult::Error(JsonRpcErrorResponse::new(
+                    id,
+                    errors::INVALID_PARAMS,
+                    format!("Invalid files.unwatch params: {e}"),
+                ));
+            }
+        };
+
+        if let Some(watcher) = &self.file_watcher {
+            watcher.unwatch(&params.watch_id).await;
+        }
+        DispatchResult::Success(JsonRpcResponse::new(id, json!({})))
+    }
+
     async fn resolve_file_backend(
         &self,
         connection_id: Option<String>,
@@ -1280,10 +2037,38 @@ fn map_file_error(e: FileError) -> (i64, String) {
         FileError::PermissionDenied(msg) => (errors::PERMISSION_DENIED, msg),
         FileError::OperationFailed(msg) => (errors::FILE_OPERATION_FAILED, msg),
         FileError::NotSupported => (errors::FILE_BROWSING_NOT_SUPPORTED, e.to_string()),
+        FileError::DestinationExists(_) => (errors::DESTINATION_EXISTS, e.to_string()),
         FileError::Io(e) => (errors::FILE_OPERATION_FAILED, e.to_string()),
     }
 }
 
+/// Remove secret values from a connection config, including nested objects
+/// (e.g. jump host settings).
+fn strip_secrets(config: &mut Value) {
+    match config {
+        Value::Object(map) => {
+            map.retain(|key, _| !SECRET_KEYS.contains(&key.as_str()));
+            map.values_mut().for_each(strip_secrets);
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_secrets),
+        _ => {}
+    }
+}
+
+/// Copy secrets from `existing` into `imported` where `imported` lacks them.
+fn carry_over_secrets(existing: &Value, imported: &mut Value) {
+    let (Value::Object(existing), Value::Object(imported)) = (existing, imported) else {
+        return;
+    };
+    for (key, value) in existing {
+        if SECRET_KEYS.contains(&key.as_str()) {
+            imported.entry(key.clone()).or_insert_with(|| value.clone());
+        } else if let Some(nested) = imported.get_mut(key) {
+            carry_over_secrets(value, nested);
+        }
+    }
+}
+
 /// Normalize user-facing session type names to registry type IDs.
 ///
 /// Connection definitions and the frontend use `"shell"` as the session
@@ -1297,6 +2082,49 @@ fn normalize_type_id(raw: &str) -> &str {
     }
 }
 
+/// `config` as the backend reads it when it connects, for validation:
+/// the schema's defaults fill missing top-level fields, and a local shell
+/// given as an absolute path (which the backend runs directly) is checked
+/// as the `custom` option with that path.
+fn validation_view(type_id: &str, schema: &SettingsSchema, config: &Value) -> Value {
+    let mut filled = match config {
+        Value::Object(map) => map.clone(),
+        _ => serde_json::Map::new(),
+    };
+    for field in schema.groups.iter().flat_map(|group| &group.fields) {
+        if let Some(default) = &field.default {
+            filled
+                .entry(field.key.clone())
+                .or_insert_with(|| default.clone());
+        }
+    }
+    if type_id == "local" {
+        let shell_path = filled
+            .get("shell")
+            .and_then(Value::as_str)
+            .filter(|s| std::path::Path::new(s).is_absolute())
+            .map(String::from);
+        if let Some(path) = shell_path {
+            filled.insert("shell".to_string(), json!("custom"));
+            filled.insert("customShellPath".to_string(), json!(path));
+        }
+    }
+    Value::Object(filled)
+}
+
+/// Parse a comma-separated list of connection types (as given to
+/// `--disable-types`) into registry type IDs.
+///
+/// Entries are trimmed and normalized like `connection.create` types, so
+/// `shell` disables `local`. Empty entries are ignored.
+pub fn parse_type_list(list: &str) -> HashSet<String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| normalize_type_id(s).to_string())
+        .collect()
+}
+
 /// Well-known shell paths to probe on the host system.
 const SHELL_CANDIDATES: &[&str] = &[
     "/bin/bash",
@@ -1423,6 +2251,71 @@ mod tests {
         assert!(matches!(result, DispatchResult::Success(_)));
     }
 
+    // ── Audit log tests ─────────────────────────────────────────────
+
+    /// Writer whose output stays readable after being moved into an `AuditLog`.
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn audit_log_records_each_request_with_input_redacted() {
+        let buf = SharedBuf::default();
+        let audit_log = Arc::new(AuditLog::from_writer(buf.clone()));
+        let mut d = make_dispatcher().with_audit_log(audit_log, "10.0.0.7:51022");
+
+        init_dispatcher(&mut d).await;
+        d.dispatch(make_request("health.check", json!({}), 2)).await;
+        d.dispatch(make_request(
+            "connection.write",
+            json!({"session_id": "nonexistent", "data": "c2VjcmV0"}),
+            3,
+        ))
+        .await;
+        d.dispatch(make_request("no.such.method", json!({}), 4))
+            .await;
+
+        let content = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        let entries: Vec<Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        let methods: Vec<&str> = entries
+            .iter()
+            .map(|e| e["method"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            methods,
+            vec![
+                "initialize",
+                "health.check",
+                "connection.write",
+                "no.such.method"
+            ]
+        );
+        assert!(entries.iter().all(|e| e["client"] == "10.0.0.7:51022"));
+        assert!(entries.iter().all(|e| e["timestamp"].is_string()));
+        assert_eq!(entries[1]["outcome"], "success");
+        assert_eq!(entries[1]["id"], 2);
+
+        let write = &entries[2];
+        assert_eq!(write["params"]["session_id"], "nonexistent");
+        assert_eq!(write["params"]["data"], crate::handler::audit::REDACTED);
+        assert_eq!(write["outcome"], "error");
+        assert_eq!(write["errorCode"], errors::SESSION_NOT_FOUND);
+        assert!(!content.contains("c2VjcmV0"));
+
+        assert_eq!(entries[3]["errorCode"], errors::METHOD_NOT_FOUND);
+    }
+
     // ── Initialize tests ────────────────────────────────────────────
 
     #[tokio::test]
@@ -1482,6 +2375,63 @@ mod tests {
         assert_eq!(json["error"]["code"], errors::INVALID_PARAMS);
     }
 
+    async fn init_with_features(d: &mut Dispatcher, features: Value) -> Value {
+        let mut params = init_params();
+        params["features"] = features;
+        let result = d.dispatch(make_request("initialize", params, 1)).await;
+        result.to_json()["result"]["features"].clone()
+    }
+
+    #[tokio::test]
+    async fn initialize_negotiates_feature_intersection() {
+        let mut d = make_dispatcher();
+        let features = init_with_features(&mut d, json!(["watch", "telepathy", "batch"])).await;
+        assert_eq!(features, json!(["batch", "watch"]));
+
+        let mut d = make_dispatcher();
+        let features = init_with_features(&mut d, json!(["watch"])).await;
+        assert_eq!(features, json!(["watch"]));
+    }
+
+    #[tokio::test]
+    async fn initialize_without_features_enables_legacy_set() {
+        let mut d = make_dispatcher();
+        let result = d
+            .dispatch(make_request("initialize", init_params(), 1))
+            .await;
+        assert_eq!(
+            result.to_json()["result"]["features"],
+            json!(["batch", "watch"])
+        );
+    }
+
+    #[tokio::test]
+    async fn unnegotiated_feature_method_is_rejected() {
+        let mut d = make_dispatcher();
+        init_with_features(&mut d, json!(["batch"])).await;
+
+        for method in ["connection.files.watch", "connection.files.unwatch"] {
+            let req = make_request(method, json!({"path": "/tmp", "watchId": "w"}), 2);
+            let json = d.dispatch(req).await.to_json();
+            assert_eq!(
+                json["error"]["code"],
+                errors::FEATURE_NOT_NEGOTIATED,
+                "{method} should require the watch feature"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn unnegotiated_batch_is_rejected() {
+        let mut d = make_dispatcher();
+        init_with_features(&mut d, json!([])).await;
+
+        let batch = vec![json!({"jsonrpc": "2.0", "method": "health.check", "id": 2})];
+        let response = d.dispatch_batch(batch).await.response.unwrap();
+        assert!(response.is_object());
+        assert_eq!(response["error"]["code"], errors::FEATURE_NOT_NEGOTIATED);
+    }
+
     // ── Not-initialized gate ────────────────────────────────────────
 
     #[tokio::test]
@@ -1543,6 +2493,70 @@ mod tests {
         assert_eq!(normalize_type_id("unknown"), "unknown");
     }
 
+    #[test]
+    fn parse_type_list_normalizes_and_trims() {
+        let types = parse_type_list(" shell, docker,,");
+        assert_eq!(
+            types,
+            HashSet::from(["local".to_string(), "docker".to_string()])
+        );
+    }
+
+    // ── Disabled type tests ─────────────────────────────────────────
+
+    #[tokio::test]
+    async fn disabled_types_absent_from_capabilities() {
+        let mut d = make_dispatcher().with_disabled_types(parse_type_list("shell,docker"));
+        let json = d
+            .dispatch(make_request("initialize", init_params(), 1))
+            .await
+            .to_json();
+        let capabilities = &json["result"]["capabilities"];
+        let type_ids: Vec<&str> = capabilities["connectionTypes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["typeId"].as_str().unwrap())
+            .collect();
+        assert!(!type_ids.contains(&"local"), "got {type_ids:?}");
+        assert!(!type_ids.contains(&"docker"), "got {type_ids:?}");
+        assert!(type_ids.contains(&"ssh"), "got {type_ids:?}");
+        assert_eq!(capabilities["availableShells"], json!([]));
+        assert_eq!(capabilities["dockerAvailable"], false);
+
+        let json = d
+            .dispatch(make_request("connection.types", json!({}), 2))
+            .await
+            .to_json();
+        let type_ids: Vec<&str> = json["result"]["types"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["typeId"].as_str().unwrap())
+            .collect();
+        assert!(!type_ids.contains(&"local"), "got {type_ids:?}");
+    }
+
+    #[tokio::test]
+    async fn disabled_type_rejected_on_create() {
+        let mut d = make_mock_dispatcher().with_disabled_types(parse_type_list("shell"));
+        init_mock(&mut d).await;
+
+        for session_type in ["shell", "local"] {
+            let req = make_request(
+                "connection.create",
+                json!({"type": session_type, "config": {}}),
+                2,
+            );
+            let result = d.dispatch(req).await.to_json();
+            assert_eq!(result["error"]["code"], errors::SESSION_CREATION_FAILED);
+            assert!(result["error"]["message"]
+                .as_str()
+                .unwrap()
+                .contains("type disabled"));
+        }
+    }
+
     // ── Session list tests ──────────────────────────────────────────
 
     #[tokio::test]
@@ -1621,6 +2635,109 @@ mod tests {
         assert_eq!(json["result"]["status"], "ok");
         assert!(json["result"]["uptime_secs"].is_number());
         assert_eq!(json["result"]["active_sessions"], 0);
+        #[cfg(unix)]
+        assert!(json["result"]["state_dir_free_bytes"]
+            .as_u64()
+            .is_some_and(|free| free > 0));
+    }
+
+    // ── logs.tail ───────────────────────────────────────────────────
+
+    /// A dispatcher serving `logs.tail` from a buffer that captured an
+    /// error, a warning, an info and a debug event, in that order.
+    async fn dispatcher_with_logs() -> Dispatcher {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let buffer = crate::log_capture::create_log_buffer();
+        let subscriber = tracing_subscriber::registry()
+            .with(crate::log_capture::LogCaptureLayer::new(buffer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::error!("session s1 failed");
+            tracing::warn!("state dir nearly full");
+            tracing::info!("client connected");
+            tracing::debug!("polling stats");
+        });
+        let mut d = make_dispatcher().with_log_buffer(buffer);
+        init_dispatcher(&mut d).await;
+        d
+    }
+
+    fn log_messages(json: &Value) -> Vec<&str> {
+        json["result"]["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["message"].as_str().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn logs_tail_returns_recent_lines() {
+        let mut d = dispatcher_with_logs().await;
+
+        let result = d.dispatch(make_request("logs.tail", Value::Null, 2)).await;
+        let json = result.to_json();
+        assert_eq!(
+            log_messages(&json),
+            vec![
+                "session s1 failed",
+                "state dir nearly full",
+                "client connected",
+                "polling stats"
+            ]
+        );
+        let first = &json["result"]["entries"][0];
+        assert_eq!(first["level"], "ERROR");
+        assert!(first["timestamp"].is_string());
+        assert!(first["target"].as_str().unwrap().contains("dispatch"));
+
+        let req = make_request("logs.tail", json!({"lines": 2}), 3);
+        let json = d.dispatch(req).await.to_json();
+        assert_eq!(
+            log_messages(&json),
+            vec!["client connected", "polling stats"]
+        );
+    }
+
+    #[tokio::test]
+    async fn logs_tail_filters_by_min_level() {
+        let mut d = dispatcher_with_logs().await;
+
+        let req = make_request("logs.tail", json!({"min_level": "warn"}), 2);
+        let json = d.dispatch(req).await.to_json();
+        assert_eq!(
+            log_messages(&json),
+            vec!["session s1 failed", "state dir nearly full"]
+        );
+
+        let req = make_request("logs.tail", json!({"lines": 1, "min_level": "INFO"}), 3);
+        let json = d.dispatch(req).await.to_json();
+        assert_eq!(log_messages(&json), vec!["client connected"]);
+    }
+
+    #[tokio::test]
+    async fn logs_tail_rejects_unknown_level() {
+        let mut d = dispatcher_with_logs().await;
+
+        let req = make_request("logs.tail", json!({"min_level": "loud"}), 2);
+        let json = d.dispatch(req).await.to_json();
+        assert_eq!(json["error"]["code"], errors::INVALID_PARAMS);
+        assert!(json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("unknown level \"loud\""));
+    }
+
+    #[tokio::test]
+    async fn logs_tail_without_capture_is_empty() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let json = d
+            .dispatch(make_request("logs.tail", json!({}), 2))
+            .await
+            .to_json();
+        assert_eq!(json["result"]["entries"], json!([]));
     }
 
     // ── Unknown method ──────────────────────────────────────────────
@@ -1636,6 +2753,76 @@ mod tests {
         assert_eq!(json["error"]["code"], errors::METHOD_NOT_FOUND);
     }
 
+    // ── Batch tests ─────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn batch_mixed_success_and_error() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let batch = vec![
+            json!({"jsonrpc": "2.0", "method": "health.check", "params": {}, "id": 10}),
+            json!({"jsonrpc": "2.0", "method": "health.check", "params": {}}),
+            json!({"jsonrpc": "2.0", "method": "unknown.method", "id": "b"}),
+            json!({"jsonrpc": "1.0", "method": "health.check", "id": 12}),
+            json!(42),
+        ];
+        let result = d.dispatch_batch(batch).await;
+        assert!(!result.shutdown);
+
+        let responses = result.response.unwrap();
+        let responses = responses.as_array().unwrap();
+        assert_eq!(responses.len(), 4, "notification must not get a response");
+        assert_eq!(responses[0]["id"], 10);
+        assert_eq!(responses[0]["result"]["status"], "ok");
+        assert_eq!(responses[1]["id"], "b");
+        assert_eq!(responses[1]["error"]["code"], errors::METHOD_NOT_FOUND);
+        assert_eq!(responses[2]["id"], 12);
+        assert_eq!(responses[2]["error"]["code"], errors::INVALID_REQUEST);
+        assert_eq!(responses[3]["id"], Value::Null);
+        assert_eq!(responses[3]["error"]["code"], errors::INVALID_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn batch_empty_is_invalid_request() {
+        let mut d = make_dispatcher();
+        let result = d.dispatch_batch(Vec::new()).await;
+        let response = result.response.unwrap();
+        assert!(response.is_object());
+        assert_eq!(response["id"], Value::Null);
+        assert_eq!(response["error"]["code"], errors::INVALID_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn batch_of_notifications_has_no_response() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let batch = vec![
+            json!({"jsonrpc": "2.0", "method": "health.check"}),
+            json!({"jsonrpc": "2.0", "method": "unknown.method"}),
+        ];
+        let result = d.dispatch_batch(batch).await;
+        assert!(result.response.is_none());
+        assert!(!result.shutdown);
+    }
+
+    #[tokio::test]
+    async fn batch_stops_after_shutdown() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let batch = vec![
+            json!({"jsonrpc": "2.0", "method": "agent.shutdown", "params": {}, "id": 1}),
+            json!({"jsonrpc": "2.0", "method": "health.check", "id": 2}),
+        ];
+        let result = d.dispatch_batch(batch).await;
+        assert!(result.shutdown);
+        let responses = result.response.unwrap();
+        assert_eq!(responses.as_array().unwrap().len(), 1);
+        assert_eq!(responses[0]["id"], 1);
+    }
+
     // ── Session attach/detach/input tests ──────────────────────────
 
     #[tokio::test]
@@ -1726,6 +2913,124 @@ mod tests {
         assert_eq!(json["error"]["code"], errors::SESSION_NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn session_line_control_returns_success() {
+        let (mut d, mgr) = make_dispatcher_with_manager();
+        init_dispatcher(&mut d).await;
+
+        let snapshot = mgr
+            .create_stub_session("serial", "break-test".to_string(), json!({}))
+            .await
+            .unwrap();
+
+        let req = make_request(
+            "connection.line_control",
+            json!({
+                "session_id": snapshot.id,
+                "control": {"type": "break", "durationMs": 250},
+            }),
+            3,
+        );
+        let result = d.dispatch(req).await;
+        let json = result.to_json();
+        assert!(json.get("result").is_some(), "{json}");
+    }
+
+    #[tokio::test]
+    async fn session_line_control_not_found() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let req = make_request(
+            "connection.line_control",
+            json!({
+                "session_id": "nonexistent",
+                "control": {"type": "setDtr", "level": true},
+            }),
+            2,
+        );
+        let result = d.dispatch(req).await;
+        let json = result.to_json();
+        assert_eq!(json["error"]["code"], errors::SESSION_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn session_line_control_invalid_control() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let req = make_request(
+            "connection.line_control",
+            json!({"session_id": "abc", "control": {"type": "reset"}}),
+            2,
+        );
+        let result = d.dispatch(req).await;
+        let json = result.to_json();
+        assert_eq!(json["error"]["code"], errors::INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn session_recording_start_and_stop() {
+        let (mut d, mgr) = make_dispatcher_with_manager();
+        init_dispatcher(&mut d).await;
+
+        let snapshot = mgr
+            .create_stub_session("local", "record-test".to_string(), json!({}))
+            .await
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.cast");
+
+        let req = make_request(
+            "connection.recording.start",
+            json!({
+                "session_id": snapshot.id,
+                "path": path.display().to_string(),
+                "cols": 100,
+                "rows": 30,
+            }),
+            3,
+        );
+        let json = d.dispatch(req).await.to_json();
+        assert!(json.get("result").is_some(), "{json}");
+        assert!(path.exists());
+
+        let req = make_request(
+            "connection.recording.stop",
+            json!({"session_id": snapshot.id}),
+            4,
+        );
+        let json = d.dispatch(req).await.to_json();
+        assert_eq!(json["result"]["stopped"], true);
+
+        let req = make_request(
+            "connection.recording.stop",
+            json!({"session_id": snapshot.id}),
+            5,
+        );
+        let json = d.dispatch(req).await.to_json();
+        assert_eq!(json["result"]["stopped"], false);
+    }
+
+    #[tokio::test]
+    async fn session_recording_start_not_found() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let req = make_request(
+            "connection.recording.start",
+            json!({
+                "session_id": "nonexistent",
+                "path": "/tmp/never.cast",
+                "cols": 80,
+                "rows": 24,
+            }),
+            2,
+        );
+        let json = d.dispatch(req).await.to_json();
+        assert_eq!(json["error"]["code"], errors::SESSION_NOT_FOUND);
+    }
+
     // ── Full protocol flow integration test ─────────────────────────
 
     #[tokio::test]
@@ -1849,14 +3154,112 @@ mod tests {
         assert_eq!(result["result"]["name"], "Build Shell");
         assert!(result["result"]["persistent"].as_bool().unwrap());
 
-        // List
-        let req = make_request("connections.list", json!({}), 3);
+        // List
+        let req = make_request("connections.list", json!({}), 3);
+        let result = d.dispatch(req).await.to_json();
+        let conns = result["result"]["connections"].as_array().unwrap();
+        assert_eq!(conns.len(), 1);
+        assert_eq!(conns[0]["name"], "Build Shell");
+        let folders = result["result"]["folders"].as_array().unwrap();
+        assert!(folders.is_empty());
+    }
+
+    #[tokio::test]
+    async fn connections_create_rejects_invalid_config() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let req = make_request(
+            "connections.create",
+            json!({"name": "No Host", "type": "ssh", "config": {"username": "admin"}}),
+            2,
+        );
+        let result = d.dispatch(req).await.to_json();
+        assert_eq!(result["error"]["code"], errors::INVALID_CONFIGURATION);
+        let message = result["error"]["message"].as_str().unwrap();
+        assert!(message.contains("host"), "message: {message}");
+        let field_errors = result["error"]["data"]["errors"].as_array().unwrap();
+        assert!(field_errors.iter().any(|e| e["field"] == "host"));
+
+        let req = make_request("connections.list", json!({}), 3);
+        let result = d.dispatch(req).await.to_json();
+        assert!(result["result"]["connections"]
+            .as_array()
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn connections_create_accepts_valid_config() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let req = make_request(
+            "connections.create",
+            json!({
+                "name": "Router",
+                "type": "ssh",
+                "config": {
+                    "host": "10.0.0.1",
+                    "port": 22,
+                    "username": "admin",
+                    "authMethod": "agent"
+                }
+            }),
+            2,
+        );
+        let result = d.dispatch(req).await.to_json();
+        assert!(result.get("error").is_none(), "create failed: {result}");
+        assert_eq!(result["result"]["config"]["host"], "10.0.0.1");
+    }
+
+    #[tokio::test]
+    async fn connections_create_rejects_unknown_shell_name() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let req = make_request(
+            "connections.create",
+            json!({"name": "Shell", "type": "shell", "config": {"shell": "no-such-shell"}}),
+            2,
+        );
+        let result = d.dispatch(req).await.to_json();
+        assert_eq!(result["error"]["code"], errors::INVALID_CONFIGURATION);
+        let field_errors = result["error"]["data"]["errors"].as_array().unwrap();
+        assert!(field_errors.iter().any(|e| e["field"] == "shell"));
+    }
+
+    #[tokio::test]
+    async fn connections_update_rejects_invalid_config() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let req = make_request(
+            "connections.create",
+            json!({
+                "name": "Router",
+                "type": "ssh",
+                "config": {"host": "10.0.0.1", "username": "admin"}
+            }),
+            2,
+        );
+        let result = d.dispatch(req).await.to_json();
+        let conn_id = result["result"]["id"].as_str().unwrap().to_string();
+
+        let req = make_request(
+            "connections.update",
+            json!({"id": conn_id, "config": {"username": "admin"}}),
+            3,
+        );
+        let result = d.dispatch(req).await.to_json();
+        assert_eq!(result["error"]["code"], errors::INVALID_CONFIGURATION);
+
+        let req = make_request("connections.list", json!({}), 4);
         let result = d.dispatch(req).await.to_json();
-        let conns = result["result"]["connections"].as_array().unwrap();
-        assert_eq!(conns.len(), 1);
-        assert_eq!(conns[0]["name"], "Build Shell");
-        let folders = result["result"]["folders"].as_array().unwrap();
-        assert!(folders.is_empty());
+        assert_eq!(
+            result["result"]["connections"][0]["config"]["host"],
+            "10.0.0.1"
+        );
     }
 
     #[tokio::test]
@@ -2054,6 +3457,150 @@ mod tests {
         assert_eq!(result["result"]["folder_id"], folder_id);
     }
 
+    // ── Export / import tests ───────────────────────────────────────
+
+    /// Populate a dispatcher with two folders (one nested) and two
+    /// connections, one of which stores a password. Returns the export.
+    async fn populate_and_export(d: &mut Dispatcher) -> Value {
+        let req = make_request("connections.folders.create", json!({"name": "Lab"}), 2);
+        let lab = d.dispatch(req).await.to_json()["result"]["id"].clone();
+        let req = make_request(
+            "connections.folders.create",
+            json!({"name": "Racks", "parent_id": lab}),
+            3,
+        );
+        let racks = d.dispatch(req).await.to_json()["result"]["id"].clone();
+
+        let req = make_request(
+            "connections.create",
+            json!({"name": "Shell", "type": "shell", "folder_id": lab}),
+            4,
+        );
+        d.dispatch(req).await;
+        let req = make_request(
+            "connections.create",
+            json!({
+                "name": "Switch",
+                "type": "ssh",
+                "folder_id": racks,
+                "config": {
+                    "host": "10.0.0.2",
+                    "username": "admin",
+                    "authMethod": "password",
+                    "password": "hunter2",
+                    "fallbackPassword": "fallback-secret",
+                    "keyPassphrase": "key-secret",
+                    "jumpHost": {
+                        "host": "bastion",
+                        "password": "jump-secret",
+                        "passphrase": "jump-key-secret"
+                    }
+                }
+            }),
+            5,
+        );
+        d.dispatch(req).await;
+
+        let req = make_request("connections.export", json!({}), 6);
+        d.dispatch(req).await.to_json()["result"].clone()
+    }
+
+    #[tokio::test]
+    async fn connections_export_excludes_secrets() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+        let export = populate_and_export(&mut d).await;
+
+        assert_eq!(export["connections"].as_array().unwrap().len(), 2);
+        assert_eq!(export["folders"].as_array().unwrap().len(), 2);
+        let text = export.to_string();
+        for secret in [
+            "hunter2",
+            "fallback-secret",
+            "key-secret",
+            "jump-secret",
+            "jump-key-secret",
+        ] {
+            assert!(!text.contains(secret), "{secret}");
+        }
+        assert!(text.contains("10.0.0.2"));
+    }
+
+    #[tokio::test]
+    async fn connections_export_import_round_trip() {
+        let mut source = make_dispatcher();
+        init_dispatcher(&mut source).await;
+        let export = populate_and_export(&mut source).await;
+
+        let mut target = make_dispatcher();
+        init_dispatcher(&mut target).await;
+        let req = make_request("connections.import", export.clone(), 2);
+        let result = target.dispatch(req).await.to_json();
+        assert_eq!(result["result"]["connections_imported"], 2);
+        assert_eq!(result["result"]["folders_imported"], 2);
+        assert_eq!(result["result"]["skipped"], 0);
+
+        let req = make_request("connections.export", json!({}), 3);
+        let reexport = target.dispatch(req).await.to_json()["result"].clone();
+        assert_eq!(reexport, export);
+    }
+
+    #[tokio::test]
+    async fn connections_import_skips_existing_by_default() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+        let mut export = populate_and_export(&mut d).await;
+        for conn in export["connections"].as_array_mut().unwrap() {
+            conn["name"] = json!("Renamed");
+        }
+
+        let req = make_request("connections.import", export, 7);
+        let result = d.dispatch(req).await.to_json();
+        assert_eq!(result["result"]["connections_imported"], 0);
+        assert_eq!(result["result"]["folders_imported"], 0);
+        assert_eq!(result["result"]["skipped"], 4);
+
+        let req = make_request("connections.list", json!({}), 8);
+        let list = d.dispatch(req).await.to_json();
+        let conns = list["result"]["connections"].as_array().unwrap();
+        assert!(conns.iter().all(|c| c["name"] != "Renamed"));
+    }
+
+    #[tokio::test]
+    async fn connections_import_overwrite_keeps_stored_secrets() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+        let mut export = populate_and_export(&mut d).await;
+        for conn in export["connections"].as_array_mut().unwrap() {
+            conn["name"] = json!("Renamed");
+        }
+        export["strategy"] = json!("overwrite");
+
+        let req = make_request("connections.import", export, 7);
+        let result = d.dispatch(req).await.to_json();
+        assert_eq!(result["result"]["connections_imported"], 2);
+        assert_eq!(result["result"]["folders_imported"], 2);
+        assert_eq!(result["result"]["skipped"], 0);
+
+        let req = make_request("connections.list", json!({}), 8);
+        let list = d.dispatch(req).await.to_json();
+        let conns = list["result"]["connections"].as_array().unwrap();
+        assert_eq!(conns.len(), 2);
+        assert!(conns.iter().all(|c| c["name"] == "Renamed"));
+        let switch = conns.iter().find(|c| c["session_type"] == "ssh").unwrap();
+        assert_eq!(switch["config"]["password"], "hunter2");
+        assert_eq!(switch["config"]["jumpHost"]["password"], "jump-secret");
+    }
+
+    #[tokio::test]
+    async fn connections_import_rejects_invalid_strategy() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+        let req = make_request("connections.import", json!({"strategy": "merge"}), 2);
+        let result = d.dispatch(req).await.to_json();
+        assert_eq!(result["error"]["code"], errors::INVALID_PARAMS);
+    }
+
     // ── File browsing tests ────────────────────────────────────────
 
     #[tokio::test]
@@ -2118,6 +3665,36 @@ mod tests {
         assert_eq!(result["result"]["size"], 13);
     }
 
+    #[tokio::test]
+    async fn files_write_normalizes_newlines() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let mixed = b"a\r\nb\nc";
+        let b64 = base64::engine::general_purpose::STANDARD;
+        let cases: [(&str, &[u8]); 3] = [
+            ("preserve", b"a\r\nb\nc"),
+            ("lf", b"a\nb\nc"),
+            ("crlf", b"a\r\nb\r\nc"),
+        ];
+        for (i, (mode, expected)) in cases.into_iter().enumerate() {
+            let file_path = dir.path().join(format!("{mode}.txt"));
+            let req = make_request(
+                "connection.files.write",
+                json!({
+                    "path": file_path.to_str().unwrap(),
+                    "data": b64.encode(mixed),
+                    "newline": mode,
+                }),
+                i as u64 + 2,
+            );
+            let result = d.dispatch(req).await.to_json();
+            assert!(result.get("result").is_some(), "{mode}: {result}");
+            assert_eq!(std::fs::read(&file_path).unwrap(), expected, "{mode}");
+        }
+    }
+
     #[tokio::test]
     async fn files_stat() {
         let mut d = make_dispatcher();
@@ -2138,6 +3715,30 @@ mod tests {
         assert_eq!(result["result"]["size"], 5);
     }
 
+    #[tokio::test]
+    async fn files_grep() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "one\nneedle two\n").unwrap();
+
+        let req = make_request(
+            "connection.files.grep",
+            json!({
+                "path": dir.path().to_str().unwrap(),
+                "pattern": "needle",
+                "options": {"maxResults": 5},
+            }),
+            2,
+        );
+        let result = d.dispatch(req).await.to_json();
+        let matches = result["result"]["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0]["line"], 2);
+        assert_eq!(matches[0]["text"], "needle two");
+    }
+
     #[tokio::test]
     async fn files_delete() {
         let mut d = make_dispatcher();
@@ -2178,6 +3779,40 @@ mod tests {
         assert!(new.exists());
     }
 
+    #[tokio::test]
+    async fn files_rename_onto_existing_returns_destination_exists() {
+        let mut d = make_dispatcher();
+        init_dispatcher(&mut d).await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.txt");
+        let new = dir.path().join("new.txt");
+        std::fs::write(&old, "old").unwrap();
+        std::fs::write(&new, "new").unwrap();
+
+        let req = make_request(
+            "connection.files.rename",
+            json!({"old_path": old.to_str().unwrap(), "new_path": new.to_str().unwrap()}),
+            2,
+        );
+        let result = d.dispatch(req).await.to_json();
+        assert_eq!(result["error"]["code"], errors::DESTINATION_EXISTS);
+        assert_eq!(std::fs::read_to_string(&new).unwrap(), "new");
+
+        let req = make_request(
+            "connection.files.rename",
+            json!({
+                "old_path": old.to_str().unwrap(),
+                "new_path": new.to_str().unwrap(),
+                "overwrite": true
+            }),
+            3,
+        );
+        let result = d.dispatch(req).await.to_json();
+        assert!(result.get("result").is_some());
+        assert_eq!(std::fs::read_to_string(&new).unwrap(), "old");
+    }
+
     #[tokio::test]
     async fn files_with_connection_id_not_found() {
         let mut d = make_dispatcher();
@@ -2447,6 +4082,45 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn monitoring_subscribe_docker_session_targets_its_container() {
+        let store = Arc::new(MockConnectionStore::new());
+        let monitor = Arc::new(MockMonitoringManager::new());
+        let subscribed = monitor.subscribed.clone();
+        let mut d = make_mock_dispatcher_with_stores(
+            store as Arc<dyn ConnectionStoreApi>,
+            monitor as Arc<dyn MonitoringManagerApi>,
+        );
+        init_mock(&mut d).await;
+
+        let create_req = make_request(
+            "connection.create",
+            json!({"type": "docker", "title": "Container", "config": {"image": "alpine"}}),
+            2,
+        );
+        let create_result = d.dispatch(create_req).await.to_json();
+        let session_id = create_result["result"]["session_id"]
+            .as_str()
+            .expect("session_id should be present")
+            .to_string();
+
+        let sub_req = make_request(
+            "connection.monitoring.subscribe",
+            json!({"host": session_id, "interval_ms": 2000}),
+            3,
+        );
+        let result = d.dispatch(sub_req).await.to_json();
+        assert!(
+            result.get("result").is_some(),
+            "subscribe with docker session id should succeed: {result}"
+        );
+        assert_eq!(
+            subscribed.lock().await.as_slice(),
+            [format!("docker:{session_id}")],
+            "docker session should resolve to its container's monitoring host"
+        );
+    }
+
     #[tokio::test]
     async fn monitoring_unsubscribe_local_session_resolves_to_self() {
         let store = Arc::new(MockConnectionStore::new());
@@ -2664,6 +4338,9 @@ mod tests {
         /// When `Some`, `create()` returns this error.
         create_error: Option<SessionCreateError>,
         sessions: Arc<AsyncMutex<Vec<SessionSnapshot>>>,
+        /// Artificial delay before `list()` and `create()` return, to
+        /// simulate a stuck backend.
+        delay: std::time::Duration,
     }
 
     impl MockSessionManager {
@@ -2672,6 +4349,7 @@ mod tests {
                 registry: crate::registry::build_registry(),
                 create_error: None,
                 sessions: Arc::new(AsyncMutex::new(Vec::new())),
+                delay: std::time::Duration::ZERO,
             }
         }
 
@@ -2680,6 +4358,14 @@ mod tests {
                 registry: crate::registry::build_registry(),
                 create_error: Some(error),
                 sessions: Arc::new(AsyncMutex::new(Vec::new())),
+                delay: std::time::Duration::ZERO,
+            }
+        }
+
+        fn with_delay(delay: std::time::Duration) -> Self {
+            Self {
+                delay,
+                ..Self::new()
             }
         }
     }
@@ -2696,6 +4382,7 @@ mod tests {
             title: String,
             _settings: serde_json::Value,
         ) -> Result<SessionSnapshot, SessionCreateError> {
+            tokio::time::sleep(self.delay).await;
             if let Some(ref e) = self.create_error {
                 return Err(match e {
                     SessionCreateError::LimitReached => SessionCreateError::LimitReached,
@@ -2715,12 +4402,15 @@ mod tests {
                 created_at: chrono::Utc::now(),
                 last_activity: chrono::Utc::now(),
                 attached: false,
+                // Stand in for a local child process with the test process itself.
+                process_id: (type_id == "local").then(std::process::id),
             };
             self.sessions.lock().await.push(snapshot.clone());
             Ok(snapshot)
         }
 
         async fn list(&self) -> Vec<SessionSnapshot> {
+            tokio::time::sleep(self.delay).await;
             self.sessions.lock().await.clone()
         }
 
@@ -2746,6 +4436,8 @@ mod tests {
 
         async fn detach_all(&self) {}
 
+        fn set_daemon_idle_ttl(&self, _ttl_secs: u64) {}
+
         async fn active_count(&self) -> u32 {
             self.sessions.lock().await.len() as u32
         }
@@ -2785,6 +4477,39 @@ mod tests {
                 Err("Session not found".to_string())
             }
         }
+
+        async fn line_control(
+            &self,
+            session_id: &str,
+            _control: termihub_core::connection::LineControl,
+        ) -> Result<(), String> {
+            let sessions = self.sessions.lock().await;
+            if sessions.iter().any(|s| s.id == session_id) {
+                Ok(())
+            } else {
+                Err("Session not found".to_string())
+            }
+        }
+
+        async fn start_recording(
+            &self,
+            session_id: &str,
+            _path: &str,
+            _cols: u16,
+            _rows: u16,
+            _record_input: bool,
+        ) -> Result<(), String> {
+            let sessions = self.sessions.lock().await;
+            if sessions.iter().any(|s| s.id == session_id) {
+                Ok(())
+            } else {
+                Err("Session not found".to_string())
+            }
+        }
+
+        async fn stop_recording(&self, _session_id: &str) -> bool {
+            false
+        }
     }
 
     fn make_mock_dispatcher() -> Dispatcher<MockSessionManager> {
@@ -2971,6 +4696,88 @@ mod tests {
         assert!(matches!(result, DispatchResult::Success(_)));
     }
 
+    /// Initialize a dispatcher whose `connection.list` and
+    /// `connection.create` take `delay`, with the given request timeout.
+    async fn init_slow_mock(
+        delay: std::time::Duration,
+        request_timeout_secs: u64,
+    ) -> Dispatcher<MockSessionManager> {
+        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let tmp = std::env::temp_dir().join(format!("termihub-mock-{}.json", uuid::Uuid::new_v4()));
+        let conn_store = Arc::new(ConnectionStore::new_temp(tmp));
+        let monitoring_manager = Arc::new(MonitoringManager::new(tx, conn_store.clone()));
+        let mut d = Dispatcher::new(
+            Arc::new(MockSessionManager::with_delay(delay)),
+            conn_store as Arc<dyn ConnectionStoreApi>,
+            monitoring_manager as Arc<dyn MonitoringManagerApi>,
+        );
+        let mut params = init_params();
+        params["agentSettings"] = json!({ "requestTimeoutSecs": request_timeout_secs });
+        let result = d.dispatch(make_request("initialize", params, 1)).await;
+        assert!(matches!(result, DispatchResult::Success(_)));
+        d
+    }
+
+    #[tokio::test]
+    async fn slow_handler_times_out_with_request_id() {
+        let mut d = init_slow_mock(std::time::Duration::from_secs(60), 1).await;
+
+        let started = Instant::now();
+        let result = d
+            .dispatch(make_request("connection.list", json!({}), 7))
+            .await;
+        assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+        let json = result.to_json();
+        assert_eq!(json["id"], 7);
+        assert_eq!(json["error"]["code"], errors::REQUEST_TIMEOUT);
+        assert!(json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("connection.list"));
+
+        // The abandoned request does not wedge the dispatcher.
+        let result = d.dispatch(make_request("health.check", json!({}), 8)).await;
+        assert!(matches!(result, DispatchResult::Success(_)));
+    }
+
+    #[tokio::test]
+    async fn session_create_is_not_cut_off_by_timeout() {
+        let mut d = init_slow_mock(std::time::Duration::from_millis(1500), 1).await;
+
+        let req = make_request(
+            "connection.create",
+            json!({"type": "local", "config": {}}),
+            2,
+        );
+        let result = d.dispatch(req).await.to_json();
+        assert!(result["result"]["session_id"].is_string(), "{result}");
+    }
+
+    #[test]
+    fn mutating_methods_run_to_completion() {
+        for method in [
+            "connection.create",
+            "connection.files.write",
+            "connection.files.rename",
+            "connection.files.delete",
+            "connection.files.mkdir",
+        ] {
+            assert!(runs_to_completion(method), "{method}");
+        }
+        assert!(!runs_to_completion("connection.list"));
+        assert!(!runs_to_completion("connection.files.read"));
+    }
+
+    #[tokio::test]
+    async fn request_within_timeout_succeeds() {
+        let mut d = init_slow_mock(std::time::Duration::from_millis(10), 5).await;
+        let result = d
+            .dispatch(make_request("connection.list", json!({}), 2))
+            .await;
+        assert!(matches!(result, DispatchResult::Success(_)));
+    }
+
     #[tokio::test]
     async fn mock_session_create_backend_failed_returns_error() {
         let mut d = make_mock_dispatcher_failing(SessionCreateError::BackendFailed(
@@ -3029,6 +4836,59 @@ mod tests {
         assert_eq!(sessions[0]["session_id"], sid);
     }
 
+    #[tokio::test]
+    async fn mock_session_stats_keyed_by_session_id() {
+        let mut d = make_mock_dispatcher();
+        init_mock(&mut d).await;
+
+        let req = make_request(
+            "connection.create",
+            json!({"type": "local", "config": {}}),
+            2,
+        );
+        let local_id = d.dispatch(req).await.to_json()["result"]["session_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+        let req = make_request(
+            "connection.create",
+            json!({"type": "serial", "config": {}}),
+            3,
+        );
+        let serial_id = d.dispatch(req).await.to_json()["result"]["session_id"]
+            .as_str()
+            .unwrap()
+            .to_string();
+
+        let req = make_request("connection.stats", json!({}), 4);
+        let result = d.dispatch(req).await.to_json();
+        let sessions = result["result"]["sessions"].as_object().unwrap();
+        assert_eq!(sessions.len(), 2);
+
+        // Network/device-backed sessions report zeros and no PID.
+        let serial = &sessions[&serial_id];
+        assert!(serial.get("pid").is_none());
+        assert_eq!(serial["cpu_percent"], 0.0);
+        assert_eq!(serial["rss_kb"], 0);
+
+        // Process-backed sessions report their PID; the first CPU sample is 0.
+        let local = &sessions[&local_id];
+        assert_eq!(local["pid"], std::process::id());
+        assert_eq!(local["cpu_percent"], 0.0);
+        #[cfg(target_os = "linux")]
+        assert!(local["rss_kb"].as_u64().unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn mock_session_stats_empty_without_sessions() {
+        let mut d = make_mock_dispatcher();
+        init_mock(&mut d).await;
+
+        let req = make_request("connection.stats", json!({}), 2);
+        let result = d.dispatch(req).await.to_json();
+        assert_eq!(result["result"]["sessions"], json!({}));
+    }
+
     #[tokio::test]
     async fn mock_health_check_counts_sessions() {
         let mut d = make_mock_dispatcher();
@@ -3060,7 +4920,11 @@ mod tests {
 
         let req = make_request(
             "connections.create",
-            json!({"name": "My SSH", "type": "ssh", "config": {"host": "example.com"}}),
+            json!({
+                "name": "My SSH",
+                "type": "ssh",
+                "config": {"host": "example.com", "username": "admin"}
+            }),
             2,
         );
         let result = d.dispatch(req).await.to_json();
@@ -1 +1,12 @@
+pub mod audit;
 pub mod dispatch;
+
+/// Connection config keys holding secrets, at any nesting level.
+///
+/// Redacted in the audit log and stripped from `connections.export`.
+pub(crate) const SECRET_KEYS: &[&str] = &[
+    "password",
+    "fallbackPassword",
+    "passphrase",
+    "keyPassphrase",
+];
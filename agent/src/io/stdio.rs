@@ -1,29 +1,39 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use tokio::io::BufReader;
 use tokio_util::sync::CancellationToken;
 use tracing::info;
 
+use crate::files::watch::FileWatchManager;
+use crate::handler::audit::AuditLog;
 use crate::handler::dispatch::Dispatcher;
-use crate::io::transport::run_transport_loop;
+use crate::io::transport::{run_transport_loop, TransportConfig};
+use crate::log_capture::SharedLogBuffer;
 use crate::monitoring::{MonitoringManager, MonitoringManagerApi};
 use crate::protocol::messages::JsonRpcNotification;
 use crate::registry::build_registry;
 use crate::session::definitions::{ConnectionStore, ConnectionStoreApi};
-use crate::session::manager::SessionManager;
+use crate::session::manager::{spawn_idle_reaper, SessionManager};
 
 /// Run the NDJSON stdio transport loop.
 ///
 /// Reads JSON-RPC messages from stdin (one per line) and writes
 /// responses to stdout. Backend notifications are interleaved via
 /// a `tokio::select!` loop. Logs go to stderr.
-pub async fn run_stdio_loop(shutdown: CancellationToken) -> anyhow::Result<()> {
+pub async fn run_stdio_loop(
+    shutdown: CancellationToken,
+    disabled_types: HashSet<String>,
+    audit_log: Option<Arc<AuditLog>>,
+    log_buffer: SharedLogBuffer,
+) -> anyhow::Result<()> {
     let (notification_tx, mut notification_rx) =
         tokio::sync::mpsc::unbounded_channel::<JsonRpcNotification>();
 
     let registry = Arc::new(build_registry());
     let session_manager = Arc::new(SessionManager::new(notification_tx.clone(), registry));
     let connection_store = Arc::new(ConnectionStore::new(ConnectionStore::default_path()));
+    let file_watcher = Arc::new(FileWatchManager::new(notification_tx.clone()));
     let monitoring_manager = Arc::new(MonitoringManager::new(
         notification_tx,
         connection_store.clone(),
@@ -36,11 +46,20 @@ pub async fn run_stdio_loop(shutdown: CancellationToken) -> anyhow::Result<()> {
     #[cfg(unix)]
     session_manager.recover_sessions().await;
 
+    // Close daemon sessions left detached and idle past the configured TTL
+    let reaper = spawn_idle_reaper(session_manager.clone(), shutdown.child_token());
+
     let mut dispatcher = Dispatcher::new(
         session_manager.clone(),
         connection_store.clone() as Arc<dyn ConnectionStoreApi>,
         monitoring_manager.clone() as Arc<dyn MonitoringManagerApi>,
-    );
+    )
+    .with_file_watcher(file_watcher.clone())
+    .with_disabled_types(disabled_types)
+    .with_log_buffer(log_buffer);
+    if let Some(audit_log) = audit_log {
+        dispatcher = dispatcher.with_audit_log(audit_log, "stdio");
+    }
 
     let stdin = tokio::io::stdin();
     let mut stdout = tokio::io::stdout();
@@ -54,12 +73,15 @@ pub async fn run_stdio_loop(shutdown: CancellationToken) -> anyhow::Result<()> {
         &mut dispatcher,
         &mut notification_rx,
         shutdown,
+        &TransportConfig::from_env(),
     )
     .await?;
 
     // Graceful shutdown: stop monitoring and close all sessions
     info!("Shutting down — stopping monitoring and closing all sessions");
+    reaper.abort();
     monitoring_manager.shutdown().await;
+    file_watcher.shutdown().await;
     session_manager.close_all().await;
 
     Ok(())
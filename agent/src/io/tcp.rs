@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use tokio::io::BufReader;
@@ -5,13 +6,16 @@ use tokio::net::TcpListener;
 use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
+use crate::files::watch::FileWatchManager;
+use crate::handler::audit::AuditLog;
 use crate::handler::dispatch::Dispatcher;
-use crate::io::transport::run_transport_loop;
+use crate::io::transport::{run_transport_loop, TransportConfig};
+use crate::log_capture::SharedLogBuffer;
 use crate::monitoring::{MonitoringManager, MonitoringManagerApi};
 use crate::protocol::messages::JsonRpcNotification;
 use crate::registry::build_registry;
 use crate::session::definitions::{ConnectionStore, ConnectionStoreApi};
-use crate::session::manager::SessionManager;
+use crate::session::manager::{spawn_idle_reaper, SessionManager};
 
 /// Run the NDJSON transport loop over a TCP listener.
 ///
@@ -21,8 +25,15 @@ use crate::session::manager::SessionManager;
 /// persist when a client disconnects and reconnects.
 ///
 /// The accept loop exits when the cancellation token is triggered.
-pub async fn run_tcp_listener(addr: &str, shutdown: CancellationToken) -> anyhow::Result<()> {
+pub async fn run_tcp_listener(
+    addr: &str,
+    shutdown: CancellationToken,
+    disabled_types: HashSet<String>,
+    audit_log: Option<Arc<AuditLog>>,
+    log_buffer: SharedLogBuffer,
+) -> anyhow::Result<()> {
     let listener = TcpListener::bind(addr).await?;
+    let transport_config = TransportConfig::from_env();
     info!("Listening on {}", listener.local_addr()?);
 
     let (notification_tx, mut notification_rx) =
@@ -30,6 +41,7 @@ pub async fn run_tcp_listener(addr: &str, shutdown: CancellationToken) -> anyhow
     let registry = Arc::new(build_registry());
     let session_manager = Arc::new(SessionManager::new(notification_tx.clone(), registry));
     let connection_store = Arc::new(ConnectionStore::new(ConnectionStore::default_path()));
+    let file_watcher = Arc::new(FileWatchManager::new(notification_tx.clone()));
     let monitoring_manager = Arc::new(MonitoringManager::new(
         notification_tx,
         connection_store.clone(),
@@ -42,6 +54,9 @@ pub async fn run_tcp_listener(addr: &str, shutdown: CancellationToken) -> anyhow
     #[cfg(unix)]
     session_manager.recover_sessions().await;
 
+    // Close daemon sessions left detached and idle past the configured TTL
+    let reaper = spawn_idle_reaper(session_manager.clone(), shutdown.child_token());
+
     loop {
         tokio::select! {
             _ = shutdown.cancelled() => {
@@ -62,7 +77,13 @@ pub async fn run_tcp_listener(addr: &str, shutdown: CancellationToken) -> anyhow
                     session_manager.clone(),
                     connection_store.clone() as Arc<dyn ConnectionStoreApi>,
                     monitoring_manager.clone() as Arc<dyn MonitoringManagerApi>,
-                );
+                )
+                .with_file_watcher(file_watcher.clone())
+                .with_disabled_types(disabled_types.clone())
+                .with_log_buffer(log_buffer.clone());
+                if let Some(audit_log) = &audit_log {
+                    dispatcher = dispatcher.with_audit_log(audit_log.clone(), peer.to_string());
+                }
 
                 let (reader_half, mut writer_half) = stream.into_split();
                 let mut reader = BufReader::new(reader_half);
@@ -73,6 +94,7 @@ pub async fn run_tcp_listener(addr: &str, shutdown: CancellationToken) -> anyhow
                     &mut dispatcher,
                     &mut notification_rx,
                     shutdown.child_token(),
+                    &transport_config,
                 )
                 .await;
 
@@ -81,6 +103,9 @@ pub async fn run_tcp_listener(addr: &str, shutdown: CancellationToken) -> anyhow
                     Err(e) => warn!("Client {} error: {}", peer, e),
                 }
 
+                // Watches only notify the client that created them
+                file_watcher.shutdown().await;
+
                 // Detach all sessions so they remain alive for the next client
                 session_manager.detach_all().await;
             }
@@ -89,7 +114,9 @@ pub async fn run_tcp_listener(addr: &str, shutdown: CancellationToken) -> anyhow
 
     // Agent shutting down: stop monitoring and close all sessions
     info!("Shutting down — stopping monitoring and closing all sessions");
+    reaper.abort();
     monitoring_manager.shutdown().await;
+    file_watcher.shutdown().await;
     session_manager.close_all().await;
 
     Ok(())
@@ -1,4 +1,4 @@
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWriteExt};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, warn};
 
@@ -6,65 +6,281 @@ use crate::handler::dispatch::Dispatcher;
 use crate::protocol::errors;
 use crate::protocol::messages::{JsonRpcErrorResponse, JsonRpcNotification, JsonRpcRequest};
 
-/// Maximum message size: 1 MiB as defined by the protocol spec.
-const MAX_LINE_SIZE: usize = 1_048_576;
+/// Default maximum request size: 1 MiB as defined by the protocol spec.
+pub const DEFAULT_MAX_REQUEST_SIZE: usize = 1_048_576;
+
+/// Environment variable overriding [`TransportConfig::max_request_size`].
+const MAX_REQUEST_SIZE_ENV: &str = "TERMIHUB_MAX_REQUEST_SIZE";
+
+/// Environment variable enabling [`TransportConfig::close_on_oversized`].
+const CLOSE_ON_OVERSIZED_ENV: &str = "TERMIHUB_CLOSE_ON_OVERSIZED";
+
+/// Number of leading bytes of an oversized request scanned for its `id`.
+const ID_RECOVERY_PREFIX: usize = 4096;
 
 /// Sender half for backend tasks to emit notifications.
 pub type NotificationSender = tokio::sync::mpsc::UnboundedSender<JsonRpcNotification>;
 
+/// Limits applied by the transport loop to incoming requests.
+#[derive(Debug, Clone)]
+pub struct TransportConfig {
+    /// Maximum size of a single NDJSON request line in bytes.
+    pub max_request_size: usize,
+    /// Close the connection after rejecting an oversized request.
+    pub close_on_oversized: bool,
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        Self {
+            max_request_size: DEFAULT_MAX_REQUEST_SIZE,
+            close_on_oversized: false,
+        }
+    }
+}
+
+impl TransportConfig {
+    /// Build the config from `TERMIHUB_MAX_REQUEST_SIZE` and
+    /// `TERMIHUB_CLOSE_ON_OVERSIZED`, falling back to the defaults.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let max_request_size = std::env::var(MAX_REQUEST_SIZE_ENV)
+            .ok()
+            .and_then(|v| v.trim().parse::<usize>().ok())
+            .filter(|&n| n > 0)
+            .unwrap_or(defaults.max_request_size);
+        let close_on_oversized = std::env::var(CLOSE_ON_OVERSIZED_ENV)
+            .map(|v| matches!(v.trim(), "1" | "true" | "yes"))
+            .unwrap_or(defaults.close_on_oversized);
+        Self {
+            max_request_size,
+            close_on_oversized,
+        }
+    }
+}
+
+/// Result of a single [`LineReader::read_line`] call.
+#[derive(Debug, PartialEq)]
+pub enum LineRead {
+    /// A complete line (without the trailing newline) within the size limit.
+    Line(Vec<u8>),
+    /// The line exceeded the size limit. Only the leading bytes are kept
+    /// so the request `id` can be recovered; the rest is discarded.
+    TooLarge { prefix: Vec<u8> },
+    /// The reader reached end of input.
+    Eof,
+}
+
+/// Incremental NDJSON line reader that enforces a maximum line size.
+///
+/// Bytes past the limit are discarded as they arrive instead of being
+/// buffered, so an oversized request cannot exhaust memory. All partial
+/// state lives in `self`, which makes [`read_line`](Self::read_line) safe
+/// to use as a `tokio::select!` branch.
+pub struct LineReader {
+    max_size: usize,
+    buf: Vec<u8>,
+    /// Set after reporting an oversized line until its newline is seen.
+    discarding: bool,
+}
+
+impl LineReader {
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            buf: Vec::new(),
+            discarding: false,
+        }
+    }
+
+    /// Read the next line, returning as soon as it completes or exceeds
+    /// the size limit.
+    pub async fn read_line<R>(&mut self, reader: &mut R) -> std::io::Result<LineRead>
+    where
+        R: AsyncBufRead + Unpin,
+    {
+        loop {
+            let available = reader.fill_buf().await?;
+            if available.is_empty() {
+                self.discarding = false;
+                if self.buf.is_empty() {
+                    return Ok(LineRead::Eof);
+                }
+                return Ok(LineRead::Line(std::mem::take(&mut self.buf)));
+            }
+
+            let newline = available.iter().position(|&b| b == b'\n');
+            let chunk_len = newline.unwrap_or(available.len());
+
+            if self.discarding {
+                let consumed = newline.map_or(chunk_len, |n| n + 1);
+                reader.consume(consumed);
+                if newline.is_some() {
+                    self.discarding = false;
+                }
+                continue;
+            }
+
+            if self.buf.len() + chunk_len > self.max_size {
+                let keep = ID_RECOVERY_PREFIX
+                    .min(self.max_size)
+                    .saturating_sub(self.buf.len())
+                    .min(chunk_len);
+                let mut prefix = std::mem::take(&mut self.buf);
+                prefix.extend_from_slice(&available[..keep]);
+                prefix.truncate(ID_RECOVERY_PREFIX);
+                match newline {
+                    Some(n) => reader.consume(n + 1),
+                    None => {
+                        reader.consume(chunk_len);
+                        self.discarding = true;
+                    }
+                }
+                return Ok(LineRead::TooLarge { prefix });
+            }
+
+            self.buf.extend_from_slice(&available[..chunk_len]);
+            match newline {
+                Some(n) => {
+                    reader.consume(n + 1);
+                    return Ok(LineRead::Line(std::mem::take(&mut self.buf)));
+                }
+                None => reader.consume(chunk_len),
+            }
+        }
+    }
+}
+
+/// Best-effort extraction of the `id` from the leading bytes of a request.
+///
+/// Returns `Value::Null` if no numeric or string `id` can be found.
+pub fn recover_request_id(prefix: &[u8]) -> serde_json::Value {
+    const KEY: &[u8] = b"\"id\"";
+    let mut start = 0;
+    while let Some(pos) = prefix[start..].windows(KEY.len()).position(|w| w == KEY) {
+        let after_key = start + pos + KEY.len();
+        let rest = &prefix[after_key..];
+        let rest = &rest[rest.iter().take_while(|b| b.is_ascii_whitespace()).count()..];
+        if let Some(value_bytes) = rest.strip_prefix(b":") {
+            let mut values =
+                serde_json::Deserializer::from_slice(value_bytes).into_iter::<serde_json::Value>();
+            if let Some(Ok(id)) = values.next() {
+                if id.is_number() || id.is_string() {
+                    return id;
+                }
+            }
+        }
+        start = after_key;
+    }
+    serde_json::Value::Null
+}
+
 /// Run the NDJSON transport loop over arbitrary async reader/writer.
 ///
 /// Reads JSON-RPC messages from `reader` (one per line) and writes
 /// responses to `writer`. Backend notifications are interleaved via
 /// `tokio::select!`. The loop exits when the reader reaches EOF,
 /// the cancellation token is triggered, or an I/O error occurs.
+///
+/// Requests larger than `config.max_request_size` are rejected with a
+/// `REQUEST_TOO_LARGE` error without being buffered in full; if
+/// `config.close_on_oversized` is set the loop exits afterwards.
 pub async fn run_transport_loop<R, W>(
     reader: &mut R,
     writer: &mut W,
     dispatcher: &mut Dispatcher,
     notification_rx: &mut tokio::sync::mpsc::UnboundedReceiver<JsonRpcNotification>,
     shutdown: CancellationToken,
+    config: &TransportConfig,
 ) -> anyhow::Result<()>
 where
     R: AsyncBufReadExt + Unpin,
     W: AsyncWriteExt + Unpin,
 {
-    let mut line = String::new();
+    let mut line_reader = LineReader::new(config.max_request_size);
 
     loop {
-        line.clear();
-
         tokio::select! {
             _ = shutdown.cancelled() => {
                 debug!("Shutdown signal received, exiting transport loop");
                 break;
             }
 
-            result = reader.read_line(&mut line) => {
-                let bytes_read = result?;
-                if bytes_read == 0 {
-                    debug!("Reader closed (EOF), exiting transport loop");
-                    break;
-                }
+            result = line_reader.read_line(reader) => {
+                let bytes = match result? {
+                    LineRead::Eof => {
+                        debug!("Reader closed (EOF), exiting transport loop");
+                        break;
+                    }
+                    LineRead::TooLarge { prefix } => {
+                        warn!(
+                            "Request exceeds {} byte size limit, dropping it",
+                            config.max_request_size
+                        );
+                        let err = JsonRpcErrorResponse::new(
+                            recover_request_id(&prefix),
+                            errors::REQUEST_TOO_LARGE,
+                            format!(
+                                "Request exceeds {} byte size limit",
+                                config.max_request_size
+                            ),
+                        );
+                        write_json(writer, &serde_json::to_value(&err)?).await?;
+                        if config.close_on_oversized {
+                            debug!("Closing connection after oversized request");
+                            break;
+                        }
+                        continue;
+                    }
+                    LineRead::Line(bytes) => bytes,
+                };
+
+                let line = match std::str::from_utf8(&bytes) {
+                    Ok(line) => line,
+                    Err(e) => {
+                        warn!("Received request that is not valid UTF-8: {e}");
+                        let err = JsonRpcErrorResponse::new(
+                            serde_json::Value::Null,
+                            errors::PARSE_ERROR,
+                            format!("Parse error: {e}"),
+                        );
+                        write_json(writer, &serde_json::to_value(&err)?).await?;
+                        continue;
+                    }
+                };
 
                 let trimmed = line.trim();
                 if trimmed.is_empty() {
                     continue;
                 }
 
-                if trimmed.len() > MAX_LINE_SIZE {
-                    warn!("Message exceeds 1 MiB limit ({} bytes)", trimmed.len());
-                    let err = JsonRpcErrorResponse::new(
-                        serde_json::Value::Null,
-                        errors::PARSE_ERROR,
-                        "Message exceeds 1 MiB size limit",
-                    );
-                    write_json(writer, &serde_json::to_value(&err)?).await?;
+                debug!("Received: {}", trimmed);
+
+                if trimmed.starts_with('[') {
+                    let batch = match serde_json::from_str::<Vec<serde_json::Value>>(trimmed) {
+                        Ok(items) => dispatcher.dispatch_batch(items).await,
+                        Err(e) => {
+                            warn!("Failed to parse JSON-RPC batch: {e}");
+                            let err = JsonRpcErrorResponse::new(
+                                serde_json::Value::Null,
+                                errors::PARSE_ERROR,
+                                format!("Parse error: {e}"),
+                            );
+                            write_json(writer, &serde_json::to_value(&err)?).await?;
+                            continue;
+                        }
+                    };
+                    if let Some(response) = batch.response {
+                        debug!("Sending: {}", response);
+                        write_json(writer, &response).await?;
+                    }
+                    if batch.shutdown {
+                        debug!("agent.shutdown handled in batch, exiting transport loop");
+                        break;
+                    }
                     continue;
                 }
 
-                debug!("Received: {}", trimmed);
-
                 let request: JsonRpcRequest = match serde_json::from_str(trimmed) {
                     Ok(r) => r,
                     Err(e) => {
@@ -127,6 +343,168 @@ pub async fn write_json<W: AsyncWriteExt + Unpin>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Arc;
+
+    use crate::monitoring::{MonitoringManager, MonitoringManagerApi};
+    use crate::session::definitions::{ConnectionStore, ConnectionStoreApi};
+    use crate::session::manager::SessionManager;
+
+    fn make_dispatcher() -> (
+        Dispatcher,
+        tokio::sync::mpsc::UnboundedReceiver<JsonRpcNotification>,
+    ) {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        let tmp = std::env::temp_dir().join(format!("termihub-test-{}.json", uuid::Uuid::new_v4()));
+        let conn_store = Arc::new(ConnectionStore::new_temp(tmp));
+        let registry = Arc::new(crate::registry::build_registry());
+        let session_manager = Arc::new(SessionManager::new(tx.clone(), registry));
+        let monitoring_manager = Arc::new(MonitoringManager::new(tx, conn_store.clone()));
+        let dispatcher = Dispatcher::new(
+            session_manager,
+            conn_store as Arc<dyn ConnectionStoreApi>,
+            monitoring_manager as Arc<dyn MonitoringManagerApi>,
+        );
+        (dispatcher, rx)
+    }
+
+    /// Run the transport loop over `input` and return the parsed responses.
+    async fn run_with_input(input: &[u8], config: &TransportConfig) -> Vec<serde_json::Value> {
+        let (mut dispatcher, mut rx) = make_dispatcher();
+        let mut reader = tokio::io::BufReader::with_capacity(64, input);
+        let mut output: Vec<u8> = Vec::new();
+        run_transport_loop(
+            &mut reader,
+            &mut output,
+            &mut dispatcher,
+            &mut rx,
+            CancellationToken::new(),
+            config,
+        )
+        .await
+        .unwrap();
+        String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect()
+    }
+
+    fn oversized_request(id: u64, size: usize) -> String {
+        format!(
+            r#"{{"jsonrpc":"2.0","id":{id},"method":"health.check","params":{{"pad":"{}"}}}}"#,
+            "x".repeat(size)
+        )
+    }
+
+    #[tokio::test]
+    async fn line_reader_splits_lines() {
+        let mut reader = tokio::io::BufReader::with_capacity(4, &b"abc\ndefgh\n"[..]);
+        let mut lines = LineReader::new(100);
+        assert_eq!(
+            lines.read_line(&mut reader).await.unwrap(),
+            LineRead::Line(b"abc".to_vec())
+        );
+        assert_eq!(
+            lines.read_line(&mut reader).await.unwrap(),
+            LineRead::Line(b"defgh".to_vec())
+        );
+        assert_eq!(lines.read_line(&mut reader).await.unwrap(), LineRead::Eof);
+    }
+
+    #[tokio::test]
+    async fn line_reader_rejects_oversized_and_resyncs() {
+        let input = format!("{}\nok\n", "y".repeat(1000));
+        let mut reader = tokio::io::BufReader::with_capacity(16, input.as_bytes());
+        let mut lines = LineReader::new(100);
+        match lines.read_line(&mut reader).await.unwrap() {
+            LineRead::TooLarge { prefix } => assert!(prefix.len() <= 100),
+            other => panic!("expected TooLarge, got {other:?}"),
+        }
+        assert_eq!(
+            lines.read_line(&mut reader).await.unwrap(),
+            LineRead::Line(b"ok".to_vec())
+        );
+    }
+
+    #[test]
+    fn recover_request_id_finds_numeric_and_string_ids() {
+        assert_eq!(
+            recover_request_id(br#"{"jsonrpc":"2.0","id": 42,"method":"x","#),
+            serde_json::json!(42)
+        );
+        assert_eq!(
+            recover_request_id(br#"{"id":"req-7","params":{"data":"AAAA"#),
+            serde_json::json!("req-7")
+        );
+        assert_eq!(
+            recover_request_id(br#"{"params":{"sessionId":"s1","data":"AAAA"#),
+            serde_json::Value::Null
+        );
+    }
+
+    #[tokio::test]
+    async fn oversized_request_returns_error_and_continues() {
+        let config = TransportConfig {
+            max_request_size: 256,
+            close_on_oversized: false,
+        };
+        let input = format!(
+            "{}\n{}\n",
+            oversized_request(7, 1024),
+            r#"{"jsonrpc":"2.0","id":8,"method":"health.check","params":{}}"#
+        );
+        let responses = run_with_input(input.as_bytes(), &config).await;
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], 7);
+        assert_eq!(responses[0]["error"]["code"], errors::REQUEST_TOO_LARGE);
+        // The following request is still processed normally.
+        assert_eq!(responses[1]["id"], 8);
+        assert_eq!(responses[1]["error"]["code"], errors::NOT_INITIALIZED);
+    }
+
+    #[tokio::test]
+    async fn oversized_request_closes_connection_when_configured() {
+        let config = TransportConfig {
+            max_request_size: 256,
+            close_on_oversized: true,
+        };
+        let input = format!(
+            "{}\n{}\n",
+            oversized_request(3, 1024),
+            r#"{"jsonrpc":"2.0","id":4,"method":"health.check","params":{}}"#
+        );
+        let responses = run_with_input(input.as_bytes(), &config).await;
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["id"], 3);
+        assert_eq!(responses[0]["error"]["code"], errors::REQUEST_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn batch_request_returns_response_array() {
+        let input = concat!(
+            r#"[{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"0.1.0","client":"test","clientVersion":"0.1.0"}},"#,
+            r#"{"jsonrpc":"2.0","id":2,"method":"no.such.method","params":{}}]"#,
+            "\n",
+            "[1,2\n",
+        );
+        let responses = run_with_input(input.as_bytes(), &TransportConfig::default()).await;
+        assert_eq!(responses.len(), 2);
+        let batch = responses[0].as_array().unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0]["id"], 1);
+        assert!(batch[0]["result"].is_object());
+        assert_eq!(batch[1]["id"], 2);
+        assert_eq!(batch[1]["error"]["code"], errors::METHOD_NOT_FOUND);
+        // A malformed batch is a parse error, as for single requests.
+        assert_eq!(responses[1]["error"]["code"], errors::PARSE_ERROR);
+    }
+
+    #[test]
+    fn transport_config_default_uses_protocol_limit() {
+        let config = TransportConfig::default();
+        assert_eq!(config.max_request_size, DEFAULT_MAX_REQUEST_SIZE);
+        assert!(!config.close_on_oversized);
+    }
 
     #[tokio::test]
     async fn write_json_appends_newline() {
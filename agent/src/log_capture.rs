@@ -0,0 +1,209 @@
+//! In-memory capture of the agent's tracing output for `logs.tail`.
+//!
+//! [`LogCaptureLayer`] is installed next to the stderr formatter and keeps
+//! the most recent events in a [`LogBuffer`], so a client can read the
+//! agent's logs without a shell on the remote host.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use tracing::field::{Field, Visit};
+use tracing::Level;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+/// Maximum number of log entries retained in the ring buffer.
+const MAX_BUFFER_SIZE: usize = 1000;
+
+/// A single captured log entry.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    /// RFC 3339 timestamp (UTC) of the event.
+    pub timestamp: String,
+    /// Level name in upper case, e.g. `"WARN"`.
+    pub level: String,
+    /// Module path or explicit target of the event.
+    pub target: String,
+    pub message: String,
+    #[serde(skip)]
+    severity: Level,
+}
+
+/// Ring buffer holding recent log entries.
+pub struct LogBuffer {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    fn push(&mut self, entry: LogEntry) {
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Return the most recent `count` entries at `min_level` or more
+    /// severe, oldest first.
+    pub fn tail(&self, count: usize, min_level: Level) -> Vec<LogEntry> {
+        let mut tail: Vec<LogEntry> = self
+            .entries
+            .iter()
+            .rev()
+            .filter(|e| e.severity <= min_level)
+            .take(count)
+            .cloned()
+            .collect();
+        tail.reverse();
+        tail
+    }
+}
+
+/// Thread-safe log buffer shared between the capture layer and dispatchers.
+pub type SharedLogBuffer = Arc<Mutex<LogBuffer>>;
+
+/// Create a new shared log buffer with default capacity.
+pub fn create_log_buffer() -> SharedLogBuffer {
+    Arc::new(Mutex::new(LogBuffer::new(MAX_BUFFER_SIZE)))
+}
+
+/// A `tracing_subscriber::Layer` that captures log events into a ring buffer.
+pub struct LogCaptureLayer {
+    buffer: SharedLogBuffer,
+}
+
+impl LogCaptureLayer {
+    pub fn new(buffer: SharedLogBuffer) -> Self {
+        Self { buffer }
+    }
+}
+
+/// Visitor that extracts the `message` field from a tracing event.
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+}
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else if self.message.is_empty() {
+            // Fall back to first field if no "message" field
+            self.message = format!("{} = {:?}", field.name(), value);
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        }
+    }
+}
+
+impl<S> Layer<S> for LogCaptureLayer
+where
+    S: tracing::Subscriber,
+{
+    fn on_event(&self, event: &tracing::Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let entry = LogEntry {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            level: metadata.level().to_string(),
+            target: metadata.target().to_string(),
+            message: visitor.message,
+            severity: *metadata.level(),
+        };
+        if let Ok(mut buf) = self.buffer.lock() {
+            buf.push(entry);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn entry(level: Level, message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: String::new(),
+            level: level.to_string(),
+            target: "test".to_string(),
+            message: message.to_string(),
+            severity: level,
+        }
+    }
+
+    fn messages(entries: &[LogEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.message.as_str()).collect()
+    }
+
+    #[test]
+    fn buffer_respects_capacity_limit() {
+        let mut buffer = LogBuffer::new(3);
+        for i in 0..5 {
+            buffer.push(entry(Level::INFO, &format!("msg {i}")));
+        }
+        let tail = buffer.tail(10, Level::TRACE);
+        assert_eq!(messages(&tail), vec!["msg 2", "msg 3", "msg 4"]);
+    }
+
+    #[test]
+    fn tail_returns_most_recent_oldest_first() {
+        let mut buffer = LogBuffer::new(10);
+        for i in 0..5 {
+            buffer.push(entry(Level::INFO, &format!("msg {i}")));
+        }
+        assert_eq!(
+            messages(&buffer.tail(2, Level::TRACE)),
+            vec!["msg 3", "msg 4"]
+        );
+        assert!(buffer.tail(0, Level::TRACE).is_empty());
+    }
+
+    #[test]
+    fn tail_filters_by_min_level_before_counting() {
+        let mut buffer = LogBuffer::new(10);
+        buffer.push(entry(Level::ERROR, "error"));
+        buffer.push(entry(Level::WARN, "warn"));
+        buffer.push(entry(Level::INFO, "info"));
+        buffer.push(entry(Level::DEBUG, "debug"));
+        assert_eq!(
+            messages(&buffer.tail(2, Level::WARN)),
+            vec!["error", "warn"]
+        );
+        assert_eq!(messages(&buffer.tail(10, Level::ERROR)), vec!["error"]);
+        assert_eq!(buffer.tail(10, Level::TRACE).len(), 4);
+    }
+
+    #[test]
+    fn layer_captures_events() {
+        let buffer = create_log_buffer();
+        let subscriber = tracing_subscriber::registry().with(LogCaptureLayer::new(buffer.clone()));
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(target: "agent::test", "disk {} full", "/var");
+            tracing::info!(session_id = "s1", "opened");
+        });
+
+        let tail = buffer.lock().unwrap().tail(10, Level::TRACE);
+        assert_eq!(tail.len(), 2);
+        assert_eq!(tail[0].level, "WARN");
+        assert_eq!(tail[0].target, "agent::test");
+        assert_eq!(tail[0].message, "disk /var full");
+        assert!(chrono::DateTime::parse_from_rfc3339(&tail[0].timestamp).is_ok());
+        assert_eq!(tail[1].level, "INFO");
+        assert_eq!(tail[1].message, "opened");
+    }
+}
@@ -3,6 +3,7 @@ mod daemon;
 mod files;
 mod handler;
 mod io;
+mod log_capture;
 mod monitoring;
 mod network;
 mod protocol;
@@ -12,10 +13,19 @@ mod session;
 mod state;
 mod transport;
 
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
 use tokio_util::sync::CancellationToken;
 use tracing::info;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::EnvFilter;
 
+use handler::audit::AuditLog;
+use log_capture::{create_log_buffer, LogCaptureLayer, SharedLogBuffer};
+
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:7685";
 
@@ -30,11 +40,50 @@ fn print_usage() {
     eprintln!("Options:");
     eprintln!("  --version   Print version and exit");
     eprintln!("  --help      Print this help message");
+    eprintln!("  --disable-types <types>");
+    eprintln!("              Comma-separated connection types to refuse (e.g. shell,docker)");
+    eprintln!("  --audit-log <path>");
+    eprintln!("              Append an audit entry for every request to <path>");
+}
+
+/// Remove `--disable-types <types>` from `args` and return the disabled
+/// registry type IDs.
+fn take_disabled_types(args: &mut Vec<String>) -> HashSet<String> {
+    let Some(pos) = args.iter().position(|a| a == "--disable-types") else {
+        return HashSet::new();
+    };
+    if pos + 1 >= args.len() {
+        eprintln!("--disable-types requires a comma-separated list of types");
+        std::process::exit(1);
+    }
+    let list = args.remove(pos + 1);
+    args.remove(pos);
+    handler::dispatch::parse_type_list(&list)
+}
+
+/// Remove `--audit-log <path>` from `args` and open the audit log.
+fn take_audit_log(args: &mut Vec<String>) -> Option<Arc<AuditLog>> {
+    let pos = args.iter().position(|a| a == "--audit-log")?;
+    if pos + 1 >= args.len() {
+        eprintln!("--audit-log requires a file path");
+        std::process::exit(1);
+    }
+    let path = PathBuf::from(args.remove(pos + 1));
+    args.remove(pos);
+    match AuditLog::open(&path) {
+        Ok(log) => Some(Arc::new(log)),
+        Err(e) => {
+            eprintln!("Cannot open audit log {}: {e}", path.display());
+            std::process::exit(1);
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let args: Vec<String> = std::env::args().collect();
+    let mut args: Vec<String> = std::env::args().collect();
+    let disabled_types = take_disabled_types(&mut args);
+    let audit_log = take_audit_log(&mut args);
 
     if args.len() < 2 {
         print_usage();
@@ -52,14 +101,17 @@ async fn main() -> anyhow::Result<()> {
         }
         "--stdio" => {
             // Configure tracing to stderr so it doesn't interfere with the protocol on stdout
-            init_tracing();
+            let log_buffer = init_tracing();
 
             let shutdown = setup_shutdown_signal();
             info!("termihub-agent {} starting in stdio mode", VERSION);
-            io::stdio::run_stdio_loop(shutdown).await
+            if !disabled_types.is_empty() {
+                info!(?disabled_types, "Connection types disabled");
+            }
+            io::stdio::run_stdio_loop(shutdown, disabled_types, audit_log, log_buffer).await
         }
         "--listen" => {
-            init_tracing();
+            let log_buffer = init_tracing();
 
             let addr = args
                 .get(2)
@@ -70,7 +122,10 @@ async fn main() -> anyhow::Result<()> {
                 "termihub-agent {} starting in TCP listener mode on {}",
                 VERSION, addr
             );
-            io::tcp::run_tcp_listener(addr, shutdown).await
+            if !disabled_types.is_empty() {
+                info!(?disabled_types, "Connection types disabled");
+            }
+            io::tcp::run_tcp_listener(addr, shutdown, disabled_types, audit_log, log_buffer).await
         }
         #[cfg(unix)]
         "--daemon" => {
@@ -90,14 +145,16 @@ async fn main() -> anyhow::Result<()> {
     }
 }
 
-/// Initialize the tracing subscriber with stderr output.
-fn init_tracing() {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-        )
-        .with_writer(std::io::stderr)
+/// Initialize the tracing subscriber with stderr output, also capturing
+/// events into the returned buffer for `logs.tail`.
+fn init_tracing() -> SharedLogBuffer {
+    let log_buffer = create_log_buffer();
+    tracing_subscriber::registry()
+        .with(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")))
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .with(LogCaptureLayer::new(log_buffer.clone()))
         .init();
+    log_buffer
 }
 
 /// Set up signal handlers for graceful shutdown.
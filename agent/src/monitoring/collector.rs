@@ -24,7 +24,7 @@ use termihub_core::monitoring::{
     cpu_percent_from_delta, parse_stats, CpuCounters, SystemStats, MONITORING_COMMAND,
 };
 #[cfg(target_os = "linux")]
-use termihub_core::monitoring::{parse_cpu_line, parse_meminfo_value};
+use termihub_core::monitoring::{parse_cpu_line, parse_meminfo};
 
 // ── Local collector ─────────────────────────────────────────────────
 
@@ -134,21 +134,7 @@ fn collect_linux(collector: &mut LocalCollector) -> Result<SystemStats> {
     collector.prev_cpu = Some(cpu_counters);
 
     // Parse memory
-    let mut mem_total_kb: u64 = 0;
-    let mut mem_available_kb: u64 = 0;
-    for line in meminfo.lines() {
-        if line.starts_with("MemTotal:") {
-            mem_total_kb = parse_meminfo_value(line);
-        } else if line.starts_with("MemAvailable:") {
-            mem_available_kb = parse_meminfo_value(line);
-        }
-    }
-    let memory_used_percent = if mem_total_kb > 0 {
-        let used = mem_total_kb.saturating_sub(mem_available_kb);
-        (used as f64 / mem_total_kb as f64) * 100.0
-    } else {
-        0.0
-    };
+    let memory = parse_meminfo(&meminfo);
 
     // Parse uptime
     let uptime_seconds: f64 = uptime
@@ -165,13 +151,20 @@ fn collect_linux(collector: &mut LocalCollector) -> Result<SystemStats> {
         uptime_seconds,
         load_average,
         cpu_usage_percent,
-        memory_total_kb: mem_total_kb,
-        memory_available_kb: mem_available_kb,
-        memory_used_percent,
+        memory_total_kb: memory.total_kb,
+        memory_available_kb: memory.available_kb,
+        memory_used_percent: memory.used_percent(),
+        memory_buffers_kb: memory.buffers_kb,
+        memory_cached_kb: memory.cached_kb,
+        swap_total_kb: memory.swap_total_kb,
+        swap_used_kb: memory.swap_used_kb,
         disk_total_kb,
         disk_used_kb,
         disk_used_percent,
         os_info: collector.os_info(),
+        network_rx_bytes: 0,
+        network_tx_bytes: 0,
+        process_count: 0,
     })
 }
 
@@ -233,10 +226,17 @@ fn collect_macos(collector: &mut LocalCollector) -> Result<SystemStats> {
         memory_total_kb: mem_total_kb,
         memory_available_kb: mem_available_kb,
         memory_used_percent,
+        memory_buffers_kb: 0,
+        memory_cached_kb: 0,
+        swap_total_kb: 0,
+        swap_used_kb: 0,
         disk_total_kb,
         disk_used_kb,
         disk_used_percent,
         os_info: collector.os_info(),
+        network_rx_bytes: 0,
+        network_tx_bytes: 0,
+        process_count: 0,
     })
 }
 
@@ -388,17 +388,8 @@ impl StatsCollector for SshCollector {
         self.prev_cpu = Some(counters);
 
         Ok(SystemStats {
-            hostname: stats.hostname,
-            uptime_seconds: stats.uptime_seconds,
-            load_average: stats.load_average,
             cpu_usage_percent,
-            memory_total_kb: stats.memory_total_kb,
-            memory_available_kb: stats.memory_available_kb,
-            memory_used_percent: stats.memory_used_percent,
-            disk_total_kb: stats.disk_total_kb,
-            disk_used_kb: stats.disk_used_kb,
-            disk_used_percent: stats.disk_used_percent,
-            os_info: stats.os_info,
+            ..stats
         })
     }
 }
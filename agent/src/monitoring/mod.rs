@@ -1,10 +1,12 @@
 //! System monitoring: periodic stats collection and notification streaming.
 //!
-//! Supports monitoring the agent's own host ("self") and remote SSH
-//! jump targets (by connection ID). Stats are collected at a configurable
-//! interval and sent as `connection.monitoring.data` JSON-RPC notifications.
+//! Supports monitoring the agent's own host ("self"), the containers of
+//! Docker sessions (`docker:<session_id>`) and remote SSH jump targets (by
+//! connection ID). Stats are collected at a configurable interval and sent
+//! as `connection.monitoring.data` JSON-RPC notifications.
 
 pub mod collector;
+pub mod process;
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -21,6 +23,8 @@ use crate::protocol::messages::JsonRpcNotification;
 use crate::protocol::methods::{MonitoringData, SshSessionConfig};
 use crate::session::definitions::ConnectionStore;
 
+use termihub_core::backends::docker::DockerStatsCollector;
+
 use self::collector::{LocalCollector, SshCollector, StatsCollector};
 
 /// Default collection interval in milliseconds.
@@ -29,6 +33,32 @@ const DEFAULT_INTERVAL_MS: u64 = 2000;
 /// Minimum allowed collection interval in milliseconds.
 const MIN_INTERVAL_MS: u64 = 500;
 
+/// Host prefix selecting the container of a Docker session.
+pub const DOCKER_HOST_PREFIX: &str = "docker:";
+
+/// What a monitoring host identifier refers to.
+#[derive(Debug, PartialEq, Eq)]
+enum MonitoringTarget<'a> {
+    /// The agent's own host (`"self"`).
+    Local,
+    /// The container of the Docker session with this ID.
+    DockerSession(&'a str),
+    /// A saved SSH connection, by connection ID.
+    Connection(&'a str),
+}
+
+impl<'a> MonitoringTarget<'a> {
+    fn from_host(host: &'a str) -> Self {
+        if host == "self" {
+            Self::Local
+        } else if let Some(session_id) = host.strip_prefix(DOCKER_HOST_PREFIX) {
+            Self::DockerSession(session_id)
+        } else {
+            Self::Connection(host)
+        }
+    }
+}
+
 // ── MonitoringManagerApi trait ─────────────────────────────────────
 
 /// Abstract interface over the monitoring manager.
@@ -79,6 +109,7 @@ impl MonitoringManager {
     /// Start monitoring a host.
     ///
     /// - `host = "self"`: monitor the agent's own host
+    /// - `host = "docker:<session_id>"`: monitor a Docker session's container
     /// - `host = "<connection_id>"`: monitor a remote host via SSH
     ///
     /// If already subscribed to this host, the existing subscription is
@@ -99,32 +130,12 @@ impl MonitoringManager {
         }
 
         // Create the appropriate collector
-        let collector: Box<dyn StatsCollector> = if host == "self" {
-            Box::new(LocalCollector::new())
-        } else {
-            // Look up the connection to get SSH config
-            let connection = self
-                .connection_store
-                .get(host)
-                .await
-                .ok_or_else(|| anyhow::anyhow!("Connection not found: {host}"))?;
-
-            if connection.session_type != "ssh" {
-                bail!(
-                    "Monitoring is only supported for SSH connections (got '{}')",
-                    connection.session_type
-                );
+        let collector: Box<dyn StatsCollector> = match MonitoringTarget::from_host(host) {
+            MonitoringTarget::Local => Box::new(LocalCollector::new()),
+            MonitoringTarget::DockerSession(session_id) => {
+                Box::new(DockerStatsCollector::for_session(session_id).await?)
             }
-
-            let ssh_config: SshSessionConfig = serde_json::from_value(connection.config)
-                .map_err(|e| anyhow::anyhow!("Invalid SSH config for connection '{host}': {e}"))?;
-
-            // Open SSH connection in a blocking task
-            let collector = tokio::task::spawn_blocking(move || SshCollector::new(&ssh_config))
-                .await
-                .map_err(|e| anyhow::anyhow!("Failed to spawn SSH collector task: {e}"))??;
-
-            Box::new(collector)
+            MonitoringTarget::Connection(host) => self.ssh_collector(host).await?,
         };
 
         let cancel = CancellationToken::new();
@@ -156,6 +167,32 @@ impl MonitoringManager {
         Ok(())
     }
 
+    /// Open an SSH collector for a saved SSH connection.
+    async fn ssh_collector(&self, host: &str) -> Result<Box<dyn StatsCollector>> {
+        let connection = self
+            .connection_store
+            .get(host)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Connection not found: {host}"))?;
+
+        if connection.session_type != "ssh" {
+            bail!(
+                "Monitoring is only supported for SSH connections (got '{}')",
+                connection.session_type
+            );
+        }
+
+        let ssh_config: SshSessionConfig = serde_json::from_value(connection.config)
+            .map_err(|e| anyhow::anyhow!("Invalid SSH config for connection '{host}': {e}"))?;
+
+        // Open SSH connection in a blocking task
+        let collector = tokio::task::spawn_blocking(move || SshCollector::new(&ssh_config))
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to spawn SSH collector task: {e}"))??;
+
+        Ok(Box::new(collector))
+    }
+
     /// Stop monitoring a host. Returns `true` if a subscription existed.
     pub async fn unsubscribe(&self, host: &str) -> bool {
         let mut subs = self.subscriptions.lock().await;
@@ -267,6 +304,23 @@ async fn monitoring_task(
 mod tests {
     use super::*;
 
+    #[test]
+    fn docker_host_selects_docker_session_target() {
+        assert_eq!(
+            MonitoringTarget::from_host("docker:sess-1"),
+            MonitoringTarget::DockerSession("sess-1")
+        );
+    }
+
+    #[test]
+    fn self_and_connection_hosts_keep_their_targets() {
+        assert_eq!(MonitoringTarget::from_host("self"), MonitoringTarget::Local);
+        assert_eq!(
+            MonitoringTarget::from_host("jump-host"),
+            MonitoringTarget::Connection("jump-host")
+        );
+    }
+
     #[tokio::test]
     async fn subscribe_self_and_unsubscribe() {
         let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
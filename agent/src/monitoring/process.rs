@@ -0,0 +1,180 @@
+//! Per-process resource usage for sessions backed by a local child process.
+//!
+//! On Linux, CPU time is read from `/proc/<pid>/stat` and resident memory
+//! from `/proc/<pid>/status`. CPU usage is a delta between two samples, so
+//! the first sample for a process reports 0%. Other platforms report no
+//! samples.
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Kernel clock ticks per second used by `/proc/<pid>/stat` (`USER_HZ`).
+///
+/// Fixed at 100 on every mainstream Linux architecture.
+const CLOCK_TICKS_PER_SEC: f64 = 100.0;
+
+/// A single resource-usage reading for one process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProcessSample {
+    /// User + system CPU time in clock ticks.
+    pub cpu_ticks: u64,
+    /// Resident set size in kB.
+    pub rss_kb: u64,
+}
+
+/// Extract `utime + stime` (in clock ticks) from `/proc/<pid>/stat`.
+///
+/// The command name (field 2) is parenthesised and may itself contain
+/// spaces or parentheses, so fields are counted from the last `)`.
+pub fn parse_proc_stat_cpu_ticks(stat: &str) -> Option<u64> {
+    let after_comm = &stat[stat.rfind(')')? + 1..];
+    // after_comm starts at field 3 (state); utime and stime are fields 14 and 15.
+    let mut fields = after_comm.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    Some(utime + stime)
+}
+
+/// Extract `VmRSS` (in kB) from `/proc/<pid>/status`.
+///
+/// Kernel threads have no `VmRSS` line and report `None`.
+pub fn parse_proc_status_rss_kb(status: &str) -> Option<u64> {
+    status
+        .lines()
+        .find(|line| line.starts_with("VmRSS:"))
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|v| v.parse().ok())
+}
+
+/// Read the current CPU time and RSS of `pid`.
+///
+/// Returns `None` if the process is gone or the platform has no `/proc`.
+#[cfg(target_os = "linux")]
+pub fn read_process_sample(pid: u32) -> Option<ProcessSample> {
+    let stat = std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    let status = std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    Some(ProcessSample {
+        cpu_ticks: parse_proc_stat_cpu_ticks(&stat)?,
+        rss_kb: parse_proc_status_rss_kb(&status).unwrap_or(0),
+    })
+}
+
+/// Read the current CPU time and RSS of `pid`.
+///
+/// Returns `None` if the process is gone or the platform has no `/proc`.
+#[cfg(not(target_os = "linux"))]
+pub fn read_process_sample(_pid: u32) -> Option<ProcessSample> {
+    None
+}
+
+/// Remembers the previous CPU sample per PID to compute usage deltas.
+#[derive(Default)]
+pub struct ProcessCpuTracker {
+    previous: HashMap<u32, (u64, Instant)>,
+}
+
+impl ProcessCpuTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `cpu_ticks` for `pid` taken at `now` and return the CPU usage
+    /// since the previous sample, as a percentage of one core.
+    ///
+    /// Returns 0.0 for the first sample of a PID.
+    pub fn update(&mut self, pid: u32, cpu_ticks: u64, now: Instant) -> f64 {
+        let percent = match self.previous.get(&pid) {
+            Some(&(prev_ticks, prev_time)) => {
+                let elapsed = now.saturating_duration_since(prev_time).as_secs_f64();
+                if elapsed > 0.0 {
+                    let cpu_secs =
+                        cpu_ticks.saturating_sub(prev_ticks) as f64 / CLOCK_TICKS_PER_SEC;
+                    cpu_secs / elapsed * 100.0
+                } else {
+                    0.0
+                }
+            }
+            None => 0.0,
+        };
+        self.previous.insert(pid, (cpu_ticks, now));
+        percent
+    }
+
+    /// Forget PIDs that are no longer being sampled.
+    pub fn retain(&mut self, live_pids: &[u32]) {
+        self.previous.retain(|pid, _| live_pids.contains(pid));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const SAMPLE_STAT: &str = "12345 (bash) S 1000 12345 12345 34816 12345 4194560 \
+        2500 10000 0 3 150 40 20 5 20 0 1 0 987654 23654400 1323 18446744073709551615";
+
+    #[test]
+    fn parse_stat_sums_utime_and_stime() {
+        assert_eq!(parse_proc_stat_cpu_ticks(SAMPLE_STAT), Some(190));
+    }
+
+    #[test]
+    fn parse_stat_handles_spaces_and_parens_in_comm() {
+        let stat = "42 (my (weird) proc) R 1 42 42 0 -1 4194304 \
+            100 0 0 0 7 3 0 0 20 0 1 0 500 1000 50 0";
+        assert_eq!(parse_proc_stat_cpu_ticks(stat), Some(10));
+    }
+
+    #[test]
+    fn parse_stat_rejects_truncated_input() {
+        assert_eq!(parse_proc_stat_cpu_ticks("12345 (bash) S 1000"), None);
+        assert_eq!(parse_proc_stat_cpu_ticks(""), None);
+    }
+
+    #[test]
+    fn parse_status_rss() {
+        let status = "Name:\tbash\nState:\tS (sleeping)\nVmPeak:\t   23100 kB\n\
+            VmRSS:\t    5292 kB\nThreads:\t1\n";
+        assert_eq!(parse_proc_status_rss_kb(status), Some(5292));
+    }
+
+    #[test]
+    fn parse_status_without_rss() {
+        assert_eq!(parse_proc_status_rss_kb("Name:\tkthreadd\n"), None);
+    }
+
+    #[test]
+    fn tracker_first_sample_is_zero() {
+        let mut tracker = ProcessCpuTracker::new();
+        assert_eq!(tracker.update(1, 500, Instant::now()), 0.0);
+    }
+
+    #[test]
+    fn tracker_computes_delta_percentage() {
+        let mut tracker = ProcessCpuTracker::new();
+        let t0 = Instant::now();
+        tracker.update(1, 1000, t0);
+        // 50 ticks = 0.5 s of CPU over 2 s wall time → 25%.
+        let pct = tracker.update(1, 1050, t0 + Duration::from_secs(2));
+        assert!((pct - 25.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn tracker_retain_forgets_dead_pids() {
+        let mut tracker = ProcessCpuTracker::new();
+        let t0 = Instant::now();
+        tracker.update(1, 100, t0);
+        tracker.update(2, 100, t0);
+        tracker.retain(&[2]);
+        assert_eq!(tracker.update(1, 200, t0 + Duration::from_secs(1)), 0.0);
+        assert!(tracker.update(2, 200, t0 + Duration::from_secs(1)) > 0.0);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn read_own_process_sample() {
+        let sample = read_process_sample(std::process::id()).expect("own /proc entry");
+        assert!(sample.rss_kb > 0);
+    }
+}
@@ -29,6 +29,11 @@ mod tests {
             FILE_BROWSING_NOT_SUPPORTED,
             MONITORING_ERROR,
             SHUTDOWN_ERROR,
+            REQUEST_TOO_LARGE,
+            DESTINATION_EXISTS,
+            REQUEST_TIMEOUT,
+            WATCH_LIMIT_REACHED,
+            FEATURE_NOT_NEGOTIATED,
         ];
         for code in codes {
             assert!(code < 0, "Error code {code} should be negative");
@@ -71,6 +76,11 @@ mod tests {
             FILE_BROWSING_NOT_SUPPORTED,
             MONITORING_ERROR,
             SHUTDOWN_ERROR,
+            REQUEST_TOO_LARGE,
+            DESTINATION_EXISTS,
+            REQUEST_TIMEOUT,
+            WATCH_LIMIT_REACHED,
+            FEATURE_NOT_NEGOTIATED,
         ];
         for code in app_codes {
             assert!(
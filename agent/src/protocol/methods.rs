@@ -2,13 +2,18 @@
 // are kept for protocol completeness and forward compatibility.
 #![allow(dead_code)]
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use termihub_core::config::{DockerConfig, EnvVar, SerialConfig, SshConfig, VolumeMount};
 pub use termihub_core::connection::ConnectionTypeInfo;
+use termihub_core::connection::LineControl;
 // Used by shell/session modules on unix; re-exported for test access on all platforms.
 #[allow(unused_imports)]
 pub use termihub_core::config::ShellConfig;
-pub use termihub_core::files::FileEntry;
+pub use termihub_core::files::{FileEntry, GrepMatch, GrepOptions, NewlineMode};
+
+use crate::session::definitions::{Connection, Folder};
 
 // Type aliases for renamed types — minimises churn in consumer files.
 pub type SerialSessionConfig = SerialConfig;
@@ -37,6 +42,14 @@ pub struct AgentSettings {
     pub log_level: String,
     #[serde(default)]
     pub verbose_tracing: bool,
+    /// Close detached, idle daemon sessions after this many seconds
+    /// (`0` disables reaping).
+    #[serde(default)]
+    pub daemon_idle_ttl_secs: u64,
+    /// Fail requests that take longer than this many seconds with
+    /// `REQUEST_TIMEOUT` (`0` disables the timeout).
+    #[serde(default)]
+    pub request_timeout_secs: u64,
 }
 
 fn default_true() -> bool {
@@ -59,6 +72,21 @@ pub struct InitializeParams {
     /// Runtime preferences from the desktop; applied on startup.
     #[serde(default)]
     pub agent_settings: AgentSettings,
+    /// Optional protocol features the client wants to use. Clients that
+    /// predate negotiation omit it and get [`LEGACY_FEATURES`].
+    #[serde(default = "legacy_features")]
+    pub features: Vec<String>,
+}
+
+/// Optional protocol features this agent implements.
+pub const SUPPORTED_FEATURES: &[&str] = &["batch", "watch"];
+
+/// Features enabled for clients that do not send `features`, i.e. those
+/// that were available before negotiation was introduced.
+pub const LEGACY_FEATURES: &[&str] = &["batch", "watch"];
+
+fn legacy_features() -> Vec<String> {
+    LEGACY_FEATURES.iter().map(|f| f.to_string()).collect()
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -80,6 +108,8 @@ pub struct InitializeResult {
     pub protocol_version: String,
     pub agent_version: String,
     pub capabilities: Capabilities,
+    /// Features both sides support; only these may be used on this connection.
+    pub features: Vec<String>,
 }
 
 // ── agent.settingsUpdate ─────────────────────────────────────────────
@@ -139,6 +169,28 @@ pub struct SessionListEntry {
     pub attached: bool,
 }
 
+// ── session.stats ───────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionStatsResult {
+    /// Resource usage keyed by session ID.
+    pub sessions: HashMap<String, SessionStatsEntry>,
+}
+
+/// Resource usage of the local child process backing a session.
+///
+/// Network-backed sessions have no `pid` and report zero usage.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SessionStatsEntry {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    /// CPU usage since the previous `connection.stats` call, as a
+    /// percentage of one core (0 on the first call).
+    pub cpu_percent: f64,
+    /// Resident set size in kB.
+    pub rss_kb: u64,
+}
+
 // ── session.close ───────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Deserialize)]
@@ -178,6 +230,40 @@ pub struct SessionResizeParams {
     pub rows: u16,
 }
 
+// ── connection.line_control ─────────────────────────────────────────
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionLineControlParams {
+    pub session_id: String,
+    pub control: LineControl,
+}
+
+// ── connection.recording.start / connection.recording.stop ──────────
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionRecordingStartParams {
+    pub session_id: String,
+    /// Path of the `.cast` file on the agent host.
+    pub path: String,
+    /// Terminal size written to the recording header.
+    pub cols: u16,
+    pub rows: u16,
+    /// Also record the input written to the session.
+    #[serde(default)]
+    pub record_input: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionRecordingStopParams {
+    pub session_id: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionRecordingStopResult {
+    /// Whether a recording was running.
+    pub stopped: bool,
+}
+
 // ── health.check ────────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Serialize)]
@@ -185,6 +271,33 @@ pub struct HealthCheckResult {
     pub status: String,
     pub uptime_secs: u64,
     pub active_sessions: u32,
+    /// Free bytes on the filesystem holding the agent's config/state
+    /// directory; `None` when it cannot be determined.
+    pub state_dir_free_bytes: Option<u64>,
+}
+
+// ── logs.tail ───────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct LogsTailParams {
+    /// Number of most recent entries to return.
+    #[serde(default = "default_log_tail_lines")]
+    pub lines: usize,
+    /// Least severe level to include (`"error"` … `"trace"`); all levels
+    /// when omitted.
+    pub min_level: Option<String>,
+}
+
+fn default_log_tail_lines() -> usize {
+    100
+}
+
+pub use crate::log_capture::LogEntry;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogsTailResult {
+    /// Matching entries, oldest first.
+    pub entries: Vec<LogEntry>,
 }
 
 // ── connections.create ──────────────────────────────────────────────
@@ -258,6 +371,37 @@ pub struct FolderDeleteParams {
     pub id: String,
 }
 
+// ── connections.import ──────────────────────────────────────────────
+
+/// How `connections.import` treats entries whose ID already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportStrategy {
+    /// Keep the existing entry and ignore the imported one.
+    #[default]
+    Skip,
+    /// Replace the existing entry with the imported one.
+    Overwrite,
+}
+
+/// Accepts the result of `connections.export`, plus an optional strategy.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConnectionsImportParams {
+    #[serde(default)]
+    pub connections: Vec<Connection>,
+    #[serde(default)]
+    pub folders: Vec<Folder>,
+    #[serde(default)]
+    pub strategy: ImportStrategy,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionsImportResult {
+    pub connections_imported: usize,
+    pub folders_imported: usize,
+    pub skipped: usize,
+}
+
 // ── Helper: distinguish absent field from explicit null ──────────────
 
 /// Deserializes a field so that absent → `None`, explicit `null` → `Some(Value::Null)`,
@@ -303,6 +447,9 @@ pub struct FilesWriteParams {
     pub path: String,
     /// Base64-encoded content to write.
     pub data: String,
+    /// Line-ending normalization applied before writing.
+    #[serde(default)]
+    pub newline: NewlineMode,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -318,6 +465,9 @@ pub struct FilesRenameParams {
     pub connection_id: Option<String>,
     pub old_path: String,
     pub new_path: String,
+    /// Replace an existing `new_path` instead of failing.
+    #[serde(default)]
+    pub overwrite: bool,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -330,12 +480,65 @@ pub struct FilesStatParams {
 pub struct FilesMkdirParams {
     pub connection_id: Option<String>,
     pub path: String,
+    /// Also create missing parent directories.
+    #[serde(default)]
+    pub recursive: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FilesGrepParams {
+    pub connection_id: Option<String>,
+    /// Directory (or file) to search.
+    pub path: String,
+    /// Literal string to search for.
+    pub pattern: String,
+    #[serde(default)]
+    pub options: GrepOptions,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FilesGrepResult {
+    pub matches: Vec<GrepMatch>,
 }
 
 /// Type alias for backward compatibility — stat results use the same shape
 /// as [`FileEntry`] from the core crate.
 pub type FilesStatResult = FileEntry;
 
+// ── files.watch / files.unwatch ─────────────────────────────────────
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FilesWatchParams {
+    pub connection_id: Option<String>,
+    /// Directory whose entries are watched.
+    pub path: String,
+    /// Poll interval in milliseconds for backends without native change
+    /// notifications (default: 2000).
+    pub interval_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FilesWatchResult {
+    pub watch_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FilesUnwatchParams {
+    pub watch_id: String,
+}
+
+/// Directory changes sent as a `connection.files.changed` notification.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesChangedNotification {
+    pub watch_id: String,
+    /// The watched directory.
+    pub path: String,
+    pub added: Vec<FileEntry>,
+    pub removed: Vec<FileEntry>,
+    pub modified: Vec<FileEntry>,
+}
+
 // ── agent.shutdown ──────────────────────────────────────────────────
 
 #[derive(Debug, Clone, Deserialize)]
@@ -489,6 +692,19 @@ mod tests {
         assert_eq!(params.protocol_version, "0.1.0");
         assert_eq!(params.client, "termihub-desktop");
         assert!(params.external_connection_files.is_empty());
+        assert_eq!(params.features, vec!["batch", "watch"]);
+    }
+
+    #[test]
+    fn initialize_params_with_features() {
+        let json = json!({
+            "protocolVersion": "0.2.0",
+            "client": "termihub-desktop",
+            "clientVersion": "0.1.0",
+            "features": []
+        });
+        let params: InitializeParams = serde_json::from_value(json).unwrap();
+        assert!(params.features.is_empty());
     }
 
     #[test]
@@ -542,9 +758,11 @@ mod tests {
                 docker_available: false,
                 available_docker_images: vec![],
             },
+            features: vec!["batch".to_string()],
         };
         let v = serde_json::to_value(&result).unwrap();
         assert_eq!(v["protocol_version"], "0.2.0");
+        assert_eq!(v["features"], json!(["batch"]));
         assert_eq!(v["capabilities"]["maxSessions"], 20);
         assert_eq!(v["capabilities"]["connectionTypes"][0]["typeId"], "local");
         assert_eq!(v["capabilities"]["availableShells"][0], "/bin/bash");
@@ -647,11 +865,13 @@ mod tests {
             status: "ok".to_string(),
             uptime_secs: 86400,
             active_sessions: 3,
+            state_dir_free_bytes: Some(1_073_741_824),
         };
         let v = serde_json::to_value(&result).unwrap();
         assert_eq!(v["status"], "ok");
         assert_eq!(v["uptime_secs"], 86400);
         assert_eq!(v["active_sessions"], 3);
+        assert_eq!(v["state_dir_free_bytes"], 1_073_741_824u64);
     }
 
     #[test]
@@ -706,6 +926,35 @@ mod tests {
         assert_eq!(params.rows, 40);
     }
 
+    #[test]
+    fn session_line_control_params_serde() {
+        let json = json!({
+            "session_id": "abc-123",
+            "control": {"type": "break", "durationMs": 250},
+        });
+        let params: SessionLineControlParams = serde_json::from_value(json).unwrap();
+        assert_eq!(params.session_id, "abc-123");
+        assert_eq!(params.control, LineControl::Break { duration_ms: 250 });
+
+        let json = json!({"session_id": "abc-123", "control": {"type": "setRts", "level": true}});
+        let params: SessionLineControlParams = serde_json::from_value(json).unwrap();
+        assert_eq!(params.control, LineControl::SetRts { level: true });
+    }
+
+    #[test]
+    fn session_recording_start_params_serde() {
+        let json = json!({
+            "session_id": "abc-123",
+            "path": "/tmp/session.cast",
+            "cols": 80,
+            "rows": 24,
+        });
+        let params: SessionRecordingStartParams = serde_json::from_value(json).unwrap();
+        assert_eq!(params.path, "/tmp/session.cast");
+        assert_eq!((params.cols, params.rows), (80, 24));
+        assert!(!params.record_input);
+    }
+
     #[test]
     fn connection_create_params_serde() {
         let json = json!({
@@ -1005,6 +1254,11 @@ mod tests {
         assert_eq!(params.path, "/tmp/out.txt");
         assert_eq!(params.data, "aGVsbG8=");
         assert!(params.connection_id.is_none());
+        assert_eq!(params.newline, NewlineMode::Preserve);
+
+        let json = json!({"path": "/tmp/out.sh", "data": "", "newline": "lf"});
+        let params: FilesWriteParams = serde_json::from_value(json).unwrap();
+        assert_eq!(params.newline, NewlineMode::Lf);
     }
 
     #[test]
@@ -1022,6 +1276,11 @@ mod tests {
         let params: FilesRenameParams = serde_json::from_value(json).unwrap();
         assert_eq!(params.old_path, "/a.txt");
         assert_eq!(params.new_path, "/b.txt");
+        assert!(!params.overwrite);
+
+        let json = json!({"old_path": "/a.txt", "new_path": "/b.txt", "overwrite": true});
+        let params: FilesRenameParams = serde_json::from_value(json).unwrap();
+        assert!(params.overwrite);
     }
 
     #[test]
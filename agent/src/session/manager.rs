@@ -6,16 +6,21 @@
 
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
 use tracing::{info, warn};
 
 use crate::io::transport::NotificationSender;
+use crate::protocol::messages::JsonRpcNotification;
+use crate::session::recording::SessionRecordings;
 use crate::session::types::{SessionBackend, SessionInfo, SessionSnapshot, SessionStatus};
 use crate::transport::JsonRpcOutputSink;
-use termihub_core::connection::{ConnectionTypeRegistry, OutputReceiver};
+use termihub_core::connection::{ConnectionTypeRegistry, LineControl, OutputReceiver};
 use termihub_core::session::traits::OutputSink;
 
 #[cfg(unix)]
@@ -28,6 +33,9 @@ use crate::state::persistence::{AgentState, PersistedSession};
 /// Maximum number of concurrent sessions the agent supports.
 pub const MAX_SESSIONS: u32 = 20;
 
+/// How often the idle reaper checks for expired daemon sessions.
+pub const IDLE_REAP_INTERVAL: Duration = Duration::from_secs(30);
+
 // ── SessionManagerApi trait ────────────────────────────────────────
 
 /// Abstract interface over the session manager.
@@ -67,6 +75,10 @@ pub trait SessionManagerApi: Send + Sync + 'static {
     #[allow(dead_code)]
     async fn detach_all(&self);
 
+    /// Set the idle TTL after which detached daemon sessions are closed
+    /// (`0` disables reaping).
+    fn set_daemon_idle_ttl(&self, ttl_secs: u64);
+
     /// Return the number of sessions with status `Running`.
     async fn active_count(&self) -> u32;
 
@@ -81,6 +93,22 @@ pub trait SessionManagerApi: Send + Sync + 'static {
 
     /// Resize a session's terminal.
     async fn resize(&self, session_id: &str, cols: u16, rows: u16) -> Result<(), String>;
+
+    /// Send a serial line control request (break, DTR, RTS) to a session.
+    async fn line_control(&self, session_id: &str, control: LineControl) -> Result<(), String>;
+
+    /// Start recording a session to an asciicast file on the agent host.
+    async fn start_recording(
+        &self,
+        session_id: &str,
+        path: &str,
+        cols: u16,
+        rows: u16,
+        record_input: bool,
+    ) -> Result<(), String>;
+
+    /// Stop recording a session; returns `false` if none was running.
+    async fn stop_recording(&self, session_id: &str) -> bool;
 }
 
 /// Errors that can occur during session creation.
@@ -122,6 +150,7 @@ pub trait DaemonLauncher: Send + Sync + 'static {
         type_id: &str,
         settings: &serde_json::Value,
         notification_tx: NotificationSender,
+        recordings: SessionRecordings,
     ) -> Result<SessionBackend, anyhow::Error>;
 }
 
@@ -138,6 +167,7 @@ impl DaemonLauncher for SystemDaemonLauncher {
         type_id: &str,
         settings: &serde_json::Value,
         notification_tx: NotificationSender,
+        recordings: SessionRecordings,
     ) -> Result<SessionBackend, anyhow::Error> {
         let socket_path = socket_dir().join(format!("session-{session_id}.sock"));
         let settings_json = serde_json::to_string(settings)?;
@@ -156,8 +186,13 @@ impl DaemonLauncher for SystemDaemonLauncher {
             .map_err(|e| anyhow::anyhow!("Failed to spawn daemon: {e}"))?;
 
         DaemonClient::wait_for_socket(&socket_path).await?;
-        let client =
-            DaemonClient::connect(session_id.to_string(), socket_path, notification_tx).await?;
+        let client = DaemonClient::connect(
+            session_id.to_string(),
+            socket_path,
+            notification_tx,
+            recordings,
+        )
+        .await?;
 
         info!("Daemon spawned for session {session_id} (type={type_id})");
         Ok(SessionBackend::Daemon(client))
@@ -171,7 +206,11 @@ impl DaemonLauncher for SystemDaemonLauncher {
 pub struct SessionManager {
     sessions: Mutex<HashMap<String, SessionInfo>>,
     notification_tx: NotificationSender,
+    /// Active asciicast recordings, shared with the output paths.
+    recordings: SessionRecordings,
     registry: Arc<ConnectionTypeRegistry>,
+    /// Idle TTL for detached daemon sessions in seconds (`0` = never reap).
+    daemon_idle_ttl_secs: AtomicU64,
     #[cfg(unix)]
     launcher: Arc<dyn DaemonLauncher>,
     #[cfg(unix)]
@@ -183,7 +222,9 @@ impl SessionManager {
         Self {
             sessions: Mutex::new(HashMap::new()),
             notification_tx,
+            recordings: SessionRecordings::new(),
             registry,
+            daemon_idle_ttl_secs: AtomicU64::new(0),
             #[cfg(unix)]
             launcher: Arc::new(SystemDaemonLauncher),
             #[cfg(unix)]
@@ -201,7 +242,9 @@ impl SessionManager {
         Self {
             sessions: Mutex::new(HashMap::new()),
             notification_tx,
+            recordings: SessionRecordings::new(),
             registry,
+            daemon_idle_ttl_secs: AtomicU64::new(0),
             launcher,
             state: Mutex::new(AgentState::load()),
         }
@@ -227,6 +270,14 @@ impl SessionManager {
         let id = uuid::Uuid::new_v4().to_string();
         let now = Utc::now();
 
+        // Lets backends tag what they create (e.g. Docker container labels,
+        // which monitoring uses to find a session's container).
+        let mut settings = settings;
+        if let Some(obj) = settings.as_object_mut() {
+            obj.entry("sessionId")
+                .or_insert_with(|| serde_json::json!(id));
+        }
+
         // Check the type exists and get capabilities.
         let capabilities = {
             let instance = self
@@ -264,10 +315,12 @@ impl SessionManager {
             title,
             type_id: type_id.to_string(),
             status: SessionStatus::Running,
+            keep_alive: is_keep_alive(&settings),
             settings,
             created_at: now,
             last_activity: now,
             attached: false,
+            persistent: cfg!(unix) && capabilities.persistent,
             backend,
         };
 
@@ -307,7 +360,13 @@ impl SessionManager {
         settings: &serde_json::Value,
     ) -> Result<SessionBackend, anyhow::Error> {
         self.launcher
-            .launch(session_id, type_id, settings, self.notification_tx.clone())
+            .launch(
+                session_id,
+                type_id,
+                settings,
+                self.notification_tx.clone(),
+                self.recordings.clone(),
+            )
             .await
     }
 
@@ -333,6 +392,7 @@ impl SessionManager {
             output_rx,
             session_id.to_string(),
             self.notification_tx.clone(),
+            self.recordings.clone(),
         );
 
         info!("In-process connection for session {session_id} (type={type_id})");
@@ -362,6 +422,7 @@ impl SessionManager {
         let mut sessions = self.sessions.lock().await;
         if let Some(mut info) = sessions.remove(session_id) {
             close_backend(&mut info.backend).await;
+            self.recordings.stop(session_id);
 
             #[cfg(unix)]
             {
@@ -445,7 +506,9 @@ impl SessionManager {
 
         write_backend(&info.backend, data)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(|e| e.to_string())?;
+        self.recordings.record_input(session_id, data);
+        Ok(())
     }
 
     /// Resize a session's terminal.
@@ -458,6 +521,56 @@ impl SessionManager {
         info.last_activity = Utc::now();
 
         resize_backend(&info.backend, cols, rows)
+            .await
+            .map_err(|e| e.to_string())?;
+        self.recordings.record_resize(session_id, cols, rows);
+        Ok(())
+    }
+
+    /// Start recording a session to an asciicast v2 file at `path` on the
+    /// agent host, replacing any recording already running for it. The
+    /// client supplies the terminal size for the header, since the agent
+    /// only learns it from later resizes.
+    pub async fn start_recording(
+        &self,
+        session_id: &str,
+        path: &str,
+        cols: u16,
+        rows: u16,
+        record_input: bool,
+    ) -> Result<(), String> {
+        if !self.sessions.lock().await.contains_key(session_id) {
+            return Err("Session not found".to_string());
+        }
+        self.recordings
+            .start(
+                session_id,
+                std::path::Path::new(path),
+                cols,
+                rows,
+                record_input,
+            )
+            .map_err(|e| format!("Failed to start recording: {e}"))
+    }
+
+    /// Stop recording a session. Returns `false` if none was running.
+    pub fn stop_recording(&self, session_id: &str) -> bool {
+        self.recordings.stop(session_id)
+    }
+
+    /// Send a serial line control request to a session's connection.
+    ///
+    /// For daemon-hosted sessions the request is forwarded to the daemon;
+    /// a failure there arrives later as a `connection.error` notification.
+    pub async fn line_control(&self, session_id: &str, control: LineControl) -> Result<(), String> {
+        let mut sessions = self.sessions.lock().await;
+        let info = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| "Session not found".to_string())?;
+
+        info.last_activity = Utc::now();
+
+        line_control_backend(&info.backend, control)
             .await
             .map_err(|e| e.to_string())
     }
@@ -490,7 +603,13 @@ impl SessionManager {
                 continue;
             }
 
-            match DaemonClient::connect(id.clone(), socket_path, self.notification_tx.clone()).await
+            match DaemonClient::connect(
+                id.clone(),
+                socket_path,
+                self.notification_tx.clone(),
+                self.recordings.clone(),
+            )
+            .await
             {
                 Ok(client) => {
                     let created_at = chrono::DateTime::parse_from_rfc3339(&session.created_at)
@@ -506,6 +625,8 @@ impl SessionManager {
                         created_at,
                         last_activity: Utc::now(),
                         attached: false,
+                        persistent: true,
+                        keep_alive: is_keep_alive(&session.settings),
                         backend: SessionBackend::Daemon(client),
                     };
 
@@ -537,6 +658,82 @@ impl SessionManager {
             .filter(|s| s.status == SessionStatus::Running)
             .count() as u32
     }
+
+    /// Set the idle TTL after which detached daemon sessions are closed
+    /// (`0` disables reaping).
+    pub fn set_daemon_idle_ttl(&self, ttl_secs: u64) {
+        self.daemon_idle_ttl_secs.store(ttl_secs, Ordering::Relaxed);
+    }
+
+    /// Close daemon sessions that have had no attached client and no
+    /// activity for longer than the idle TTL, as of `now`. Output counts
+    /// as activity, so a detached session that keeps printing stays open.
+    ///
+    /// Sessions marked `keepAlive` are never reaped. Emits a
+    /// `connection.reaped` notification for each closed session and
+    /// returns their IDs.
+    pub async fn reap_idle_daemons(&self, now: DateTime<Utc>) -> Vec<String> {
+        let ttl_secs = self.daemon_idle_ttl_secs.load(Ordering::Relaxed);
+        if ttl_secs == 0 {
+            return Vec::new();
+        }
+        let ttl = chrono::Duration::seconds(ttl_secs.min(i64::MAX as u64) as i64);
+
+        let expired: Vec<(String, i64)> = {
+            let sessions = self.sessions.lock().await;
+            sessions
+                .values()
+                .filter(|s| s.persistent && !s.attached && !s.keep_alive)
+                .map(|s| (s.id.clone(), now - s.last_active()))
+                .filter(|(_, idle)| *idle >= ttl)
+                .map(|(id, idle)| (id, idle.num_seconds()))
+                .collect()
+        };
+
+        let mut reaped = Vec::new();
+        for (id, idle_secs) in expired {
+            if !self.close(&id).await {
+                continue;
+            }
+            info!("Reaped session {id} after {idle_secs}s idle");
+            let _ = self.notification_tx.send(JsonRpcNotification::new(
+                "connection.reaped",
+                serde_json::json!({
+                    "session_id": id,
+                    "idle_secs": idle_secs,
+                }),
+            ));
+            reaped.push(id);
+        }
+        reaped
+    }
+}
+
+/// Spawn a background task that periodically reaps idle daemon sessions
+/// until `shutdown` is cancelled.
+pub fn spawn_idle_reaper(
+    manager: Arc<SessionManager>,
+    shutdown: CancellationToken,
+) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(IDLE_REAP_INTERVAL);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => return,
+                _ = interval.tick() => {
+                    manager.reap_idle_daemons(Utc::now()).await;
+                }
+            }
+        }
+    })
+}
+
+/// Whether the session settings opt out of idle reaping.
+fn is_keep_alive(settings: &serde_json::Value) -> bool {
+    settings
+        .get("keepAlive")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
 }
 
 // ── Backend operations ─────────────────────────────────────────────
@@ -655,20 +852,43 @@ async fn resize_backend(
     Ok(())
 }
 
+async fn line_control_backend(
+    backend: &SessionBackend,
+    control: LineControl,
+) -> Result<(), anyhow::Error> {
+    match backend {
+        #[cfg(unix)]
+        SessionBackend::Daemon(ref client) => {
+            client.line_control(control).await?;
+        }
+        SessionBackend::InProcess { connection, .. } => {
+            connection
+                .line_control(control)
+                .map_err(|e| anyhow::anyhow!("{e}"))?;
+        }
+        #[cfg(test)]
+        SessionBackend::Stub => {}
+    }
+    Ok(())
+}
+
 // ── Output forwarding ──────────────────────────────────────────────
 
 /// Spawn a background task that reads from the ConnectionType's output
-/// channel and sends JSON-RPC notifications via [`JsonRpcOutputSink`].
+/// channel and sends JSON-RPC notifications via [`JsonRpcOutputSink`],
+/// recording the output if the session is being recorded.
 fn spawn_output_forwarder(
     mut output_rx: OutputReceiver,
     session_id: String,
     notification_tx: NotificationSender,
+    recordings: SessionRecordings,
 ) -> tokio::task::JoinHandle<()> {
     let sink = JsonRpcOutputSink::new(notification_tx);
     tokio::spawn(async move {
         loop {
             match output_rx.recv().await {
                 Some(data) => {
+                    recordings.record_output(&session_id, &data);
                     if sink.send_output(&session_id, data).is_err() {
                         return; // transport loop dropped
                     }
@@ -719,6 +939,10 @@ impl SessionManagerApi for SessionManager {
         SessionManager::detach_all(self).await
     }
 
+    fn set_daemon_idle_ttl(&self, ttl_secs: u64) {
+        SessionManager::set_daemon_idle_ttl(self, ttl_secs)
+    }
+
     async fn active_count(&self) -> u32 {
         SessionManager::active_count(self).await
     }
@@ -738,6 +962,25 @@ impl SessionManagerApi for SessionManager {
     async fn resize(&self, session_id: &str, cols: u16, rows: u16) -> Result<(), String> {
         SessionManager::resize(self, session_id, cols, rows).await
     }
+
+    async fn line_control(&self, session_id: &str, control: LineControl) -> Result<(), String> {
+        SessionManager::line_control(self, session_id, control).await
+    }
+
+    async fn start_recording(
+        &self,
+        session_id: &str,
+        path: &str,
+        cols: u16,
+        rows: u16,
+        record_input: bool,
+    ) -> Result<(), String> {
+        SessionManager::start_recording(self, session_id, path, cols, rows, record_input).await
+    }
+
+    async fn stop_recording(&self, session_id: &str) -> bool {
+        SessionManager::stop_recording(self, session_id)
+    }
 }
 
 // ── Tests ──────────────────────────────────────────────────────────
@@ -787,6 +1030,8 @@ mod tests {
                 created_at: now,
                 last_activity: now,
                 attached: false,
+                persistent: false,
+                keep_alive: false,
                 backend: SessionBackend::Stub,
             };
 
@@ -838,6 +1083,36 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn recording_captures_input_and_resize_until_close() {
+        let mgr = SessionManager::new(test_notification_tx(), test_registry());
+        let snapshot = mgr
+            .create_stub_session("local", "rec".to_string(), json!({}))
+            .await
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.cast");
+        let path_str = path.display().to_string();
+
+        assert!(mgr
+            .start_recording("nonexistent", &path_str, 80, 24, true)
+            .await
+            .is_err());
+        mgr.start_recording(&snapshot.id, &path_str, 80, 24, true)
+            .await
+            .unwrap();
+        mgr.write_input(&snapshot.id, b"ls\r").await.unwrap();
+        mgr.resize(&snapshot.id, 120, 40).await.unwrap();
+        assert!(mgr.close(&snapshot.id).await);
+        assert!(!mgr.stop_recording(&snapshot.id));
+
+        let recording = std::fs::read_to_string(&path).unwrap();
+        let events: Vec<&str> = recording.lines().skip(1).collect();
+        assert_eq!(events.len(), 2);
+        assert!(events[0].ends_with(r#","i","ls\r"]"#));
+        assert!(events[1].ends_with(r#","r","120x40"]"#));
+    }
+
     #[tokio::test]
     async fn close_all_on_empty() {
         let mgr = SessionManager::new(test_notification_tx(), test_registry());
@@ -897,6 +1172,7 @@ mod tests {
                 type_id: &str,
                 _settings: &serde_json::Value,
                 _notification_tx: NotificationSender,
+                _recordings: SessionRecordings,
             ) -> Result<SessionBackend, anyhow::Error> {
                 if self.should_fail {
                     return Err(anyhow::anyhow!("mock: daemon spawn failed"));
@@ -1032,5 +1308,69 @@ mod tests {
                 "close_all() must remove sessions from the in-memory list"
             );
         }
+
+        #[tokio::test]
+        async fn reap_idle_daemons_closes_only_detached_expired_sessions() {
+            let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+            let mgr = SessionManager::with_launcher(
+                tx,
+                test_registry(),
+                Arc::new(MockDaemonLauncher::new()),
+            );
+            let ssh = |keep_alive: bool| {
+                serde_json::json!({
+                    "host": "example.com",
+                    "username": "user",
+                    "authMethod": "password",
+                    "keepAlive": keep_alive,
+                })
+            };
+            let detached = mgr.create("ssh", "idle".into(), ssh(false)).await.unwrap();
+            let attached = mgr.create("ssh", "busy".into(), ssh(false)).await.unwrap();
+            let pinned = mgr.create("ssh", "pinned".into(), ssh(true)).await.unwrap();
+            mgr.attach(&attached.id).await.unwrap();
+            mgr.set_daemon_idle_ttl(60);
+
+            let start = Utc::now();
+            // Before the TTL elapses nothing is reaped.
+            let reaped = mgr
+                .reap_idle_daemons(start + chrono::Duration::seconds(30))
+                .await;
+            assert!(reaped.is_empty());
+
+            let reaped = mgr
+                .reap_idle_daemons(start + chrono::Duration::seconds(61))
+                .await;
+            assert_eq!(reaped, vec![detached.id.clone()]);
+
+            let mut remaining: Vec<String> = mgr.list().await.into_iter().map(|s| s.id).collect();
+            remaining.sort();
+            let mut expected = vec![attached.id, pinned.id];
+            expected.sort();
+            assert_eq!(remaining, expected);
+
+            let notification = rx.try_recv().expect("reap notification");
+            assert_eq!(notification.method, "connection.reaped");
+            assert_eq!(notification.params["session_id"], detached.id);
+        }
+
+        #[tokio::test]
+        async fn reap_idle_daemons_disabled_by_default() {
+            let (mgr, _) = make_manager_with_mock(MockDaemonLauncher::new());
+            mgr.create(
+                "ssh",
+                "idle".to_string(),
+                serde_json::json!({
+                    "host": "example.com",
+                    "username": "user",
+                    "authMethod": "password",
+                }),
+            )
+            .await
+            .unwrap();
+            let far_future = Utc::now() + chrono::Duration::days(365);
+            assert!(mgr.reap_idle_daemons(far_future).await.is_empty());
+            assert_eq!(mgr.list().await.len(), 1);
+        }
     }
 }
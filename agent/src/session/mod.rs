@@ -1,3 +1,4 @@
 pub mod definitions;
 pub mod manager;
+pub mod recording;
 pub mod types;
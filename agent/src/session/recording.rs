@@ -0,0 +1,152 @@
+//! Per-session asciicast recordings.
+//!
+//! [`SessionRecordings`] is shared by the session manager, which records
+//! input and resizes, and the output paths (the in-process forwarder and
+//! the daemon client reader), which record what the client is sent.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use termihub_core::output::cast_recorder::CastRecorder;
+use tracing::{info, warn};
+
+type Recorder = CastRecorder<BufWriter<File>>;
+
+/// Active session recordings, keyed by session ID.
+#[derive(Clone, Default)]
+pub struct SessionRecordings {
+    recorders: Arc<Mutex<HashMap<String, Recorder>>>,
+}
+
+impl SessionRecordings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start recording a session to an asciicast file at `path`, replacing
+    /// any recording already running for it.
+    pub fn start(
+        &self,
+        session_id: &str,
+        path: &Path,
+        cols: u16,
+        rows: u16,
+        record_input: bool,
+    ) -> std::io::Result<()> {
+        let file = File::create(path)?;
+        let recorder = CastRecorder::new(BufWriter::new(file), cols, rows, record_input)?;
+        let previous = self.lock().insert(session_id.to_string(), recorder);
+        if let Some(previous) = previous {
+            finish(session_id, previous);
+        }
+        info!(session_id, path = %path.display(), record_input, "Started session recording");
+        Ok(())
+    }
+
+    /// Stop recording a session. Returns `false` if no recording was running.
+    pub fn stop(&self, session_id: &str) -> bool {
+        let recorder = self.lock().remove(session_id);
+        match recorder {
+            Some(recorder) => {
+                finish(session_id, recorder);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Record output sent to the client.
+    pub fn record_output(&self, session_id: &str, data: &[u8]) {
+        self.record(session_id, |recorder| recorder.record_output(data));
+    }
+
+    /// Record input written to the session.
+    pub fn record_input(&self, session_id: &str, data: &[u8]) {
+        self.record(session_id, |recorder| recorder.record_input(data));
+    }
+
+    /// Record a resize of the session's terminal.
+    pub fn record_resize(&self, session_id: &str, cols: u16, rows: u16) {
+        self.record(session_id, |recorder| recorder.record_resize(cols, rows));
+    }
+
+    /// Apply `record` to the session's recording, if one is running,
+    /// stopping the recording when it fails.
+    fn record(&self, session_id: &str, record: impl FnOnce(&mut Recorder) -> std::io::Result<()>) {
+        let mut recorders = self.lock();
+        if let Some(recorder) = recorders.get_mut(session_id) {
+            if let Err(e) = record(recorder) {
+                warn!(session_id, "Session recording failed, stopping it: {e}");
+                recorders.remove(session_id);
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Recorder>> {
+        self.recorders
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+fn finish(session_id: &str, recorder: Recorder) {
+    if let Err(e) = recorder.finish() {
+        warn!(session_id, "Failed to finish session recording: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use termihub_core::output::cast_recorder::{parse_cast, CastEventKind};
+
+    fn read(path: &Path) -> Vec<(CastEventKind, String)> {
+        let file = std::io::BufReader::new(File::open(path).unwrap());
+        let (_, events) = parse_cast(file).unwrap();
+        events.into_iter().map(|e| (e.kind, e.data)).collect()
+    }
+
+    #[test]
+    fn records_only_while_running() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.cast");
+        let recordings = SessionRecordings::new();
+
+        recordings.record_output("s1", b"before");
+        recordings.start("s1", &path, 80, 24, false).unwrap();
+        recordings.record_output("s1", b"$ ");
+        recordings.record_input("s1", b"ls\r");
+        recordings.record_output("s2", b"other session");
+        recordings.record_resize("s1", 100, 30);
+        assert!(recordings.stop("s1"));
+        recordings.record_output("s1", b"after");
+        assert!(!recordings.stop("s1"));
+
+        assert_eq!(
+            read(&path),
+            vec![
+                (CastEventKind::Output, "$ ".to_string()),
+                (CastEventKind::Resize, "100x30".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn input_is_recorded_when_enabled() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.cast");
+        let recordings = SessionRecordings::new();
+
+        recordings.start("s1", &path, 80, 24, true).unwrap();
+        recordings.record_input("s1", b"exit\r");
+        recordings.stop("s1");
+
+        assert_eq!(
+            read(&path),
+            vec![(CastEventKind::Input, "exit\r".to_string())]
+        );
+    }
+}
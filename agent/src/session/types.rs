@@ -62,6 +62,10 @@ pub struct SessionInfo {
     pub created_at: DateTime<Utc>,
     pub last_activity: DateTime<Utc>,
     pub attached: bool,
+    /// Hosted in a daemon that outlives the client; subject to idle reaping.
+    pub persistent: bool,
+    /// Set via the `keepAlive` setting; exempts the session from idle reaping.
+    pub keep_alive: bool,
     pub backend: SessionBackend,
 }
 
@@ -75,9 +79,19 @@ pub struct SessionSnapshot {
     pub created_at: DateTime<Utc>,
     pub last_activity: DateTime<Utc>,
     pub attached: bool,
+    /// PID of the local child process backing the session, if any.
+    pub process_id: Option<u32>,
 }
 
 impl SessionInfo {
+    /// The later of the last client activity and the last output, which
+    /// is what the idle reaper measures from.
+    pub fn last_active(&self) -> DateTime<Utc> {
+        self.backend
+            .last_output()
+            .map_or(self.last_activity, |output| output.max(self.last_activity))
+    }
+
     /// Create a read-only snapshot of this session's state.
     pub fn snapshot(&self) -> SessionSnapshot {
         SessionSnapshot {
@@ -88,6 +102,39 @@ impl SessionInfo {
             created_at: self.created_at,
             last_activity: self.last_activity,
             attached: self.attached,
+            process_id: self.backend.process_id(),
+        }
+    }
+}
+
+impl SessionBackend {
+    /// PID of the local child process backing this session.
+    ///
+    /// Only in-process connections can report one (e.g. the local shell);
+    /// daemon-hosted sessions are network- or device-backed.
+    pub fn process_id(&self) -> Option<u32> {
+        match self {
+            Self::InProcess { connection, .. } => connection.process_id(),
+            #[cfg(unix)]
+            Self::Daemon(_) => None,
+            #[cfg(test)]
+            Self::Stub => None,
+        }
+    }
+
+    /// When the connection last produced output while no client was
+    /// attached. Daemons stamp it on their socket file; in-process
+    /// connections report nothing.
+    pub fn last_output(&self) -> Option<DateTime<Utc>> {
+        match self {
+            #[cfg(unix)]
+            Self::Daemon(client) => std::fs::metadata(client.socket_path())
+                .and_then(|meta| meta.modified())
+                .ok()
+                .map(DateTime::<Utc>::from),
+            Self::InProcess { .. } => None,
+            #[cfg(test)]
+            Self::Stub => None,
         }
     }
 }
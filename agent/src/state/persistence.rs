@@ -4,7 +4,7 @@
 //! can reconnect to surviving daemon processes on startup.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
 use tracing::{debug, warn};
@@ -112,6 +112,28 @@ impl AgentState {
     }
 }
 
+/// Free space in bytes on the filesystem holding the agent's
+/// config/state directory, or `None` if it cannot be determined.
+pub fn state_dir_free_bytes() -> Option<u64> {
+    free_bytes(&config_dir())
+}
+
+/// Free space available to unprivileged users on the filesystem holding
+/// `path`. The directory may not exist before the first save, so the
+/// nearest existing ancestor is queried instead.
+#[cfg(unix)]
+#[allow(clippy::useless_conversion)] // statvfs field types are 32-bit on some platforms
+fn free_bytes(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let stat = nix::sys::statvfs::statvfs(existing).ok()?;
+    Some(u64::from(stat.blocks_available()).saturating_mul(u64::from(stat.fragment_size())))
+}
+
+#[cfg(not(unix))]
+fn free_bytes(_path: &Path) -> Option<u64> {
+    None
+}
+
 /// Get the platform config directory for the agent.
 fn config_dir() -> PathBuf {
     if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME") {
@@ -237,4 +259,24 @@ mod tests {
         let loaded = AgentState::load_from(&path);
         assert!(loaded.sessions.is_empty());
     }
+
+    #[cfg(unix)]
+    #[test]
+    #[allow(clippy::useless_conversion)]
+    fn free_bytes_is_plausible() {
+        let dir = TempDir::new().unwrap();
+        let free = free_bytes(dir.path()).unwrap();
+        let stat = nix::sys::statvfs::statvfs(dir.path()).unwrap();
+        let total = u64::from(stat.blocks()) * u64::from(stat.fragment_size());
+        assert!(free > 0);
+        assert!(free <= total);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn free_bytes_uses_existing_ancestor() {
+        let dir = TempDir::new().unwrap();
+        let missing = dir.path().join("not-yet").join("created");
+        assert!(free_bytes(&missing).is_some_and(|free| free > 0));
+    }
 }
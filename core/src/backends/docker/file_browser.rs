@@ -3,19 +3,32 @@
 //! Uses bollard's exec API to run commands inside a running container
 //! for file listing, reading, writing, deleting, renaming, and stat.
 
+use std::future::Future;
+use std::io::Write;
+use std::path::Path;
+use std::pin::Pin;
+
 use bollard::exec::{CreateExecOptions, StartExecOptions, StartExecResults};
 use futures_util::StreamExt;
 use tokio::io::AsyncWriteExt;
+use tokio_util::sync::CancellationToken;
 
 use crate::errors::FileError;
+use crate::files::archive::{archive_command, archive_command_error, discard_partial};
+use crate::files::compare::{parse_sha256sum, sha256_command};
+use crate::files::dir_size::{parse_du_output, walk_dir_size, DIR_SIZE_MAX_ENTRIES};
+use crate::files::grep::{grep_command, parse_grep_output, GREP_TIMEOUT};
 use crate::files::utils::{chrono_from_epoch, format_permissions};
-use crate::files::{FileBrowser, FileEntry};
+use crate::files::{
+    ArchiveDownloader, ArchiveFormat, FileBrowser, FileEntry, GrepMatch, GrepOptions,
+};
 
 /// File browser for Docker containers via `docker exec`.
 ///
 /// Created during [`Docker::connect()`](super::Docker) and stored for
 /// the lifetime of the connection. Operations run commands inside the
 /// container using bollard's exec API.
+#[derive(Clone)]
 pub(crate) struct DockerFileBrowser {
     client: bollard::Docker,
     container_id: String,
@@ -212,6 +225,21 @@ impl FileBrowser for DockerFileBrowser {
         Ok(data)
     }
 
+    fn sha256<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, FileError>> + Send + 'a>> {
+        let client = self.client.clone();
+        let container_id = self.container_id.clone();
+        let command = sha256_command(path);
+        Box::pin(async move {
+            let output = exec_command(&client, &container_id, vec!["sh", "-c", &command]).await?;
+            parse_sha256sum(&output).ok_or_else(|| {
+                FileError::OperationFailed(format!("Unexpected checksum output: {}", output.trim()))
+            })
+        })
+    }
+
     async fn write_file(&self, path: &str, data: &[u8]) -> Result<(), FileError> {
         let encoded = base64_encode(data);
         let script = format!("base64 -d > '{}'", shell_escape(path));
@@ -235,8 +263,11 @@ impl FileBrowser for DockerFileBrowser {
         Ok(())
     }
 
-    async fn rename(&self, from: &str, to: &str) -> Result<(), FileError> {
-        exec_command(&self.client, &self.container_id, vec!["mv", from, to]).await?;
+    async fn rename(&self, from: &str, to: &str, overwrite: bool) -> Result<(), FileError> {
+        if !overwrite && self.stat(to).await.is_ok() {
+            return Err(FileError::DestinationExists(to.to_string()));
+        }
+        exec_command(&self.client, &self.container_id, vec!["mv", "-f", from, to]).await?;
         Ok(())
     }
 
@@ -250,10 +281,185 @@ impl FileBrowser for DockerFileBrowser {
         parse_stat_output(&output, path)
     }
 
-    async fn mkdir(&self, path: &str) -> Result<(), FileError> {
-        exec_command(&self.client, &self.container_id, vec!["mkdir", "-p", path]).await?;
+    async fn mkdir(&self, path: &str, recursive: bool) -> Result<(), FileError> {
+        let cmd = if recursive {
+            vec!["mkdir", "-p", "--", path]
+        } else {
+            vec!["mkdir", "--", path]
+        };
+        exec_command(&self.client, &self.container_id, cmd).await?;
         Ok(())
     }
+
+    async fn dir_size(&self, path: &str, cancel: &CancellationToken) -> Result<u64, FileError> {
+        // Prefer `du` inside the container; minimal images may lack it.
+        let du = exec_command(
+            &self.client,
+            &self.container_id,
+            vec!["du", "-sb", "--", path],
+        );
+        let output = tokio::select! {
+            output = du => output,
+            _ = cancel.cancelled() => {
+                return Err(FileError::OperationFailed(
+                    "Directory size computation cancelled".to_string(),
+                ));
+            }
+        };
+        if let Ok(size) = output.and_then(|out| parse_du_output(&out)) {
+            return Ok(size);
+        }
+        walk_dir_size(path, cancel, DIR_SIZE_MAX_ENTRIES, |p| async move {
+            self.list_dir(&p).await
+        })
+        .await
+    }
+
+    async fn grep(
+        &self,
+        root: &str,
+        pattern: &str,
+        opts: &GrepOptions,
+    ) -> Result<Vec<GrepMatch>, FileError> {
+        let command = grep_command(root, pattern, opts)?;
+        let exec = exec_command(&self.client, &self.container_id, vec!["sh", "-c", &command]);
+        let output = tokio::time::timeout(GREP_TIMEOUT, exec)
+            .await
+            .map_err(|_| {
+                FileError::OperationFailed(format!(
+                    "grep timed out after {} seconds",
+                    GREP_TIMEOUT.as_secs()
+                ))
+            })??;
+        parse_grep_output(&output, opts.max_results)
+    }
+
+    fn archive_downloader(&self) -> Option<Box<dyn ArchiveDownloader>> {
+        Some(Box::new(DockerArchiveDownloader {
+            client: self.client.clone(),
+            container_id: self.container_id.clone(),
+        }))
+    }
+
+    fn clone_box(&self) -> Box<dyn FileBrowser> {
+        Box::new(self.clone())
+    }
+}
+
+/// [`ArchiveDownloader`] that streams `tar`/`zip` output from the container.
+struct DockerArchiveDownloader {
+    client: bollard::Docker,
+    container_id: String,
+}
+
+#[async_trait::async_trait]
+impl ArchiveDownloader for DockerArchiveDownloader {
+    async fn download(
+        &self,
+        path: &str,
+        format: ArchiveFormat,
+        local_path: &Path,
+        cancel: &CancellationToken,
+        on_progress: &mut (dyn FnMut(u64) + Send),
+    ) -> Result<u64, FileError> {
+        let command = archive_command(path, format)?;
+        let mut file = std::fs::File::create(local_path)?;
+        let streamed = tokio::select! {
+            result = stream_exec_stdout(
+                &self.client,
+                &self.container_id,
+                vec!["sh", "-c", &command],
+                &mut file,
+                on_progress,
+            ) => result,
+            _ = cancel.cancelled() => {
+                Err(FileError::OperationFailed("Transfer cancelled".to_string()))
+            }
+        };
+        drop(file);
+        if streamed.is_err() {
+            discard_partial(local_path);
+        }
+        streamed
+    }
+}
+
+/// Run a command inside the container, writing its stdout to `file` as it
+/// arrives. Returns the number of bytes written.
+async fn stream_exec_stdout(
+    client: &bollard::Docker,
+    container_id: &str,
+    cmd: Vec<&str>,
+    file: &mut std::fs::File,
+    on_progress: &mut (dyn FnMut(u64) + Send),
+) -> Result<u64, FileError> {
+    let exec_config = CreateExecOptions {
+        attach_stdout: Some(true),
+        attach_stderr: Some(true),
+        cmd: Some(cmd),
+        ..Default::default()
+    };
+
+    let exec = client
+        .create_exec(container_id, exec_config)
+        .await
+        .map_err(|e| FileError::OperationFailed(format!("Failed to create exec: {e}")))?;
+
+    let start_config = StartExecOptions {
+        detach: false,
+        ..Default::default()
+    };
+
+    let result = client
+        .start_exec(&exec.id, Some(start_config))
+        .await
+        .map_err(|e| FileError::OperationFailed(format!("Failed to start exec: {e}")))?;
+
+    let StartExecResults::Attached { mut output, .. } = result else {
+        return Err(FileError::OperationFailed(
+            "Exec started in detached mode".to_string(),
+        ));
+    };
+
+    let mut written = 0u64;
+    let mut stderr = Vec::new();
+    on_progress(written);
+    while let Some(chunk) = output.next().await {
+        match chunk {
+            Ok(bollard::container::LogOutput::StdOut { message }) => {
+                file.write_all(&message)?;
+                written += message.len() as u64;
+                on_progress(written);
+            }
+            Ok(bollard::container::LogOutput::StdErr { message }) => {
+                stderr.extend_from_slice(&message);
+            }
+            Ok(_) => {}
+            Err(e) => {
+                return Err(FileError::OperationFailed(format!(
+                    "Exec output error: {e}"
+                )));
+            }
+        }
+    }
+    file.flush()?;
+
+    // Without an exit code the archive may be truncated, so don't report it
+    // as a success.
+    let inspect = client
+        .inspect_exec(&exec.id)
+        .await
+        .map_err(|e| FileError::OperationFailed(format!("Failed to inspect exec: {e}")))?;
+    let exit_code = inspect.exit_code.ok_or_else(|| {
+        FileError::OperationFailed("Archive command did not report an exit code".to_string())
+    })?;
+    if exit_code != 0 {
+        return Err(archive_command_error(
+            exit_code,
+            &String::from_utf8_lossy(&stderr),
+        ));
+    }
+    Ok(written)
 }
 
 // --- Parsing helpers (ported from agent/src/files/docker.rs) ---
@@ -1,35 +1,49 @@
 //! Docker backend implementing [`ConnectionType`](crate::connection::ConnectionType).
 //!
 //! Provides terminal I/O to Docker containers with in-container file
-//! browsing via `docker exec`. Uses the [`bollard`] crate for async
-//! Docker API access instead of shelling out to the Docker CLI.
+//! browsing via `docker exec` and container monitoring via the stats API.
+//! Images without the configured shell are reached by attaching to the
+//! container's main process instead, like `docker attach`. Instead of
+//! creating its own container, a session can also exec a shell in one that
+//! is already running. Uses the [`bollard`] crate for async Docker API
+//! access instead of shelling out to the Docker CLI.
 
 mod file_browser;
+mod monitoring;
 
+use std::future::Future;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use bollard::container::{
-    Config, CreateContainerOptions, RemoveContainerOptions, StopContainerOptions,
+    AttachContainerOptions, AttachContainerResults, Config, CreateContainerOptions,
+    RemoveContainerOptions, ResizeContainerTtyOptions, StopContainerOptions,
 };
 use bollard::exec::{CreateExecOptions, ResizeExecOptions, StartExecOptions, StartExecResults};
 use bollard::image::CreateImageOptions;
-use bollard::models::HostConfig;
+use bollard::models::{ContainerInspectResponse, ContainerStateStatusEnum, HostConfig};
 use futures_util::StreamExt;
 use tokio::io::AsyncWriteExt;
 use tracing::{debug, info, warn};
 
-use crate::config::{ContainerRuntime, DockerConfig};
+use crate::config::{ContainerRuntime, DockerConfig, DockerContainerMode, DockerSessionMode};
 use crate::connection::{
-    Capabilities, ConnectionType, FieldType, OutputReceiver, OutputSender, SelectOption,
-    SettingsField, SettingsGroup, SettingsSchema,
+    cleanup_policy_field, Capabilities, Condition, ConnectionType, FieldType, OutputReceiver,
+    OutputSender, SelectOption, SettingsField, SettingsGroup, SettingsSchema,
 };
 use crate::errors::SessionError;
+use crate::files::utils::shell_quote;
 use crate::files::FileBrowser;
 use crate::monitoring::MonitoringProvider;
-use crate::session::docker::validate_docker_config;
+use crate::session::docker::{
+    container_labels, normalize_capability, persistent_container_name, validate_docker_config,
+};
 
 use self::file_browser::DockerFileBrowser;
+use self::monitoring::DockerMonitoringProvider;
+
+pub use self::monitoring::{stats_from_docker, DockerStatsCollector};
 
 /// Channel capacity for output data from the Docker reader task.
 const OUTPUT_CHANNEL_CAPACITY: usize = 64;
@@ -37,6 +51,28 @@ const OUTPUT_CHANNEL_CAPACITY: usize = 64;
 /// Default container name prefix.
 const CONTAINER_PREFIX: &str = "termihub";
 
+/// Delay before the first retry when the daemon is unreachable; doubles
+/// after every failed attempt up to [`MAX_DAEMON_RETRY_BACKOFF`].
+const DAEMON_RETRY_BACKOFF: Duration = Duration::from_millis(250);
+
+/// Upper bound for the delay between daemon connection attempts.
+const MAX_DAEMON_RETRY_BACKOFF: Duration = Duration::from_secs(2);
+
+/// How long a new exec is watched for failing to start its command.
+/// Docker starts the exec before the runtime looks the command up, so a
+/// missing shell only shows as the exec's exit code.
+const EXEC_START_CHECK: Duration = Duration::from_millis(500);
+
+/// Interval between exec inspections while waiting for it to start or exit.
+const EXEC_POLL: Duration = Duration::from_millis(25);
+
+/// Environment variable tagging the processes of a session's exec'd
+/// shell, so disconnect can find them inside the container.
+const EXEC_TOKEN_VAR: &str = "TERMIHUB_EXEC_TOKEN";
+
+/// How long disconnect waits for an exec'd shell to exit.
+const SHELL_EXIT_WAIT: Duration = Duration::from_secs(2);
+
 /// Docker backend using `bollard`, implementing [`ConnectionType`].
 ///
 /// # Lifecycle
@@ -45,7 +81,8 @@ const CONTAINER_PREFIX: &str = "termihub";
 /// 2. Call [`connect()`](ConnectionType::connect) with settings JSON.
 /// 3. Use [`write()`](ConnectionType::write),
 ///    [`subscribe_output()`](ConnectionType::subscribe_output) for I/O.
-/// 4. Optional: [`file_browser()`](ConnectionType::file_browser).
+/// 4. Optional: [`file_browser()`](ConnectionType::file_browser),
+///    [`monitoring()`](ConnectionType::monitoring).
 /// 5. Call [`disconnect()`](ConnectionType::disconnect) to clean up.
 pub struct Docker {
     /// State is `None` when disconnected, `Some` when connected.
@@ -56,6 +93,8 @@ pub struct Docker {
     output_tx: Arc<Mutex<Option<OutputSender>>>,
     /// File browser provider, created on connect.
     file_browser_provider: Option<DockerFileBrowser>,
+    /// Container stats provider, created on connect.
+    monitoring_provider: Option<DockerMonitoringProvider>,
 }
 
 /// Internal state of an active Docker connection.
@@ -64,16 +103,41 @@ struct ConnectedState {
     client: bollard::Docker,
     /// Docker container ID (full hash).
     container_id: String,
-    /// The exec instance ID for the interactive shell.
-    exec_id: String,
+    /// The process the terminal is connected to.
+    process: SessionProcess,
+    /// Whether the container was created for this session. Containers the
+    /// session attached to are left running on disconnect.
+    owns_container: bool,
+    /// Whether the container is the connection's persistent container,
+    /// which other sessions of the connection may share.
+    persistent: bool,
     /// Whether to remove the container on disconnect.
     remove_on_exit: bool,
+    /// Seconds to wait for the container to stop on disconnect.
+    stop_timeout_secs: u32,
     /// Shared alive flag — set to `false` to signal the reader task to stop.
     alive: Arc<AtomicBool>,
     /// Sender for writing to the exec stdin.
     stdin_tx: tokio::sync::mpsc::Sender<Vec<u8>>,
 }
 
+/// The container process backing the terminal.
+enum SessionProcess {
+    /// A shell started with `docker exec`.
+    Exec(ShellExec),
+    /// The container's main process, attached to directly.
+    Attached,
+}
+
+/// An interactive shell started with `docker exec`.
+struct ShellExec {
+    /// Docker exec ID.
+    id: String,
+    /// Value of [`EXEC_TOKEN_VAR`] in the shell's environment, inherited by
+    /// the programs it starts.
+    token: String,
+}
+
 impl Docker {
     /// Create a new disconnected `Docker` instance.
     pub fn new() -> Self {
@@ -81,6 +145,7 @@ impl Docker {
             state: None,
             output_tx: Arc::new(Mutex::new(None)),
             file_browser_provider: None,
+            monitoring_provider: None,
         }
     }
 }
@@ -167,6 +232,49 @@ fn connect_to_runtime(runtime: &ContainerRuntime) -> Result<bollard::Docker, Ses
     }
 }
 
+/// Connect to the runtime and ping it, so an unreachable daemon is
+/// detected here rather than on the first API call.
+async fn connect_and_ping(runtime: &ContainerRuntime) -> Result<bollard::Docker, SessionError> {
+    let client = connect_to_runtime(runtime)?;
+    client.ping().await.map_err(|e| {
+        SessionError::SpawnFailed(format!("Container daemon is not responding: {e}"))
+    })?;
+    Ok(client)
+}
+
+/// Run `connect` until it succeeds or `timeout` has elapsed.
+///
+/// Waits `backoff` after the first failure, doubling up to
+/// [`MAX_DAEMON_RETRY_BACKOFF`]. A retry is only started if it can begin
+/// before the deadline; the last error is returned when time runs out.
+async fn connect_with_retry<T, F, Fut>(
+    timeout: Duration,
+    mut backoff: Duration,
+    mut connect: F,
+) -> Result<T, SessionError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, SessionError>>,
+{
+    let deadline = Instant::now() + timeout;
+    let mut attempt = 1u32;
+    loop {
+        match connect().await {
+            Ok(client) => return Ok(client),
+            Err(e) if Instant::now() + backoff < deadline => {
+                warn!(
+                    attempt,
+                    "Container daemon unavailable, retrying in {backoff:?}: {e}"
+                );
+            }
+            Err(e) => return Err(e),
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_DAEMON_RETRY_BACKOFF);
+        attempt += 1;
+    }
+}
+
 /// Connect to a container runtime via an explicit URI string.
 fn connect_podman(uri: &str) -> Result<bollard::Docker, SessionError> {
     #[cfg(unix)]
@@ -269,15 +377,45 @@ fn parse_docker_settings(settings: &serde_json::Value) -> DockerConfig {
         })
         .unwrap_or_default();
 
+    // Capabilities come as a list or as a comma/space separated string.
+    let capabilities = |key: &str| -> Vec<String> {
+        match settings.get(key) {
+            Some(serde_json::Value::Array(arr)) => arr
+                .iter()
+                .filter_map(|v| v.as_str())
+                .map(normalize_capability)
+                .filter(|s| !s.is_empty())
+                .collect(),
+            Some(serde_json::Value::String(s)) => s
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .map(normalize_capability)
+                .filter(|s| !s.is_empty())
+                .collect(),
+            _ => Vec::new(),
+        }
+    };
+
     let runtime = settings
         .get("runtime")
         .and_then(|v| v.as_str())
         .and_then(|s| serde_json::from_value::<ContainerRuntime>(serde_json::json!(s)).ok())
         .unwrap_or_default();
+    let session_mode = settings
+        .get("sessionMode")
+        .and_then(|v| v.as_str())
+        .and_then(|s| serde_json::from_value::<DockerSessionMode>(serde_json::json!(s)).ok())
+        .unwrap_or_default();
+    let container_mode = settings
+        .get("mode")
+        .and_then(|v| v.as_str())
+        .and_then(|s| serde_json::from_value::<DockerContainerMode>(serde_json::json!(s)).ok())
+        .unwrap_or_default();
 
     DockerConfig {
         runtime,
+        container_mode,
         image: str_field("image"),
+        container_name: opt_str("containerName").or_else(|| opt_str("containerId")),
         shell: opt_str("shell"),
         cols: 80,
         rows: 24,
@@ -285,8 +423,674 @@ fn parse_docker_settings(settings: &serde_json::Value) -> DockerConfig {
         volumes,
         working_directory: opt_str("workingDirectory"),
         remove_on_exit: bool_field("removeOnExit", true),
+        stop_timeout_secs: settings
+            .get("stopTimeoutSecs")
+            .and_then(|v| v.as_u64())
+            .and_then(|n| u32::try_from(n).ok())
+            .unwrap_or(DockerConfig::default().stop_timeout_secs),
+        daemon_connect_timeout_secs: settings
+            .get("daemonConnectTimeoutSecs")
+            .and_then(|v| v.as_u64())
+            .and_then(|n| u32::try_from(n).ok())
+            .unwrap_or(DockerConfig::default().daemon_connect_timeout_secs),
+        drop_capabilities: capabilities("dropCapabilities"),
+        add_capabilities: capabilities("addCapabilities"),
+        privileged: bool_field("privileged", false),
+        memory_limit_mb: settings
+            .get("memoryLimitMb")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0),
+        cpus: settings.get("cpus").and_then(|v| v.as_f64()).unwrap_or(0.0),
+        cpu_shares: settings
+            .get("cpuShares")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0),
+        session_mode,
         env: std::collections::HashMap::new(),
+        session_id: opt_str("sessionId"),
+        connection_id: opt_str("connectionId"),
+    }
+}
+
+/// Reject a `memoryLimitMb` that is not a whole number of MiB, which
+/// [`parse_docker_settings`] would otherwise read as unlimited.
+fn check_memory_limit_setting(settings: &serde_json::Value) -> Result<(), SessionError> {
+    match settings.get("memoryLimitMb") {
+        Some(v) if v.is_number() && v.as_i64().is_none() => Err(SessionError::InvalidConfig(
+            format!("Memory limit must be a whole number of MiB: {v}"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Build the container's host configuration: volume binds, the init
+/// process, capability restrictions and resource limits.
+fn host_config(config: &DockerConfig) -> HostConfig {
+    let binds: Vec<String> = config
+        .volumes
+        .iter()
+        .map(|v| {
+            let mut bind = format!("{}:{}", v.host_path, v.container_path);
+            if v.read_only {
+                bind.push_str(":ro");
+            }
+            bind
+        })
+        .collect();
+    let non_empty = |v: &Vec<String>| (!v.is_empty()).then(|| v.clone());
+
+    HostConfig {
+        binds: non_empty(&binds),
+        init: Some(true),
+        cap_drop: non_empty(&config.drop_capabilities),
+        cap_add: non_empty(&config.add_capabilities),
+        privileged: config.privileged.then_some(true),
+        memory: (config.memory_limit_mb > 0)
+            .then_some(config.memory_limit_mb)
+            .and_then(|mb| mb.checked_mul(1024 * 1024)),
+        nano_cpus: (config.cpus > 0.0).then(|| (config.cpus * 1e9).round() as i64),
+        cpu_shares: (config.cpu_shares > 0).then_some(config.cpu_shares),
+        ..Default::default()
+    }
+}
+
+/// Options for `stop_container` with the given timeout in seconds.
+fn stop_options(timeout_secs: u32) -> StopContainerOptions {
+    StopContainerOptions {
+        t: i64::from(timeout_secs),
+    }
+}
+
+/// Build the configuration of the session container: environment, host
+/// settings and the termiHub labels. With `keep_alive` the image's command
+/// is replaced by `tail -f /dev/null` so shells can be exec'd into it;
+/// otherwise the image's own command runs as the main process.
+fn container_config(
+    config: &DockerConfig,
+    container_name: &str,
+    created: u64,
+    keep_alive: bool,
+) -> Config<String> {
+    let env: Vec<String> = config
+        .env_vars
+        .iter()
+        .map(|ev| format!("{}={}", ev.key, ev.value))
+        .collect();
+    Config {
+        image: Some(config.image.clone()),
+        tty: Some(true),
+        open_stdin: Some(true),
+        env: if env.is_empty() { None } else { Some(env) },
+        working_dir: config.working_directory.clone(),
+        // Use `tail -f /dev/null` to keep the container alive.
+        cmd: keep_alive.then(|| {
+            vec![
+                "tail".to_string(),
+                "-f".to_string(),
+                "/dev/null".to_string(),
+            ]
+        }),
+        host_config: Some(host_config(config)),
+        labels: Some(container_labels(config, container_name, created)),
+        ..Default::default()
+    }
+}
+
+/// Create the session container, returning its ID.
+async fn create_session_container(
+    client: &bollard::Docker,
+    config: &DockerConfig,
+    container_name: &str,
+    keep_alive: bool,
+) -> Result<String, SessionError> {
+    let container_config = container_config(config, container_name, unix_now_secs(), keep_alive);
+    let create_opts = CreateContainerOptions {
+        name: container_name,
+        platform: None,
+    };
+    let create_response = client
+        .create_container(Some(create_opts), container_config)
+        .await
+        .map_err(|e| match e {
+            bollard::errors::Error::DockerResponseServerError {
+                status_code: 409, ..
+            } => SessionError::AlreadyExists(format!("Container '{container_name}': {e}")),
+            e => SessionError::SpawnFailed(format!("Failed to create container: {e}")),
+        })?;
+
+    debug!(container_id = %create_response.id, "Container created");
+    Ok(create_response.id)
+}
+
+/// Start the container and an interactive `shell` in it via exec,
+/// returning the exec and the shell's I/O streams.
+async fn start_exec_shell(
+    client: &bollard::Docker,
+    container_id: &str,
+    shell: String,
+) -> Result<(ShellExec, AttachContainerResults), SessionError> {
+    client
+        .start_container::<String>(container_id, None)
+        .await
+        .map_err(|e| SessionError::SpawnFailed(format!("Failed to start container: {e}")))?;
+
+    info!(container_id = %container_id, "Container started");
+
+    exec_shell(client, container_id, shell_exec_options(shell)).await
+}
+
+/// Options for an interactive exec of `shell` with a TTY.
+fn shell_exec_options(shell: String) -> CreateExecOptions<String> {
+    CreateExecOptions {
+        attach_stdin: Some(true),
+        attach_stdout: Some(true),
+        attach_stderr: Some(true),
+        tty: Some(true),
+        cmd: Some(vec![shell]),
+        ..Default::default()
+    }
+}
+
+/// Create and start an exec instance in a running container, returning
+/// the exec and the process's I/O streams. The process's environment gets
+/// a fresh [`EXEC_TOKEN_VAR`].
+async fn exec_shell(
+    client: &bollard::Docker,
+    container_id: &str,
+    mut exec_config: CreateExecOptions<String>,
+) -> Result<(ShellExec, AttachContainerResults), SessionError> {
+    let token = format!("{:016x}", rand::random::<u64>());
+    exec_config
+        .env
+        .get_or_insert_with(Vec::new)
+        .push(format!("{EXEC_TOKEN_VAR}={token}"));
+    let exec_response = client
+        .create_exec(container_id, exec_config)
+        .await
+        .map_err(|e| SessionError::SpawnFailed(format!("Failed to create exec: {e}")))?;
+
+    let exec_id = exec_response.id;
+    debug!(exec_id = %exec_id, "Exec instance created");
+
+    // Start the exec instance.
+    let start_config = StartExecOptions {
+        detach: false,
+        ..Default::default()
+    };
+
+    let io = match client
+        .start_exec(&exec_id, Some(start_config))
+        .await
+        .map_err(|e| SessionError::SpawnFailed(format!("Failed to start exec: {e}")))?
+    {
+        StartExecResults::Attached { output, input } => AttachContainerResults { output, input },
+        StartExecResults::Detached => {
+            return Err(SessionError::SpawnFailed(
+                "Exec started in detached mode unexpectedly".to_string(),
+            ))
+        }
+    };
+    wait_for_exec_start(client, &exec_id).await?;
+    Ok((ShellExec { id: exec_id, token }, io))
+}
+
+/// How far an exec's command got, from its inspect details.
+#[derive(Debug, PartialEq, Eq)]
+enum ExecStart {
+    /// The runtime has not started the command yet.
+    Pending,
+    /// The command is running.
+    Running,
+    /// The command could not be found (127) or executed (126).
+    MissingExecutable(i64),
+    /// The command ran and exited on its own.
+    Exited,
+}
+
+/// Classify an exec from its `running` flag and `exit_code`.
+fn exec_start_state(running: Option<bool>, exit_code: Option<i64>) -> ExecStart {
+    match (running, exit_code) {
+        (Some(true), _) => ExecStart::Running,
+        (_, Some(code @ (126 | 127))) => ExecStart::MissingExecutable(code),
+        (_, Some(_)) => ExecStart::Exited,
+        (_, None) => ExecStart::Pending,
+    }
+}
+
+/// Wait until the exec `exec_id` has started its command, failing when
+/// the command does not exist in the container. Gives up waiting, and
+/// succeeds, after [`EXEC_START_CHECK`].
+async fn wait_for_exec_start(client: &bollard::Docker, exec_id: &str) -> Result<(), SessionError> {
+    let deadline = Instant::now() + EXEC_START_CHECK;
+    loop {
+        let exec = client
+            .inspect_exec(exec_id)
+            .await
+            .map_err(|e| SessionError::SpawnFailed(format!("Failed to inspect exec: {e}")))?;
+        match exec_start_state(exec.running, exec.exit_code) {
+            ExecStart::Running | ExecStart::Exited => return Ok(()),
+            ExecStart::MissingExecutable(code) => {
+                return Err(SessionError::SpawnFailed(format!(
+                    "Failed to start exec: executable file not found (exit code {code})"
+                )))
+            }
+            ExecStart::Pending if Instant::now() >= deadline => return Ok(()),
+            ExecStart::Pending => tokio::time::sleep(EXEC_POLL).await,
+        }
+    }
+}
+
+/// The full ID of the container described by `details`, which was looked
+/// up as `container`, provided that it is running.
+fn running_container_id(
+    container: &str,
+    details: ContainerInspectResponse,
+) -> Result<String, SessionError> {
+    let state = details.state.unwrap_or_default();
+    if state.running != Some(true) {
+        let status = state
+            .status
+            .map_or_else(|| "unknown".to_string(), |s| s.to_string());
+        return Err(SessionError::NotRunning(format!(
+            "Container '{container}' is not running (status: {status})"
+        )));
+    }
+    details.id.ok_or_else(|| {
+        SessionError::SpawnFailed(format!("Docker reported no ID for container '{container}'"))
+    })
+}
+
+/// Exec a shell in the already running container named in `config`,
+/// returning the container's full ID, the exec and the shell's I/O
+/// streams. The configured environment variables and working directory
+/// apply to the shell.
+async fn exec_in_existing_container(
+    client: &bollard::Docker,
+    config: &DockerConfig,
+    shell: String,
+) -> Result<(String, ShellExec, AttachContainerResults), SessionError> {
+    let container = config.container_name.as_deref().unwrap_or_default();
+    let details = client
+        .inspect_container(container, None)
+        .await
+        .map_err(|e| match e {
+            bollard::errors::Error::DockerResponseServerError {
+                status_code: 404, ..
+            } => SessionError::NotRunning(format!("Container '{container}' does not exist")),
+            e => {
+                SessionError::SpawnFailed(format!("Failed to inspect container '{container}': {e}"))
+            }
+        })?;
+    let container_id = running_container_id(container, details)?;
+
+    let env: Vec<String> = config
+        .env_vars
+        .iter()
+        .map(|ev| format!("{}={}", ev.key, ev.value))
+        .collect();
+    let exec_config = CreateExecOptions {
+        env: (!env.is_empty()).then_some(env),
+        working_dir: config.working_directory.clone(),
+        ..shell_exec_options(shell)
+    };
+    let (exec, io) = exec_shell(client, &container_id, exec_config).await?;
+    info!(container_id = %container_id, "Attached to existing container");
+    Ok((container_id, exec, io))
+}
+
+/// Attach to the container's main process, returning its I/O streams.
+async fn attach_main_process(
+    client: &bollard::Docker,
+    container_id: &str,
+) -> Result<AttachContainerResults, SessionError> {
+    let attach_opts = AttachContainerOptions::<String> {
+        stdin: Some(true),
+        stdout: Some(true),
+        stderr: Some(true),
+        stream: Some(true),
+        ..Default::default()
+    };
+    client
+        .attach_container(container_id, Some(attach_opts))
+        .await
+        .map_err(|e| SessionError::SpawnFailed(format!("Failed to attach to container: {e}")))
+}
+
+/// Attach to the container's main process and start the container,
+/// returning the process's I/O streams. Attaching first keeps the
+/// process's initial output.
+async fn start_attached(
+    client: &bollard::Docker,
+    container_id: &str,
+) -> Result<AttachContainerResults, SessionError> {
+    let io = attach_main_process(client, container_id).await?;
+
+    client
+        .start_container::<String>(container_id, None)
+        .await
+        .map_err(|e| SessionError::SpawnFailed(format!("Failed to start container: {e}")))?;
+
+    info!(container_id = %container_id, "Container started, attached to main process");
+    Ok(io)
+}
+
+/// Pull the image, then create and start the session's own container
+/// named `container_name`, returning its ID, the process the terminal
+/// connects to and that process's I/O streams.
+async fn start_session_container(
+    client: &bollard::Docker,
+    config: &DockerConfig,
+    container_name: &str,
+    shell: String,
+) -> Result<(String, SessionProcess, AttachContainerResults), SessionError> {
+    // Pull the image if it's not already available locally.
+    info!(image = %config.image, "Pulling Docker image");
+    let pull_opts = CreateImageOptions {
+        from_image: config.image.as_str(),
+        ..Default::default()
+    };
+    let mut pull_stream = client.create_image(Some(pull_opts), None, None);
+    while let Some(result) = pull_stream.next().await {
+        match result {
+            Ok(info) => {
+                debug!(?info, "Image pull progress");
+            }
+            Err(e) => {
+                return Err(SessionError::SpawnFailed(format!(
+                    "Failed to pull image '{}': {e}",
+                    config.image
+                )));
+            }
+        }
+    }
+    info!(image = %config.image, "Image ready");
+
+    let mode = config.session_mode;
+    let keep_alive = mode != DockerSessionMode::Attach;
+    let mut container_id =
+        create_session_container(client, config, container_name, keep_alive).await?;
+    let started = if keep_alive {
+        start_exec_shell(client, &container_id, shell)
+            .await
+            .map(|(exec, io)| (SessionProcess::Exec(exec), io))
+    } else {
+        start_attached(client, &container_id)
+            .await
+            .map(|io| (SessionProcess::Attached, io))
+    };
+    let (process, io) = match started {
+        Ok(started) => started,
+        Err(e) if should_fall_back_to_attach(mode, &e) => {
+            // The image has no shell (or not even `tail`); recreate the
+            // container running its own command and attach to that.
+            info!(
+                container_id = %container_id,
+                "Shell unavailable, attaching to the container instead: {e}"
+            );
+            discard_container(client, &container_id).await;
+            container_id = create_session_container(client, config, container_name, false).await?;
+            let io = start_attached(client, &container_id).await?;
+            (SessionProcess::Attached, io)
+        }
+        Err(e) => return Err(e),
+    };
+    Ok((container_id, process, io))
+}
+
+/// What to do with a connection's persistent container on connect.
+#[derive(Debug, PartialEq, Eq)]
+enum PersistentContainer {
+    /// There is none yet: create it.
+    Create,
+    /// It is running: open the terminal in it.
+    Reuse(String),
+    /// It is created or stopped: start it first.
+    Start(String),
+    /// It is paused: unpause it first.
+    Unpause(String),
+    /// It is dead or being removed: remove it and create a new one.
+    Replace(String),
+}
+
+/// Decide how to open the persistent container `container_name` given
+/// its inspect details, `None` when it does not exist. The container is
+/// referred to by its ID when Docker reported one.
+fn persistent_container_action(
+    container_name: &str,
+    details: Option<&ContainerInspectResponse>,
+) -> PersistentContainer {
+    let Some(details) = details else {
+        return PersistentContainer::Create;
+    };
+    let id = details
+        .id
+        .clone()
+        .unwrap_or_else(|| container_name.to_string());
+    match details.state.as_ref().and_then(|s| s.status) {
+        Some(ContainerStateStatusEnum::RUNNING) => PersistentContainer::Reuse(id),
+        Some(ContainerStateStatusEnum::PAUSED) => PersistentContainer::Unpause(id),
+        Some(ContainerStateStatusEnum::DEAD | ContainerStateStatusEnum::REMOVING) => {
+            PersistentContainer::Replace(id)
+        }
+        _ => PersistentContainer::Start(id),
+    }
+}
+
+/// Open the terminal in the connection's persistent container
+/// `container_name`, reusing the container when it exists and creating it
+/// otherwise. Returns the same as [`start_session_container`].
+///
+/// When another session of the connection creates the container first,
+/// creating it here fails with a name conflict; the container is then
+/// looked up again and reused.
+async fn open_persistent_container(
+    client: &bollard::Docker,
+    config: &DockerConfig,
+    container_name: &str,
+    shell: String,
+) -> Result<(String, SessionProcess, AttachContainerResults), SessionError> {
+    match open_persistent_container_once(client, config, container_name, shell.clone()).await {
+        Err(SessionError::AlreadyExists(reason)) => {
+            info!(container = %container_name, "Persistent container created concurrently: {reason}");
+            open_persistent_container_once(client, config, container_name, shell).await
+        }
+        result => result,
+    }
+}
+
+/// One attempt of [`open_persistent_container`].
+async fn open_persistent_container_once(
+    client: &bollard::Docker,
+    config: &DockerConfig,
+    container_name: &str,
+    shell: String,
+) -> Result<(String, SessionProcess, AttachContainerResults), SessionError> {
+    let details = match client.inspect_container(container_name, None).await {
+        Ok(details) => Some(details),
+        Err(bollard::errors::Error::DockerResponseServerError {
+            status_code: 404, ..
+        }) => None,
+        Err(e) => {
+            return Err(SessionError::SpawnFailed(format!(
+                "Failed to inspect container '{container_name}': {e}"
+            )))
+        }
+    };
+    let mode = config.session_mode;
+    let container_id = match persistent_container_action(container_name, details.as_ref()) {
+        PersistentContainer::Create => {
+            return start_session_container(client, config, container_name, shell).await;
+        }
+        PersistentContainer::Replace(id) => {
+            info!(container_id = %id, "Replacing unusable persistent container");
+            discard_container(client, &id).await;
+            return start_session_container(client, config, container_name, shell).await;
+        }
+        PersistentContainer::Start(id) if mode == DockerSessionMode::Attach => {
+            let io = start_attached(client, &id).await?;
+            return Ok((id, SessionProcess::Attached, io));
+        }
+        PersistentContainer::Start(id) => {
+            client
+                .start_container::<String>(&id, None)
+                .await
+                .map_err(|e| {
+                    SessionError::SpawnFailed(format!("Failed to start container: {e}"))
+                })?;
+            id
+        }
+        PersistentContainer::Unpause(id) => {
+            client.unpause_container(&id).await.map_err(|e| {
+                SessionError::SpawnFailed(format!("Failed to unpause container: {e}"))
+            })?;
+            id
+        }
+        PersistentContainer::Reuse(id) => id,
+    };
+    info!(container_id = %container_id, "Reusing persistent container");
+
+    if mode == DockerSessionMode::Attach {
+        let io = attach_main_process(client, &container_id).await?;
+        return Ok((container_id, SessionProcess::Attached, io));
+    }
+    match exec_shell(client, &container_id, shell_exec_options(shell)).await {
+        Ok((exec, io)) => Ok((container_id, SessionProcess::Exec(exec), io)),
+        // Created from an image without a shell; attach as when it was created.
+        Err(e) if should_fall_back_to_attach(mode, &e) => {
+            let io = attach_main_process(client, &container_id).await?;
+            Ok((container_id, SessionProcess::Attached, io))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether other sessions may still use the persistent container
+/// `container_id` that the session's `process` ran in. Other sessions'
+/// shells show up as running execs; clients attached to the main process
+/// cannot be seen, so an attached container always counts as shared.
+/// Also `true` when Docker cannot tell, so no session loses its shell.
+async fn persistent_container_in_use(
+    client: &bollard::Docker,
+    container_id: &str,
+    process: &SessionProcess,
+) -> bool {
+    let SessionProcess::Exec(own_exec) = process else {
+        return true;
+    };
+    let exec_ids = match client.inspect_container(container_id, None).await {
+        Ok(details) => details.exec_ids.unwrap_or_default(),
+        Err(e) => {
+            warn!(container_id = %container_id, "Failed to inspect container: {e}");
+            return true;
+        }
+    };
+    for exec_id in exec_ids.iter().filter(|id| **id != own_exec.id) {
+        match client.inspect_exec(exec_id).await {
+            Ok(exec) if exec.running == Some(true) => return true,
+            Ok(_) => {}
+            Err(e) => {
+                warn!(exec_id = %exec_id, "Failed to inspect exec: {e}");
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Hang up the interactive shell `exec` and wait up to
+/// [`SHELL_EXIT_WAIT`] for it to exit.
+///
+/// Docker cannot signal an exec, and the PID `inspect_exec` reports is
+/// the host's, not the container's. So a separate exec sends `SIGHUP`,
+/// as a closing terminal would, to every process in the container whose
+/// environment carries the shell's [`EXEC_TOKEN_VAR`]: the shell and the
+/// programs it started. A program that ignores the signal keeps the
+/// shell alive; that is only logged.
+async fn end_exec_shell(client: &bollard::Docker, container_id: &str, exec: &ShellExec) {
+    let options = CreateExecOptions {
+        cmd: Some(vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            hangup_script(&exec.token),
+        ]),
+        ..Default::default()
+    };
+    let hangup = match client.create_exec(container_id, options).await {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            warn!(exec_id = %exec.id, "Failed to create hangup exec: {e}");
+            return;
+        }
+    };
+    let start = StartExecOptions {
+        detach: true,
+        ..Default::default()
+    };
+    if let Err(e) = client.start_exec(&hangup.id, Some(start)).await {
+        warn!(exec_id = %exec.id, "Failed to start hangup exec: {e}");
+        return;
     }
+    let deadline = Instant::now() + SHELL_EXIT_WAIT;
+    loop {
+        match client.inspect_exec(&exec.id).await {
+            Ok(inspect) if inspect.running != Some(true) => return,
+            Ok(inspect) if Instant::now() >= deadline => {
+                warn!(exec_id = %exec.id, pid = ?inspect.pid, "Shell did not exit on disconnect");
+                return;
+            }
+            Ok(_) => tokio::time::sleep(EXEC_POLL).await,
+            Err(e) => {
+                warn!(exec_id = %exec.id, "Failed to inspect exec: {e}");
+                return;
+            }
+        }
+    }
+}
+
+/// Shell script sending `SIGHUP` to the processes whose environment has
+/// [`EXEC_TOKEN_VAR`] set to `token`.
+fn hangup_script(token: &str) -> String {
+    let entry = shell_quote(&format!("{EXEC_TOKEN_VAR}={token}"));
+    format!(
+        "for f in /proc/[0-9]*/environ; do \
+         if tr '\\0' '\\n' < \"$f\" 2>/dev/null | grep -qx {entry}; then \
+         p=${{f#/proc/}}; kill -HUP \"${{p%/environ}}\" 2>/dev/null; \
+         fi; done"
+    )
+}
+
+/// Force-remove a container that could not be used for the session.
+async fn discard_container(client: &bollard::Docker, container_id: &str) {
+    let options = RemoveContainerOptions {
+        force: true,
+        ..Default::default()
+    };
+    if let Err(e) = client.remove_container(container_id, Some(options)).await {
+        warn!(container_id = %container_id, "Failed to remove container: {e}");
+    }
+}
+
+/// Whether a container or exec start failure reports that the executable
+/// to run does not exist in the image. Container starts report this as an
+/// error from the daemon; execs via [`wait_for_exec_start`].
+fn is_missing_executable(message: &str) -> bool {
+    let message = message.to_ascii_lowercase();
+    message.contains("executable file not found") || message.contains("no such file or directory")
+}
+
+/// Whether a failure to start the interactive shell should be retried by
+/// attaching to the container's main process.
+fn should_fall_back_to_attach(mode: DockerSessionMode, error: &SessionError) -> bool {
+    mode == DockerSessionMode::Auto && is_missing_executable(&error.to_string())
+}
+
+/// Seconds since the Unix epoch.
+fn unix_now_secs() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
 }
 
 /// Generate a unique container name for this session.
@@ -320,15 +1124,65 @@ impl ConnectionType for Docker {
                     key: "container".to_string(),
                     label: "Container".to_string(),
                     fields: vec![
+                        SettingsField {
+                            key: "mode".to_string(),
+                            label: "Container".to_string(),
+                            description: Some(
+                                "Create a new container, or open a shell in one that is already running"
+                                    .to_string(),
+                            ),
+                            help_text: Some(
+                                "An attached container is never stopped or removed when the \
+                                 session closes"
+                                    .to_string(),
+                            ),
+                            field_type: FieldType::Select {
+                                options: vec![
+                                    SelectOption {
+                                        value: "create".to_string(),
+                                        label: "Create new container".to_string(),
+                                    },
+                                    SelectOption {
+                                        value: "attach".to_string(),
+                                        label: "Attach to running container".to_string(),
+                                    },
+                                ],
+                            },
+                            required: false,
+                            default: Some(serde_json::json!("create")),
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "containerName".to_string(),
+                            label: "Container Name or ID".to_string(),
+                            description: Some("Running container to open a shell in".to_string()),
+                            help_text: None,
+                            field_type: FieldType::Text,
+                            required: true,
+                            default: None,
+                            placeholder: Some("my-app".to_string()),
+                            supports_env_expansion: true,
+                            supports_tilde_expansion: false,
+                            visible_when: Some(Condition {
+                                field: "mode".to_string(),
+                                equals: serde_json::json!("attach"),
+                            }),
+                        },
                         SettingsField {
                             key: "image".to_string(),
                             label: "Image".to_string(),
                             description: Some(
                                 "Docker image to use (e.g., ubuntu:22.04)".to_string(),
                             ),
-                            help_text: None,
+                            help_text: Some(
+                                "Required when creating a container; not used when attaching"
+                                    .to_string(),
+                            ),
                             field_type: FieldType::Text,
-                            required: true,
+                            required: false,
                             default: None,
                             placeholder: Some("ubuntu:22.04".to_string()),
                             supports_env_expansion: true,
@@ -351,6 +1205,41 @@ impl ConnectionType for Docker {
                             supports_tilde_expansion: false,
                             visible_when: None,
                         },
+                        SettingsField {
+                            key: "sessionMode".to_string(),
+                            label: "Session Mode".to_string(),
+                            description: Some(
+                                "Run the shell via exec, or attach to the container's main process"
+                                    .to_string(),
+                            ),
+                            help_text: Some(
+                                "Auto attaches when the image has no shell, e.g. distroless or \
+                                 scratch images"
+                                    .to_string(),
+                            ),
+                            field_type: FieldType::Select {
+                                options: vec![
+                                    SelectOption {
+                                        value: "auto".to_string(),
+                                        label: "Auto (exec, attach without shell)".to_string(),
+                                    },
+                                    SelectOption {
+                                        value: "exec".to_string(),
+                                        label: "Exec".to_string(),
+                                    },
+                                    SelectOption {
+                                        value: "attach".to_string(),
+                                        label: "Attach".to_string(),
+                                    },
+                                ],
+                            },
+                            required: false,
+                            default: Some(serde_json::json!("auto")),
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
                         SettingsField {
                             key: "workingDirectory".to_string(),
                             label: "Working Directory".to_string(),
@@ -372,7 +1261,22 @@ impl ConnectionType for Docker {
                             description: Some(
                                 "Remove the container when the session is closed".to_string(),
                             ),
-                            help_text: None,
+                            help_text: Some(
+                                concat!(
+                                    "When off, a saved connection keeps one container, named ",
+                                    "termihub-conn-<connection id>, shared by all its sessions. ",
+                                    "It is stopped when the last session using it closes and ",
+                                    "started again on the next connect, so files and installed ",
+                                    "packages survive app restarts. In attach mode it keeps ",
+                                    "running, as other sessions may be attached to it.\n\n",
+                                    "Changes to the image or container settings apply once that ",
+                                    "container is removed.\n\n",
+                                    "The container is only stopped or removed when the cleanup ",
+                                    "policy releases the session; a detached session keeps it ",
+                                    "running."
+                                )
+                                .to_string(),
+                            ),
                             field_type: FieldType::Boolean,
                             required: false,
                             default: Some(serde_json::json!(true)),
@@ -381,6 +1285,45 @@ impl ConnectionType for Docker {
                             supports_tilde_expansion: false,
                             visible_when: None,
                         },
+                        cleanup_policy_field(),
+                        SettingsField {
+                            key: "stopTimeoutSecs".to_string(),
+                            label: "Stop Timeout (s)".to_string(),
+                            description: Some(
+                                "Seconds to wait for the container to stop before killing it"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Number {
+                                min: Some(0.0),
+                                max: None,
+                            },
+                            required: false,
+                            default: Some(serde_json::json!(5)),
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "daemonConnectTimeoutSecs".to_string(),
+                            label: "Daemon Connect Timeout (s)".to_string(),
+                            description: Some(
+                                "Seconds to keep retrying while the container daemon is unavailable"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Number {
+                                min: Some(0.0),
+                                max: None,
+                            },
+                            required: false,
+                            default: Some(serde_json::json!(10)),
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
                         SettingsField {
                             key: "runtime".to_string(),
                             label: "Runtime".to_string(),
@@ -492,13 +1435,129 @@ impl ConnectionType for Docker {
                         },
                     ],
                 },
+                SettingsGroup {
+                    key: "security".to_string(),
+                    label: "Security".to_string(),
+                    fields: vec![
+                        SettingsField {
+                            key: "dropCapabilities".to_string(),
+                            label: "Drop Capabilities".to_string(),
+                            description: Some(
+                                "Linux capabilities to remove, comma separated (ALL drops every one)"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Text,
+                            required: false,
+                            default: None,
+                            placeholder: Some("ALL".to_string()),
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "addCapabilities".to_string(),
+                            label: "Add Capabilities".to_string(),
+                            description: Some(
+                                "Linux capabilities to grant, comma separated".to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Text,
+                            required: false,
+                            default: None,
+                            placeholder: Some("NET_BIND_SERVICE, CHOWN".to_string()),
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "privileged".to_string(),
+                            label: "Privileged".to_string(),
+                            description: Some(
+                                "Give the container full access to the host (avoid for untrusted images)"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Boolean,
+                            required: false,
+                            default: Some(serde_json::json!(false)),
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                    ],
+                },
+                SettingsGroup {
+                    key: "resources".to_string(),
+                    label: "Resources".to_string(),
+                    fields: vec![
+                        SettingsField {
+                            key: "memoryLimitMb".to_string(),
+                            label: "Memory Limit (MiB)".to_string(),
+                            description: Some(
+                                "Maximum memory the container may use (0 for unlimited)"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Number {
+                                min: Some(0.0),
+                                max: None,
+                            },
+                            required: false,
+                            default: None,
+                            placeholder: Some("512".to_string()),
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "cpus".to_string(),
+                            label: "CPUs".to_string(),
+                            description: Some(
+                                "Number of CPUs the container may use, e.g. 1.5 (0 for unlimited)"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Number {
+                                min: Some(0.0),
+                                max: None,
+                            },
+                            required: false,
+                            default: None,
+                            placeholder: Some("1.5".to_string()),
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "cpuShares".to_string(),
+                            label: "CPU Shares".to_string(),
+                            description: Some(
+                                "Relative CPU weight when CPUs are contended (default 1024)"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Number {
+                                min: Some(0.0),
+                                max: None,
+                            },
+                            required: false,
+                            default: None,
+                            placeholder: Some("1024".to_string()),
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                    ],
+                },
             ],
         }
     }
 
     fn capabilities(&self) -> Capabilities {
         Capabilities {
-            monitoring: false,
+            monitoring: true,
             file_browser: true,
             resize: true,
             persistent: true,
@@ -510,208 +1569,116 @@ impl ConnectionType for Docker {
             return Err(SessionError::AlreadyExists("Already connected".to_string()));
         }
 
+        check_memory_limit_setting(&settings)?;
         let config = parse_docker_settings(&settings);
         let config = config.expand();
 
         validate_docker_config(&config)?;
 
-        info!(image = %config.image, "Connecting Docker session");
-
-        // Connect to the container runtime (Docker or Podman).
-        let client = connect_to_runtime(&config.runtime)?;
-
-        // Pull the image if it's not already available locally.
-        info!(image = %config.image, "Pulling Docker image");
-        let pull_opts = CreateImageOptions {
-            from_image: config.image.as_str(),
-            ..Default::default()
-        };
-        let mut pull_stream = client.create_image(Some(pull_opts), None, None);
-        while let Some(result) = pull_stream.next().await {
-            match result {
-                Ok(info) => {
-                    debug!(?info, "Image pull progress");
-                }
-                Err(e) => {
-                    return Err(SessionError::SpawnFailed(format!(
-                        "Failed to pull image '{}': {e}",
-                        config.image
-                    )));
-                }
-            }
-        }
-        info!(image = %config.image, "Image ready");
+        // Connect to the container runtime (Docker or Podman), retrying
+        // while the daemon is still starting up.
+        let client = connect_with_retry(
+            Duration::from_secs(u64::from(config.daemon_connect_timeout_secs)),
+            DAEMON_RETRY_BACKOFF,
+            || connect_and_ping(&config.runtime),
+        )
+        .await?;
 
-        let container_name = generate_container_name();
         let shell = config
             .shell
             .clone()
             .unwrap_or_else(|| "/bin/sh".to_string());
-
-        // Build environment variables for the container.
-        let env: Vec<String> = config
-            .env_vars
-            .iter()
-            .map(|ev| format!("{}={}", ev.key, ev.value))
-            .collect();
-
-        // Build volume binds.
-        let binds: Vec<String> = config
-            .volumes
-            .iter()
-            .map(|v| {
-                let mut bind = format!("{}:{}", v.host_path, v.container_path);
-                if v.read_only {
-                    bind.push_str(":ro");
-                }
-                bind
-            })
-            .collect();
-
-        // Create container configuration.
-        let container_config = Config {
-            image: Some(config.image.clone()),
-            tty: Some(true),
-            open_stdin: Some(true),
-            env: if env.is_empty() { None } else { Some(env) },
-            working_dir: config.working_directory.clone(),
-            // Use `tail -f /dev/null` to keep the container alive.
-            cmd: Some(vec![
-                "tail".to_string(),
-                "-f".to_string(),
-                "/dev/null".to_string(),
-            ]),
-            host_config: Some(HostConfig {
-                binds: if binds.is_empty() { None } else { Some(binds) },
-                init: Some(true),
-                ..Default::default()
-            }),
-            ..Default::default()
-        };
-
-        // Create the container.
-        let create_opts = CreateContainerOptions {
-            name: container_name.as_str(),
-            platform: None,
-        };
-        let create_response = client
-            .create_container(Some(create_opts), container_config)
-            .await
-            .map_err(|e| SessionError::SpawnFailed(format!("Failed to create container: {e}")))?;
-
-        let container_id = create_response.id;
-        debug!(container_id = %container_id, "Container created");
-
-        // Start the container.
-        client
-            .start_container::<String>(&container_id, None)
-            .await
-            .map_err(|e| SessionError::SpawnFailed(format!("Failed to start container: {e}")))?;
-
-        info!(container_id = %container_id, "Container started");
-
-        // Create an interactive exec instance with the shell.
-        let exec_config = CreateExecOptions {
-            attach_stdin: Some(true),
-            attach_stdout: Some(true),
-            attach_stderr: Some(true),
-            tty: Some(true),
-            cmd: Some(vec![shell]),
-            ..Default::default()
-        };
-
-        let exec_response = client
-            .create_exec(&container_id, exec_config)
-            .await
-            .map_err(|e| SessionError::SpawnFailed(format!("Failed to create exec: {e}")))?;
-
-        let exec_id = exec_response.id;
-        debug!(exec_id = %exec_id, "Exec instance created");
-
-        // Start the exec instance.
-        let start_config = StartExecOptions {
-            detach: false,
-            ..Default::default()
-        };
-
-        let exec_result = client
-            .start_exec(&exec_id, Some(start_config))
-            .await
-            .map_err(|e| SessionError::SpawnFailed(format!("Failed to start exec: {e}")))?;
+        let owns_container = config.container_mode == DockerContainerMode::Create;
+        let persistent_name = persistent_container_name(&config);
+        let persistent = persistent_name.is_some();
+        let (container_id, process, io) = if let Some(name) = persistent_name {
+            info!(image = %config.image, container = %name, "Connecting Docker session");
+            open_persistent_container(&client, &config, &name, shell).await?
+        } else if owns_container {
+            info!(image = %config.image, "Connecting Docker session");
+            start_session_container(&client, &config, &generate_container_name(), shell).await?
+        } else {
+            info!(
+                container = config.container_name.as_deref().unwrap_or_default(),
+                "Connecting Docker session to existing container"
+            );
+            let (container_id, exec, io) =
+                exec_in_existing_container(&client, &config, shell).await?;
+            (container_id, SessionProcess::Exec(exec), io)
+        };
 
         let alive = Arc::new(AtomicBool::new(true));
 
         // Set up stdin channel.
         let (stdin_tx, mut stdin_rx) = tokio::sync::mpsc::channel::<Vec<u8>>(64);
 
-        match exec_result {
-            StartExecResults::Attached { mut output, input } => {
-                // Spawn reader task: forwards exec output to the output channel.
-                let alive_clone = alive.clone();
-                let output_tx_clone = self.output_tx.clone();
-                tokio::spawn(async move {
-                    while alive_clone.load(Ordering::SeqCst) {
-                        match output.next().await {
-                            Some(Ok(log_output)) => {
-                                let bytes = log_output.into_bytes();
-                                if bytes.is_empty() {
-                                    continue;
-                                }
-                                // Clone the sender out of the lock before awaiting.
-                                let sender =
-                                    output_tx_clone.lock().ok().and_then(|guard| guard.clone());
-                                if let Some(sender) = sender {
-                                    if sender.send(bytes.to_vec()).await.is_err() {
-                                        break;
-                                    }
-                                }
-                                // No subscriber yet — discard output and keep reading.
-                            }
-                            Some(Err(e)) => {
-                                warn!("Docker exec output error: {e}");
+        let AttachContainerResults { mut output, input } = io;
+
+        // Spawn reader task: forwards process output to the output channel.
+        let alive_clone = alive.clone();
+        let output_tx_clone = self.output_tx.clone();
+        tokio::spawn(async move {
+            while alive_clone.load(Ordering::SeqCst) {
+                match output.next().await {
+                    Some(Ok(log_output)) => {
+                        let bytes = log_output.into_bytes();
+                        if bytes.is_empty() {
+                            continue;
+                        }
+                        // Clone the sender out of the lock before awaiting.
+                        let sender = output_tx_clone.lock().ok().and_then(|guard| guard.clone());
+                        if let Some(sender) = sender {
+                            if sender.send(bytes.to_vec()).await.is_err() {
                                 break;
                             }
-                            None => break,
                         }
+                        // No subscriber yet — discard output and keep reading.
                     }
-                    alive_clone.store(false, Ordering::SeqCst);
-                });
-
-                // Spawn stdin writer task: forwards stdin channel to exec input.
-                let alive_clone = alive.clone();
-                tokio::spawn(async move {
-                    let mut input = input;
-                    while alive_clone.load(Ordering::SeqCst) {
-                        match stdin_rx.recv().await {
-                            Some(data) => {
-                                if input.write_all(&data).await.is_err() {
-                                    break;
-                                }
-                                if input.flush().await.is_err() {
-                                    break;
-                                }
-                            }
-                            None => break,
-                        }
+                    Some(Err(e)) => {
+                        warn!("Docker output error: {e}");
+                        break;
                     }
-                });
+                    None => break,
+                }
             }
-            StartExecResults::Detached => {
-                return Err(SessionError::SpawnFailed(
-                    "Exec started in detached mode unexpectedly".to_string(),
-                ));
+            alive_clone.store(false, Ordering::SeqCst);
+        });
+
+        // Spawn stdin writer task: forwards stdin channel to process input.
+        let alive_clone = alive.clone();
+        tokio::spawn(async move {
+            let mut input = input;
+            while alive_clone.load(Ordering::SeqCst) {
+                match stdin_rx.recv().await {
+                    Some(data) => {
+                        if input.write_all(&data).await.is_err() {
+                            break;
+                        }
+                        if input.flush().await.is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                }
             }
-        }
+        });
 
-        // Create file browser provider.
+        // Create file browser and monitoring providers.
         self.file_browser_provider =
             Some(DockerFileBrowser::new(client.clone(), container_id.clone()));
+        self.monitoring_provider = Some(DockerMonitoringProvider::new(
+            client.clone(),
+            container_id.clone(),
+        ));
 
         self.state = Some(ConnectedState {
             client,
             container_id,
-            exec_id,
+            process,
+            owns_container,
+            persistent,
             remove_on_exit: config.remove_on_exit,
+            stop_timeout_secs: config.stop_timeout_secs,
             alive,
             stdin_tx,
         });
@@ -720,9 +1687,28 @@ impl ConnectionType for Docker {
     }
 
     async fn disconnect(&mut self) -> Result<(), SessionError> {
+        if let Some(ref monitoring) = self.monitoring_provider {
+            let _ = monitoring.unsubscribe().await;
+        }
+        self.monitoring_provider = None;
         self.file_browser_provider = None;
 
         if let Some(state) = self.state.take() {
+            // A container the session attached to belongs to someone else:
+            // leave it running, whatever `removeOnExit` says. The same goes
+            // for a persistent container other tabs are still using.
+            let shared = state.persistent
+                && persistent_container_in_use(&state.client, &state.container_id, &state.process)
+                    .await;
+
+            // Closing the exec's streams does not end its shell, so one left
+            // in a running container is hung up first.
+            if shared || !state.owns_container {
+                if let SessionProcess::Exec(exec) = &state.process {
+                    end_exec_shell(&state.client, &state.container_id, exec).await;
+                }
+            }
+
             state.alive.store(false, Ordering::SeqCst);
 
             // Drop the stdin sender to signal the writer task to stop.
@@ -733,38 +1719,48 @@ impl ConnectionType for Docker {
                 *guard = None;
             }
 
-            // Stop the container (5-second timeout).
-            let stop_result = state
-                .client
-                .stop_container(&state.container_id, Some(StopContainerOptions { t: 5 }))
-                .await;
-
-            if let Err(e) = stop_result {
-                warn!(
+            if shared {
+                info!(
                     container_id = %state.container_id,
-                    "Failed to stop container: {e}"
+                    "Leaving persistent container running for other sessions"
                 );
-            }
-
-            // Optionally remove the container.
-            if state.remove_on_exit {
-                let remove_result = state
+            } else if state.owns_container {
+                // Stop the container, killing it after the configured timeout.
+                let stop_result = state
                     .client
-                    .remove_container(
+                    .stop_container(
                         &state.container_id,
-                        Some(RemoveContainerOptions {
-                            force: true,
-                            ..Default::default()
-                        }),
+                        Some(stop_options(state.stop_timeout_secs)),
                     )
                     .await;
 
-                if let Err(e) = remove_result {
+                if let Err(e) = stop_result {
                     warn!(
                         container_id = %state.container_id,
-                        "Failed to remove container: {e}"
+                        "Failed to stop container: {e}"
                     );
                 }
+
+                // Optionally remove the container.
+                if state.remove_on_exit {
+                    let remove_result = state
+                        .client
+                        .remove_container(
+                            &state.container_id,
+                            Some(RemoveContainerOptions {
+                                force: true,
+                                ..Default::default()
+                            }),
+                        )
+                        .await;
+
+                    if let Err(e) = remove_result {
+                        warn!(
+                            container_id = %state.container_id,
+                            "Failed to remove container: {e}"
+                        );
+                    }
+                }
             }
 
             debug!("Docker session disconnected");
@@ -797,16 +1793,32 @@ impl ConnectionType for Docker {
             .ok_or_else(|| SessionError::NotRunning("Not connected".to_string()))?;
 
         let client = state.client.clone();
-        let exec_id = state.exec_id.clone();
-        let options = ResizeExecOptions {
-            width: cols,
-            height: rows,
+        let container_id = state.container_id.clone();
+        let exec_id = match &state.process {
+            SessionProcess::Exec(exec) => Some(exec.id.clone()),
+            SessionProcess::Attached => None,
         };
 
         // Spawn a task to perform the async resize.
         tokio::spawn(async move {
-            if let Err(e) = client.resize_exec(&exec_id, options).await {
-                warn!("Docker exec resize failed: {e}");
+            let result = match exec_id {
+                Some(exec_id) => {
+                    let options = ResizeExecOptions {
+                        width: cols,
+                        height: rows,
+                    };
+                    client.resize_exec(&exec_id, options).await
+                }
+                None => {
+                    let options = ResizeContainerTtyOptions {
+                        width: cols,
+                        height: rows,
+                    };
+                    client.resize_container_tty(&container_id, options).await
+                }
+            };
+            if let Err(e) = result {
+                warn!("Docker resize failed: {e}");
             }
         });
 
@@ -822,7 +1834,9 @@ impl ConnectionType for Docker {
     }
 
     fn monitoring(&self) -> Option<&dyn MonitoringProvider> {
-        None
+        self.monitoring_provider
+            .as_ref()
+            .map(|p| p as &dyn MonitoringProvider)
     }
 
     fn file_browser(&self) -> Option<&dyn FileBrowser> {
@@ -837,6 +1851,7 @@ mod tests {
     use super::*;
     use crate::config::ContainerRuntime;
     use crate::connection::validate_settings;
+    use bollard::models::{ContainerState, ContainerStateStatusEnum};
 
     // --- Metadata tests ---
 
@@ -856,7 +1871,7 @@ mod tests {
     fn capabilities() {
         let docker = Docker::new();
         let caps = docker.capabilities();
-        assert!(!caps.monitoring);
+        assert!(caps.monitoring);
         assert!(caps.file_browser);
         assert!(caps.resize);
         assert!(caps.persistent);
@@ -889,7 +1904,7 @@ mod tests {
     }
 
     #[test]
-    fn monitoring_always_none() {
+    fn monitoring_none_when_disconnected() {
         let docker = Docker::new();
         assert!(docker.monitoring().is_none());
     }
@@ -912,12 +1927,48 @@ mod tests {
     // --- Schema tests ---
 
     #[test]
-    fn schema_has_two_groups() {
+    fn schema_has_four_groups() {
         let docker = Docker::new();
         let schema = docker.settings_schema();
-        assert_eq!(schema.groups.len(), 2);
+        assert_eq!(schema.groups.len(), 4);
         assert_eq!(schema.groups[0].key, "container");
         assert_eq!(schema.groups[1].key, "environment");
+        assert_eq!(schema.groups[2].key, "security");
+        assert_eq!(schema.groups[3].key, "resources");
+    }
+
+    #[test]
+    fn schema_resources_group_fields() {
+        let docker = Docker::new();
+        let schema = docker.settings_schema();
+        let group = &schema.groups[3];
+        let keys: Vec<&str> = group.fields.iter().map(|f| f.key.as_str()).collect();
+        assert_eq!(keys, vec!["memoryLimitMb", "cpus", "cpuShares"]);
+        for field in &group.fields {
+            assert!(!field.required);
+            assert!(matches!(
+                field.field_type,
+                FieldType::Number {
+                    min: Some(min),
+                    max: None
+                } if min == 0.0
+            ));
+        }
+    }
+
+    #[test]
+    fn schema_security_group_fields() {
+        let docker = Docker::new();
+        let schema = docker.settings_schema();
+        let group = &schema.groups[2];
+        let keys: Vec<&str> = group.fields.iter().map(|f| f.key.as_str()).collect();
+        assert_eq!(
+            keys,
+            vec!["dropCapabilities", "addCapabilities", "privileged"]
+        );
+        let privileged = &group.fields[2];
+        assert!(matches!(privileged.field_type, FieldType::Boolean));
+        assert_eq!(privileged.default, Some(serde_json::json!(false)));
     }
 
     #[test]
@@ -929,10 +1980,16 @@ mod tests {
         assert_eq!(
             keys,
             vec![
+                "mode",
+                "containerName",
                 "image",
                 "shell",
+                "sessionMode",
                 "workingDirectory",
                 "removeOnExit",
+                "cleanupPolicy",
+                "stopTimeoutSecs",
+                "daemonConnectTimeoutSecs",
                 "runtime"
             ]
         );
@@ -959,6 +2016,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn schema_mode_field_is_select() {
+        let docker = Docker::new();
+        let schema = docker.settings_schema();
+        let mode = &schema.groups[0].fields[0];
+        assert_eq!(mode.key, "mode");
+        assert_eq!(mode.default, Some(serde_json::json!("create")));
+        let FieldType::Select { ref options } = mode.field_type else {
+            panic!("expected Select field type for mode");
+        };
+        let values: Vec<&str> = options.iter().map(|o| o.value.as_str()).collect();
+        assert_eq!(values, vec!["create", "attach"]);
+
+        let container = &schema.groups[0].fields[1];
+        assert_eq!(container.key, "containerName");
+        assert!(container.required);
+        let condition = container.visible_when.as_ref().unwrap();
+        assert_eq!(condition.field, "mode");
+        assert_eq!(condition.equals, serde_json::json!("attach"));
+    }
+
     #[test]
     fn schema_environment_group_fields() {
         let docker = Docker::new();
@@ -977,7 +2055,8 @@ mod tests {
             .iter()
             .find(|f| f.key == "image")
             .unwrap();
-        assert!(image.required);
+        // Not required in attach mode; validated on connect instead.
+        assert!(!image.required);
         assert!(image.supports_env_expansion);
         assert!(!image.supports_tilde_expansion);
         assert!(matches!(image.field_type, FieldType::Text));
@@ -1075,13 +2154,16 @@ mod tests {
     // --- Settings validation tests ---
 
     #[test]
-    fn validation_missing_image_fails() {
+    fn validation_attach_mode_requires_container() {
         let docker = Docker::new();
         let schema = docker.settings_schema();
-        let settings = serde_json::json!({});
+        let settings = serde_json::json!({"mode": "attach"});
+        let errors = validate_settings(&schema, &settings);
+        assert!(errors.iter().any(|e| e.field == "containerName"));
+
+        let settings = serde_json::json!({"mode": "attach", "containerName": "web-1"});
         let errors = validate_settings(&schema, &settings);
-        assert!(!errors.is_empty());
-        assert!(errors.iter().any(|e| e.field == "image"));
+        assert!(errors.is_empty(), "errors: {errors:?}");
     }
 
     #[test]
@@ -1194,6 +2276,50 @@ mod tests {
         assert_eq!(config.runtime, ContainerRuntime::Auto);
     }
 
+    #[test]
+    fn parse_session_mode() {
+        let config = parse_docker_settings(&serde_json::json!({"image": "alpine"}));
+        assert_eq!(config.session_mode, DockerSessionMode::Auto);
+
+        let settings = serde_json::json!({"image": "alpine", "sessionMode": "attach"});
+        let config = parse_docker_settings(&settings);
+        assert_eq!(config.session_mode, DockerSessionMode::Attach);
+
+        let settings = serde_json::json!({"image": "alpine", "sessionMode": "exec"});
+        let config = parse_docker_settings(&settings);
+        assert_eq!(config.session_mode, DockerSessionMode::Exec);
+
+        // Unknown modes fall back to Auto
+        let settings = serde_json::json!({"image": "alpine", "sessionMode": "bogus"});
+        let config = parse_docker_settings(&settings);
+        assert_eq!(config.session_mode, DockerSessionMode::Auto);
+    }
+
+    #[test]
+    fn parse_container_mode() {
+        let config = parse_docker_settings(&serde_json::json!({"image": "alpine"}));
+        assert_eq!(config.container_mode, DockerContainerMode::Create);
+        assert!(config.container_name.is_none());
+
+        let config = parse_docker_settings(&serde_json::json!({
+            "mode": "attach",
+            "containerName": "web-1",
+        }));
+        assert_eq!(config.container_mode, DockerContainerMode::Attach);
+        assert_eq!(config.container_name.as_deref(), Some("web-1"));
+
+        // A container ID is accepted under its own key too.
+        let config = parse_docker_settings(&serde_json::json!({
+            "mode": "attach",
+            "containerId": "4f2a9c",
+        }));
+        assert_eq!(config.container_name.as_deref(), Some("4f2a9c"));
+
+        // Unknown modes fall back to Create
+        let config = parse_docker_settings(&serde_json::json!({"mode": "bogus"}));
+        assert_eq!(config.container_mode, DockerContainerMode::Create);
+    }
+
     #[test]
     fn parse_full_settings() {
         let settings = serde_json::json!({
@@ -1238,6 +2364,260 @@ mod tests {
         assert!(config.remove_on_exit);
     }
 
+    #[test]
+    fn parse_session_and_connection_ids() {
+        let settings = serde_json::json!({
+            "image": "alpine",
+            "sessionId": "sess-1",
+            "connectionId": "conn-1",
+        });
+        let config = parse_docker_settings(&settings);
+        assert_eq!(config.session_id.as_deref(), Some("sess-1"));
+        assert_eq!(config.connection_id.as_deref(), Some("conn-1"));
+    }
+
+    #[test]
+    fn container_config_carries_termihub_labels() {
+        use crate::session::docker::{LABEL_CONNECTION_ID, LABEL_CREATED, LABEL_SESSION_ID};
+
+        let config = parse_docker_settings(&serde_json::json!({
+            "image": "alpine",
+            "sessionId": "sess-1",
+            "connectionId": "conn-1",
+        }));
+        let container = container_config(&config, "termihub-1-2", 42, true);
+        let labels = container.labels.unwrap();
+        assert_eq!(labels[LABEL_SESSION_ID], "sess-1");
+        assert_eq!(labels[LABEL_CONNECTION_ID], "conn-1");
+        assert_eq!(labels[LABEL_CREATED], "42");
+        assert_eq!(container.image.as_deref(), Some("alpine"));
+    }
+
+    #[test]
+    fn parse_stop_timeout_defaults_to_five() {
+        let config = parse_docker_settings(&serde_json::json!({"image": "alpine"}));
+        assert_eq!(config.stop_timeout_secs, 5);
+    }
+
+    #[test]
+    fn parse_stop_timeout_secs() {
+        let settings = serde_json::json!({
+            "image": "alpine",
+            "stopTimeoutSecs": 30,
+        });
+        let config = parse_docker_settings(&settings);
+        assert_eq!(config.stop_timeout_secs, 30);
+    }
+
+    #[test]
+    fn parse_invalid_stop_timeout_uses_default() {
+        let settings = serde_json::json!({
+            "image": "alpine",
+            "stopTimeoutSecs": -1,
+        });
+        let config = parse_docker_settings(&settings);
+        assert_eq!(config.stop_timeout_secs, 5);
+    }
+
+    #[test]
+    fn parse_daemon_connect_timeout() {
+        let config = parse_docker_settings(&serde_json::json!({"image": "alpine"}));
+        assert_eq!(config.daemon_connect_timeout_secs, 10);
+        let config = parse_docker_settings(&serde_json::json!({
+            "image": "alpine",
+            "daemonConnectTimeoutSecs": 0,
+        }));
+        assert_eq!(config.daemon_connect_timeout_secs, 0);
+    }
+
+    #[test]
+    fn parse_capabilities_from_list_and_string() {
+        let config = parse_docker_settings(&serde_json::json!({
+            "image": "alpine",
+            "dropCapabilities": ["all"],
+            "addCapabilities": "CAP_NET_BIND_SERVICE, chown",
+            "privileged": true,
+        }));
+        assert_eq!(config.drop_capabilities, vec!["ALL"]);
+        assert_eq!(config.add_capabilities, vec!["NET_BIND_SERVICE", "CHOWN"]);
+        assert!(config.privileged);
+    }
+
+    #[test]
+    fn parse_capabilities_default_empty() {
+        let config = parse_docker_settings(&serde_json::json!({
+            "image": "alpine",
+            "addCapabilities": "",
+        }));
+        assert!(config.drop_capabilities.is_empty());
+        assert!(config.add_capabilities.is_empty());
+        assert!(!config.privileged);
+    }
+
+    #[test]
+    fn parse_unknown_capability_fails_validation() {
+        let config = parse_docker_settings(&serde_json::json!({
+            "image": "alpine",
+            "dropCapabilities": "NET_RAW, NOT_A_CAP",
+        }));
+        let err = validate_docker_config(&config).unwrap_err();
+        assert!(
+            err.to_string().contains("NOT_A_CAP"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn host_config_sets_capability_fields() {
+        let config = parse_docker_settings(&serde_json::json!({
+            "image": "alpine",
+            "dropCapabilities": "ALL",
+            "addCapabilities": ["NET_BIND_SERVICE"],
+            "privileged": true,
+            "volumes": [{"hostPath": "/tmp", "containerPath": "/mnt", "readOnly": true}],
+        }));
+        let host = host_config(&config);
+        assert_eq!(host.cap_drop, Some(vec!["ALL".to_string()]));
+        assert_eq!(host.cap_add, Some(vec!["NET_BIND_SERVICE".to_string()]));
+        assert_eq!(host.privileged, Some(true));
+        assert_eq!(host.binds, Some(vec!["/tmp:/mnt:ro".to_string()]));
+        assert_eq!(host.init, Some(true));
+    }
+
+    #[test]
+    fn parse_resource_limits() {
+        let config = parse_docker_settings(&serde_json::json!({
+            "image": "alpine",
+            "memoryLimitMb": 512,
+            "cpus": 1.5,
+            "cpuShares": 256,
+        }));
+        assert_eq!(config.memory_limit_mb, 512);
+        assert_eq!(config.cpus, 1.5);
+        assert_eq!(config.cpu_shares, 256);
+
+        let config = parse_docker_settings(&serde_json::json!({"image": "alpine", "cpus": ""}));
+        assert_eq!(config.memory_limit_mb, 0);
+        assert_eq!(config.cpus, 0.0);
+        assert_eq!(config.cpu_shares, 0);
+    }
+
+    #[test]
+    fn negative_resource_limits_fail_validation() {
+        let config = parse_docker_settings(&serde_json::json!({
+            "image": "alpine",
+            "memoryLimitMb": -256,
+        }));
+        assert!(matches!(
+            validate_docker_config(&config),
+            Err(SessionError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn fractional_memory_limit_is_rejected() {
+        let err = check_memory_limit_setting(&serde_json::json!({
+            "image": "alpine",
+            "memoryLimitMb": 512.5,
+        }))
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("whole number of MiB: 512.5"),
+            "unexpected error: {err}"
+        );
+        assert!(check_memory_limit_setting(&serde_json::json!({"memoryLimitMb": 512})).is_ok());
+        assert!(check_memory_limit_setting(&serde_json::json!({"image": "alpine"})).is_ok());
+    }
+
+    #[test]
+    fn host_config_sets_resource_limits() {
+        let config = parse_docker_settings(&serde_json::json!({
+            "image": "alpine",
+            "memoryLimitMb": 512,
+            "cpus": 1.5,
+            "cpuShares": 256,
+        }));
+        let host = host_config(&config);
+        assert_eq!(host.memory, Some(512 * 1024 * 1024));
+        assert_eq!(host.nano_cpus, Some(1_500_000_000));
+        assert_eq!(host.cpu_shares, Some(256));
+    }
+
+    #[test]
+    fn host_config_defaults_leave_capabilities_unset() {
+        let config = parse_docker_settings(&serde_json::json!({"image": "alpine"}));
+        let host = host_config(&config);
+        assert!(host.cap_drop.is_none());
+        assert!(host.cap_add.is_none());
+        assert!(host.privileged.is_none());
+        assert!(host.binds.is_none());
+        assert!(host.memory.is_none());
+        assert!(host.nano_cpus.is_none());
+        assert!(host.cpu_shares.is_none());
+    }
+
+    /// Client factory that fails `failures` times before succeeding.
+    fn flaky_factory(
+        failures: u32,
+        attempts: &std::sync::atomic::AtomicU32,
+    ) -> impl FnMut() -> std::future::Ready<Result<&'static str, SessionError>> + '_ {
+        move || {
+            let n = attempts.fetch_add(1, Ordering::SeqCst);
+            std::future::ready(if n < failures {
+                Err(SessionError::SpawnFailed("daemon not running".to_string()))
+            } else {
+                Ok("client")
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn connect_retry_succeeds_after_failures() {
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let client = connect_with_retry(
+            Duration::from_secs(5),
+            Duration::from_millis(1),
+            flaky_factory(2, &attempts),
+        )
+        .await
+        .unwrap();
+        assert_eq!(client, "client");
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn connect_retry_gives_up_after_timeout() {
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let result = connect_with_retry(
+            Duration::from_millis(50),
+            Duration::from_millis(5),
+            flaky_factory(u32::MAX, &attempts),
+        )
+        .await;
+        assert!(matches!(result, Err(SessionError::SpawnFailed(_))));
+        let n = attempts.load(Ordering::SeqCst);
+        assert!((2..=10).contains(&n), "unexpected attempt count {n}");
+    }
+
+    #[tokio::test]
+    async fn connect_retry_zero_timeout_tries_once() {
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+        let result = connect_with_retry(
+            Duration::ZERO,
+            Duration::from_millis(1),
+            flaky_factory(1, &attempts),
+        )
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn stop_options_pass_timeout() {
+        assert_eq!(stop_options(30).t, 30);
+        assert_eq!(stop_options(0).t, 0);
+    }
+
     #[test]
     fn parse_empty_shell_is_none() {
         let settings = serde_json::json!({
@@ -1276,6 +2656,126 @@ mod tests {
         assert_eq!(config.volumes.len(), 1);
     }
 
+    #[tokio::test]
+    async fn connect_attach_without_container_fails() {
+        let mut docker = Docker::new();
+        let settings = serde_json::json!({"mode": "attach", "image": "alpine"});
+        let result = docker.connect(settings).await;
+        assert!(matches!(result, Err(SessionError::InvalidConfig(_))));
+    }
+
+    // --- Attach mode tests ---
+
+    fn inspect_response(
+        running: bool,
+        status: ContainerStateStatusEnum,
+    ) -> ContainerInspectResponse {
+        ContainerInspectResponse {
+            id: Some("4f2a9c0d".to_string()),
+            state: Some(ContainerState {
+                running: Some(running),
+                status: Some(status),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn running_container_resolves_to_full_id() {
+        let details = inspect_response(true, ContainerStateStatusEnum::RUNNING);
+        assert_eq!(running_container_id("web-1", details).unwrap(), "4f2a9c0d");
+    }
+
+    #[test]
+    fn stopped_container_is_not_running() {
+        let details = inspect_response(false, ContainerStateStatusEnum::EXITED);
+        let err = running_container_id("web-1", details).unwrap_err();
+        assert!(matches!(err, SessionError::NotRunning(_)));
+        assert!(
+            err.to_string()
+                .contains("Container 'web-1' is not running (status: exited)"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn container_without_state_is_not_running() {
+        let details = ContainerInspectResponse::default();
+        let err = running_container_id("web-1", details).unwrap_err();
+        assert!(err.to_string().contains("status: unknown"), "{err}");
+    }
+
+    // --- Persistent container tests ---
+
+    #[test]
+    fn missing_persistent_container_is_created() {
+        assert_eq!(
+            persistent_container_action("termihub-conn-1", None),
+            PersistentContainer::Create
+        );
+    }
+
+    #[test]
+    fn running_persistent_container_is_reused() {
+        let details = inspect_response(true, ContainerStateStatusEnum::RUNNING);
+        assert_eq!(
+            persistent_container_action("termihub-conn-1", Some(&details)),
+            PersistentContainer::Reuse("4f2a9c0d".to_string())
+        );
+    }
+
+    #[test]
+    fn stopped_persistent_container_is_started() {
+        for status in [
+            ContainerStateStatusEnum::EXITED,
+            ContainerStateStatusEnum::CREATED,
+        ] {
+            let details = inspect_response(false, status);
+            assert_eq!(
+                persistent_container_action("termihub-conn-1", Some(&details)),
+                PersistentContainer::Start("4f2a9c0d".to_string()),
+                "{status}"
+            );
+        }
+    }
+
+    #[test]
+    fn paused_persistent_container_is_unpaused() {
+        let details = inspect_response(true, ContainerStateStatusEnum::PAUSED);
+        assert_eq!(
+            persistent_container_action("termihub-conn-1", Some(&details)),
+            PersistentContainer::Unpause("4f2a9c0d".to_string())
+        );
+    }
+
+    #[test]
+    fn dead_persistent_container_is_replaced() {
+        for status in [
+            ContainerStateStatusEnum::DEAD,
+            ContainerStateStatusEnum::REMOVING,
+        ] {
+            let details = inspect_response(false, status);
+            assert_eq!(
+                persistent_container_action("termihub-conn-1", Some(&details)),
+                PersistentContainer::Replace("4f2a9c0d".to_string()),
+                "{status}"
+            );
+        }
+    }
+
+    #[test]
+    fn persistent_container_without_id_is_named() {
+        let details = ContainerInspectResponse {
+            id: None,
+            ..inspect_response(true, ContainerStateStatusEnum::RUNNING)
+        };
+        assert_eq!(
+            persistent_container_action("termihub-conn-1", Some(&details)),
+            PersistentContainer::Reuse("termihub-conn-1".to_string())
+        );
+    }
+
     #[tokio::test]
     async fn connect_empty_image_fails() {
         let mut docker = Docker::new();
@@ -1285,4 +2785,117 @@ mod tests {
         let result = docker.connect(settings).await;
         assert!(result.is_err());
     }
+
+    // --- Session mode tests ---
+
+    /// Error [`wait_for_exec_start`] reports for an exec whose shell does
+    /// not exist in the image.
+    fn missing_shell_error() -> SessionError {
+        match exec_start_state(Some(false), Some(127)) {
+            ExecStart::MissingExecutable(code) => SessionError::SpawnFailed(format!(
+                "Failed to start exec: executable file not found (exit code {code})"
+            )),
+            state => panic!("unexpected exec state {state:?}"),
+        }
+    }
+
+    #[test]
+    fn exec_start_state_detects_missing_executable_by_exit_code() {
+        assert_eq!(
+            exec_start_state(Some(false), Some(127)),
+            ExecStart::MissingExecutable(127)
+        );
+        assert_eq!(
+            exec_start_state(Some(false), Some(126)),
+            ExecStart::MissingExecutable(126)
+        );
+        assert_eq!(exec_start_state(Some(false), Some(0)), ExecStart::Exited);
+        assert_eq!(exec_start_state(Some(false), None), ExecStart::Pending);
+        assert_eq!(exec_start_state(None, None), ExecStart::Pending);
+        // A running shell may later exit with 127 from a mistyped command.
+        assert_eq!(exec_start_state(Some(true), Some(127)), ExecStart::Running);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn hangup_script_signals_only_tagged_processes() {
+        use std::os::unix::process::ExitStatusExt;
+        use std::process::Command;
+
+        let spawn = |token: &str| {
+            Command::new("sleep")
+                .arg("30")
+                .env(EXEC_TOKEN_VAR, token)
+                .spawn()
+                .unwrap()
+        };
+        let mut tagged = spawn("0123456789abcdef");
+        let mut other = spawn("fedcba9876543210");
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(hangup_script("0123456789abcdef"))
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        assert_eq!(tagged.wait().unwrap().signal(), Some(1), "SIGHUP");
+        assert!(other.try_wait().unwrap().is_none());
+        other.kill().unwrap();
+        other.wait().unwrap();
+    }
+
+    #[test]
+    fn auto_mode_falls_back_to_attach_without_shell() {
+        assert!(should_fall_back_to_attach(
+            DockerSessionMode::Auto,
+            &missing_shell_error()
+        ));
+    }
+
+    #[test]
+    fn auto_mode_falls_back_when_keep_alive_command_is_missing() {
+        let err = SessionError::SpawnFailed(
+            "Failed to start container: OCI runtime create failed: exec: \"tail\": \
+             executable file not found in $PATH: unknown"
+                .to_string(),
+        );
+        assert!(should_fall_back_to_attach(DockerSessionMode::Auto, &err));
+    }
+
+    #[test]
+    fn explicit_exec_mode_never_falls_back() {
+        assert!(!should_fall_back_to_attach(
+            DockerSessionMode::Exec,
+            &missing_shell_error()
+        ));
+    }
+
+    #[test]
+    fn unrelated_errors_do_not_fall_back() {
+        let err = SessionError::SpawnFailed(
+            "Failed to create exec: Docker responded with status code 409: container is not \
+             running"
+                .to_string(),
+        );
+        assert!(!should_fall_back_to_attach(DockerSessionMode::Auto, &err));
+    }
+
+    #[test]
+    fn container_config_keeps_image_command_when_attaching() {
+        let config = DockerConfig {
+            image: "alpine".to_string(),
+            ..Default::default()
+        };
+        assert!(container_config(&config, "termihub-1-2", 42, false)
+            .cmd
+            .is_none());
+        assert_eq!(
+            container_config(&config, "termihub-1-2", 42, true).cmd,
+            Some(vec![
+                "tail".to_string(),
+                "-f".to_string(),
+                "/dev/null".to_string()
+            ])
+        );
+    }
 }
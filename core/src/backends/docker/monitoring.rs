@@ -0,0 +1,309 @@
+//! Docker monitoring: container resource usage from the daemon's stats API.
+//!
+//! Unlike SSH monitoring, nothing runs inside the container. CPU, memory,
+//! network and process figures come from the container's cgroup through
+//! the stats endpoint (as shown by `docker stats`), so images without a
+//! shell can be monitored too. Load average, uptime and disk usage are not
+//! reported by the daemon and are left at zero.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use bollard::container::{ListContainersOptions, MemoryStatsStats, Stats, StatsOptions};
+use futures_util::StreamExt;
+use tracing::{debug, warn};
+
+use crate::config::ContainerRuntime;
+use crate::errors::CoreError;
+use crate::monitoring::{
+    MonitoringProvider, MonitoringReceiver, MonitoringSender, StatsCollector, SystemStats,
+};
+use crate::session::docker::LABEL_SESSION_ID;
+
+use super::connect_and_ping;
+
+/// Channel capacity for monitoring stats updates.
+const MONITORING_CHANNEL_CAPACITY: usize = 16;
+
+/// OS description reported for containers.
+const CONTAINER_OS_INFO: &str = "Docker container";
+
+/// Convert a stats sample into [`SystemStats`].
+///
+/// CPU usage is the share of the host's total CPU time used since the
+/// previous sample, so it stays within 0–100 like a host's CPU usage.
+/// Memory excludes the reclaimable inactive page cache, matching
+/// `docker stats`; the total is the container's memory limit.
+pub fn stats_from_docker(stats: &Stats) -> SystemStats {
+    let cpu_delta = stats
+        .cpu_stats
+        .cpu_usage
+        .total_usage
+        .saturating_sub(stats.precpu_stats.cpu_usage.total_usage);
+    let system_delta = stats
+        .cpu_stats
+        .system_cpu_usage
+        .unwrap_or(0)
+        .saturating_sub(stats.precpu_stats.system_cpu_usage.unwrap_or(0));
+    let cpu_usage_percent = if system_delta > 0 {
+        (cpu_delta as f64 / system_delta as f64 * 100.0).clamp(0.0, 100.0)
+    } else {
+        0.0
+    };
+
+    let inactive_file = match stats.memory_stats.stats {
+        Some(MemoryStatsStats::V1(v1)) => v1.total_inactive_file,
+        Some(MemoryStatsStats::V2(v2)) => v2.inactive_file,
+        None => 0,
+    };
+    let usage = stats.memory_stats.usage.unwrap_or(0);
+    let used_kb = usage.saturating_sub(inactive_file) / 1024;
+    let memory_total_kb = stats.memory_stats.limit.unwrap_or(0) / 1024;
+    let memory_used_percent = if memory_total_kb > 0 {
+        used_kb as f64 / memory_total_kb as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    let (network_rx_bytes, network_tx_bytes) = stats
+        .networks
+        .iter()
+        .flat_map(|networks| networks.values())
+        .fold((0, 0), |(rx, tx), net| {
+            (rx + net.rx_bytes, tx + net.tx_bytes)
+        });
+
+    SystemStats {
+        hostname: stats.name.trim_start_matches('/').to_string(),
+        uptime_seconds: 0.0,
+        load_average: [0.0; 3],
+        cpu_usage_percent,
+        memory_total_kb,
+        memory_available_kb: memory_total_kb.saturating_sub(used_kb),
+        memory_used_percent,
+        memory_buffers_kb: 0,
+        memory_cached_kb: inactive_file / 1024,
+        swap_total_kb: 0,
+        swap_used_kb: 0,
+        disk_total_kb: 0,
+        disk_used_kb: 0,
+        disk_used_percent: 0.0,
+        os_info: CONTAINER_OS_INFO.to_string(),
+        network_rx_bytes,
+        network_tx_bytes,
+        process_count: stats.pids_stats.current.unwrap_or(0) as u32,
+    }
+}
+
+/// Collects stats of one container through the Docker API.
+///
+/// [`StatsCollector::collect`] is synchronous, so requests are driven on
+/// the Tokio runtime the collector was created in. Call it from a blocking
+/// context (e.g. `spawn_blocking` or a dedicated thread), never from an
+/// async task.
+pub struct DockerStatsCollector {
+    client: bollard::Docker,
+    container_id: String,
+    runtime: tokio::runtime::Handle,
+}
+
+impl DockerStatsCollector {
+    /// Create a collector for `container_id`. Must be called within a
+    /// Tokio runtime.
+    pub fn new(client: bollard::Docker, container_id: String) -> Self {
+        Self {
+            client,
+            container_id,
+            runtime: tokio::runtime::Handle::current(),
+        }
+    }
+
+    /// Create a collector for the container of a termiHub session, found
+    /// by its session label on the local container runtime.
+    pub async fn for_session(session_id: &str) -> Result<Self, CoreError> {
+        let client = connect_and_ping(&ContainerRuntime::Auto)
+            .await
+            .map_err(|e| CoreError::Other(e.to_string()))?;
+        let filters = HashMap::from([(
+            "label".to_string(),
+            vec![format!("{LABEL_SESSION_ID}={session_id}")],
+        )]);
+        let containers = client
+            .list_containers(Some(ListContainersOptions {
+                filters,
+                ..Default::default()
+            }))
+            .await
+            .map_err(|e| CoreError::Other(format!("Failed to list containers: {e}")))?;
+        let container_id = containers.into_iter().find_map(|c| c.id).ok_or_else(|| {
+            CoreError::Other(format!("No running container for session '{session_id}'"))
+        })?;
+        Ok(Self::new(client, container_id))
+    }
+}
+
+impl StatsCollector for DockerStatsCollector {
+    fn collect(&mut self, _host_label: &str) -> Result<SystemStats, CoreError> {
+        // A single sample that still includes the previous CPU counters,
+        // so the CPU delta covers the daemon's sampling interval.
+        let options = StatsOptions {
+            stream: false,
+            one_shot: false,
+        };
+        let sample = self.runtime.block_on(async {
+            self.client
+                .stats(&self.container_id, Some(options))
+                .next()
+                .await
+        });
+        match sample {
+            Some(Ok(stats)) => Ok(stats_from_docker(&stats)),
+            Some(Err(e)) => Err(CoreError::Other(format!("Container stats failed: {e}"))),
+            None => Err(CoreError::Other("Container stats unavailable".to_string())),
+        }
+    }
+}
+
+/// Background monitoring task state.
+struct MonitoringTask {
+    handle: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for MonitoringTask {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
+
+/// Docker monitoring provider, forwarding the container's live stats
+/// stream from the daemon.
+///
+/// The daemon sends a sample about once a second and closes the stream
+/// when the container stops, which ends the subscription: the receiver
+/// returns `None` once the buffered samples are drained.
+pub(crate) struct DockerMonitoringProvider {
+    client: bollard::Docker,
+    container_id: String,
+    task: Arc<Mutex<Option<MonitoringTask>>>,
+}
+
+impl DockerMonitoringProvider {
+    pub(crate) fn new(client: bollard::Docker, container_id: String) -> Self {
+        Self {
+            client,
+            container_id,
+            task: Arc::new(Mutex::new(None)),
+        }
+    }
+}
+
+/// Forward samples from the stats stream of `container_id` until the
+/// stream ends or the receiver is dropped.
+async fn forward_stats(client: bollard::Docker, container_id: String, tx: MonitoringSender) {
+    let options = StatsOptions {
+        stream: true,
+        one_shot: false,
+    };
+    let mut stream = client.stats(&container_id, Some(options));
+    loop {
+        match stream.next().await {
+            Some(Ok(stats)) => {
+                if tx.send(stats_from_docker(&stats)).await.is_err() {
+                    // Receiver dropped.
+                    break;
+                }
+            }
+            Some(Err(e)) => {
+                warn!("Container stats stream failed: {e}");
+                break;
+            }
+            None => {
+                debug!("Container stats stream ended");
+                break;
+            }
+        }
+    }
+    debug!("Docker monitoring task stopped");
+}
+
+#[async_trait::async_trait]
+impl MonitoringProvider for DockerMonitoringProvider {
+    async fn subscribe(&self) -> Result<MonitoringReceiver, CoreError> {
+        let (tx, rx): (MonitoringSender, MonitoringReceiver) =
+            tokio::sync::mpsc::channel(MONITORING_CHANNEL_CAPACITY);
+        let handle = tokio::spawn(forward_stats(
+            self.client.clone(),
+            self.container_id.clone(),
+            tx,
+        ));
+
+        // Replacing an existing task stops it.
+        if let Ok(mut guard) = self.task.lock() {
+            *guard = Some(MonitoringTask { handle });
+        }
+
+        Ok(rx)
+    }
+
+    async fn unsubscribe(&self) -> Result<(), CoreError> {
+        if let Ok(mut guard) = self.task.lock() {
+            // Drop the task, which aborts it via the Drop impl.
+            *guard = None;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stats sample captured from a cgroup v2 host: 4 of 16 elapsed
+    /// host CPU seconds used, 300 MiB of memory (100 MiB of it inactive
+    /// cache) of a 1 GiB limit.
+    const CGROUP_V2_FIXTURE: &str =
+        include_str!("../../../../tests/fixtures/docker-stats/cgroup-v2.json");
+
+    fn sample() -> Stats {
+        serde_json::from_str(CGROUP_V2_FIXTURE).unwrap()
+    }
+
+    #[test]
+    fn converts_cpu_share_of_host() {
+        let stats = stats_from_docker(&sample());
+        assert!((stats.cpu_usage_percent - 25.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn converts_memory_without_inactive_cache() {
+        let stats = stats_from_docker(&sample());
+        assert_eq!(stats.memory_total_kb, 1024 * 1024);
+        assert_eq!(stats.memory_available_kb, (1024 - 200) * 1024);
+        assert_eq!(stats.memory_cached_kb, 100 * 1024);
+        assert!((stats.memory_used_percent - 200.0 / 1024.0 * 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn sums_network_interfaces_and_counts_processes() {
+        let stats = stats_from_docker(&sample());
+        assert_eq!(stats.network_rx_bytes, 1024);
+        assert_eq!(stats.network_tx_bytes, 512);
+        assert_eq!(stats.process_count, 7);
+    }
+
+    #[test]
+    fn reports_container_name_as_hostname() {
+        let stats = stats_from_docker(&sample());
+        assert_eq!(stats.hostname, "termihub-1-2");
+        assert_eq!(stats.os_info, CONTAINER_OS_INFO);
+    }
+
+    #[test]
+    fn first_sample_without_previous_counters_reports_zero_cpu() {
+        let mut stats = sample();
+        stats.precpu_stats.system_cpu_usage = None;
+        stats.precpu_stats.cpu_usage.total_usage = 0;
+        stats.cpu_stats.system_cpu_usage = None;
+        assert_eq!(stats_from_docker(&stats).cpu_usage_percent, 0.0);
+    }
+}
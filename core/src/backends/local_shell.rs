@@ -13,8 +13,8 @@ use tracing::{debug, info};
 
 use crate::config::ShellConfig;
 use crate::connection::{
-    Capabilities, Condition, ConnectionType, FieldType, FilePathKind, OutputReceiver, OutputSender,
-    SelectOption, SettingsField, SettingsGroup, SettingsSchema,
+    cleanup_policy_field, Capabilities, Condition, ConnectionType, FieldType, FilePathKind,
+    OutputReceiver, OutputSender, SelectOption, SettingsField, SettingsGroup, SettingsSchema,
 };
 use crate::errors::SessionError;
 use crate::files::{FileBrowser, LocalFileBrowser};
@@ -76,6 +76,7 @@ impl LocalShellSpawner for NativeLocalShellSpawner {
             .try_clone_reader()
             .map_err(|e| SessionError::SpawnFailed(e.to_string()))?;
 
+        let process_id = child.process_id();
         let master = Arc::new(Mutex::new(pty_pair.master));
         let child = Arc::new(Mutex::new(child));
 
@@ -102,6 +103,7 @@ impl LocalShellSpawner for NativeLocalShellSpawner {
                     let _ = c.kill();
                 }
             }),
+            process_id,
         })
     }
 }
@@ -114,6 +116,7 @@ struct ConnectedState {
     resize: Box<dyn Fn(u16, u16) -> Result<(), SessionError> + Send + Sync>,
     kill: Box<dyn Fn() + Send + Sync>,
     alive: Arc<AtomicBool>,
+    process_id: Option<u32>,
 }
 
 // ── LocalShell ─────────────────────────────────────────────────────
@@ -297,6 +300,7 @@ impl<S: LocalShellSpawner> ConnectionType for LocalShell<S> {
                         supports_tilde_expansion: false,
                         visible_when: None,
                     },
+                    cleanup_policy_field(),
                 ],
             }],
         }
@@ -454,6 +458,7 @@ impl<S: LocalShellSpawner> ConnectionType for LocalShell<S> {
             resize: spawned.resize,
             kill: spawned.kill,
             alive,
+            process_id: spawned.process_id,
         });
 
         // Inject OSC 7 PROMPT_COMMAND hook for CWD tracking via stdin.
@@ -524,6 +529,14 @@ impl<S: LocalShellSpawner> ConnectionType for LocalShell<S> {
     fn file_browser(&self) -> Option<&dyn FileBrowser> {
         Some(&self.file_backend)
     }
+
+    fn process_id(&self) -> Option<u32> {
+        // Once the shell has exited its PID may be reused by another process.
+        self.state
+            .as_ref()
+            .filter(|s| s.alive.load(Ordering::SeqCst))
+            .and_then(|s| s.process_id)
+    }
 }
 
 // ── Tests ──────────────────────────────────────────────────────────
@@ -582,6 +595,9 @@ mod tests {
         }
     }
 
+    /// Process ID reported by [`MockLocalShellSpawner`].
+    const MOCK_PID: u32 = 4242;
+
     struct MockLocalShellSpawner {
         /// When `true`, `spawn()` returns an error.
         should_fail: bool,
@@ -643,6 +659,7 @@ mod tests {
                     // Drop the sender → ChannelReader.read() returns Ok(0) (EOF)
                     *reader_tx_slot.lock().unwrap() = None;
                 }),
+                process_id: Some(MOCK_PID),
             })
         }
     }
@@ -918,6 +935,35 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn process_id_reported_only_while_connected() {
+        let mut shell = LocalShell::with_spawner(MockLocalShellSpawner::new());
+        assert_eq!(shell.process_id(), None);
+
+        shell.connect(valid_settings()).await.expect("connect");
+        assert_eq!(shell.process_id(), Some(MOCK_PID));
+
+        shell.disconnect().await.expect("disconnect");
+        assert_eq!(shell.process_id(), None);
+    }
+
+    #[tokio::test]
+    async fn process_id_cleared_when_shell_exits() {
+        let spawner = MockLocalShellSpawner::new();
+        let reader_tx = spawner.reader_tx.clone();
+        let mut shell = LocalShell::with_spawner(spawner);
+        shell.connect(valid_settings()).await.expect("connect");
+        assert_eq!(shell.process_id(), Some(MOCK_PID));
+
+        // Closing the PTY output is how the reader thread sees the shell exit.
+        reader_tx.lock().unwrap().take();
+        let deadline = tokio::time::Instant::now() + tokio::time::Duration::from_secs(2);
+        while shell.process_id().is_some() && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(shell.process_id(), None);
+    }
+
     #[tokio::test]
     async fn write_routed_through_mock_writer() {
         let mock = MockLocalShellSpawner::new();
@@ -1153,6 +1199,43 @@ mod tests {
         shell.disconnect().await.ok();
     }
 
+    /// The reported process ID is the shell's own `$$`.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn process_id_matches_live_shell() {
+        let mut shell = LocalShell::new();
+        let settings = serde_json::json!({ "shell": "sh" });
+
+        shell.connect(settings).await.expect("connect failed");
+        let pid = shell.process_id().expect("connected shell has a PID");
+        assert_ne!(pid, 0);
+
+        let mut rx = shell.subscribe_output();
+        shell.write(b"echo \"PID=$$.\"\n").expect("write failed");
+
+        let marker = format!("PID={pid}.");
+        let mut output = Vec::new();
+        let deadline = tokio::time::Duration::from_secs(5);
+        let found = tokio::time::timeout(deadline, async {
+            while let Some(chunk) = rx.recv().await {
+                output.extend_from_slice(&chunk);
+                if String::from_utf8_lossy(&output).contains(&marker) {
+                    return true;
+                }
+            }
+            false
+        })
+        .await;
+        assert!(
+            found.unwrap_or(false),
+            "expected {marker} in output, got: {:?}",
+            String::from_utf8_lossy(&output)
+        );
+
+        shell.disconnect().await.expect("disconnect failed");
+        assert_eq!(shell.process_id(), None);
+    }
+
     /// Old saved connections use `"shellType"` instead of `"shell"`.
     #[tokio::test]
     async fn connect_with_legacy_shell_type_key() {
@@ -6,20 +6,28 @@
 //! `src-tauri/src/terminal/serial.rs`).
 
 use std::io::{Read, Write};
+use std::str::FromStr;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{mpsc, Arc, Mutex};
 
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-use crate::config::SerialConfig;
+use crate::config::{DisplayMode, LineEnding, SerialConfig};
 use crate::connection::{
-    Capabilities, ConnectionType, FieldType, OutputReceiver, OutputSender, SelectOption,
-    SettingsField, SettingsGroup, SettingsSchema,
+    cleanup_policy_field, Capabilities, ConnectionType, FieldType, LineControl, ModemStatus,
+    OutputReceiver, OutputSender, SelectOption, SessionNotice, SettingsField, SettingsGroup,
+    SettingsSchema,
 };
 use crate::errors::SessionError;
 use crate::files::FileBrowser;
 use crate::monitoring::MonitoringProvider;
-use crate::session::serial::parse_serial_config;
+use crate::output::hexdump::{display_mode_options, parse_display_mode, HexDump, IDLE_FLUSH};
+use crate::output::transform::OutputTransform;
+use crate::session::line_ending::{line_ending_options, parse_line_ending, LineEndingTranslator};
+use crate::session::serial::{
+    apply_modem_lines, break_duration, check_baud_rate, modem_line_error, parse_serial_config,
+    read_modem_status, BaudRateCheck, SendPacer, STANDARD_BAUD_RATES,
+};
 
 /// Channel capacity for output data from the serial reader thread.
 const OUTPUT_CHANNEL_CAPACITY: usize = 64;
@@ -44,8 +52,24 @@ pub struct Serial {
 
 /// Internal state of an active serial connection.
 struct ConnectedState {
-    writer: Arc<Mutex<Box<dyn serialport::SerialPort>>>,
+    writer: SharedPort,
+    /// Queue of the paced writer thread, present when send delays are
+    /// configured. Input is paced there rather than in `write()`, whose
+    /// callers may hold locks of their own.
+    paced_input: Option<mpsc::Sender<Vec<u8>>>,
+    /// Rewrites outgoing line breaks; locked while the writer is held.
+    line_ending: Mutex<LineEndingTranslator>,
+    /// Present with `localEcho`: turns typed line breaks into CRLF for the
+    /// echoed copy, so the cursor moves to a new line.
+    echo: Option<Mutex<LineEndingTranslator>>,
+    /// Present in hex display mode; shared with the reader thread so that
+    /// echoed input is dumped in line with the received bytes.
+    hexdump: Option<Arc<HexDump>>,
+    /// Set during a binary file transfer: received bytes bypass the hex dump.
+    raw_output: Arc<AtomicBool>,
     alive: Arc<AtomicBool>,
+    /// Reported once on connect, e.g. when the baud rate was rounded.
+    notices: Vec<SessionNotice>,
 }
 
 impl Serial {
@@ -133,6 +157,82 @@ fn flow_control_options() -> Vec<SelectOption> {
     ]
 }
 
+/// Helper to build modem control line select options.
+fn modem_line_options() -> Vec<SelectOption> {
+    vec![
+        SelectOption {
+            value: "default".to_string(),
+            label: "Driver default".to_string(),
+        },
+        SelectOption {
+            value: "on".to_string(),
+            label: "On".to_string(),
+        },
+        SelectOption {
+            value: "off".to_string(),
+            label: "Off".to_string(),
+        },
+    ]
+}
+
+/// A select field's string value (`"115200"`) parsed as `T`.
+fn parse_field<T: FromStr>(settings: &serde_json::Value, key: &str) -> Option<T> {
+    settings.get(key)?.as_str()?.parse().ok()
+}
+
+/// Parse settings JSON into a `SerialConfig`.
+///
+/// Select fields arrive as strings (`"115200"`); missing or unparsable
+/// values fall back to the defaults. The `dtr` and `rts` lines accept
+/// `"on"`/`"off"` or a boolean; anything else leaves the line alone.
+pub fn parse_serial_settings(settings: &serde_json::Value) -> SerialConfig {
+    let str_field = |key: &str, default: &str| -> String {
+        settings
+            .get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or(default)
+            .to_string()
+    };
+    let delay_ms = |key: &str| -> u32 {
+        settings
+            .get(key)
+            .and_then(|v| v.as_u64())
+            .and_then(|n| u32::try_from(n).ok())
+            .unwrap_or(0)
+    };
+    let line_level = |key: &str| -> Option<bool> {
+        let value = settings.get(key)?;
+        value.as_bool().or_else(|| match value.as_str()? {
+            "on" => Some(true),
+            "off" => Some(false),
+            _ => None,
+        })
+    };
+
+    SerialConfig {
+        port: str_field("port", ""),
+        baud_rate: parse_field(settings, "baudRate").unwrap_or(115200),
+        data_bits: parse_field(settings, "dataBits").unwrap_or(8),
+        stop_bits: parse_field(settings, "stopBits").unwrap_or(1),
+        parity: str_field("parity", "none"),
+        flow_control: str_field("flowControl", "none"),
+        send_byte_delay_ms: delay_ms("sendByteDelayMs"),
+        send_line_delay_ms: delay_ms("sendLineDelayMs"),
+        strict_baud_rate: settings
+            .get("strictBaudRate")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        dtr: line_level("dtr"),
+        rts: line_level("rts"),
+        line_ending: parse_line_ending(settings),
+        local_echo: settings
+            .get("localEcho")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        display_mode: parse_display_mode(settings),
+    }
+}
+
 #[async_trait::async_trait]
 impl ConnectionType for Serial {
     fn type_id(&self) -> &str {
@@ -244,6 +344,154 @@ impl ConnectionType for Serial {
                         supports_tilde_expansion: false,
                         visible_when: None,
                     },
+                    SettingsField {
+                        key: "sendByteDelayMs".to_string(),
+                        label: "Byte Delay (ms)".to_string(),
+                        description: Some(
+                            "Pause between sent characters, for devices that drop input sent too fast"
+                                .to_string(),
+                        ),
+                        help_text: None,
+                        field_type: FieldType::Number {
+                            min: Some(0.0),
+                            max: None,
+                        },
+                        required: false,
+                        default: Some(serde_json::json!(0)),
+                        placeholder: None,
+                        supports_env_expansion: false,
+                        supports_tilde_expansion: false,
+                        visible_when: None,
+                    },
+                    SettingsField {
+                        key: "sendLineDelayMs".to_string(),
+                        label: "Line Delay (ms)".to_string(),
+                        description: Some("Pause after each sent line ending".to_string()),
+                        help_text: None,
+                        field_type: FieldType::Number {
+                            min: Some(0.0),
+                            max: None,
+                        },
+                        required: false,
+                        default: Some(serde_json::json!(0)),
+                        placeholder: None,
+                        supports_env_expansion: false,
+                        supports_tilde_expansion: false,
+                        visible_when: None,
+                    },
+                    SettingsField {
+                        key: "strictBaudRate".to_string(),
+                        label: "Strict Baud Rate".to_string(),
+                        description: Some(
+                            "Fail instead of rounding an unsupported baud rate to the nearest standard rate (rounding is reported when the session starts)"
+                                .to_string(),
+                        ),
+                        help_text: None,
+                        field_type: FieldType::Boolean,
+                        required: false,
+                        default: Some(serde_json::json!(false)),
+                        placeholder: None,
+                        supports_env_expansion: false,
+                        supports_tilde_expansion: false,
+                        visible_when: None,
+                    },
+                    SettingsField {
+                        key: "dtr".to_string(),
+                        label: "DTR on Connect".to_string(),
+                        description: Some(
+                            "Level of the Data Terminal Ready line after opening the port"
+                                .to_string(),
+                        ),
+                        help_text: None,
+                        field_type: FieldType::Select {
+                            options: modem_line_options(),
+                        },
+                        required: false,
+                        default: Some(serde_json::json!("default")),
+                        placeholder: None,
+                        supports_env_expansion: false,
+                        supports_tilde_expansion: false,
+                        visible_when: None,
+                    },
+                    SettingsField {
+                        key: "rts".to_string(),
+                        label: "RTS on Connect".to_string(),
+                        description: Some(
+                            "Level of the Request To Send line after opening the port; hardware flow control drives it itself"
+                                .to_string(),
+                        ),
+                        help_text: None,
+                        field_type: FieldType::Select {
+                            options: modem_line_options(),
+                        },
+                        required: false,
+                        default: Some(serde_json::json!("default")),
+                        placeholder: None,
+                        supports_env_expansion: false,
+                        supports_tilde_expansion: false,
+                        visible_when: None,
+                    },
+                    SettingsField {
+                        key: "lineEnding".to_string(),
+                        label: "Line Ending".to_string(),
+                        description: Some(
+                            "Sequence sent for Enter and for line breaks in pasted text"
+                                .to_string(),
+                        ),
+                        help_text: None,
+                        field_type: FieldType::Select {
+                            options: line_ending_options(),
+                        },
+                        required: false,
+                        default: Some(serde_json::json!("none")),
+                        placeholder: None,
+                        supports_env_expansion: false,
+                        supports_tilde_expansion: false,
+                        visible_when: None,
+                    },
+                    SettingsField {
+                        key: "localEcho".to_string(),
+                        label: "Local Echo".to_string(),
+                        description: Some(
+                            "Show typed characters for devices that do not echo them".to_string(),
+                        ),
+                        help_text: None,
+                        field_type: FieldType::Boolean,
+                        required: false,
+                        default: Some(serde_json::json!(false)),
+                        placeholder: None,
+                        supports_env_expansion: false,
+                        supports_tilde_expansion: false,
+                        visible_when: None,
+                    },
+                    SettingsField {
+                        key: "displayMode".to_string(),
+                        label: "Display Mode".to_string(),
+                        description: Some(
+                            "Show received data as text or as a hex dump".to_string(),
+                        ),
+                        help_text: Some(
+                            concat!(
+                                "Hex dump shows each received byte in the layout of `hexdump -C`: ",
+                                "the byte offset, sixteen hex columns and the printable characters. ",
+                                "Use it to debug binary protocols.\n\n",
+                                "A row is shown once its sixteen bytes have arrived, or when no ",
+                                "more data arrives for half a second. With Local Echo on, the sent ",
+                                "bytes are dumped in line with the received ones."
+                            )
+                            .to_string(),
+                        ),
+                        field_type: FieldType::Select {
+                            options: display_mode_options(),
+                        },
+                        required: false,
+                        default: Some(serde_json::json!("text")),
+                        placeholder: None,
+                        supports_env_expansion: false,
+                        supports_tilde_expansion: false,
+                        visible_when: None,
+                    },
+                    cleanup_policy_field(),
                 ],
             }],
         }
@@ -258,56 +506,39 @@ impl ConnectionType for Serial {
         }
     }
 
+    fn notices(&self) -> Vec<SessionNotice> {
+        self.state
+            .as_ref()
+            .map(|state| state.notices.clone())
+            .unwrap_or_default()
+    }
+
     async fn connect(&mut self, settings: serde_json::Value) -> Result<(), SessionError> {
         if self.state.is_some() {
             return Err(SessionError::AlreadyExists("Already connected".to_string()));
         }
 
-        // Parse settings JSON into SerialConfig.
-        let port = settings
-            .get("port")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let baud_rate: u32 = settings
-            .get("baudRate")
-            .and_then(|v| v.as_str())
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(115200);
-        let data_bits: u8 = settings
-            .get("dataBits")
-            .and_then(|v| v.as_str())
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(8);
-        let stop_bits: u8 = settings
-            .get("stopBits")
-            .and_then(|v| v.as_str())
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(1);
-        let parity = settings
-            .get("parity")
-            .and_then(|v| v.as_str())
-            .unwrap_or("none")
-            .to_string();
-        let flow_control = settings
-            .get("flowControl")
-            .and_then(|v| v.as_str())
-            .unwrap_or("none")
-            .to_string();
-
-        let config = SerialConfig {
-            port,
-            baud_rate,
-            data_bits,
-            stop_bits,
-            parity,
-            flow_control,
-        };
+        let config = parse_serial_settings(&settings);
 
         // Expand ${env:VAR} placeholders in port name.
         let config = config.expand();
 
-        let parsed = parse_serial_config(&config)?;
+        let mut parsed = parse_serial_config(&config)?;
+
+        // serialport cannot query the rates a device supports, so check
+        // against the standard rates the drivers accept.
+        let baud = check_baud_rate(
+            parsed.baud_rate,
+            STANDARD_BAUD_RATES,
+            config.strict_baud_rate,
+        )?;
+        if let BaudRateCheck::Rounded { requested, rate } = baud {
+            warn!(
+                requested,
+                rate, "Unsupported baud rate, using the nearest standard rate"
+            );
+        }
+        parsed.baud_rate = baud.rate();
 
         info!(
             port = %parsed.port,
@@ -316,7 +547,10 @@ impl ConnectionType for Serial {
         );
 
         // Open the serial port.
-        let port_handle = crate::session::serial::open_serial_port(&parsed)?;
+        let mut port_handle = crate::session::serial::open_serial_port(&parsed)?;
+        if let Err(e) = apply_modem_lines(&mut *port_handle, &config) {
+            warn!(port = %parsed.port, "{e}");
+        }
 
         // Clone for the reader thread.
         let mut reader = port_handle
@@ -337,18 +571,30 @@ impl ConnectionType for Serial {
 
         // Spawn reader thread: bridges sync serial reads to async tokio channel.
         let alive_clone = alive.clone();
+        let raw_output = Arc::new(AtomicBool::new(false));
+        let raw_output_clone = raw_output.clone();
         let output_tx_clone = self.output_tx.clone();
+        let hexdump = (config.display_mode == DisplayMode::Hex).then(|| Arc::new(HexDump::new()));
+        let hexdump_clone = hexdump.clone();
         std::thread::spawn(move || {
+            let hexdump = hexdump_clone;
             let mut buf = [0u8; 1024];
             loop {
                 match reader.read(&mut buf) {
                     Ok(0) => break,
                     Ok(n) => {
-                        let data = buf[..n].to_vec();
+                        let data = match hexdump {
+                            Some(ref hexdump) if !raw_output_clone.load(Ordering::SeqCst) => {
+                                hexdump.apply(&buf[..n])
+                            }
+                            _ => buf[..n].to_vec(),
+                        };
                         let guard = output_tx_clone.lock().ok();
                         if let Some(ref guard) = guard {
                             if let Some(ref sender) = **guard {
-                                let _ = sender.blocking_send(data);
+                                if !data.is_empty() {
+                                    let _ = sender.blocking_send(data);
+                                }
                             } else {
                                 // No sender — disconnected.
                                 break;
@@ -357,16 +603,56 @@ impl ConnectionType for Serial {
                             break;
                         }
                     }
-                    Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => continue,
+                    Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => {
+                        // Show an incomplete hexdump row once the line goes quiet.
+                        let rest = hexdump.as_ref().map(|h| h.flush_idle(IDLE_FLUSH));
+                        if let Some(rest) = rest.filter(|r| !r.is_empty()) {
+                            match output_tx_clone.lock() {
+                                Ok(guard) => match guard.as_ref() {
+                                    Some(sender) => {
+                                        let _ = sender.blocking_send(rest);
+                                    }
+                                    None => break,
+                                },
+                                Err(_) => break,
+                            }
+                        }
+                    }
                     Err(_) => break,
                 }
             }
+            // Show the last, incomplete hexdump row when the port closes.
+            if let Some(rest) = hexdump.map(|h| h.flush()).filter(|r| !r.is_empty()) {
+                if let Ok(guard) = output_tx_clone.lock() {
+                    if let Some(ref sender) = *guard {
+                        let _ = sender.blocking_send(rest);
+                    }
+                }
+            }
             alive_clone.store(false, Ordering::SeqCst);
         });
 
+        let writer = Arc::new(Mutex::new(port_handle));
+        let paced_input = if config.send_byte_delay_ms > 0 || config.send_line_delay_ms > 0 {
+            Some(spawn_paced_writer(
+                PortWriter(writer.clone()),
+                SendPacer::from_config(&config),
+            )?)
+        } else {
+            None
+        };
+
         self.state = Some(ConnectedState {
-            writer: Arc::new(Mutex::new(port_handle)),
+            writer,
+            paced_input,
+            line_ending: Mutex::new(LineEndingTranslator::new(config.line_ending)),
+            echo: config
+                .local_echo
+                .then(|| Mutex::new(LineEndingTranslator::new(LineEnding::Crlf))),
+            hexdump,
+            raw_output,
             alive,
+            notices: baud.notice().into_iter().collect(),
         });
 
         Ok(())
@@ -395,11 +681,56 @@ impl ConnectionType for Serial {
             .state
             .as_ref()
             .ok_or_else(|| SessionError::NotRunning("Not connected".to_string()))?;
-        let mut writer = state.writer.lock().map_err(|e| {
-            SessionError::Io(std::io::Error::other(format!("Failed to lock writer: {e}")))
+        let mut line_ending = state.line_ending.lock().map_err(|e| {
+            SessionError::Io(std::io::Error::other(format!(
+                "Failed to lock line ending: {e}"
+            )))
         })?;
+        let data_out = line_ending.translate(data);
+        match &state.paced_input {
+            Some(paced_input) => paced_input
+                .send(data_out.to_vec())
+                .map_err(|_| SessionError::NotRunning("Serial writer stopped".to_string()))?,
+            None => {
+                let mut writer = lock_port(&state.writer)?;
+                writer.write_all(&data_out).map_err(SessionError::Io)?;
+                writer.flush().map_err(SessionError::Io)?;
+            }
+        }
+
+        if let Some(echo) = &state.echo {
+            // In hex mode the bytes as sent join the dump; otherwise the
+            // typed text is echoed with line breaks the terminal follows.
+            let echoed = match &state.hexdump {
+                Some(hexdump) => Some(hexdump.apply(&data_out)),
+                None => echo.lock().ok().map(|mut e| e.translate(data).into_owned()),
+            };
+            // Dropped rather than blocking the writer when output backs up.
+            if let (Some(echoed), Ok(guard)) = (echoed, self.output_tx.lock()) {
+                if let Some(sender) = guard.as_ref().filter(|_| !echoed.is_empty()) {
+                    let _ = sender.try_send(echoed);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write_binary(&self, data: &[u8]) -> Result<(), SessionError> {
+        let state = self
+            .state
+            .as_ref()
+            .ok_or_else(|| SessionError::NotRunning("Not connected".to_string()))?;
+        let mut writer = lock_port(&state.writer)?;
         writer.write_all(data).map_err(SessionError::Io)?;
-        writer.flush().map_err(SessionError::Io)?;
+        writer.flush().map_err(SessionError::Io)
+    }
+
+    fn set_binary_transfer(&self, active: bool) -> Result<(), SessionError> {
+        let state = self
+            .state
+            .as_ref()
+            .ok_or_else(|| SessionError::NotRunning("Not connected".to_string()))?;
+        state.raw_output.store(active, Ordering::SeqCst);
         Ok(())
     }
 
@@ -423,6 +754,124 @@ impl ConnectionType for Serial {
     fn file_browser(&self) -> Option<&dyn FileBrowser> {
         None
     }
+
+    fn line_control(&self, control: LineControl) -> Result<(), SessionError> {
+        let state = self
+            .state
+            .as_ref()
+            .ok_or_else(|| SessionError::NotRunning("Not connected".to_string()))?;
+        match control {
+            LineControl::Break { duration_ms } => {
+                send_break(&state.writer, break_duration(duration_ms)?)
+            }
+            LineControl::SetDtr { level } => lock_port(&state.writer)?
+                .write_data_terminal_ready(level)
+                .map_err(|e| modem_line_error("DTR", e)),
+            LineControl::SetRts { level } => lock_port(&state.writer)?
+                .write_request_to_send(level)
+                .map_err(|e| modem_line_error("RTS", e)),
+        }
+    }
+
+    fn modem_status(&self) -> Result<ModemStatus, SessionError> {
+        let state = self
+            .state
+            .as_ref()
+            .ok_or_else(|| SessionError::NotRunning("Not connected".to_string()))?;
+        read_modem_status(&mut **lock_port(&state.writer)?)
+    }
+}
+
+type SharedPort = Arc<Mutex<Box<dyn serialport::SerialPort>>>;
+
+fn lock_port(
+    port: &SharedPort,
+) -> Result<std::sync::MutexGuard<'_, Box<dyn serialport::SerialPort>>, SessionError> {
+    port.lock()
+        .map_err(|e| SessionError::Io(std::io::Error::other(format!("Failed to lock writer: {e}"))))
+}
+
+/// Writes to a shared port, holding its lock for one call at a time so
+/// line controls and binary writes can run between paced bytes.
+struct PortWriter(SharedPort);
+
+impl Write for PortWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.lock()?.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.lock()?.flush()
+    }
+}
+
+impl PortWriter {
+    fn lock(&self) -> std::io::Result<std::sync::MutexGuard<'_, Box<dyn serialport::SerialPort>>> {
+        self.0
+            .lock()
+            .map_err(|e| std::io::Error::other(format!("Failed to lock writer: {e}")))
+    }
+}
+
+/// Start the thread that writes queued input to `writer` through `pacer`,
+/// returning the queue. The thread ends when the queue's sender is
+/// dropped on disconnect, or on the first write error.
+fn spawn_paced_writer<W: Write + Send + 'static>(
+    mut writer: W,
+    mut pacer: SendPacer,
+) -> Result<mpsc::Sender<Vec<u8>>, SessionError> {
+    let (tx, rx) = mpsc::channel::<Vec<u8>>();
+    std::thread::Builder::new()
+        .name("serial-paced-writer".to_string())
+        .spawn(move || {
+            for data in rx {
+                if let Err(e) = pacer.write(&mut writer, &data) {
+                    warn!("Serial write failed: {e}");
+                    break;
+                }
+            }
+        })
+        .map_err(|e| SessionError::SpawnFailed(format!("Failed to spawn writer thread: {e}")))?;
+    Ok(tx)
+}
+
+/// Hold the line in the break condition for `duration` on a background
+/// thread. Returns once the break has started, or failed to.
+///
+/// The thread holds the port for the whole break, so writes made in the
+/// meantime wait and are sent after it instead of being lost in it.
+fn send_break(port: &SharedPort, duration: std::time::Duration) -> Result<(), SessionError> {
+    let port = port.clone();
+    let (started_tx, started_rx) = mpsc::channel();
+    std::thread::Builder::new()
+        .name("serial-break".to_string())
+        .spawn(move || {
+            let Ok(port) = port.lock() else {
+                let _ = started_tx.send(Err("Failed to lock writer".to_string()));
+                return;
+            };
+            if let Err(e) = port.set_break() {
+                let _ = started_tx.send(Err(format!("Failed to send break: {e}")));
+                return;
+            }
+            let _ = started_tx.send(Ok(()));
+            std::thread::sleep(duration);
+            if let Err(e) = port.clear_break() {
+                warn!("Failed to clear break: {e}");
+            }
+            debug!(
+                duration_ms = duration.as_millis() as u64,
+                "Serial break sent"
+            );
+        })
+        .map_err(|e| SessionError::SpawnFailed(format!("Failed to spawn break thread: {e}")))?;
+    match started_rx.recv() {
+        Ok(Ok(())) => Ok(()),
+        Ok(Err(msg)) => Err(SessionError::Io(std::io::Error::other(msg))),
+        Err(_) => Err(SessionError::Io(std::io::Error::other(
+            "Break thread exited unexpectedly",
+        ))),
+    }
 }
 
 #[cfg(test)]
@@ -471,7 +920,16 @@ mod tests {
         assert!(keys.contains(&"stopBits"));
         assert!(keys.contains(&"parity"));
         assert!(keys.contains(&"flowControl"));
-        assert_eq!(keys.len(), 6);
+        assert!(keys.contains(&"sendByteDelayMs"));
+        assert!(keys.contains(&"sendLineDelayMs"));
+        assert!(keys.contains(&"strictBaudRate"));
+        assert!(keys.contains(&"dtr"));
+        assert!(keys.contains(&"rts"));
+        assert!(keys.contains(&"lineEnding"));
+        assert!(keys.contains(&"localEcho"));
+        assert!(keys.contains(&"displayMode"));
+        assert!(keys.contains(&"cleanupPolicy"));
+        assert_eq!(keys.len(), 15);
     }
 
     #[test]
@@ -561,6 +1019,104 @@ mod tests {
         assert_eq!(field.default, Some(serde_json::json!("none")));
     }
 
+    #[test]
+    fn schema_modem_lines_default_to_driver() {
+        let serial = Serial::new();
+        let schema = serial.settings_schema();
+        for key in ["dtr", "rts"] {
+            let field = schema.groups[0]
+                .fields
+                .iter()
+                .find(|f| f.key == key)
+                .unwrap();
+            if let FieldType::Select { options } = &field.field_type {
+                let values: Vec<&str> = options.iter().map(|o| o.value.as_str()).collect();
+                assert_eq!(values, vec!["default", "on", "off"]);
+            } else {
+                panic!("expected Select field type for {key}");
+            }
+            assert_eq!(field.default, Some(serde_json::json!("default")));
+        }
+    }
+
+    #[test]
+    fn parse_settings_defaults() {
+        let config = parse_serial_settings(&serde_json::json!({"port": "/dev/ttyUSB0"}));
+        assert_eq!(config.port, "/dev/ttyUSB0");
+        assert_eq!(config.baud_rate, 115200);
+        assert_eq!(config.data_bits, 8);
+        assert_eq!(config.stop_bits, 1);
+        assert_eq!(config.parity, "none");
+        assert_eq!(config.flow_control, "none");
+        assert!(!config.strict_baud_rate);
+        assert_eq!(config.dtr, None);
+        assert_eq!(config.rts, None);
+        assert_eq!(config.line_ending, LineEnding::None);
+        assert!(!config.local_echo);
+        assert_eq!(config.display_mode, DisplayMode::Text);
+    }
+
+    #[test]
+    fn parse_settings_full() {
+        let config = parse_serial_settings(&serde_json::json!({
+            "port": "COM3",
+            "baudRate": "9600",
+            "dataBits": "7",
+            "stopBits": "2",
+            "parity": "even",
+            "flowControl": "hardware",
+            "sendByteDelayMs": 2,
+            "sendLineDelayMs": 50,
+            "strictBaudRate": true,
+            "dtr": "off",
+            "rts": "on",
+            "lineEnding": "cr",
+            "localEcho": true,
+            "displayMode": "hex",
+        }));
+        assert_eq!(config.baud_rate, 9600);
+        assert_eq!(config.data_bits, 7);
+        assert_eq!(config.stop_bits, 2);
+        assert_eq!(config.parity, "even");
+        assert_eq!(config.flow_control, "hardware");
+        assert_eq!(config.send_byte_delay_ms, 2);
+        assert_eq!(config.send_line_delay_ms, 50);
+        assert!(config.strict_baud_rate);
+        assert_eq!(config.dtr, Some(false));
+        assert_eq!(config.rts, Some(true));
+        assert_eq!(config.line_ending, LineEnding::Cr);
+        assert!(config.local_echo);
+        assert_eq!(config.display_mode, DisplayMode::Hex);
+    }
+
+    #[test]
+    fn parse_settings_modem_lines() {
+        let parse = |value: serde_json::Value| {
+            let config = parse_serial_settings(&serde_json::json!({"dtr": value}));
+            config.dtr
+        };
+        assert_eq!(parse(serde_json::json!(true)), Some(true));
+        assert_eq!(parse(serde_json::json!(false)), Some(false));
+        assert_eq!(parse(serde_json::json!("default")), None);
+        assert_eq!(parse(serde_json::json!("high")), None);
+        assert_eq!(parse(serde_json::json!(null)), None);
+    }
+
+    #[test]
+    fn line_control_when_disconnected_errors() {
+        let serial = Serial::new();
+        let err = serial
+            .line_control(LineControl::SetDtr { level: true })
+            .unwrap_err();
+        assert!(matches!(err, SessionError::NotRunning(_)), "{err}");
+    }
+
+    #[test]
+    fn modem_status_when_disconnected_errors() {
+        let err = Serial::new().modem_status().unwrap_err();
+        assert!(matches!(err, SessionError::NotRunning(_)), "{err}");
+    }
+
     #[test]
     fn write_when_disconnected_errors() {
         let serial = Serial::new();
@@ -568,6 +1124,13 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn binary_transfer_when_disconnected_errors() {
+        let serial = Serial::new();
+        assert!(serial.write_binary(&[0x01, 0xFF]).is_err());
+        assert!(serial.set_binary_transfer(true).is_err());
+    }
+
     #[test]
     fn resize_when_disconnected_is_ok() {
         let serial = Serial::new();
@@ -613,6 +1176,48 @@ mod tests {
         assert!(!serial.is_connected());
     }
 
+    /// A writer recording everything written to it.
+    #[derive(Clone, Default)]
+    struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuf {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn paced_writer_queues_without_waiting_for_the_delays() {
+        let config = SerialConfig {
+            send_byte_delay_ms: 20,
+            ..Default::default()
+        };
+        let buf = SharedBuf::default();
+        let tx = spawn_paced_writer(buf.clone(), SendPacer::from_config(&config)).unwrap();
+
+        let started = std::time::Instant::now();
+        tx.send(b"hello".to_vec()).unwrap();
+        tx.send(b" world".to_vec()).unwrap();
+        assert!(started.elapsed() < std::time::Duration::from_millis(20));
+
+        let deadline = started + std::time::Duration::from_secs(5);
+        while buf.0.lock().unwrap().len() < 11 {
+            assert!(
+                std::time::Instant::now() < deadline,
+                "paced input not written"
+            );
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        assert_eq!(buf.0.lock().unwrap().as_slice(), b"hello world");
+        // Ten gaps of 20 ms between the eleven bytes.
+        assert!(started.elapsed() >= std::time::Duration::from_millis(200));
+    }
+
     // -----------------------------------------------------------------------
     // Integration tests (no real hardware required)
     // -----------------------------------------------------------------------
@@ -647,6 +1252,21 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn connect_strict_rejects_unsupported_baud_rate() {
+        let mut serial = Serial::new();
+        let settings = serde_json::json!({
+            "port": "/dev/__nonexistent_serial_port__",
+            "baudRate": "9500",
+            "strictBaudRate": true,
+        });
+        let err = serial.connect(settings).await.unwrap_err();
+        assert!(
+            matches!(err, SessionError::InvalidConfig(ref msg) if msg.contains("9500")),
+            "unexpected error: {err}"
+        );
+    }
+
     #[tokio::test]
     async fn disconnect_when_not_connected_is_noop() {
         let mut serial = Serial::new();
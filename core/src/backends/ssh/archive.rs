@@ -0,0 +1,157 @@
+//! Directory archive downloads over an SSH exec channel.
+//!
+//! The remote side packs the directory with `tar` or `zip` writing to
+//! stdout, and the output is streamed straight into the local file, so
+//! no temporary archive is created on the server.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::config::SshConfig;
+use crate::errors::FileError;
+use crate::files::archive::{archive_command, archive_command_error, discard_partial};
+use crate::files::{ArchiveDownloader, ArchiveFormat};
+
+use super::auth::connect_for_transfer;
+use super::exec::{drain, POLL_INTERVAL};
+
+fn exec_error(e: ssh2::Error) -> FileError {
+    FileError::OperationFailed(format!("Archive exec failed: {e}"))
+}
+
+/// Archive the remote directory `path` and write it to `local`.
+///
+/// Runs on `session` in blocking mode and returns the number of archive
+/// bytes written. Calls `on_progress` with the running byte count after
+/// every chunk.
+pub fn download_archive(
+    session: &ssh2::Session,
+    path: &str,
+    format: ArchiveFormat,
+    local: &Path,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(u64),
+) -> Result<u64, FileError> {
+    let command = archive_command(path, format)?;
+    let mut channel = session.channel_session().map_err(exec_error)?;
+    channel.exec(&command).map_err(exec_error)?;
+
+    let mut file = File::create(local)?;
+    let result = stream_channel(session, &mut channel, &mut file, cancel, on_progress);
+    session.set_blocking(true);
+    let (written, stderr) = match result {
+        Ok(streamed) => streamed,
+        Err(e) => {
+            let _ = channel.close();
+            drop(file);
+            discard_partial(local);
+            return Err(e);
+        }
+    };
+
+    channel.wait_close().map_err(exec_error)?;
+    let exit_code = channel.exit_status().map_err(exec_error)?;
+    if exit_code != 0 {
+        drop(file);
+        discard_partial(local);
+        return Err(archive_command_error(exit_code.into(), &stderr));
+    }
+    Ok(written)
+}
+
+/// Copy the channel's stdout into `file` until EOF, collecting stderr.
+///
+/// Both streams are read without blocking so a chatty stderr cannot stall
+/// the archive stream. Returns the bytes written and the captured stderr.
+fn stream_channel(
+    session: &ssh2::Session,
+    channel: &mut ssh2::Channel,
+    file: &mut File,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(u64),
+) -> Result<(u64, String), FileError> {
+    session.set_blocking(false);
+    let mut chunk = Vec::new();
+    let mut stderr = Vec::new();
+    let mut written = 0u64;
+    on_progress(written);
+    loop {
+        if cancel.is_cancelled() {
+            return Err(FileError::OperationFailed("Transfer cancelled".to_string()));
+        }
+        let read_out = drain(channel, &mut chunk)?;
+        let read_err = drain(&mut channel.stderr(), &mut stderr)?;
+        if !chunk.is_empty() {
+            file.write_all(&chunk)?;
+            written += chunk.len() as u64;
+            chunk.clear();
+            on_progress(written);
+        }
+        if read_out || read_err {
+            continue;
+        }
+        if channel.eof() {
+            break;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    file.flush()?;
+    Ok((written, String::from_utf8_lossy(&stderr).into_owned()))
+}
+
+/// [`ArchiveDownloader`] that opens its own SSH session per download.
+pub(crate) struct SshArchiveDownloader {
+    config: SshConfig,
+}
+
+impl SshArchiveDownloader {
+    pub(crate) fn new(config: SshConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl ArchiveDownloader for SshArchiveDownloader {
+    async fn download(
+        &self,
+        path: &str,
+        format: ArchiveFormat,
+        local_path: &Path,
+        cancel: &CancellationToken,
+        on_progress: &mut (dyn FnMut(u64) + Send),
+    ) -> Result<u64, FileError> {
+        let config = self.config.clone();
+        let path = path.to_string();
+        let local_path: PathBuf = local_path.to_path_buf();
+        let cancel = cancel.clone();
+        let (progress_tx, mut progress_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let task = tokio::task::spawn_blocking(move || {
+            let session = connect_for_transfer(&config)
+                .map_err(|e| FileError::OperationFailed(format!("SSH connection failed: {e}")))?;
+            session.set_blocking(true);
+            let result = download_archive(
+                &session,
+                &path,
+                format,
+                &local_path,
+                &cancel,
+                &mut |written| {
+                    let _ = progress_tx.send(written);
+                },
+            );
+            let _ = session.disconnect(None, "Archive download finished", None);
+            result
+        });
+
+        // The sender is dropped when the blocking task ends, closing the channel.
+        while let Some(written) = progress_rx.recv().await {
+            on_progress(written);
+        }
+        task.await
+            .map_err(|e| FileError::OperationFailed(format!("Task join failed: {e}")))?
+    }
+}
@@ -14,21 +14,47 @@
 //! Inject any `Box<dyn SshConnector>` implementation that returns in-memory
 //! pipes. [`MockSshConnector`] (in `#[cfg(test)]`) provides this.
 
-use std::io::Read;
+use std::io::{Read, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use crate::config::SshConfig;
+use crate::connection::HostKeyFingerprint;
 use crate::errors::SessionError;
 
+use super::exec::CommandOutput;
+use super::forward::SessionForwardStatus;
+use super::host_key::host_key_fingerprint;
+use super::keepalive::{KeepaliveResponder, Ssh2KeepaliveResponder};
+use super::nonblocking::{self, REQUEST_TIMEOUT, WRITE_STALL_TIMEOUT};
+
 // ── Type aliases for complex closure types ─────────────────────────
 
 type WriteFn = Arc<dyn Fn(&[u8]) -> Result<(), SessionError> + Send + Sync>;
 type ResizeFn = Arc<dyn Fn(u16, u16) -> Result<(), SessionError> + Send + Sync>;
-type SetBlockingFn = Arc<dyn Fn(bool) + Send + Sync>;
 type IoFn = Arc<dyn Fn() -> Result<(), SessionError> + Send + Sync>;
 
+/// Runs a command on a new exec channel of the shell's session.
+pub type ExecFn = Arc<dyn Fn(&str) -> Result<CommandOutput, SessionError> + Send + Sync>;
+
+/// Checks that the SFTP subsystem answers on the shell's session.
+pub type SftpProbeFn = Arc<dyn Fn() -> Result<(), SessionError> + Send + Sync>;
+
+/// Opaque guard that keeps session-bundled forwards running until dropped.
+pub type ForwardGuard = Box<dyn std::any::Any + Send>;
+
+/// Opens the local port forwards listed in `config.session_forwards` as
+/// channels of the shell's session, returning a guard that keeps them
+/// running until dropped and the status of each configured forward.
+pub type OpenForwardsFn = Box<
+    dyn FnOnce(
+            &SshConfig,
+            Arc<AtomicBool>,
+        ) -> Result<(ForwardGuard, Vec<SessionForwardStatus>), SessionError>
+        + Send,
+>;
+
 // ── SshShellHandle ─────────────────────────────────────────────────
 
 /// Handles for an established SSH shell session.
@@ -40,18 +66,29 @@ pub struct SshShellHandle {
     /// when no data is available; the [`Ssh2SshShellReader`] wrapper
     /// handles the retry loop.
     pub reader: Box<dyn Read + Send>,
-    /// Writes input data to the channel (handles blocking-mode toggle internally).
+    /// Writes input data to the channel.
     pub write: WriteFn,
     /// Resizes the PTY to `(cols, rows)`.
     pub resize: ResizeFn,
-    /// Switches the underlying session's blocking mode.
-    pub set_blocking: SetBlockingFn,
+    /// Runs commands on independent exec channels of the same session,
+    /// e.g. for monitoring. `None` makes monitoring open its own session.
+    pub exec: Option<ExecFn>,
+    /// Checks the SFTP subsystem when the login shell is restricted.
+    /// `None` takes SFTP as unavailable then.
+    pub probe_sftp: Option<SftpProbeFn>,
+    /// Opens the session-bundled port forwards on the same session.
+    /// `None` reports every configured forward as failed.
+    pub open_forwards: Option<OpenForwardsFn>,
     /// Sends EOF on the channel.
     pub send_eof: IoFn,
     /// Closes the channel.
     pub close: IoFn,
     /// Opaque extensions kept alive for the session lifetime (e.g. X11Forwarder).
     pub extensions: Vec<Box<dyn std::any::Any + Send>>,
+    /// Probes the server for keepalive replies; `None` disables keepalives.
+    pub keepalive: Option<Box<dyn KeepaliveResponder>>,
+    /// Fingerprint of the host key the server presented.
+    pub host_key: Option<HostKeyFingerprint>,
 }
 
 // ── SshConnector trait ─────────────────────────────────────────────
@@ -119,6 +156,29 @@ impl Read for Ssh2SshShellReader {
     }
 }
 
+/// Writer over the shared shell channel that holds the channel lock for a
+/// single write or flush only, so the reader keeps draining output while a
+/// long input write waits for the remote window to open.
+struct SharedChannelWriter<'a>(&'a Mutex<ssh2::Channel>);
+
+impl SharedChannelWriter<'_> {
+    fn lock(&self) -> std::io::Result<std::sync::MutexGuard<'_, ssh2::Channel>> {
+        self.0
+            .lock()
+            .map_err(|e| std::io::Error::other(format!("channel lock: {e}")))
+    }
+}
+
+impl Write for SharedChannelWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.lock()?.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.lock()?.flush()
+    }
+}
+
 // ── Ssh2SshConnector (production) ─────────────────────────────────
 
 /// Production SSH connector using libssh2.
@@ -138,6 +198,14 @@ impl SshConnector for Ssh2SshConnector {
         use super::x11::X11Forwarder;
 
         let session = Arc::new(connect_and_authenticate(config)?);
+        let host_key = host_key_fingerprint(&session);
+        if let Some(ref key) = host_key {
+            tracing::info!(
+                key_type = %key.key_type,
+                fingerprint = %key.fingerprint,
+                "SSH host key"
+            );
+        }
 
         // Optional X11 forwarding must be set up before the shell channel.
         let mut extensions: Vec<Box<dyn std::any::Any + Send>> = Vec::new();
@@ -174,6 +242,15 @@ impl SshConnector for Ssh2SshConnector {
             let _ = channel.setenv(key, value);
         }
 
+        // Locale variables the server refused (no matching `AcceptEnv`)
+        // are exported in the shell instead.
+        let locale_exports: Vec<String> = config
+            .locale_env()
+            .into_iter()
+            .filter(|(key, value)| channel.setenv(key, value).is_err())
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+
         channel
             .request_pty(
                 "xterm-256color",
@@ -182,12 +259,26 @@ impl SshConnector for Ssh2SshConnector {
             )
             .map_err(|e| SessionError::SpawnFailed(format!("PTY request failed: {e}")))?;
 
-        channel
-            .shell()
-            .map_err(|e| SessionError::SpawnFailed(format!("Shell request failed: {e}")))?;
+        // With a sudo prefix, the first thing on the terminal may be sudo's
+        // password prompt, so nothing is typed into the shell for the user.
+        let elevated_shell = super::sudo::shell_command(config.sudo_prefix.as_deref());
+        let elevated = elevated_shell.is_some();
+        match elevated_shell {
+            Some(command) => channel.exec(&command),
+            None => channel.shell(),
+        }
+        .map_err(|e| SessionError::SpawnFailed(format!("Shell request failed: {e}")))?;
+
+        // The locale is validated, so it needs no quoting.
+        if !elevated && !locale_exports.is_empty() {
+            let _ = std::io::Write::write_all(
+                &mut channel,
+                format!("export {}\n", locale_exports.join(" ")).as_bytes(),
+            );
+        }
 
         // Inject DISPLAY/xauth if setenv failed (most servers reject it).
-        if let Some(display_num) = x11_display {
+        if let Some(display_num) = x11_display.filter(|_| !elevated) {
             if !display_set_via_env {
                 let _ = std::io::Write::write_all(
                     &mut channel,
@@ -205,22 +296,27 @@ impl SshConnector for Ssh2SshConnector {
             }
         }
 
-        // Switch to non-blocking for the reader thread.
+        // The session stays non-blocking from here on; every operation
+        // waits on its own (see `nonblocking`) so that the reader, input,
+        // resizes and exec channels never change the mode under each other.
         session.set_blocking(false);
 
         let channel = Arc::new(Mutex::new(channel));
+        let session_for_exec = session.clone();
+        let session_for_forwards = session.clone();
+        let session_for_sftp = session.clone();
         let session_for_write = session.clone();
-        let session_for_resize = session.clone();
-        let session_for_blocking = session.clone();
         let channel_for_write = channel.clone();
         let channel_for_resize = channel.clone();
         let channel_for_eof = channel.clone();
         let channel_for_close = channel.clone();
-        let session_for_close = session.clone();
-        // Clone alive for the write closure so a write failure (e.g. TCP write
-        // timeout on a dead connection) signals the reader thread to exit, which
-        // then drops the output sender and triggers terminal-exit.
+        // Clone alive for the write closure so a write failure on a dead
+        // connection signals the reader thread to exit, which then drops the
+        // output sender and triggers terminal-exit.
         let alive_for_write = alive.clone();
+        // Keeps concurrent writes from interleaving now that a write only
+        // holds the channel lock for one libssh2 call at a time.
+        let write_order = Mutex::new(());
 
         Ok(SshShellHandle {
             reader: Box::new(Ssh2SshShellReader::new(channel.clone(), alive)),
@@ -228,52 +324,68 @@ impl SshConnector for Ssh2SshConnector {
                 if !alive_for_write.load(Ordering::SeqCst) {
                     return Err(SessionError::Io(std::io::Error::other("session dead")));
                 }
-                let mut ch = channel_for_write
+                let _order = write_order
                     .lock()
                     .map_err(|e| SessionError::Io(std::io::Error::other(format!("lock: {e}"))))?;
-                session_for_write.set_blocking(true);
-                let result = std::io::Write::write_all(&mut *ch, data);
-                session_for_write.set_blocking(false);
-                drop(ch);
-                if result.is_err() {
+                // A stalled write (e.g. a large paste into a program whose
+                // output is paused) keeps waiting for as long as the server
+                // still answers, so no part of the input is dropped.
+                nonblocking::write_all(
+                    &mut SharedChannelWriter(&channel_for_write),
+                    data,
+                    WRITE_STALL_TIMEOUT,
+                    || {
+                        alive_for_write.load(Ordering::SeqCst)
+                            && nonblocking::remote_answers(&session_for_write, REQUEST_TIMEOUT)
+                    },
+                )
+                .map_err(|e| {
                     alive_for_write.store(false, Ordering::SeqCst);
-                }
-                result.map_err(SessionError::Io)
+                    SessionError::Io(e)
+                })
             }),
             resize: Arc::new(move |cols: u16, rows: u16| {
                 let mut ch = channel_for_resize
                     .lock()
                     .map_err(|e| SessionError::Io(std::io::Error::other(format!("lock: {e}"))))?;
-                session_for_resize.set_blocking(true);
-                let result = ch.request_pty_size(cols as u32, rows as u32, None, None);
-                session_for_resize.set_blocking(false);
+                let result = nonblocking::retry(REQUEST_TIMEOUT, || {
+                    ch.request_pty_size(cols as u32, rows as u32, None, None)
+                });
                 drop(ch);
                 result.map_err(|e| {
                     SessionError::Io(std::io::Error::other(format!("PTY resize failed: {e}")))
                 })
             }),
-            set_blocking: Arc::new(move |blocking: bool| {
-                session_for_blocking.set_blocking(blocking);
-            }),
+            exec: Some(Arc::new(move |command: &str| {
+                nonblocking::exec(&session_for_exec, command, REQUEST_TIMEOUT)
+            })),
+            probe_sftp: Some(Arc::new(move || {
+                nonblocking::probe_sftp(&session_for_sftp, REQUEST_TIMEOUT)
+            })),
+            open_forwards: Some(Box::new(move |config: &SshConfig, alive| {
+                let (forwarder, statuses) =
+                    super::forward::SessionForwarder::start(session_for_forwards, config, alive)?;
+                Ok((Box::new(forwarder) as ForwardGuard, statuses))
+            })),
             send_eof: Arc::new(move || {
                 let mut ch = channel_for_eof
                     .lock()
                     .map_err(|e| SessionError::Io(std::io::Error::other(format!("lock: {e}"))))?;
-                ch.send_eof()
+                nonblocking::retry(REQUEST_TIMEOUT, || ch.send_eof())
                     .map_err(|e| SessionError::Io(std::io::Error::other(e.to_string())))
             }),
             close: Arc::new(move || {
                 let mut ch = channel_for_close
                     .lock()
                     .map_err(|e| SessionError::Io(std::io::Error::other(format!("lock: {e}"))))?;
-                session_for_close.set_blocking(true);
-                let _ = ch.send_eof();
-                let result = ch.close();
-                session_for_close.set_blocking(false);
+                let _ = nonblocking::retry(REQUEST_TIMEOUT, || ch.send_eof());
+                let result = nonblocking::retry(REQUEST_TIMEOUT, || ch.close());
                 drop(ch);
                 result.map_err(|e| SessionError::Io(std::io::Error::other(e.to_string())))
             }),
             extensions,
+            keepalive: Some(Box::new(Ssh2KeepaliveResponder::new(channel))),
+            host_key,
         })
     }
 }
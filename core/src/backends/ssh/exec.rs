@@ -0,0 +1,144 @@
+//! One-shot command execution over SSH.
+//!
+//! [`run_command`] opens its own session, runs the command on an exec
+//! channel without a PTY, and returns stdout, stderr and the exit code
+//! separately. Useful for quick checks where an interactive shell is
+//! unnecessary. The command runs through the connection's `sudoPrefix`,
+//! if one is set.
+
+use std::io::{self, Read};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::config::SshConfig;
+use crate::errors::SessionError;
+
+use super::auth::connect_and_authenticate;
+use super::sudo;
+
+/// How long to wait before polling the channel again when no data arrived.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Captured result of a command run with [`run_command`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+}
+
+/// Connect with `config`, run `command` without a PTY, and disconnect.
+///
+/// Output is decoded as UTF-8 with invalid sequences replaced. Blocks until
+/// the command exits.
+pub fn run_command(config: &SshConfig, command: &str) -> Result<CommandOutput, SessionError> {
+    let session = connect_and_authenticate(config)?;
+    let exec_err = |e: ssh2::Error| SessionError::SpawnFailed(format!("Exec failed: {e}"));
+
+    let mut channel = session.channel_session().map_err(exec_err)?;
+    channel
+        .exec(&sudo::wrap_command(config.sudo_prefix.as_deref(), command))
+        .map_err(exec_err)?;
+
+    // Read both streams without blocking on either, so a command that fills
+    // the stderr window while stdout is being read cannot stall.
+    session.set_blocking(false);
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    loop {
+        let read_out = drain(&mut channel, &mut stdout)?;
+        let read_err = drain(&mut channel.stderr(), &mut stderr)?;
+        if read_out || read_err {
+            continue;
+        }
+        if channel.eof() {
+            break;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    session.set_blocking(true);
+
+    channel.wait_close().map_err(exec_err)?;
+    let exit_code = channel.exit_status().map_err(exec_err)?;
+    let _ = session.disconnect(None, "Command finished", None);
+
+    Ok(CommandOutput {
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        exit_code,
+    })
+}
+
+/// Read everything currently available from `reader` into `buf`.
+/// Returns whether any data was read.
+pub(crate) fn drain(reader: &mut impl Read, buf: &mut Vec<u8>) -> io::Result<bool> {
+    let mut chunk = [0u8; 8192];
+    let mut read_any = false;
+    loop {
+        match reader.read(&mut chunk) {
+            Ok(0) => return Ok(read_any),
+            Ok(n) => {
+                buf.extend_from_slice(&chunk[..n]);
+                read_any = true;
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(read_any),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reader that yields queued results, then `WouldBlock`.
+    struct Scripted(Vec<io::Result<Vec<u8>>>);
+
+    impl Read for Scripted {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            let data = self.0.remove(0)?;
+            buf[..data.len()].copy_from_slice(&data);
+            Ok(data.len())
+        }
+    }
+
+    #[test]
+    fn drain_reads_until_would_block() {
+        let mut reader = Scripted(vec![Ok(b"hel".to_vec()), Ok(b"lo".to_vec())]);
+        let mut buf = Vec::new();
+        assert!(drain(&mut reader, &mut buf).unwrap());
+        assert_eq!(buf, b"hello");
+        assert!(!drain(&mut reader, &mut buf).unwrap());
+    }
+
+    #[test]
+    fn drain_stops_at_eof() {
+        let mut reader = Scripted(vec![Ok(b"x".to_vec()), Ok(Vec::new())]);
+        let mut buf = Vec::new();
+        assert!(drain(&mut reader, &mut buf).unwrap());
+        assert_eq!(buf, b"x");
+    }
+
+    #[test]
+    fn drain_propagates_errors() {
+        let mut reader = Scripted(vec![Err(io::ErrorKind::ConnectionReset.into())]);
+        assert!(drain(&mut reader, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn command_output_serializes_camel_case() {
+        let output = CommandOutput {
+            stdout: "hello\n".to_string(),
+            stderr: String::new(),
+            exit_code: 7,
+        };
+        let json = serde_json::to_value(&output).unwrap();
+        assert_eq!(json["exitCode"], 7);
+        assert_eq!(json["stdout"], "hello\n");
+    }
+}
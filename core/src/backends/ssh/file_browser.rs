@@ -4,19 +4,28 @@
 //! Blocking calls are offloaded to `tokio::task::spawn_blocking` to
 //! avoid blocking the async executor.
 
+use std::future::Future;
 use std::io::{Read, Write};
+use std::pin::Pin;
 use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
 use crate::config::SshConfig;
 use crate::errors::FileError;
-use crate::files::utils::{chrono_from_epoch, format_permissions};
-use crate::files::{FileBrowser, FileEntry};
+use tokio_util::sync::CancellationToken;
 
-use super::auth::connect_and_authenticate;
+use crate::files::dir_size::{walk_dir_size, DIR_SIZE_MAX_ENTRIES};
+use crate::files::grep::{grep_command, parse_grep_output, GREP_TIMEOUT};
+use crate::files::utils::{chrono_from_epoch, format_permissions, mkdir_prefixes};
+use crate::files::{ArchiveDownloader, FileBrowser, FileEntry, GrepMatch, GrepOptions};
+
+use super::archive::SshArchiveDownloader;
+use super::auth::connect_for_transfer;
+use super::transfer::remote_sha256;
 
 /// State of a connected SFTP session.
 struct SftpState {
-    _session: ssh2::Session,
+    session: ssh2::Session,
     sftp: ssh2::Sftp,
 }
 
@@ -24,6 +33,7 @@ struct SftpState {
 ///
 /// The SFTP session is opened lazily on first use and reused for
 /// subsequent operations. Uses a separate SSH session in blocking mode.
+#[derive(Clone)]
 pub(crate) struct SftpFileBrowser {
     config: SshConfig,
     state: Arc<Mutex<Option<SftpState>>>,
@@ -50,7 +60,7 @@ impl SftpFileBrowser {
             return Ok(());
         }
 
-        let session = connect_and_authenticate(config)
+        let session = connect_for_transfer(config)
             .map_err(|e| FileError::OperationFailed(format!("SFTP connection failed: {e}")))?;
         session.set_blocking(true);
 
@@ -58,15 +68,42 @@ impl SftpFileBrowser {
             .sftp()
             .map_err(|e| FileError::OperationFailed(format!("SFTP init failed: {e}")))?;
 
-        *guard = Some(SftpState {
-            _session: session,
-            sftp,
-        });
+        *guard = Some(SftpState { session, sftp });
 
         Ok(())
     }
 }
 
+/// Timeout in milliseconds of a single blocking read while searching, so
+/// the overall [`GREP_TIMEOUT`] is checked regularly.
+const GREP_POLL_MS: u32 = 500;
+
+/// Read `channel` to the end, failing once `deadline` has passed.
+///
+/// The session must have a timeout set so that reads return regularly.
+fn read_until_deadline(
+    channel: &mut ssh2::Channel,
+    deadline: Instant,
+) -> Result<Vec<u8>, FileError> {
+    let mut output = Vec::new();
+    let mut buf = [0u8; 8192];
+    loop {
+        match channel.read(&mut buf) {
+            Ok(0) => return Ok(output),
+            Ok(n) => output.extend_from_slice(&buf[..n]),
+            Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {}
+            Err(e) => return Err(e.into()),
+        }
+        if Instant::now() >= deadline {
+            let _ = channel.close();
+            return Err(FileError::OperationFailed(format!(
+                "grep timed out after {} seconds",
+                GREP_TIMEOUT.as_secs()
+            )));
+        }
+    }
+}
+
 #[async_trait::async_trait]
 impl FileBrowser for SftpFileBrowser {
     async fn list_dir(&self, path: &str) -> Result<Vec<FileEntry>, FileError> {
@@ -144,6 +181,31 @@ impl FileBrowser for SftpFileBrowser {
         .map_err(|e| FileError::OperationFailed(format!("Task join failed: {e}")))?
     }
 
+    fn sha256<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, FileError>> + Send + 'a>> {
+        let state = self.state.clone();
+        let config = self.config.clone();
+        let path = path.to_string();
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || {
+                Self::ensure_connected(&state, &config)?;
+                let guard = state
+                    .lock()
+                    .map_err(|e| FileError::OperationFailed(format!("Lock failed: {e}")))?;
+                let sftp_state = guard
+                    .as_ref()
+                    .ok_or(FileError::OperationFailed("SFTP not connected".to_string()))?;
+
+                // Hashed on the server, so the contents never cross the wire.
+                remote_sha256(&sftp_state.session, std::path::Path::new(&path))
+            })
+            .await
+            .map_err(|e| FileError::OperationFailed(format!("Task join failed: {e}")))?
+        })
+    }
+
     async fn write_file(&self, path: &str, data: &[u8]) -> Result<(), FileError> {
         let state = self.state.clone();
         let config = self.config.clone();
@@ -211,7 +273,7 @@ impl FileBrowser for SftpFileBrowser {
         .map_err(|e| FileError::OperationFailed(format!("Task join failed: {e}")))?
     }
 
-    async fn rename(&self, from: &str, to: &str) -> Result<(), FileError> {
+    async fn rename(&self, from: &str, to: &str, overwrite: bool) -> Result<(), FileError> {
         let state = self.state.clone();
         let config = self.config.clone();
         let from = from.to_string();
@@ -227,17 +289,13 @@ impl FileBrowser for SftpFileBrowser {
 
             let old = std::path::Path::new(&from);
             let new = std::path::Path::new(&to);
-            sftp_state
-                .sftp
-                .rename(old, new, None)
-                .map_err(|e| FileError::OperationFailed(format!("rename failed: {e}")))?;
-            Ok(())
+            sftp_rename(&sftp_state.sftp, old, new, overwrite)
         })
         .await
         .map_err(|e| FileError::OperationFailed(format!("Task join failed: {e}")))?
     }
 
-    async fn mkdir(&self, path: &str) -> Result<(), FileError> {
+    async fn mkdir(&self, path: &str, recursive: bool) -> Result<(), FileError> {
         let state = self.state.clone();
         let config = self.config.clone();
         let path = path.to_string();
@@ -250,17 +308,69 @@ impl FileBrowser for SftpFileBrowser {
                 .as_ref()
                 .ok_or(FileError::OperationFailed("SFTP not connected".to_string()))?;
 
-            let p = std::path::Path::new(&path);
-            sftp_state
-                .sftp
-                .mkdir(p, 0o755)
-                .map_err(|e| FileError::OperationFailed(format!("mkdir failed: {e}")))?;
-            Ok(())
+            let created = if recursive {
+                sftp_mkdir_all(&sftp_state.sftp, &path)
+            } else {
+                sftp_state.sftp.mkdir(std::path::Path::new(&path), 0o755)
+            };
+            created.map_err(|e| FileError::OperationFailed(format!("mkdir failed: {e}")))
+        })
+        .await
+        .map_err(|e| FileError::OperationFailed(format!("Task join failed: {e}")))?
+    }
+
+    async fn dir_size(&self, path: &str, cancel: &CancellationToken) -> Result<u64, FileError> {
+        let entry = self.stat(path).await?;
+        if !entry.is_directory {
+            return Ok(entry.size);
+        }
+        walk_dir_size(path, cancel, DIR_SIZE_MAX_ENTRIES, |p| async move {
+            self.list_dir(&p).await
+        })
+        .await
+    }
+
+    async fn grep(
+        &self,
+        root: &str,
+        pattern: &str,
+        opts: &GrepOptions,
+    ) -> Result<Vec<GrepMatch>, FileError> {
+        let command = grep_command(root, pattern, opts)?;
+        let max_results = opts.max_results;
+        let state = self.state.clone();
+        let config = self.config.clone();
+        tokio::task::spawn_blocking(move || {
+            Self::ensure_connected(&state, &config)?;
+            let guard = state
+                .lock()
+                .map_err(|e| FileError::OperationFailed(format!("Lock failed: {e}")))?;
+            let sftp_state = guard
+                .as_ref()
+                .ok_or(FileError::OperationFailed("SFTP not connected".to_string()))?;
+
+            // The SFTP session is blocking; a short session timeout lets the
+            // read loop give up once the search has run for `GREP_TIMEOUT`.
+            let exec_err = |e: ssh2::Error| FileError::OperationFailed(format!("grep failed: {e}"));
+            let session = &sftp_state.session;
+            let mut channel = session.channel_session().map_err(exec_err)?;
+            channel.exec(&command).map_err(exec_err)?;
+            let previous_timeout = session.timeout();
+            session.set_timeout(GREP_POLL_MS);
+            let output = read_until_deadline(&mut channel, Instant::now() + GREP_TIMEOUT);
+            session.set_timeout(previous_timeout);
+            let output = output?;
+            channel.wait_close().map_err(exec_err)?;
+            parse_grep_output(&String::from_utf8_lossy(&output), max_results)
         })
         .await
         .map_err(|e| FileError::OperationFailed(format!("Task join failed: {e}")))?
     }
 
+    fn archive_downloader(&self) -> Option<Box<dyn ArchiveDownloader>> {
+        Some(Box::new(SshArchiveDownloader::new(self.config.clone())))
+    }
+
     async fn stat(&self, path: &str) -> Result<FileEntry, FileError> {
         let state = self.state.clone();
         let config = self.config.clone();
@@ -297,4 +407,55 @@ impl FileBrowser for SftpFileBrowser {
         .await
         .map_err(|e| FileError::OperationFailed(format!("Task join failed: {e}")))?
     }
+
+    fn clone_box(&self) -> Box<dyn FileBrowser> {
+        Box::new(self.clone())
+    }
+}
+
+/// Create the directory `path` over SFTP along with any missing parents,
+/// like `mkdir -p`. SFTP has no recursive mkdir, so each component that
+/// is not already a directory is created in turn.
+pub fn sftp_mkdir_all(sftp: &ssh2::Sftp, path: &str) -> Result<(), ssh2::Error> {
+    for dir in mkdir_prefixes(path) {
+        let dir = std::path::Path::new(&dir);
+        if sftp.stat(dir).is_ok_and(|stat| stat.is_dir()) {
+            continue;
+        }
+        sftp.mkdir(dir, 0o755)?;
+    }
+    Ok(())
+}
+
+/// Rename over SFTP, refusing to replace an existing destination unless
+/// `overwrite` is set.
+///
+/// SFTP v3 servers (e.g. OpenSSH without `posix-rename`) ignore the
+/// overwrite flag and fail when the target exists, so an overwriting
+/// rename of a file falls back to removing the target first.
+fn sftp_rename(
+    sftp: &ssh2::Sftp,
+    old: &std::path::Path,
+    new: &std::path::Path,
+    overwrite: bool,
+) -> Result<(), FileError> {
+    let existing = sftp.lstat(new).ok();
+    if existing.is_some() && !overwrite {
+        return Err(FileError::DestinationExists(new.display().to_string()));
+    }
+    let flags = if overwrite {
+        ssh2::RenameFlags::ATOMIC | ssh2::RenameFlags::OVERWRITE | ssh2::RenameFlags::NATIVE
+    } else {
+        ssh2::RenameFlags::ATOMIC | ssh2::RenameFlags::NATIVE
+    };
+    match sftp.rename(old, new, Some(flags)) {
+        Ok(()) => Ok(()),
+        Err(_) if existing.is_some_and(|stat| !stat.is_dir()) => {
+            sftp.unlink(new)
+                .map_err(|e| FileError::OperationFailed(format!("unlink failed: {e}")))?;
+            sftp.rename(old, new, Some(flags))
+                .map_err(|e| FileError::OperationFailed(format!("rename failed: {e}")))
+        }
+        Err(e) => Err(FileError::OperationFailed(format!("rename failed: {e}"))),
+    }
 }
@@ -0,0 +1,289 @@
+//! Local port forwards bundled with an SSH terminal session.
+//!
+//! Forwards listed in `sessionForwards` are opened when the terminal
+//! connects and torn down when it disconnects. They run as `direct-tcpip`
+//! channels of the terminal's own SSH session, driven from one thread.
+//! The session stays non-blocking, so channel setup waits through
+//! [`super::nonblocking`] rather than switching the session's mode.
+
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::config::{SshConfig, SshForward};
+use crate::errors::SessionError;
+
+use super::nonblocking::{self, REQUEST_TIMEOUT};
+use super::x11::write_all_retry;
+
+/// Runtime status of a single session-bundled forward.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionForwardStatus {
+    pub local_port: u16,
+    pub remote_host: String,
+    pub remote_port: u16,
+    /// Whether the local listener is bound and accepting connections.
+    pub active: bool,
+    /// Why the forward could not be opened, if it is not active.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl SessionForwardStatus {
+    /// Status for a forward that is listening.
+    pub fn active(forward: &SshForward) -> Self {
+        Self {
+            local_port: forward.local_port,
+            remote_host: forward.remote_host.clone(),
+            remote_port: forward.remote_port,
+            active: true,
+            error: None,
+        }
+    }
+
+    /// Status for a forward that failed to open.
+    pub fn failed(forward: &SshForward, error: impl Into<String>) -> Self {
+        Self {
+            local_port: forward.local_port,
+            remote_host: forward.remote_host.clone(),
+            remote_port: forward.remote_port,
+            active: false,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// Runs the session-bundled local forwards until dropped.
+pub struct SessionForwarder {
+    shutdown: Arc<AtomicBool>,
+    event_loop: Option<std::thread::JoinHandle<()>>,
+}
+
+impl SessionForwarder {
+    /// Bind a local listener for every forward in `config.session_forwards`
+    /// and relay accepted connections over the terminal's non-blocking
+    /// `session`.
+    ///
+    /// A forward whose local port cannot be bound is reported as failed
+    /// without affecting the others. The forwarder also stops when the
+    /// terminal's `alive` flag is cleared.
+    pub fn start(
+        session: Arc<ssh2::Session>,
+        config: &SshConfig,
+        alive: Arc<AtomicBool>,
+    ) -> Result<(Self, Vec<SessionForwardStatus>), SessionError> {
+        let mut statuses = Vec::with_capacity(config.session_forwards.len());
+        let mut listeners = Vec::new();
+        for forward in &config.session_forwards {
+            match bind_listener(forward.local_port) {
+                Ok(listener) => {
+                    info!(
+                        "Session forward: 127.0.0.1:{} -> {}:{}",
+                        forward.local_port, forward.remote_host, forward.remote_port
+                    );
+                    statuses.push(SessionForwardStatus::active(forward));
+                    listeners.push((listener, forward.clone()));
+                }
+                Err(e) => {
+                    warn!(
+                        "Session forward on local port {} failed: {e}",
+                        forward.local_port
+                    );
+                    statuses.push(SessionForwardStatus::failed(forward, e.to_string()));
+                }
+            }
+        }
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let shutdown_clone = shutdown.clone();
+        let event_loop = std::thread::Builder::new()
+            .name("ssh-session-forwards".to_string())
+            .spawn(move || event_loop(&session, &listeners, &shutdown_clone, &alive))
+            .map_err(|e| {
+                SessionError::SpawnFailed(format!("Failed to spawn forward event loop: {e}"))
+            })?;
+
+        Ok((
+            Self {
+                shutdown,
+                event_loop: Some(event_loop),
+            },
+            statuses,
+        ))
+    }
+}
+
+impl Drop for SessionForwarder {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.event_loop.take() {
+            let _ = handle.join();
+        }
+        info!("Session forwards stopped");
+    }
+}
+
+fn bind_listener(local_port: u16) -> std::io::Result<TcpListener> {
+    let listener = TcpListener::bind(("127.0.0.1", local_port))?;
+    listener.set_nonblocking(true)?;
+    Ok(listener)
+}
+
+/// An accepted local connection relayed over an SSH `direct-tcpip` channel.
+///
+/// Both the channel's session and the stream must be non-blocking.
+pub(super) struct ForwardProxy {
+    pub(super) channel: ssh2::Channel,
+    pub(super) stream: TcpStream,
+}
+
+impl ForwardProxy {
+    /// Pump data in both directions. Returns `true` if the proxy is still alive.
+    pub(super) fn pump(&mut self) -> bool {
+        let mut buf = [0u8; 16384];
+
+        match self.channel.read(&mut buf) {
+            Ok(0) => return false,
+            Ok(n) => {
+                if write_all_nonblocking(&mut self.stream, &buf[..n]).is_err() {
+                    return false;
+                }
+            }
+            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(_) => return false,
+        }
+
+        match self.stream.read(&mut buf) {
+            Ok(0) => return false,
+            Ok(n) => {
+                if write_all_retry(&mut self.channel, &buf[..n]).is_err() {
+                    return false;
+                }
+            }
+            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+            Err(_) => return false,
+        }
+
+        true
+    }
+}
+
+/// Single-threaded event loop: accept local connections and pump all proxies.
+fn event_loop(
+    session: &ssh2::Session,
+    listeners: &[(TcpListener, SshForward)],
+    shutdown: &AtomicBool,
+    alive: &AtomicBool,
+) {
+    let mut proxies: Vec<ForwardProxy> = Vec::new();
+
+    while !shutdown.load(Ordering::SeqCst) && alive.load(Ordering::SeqCst) {
+        for (listener, forward) in listeners {
+            match listener.accept() {
+                Ok((stream, peer)) => {
+                    debug!("Session forward: accepted {peer} on {}", forward.local_port);
+                    let channel = nonblocking::retry(REQUEST_TIMEOUT, || {
+                        session.channel_direct_tcpip(
+                            &forward.remote_host,
+                            forward.remote_port,
+                            None,
+                        )
+                    });
+                    match channel {
+                        Ok(channel) => {
+                            let _ = stream.set_nonblocking(true);
+                            proxies.push(ForwardProxy { channel, stream });
+                        }
+                        Err(e) => warn!(
+                            "Session forward to {}:{} failed: {e}",
+                            forward.remote_host, forward.remote_port
+                        ),
+                    }
+                }
+                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(e) => warn!("Session forward accept error: {e}"),
+            }
+        }
+
+        proxies.retain_mut(|proxy| proxy.pump());
+
+        if proxies.is_empty() {
+            std::thread::sleep(Duration::from_millis(50));
+        } else {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    for proxy in &mut proxies {
+        let _ = proxy.channel.send_eof();
+        let _ = proxy.channel.close();
+    }
+    debug!(
+        "Session forward event loop finished ({} proxies cleaned up)",
+        proxies.len()
+    );
+}
+
+/// Write all bytes to a non-blocking TCP stream, retrying on WouldBlock.
+fn write_all_nonblocking(stream: &mut TcpStream, mut buf: &[u8]) -> std::io::Result<()> {
+    while !buf.is_empty() {
+        match stream.write(buf) {
+            Ok(0) => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::WriteZero,
+                    "write returned 0",
+                ))
+            }
+            Ok(n) => buf = &buf[n..],
+            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                std::thread::sleep(Duration::from_micros(100));
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forward(local_port: u16) -> SshForward {
+        SshForward {
+            local_port,
+            remote_host: "localhost".to_string(),
+            remote_port: 80,
+        }
+    }
+
+    #[test]
+    fn status_serializes_camel_case() {
+        let status = SessionForwardStatus::active(&forward(8080));
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["localPort"], 8080);
+        assert_eq!(json["remoteHost"], "localhost");
+        assert_eq!(json["remotePort"], 80);
+        assert_eq!(json["active"], true);
+        assert!(json.get("error").is_none());
+    }
+
+    #[test]
+    fn failed_status_carries_error() {
+        let status = SessionForwardStatus::failed(&forward(8080), "address in use");
+        assert!(!status.active);
+        assert_eq!(status.error.as_deref(), Some("address in use"));
+    }
+
+    #[test]
+    fn bind_listener_reports_port_in_use() {
+        let taken = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = taken.local_addr().unwrap().port();
+        assert!(bind_listener(port).is_err());
+    }
+}
@@ -0,0 +1,509 @@
+//! Host key verification and fingerprints.
+//!
+//! Checks the server's host key against an OpenSSH `known_hosts` file as
+//! configured by [`SshConfig::strict_host_key_checking`], and formats host
+//! keys the way OpenSSH prints them (`SHA256:` followed by unpadded
+//! Base64), so they can be compared with `ssh-keygen -lf` output on the
+//! server.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use ssh2::{CheckResult, KnownHostFileKind, MethodType};
+use tracing::info;
+
+use crate::config::expand::expand_tilde;
+use crate::config::SshConfig;
+use crate::connection::HostKeyFingerprint;
+use crate::errors::SessionError;
+
+/// How an unknown or changed host key is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostKeyChecking {
+    /// Record keys of unknown hosts; reject changed keys.
+    AcceptNew,
+    /// Reject unknown hosts and changed keys.
+    Strict,
+    /// Accept any host key.
+    Off,
+}
+
+impl HostKeyChecking {
+    /// Parse a `strict_host_key_checking` mode. Blank means the default,
+    /// `accept-new`.
+    pub fn parse(mode: &str) -> Result<Self, SessionError> {
+        match mode.trim() {
+            "" | "accept-new" => Ok(Self::AcceptNew),
+            "strict" => Ok(Self::Strict),
+            "off" => Ok(Self::Off),
+            other => Err(SessionError::InvalidConfig(format!(
+                "Unknown host key checking mode \"{other}\" \
+                 (expected \"accept-new\", \"strict\" or \"off\")"
+            ))),
+        }
+    }
+}
+
+/// Check the host key `session` received during the handshake against
+/// `config`'s `known_hosts` file.
+///
+/// Fails with [`SessionError::HostKeyMismatch`] when the recorded key
+/// differs, and in strict mode when the host is not recorded at all. In
+/// accept-new mode an unknown host's key is appended to the file.
+pub fn verify_host_key(session: &ssh2::Session, config: &SshConfig) -> Result<(), SessionError> {
+    if HostKeyChecking::parse(&config.strict_host_key_checking)? == HostKeyChecking::Off {
+        return Ok(());
+    }
+    let (key, _) = session.host_key().ok_or_else(|| {
+        SessionError::SpawnFailed("Server did not present a host key".to_string())
+    })?;
+    check_known_host(session, config, key)
+}
+
+/// Make the server present a host key of a type recorded for it in
+/// `config`'s `known_hosts` file, as OpenSSH does. Call before the
+/// handshake.
+///
+/// libssh2 negotiates its own preferred type otherwise (ECDSA before
+/// Ed25519), and a host recorded only with a key of another type would
+/// fail the check with a mismatch although its key never changed.
+pub fn prefer_known_host_key_types(
+    session: &ssh2::Session,
+    config: &SshConfig,
+) -> Result<(), SessionError> {
+    if HostKeyChecking::parse(&config.strict_host_key_checking)? == HostKeyChecking::Off {
+        return Ok(());
+    }
+    let recorded = recorded_key_types(session, config)?;
+    if recorded.is_empty() {
+        return Ok(());
+    }
+    let supported = session
+        .supported_algs(MethodType::HostKey)
+        .map_err(|e| SessionError::SpawnFailed(format!("Failed to list host key types: {e}")))?;
+    session
+        .method_pref(
+            MethodType::HostKey,
+            &host_key_preference(&recorded, &supported),
+        )
+        .map_err(|e| SessionError::SpawnFailed(format!("Failed to set host key types: {e}")))
+}
+
+/// Algorithms of the keys recorded for `config.host:config.port`, in file
+/// order. Lines the parser does not understand are skipped.
+fn recorded_key_types(
+    session: &ssh2::Session,
+    config: &SshConfig,
+) -> Result<Vec<String>, SessionError> {
+    let path = known_hosts_path(config);
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return Ok(Vec::new());
+    };
+    let known_hosts = load_known_hosts(session, &path)?;
+    let mut types = Vec::new();
+    for entry in ssh_key::known_hosts::KnownHosts::new(&contents).flatten() {
+        if entry.marker().is_some() {
+            continue;
+        }
+        let Ok(key) = entry.public_key().to_bytes() else {
+            continue;
+        };
+        // libssh2 matches the host patterns, hashed ones included.
+        if matches!(
+            known_hosts.check_port(&config.host, config.port, &key),
+            CheckResult::Match
+        ) {
+            let algorithm = entry.public_key().algorithm().to_string();
+            if !types.contains(&algorithm) {
+                types.push(algorithm);
+            }
+        }
+    }
+    Ok(types)
+}
+
+/// Host key method preference listing the `recorded` key types first,
+/// then the rest of `supported`. RSA keys are offered with their SHA-2
+/// signature algorithms ahead of `ssh-rsa`.
+fn host_key_preference(recorded: &[String], supported: &[&str]) -> String {
+    let mut preferred: Vec<&str> = Vec::new();
+    for key_type in recorded {
+        let methods: &[&str] = match key_type.as_str() {
+            "ssh-rsa" => &["rsa-sha2-512", "rsa-sha2-256", "ssh-rsa"],
+            other => &[other],
+        };
+        preferred.extend(methods.iter().filter(|m| supported.contains(m)));
+    }
+    for method in supported {
+        if !preferred.contains(method) {
+            preferred.push(method);
+        }
+    }
+    preferred.join(",")
+}
+
+/// libssh2's parser loaded with the `known_hosts` file at `path`, if it
+/// exists.
+fn load_known_hosts(
+    session: &ssh2::Session,
+    path: &Path,
+) -> Result<ssh2::KnownHosts, SessionError> {
+    let mut known_hosts = session
+        .known_hosts()
+        .map_err(|e| SessionError::SpawnFailed(format!("Failed to init known_hosts: {e}")))?;
+    if path.exists() {
+        known_hosts
+            .read_file(path, KnownHostFileKind::OpenSSH)
+            .map_err(|e| {
+                SessionError::SpawnFailed(format!("Failed to read {}: {e}", path.display()))
+            })?;
+    }
+    Ok(known_hosts)
+}
+
+/// Check `key` for `config.host:config.port` against the `known_hosts`
+/// file, recording it there if the host is unknown and the mode allows.
+///
+/// `session` only provides libssh2's `known_hosts` parser; it need not
+/// be connected.
+fn check_known_host(
+    session: &ssh2::Session,
+    config: &SshConfig,
+    key: &[u8],
+) -> Result<(), SessionError> {
+    let mode = HostKeyChecking::parse(&config.strict_host_key_checking)?;
+    if mode == HostKeyChecking::Off {
+        return Ok(());
+    }
+    let path = known_hosts_path(config);
+    let known_hosts = load_known_hosts(session, &path)?;
+
+    let host = known_hosts_host(&config.host, config.port);
+    match known_hosts.check_port(&config.host, config.port, key) {
+        CheckResult::Match => Ok(()),
+        CheckResult::Mismatch => Err(SessionError::HostKeyMismatch {
+            host,
+            fingerprint: describe_key(key),
+        }),
+        CheckResult::NotFound if mode == HostKeyChecking::Strict => {
+            Err(SessionError::SpawnFailed(format!(
+                "Host key for {host} is not in {} and strict host key checking is on; \
+                 the server presented {}",
+                path.display(),
+                describe_key(key)
+            )))
+        }
+        CheckResult::NotFound => {
+            append_known_host(&path, &host, key)?;
+            info!(host, fingerprint = %describe_key(key), "Added host key to known_hosts");
+            Ok(())
+        }
+        CheckResult::Failure => Err(SessionError::SpawnFailed(format!(
+            "Failed to check the host key for {host} against {}",
+            path.display()
+        ))),
+    }
+}
+
+/// The `known_hosts` file for `config`: `known_hosts_file`, or
+/// `~/.ssh/known_hosts`.
+fn known_hosts_path(config: &SshConfig) -> PathBuf {
+    let path = config
+        .known_hosts_file
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .unwrap_or("~/.ssh/known_hosts");
+    PathBuf::from(expand_tilde(path))
+}
+
+/// How OpenSSH names `host:port` in `known_hosts`: the bare host for the
+/// default port, `[host]:port` otherwise.
+fn known_hosts_host(host: &str, port: u16) -> String {
+    if port == 22 {
+        host.to_string()
+    } else {
+        format!("[{host}]:{port}")
+    }
+}
+
+/// Key type and SHA-256 fingerprint of a wire-format public key, e.g.
+/// `ssh-ed25519 SHA256:...`.
+fn describe_key(key: &[u8]) -> String {
+    match ssh_key::PublicKey::from_bytes(key) {
+        Ok(public_key) => format!(
+            "{} {}",
+            public_key.algorithm(),
+            public_key.fingerprint(ssh_key::HashAlg::Sha256)
+        ),
+        Err(_) => "an unrecognized key".to_string(),
+    }
+}
+
+/// Append a `known_hosts` line for `host` with the wire-format `key`,
+/// creating the file and its directory if needed.
+///
+/// The line is appended rather than the whole file rewritten, so entries
+/// and comments libssh2 does not preserve are left alone.
+fn append_known_host(path: &Path, host: &str, key: &[u8]) -> Result<(), SessionError> {
+    let encoded = ssh_key::PublicKey::from_bytes(key)
+        .and_then(|public_key| public_key.to_openssh())
+        .map_err(|e| SessionError::SpawnFailed(format!("Unsupported host key: {e}")))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Start on a fresh line if the file does not end with one.
+    let separator = match fs::read(path) {
+        Ok(existing) if !existing.is_empty() && !existing.ends_with(b"\n") => "\n",
+        _ => "",
+    };
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(format!("{separator}{host} {encoded}\n").as_bytes())?;
+    Ok(())
+}
+
+/// Fingerprint of the host key presented by `session`.
+///
+/// Returns `None` before the handshake or if libssh2 cannot hash the key.
+pub fn host_key_fingerprint(session: &ssh2::Session) -> Option<HostKeyFingerprint> {
+    let (_, key_type) = session.host_key()?;
+    let hash = session.host_key_hash(ssh2::HashType::Sha256)?;
+    Some(HostKeyFingerprint {
+        key_type: key_type_name(key_type).to_string(),
+        fingerprint: format_sha256_fingerprint(hash)?,
+    })
+}
+
+/// OpenSSH algorithm name for a libssh2 host key type.
+fn key_type_name(key_type: ssh2::HostKeyType) -> &'static str {
+    match key_type {
+        ssh2::HostKeyType::Rsa => "ssh-rsa",
+        ssh2::HostKeyType::Dss => "ssh-dss",
+        ssh2::HostKeyType::Ecdsa256 => "ecdsa-sha2-nistp256",
+        ssh2::HostKeyType::Ecdsa384 => "ecdsa-sha2-nistp384",
+        ssh2::HostKeyType::Ecdsa521 => "ecdsa-sha2-nistp521",
+        ssh2::HostKeyType::Ed25519 => "ssh-ed25519",
+        ssh2::HostKeyType::Unknown => "unknown",
+    }
+}
+
+/// Format a raw SHA-256 digest as `SHA256:<base64>`.
+///
+/// Returns `None` if `hash` is not 32 bytes long.
+pub fn format_sha256_fingerprint(hash: &[u8]) -> Option<String> {
+    let digest: [u8; 32] = hash.try_into().ok()?;
+    Some(ssh_key::Fingerprint::Sha256(digest).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A random Ed25519 public key in SSH wire format.
+    fn random_host_key() -> Vec<u8> {
+        ssh_key::PrivateKey::random(&mut rand::thread_rng(), ssh_key::Algorithm::Ed25519)
+            .unwrap()
+            .public_key()
+            .to_bytes()
+            .unwrap()
+    }
+
+    fn config(mode: &str, known_hosts: &Path, port: u16) -> SshConfig {
+        SshConfig {
+            host: "server.test".into(),
+            port,
+            strict_host_key_checking: mode.into(),
+            known_hosts_file: Some(known_hosts.to_string_lossy().into_owned()),
+            ..SshConfig::default()
+        }
+    }
+
+    fn parser() -> ssh2::Session {
+        ssh2::Session::new().unwrap()
+    }
+
+    #[test]
+    fn checking_modes_parse() {
+        assert_eq!(
+            HostKeyChecking::parse("accept-new").unwrap(),
+            HostKeyChecking::AcceptNew
+        );
+        assert_eq!(
+            HostKeyChecking::parse("").unwrap(),
+            HostKeyChecking::AcceptNew
+        );
+        assert_eq!(
+            HostKeyChecking::parse("strict").unwrap(),
+            HostKeyChecking::Strict
+        );
+        assert_eq!(HostKeyChecking::parse("off").unwrap(), HostKeyChecking::Off);
+        assert!(matches!(
+            HostKeyChecking::parse("yes"),
+            Err(SessionError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn recorded_key_types_lists_types_for_the_host_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known_hosts");
+        let other = SshConfig {
+            host: "other.test".into(),
+            ..config("accept-new", &path, 2222)
+        };
+        check_known_host(&parser(), &other, &random_host_key()).unwrap();
+        check_known_host(
+            &parser(),
+            &config("accept-new", &path, 2222),
+            &random_host_key(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            recorded_key_types(&parser(), &config("strict", &path, 2222)).unwrap(),
+            vec!["ssh-ed25519".to_string()]
+        );
+        assert!(recorded_key_types(&parser(), &config("strict", &path, 22))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn host_key_preference_puts_recorded_types_first() {
+        let supported = [
+            "ecdsa-sha2-nistp256",
+            "ssh-ed25519",
+            "rsa-sha2-512",
+            "rsa-sha2-256",
+            "ssh-rsa",
+        ];
+        assert_eq!(
+            host_key_preference(&["ssh-ed25519".to_string()], &supported),
+            "ssh-ed25519,ecdsa-sha2-nistp256,rsa-sha2-512,rsa-sha2-256,ssh-rsa"
+        );
+        assert_eq!(
+            host_key_preference(&["ssh-rsa".to_string()], &supported),
+            "rsa-sha2-512,rsa-sha2-256,ssh-rsa,ecdsa-sha2-nistp256,ssh-ed25519"
+        );
+        // Types libssh2 cannot use are left out.
+        assert_eq!(
+            host_key_preference(&["ssh-dss".to_string()], &supported[..2]),
+            "ecdsa-sha2-nistp256,ssh-ed25519"
+        );
+    }
+
+    #[test]
+    fn known_hosts_host_brackets_non_default_ports() {
+        assert_eq!(known_hosts_host("example.com", 22), "example.com");
+        assert_eq!(known_hosts_host("example.com", 2222), "[example.com]:2222");
+    }
+
+    #[test]
+    fn accept_new_records_unknown_host_then_matches() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ssh").join("known_hosts");
+        let key = random_host_key();
+        let config = config("accept-new", &path, 2222);
+
+        check_known_host(&parser(), &config, &key).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(
+            written.starts_with("[server.test]:2222 ssh-ed25519 "),
+            "{written}"
+        );
+        assert_eq!(written.lines().count(), 1);
+
+        // Recorded now, so a second connection matches without appending.
+        check_known_host(&parser(), &config, &key).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), written);
+    }
+
+    #[test]
+    fn changed_key_is_mismatch_with_fingerprint() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known_hosts");
+        check_known_host(
+            &parser(),
+            &config("accept-new", &path, 22),
+            &random_host_key(),
+        )
+        .unwrap();
+
+        let new_key = random_host_key();
+        for mode in ["accept-new", "strict"] {
+            let err = check_known_host(&parser(), &config(mode, &path, 22), &new_key).unwrap_err();
+            let SessionError::HostKeyMismatch { host, fingerprint } = err else {
+                panic!("expected HostKeyMismatch in {mode} mode, got {err}");
+            };
+            assert_eq!(host, "server.test");
+            assert_eq!(fingerprint, describe_key(&new_key));
+            assert!(
+                fingerprint.starts_with("ssh-ed25519 SHA256:"),
+                "{fingerprint}"
+            );
+        }
+    }
+
+    #[test]
+    fn strict_rejects_unknown_host_without_recording() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known_hosts");
+        let key = random_host_key();
+
+        let err = check_known_host(&parser(), &config("strict", &path, 22), &key)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("not in"), "{err}");
+        assert!(err.contains(&describe_key(&key)), "{err}");
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn off_accepts_any_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known_hosts");
+        check_known_host(
+            &parser(),
+            &config("accept-new", &path, 22),
+            &random_host_key(),
+        )
+        .unwrap();
+        check_known_host(&parser(), &config("off", &path, 22), &random_host_key()).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+    }
+
+    #[test]
+    fn append_keeps_existing_entries_and_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("known_hosts");
+        fs::write(&path, "# managed by hand").unwrap();
+
+        append_known_host(&path, "[server.test]:2222", &random_host_key()).unwrap();
+        let written = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = written.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "# managed by hand");
+        assert!(lines[1].starts_with("[server.test]:2222 ssh-ed25519 "));
+    }
+
+    #[test]
+    fn sha256_fingerprint_uses_openssh_format() {
+        let fingerprint = format_sha256_fingerprint(&[0u8; 32]).unwrap();
+        assert_eq!(
+            fingerprint,
+            "SHA256:AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"
+        );
+    }
+
+    #[test]
+    fn sha256_fingerprint_rejects_wrong_length() {
+        assert!(format_sha256_fingerprint(&[0u8; 20]).is_none());
+    }
+
+    #[test]
+    fn key_type_names_match_openssh() {
+        assert_eq!(key_type_name(ssh2::HostKeyType::Ed25519), "ssh-ed25519");
+        assert_eq!(key_type_name(ssh2::HostKeyType::Rsa), "ssh-rsa");
+    }
+}
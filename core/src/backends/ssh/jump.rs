@@ -0,0 +1,309 @@
+//! Connecting through SSH jump hosts, like OpenSSH's `ProxyJump`.
+//!
+//! Each hop is an authenticated SSH session that opens a `direct-tcpip`
+//! channel to the next hop, or to the target after the last one. libssh2
+//! can only run a session over a real socket, so every channel is relayed
+//! to a loopback TCP connection by a thread that owns the hop's session,
+//! and the next session is established over that connection. A relay
+//! ends when either side closes, taking its hop's session with it.
+
+use std::io;
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::time::Duration;
+
+use tracing::{debug, info};
+
+use crate::config::SshConfig;
+use crate::errors::SessionError;
+use crate::session::ssh::{parse_proxy_jump, JumpHost};
+
+use super::auth::{authenticate_stream, connect_tcp};
+use super::forward::ForwardProxy;
+
+/// Pause between relay passes, as in the session forward event loop.
+const RELAY_POLL: Duration = Duration::from_millis(1);
+
+/// The jump hosts a connection to `config` goes through, outermost first.
+///
+/// `config.jump_hosts` is used as-is when set; otherwise the hops come
+/// from `config.proxy_jump` (see [`jump_host_config`]). Returns an empty
+/// list for a direct connection.
+pub fn jump_chain(config: &SshConfig) -> Result<Vec<SshConfig>, SessionError> {
+    if !config.jump_hosts.is_empty() {
+        return Ok(config.jump_hosts.clone());
+    }
+    let Some(spec) = config.proxy_jump.as_deref() else {
+        return Ok(Vec::new());
+    };
+    Ok(parse_proxy_jump(spec)?
+        .iter()
+        .map(|hop| jump_host_config(config, hop))
+        .collect())
+}
+
+/// Connection settings for `hop` on the way to `target`.
+///
+/// The hop authenticates with the target's methods and local identities
+/// (key files and agent), and as the target's user unless it names its
+/// own. The target's password and key passphrase are never sent to a hop,
+/// so a hop needing one must be a saved connection with its own. Its host
+/// key is checked like the target's, and it gets the same connect timeout.
+/// The target's source address applies to the outermost hop, the only one
+/// reached directly.
+pub fn jump_host_config(target: &SshConfig, hop: &JumpHost) -> SshConfig {
+    SshConfig {
+        host: hop.host.clone(),
+        port: hop.port,
+        username: hop
+            .username
+            .clone()
+            .unwrap_or_else(|| target.username.clone()),
+        auth_method: target.auth_method.clone(),
+        auth_fallback: target.auth_fallback.clone(),
+        key_path: target.key_path.clone(),
+        identity_files: target.identity_files.clone(),
+        bind_address: target.bind_address.clone(),
+        connect_timeout_secs: target.connect_timeout_secs,
+        strict_host_key_checking: target.strict_host_key_checking.clone(),
+        known_hosts_file: target.known_hosts_file.clone(),
+        ..SshConfig::default()
+    }
+}
+
+/// Open the stream an SSH session to `config` runs over: a direct TCP
+/// connection, or a loopback connection tunneled through its jump hosts.
+///
+/// Failures at a jump host name that hop, so they can be told apart from
+/// failures at the target.
+pub fn open_transport(config: &SshConfig) -> Result<TcpStream, SessionError> {
+    let hops = jump_chain(config)?;
+    let Some(first) = hops.first() else {
+        return connect_tcp(config);
+    };
+
+    let mut stream = connect_tcp(first).map_err(|e| jump_host_error(first, e))?;
+    for (i, hop) in hops.iter().enumerate() {
+        let session =
+            authenticate_stream(stream, hop, false).map_err(|e| jump_host_error(hop, e))?;
+        let (host, port) = match hops.get(i + 1) {
+            Some(next) => (next.host.as_str(), next.port),
+            None => (config.host.as_str(), config.port),
+        };
+        info!(
+            jump_host = %hop.host,
+            jump_port = hop.port,
+            host,
+            port,
+            "Tunneling SSH connection through jump host"
+        );
+        stream = relay_channel(session, host, port).map_err(|e| jump_host_error(hop, e))?;
+    }
+    Ok(stream)
+}
+
+/// Prefix `e` with the jump host it happened at, keeping authentication
+/// failures distinguishable from other errors.
+fn jump_host_error(hop: &SshConfig, e: SessionError) -> SessionError {
+    let at = format!("Jump host {}:{}", hop.host, hop.port);
+    match e {
+        SessionError::AuthFailed(msg) => SessionError::AuthFailed(format!("{at}: {msg}")),
+        SessionError::SpawnFailed(msg) => SessionError::SpawnFailed(format!("{at}: {msg}")),
+        other => SessionError::SpawnFailed(format!("{at}: {other}")),
+    }
+}
+
+/// Open a `direct-tcpip` channel from `session` to `host:port` and relay
+/// it to a loopback connection, which is returned.
+fn relay_channel(session: ssh2::Session, host: &str, port: u16) -> Result<TcpStream, SessionError> {
+    let channel = session
+        .channel_direct_tcpip(host, port, None)
+        .map_err(|e| SessionError::SpawnFailed(format!("Tunnel to {host}:{port} failed: {e}")))?;
+    let (local, relay_end) = loopback_pair()?;
+    relay_end.set_nonblocking(true)?;
+    session.set_blocking(false);
+
+    let destination = format!("{host}:{port}");
+    let mut proxy = ForwardProxy {
+        channel,
+        stream: relay_end,
+    };
+    std::thread::Builder::new()
+        .name("ssh-jump-relay".to_string())
+        .spawn(move || {
+            // The hop's session must outlive the channel relayed over it.
+            let _session = session;
+            while proxy.pump() {
+                std::thread::sleep(RELAY_POLL);
+            }
+            let _ = proxy.channel.send_eof();
+            let _ = proxy.channel.close();
+            debug!("Jump host relay to {destination} finished");
+        })
+        .map_err(|e| SessionError::SpawnFailed(format!("Failed to spawn jump host relay: {e}")))?;
+    Ok(local)
+}
+
+/// Two connected loopback TCP streams.
+fn loopback_pair() -> io::Result<(TcpStream, TcpStream)> {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let local = TcpStream::connect(listener.local_addr()?)?;
+    let local_addr = local.local_addr()?;
+    // Another local process may connect to the port first; skip it.
+    loop {
+        let (remote, peer) = listener.accept()?;
+        if peer == local_addr {
+            return Ok((local, remote));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read, Write};
+
+    use super::*;
+
+    fn target() -> SshConfig {
+        SshConfig {
+            host: "app.internal".into(),
+            port: 2222,
+            username: "dev".into(),
+            auth_method: "key".into(),
+            auth_fallback: vec!["agent".into()],
+            key_path: Some("/keys/dev".into()),
+            identity_files: vec!["/keys/extra".into()],
+            bind_address: Some("10.0.0.5".into()),
+            connect_timeout_secs: 7,
+            strict_host_key_checking: "strict".into(),
+            ..SshConfig::default()
+        }
+    }
+
+    #[test]
+    fn direct_connection_has_no_jump_hosts() {
+        assert!(jump_chain(&target()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn proxy_jump_hops_inherit_target_identities() {
+        let config = SshConfig {
+            proxy_jump: Some("ops@bastion:2204,inner".into()),
+            ..target()
+        };
+        let hops = jump_chain(&config).unwrap();
+        assert_eq!(hops.len(), 2);
+
+        assert_eq!(hops[0].host, "bastion");
+        assert_eq!(hops[0].port, 2204);
+        assert_eq!(hops[0].username, "ops");
+        assert_eq!(hops[1].host, "inner");
+        assert_eq!(hops[1].port, 22);
+        assert_eq!(hops[1].username, "dev");
+        for hop in &hops {
+            assert_eq!(hop.auth_method, "key");
+            assert_eq!(hop.auth_fallback, vec!["agent".to_string()]);
+            assert_eq!(hop.key_path.as_deref(), Some("/keys/dev"));
+            assert_eq!(hop.identity_files, vec!["/keys/extra".to_string()]);
+            assert_eq!(hop.bind_address.as_deref(), Some("10.0.0.5"));
+            assert_eq!(hop.connect_timeout_secs, 7);
+            assert_eq!(hop.strict_host_key_checking, "strict");
+            assert!(hop.proxy_jump.is_none());
+        }
+    }
+
+    #[test]
+    fn proxy_jump_hops_get_no_target_passwords() {
+        let config = SshConfig {
+            auth_method: "password".into(),
+            password: Some("target-secret".into()),
+            fallback_password: Some("fallback-secret".into()),
+            proxy_jump: Some("bastion".into()),
+            ..target()
+        };
+        let hops = jump_chain(&config).unwrap();
+        assert_eq!(hops[0].password, None);
+        assert_eq!(hops[0].fallback_password, None);
+    }
+
+    #[test]
+    fn resolved_jump_hosts_take_precedence() {
+        let bastion = SshConfig {
+            host: "bastion".into(),
+            username: "ops".into(),
+            auth_method: "password".into(),
+            password: Some("secret".into()),
+            ..SshConfig::default()
+        };
+        let config = SshConfig {
+            proxy_jump: Some("saved-bastion-id".into()),
+            jump_hosts: vec![bastion],
+            ..target()
+        };
+        let hops = jump_chain(&config).unwrap();
+        assert_eq!(hops.len(), 1);
+        assert_eq!(hops[0].auth_method, "password");
+        assert_eq!(hops[0].password.as_deref(), Some("secret"));
+    }
+
+    #[test]
+    fn invalid_proxy_jump_is_rejected() {
+        let config = SshConfig {
+            proxy_jump: Some("bastion:notaport".into()),
+            ..target()
+        };
+        assert!(matches!(
+            jump_chain(&config),
+            Err(SessionError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn jump_host_error_names_hop_and_keeps_auth_failures() {
+        let hop = SshConfig {
+            host: "bastion".into(),
+            port: 2204,
+            ..SshConfig::default()
+        };
+        let err = jump_host_error(&hop, SessionError::AuthFailed("no identities".into()));
+        assert!(matches!(err, SessionError::AuthFailed(_)));
+        assert!(err
+            .to_string()
+            .contains("Jump host bastion:2204: no identities"));
+
+        let err = jump_host_error(
+            &hop,
+            SessionError::SpawnFailed("Password auth failed".into()),
+        );
+        assert_eq!(
+            err.to_string(),
+            "Spawn failed: Jump host bastion:2204: Password auth failed"
+        );
+    }
+
+    #[test]
+    fn unreachable_jump_host_is_named() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        drop(listener);
+        let config = SshConfig {
+            proxy_jump: Some(format!("127.0.0.1:{port}")),
+            bind_address: None,
+            ..target()
+        };
+        let err = open_transport(&config).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains(&format!("Jump host 127.0.0.1:{port}: Connection failed")),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn loopback_pair_is_connected() {
+        let (mut a, mut b) = loopback_pair().unwrap();
+        a.write_all(b"ping").unwrap();
+        let mut buf = [0u8; 4];
+        b.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"ping");
+    }
+}
@@ -0,0 +1,224 @@
+//! Dead-peer detection with SSH-level keepalives.
+//!
+//! Mirrors OpenSSH's `ServerAliveInterval` / `ServerAliveCountMax`: every
+//! interval a request the server must answer is sent, and once the
+//! configured number of consecutive requests go unanswered the peer is
+//! considered lost and the session is torn down.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Environment variable named in the probe request. Servers reject it
+/// after the shell has started, but the rejection is still a reply.
+const PROBE_VARIABLE: &str = "TERMIHUB_KEEPALIVE";
+
+/// Step used when waiting, so a cleared `alive` flag is noticed quickly.
+const POLL_STEP: Duration = Duration::from_millis(10);
+
+/// libssh2 error codes the probe distinguishes.
+const LIBSSH2_ERROR_CHANNEL_REQUEST_DENIED: i32 = -22;
+const LIBSSH2_ERROR_EAGAIN: i32 = -37;
+
+/// Sends keepalive requests and reports whether the peer answered.
+pub trait KeepaliveResponder: Send + 'static {
+    /// Send a keepalive and wait up to `timeout` for the reply.
+    ///
+    /// Returns `true` if the peer answered in time.
+    fn probe(&mut self, timeout: Duration) -> bool;
+}
+
+/// Counts consecutive unanswered keepalives.
+#[derive(Debug)]
+pub struct KeepaliveCounter {
+    count_max: u32,
+    misses: u32,
+}
+
+impl KeepaliveCounter {
+    /// Create a counter that trips after `count_max` consecutive misses
+    /// (at least one).
+    pub fn new(count_max: u32) -> Self {
+        Self {
+            count_max: count_max.max(1),
+            misses: 0,
+        }
+    }
+
+    /// Record the outcome of one keepalive. An answer resets the count.
+    ///
+    /// Returns `true` once `count_max` consecutive keepalives have gone
+    /// unanswered.
+    pub fn record(&mut self, answered: bool) -> bool {
+        if answered {
+            self.misses = 0;
+        } else {
+            self.misses += 1;
+        }
+        self.misses >= self.count_max
+    }
+}
+
+/// Probe the peer every `interval` until it stops answering or `alive` is
+/// cleared.
+///
+/// Returns `true` when the peer was declared lost after `count_max`
+/// consecutive unanswered keepalives, `false` when the session ended
+/// for another reason.
+pub fn run_keepalive(
+    responder: &mut dyn KeepaliveResponder,
+    interval: Duration,
+    count_max: u32,
+    alive: &AtomicBool,
+) -> bool {
+    let mut counter = KeepaliveCounter::new(count_max);
+    if !sleep_while_alive(interval, alive) {
+        return false;
+    }
+    loop {
+        let started = Instant::now();
+        let answered = responder.probe(interval);
+        if !alive.load(Ordering::SeqCst) {
+            return false;
+        }
+        if counter.record(answered) {
+            return true;
+        }
+        // Keep the cadence at one probe per interval.
+        if !sleep_while_alive(interval.saturating_sub(started.elapsed()), alive) {
+            return false;
+        }
+    }
+}
+
+/// Sleep for `duration` unless `alive` is cleared first. Returns whether
+/// the session is still alive.
+fn sleep_while_alive(duration: Duration, alive: &AtomicBool) -> bool {
+    let deadline = Instant::now() + duration;
+    while alive.load(Ordering::SeqCst) {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return true;
+        }
+        std::thread::sleep(remaining.min(POLL_STEP));
+    }
+    false
+}
+
+/// Keepalive responder for a libssh2 shell channel.
+///
+/// libssh2 does not report replies to its own keepalive messages, so the
+/// probe is a channel `env` request with want-reply set, whose success or
+/// failure reply is observable. An unanswered request stays pending and
+/// is resumed by the next probe.
+pub struct Ssh2KeepaliveResponder {
+    channel: Arc<Mutex<ssh2::Channel>>,
+}
+
+impl Ssh2KeepaliveResponder {
+    pub fn new(channel: Arc<Mutex<ssh2::Channel>>) -> Self {
+        Self { channel }
+    }
+}
+
+impl KeepaliveResponder for Ssh2KeepaliveResponder {
+    fn probe(&mut self, timeout: Duration) -> bool {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let result = match self.channel.lock() {
+                Ok(mut channel) => channel.setenv(PROBE_VARIABLE, "1"),
+                Err(_) => return false,
+            };
+            match result {
+                Ok(()) => return true,
+                Err(e) => match e.code() {
+                    ssh2::ErrorCode::Session(LIBSSH2_ERROR_EAGAIN) => {}
+                    // The server refused the request, which is an answer.
+                    ssh2::ErrorCode::Session(LIBSSH2_ERROR_CHANNEL_REQUEST_DENIED) => return true,
+                    _ => return false,
+                },
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            std::thread::sleep(POLL_STEP);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Answers the first `answers` probes, then goes silent.
+    struct ScriptedResponder {
+        answers: usize,
+        probes: Arc<AtomicUsize>,
+    }
+
+    impl KeepaliveResponder for ScriptedResponder {
+        fn probe(&mut self, _timeout: Duration) -> bool {
+            let n = self.probes.fetch_add(1, Ordering::SeqCst);
+            n < self.answers
+        }
+    }
+
+    #[test]
+    fn counter_trips_after_consecutive_misses() {
+        let mut counter = KeepaliveCounter::new(3);
+        assert!(!counter.record(false));
+        assert!(!counter.record(false));
+        assert!(!counter.record(true));
+        assert!(!counter.record(false));
+        assert!(!counter.record(false));
+        assert!(counter.record(false));
+    }
+
+    #[test]
+    fn counter_needs_at_least_one_miss() {
+        let mut counter = KeepaliveCounter::new(0);
+        assert!(!counter.record(true));
+        assert!(counter.record(false));
+    }
+
+    #[test]
+    fn peer_lost_after_count_max_unanswered_probes() {
+        let probes = Arc::new(AtomicUsize::new(0));
+        let mut responder = ScriptedResponder {
+            answers: 2,
+            probes: probes.clone(),
+        };
+        let alive = AtomicBool::new(true);
+
+        let lost = run_keepalive(&mut responder, Duration::from_millis(5), 3, &alive);
+
+        assert!(lost);
+        assert_eq!(probes.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn stops_without_teardown_when_session_ends() {
+        let probes = Arc::new(AtomicUsize::new(0));
+        let mut responder = ScriptedResponder {
+            answers: usize::MAX,
+            probes: probes.clone(),
+        };
+        let alive = Arc::new(AtomicBool::new(true));
+        let alive_for_thread = alive.clone();
+        let handle = std::thread::spawn(move || {
+            run_keepalive(
+                &mut responder,
+                Duration::from_millis(5),
+                1,
+                &alive_for_thread,
+            )
+        });
+
+        std::thread::sleep(Duration::from_millis(50));
+        alive.store(false, Ordering::SeqCst);
+
+        assert!(!handle.join().unwrap());
+        assert!(probes.load(Ordering::SeqCst) > 0);
+    }
+}
@@ -0,0 +1,238 @@
+//! SSH keypair generation.
+//!
+//! Keys are generated in-process with the pure-Rust `ssh-key` crate and
+//! written in OpenSSH format: the private key to `out_path` (mode `0600`
+//! on Unix) and the public key to `out_path.pub`. Existing files are never
+//! overwritten.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use ssh_key::private::{KeypairData, RsaKeypair};
+use ssh_key::{Algorithm, LineEnding, PrivateKey};
+
+use crate::errors::{CoreError, FileError};
+
+/// RSA key size used when none is given.
+pub const DEFAULT_RSA_BITS: usize = 4096;
+
+/// Smallest accepted RSA key size.
+pub const MIN_RSA_BITS: usize = 2048;
+
+/// Largest accepted RSA key size.
+pub const MAX_RSA_BITS: usize = 16384;
+
+/// Key algorithm to generate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SshKeyType {
+    Ed25519,
+    Rsa,
+}
+
+/// Path of the public key written next to the private key at `out_path`.
+pub fn public_key_path(out_path: &Path) -> PathBuf {
+    let mut path = out_path.as_os_str().to_owned();
+    path.push(".pub");
+    PathBuf::from(path)
+}
+
+/// Generate a keypair and write it to `out_path` and `out_path.pub`.
+///
+/// `bits` applies to RSA only (default [`DEFAULT_RSA_BITS`]). A non-empty
+/// `passphrase` encrypts the private key. Returns the public key line for
+/// pasting into `authorized_keys`.
+pub fn generate_key(
+    key_type: SshKeyType,
+    bits: Option<usize>,
+    passphrase: Option<&str>,
+    comment: &str,
+    out_path: &Path,
+) -> Result<String, CoreError> {
+    let pub_path = public_key_path(out_path);
+    for path in [out_path, pub_path.as_path()] {
+        if path.exists() {
+            return Err(FileError::DestinationExists(path.display().to_string()).into());
+        }
+    }
+
+    let mut rng = rand::thread_rng();
+    let mut key = match key_type {
+        SshKeyType::Ed25519 => PrivateKey::random(&mut rng, Algorithm::Ed25519),
+        SshKeyType::Rsa => {
+            let bits = bits.unwrap_or(DEFAULT_RSA_BITS);
+            if !(MIN_RSA_BITS..=MAX_RSA_BITS).contains(&bits) {
+                return Err(CoreError::Config(format!(
+                    "RSA key size must be between {MIN_RSA_BITS} and {MAX_RSA_BITS} bits, got {bits}"
+                )));
+            }
+            RsaKeypair::random(&mut rng, bits)
+                .and_then(|pair| PrivateKey::new(KeypairData::from(pair), comment))
+        }
+    }
+    .map_err(|e| CoreError::Other(format!("Key generation failed: {e}")))?;
+    key.set_comment(comment);
+    let public_key = key
+        .public_key()
+        .to_openssh()
+        .map_err(|e| CoreError::Other(format!("Failed to encode public key: {e}")))?;
+
+    let key = match passphrase.filter(|p| !p.is_empty()) {
+        Some(passphrase) => key
+            .encrypt(&mut rng, passphrase)
+            .map_err(|e| CoreError::Other(format!("Failed to encrypt private key: {e}")))?,
+        None => key,
+    };
+    let pem = key
+        .to_openssh(LineEnding::LF)
+        .map_err(|e| CoreError::Other(format!("Failed to encode private key: {e}")))?;
+
+    if let Some(parent) = out_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        create_key_dir(parent)?;
+    }
+    write_new_file(out_path, pem.as_bytes(), 0o600)?;
+    write_new_file(&pub_path, format!("{public_key}\n").as_bytes(), 0o644)?;
+
+    Ok(public_key)
+}
+
+/// Create the key directory (mode `0700` on Unix, like `~/.ssh`).
+fn create_key_dir(dir: &Path) -> std::io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)
+}
+
+/// Write `data` to a file that must not exist yet, with `mode` on Unix.
+fn write_new_file(path: &Path, data: &[u8], mode: u32) -> std::io::Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, mode);
+    #[cfg(not(unix))]
+    let _ = mode;
+    options.open(path)?.write_all(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ssh_key::PublicKey;
+    use tempfile::TempDir;
+
+    #[cfg(unix)]
+    fn mode(path: &Path) -> u32 {
+        use std::os::unix::fs::PermissionsExt;
+        fs::metadata(path).unwrap().permissions().mode() & 0o777
+    }
+
+    #[test]
+    fn public_key_path_appends_pub() {
+        assert_eq!(
+            public_key_path(Path::new("/home/u/.ssh/id_ed25519")),
+            PathBuf::from("/home/u/.ssh/id_ed25519.pub")
+        );
+    }
+
+    #[test]
+    fn generates_valid_ed25519_keypair() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("keys").join("id_ed25519");
+
+        let public = generate_key(SshKeyType::Ed25519, None, None, "alice@laptop", &out).unwrap();
+        assert!(public.starts_with("ssh-ed25519 "));
+        assert!(public.ends_with(" alice@laptop"));
+
+        let private = PrivateKey::read_openssh_file(&out).unwrap();
+        assert!(!private.is_encrypted());
+        assert_eq!(private.algorithm(), Algorithm::Ed25519);
+        assert_eq!(private.comment(), "alice@laptop");
+
+        let written = PublicKey::read_openssh_file(&public_key_path(&out)).unwrap();
+        assert_eq!(written.key_data(), private.public_key().key_data());
+        assert_eq!(written.to_openssh().unwrap(), public);
+
+        #[cfg(unix)]
+        {
+            assert_eq!(mode(&out), 0o600);
+            assert_eq!(mode(out.parent().unwrap()), 0o700);
+        }
+    }
+
+    #[test]
+    fn generates_valid_rsa_keypair() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("id_rsa");
+
+        let public =
+            generate_key(SshKeyType::Rsa, Some(MIN_RSA_BITS), None, "deploy", &out).unwrap();
+        assert!(public.starts_with("ssh-rsa "));
+
+        let private = PrivateKey::read_openssh_file(&out).unwrap();
+        assert_eq!(private.algorithm(), Algorithm::Rsa { hash: None });
+        let rsa = private.key_data().rsa().unwrap();
+        assert_eq!(
+            rsa.public.n.as_positive_bytes().unwrap().len() * 8,
+            MIN_RSA_BITS
+        );
+        assert_eq!(private.public_key().to_openssh().unwrap(), public);
+
+        #[cfg(unix)]
+        assert_eq!(mode(&out), 0o600);
+    }
+
+    #[test]
+    fn passphrase_encrypts_private_key() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("id_ed25519");
+
+        let public = generate_key(SshKeyType::Ed25519, None, Some("s3cret"), "ci", &out).unwrap();
+
+        let private = PrivateKey::read_openssh_file(&out).unwrap();
+        assert!(private.is_encrypted());
+        assert!(private.decrypt("wrong").is_err());
+        let decrypted = private.decrypt("s3cret").unwrap();
+        assert_eq!(decrypted.public_key().to_openssh().unwrap(), public);
+    }
+
+    #[test]
+    fn rejects_invalid_rsa_size() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("id_rsa");
+        let err = generate_key(SshKeyType::Rsa, Some(1024), None, "", &out).unwrap_err();
+        assert!(matches!(err, CoreError::Config(_)));
+        assert!(!out.exists());
+    }
+
+    #[test]
+    fn refuses_to_overwrite_existing_key() {
+        let dir = TempDir::new().unwrap();
+        let out = dir.path().join("id_ed25519");
+        fs::write(public_key_path(&out), "existing").unwrap();
+
+        let err = generate_key(SshKeyType::Ed25519, None, None, "", &out).unwrap_err();
+        assert!(matches!(
+            err,
+            CoreError::File(FileError::DestinationExists(_))
+        ));
+        assert!(!out.exists());
+        assert_eq!(
+            fs::read_to_string(public_key_path(&out)).unwrap(),
+            "existing"
+        );
+    }
+
+    #[test]
+    fn key_type_serde_lowercase() {
+        let t: SshKeyType = serde_json::from_value(serde_json::json!("rsa")).unwrap();
+        assert_eq!(t, SshKeyType::Rsa);
+        assert_eq!(
+            serde_json::to_value(SshKeyType::Ed25519).unwrap(),
+            serde_json::json!("ed25519")
+        );
+    }
+}
@@ -4,32 +4,53 @@
 //! and file browsing (SFTP). This is the canonical SSH implementation,
 //! used by both the desktop and agent crates.
 
+pub mod archive;
 pub mod auth;
 pub mod connector;
+pub mod exec;
 mod file_browser;
+pub mod forward;
+pub mod host_key;
+pub mod jump;
+pub mod keepalive;
+pub mod keygen;
 mod monitoring;
+mod nonblocking;
+pub mod restricted;
+pub mod sudo;
+pub mod transfer;
 pub mod x11;
 
 use std::io::Read;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-use crate::config::SshConfig;
+use crate::config::{
+    SshConfig, SshForward, DEFAULT_KEEPALIVE_COUNT_MAX, DEFAULT_SSH_CONNECT_TIMEOUT_SECS,
+    DEFAULT_STRICT_HOST_KEY_CHECKING,
+};
 use crate::connection::{
-    Capabilities, Condition, ConnectionType, FieldType, FilePathKind, OutputReceiver, OutputSender,
-    SelectOption, SettingsField, SettingsGroup, SettingsSchema,
+    cleanup_policy_field, Capabilities, CloseReason, Condition, ConnectionType, FieldType,
+    FilePathKind, HostKeyFingerprint, OutputReceiver, OutputSender, SelectOption, SessionNotice,
+    SettingsField, SettingsGroup, SettingsSchema,
 };
 use crate::errors::SessionError;
 use crate::files::FileBrowser;
 use crate::monitoring::MonitoringProvider;
+use crate::output::sudo_prompt::{DEFAULT_SUDO_MAX_ANSWERS, DEFAULT_SUDO_PROMPT_PATTERN};
 use crate::session::shell::osc7_setup_command;
 use crate::session::ssh::validate_ssh_config;
 
-use self::connector::{Ssh2SshConnector, SshConnector};
+use self::connector::{ForwardGuard, OpenForwardsFn, Ssh2SshConnector, SshConnector};
 use self::file_browser::SftpFileBrowser;
+use self::forward::SessionForwardStatus;
+use self::keepalive::run_keepalive;
 use self::monitoring::SshMonitoringProvider;
+use self::restricted::{detect_restricted_shell, restricted_shell_notice, sftp_available};
+
+pub use self::file_browser::sftp_mkdir_all;
 
 /// Channel capacity for output data from the SSH reader thread.
 const OUTPUT_CHANNEL_CAPACITY: usize = 64;
@@ -57,6 +78,17 @@ pub struct Ssh {
     monitoring_provider: Option<SshMonitoringProvider>,
     /// File browser provider (SFTP), created on connect.
     file_browser_provider: Option<SftpFileBrowser>,
+    /// Status of the session-bundled port forwards, set on connect.
+    forward_statuses: Vec<SessionForwardStatus>,
+    /// Set by the keepalive thread when it tears the session down.
+    close_reason: Arc<Mutex<Option<CloseReason>>>,
+    /// Fingerprint of the server's host key, set on connect.
+    host_key: Option<HostKeyFingerprint>,
+    /// The login shell is restricted (`rbash`), so monitoring is off for
+    /// this session.
+    restricted_shell: bool,
+    /// SFTP failed on a restricted shell, so file browsing is off too.
+    sftp_unavailable: bool,
 }
 
 type WriteFn = Arc<dyn Fn(&[u8]) -> Result<(), SessionError> + Send + Sync>;
@@ -72,6 +104,8 @@ struct ConnectedState {
     alive: Arc<AtomicBool>,
     /// Keeps opaque resources alive for the session lifetime (e.g. X11Forwarder).
     _extensions: Vec<Box<dyn std::any::Any + Send>>,
+    /// Keeps the session-bundled port forwards open; dropped on disconnect.
+    _forwards: Option<ForwardGuard>,
 }
 
 impl Ssh {
@@ -88,6 +122,53 @@ impl Ssh {
             output_tx: Arc::new(Mutex::new(None)),
             monitoring_provider: None,
             file_browser_provider: None,
+            forward_statuses: Vec::new(),
+            close_reason: Arc::new(Mutex::new(None)),
+            host_key: None,
+            restricted_shell: false,
+            sftp_unavailable: false,
+        }
+    }
+
+    /// Status of the port forwards opened alongside the terminal.
+    ///
+    /// Empty when disconnected or when no `sessionForwards` are configured.
+    pub fn session_forwards(&self) -> &[SessionForwardStatus] {
+        &self.forward_statuses
+    }
+
+    /// Open the configured session forwards on the shell's connection,
+    /// never failing the terminal itself: errors are reported through the
+    /// per-forward status.
+    fn open_session_forwards(
+        &mut self,
+        open_forwards: Option<OpenForwardsFn>,
+        config: &SshConfig,
+        alive: Arc<AtomicBool>,
+    ) -> Option<ForwardGuard> {
+        if config.session_forwards.is_empty() {
+            return None;
+        }
+        let result = match open_forwards {
+            Some(open_forwards) => open_forwards(config, alive),
+            None => Err(SessionError::SpawnFailed(
+                "Port forwarding is not available on this connection".to_string(),
+            )),
+        };
+        match result {
+            Ok((guard, statuses)) => {
+                self.forward_statuses = statuses;
+                Some(guard)
+            }
+            Err(e) => {
+                warn!("Session forwards could not be opened: {e}");
+                self.forward_statuses = config
+                    .session_forwards
+                    .iter()
+                    .map(|f| SessionForwardStatus::failed(f, e.to_string()))
+                    .collect();
+                None
+            }
         }
     }
 }
@@ -121,6 +202,16 @@ pub fn parse_ssh_settings(settings: &serde_json::Value) -> SshConfig {
             .unwrap_or(default)
     };
     let opt_bool = |key: &str| -> Option<bool> { settings.get(key).and_then(|v| v.as_bool()) };
+    let u32_field = |key: &str, default: u32| -> u32 {
+        settings
+            .get(key)
+            .and_then(|v| {
+                v.as_u64()
+                    .and_then(|n| u32::try_from(n).ok())
+                    .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+            })
+            .unwrap_or(default)
+    };
 
     let port: u16 = settings
         .get("port")
@@ -145,21 +236,99 @@ pub fn parse_ssh_settings(settings: &serde_json::Value) -> SshConfig {
         })
         .unwrap_or_default();
 
+    let session_forwards = settings
+        .get("sessionForwards")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|item| {
+                    let port = |key: &str| -> Option<u16> {
+                        let v = item.get(key)?;
+                        v.as_u64()
+                            .and_then(|n| u16::try_from(n).ok())
+                            .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+                    };
+                    let remote_host = item
+                        .get("remoteHost")
+                        .and_then(|v| v.as_str())
+                        .filter(|s| !s.is_empty())
+                        .unwrap_or("localhost");
+                    Some(SshForward {
+                        local_port: port("localPort")?,
+                        remote_host: remote_host.to_string(),
+                        remote_port: port("remotePort")?,
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Accept plain strings or `{"path": ...}` objects (schema object list).
+    let identity_files = settings
+        .get("identityFiles")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|item| item.as_str().or_else(|| item.get("path")?.as_str()))
+                .filter(|s| !s.trim().is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let auth_fallback = settings
+        .get("authFallback")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Resolved jump hosts are full SSH settings objects of their own.
+    let jump_hosts = settings
+        .get("jumpHosts")
+        .and_then(|v| v.as_array())
+        .map(|arr| arr.iter().map(parse_ssh_settings).collect())
+        .unwrap_or_default();
+
     SshConfig {
         host: str_field("host"),
         port,
         username: str_field("username"),
         auth_method: str_field("authMethod"),
+        auth_fallback,
         password: opt_str("password"),
+        fallback_password: opt_str("fallbackPassword"),
         key_path: opt_str("keyPath"),
+        identity_files,
         shell: opt_str("shell"),
         cols: 80,
         rows: 24,
         env,
+        locale: opt_str("locale"),
         enable_x11_forwarding: bool_field("enableX11Forwarding", false),
         enable_monitoring: opt_bool("enableMonitoring"),
         enable_file_browser: opt_bool("enableFileBrowser"),
         save_password: opt_bool("savePassword"),
+        session_forwards,
+        transfer_compression: bool_field("transferCompression", false),
+        bind_address: opt_str("bindAddress"),
+        ssh_config_file: opt_str("sshConfigFile"),
+        proxy_jump: opt_str("proxyJump"),
+        jump_hosts,
+        connect_timeout_secs: u32_field("connectTimeoutSecs", DEFAULT_SSH_CONNECT_TIMEOUT_SECS),
+        keepalive_interval: u32_field("keepaliveInterval", 0),
+        keepalive_count_max: u32_field("keepaliveCountMax", DEFAULT_KEEPALIVE_COUNT_MAX),
+        strict_host_key_checking: opt_str("strictHostKeyChecking")
+            .unwrap_or_else(|| DEFAULT_STRICT_HOST_KEY_CHECKING.to_string()),
+        known_hosts_file: opt_str("knownHostsFile"),
+        file_browser_root: opt_str("fileBrowserRoot"),
+        monitoring_metrics: opt_str("monitoringMetrics"),
+        editor_command: opt_str("editorCommand"),
+        sudo_prefix: opt_str("sudoPrefix"),
     }
 }
 
@@ -221,6 +390,40 @@ impl ConnectionType for Ssh {
                             supports_tilde_expansion: false,
                             visible_when: None,
                         },
+                        SettingsField {
+                            key: "sshConfigFile".to_string(),
+                            label: "SSH Config File".to_string(),
+                            description: Some(
+                                "OpenSSH config file used for defaults; the host above is looked up as a Host alias"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::FilePath {
+                                kind: FilePathKind::File,
+                            },
+                            required: false,
+                            default: None,
+                            placeholder: Some("~/.ssh/config".to_string()),
+                            supports_env_expansion: true,
+                            supports_tilde_expansion: true,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "proxyJump".to_string(),
+                            label: "Jump Hosts".to_string(),
+                            description: Some(
+                                "Comma-separated [user@]host[:port] hops or saved connection IDs to connect through, outermost first"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Text,
+                            required: false,
+                            default: None,
+                            placeholder: Some("user@bastion:22".to_string()),
+                            supports_env_expansion: true,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
                     ],
                 },
                 SettingsGroup {
@@ -289,6 +492,71 @@ impl ConnectionType for Ssh {
                                 equals: serde_json::json!("key"),
                             }),
                         },
+                        SettingsField {
+                            key: "identityFiles".to_string(),
+                            label: "Additional Keys".to_string(),
+                            description: Some(
+                                "Further private keys tried in order if the key above is rejected"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::ObjectList {
+                                fields: vec![SettingsField {
+                                    key: "path".to_string(),
+                                    label: "Key Path".to_string(),
+                                    description: None,
+                                    help_text: None,
+                                    field_type: FieldType::FilePath {
+                                        kind: FilePathKind::File,
+                                    },
+                                    required: true,
+                                    default: None,
+                                    placeholder: Some("~/.ssh/id_ed25519".to_string()),
+                                    supports_env_expansion: true,
+                                    supports_tilde_expansion: true,
+                                    visible_when: None,
+                                }],
+                            },
+                            required: false,
+                            default: None,
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: Some(Condition {
+                                field: "authMethod".to_string(),
+                                equals: serde_json::json!("key"),
+                            }),
+                        },
+                        SettingsField {
+                            key: "authFallback".to_string(),
+                            label: "Fallback Methods".to_string(),
+                            description: Some(
+                                "Methods tried in order if the method above fails".to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::MultiSelect {
+                                options: vec![
+                                    SelectOption {
+                                        value: "key".to_string(),
+                                        label: "SSH Key".to_string(),
+                                    },
+                                    SelectOption {
+                                        value: "password".to_string(),
+                                        label: "Password".to_string(),
+                                    },
+                                    SelectOption {
+                                        value: "agent".to_string(),
+                                        label: "SSH Agent".to_string(),
+                                    },
+                                ],
+                            },
+                            required: false,
+                            default: None,
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
                         SettingsField {
                             key: "savePassword".to_string(),
                             label: "Save credentials".to_string(),
@@ -320,71 +588,469 @@ impl ConnectionType for Ssh {
                             key: "shell".to_string(),
                             label: "Shell".to_string(),
                             description: Some(
-                                "Remote shell to use (leave empty for default)".to_string(),
+                                "Remote shell to use (leave empty for default)".to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Text,
+                            required: false,
+                            default: None,
+                            placeholder: Some("/bin/bash".to_string()),
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "sudoPrefix".to_string(),
+                            label: "sudo Prefix".to_string(),
+                            description: Some(
+                                "Run the shell and commands through this prefix, e.g. sudo -S"
+                                    .to_string(),
+                            ),
+                            help_text: Some(concat!(
+                                "When set, the terminal starts as a root login shell ",
+                                "(`<prefix> -i`) instead of your own shell, and one-shot ",
+                                "commands run as `<prefix> sh -c '...'`.\n\n",
+                                "Everything typed in the session then runs with elevated ",
+                                "privileges, with no `sudo` in the command to remind you. ",
+                                "Only use it on hosts you administer. One-shot commands cannot ",
+                                "answer a password prompt, so they need passwordless sudo ",
+                                "(e.g. `sudo -n` with a NOPASSWD rule). Monitoring and file ",
+                                "browsing still run as the login user.",
+                            ).to_string()),
+                            field_type: FieldType::Text,
+                            required: false,
+                            default: None,
+                            placeholder: Some("sudo -S".to_string()),
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "enableX11Forwarding".to_string(),
+                            label: "X11 Forwarding".to_string(),
+                            description: Some(
+                                "Forward X11 display from remote to local".to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Boolean,
+                            required: false,
+                            default: Some(serde_json::json!(true)),
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "transferCompression".to_string(),
+                            label: "Compress File Transfers".to_string(),
+                            description: Some(
+                                "Use SSH compression for SFTP uploads and downloads".to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Boolean,
+                            required: false,
+                            default: Some(serde_json::json!(false)),
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "bindAddress".to_string(),
+                            label: "Source Address".to_string(),
+                            description: Some(
+                                "Local IP address to connect from (leave empty for default)"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Text,
+                            required: false,
+                            default: None,
+                            placeholder: Some("192.168.10.5".to_string()),
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "connectTimeoutSecs".to_string(),
+                            label: "Connect Timeout (s)".to_string(),
+                            description: Some(
+                                "Seconds to wait for the server to accept the connection"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Number {
+                                min: Some(1.0),
+                                max: Some(600.0),
+                            },
+                            required: false,
+                            default: Some(serde_json::json!(DEFAULT_SSH_CONNECT_TIMEOUT_SECS)),
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "keepaliveInterval".to_string(),
+                            label: "Keepalive Interval".to_string(),
+                            description: Some(
+                                "Seconds between keepalive requests (0 disables them)"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Number {
+                                min: Some(0.0),
+                                max: Some(3600.0),
+                            },
+                            required: false,
+                            default: Some(serde_json::json!(0)),
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "keepaliveCountMax".to_string(),
+                            label: "Keepalive Count Max".to_string(),
+                            description: Some(
+                                "Unanswered keepalives before the connection is closed"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Number {
+                                min: Some(1.0),
+                                max: Some(100.0),
+                            },
+                            required: false,
+                            default: Some(serde_json::json!(DEFAULT_KEEPALIVE_COUNT_MAX)),
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "strictHostKeyChecking".to_string(),
+                            label: "Host Key Checking".to_string(),
+                            description: Some(
+                                "How the server's host key is checked against known_hosts"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Select {
+                                options: vec![
+                                    SelectOption {
+                                        value: "accept-new".to_string(),
+                                        label: "Accept new hosts".to_string(),
+                                    },
+                                    SelectOption {
+                                        value: "strict".to_string(),
+                                        label: "Strict".to_string(),
+                                    },
+                                    SelectOption {
+                                        value: "off".to_string(),
+                                        label: "Off (insecure)".to_string(),
+                                    },
+                                ],
+                            },
+                            required: false,
+                            default: Some(serde_json::json!(DEFAULT_STRICT_HOST_KEY_CHECKING)),
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "knownHostsFile".to_string(),
+                            label: "Known Hosts File".to_string(),
+                            description: Some(
+                                "File host keys are checked against and recorded in".to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::FilePath {
+                                kind: FilePathKind::File,
+                            },
+                            required: false,
+                            default: None,
+                            placeholder: Some("~/.ssh/known_hosts".to_string()),
+                            supports_env_expansion: true,
+                            supports_tilde_expansion: true,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "fileBrowserRoot".to_string(),
+                            label: "File Browser Root".to_string(),
+                            description: Some(
+                                "Directory the file browser opens to (defaults to the remote home)"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Text,
+                            required: false,
+                            default: None,
+                            placeholder: Some("~".to_string()),
+                            supports_env_expansion: true,
+                            supports_tilde_expansion: true,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "monitoringMetrics".to_string(),
+                            label: "Monitoring Metrics".to_string(),
+                            description: Some(
+                                "Comma-separated metric groups to collect: cpu, mem, disk, net, processes"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Text,
+                            required: false,
+                            default: None,
+                            placeholder: Some("cpu,mem,disk".to_string()),
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "editorCommand".to_string(),
+                            label: "Editor Command".to_string(),
+                            description: Some(
+                                "Local editor for this connection's files; {path} is replaced with the file"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Text,
+                            required: false,
+                            default: None,
+                            placeholder: Some("code --wait {path}".to_string()),
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "locale".to_string(),
+                            label: "Locale".to_string(),
+                            description: Some(
+                                "Sets LANG and LC_ALL in the remote shell unless set below"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::Text,
+                            required: false,
+                            default: None,
+                            placeholder: Some("en_US.UTF-8".to_string()),
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "env".to_string(),
+                            label: "Environment Variables".to_string(),
+                            description: Some(
+                                "Additional environment variables for the remote shell".to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::KeyValueList,
+                            required: false,
+                            default: None,
+                            placeholder: None,
+                            supports_env_expansion: true,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "sessionForwards".to_string(),
+                            label: "Port Forwards".to_string(),
+                            description: Some(
+                                "Local port forwards opened with the terminal and closed with it"
+                                    .to_string(),
+                            ),
+                            help_text: None,
+                            field_type: FieldType::ObjectList {
+                                fields: vec![
+                                    SettingsField {
+                                        key: "localPort".to_string(),
+                                        label: "Local Port".to_string(),
+                                        description: Some(
+                                            "Port to listen on at 127.0.0.1".to_string(),
+                                        ),
+                                        help_text: None,
+                                        field_type: FieldType::Port,
+                                        required: true,
+                                        default: None,
+                                        placeholder: Some("8080".to_string()),
+                                        supports_env_expansion: false,
+                                        supports_tilde_expansion: false,
+                                        visible_when: None,
+                                    },
+                                    SettingsField {
+                                        key: "remoteHost".to_string(),
+                                        label: "Remote Host".to_string(),
+                                        description: Some(
+                                            "Destination host as seen from the SSH server"
+                                                .to_string(),
+                                        ),
+                                        help_text: None,
+                                        field_type: FieldType::Text,
+                                        required: true,
+                                        default: Some(serde_json::json!("localhost")),
+                                        placeholder: Some("localhost".to_string()),
+                                        supports_env_expansion: false,
+                                        supports_tilde_expansion: false,
+                                        visible_when: None,
+                                    },
+                                    SettingsField {
+                                        key: "remotePort".to_string(),
+                                        label: "Remote Port".to_string(),
+                                        description: Some("Destination port".to_string()),
+                                        help_text: None,
+                                        field_type: FieldType::Port,
+                                        required: true,
+                                        default: None,
+                                        placeholder: Some("80".to_string()),
+                                        supports_env_expansion: false,
+                                        supports_tilde_expansion: false,
+                                        visible_when: None,
+                                    },
+                                ],
+                            },
+                            required: false,
+                            default: None,
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "shellIntegration".to_string(),
+                            label: "Shell Integration".to_string(),
+                            description: Some(
+                                "Inject OSC 7 CWD tracking at startup (used by the file browser)"
+                                    .to_string(),
+                            ),
+                            help_text: Some(concat!(
+                                "When enabled, termiHub injects a small shell function at startup ",
+                                "that emits OSC 7 (current working directory) sequences on every prompt.\n\n",
+                                "This lets the file browser automatically follow the current directory ",
+                                "as you navigate in the shell.\n\n",
+                                "The setup runs visibly in the terminal — you can always see what ",
+                                "termiHub is doing. Disable this if you manage your own shell ",
+                                "integration or prefer a clean terminal start.",
+                            ).to_string()),
+                            field_type: FieldType::Boolean,
+                            required: false,
+                            default: Some(serde_json::json!(true)),
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "rawKeyMode".to_string(),
+                            label: "Raw Key Mode".to_string(),
+                            description: Some(
+                                "Send all keystrokes to the session, including app shortcuts"
+                                    .to_string(),
+                            ),
+                            help_text: Some(concat!(
+                                "When enabled, every key pressed in this session's terminal is sent ",
+                                "to the remote side, including combinations termiHub normally uses ",
+                                "for its own shortcuts (copy, paste, tab switching and so on).\n\n",
+                                "Use this for full-screen programs such as editors or multiplexers ",
+                                "that rely on those keys.",
+                            ).to_string()),
+                            field_type: FieldType::Boolean,
+                            required: false,
+                            default: Some(serde_json::json!(false)),
+                            placeholder: None,
+                            supports_env_expansion: false,
+                            supports_tilde_expansion: false,
+                            visible_when: None,
+                        },
+                        SettingsField {
+                            key: "binaryOutputGuard".to_string(),
+                            label: "Binary Output Guard".to_string(),
+                            description: Some(
+                                "Neutralize control bytes when binary data is printed".to_string(),
                             ),
-                            help_text: None,
-                            field_type: FieldType::Text,
+                            help_text: Some(concat!(
+                                "When enabled, output that is mostly non-printable, such as a ",
+                                "binary file printed with cat, has its control characters shown ",
+                                "as \u{FFFD} instead of being sent to the terminal, where they ",
+                                "could switch its character set or leave it unusable.\n\n",
+                                "Regular output, including colors, is not affected.",
+                            ).to_string()),
+                            field_type: FieldType::Boolean,
                             required: false,
-                            default: None,
-                            placeholder: Some("/bin/bash".to_string()),
+                            default: Some(serde_json::json!(false)),
+                            placeholder: None,
                             supports_env_expansion: false,
                             supports_tilde_expansion: false,
                             visible_when: None,
                         },
                         SettingsField {
-                            key: "enableX11Forwarding".to_string(),
-                            label: "X11 Forwarding".to_string(),
+                            key: "sudoAutoFill".to_string(),
+                            label: "Auto-fill sudo Password".to_string(),
                             description: Some(
-                                "Forward X11 display from remote to local".to_string(),
+                                "Answer sudo password prompts with the login password".to_string(),
                             ),
-                            help_text: None,
+                            help_text: Some(concat!(
+                                "When enabled, termiHub watches the terminal output for a sudo ",
+                                "password prompt and answers it with the password used to log in ",
+                                "(from the credential store or the connect prompt).\n\n",
+                                "Only available with password authentication. To avoid sending a ",
+                                "rejected password repeatedly, only a limited number of prompts ",
+                                "are answered per session.",
+                            ).to_string()),
                             field_type: FieldType::Boolean,
                             required: false,
-                            default: Some(serde_json::json!(true)),
+                            default: Some(serde_json::json!(false)),
                             placeholder: None,
                             supports_env_expansion: false,
                             supports_tilde_expansion: false,
-                            visible_when: None,
+                            visible_when: Some(Condition {
+                                field: "authMethod".to_string(),
+                                equals: serde_json::json!("password"),
+                            }),
                         },
                         SettingsField {
-                            key: "env".to_string(),
-                            label: "Environment Variables".to_string(),
+                            key: "sudoPromptPattern".to_string(),
+                            label: "sudo Prompt Pattern".to_string(),
                             description: Some(
-                                "Additional environment variables for the remote shell".to_string(),
+                                "Regular expression matching the whole prompt line (leave empty for default)"
+                                    .to_string(),
                             ),
                             help_text: None,
-                            field_type: FieldType::KeyValueList,
+                            field_type: FieldType::Text,
                             required: false,
                             default: None,
-                            placeholder: None,
-                            supports_env_expansion: true,
+                            placeholder: Some(DEFAULT_SUDO_PROMPT_PATTERN.to_string()),
+                            supports_env_expansion: false,
                             supports_tilde_expansion: false,
-                            visible_when: None,
+                            visible_when: Some(Condition {
+                                field: "sudoAutoFill".to_string(),
+                                equals: serde_json::json!(true),
+                            }),
                         },
                         SettingsField {
-                            key: "shellIntegration".to_string(),
-                            label: "Shell Integration".to_string(),
+                            key: "sudoAutoFillMax".to_string(),
+                            label: "Max Auto-fills".to_string(),
                             description: Some(
-                                "Inject OSC 7 CWD tracking at startup (used by the file browser)"
-                                    .to_string(),
+                                "Maximum number of prompts answered per session".to_string(),
                             ),
-                            help_text: Some(concat!(
-                                "When enabled, termiHub injects a small shell function at startup ",
-                                "that emits OSC 7 (current working directory) sequences on every prompt.\n\n",
-                                "This lets the file browser automatically follow the current directory ",
-                                "as you navigate in the shell.\n\n",
-                                "The setup runs visibly in the terminal — you can always see what ",
-                                "termiHub is doing. Disable this if you manage your own shell ",
-                                "integration or prefer a clean terminal start.",
-                            ).to_string()),
-                            field_type: FieldType::Boolean,
+                            help_text: None,
+                            field_type: FieldType::Number {
+                                min: Some(1.0),
+                                max: Some(10.0),
+                            },
                             required: false,
-                            default: Some(serde_json::json!(true)),
+                            default: Some(serde_json::json!(DEFAULT_SUDO_MAX_ANSWERS)),
                             placeholder: None,
                             supports_env_expansion: false,
                             supports_tilde_expansion: false,
-                            visible_when: None,
+                            visible_when: Some(Condition {
+                                field: "sudoAutoFill".to_string(),
+                                equals: serde_json::json!(true),
+                            }),
                         },
+                        cleanup_policy_field(),
                     ],
                 },
             ],
@@ -393,8 +1059,8 @@ impl ConnectionType for Ssh {
 
     fn capabilities(&self) -> Capabilities {
         Capabilities {
-            monitoring: true,
-            file_browser: true,
+            monitoring: !self.restricted_shell,
+            file_browser: !self.sftp_unavailable,
             resize: true,
             persistent: true,
         }
@@ -411,7 +1077,10 @@ impl ConnectionType for Ssh {
             .unwrap_or(true);
 
         let config = parse_ssh_settings(&settings);
-        let config = config.expand();
+        let config = config
+            .expand()
+            .apply_ssh_config_file()
+            .map_err(|e| SessionError::InvalidConfig(e.to_string()))?;
 
         validate_ssh_config(&config)?;
 
@@ -423,7 +1092,13 @@ impl ConnectionType for Ssh {
         );
 
         let alive = Arc::new(AtomicBool::new(true));
-        let handle = self.connector.open_shell(&config, alive.clone())?;
+        let mut handle = self.connector.open_shell(&config, alive.clone())?;
+        if let Ok(mut reason) = self.close_reason.lock() {
+            *reason = None;
+        }
+        let forwards =
+            self.open_session_forwards(handle.open_forwards.take(), &config, alive.clone());
+        self.host_key = handle.host_key;
 
         // Inject OSC 7 PROMPT_COMMAND hook for CWD tracking when enabled.
         if shell_integration {
@@ -480,9 +1155,48 @@ impl ConnectionType for Ssh {
             }
         });
 
-        // Create monitoring and file browser providers.
-        self.monitoring_provider = Some(SshMonitoringProvider::new(config.clone()));
-        self.file_browser_provider = Some(SftpFileBrowser::new(config));
+        if let (Some(mut responder), true) = (handle.keepalive, config.keepalive_interval > 0) {
+            let interval = std::time::Duration::from_secs(config.keepalive_interval.into());
+            let count_max = config.keepalive_count_max;
+            let alive = alive.clone();
+            let close_reason = self.close_reason.clone();
+            std::thread::spawn(move || {
+                if run_keepalive(&mut *responder, interval, count_max, &alive) {
+                    warn!(
+                        count_max,
+                        "SSH peer stopped answering keepalives, closing session"
+                    );
+                    if let Ok(mut reason) = close_reason.lock() {
+                        *reason = Some(CloseReason::ConnectionLost);
+                    }
+                    // Clearing `alive` stops the reader, which drops the output
+                    // sender and ends the session. The channel is not closed
+                    // here: a close would wait for a reply that never comes.
+                    alive.store(false, Ordering::SeqCst);
+                }
+            });
+        }
+
+        // Monitoring runs through the login shell, which a restricted shell
+        // refuses; leave it off rather than failing on every request. SFTP
+        // only needs the shell for an external `sftp-server`, so it stays
+        // on (e.g. with `internal-sftp` or a chroot) unless it fails to
+        // answer.
+        self.restricted_shell = handle.exec.as_ref().is_some_and(detect_restricted_shell);
+        if self.restricted_shell {
+            self.sftp_unavailable = !handle.probe_sftp.as_ref().is_some_and(sftp_available);
+            info!(
+                host = %config.host,
+                file_browsing = !self.sftp_unavailable,
+                "Restricted shell detected, disabling monitoring"
+            );
+        } else {
+            self.monitoring_provider =
+                Some(SshMonitoringProvider::new(config.clone(), handle.exec));
+        }
+        if !self.sftp_unavailable {
+            self.file_browser_provider = Some(SftpFileBrowser::new(config));
+        }
 
         self.state = Some(ConnectedState {
             write: handle.write,
@@ -491,6 +1205,7 @@ impl ConnectionType for Ssh {
             close: handle.close,
             alive,
             _extensions: handle.extensions,
+            _forwards: forwards,
         });
 
         Ok(())
@@ -503,6 +1218,9 @@ impl ConnectionType for Ssh {
         }
         self.monitoring_provider = None;
         self.file_browser_provider = None;
+        self.forward_statuses.clear();
+        self.restricted_shell = false;
+        self.sftp_unavailable = false;
 
         if let Some(state) = self.state.take() {
             state.alive.store(false, Ordering::SeqCst);
@@ -545,6 +1263,21 @@ impl ConnectionType for Ssh {
         rx
     }
 
+    fn close_reason(&self) -> Option<CloseReason> {
+        self.close_reason.lock().ok().and_then(|reason| *reason)
+    }
+
+    fn host_key_fingerprint(&self) -> Option<HostKeyFingerprint> {
+        self.host_key.clone()
+    }
+
+    fn notices(&self) -> Vec<SessionNotice> {
+        if !self.restricted_shell {
+            return Vec::new();
+        }
+        vec![restricted_shell_notice(self.sftp_unavailable)]
+    }
+
     fn monitoring(&self) -> Option<&dyn MonitoringProvider> {
         self.monitoring_provider
             .as_ref()
@@ -562,16 +1295,28 @@ impl ConnectionType for Ssh {
 mod tests {
     use super::*;
     use crate::connection::validate_settings;
+    use crate::monitoring::MetricGroup;
 
     // ── MockSshConnector ───────────────────────────────────────────────
 
     use connector::{SshConnector, SshShellHandle};
+    use std::sync::atomic::AtomicUsize;
     use std::time::Duration;
 
     struct MockSshConnector {
         should_fail: bool,
         write_log: Arc<Mutex<Vec<Vec<u8>>>>,
         resize_log: Arc<Mutex<Vec<(u16, u16)>>>,
+        /// Forwards currently held open by a live [`MockForwardGuard`].
+        open_forwards: Arc<Mutex<Vec<SshForward>>>,
+        /// Number of keepalive probes the mock server answers.
+        keepalive_answers: usize,
+        /// Keepalive probes received so far.
+        keepalive_probes: Arc<AtomicUsize>,
+        /// Exec channel handed to the session, if any.
+        exec: Option<connector::ExecFn>,
+        /// Whether the mock server's SFTP subsystem answers.
+        sftp_answers: bool,
     }
 
     impl MockSshConnector {
@@ -580,18 +1325,33 @@ mod tests {
                 should_fail: false,
                 write_log: Arc::new(Mutex::new(Vec::new())),
                 resize_log: Arc::new(Mutex::new(Vec::new())),
+                open_forwards: Arc::new(Mutex::new(Vec::new())),
+                keepalive_answers: usize::MAX,
+                keepalive_probes: Arc::new(AtomicUsize::new(0)),
+                exec: None,
+                sftp_answers: true,
             }
         }
 
         fn failing() -> Self {
             Self {
                 should_fail: true,
-                write_log: Arc::new(Mutex::new(Vec::new())),
-                resize_log: Arc::new(Mutex::new(Vec::new())),
+                ..Self::new()
             }
         }
     }
 
+    /// Removes its forwards from the connector's open list when dropped.
+    struct MockForwardGuard {
+        open_forwards: Arc<Mutex<Vec<SshForward>>>,
+    }
+
+    impl Drop for MockForwardGuard {
+        fn drop(&mut self) {
+            self.open_forwards.lock().unwrap().clear();
+        }
+    }
+
     impl SshConnector for MockSshConnector {
         fn open_shell(
             &self,
@@ -605,6 +1365,8 @@ mod tests {
             }
             let write_log = self.write_log.clone();
             let resize_log = self.resize_log.clone();
+            let open_forwards = self.open_forwards.clone();
+            let sftp_answers = self.sftp_answers;
             let alive_for_reader = alive.clone();
             let alive_for_close = alive.clone();
             Ok(SshShellHandle {
@@ -619,17 +1381,54 @@ mod tests {
                     resize_log.lock().unwrap().push((cols, rows));
                     Ok(())
                 }),
-                set_blocking: Arc::new(|_| {}),
+                exec: self.exec.clone(),
+                probe_sftp: Some(Arc::new(move || {
+                    if sftp_answers {
+                        Ok(())
+                    } else {
+                        Err(SessionError::SpawnFailed("SFTP probe failed".to_string()))
+                    }
+                })),
+                open_forwards: Some(Box::new(move |config: &SshConfig, _alive| {
+                    *open_forwards.lock().unwrap() = config.session_forwards.clone();
+                    let statuses = config
+                        .session_forwards
+                        .iter()
+                        .map(SessionForwardStatus::active)
+                        .collect();
+                    let guard = MockForwardGuard { open_forwards };
+                    Ok((Box::new(guard) as ForwardGuard, statuses))
+                })),
                 send_eof: Arc::new(|| Ok(())),
                 close: Arc::new(move || {
                     alive_for_close.store(false, Ordering::SeqCst);
                     Ok(())
                 }),
                 extensions: Vec::new(),
+                keepalive: Some(Box::new(MockKeepaliveResponder {
+                    answers: self.keepalive_answers,
+                    probes: self.keepalive_probes.clone(),
+                })),
+                host_key: Some(HostKeyFingerprint {
+                    key_type: "ssh-ed25519".to_string(),
+                    fingerprint: "SHA256:mock".to_string(),
+                }),
             })
         }
     }
 
+    /// Answers the first `answers` keepalive probes, then goes silent.
+    struct MockKeepaliveResponder {
+        answers: usize,
+        probes: Arc<AtomicUsize>,
+    }
+
+    impl keepalive::KeepaliveResponder for MockKeepaliveResponder {
+        fn probe(&mut self, _timeout: Duration) -> bool {
+            self.probes.fetch_add(1, Ordering::SeqCst) < self.answers
+        }
+    }
+
     /// Blocks until `alive` is cleared, then returns EOF — mirrors `Ssh2SshShellReader`.
     struct MockReader {
         alive: Arc<AtomicBool>,
@@ -724,7 +1523,10 @@ mod tests {
         let schema = ssh.settings_schema();
         let group = &schema.groups[0];
         let keys: Vec<&str> = group.fields.iter().map(|f| f.key.as_str()).collect();
-        assert_eq!(keys, vec!["host", "port", "username"]);
+        assert_eq!(
+            keys,
+            vec!["host", "port", "username", "sshConfigFile", "proxyJump"]
+        );
     }
 
     #[test]
@@ -735,7 +1537,14 @@ mod tests {
         let keys: Vec<&str> = group.fields.iter().map(|f| f.key.as_str()).collect();
         assert_eq!(
             keys,
-            vec!["authMethod", "password", "keyPath", "savePassword"]
+            vec![
+                "authMethod",
+                "password",
+                "keyPath",
+                "identityFiles",
+                "authFallback",
+                "savePassword"
+            ]
         );
     }
 
@@ -747,7 +1556,31 @@ mod tests {
         let keys: Vec<&str> = group.fields.iter().map(|f| f.key.as_str()).collect();
         assert_eq!(
             keys,
-            vec!["shell", "enableX11Forwarding", "env", "shellIntegration"]
+            vec![
+                "shell",
+                "sudoPrefix",
+                "enableX11Forwarding",
+                "transferCompression",
+                "bindAddress",
+                "connectTimeoutSecs",
+                "keepaliveInterval",
+                "keepaliveCountMax",
+                "strictHostKeyChecking",
+                "knownHostsFile",
+                "fileBrowserRoot",
+                "monitoringMetrics",
+                "editorCommand",
+                "locale",
+                "env",
+                "sessionForwards",
+                "shellIntegration",
+                "rawKeyMode",
+                "binaryOutputGuard",
+                "sudoAutoFill",
+                "sudoPromptPattern",
+                "sudoAutoFillMax",
+                "cleanupPolicy"
+            ]
         );
     }
 
@@ -1131,6 +1964,120 @@ mod tests {
         assert_eq!(config.port, 22);
         assert_eq!(config.username, "");
         assert!(config.env.is_empty());
+        assert!(!config.transfer_compression);
+        assert_eq!(config.keepalive_interval, 0);
+        assert_eq!(config.keepalive_count_max, DEFAULT_KEEPALIVE_COUNT_MAX);
+    }
+
+    #[test]
+    fn parse_ssh_settings_transfer_compression() {
+        let settings = serde_json::json!({"transferCompression": true});
+        assert!(parse_ssh_settings(&settings).transfer_compression);
+    }
+
+    #[test]
+    fn parse_ssh_settings_bind_address() {
+        let settings = serde_json::json!({"bindAddress": "10.20.0.7"});
+        assert_eq!(
+            parse_ssh_settings(&settings).bind_address.as_deref(),
+            Some("10.20.0.7")
+        );
+        assert!(parse_ssh_settings(&serde_json::json!({}))
+            .bind_address
+            .is_none());
+    }
+
+    #[test]
+    fn parse_ssh_settings_file_browser_root() {
+        let settings = serde_json::json!({"fileBrowserRoot": "~/projects"});
+        assert_eq!(
+            parse_ssh_settings(&settings).file_browser_root.as_deref(),
+            Some("~/projects")
+        );
+        assert!(parse_ssh_settings(&serde_json::json!({}))
+            .file_browser_root
+            .is_none());
+    }
+
+    #[test]
+    fn parse_ssh_settings_monitoring_metrics() {
+        let settings = serde_json::json!({"monitoringMetrics": "mem, net"});
+        assert_eq!(
+            parse_ssh_settings(&settings).monitoring_groups(),
+            vec![MetricGroup::Memory, MetricGroup::Network]
+        );
+        assert_eq!(
+            parse_ssh_settings(&serde_json::json!({})).monitoring_groups(),
+            MetricGroup::DEFAULT.to_vec()
+        );
+    }
+
+    #[test]
+    fn parse_ssh_settings_sudo_prefix() {
+        let settings = serde_json::json!({"sudoPrefix": "sudo -S"});
+        assert_eq!(
+            parse_ssh_settings(&settings).sudo_prefix.as_deref(),
+            Some("sudo -S")
+        );
+        assert!(parse_ssh_settings(&serde_json::json!({}))
+            .sudo_prefix
+            .is_none());
+    }
+
+    #[test]
+    fn parse_ssh_settings_editor_command() {
+        let settings = serde_json::json!({"editorCommand": "nvim {path}"});
+        assert_eq!(
+            parse_ssh_settings(&settings).editor_command.as_deref(),
+            Some("nvim {path}")
+        );
+        assert!(parse_ssh_settings(&serde_json::json!({}))
+            .editor_command
+            .is_none());
+    }
+
+    #[test]
+    fn parse_ssh_settings_locale() {
+        let config = parse_ssh_settings(&serde_json::json!({"locale": "en_US.UTF-8"}));
+        assert_eq!(config.locale.as_deref(), Some("en_US.UTF-8"));
+        assert_eq!(config.locale_env().len(), 2);
+        assert!(parse_ssh_settings(&serde_json::json!({"locale": ""}))
+            .locale
+            .is_none());
+    }
+
+    #[test]
+    fn parse_ssh_settings_proxy_jump() {
+        let settings = serde_json::json!({
+            "proxyJump": "ops@bastion:2204,inner",
+            "jumpHosts": [
+                {"host": "bastion", "port": 2204, "username": "ops", "authMethod": "agent"},
+                {"host": "inner", "username": "admin", "authMethod": "password", "password": "pw"}
+            ]
+        });
+        let config = parse_ssh_settings(&settings);
+        assert_eq!(config.proxy_jump.as_deref(), Some("ops@bastion:2204,inner"));
+        assert_eq!(config.jump_hosts.len(), 2);
+        assert_eq!(config.jump_hosts[0].host, "bastion");
+        assert_eq!(config.jump_hosts[0].port, 2204);
+        assert_eq!(config.jump_hosts[1].username, "admin");
+        assert_eq!(config.jump_hosts[1].password.as_deref(), Some("pw"));
+
+        let config = parse_ssh_settings(&serde_json::json!({}));
+        assert!(config.proxy_jump.is_none());
+        assert!(config.jump_hosts.is_empty());
+    }
+
+    #[test]
+    fn parse_ssh_settings_ssh_config_file() {
+        let settings = serde_json::json!({"sshConfigFile": "~/.ssh/config"});
+        assert_eq!(
+            parse_ssh_settings(&settings).ssh_config_file.as_deref(),
+            Some("~/.ssh/config")
+        );
+        assert!(parse_ssh_settings(&serde_json::json!({}))
+            .ssh_config_file
+            .is_none());
     }
 
     // ── DI unit tests (MockSshConnector — no real TCP/SSH needed) ─────
@@ -1153,6 +2100,120 @@ mod tests {
         ssh.disconnect().await.unwrap();
     }
 
+    #[tokio::test]
+    async fn host_key_fingerprint_reported_after_connect() {
+        let mut ssh = Ssh::with_connector(Box::new(MockSshConnector::new()));
+        assert!(ssh.host_key_fingerprint().is_none());
+        ssh.connect(mock_settings()).await.unwrap();
+        let key = ssh.host_key_fingerprint().unwrap();
+        assert_eq!(key.key_type, "ssh-ed25519");
+        assert_eq!(key.fingerprint, "SHA256:mock");
+        ssh.disconnect().await.unwrap();
+    }
+
+    /// Mock exec channel answering every command as `shell` would.
+    fn mock_exec(shell: &'static str) -> connector::ExecFn {
+        Arc::new(move |command: &str| {
+            let restricted = shell == "rbash" && command == restricted::RESTRICTED_SHELL_PROBE;
+            Ok(exec::CommandOutput {
+                stdout: String::new(),
+                stderr: if restricted {
+                    "rbash: line 1: cd: restricted\n".to_string()
+                } else {
+                    String::new()
+                },
+                exit_code: i32::from(restricted),
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn restricted_shell_disables_monitoring_and_failing_sftp() {
+        let connector = MockSshConnector {
+            exec: Some(mock_exec("rbash")),
+            sftp_answers: false,
+            ..MockSshConnector::new()
+        };
+        let mut ssh = Ssh::with_connector(Box::new(connector));
+        ssh.connect(mock_settings()).await.unwrap();
+
+        let caps = ssh.capabilities();
+        assert!(!caps.monitoring);
+        assert!(!caps.file_browser);
+        assert!(caps.resize);
+        assert!(ssh.monitoring().is_none());
+        assert!(ssh.file_browser().is_none());
+        let notices = ssh.notices();
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].code, "restrictedShell");
+        assert!(notices[0].message.contains("file browsing"));
+
+        ssh.disconnect().await.unwrap();
+        assert!(ssh.capabilities().monitoring);
+        assert!(ssh.capabilities().file_browser);
+        assert!(ssh.notices().is_empty());
+    }
+
+    #[tokio::test]
+    async fn restricted_shell_keeps_answering_sftp() {
+        let connector = MockSshConnector {
+            exec: Some(mock_exec("rbash")),
+            ..MockSshConnector::new()
+        };
+        let mut ssh = Ssh::with_connector(Box::new(connector));
+        ssh.connect(mock_settings()).await.unwrap();
+
+        let caps = ssh.capabilities();
+        assert!(!caps.monitoring);
+        assert!(caps.file_browser);
+        assert!(ssh.monitoring().is_none());
+        assert!(ssh.file_browser().is_some());
+        let notices = ssh.notices();
+        assert_eq!(notices.len(), 1);
+        assert!(!notices[0].message.contains("file browsing"));
+        ssh.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn unrestricted_shell_keeps_all_capabilities() {
+        let connector = MockSshConnector {
+            exec: Some(mock_exec("bash")),
+            ..MockSshConnector::new()
+        };
+        let mut ssh = Ssh::with_connector(Box::new(connector));
+        ssh.connect(mock_settings()).await.unwrap();
+
+        let caps = ssh.capabilities();
+        assert!(caps.monitoring);
+        assert!(caps.file_browser);
+        assert!(ssh.monitoring().is_some());
+        assert!(ssh.file_browser().is_some());
+        assert!(ssh.notices().is_empty());
+        ssh.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn unanswered_keepalives_tear_down_session() {
+        let connector = MockSshConnector {
+            keepalive_answers: 0,
+            ..MockSshConnector::new()
+        };
+        let probes = connector.keepalive_probes.clone();
+        let mut ssh = Ssh::with_connector(Box::new(connector));
+        let mut settings = mock_settings();
+        settings["keepaliveInterval"] = serde_json::json!(1);
+        settings["keepaliveCountMax"] = serde_json::json!(2);
+        ssh.connect(settings).await.unwrap();
+        let mut output = ssh.subscribe_output();
+
+        // The reader drops the output sender once the session is torn down.
+        let closed = tokio::time::timeout(Duration::from_secs(5), output.recv()).await;
+        assert_eq!(closed.unwrap(), None);
+        assert!(!ssh.is_connected());
+        assert_eq!(ssh.close_reason(), Some(CloseReason::ConnectionLost));
+        assert_eq!(probes.load(Ordering::SeqCst), 2);
+    }
+
     #[tokio::test]
     async fn connect_failure_propagates_as_error() {
         let mut ssh = Ssh::with_connector(Box::new(MockSshConnector::failing()));
@@ -1204,6 +2265,134 @@ mod tests {
         ssh.disconnect().await.unwrap();
     }
 
+    #[tokio::test]
+    async fn session_forwards_opened_on_connect_and_closed_on_disconnect() {
+        let connector = MockSshConnector::new();
+        let open_forwards = connector.open_forwards.clone();
+        let mut ssh = Ssh::with_connector(Box::new(connector));
+        let mut settings = mock_settings();
+        settings["sessionForwards"] = serde_json::json!([
+            {"localPort": 8080, "remoteHost": "localhost", "remotePort": 80},
+            {"localPort": 5433, "remoteHost": "db.internal", "remotePort": 5432}
+        ]);
+
+        ssh.connect(settings).await.unwrap();
+        assert_eq!(open_forwards.lock().unwrap().len(), 2);
+        let statuses = ssh.session_forwards();
+        assert_eq!(statuses.len(), 2);
+        assert!(statuses.iter().all(|s| s.active));
+        assert_eq!(statuses[1].remote_host, "db.internal");
+
+        ssh.disconnect().await.unwrap();
+        assert!(open_forwards.lock().unwrap().is_empty());
+        assert!(ssh.session_forwards().is_empty());
+    }
+
+    #[tokio::test]
+    async fn no_session_forwards_opened_when_unconfigured() {
+        let connector = MockSshConnector::new();
+        let open_forwards = connector.open_forwards.clone();
+        let mut ssh = Ssh::with_connector(Box::new(connector));
+        ssh.connect(mock_settings()).await.unwrap();
+        assert!(open_forwards.lock().unwrap().is_empty());
+        assert!(ssh.session_forwards().is_empty());
+        ssh.disconnect().await.unwrap();
+    }
+
+    #[test]
+    fn parse_session_forwards() {
+        let settings = serde_json::json!({
+            "host": "h",
+            "username": "u",
+            "authMethod": "agent",
+            "sessionForwards": [
+                {"localPort": 8080, "remoteHost": "", "remotePort": "3000"},
+                {"localPort": 99999, "remoteHost": "x", "remotePort": 1}
+            ]
+        });
+        let config = parse_ssh_settings(&settings);
+        assert_eq!(
+            config.session_forwards,
+            vec![SshForward {
+                local_port: 8080,
+                remote_host: "localhost".to_string(),
+                remote_port: 3000,
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_identity_files() {
+        let settings = serde_json::json!({
+            "host": "h",
+            "username": "u",
+            "authMethod": "key",
+            "keyPath": "~/.ssh/id_ed25519",
+            "identityFiles": [{"path": "~/.ssh/work"}, "~/.ssh/personal", {"path": ""}]
+        });
+        let config = parse_ssh_settings(&settings);
+        assert_eq!(
+            config.identity_files,
+            vec!["~/.ssh/work", "~/.ssh/personal"]
+        );
+    }
+
+    #[test]
+    fn parse_connect_timeout() {
+        let settings = serde_json::json!({"connectTimeoutSecs": 5});
+        assert_eq!(parse_ssh_settings(&settings).connect_timeout_secs, 5);
+        assert_eq!(
+            parse_ssh_settings(&serde_json::json!({})).connect_timeout_secs,
+            DEFAULT_SSH_CONNECT_TIMEOUT_SECS
+        );
+    }
+
+    #[test]
+    fn parse_host_key_checking() {
+        let settings = serde_json::json!({
+            "strictHostKeyChecking": "strict",
+            "knownHostsFile": "~/.ssh/termihub_known_hosts"
+        });
+        let config = parse_ssh_settings(&settings);
+        assert_eq!(config.strict_host_key_checking, "strict");
+        assert_eq!(
+            config.known_hosts_file.as_deref(),
+            Some("~/.ssh/termihub_known_hosts")
+        );
+
+        let config = parse_ssh_settings(&serde_json::json!({}));
+        assert_eq!(config.strict_host_key_checking, "accept-new");
+        assert!(config.known_hosts_file.is_none());
+    }
+
+    #[test]
+    fn parse_auth_fallback() {
+        let settings = serde_json::json!({
+            "authMethod": "key",
+            "authFallback": ["agent", "password"]
+        });
+        let config = parse_ssh_settings(&settings);
+        assert_eq!(config.auth_fallback, vec!["agent", "password"]);
+        assert!(parse_ssh_settings(&serde_json::json!({}))
+            .auth_fallback
+            .is_empty());
+    }
+
+    #[test]
+    fn validation_invalid_auth_fallback() {
+        let ssh = Ssh::new();
+        let schema = ssh.settings_schema();
+        let settings = serde_json::json!({
+            "host": "example.com",
+            "port": 22,
+            "username": "admin",
+            "authMethod": "agent",
+            "authFallback": ["password", "token"],
+        });
+        let errors = validate_settings(&schema, &settings);
+        assert!(errors.iter().any(|e| e.field == "authFallback.1"));
+    }
+
     #[tokio::test]
     async fn disconnect_clears_alive_flag() {
         let mut ssh = Ssh::with_connector(Box::new(MockSshConnector::new()));
@@ -1,8 +1,10 @@
 //! SSH monitoring provider implementing [`MonitoringProvider`].
 //!
 //! Collects system statistics from a remote host by periodically running
-//! a monitoring command over SSH and parsing the output. Uses a dedicated
-//! SSH session in blocking mode.
+//! a monitoring command over SSH and parsing the output. Commands run on
+//! independent exec channels of the interactive session when it provides
+//! them, so monitoring and the shell share one connection without stalling
+//! each other; otherwise a dedicated SSH session in blocking mode is used.
 
 use std::io::Read;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -14,11 +16,12 @@ use tracing::{debug, warn};
 use crate::config::SshConfig;
 use crate::errors::CoreError;
 use crate::monitoring::{
-    parse_stats, CpuDeltaTracker, MonitoringProvider, MonitoringReceiver, MonitoringSender,
-    MONITORING_COMMAND,
+    check_exit_status, monitoring_command, parse_stats_for, CpuDeltaTracker, MonitoringProvider,
+    MonitoringReceiver, MonitoringSender,
 };
 
 use super::auth::connect_and_authenticate;
+use super::connector::ExecFn;
 
 /// Polling interval for collecting system stats.
 const MONITORING_INTERVAL: Duration = Duration::from_secs(2);
@@ -43,19 +46,40 @@ impl Drop for MonitoringTask {
 /// command over SSH, parses the output, and sends stats through a channel.
 pub(crate) struct SshMonitoringProvider {
     config: SshConfig,
+    /// Exec channels on the interactive session; `None` opens a dedicated
+    /// session per subscription.
+    exec: Option<ExecFn>,
     task: Arc<Mutex<Option<MonitoringTask>>>,
 }
 
 impl SshMonitoringProvider {
-    pub(crate) fn new(config: SshConfig) -> Self {
+    pub(crate) fn new(config: SshConfig, exec: Option<ExecFn>) -> Self {
         Self {
             config,
+            exec,
             task: Arc::new(Mutex::new(None)),
         }
     }
 }
 
+/// Runs the monitoring command and returns its stdout.
+type CommandRunner = Box<dyn FnMut(&str) -> Result<String, CoreError>>;
+
+/// Run `command` through the interactive session's exec channels.
+fn shared_exec(exec: &ExecFn, command: &str) -> Result<String, CoreError> {
+    let output = exec(command).map_err(|e| CoreError::Other(e.to_string()))?;
+    Ok(check_exit_status(
+        command,
+        output.stdout,
+        &output.stderr,
+        output.exit_code,
+    )?)
+}
+
 /// Execute a command over an SSH session and return stdout as a string.
+///
+/// A non-zero exit status is reported as [`CoreError::Monitoring`] with
+/// the tail of the command's stderr.
 fn ssh_exec(session: &ssh2::Session, command: &str) -> Result<String, CoreError> {
     let mut channel = session
         .channel_session()
@@ -70,9 +94,15 @@ fn ssh_exec(session: &ssh2::Session, command: &str) -> Result<String, CoreError>
         .read_to_string(&mut output)
         .map_err(|e| CoreError::Other(format!("Read failed: {e}")))?;
 
+    let mut stderr = String::new();
+    channel.stderr().read_to_string(&mut stderr).ok();
+
     channel.wait_close().ok();
+    let exit_code = channel
+        .exit_status()
+        .map_err(|e| CoreError::Other(format!("Exit status unavailable: {e}")))?;
 
-    Ok(output)
+    Ok(check_exit_status(command, output, &stderr, exit_code)?)
 }
 
 #[async_trait::async_trait]
@@ -84,6 +114,9 @@ impl MonitoringProvider for SshMonitoringProvider {
         }
 
         let config = self.config.clone();
+        let exec = self.exec.clone();
+        let groups = config.monitoring_groups();
+        let command = monitoring_command(&groups);
         let (tx, rx): (MonitoringSender, MonitoringReceiver) =
             tokio::sync::mpsc::channel(MONITORING_CHANNEL_CAPACITY);
 
@@ -93,22 +126,28 @@ impl MonitoringProvider for SshMonitoringProvider {
         std::thread::Builder::new()
             .name("ssh-monitoring".to_string())
             .spawn(move || {
-                // Open a dedicated SSH session for monitoring.
-                let session = match connect_and_authenticate(&config) {
-                    Ok(s) => s,
-                    Err(e) => {
-                        warn!("Monitoring SSH connection failed: {e}");
-                        return;
+                let mut run: CommandRunner = match exec {
+                    Some(exec) => Box::new(move |command| shared_exec(&exec, command)),
+                    None => {
+                        // Open a dedicated SSH session for monitoring.
+                        let session = match connect_and_authenticate(&config) {
+                            Ok(s) => s,
+                            Err(e) => {
+                                warn!("Monitoring SSH connection failed: {e}");
+                                return;
+                            }
+                        };
+                        session.set_blocking(true);
+                        Box::new(move |command| ssh_exec(&session, command))
                     }
                 };
-                session.set_blocking(true);
 
                 let mut cpu_tracker = CpuDeltaTracker::new();
 
                 while alive_clone.load(Ordering::SeqCst) {
-                    match ssh_exec(&session, MONITORING_COMMAND) {
+                    match run(&command) {
                         Ok(output) => {
-                            match parse_stats(&output) {
+                            match parse_stats_for(&output, &groups) {
                                 Ok((mut stats, counters)) => {
                                     if let Some(pct) = cpu_tracker.update(counters) {
                                         stats.cpu_usage_percent = pct;
@@ -123,6 +162,9 @@ impl MonitoringProvider for SshMonitoringProvider {
                                 }
                             }
                         }
+                        Err(e @ CoreError::Monitoring(_)) => {
+                            warn!("{e}");
+                        }
                         Err(e) => {
                             debug!("Monitoring exec failed: {e}");
                         }
@@ -156,3 +198,50 @@ impl MonitoringProvider for SshMonitoringProvider {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backends::ssh::exec::CommandOutput;
+    use std::sync::atomic::AtomicUsize;
+
+    const MEMORY_OUTPUT: &str = "\
+myhost
+MemTotal:        8000000 kB
+MemAvailable:    6000000 kB
+12345.67 45678.90
+Linux 6.1.0";
+
+    #[tokio::test]
+    async fn runs_monitoring_on_shared_exec_channels() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = calls.clone();
+        let exec: ExecFn = Arc::new(move |command: &str| {
+            assert!(command.contains("meminfo"), "{command}");
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok(CommandOutput {
+                stdout: MEMORY_OUTPUT.to_string(),
+                stderr: String::new(),
+                exit_code: 0,
+            })
+        });
+        // An unreachable host: a dedicated session could never connect.
+        let config = SshConfig {
+            host: "203.0.113.1".to_string(),
+            monitoring_metrics: Some("mem".to_string()),
+            ..SshConfig::default()
+        };
+        let provider = SshMonitoringProvider::new(config, Some(exec));
+
+        let mut rx = provider.subscribe().await.unwrap();
+        let stats = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("stats should arrive")
+            .expect("channel should stay open");
+        provider.unsubscribe().await.unwrap();
+
+        assert_eq!(stats.hostname, "myhost");
+        assert_eq!(stats.memory_total_kb, 8000000);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+}
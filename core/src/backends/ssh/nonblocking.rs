@@ -0,0 +1,362 @@
+//! Blocking operations on a shared, non-blocking SSH session.
+//!
+//! An interactive SSH session stays in non-blocking mode for its whole
+//! lifetime: the shell reader, input writes, PTY resizes and extra exec
+//! channels (monitoring) all share it. `Session::set_blocking` switches the
+//! mode for every user of the session at once, so instead of toggling it
+//! around an operation, the helpers here wait for each operation on their
+//! own by retrying while libssh2 reports `EAGAIN`. An operation only holds
+//! the session for a single libssh2 call at a time, so a slow exec never
+//! stalls keystrokes and vice versa.
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crate::errors::SessionError;
+
+use super::exec::{drain, CommandOutput, POLL_INTERVAL};
+
+/// libssh2's "would block" error code.
+pub(crate) const LIBSSH2_ERROR_EAGAIN: i32 = -37;
+
+/// How long a write may make no progress before [`write_all`] asks whether
+/// to keep waiting. A stall alone does not mean the connection is dead: the
+/// remote channel window stays full while the program there is not reading
+/// input, e.g. because its output is paused.
+pub(crate) const WRITE_STALL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a single channel request (open, exec, resize, close) may keep
+/// being retried.
+pub(crate) const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Step between retries of an operation that would block.
+const RETRY_STEP: Duration = Duration::from_millis(5);
+
+/// SFTP `SSH_FXP_INIT` packet asking for protocol version 3: length 5,
+/// type 1, version 3.
+const SFTP_INIT: [u8; 9] = [0, 0, 0, 5, 1, 0, 0, 0, 3];
+
+/// Packet type of the server's `SSH_FXP_VERSION` reply.
+const SSH_FXP_VERSION: u8 = 2;
+
+fn is_eagain(e: &ssh2::Error) -> bool {
+    e.code() == ssh2::ErrorCode::Session(LIBSSH2_ERROR_EAGAIN)
+}
+
+/// Run `op` until it completes without `EAGAIN`, giving up with the last
+/// `EAGAIN` error after `timeout`.
+pub(crate) fn retry<T>(
+    timeout: Duration,
+    mut op: impl FnMut() -> Result<T, ssh2::Error>,
+) -> Result<T, ssh2::Error> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        match op() {
+            Err(e) if is_eagain(&e) && Instant::now() < deadline => {
+                std::thread::sleep(RETRY_STEP);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Write all of `data`, retrying while the writer would block.
+///
+/// Each time no byte could be written for `stall_timeout`, `keep_waiting`
+/// decides whether to go on retrying; once it returns `false` the write
+/// fails with [`io::ErrorKind::TimedOut`], with only a prefix of `data`
+/// written.
+pub(crate) fn write_all(
+    writer: &mut impl Write,
+    mut data: &[u8],
+    stall_timeout: Duration,
+    mut keep_waiting: impl FnMut() -> bool,
+) -> io::Result<()> {
+    let mut last_progress = Instant::now();
+    let mut stalled = |what: &str, last_progress: &mut Instant| {
+        if last_progress.elapsed() < stall_timeout {
+            return Ok(());
+        }
+        if keep_waiting() {
+            *last_progress = Instant::now();
+            Ok(())
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("{what} made no progress"),
+            ))
+        }
+    };
+    while !data.is_empty() {
+        match writer.write(data) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) => {
+                data = &data[n..];
+                last_progress = Instant::now();
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                stalled("write", &mut last_progress)?;
+                std::thread::sleep(RETRY_STEP);
+            }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    loop {
+        match writer.flush() {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                stalled("flush", &mut last_progress)?;
+                std::thread::sleep(RETRY_STEP);
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Whether the server behind a non-blocking `session` still answers, by
+/// opening (and closing) a channel within `timeout`.
+pub(crate) fn remote_answers(session: &ssh2::Session, timeout: Duration) -> bool {
+    match retry(timeout, || session.channel_session()) {
+        Ok(mut channel) => {
+            let _ = retry(timeout, || channel.close());
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Run `command` on a new exec channel of a non-blocking `session`, without
+/// a PTY, and collect its output. Fails if the command runs longer than
+/// `timeout`.
+pub(crate) fn exec(
+    session: &ssh2::Session,
+    command: &str,
+    timeout: Duration,
+) -> Result<CommandOutput, SessionError> {
+    let exec_err = |e: ssh2::Error| SessionError::SpawnFailed(format!("Exec failed: {e}"));
+    let deadline = Instant::now() + timeout;
+
+    let mut channel = retry(REQUEST_TIMEOUT, || session.channel_session()).map_err(exec_err)?;
+    retry(REQUEST_TIMEOUT, || channel.exec(command)).map_err(exec_err)?;
+
+    let mut stdout = Vec::new();
+    let mut stderr = Vec::new();
+    loop {
+        let read_out = drain(&mut channel, &mut stdout)?;
+        let read_err = drain(&mut channel.stderr(), &mut stderr)?;
+        if read_out || read_err {
+            continue;
+        }
+        if channel.eof() {
+            break;
+        }
+        if Instant::now() >= deadline {
+            let _ = channel.close();
+            return Err(SessionError::Io(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("Command did not finish within {}s", timeout.as_secs()),
+            )));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+
+    retry(REQUEST_TIMEOUT, || channel.wait_close()).map_err(exec_err)?;
+    let exit_code = channel.exit_status().map_err(exec_err)?;
+
+    Ok(CommandOutput {
+        stdout: String::from_utf8_lossy(&stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&stderr).into_owned(),
+        exit_code,
+    })
+}
+
+/// Check that the SFTP subsystem answers on a new channel of a
+/// non-blocking `session`: start it, send `SSH_FXP_INIT` and wait for the
+/// `SSH_FXP_VERSION` reply. Fails if the subsystem does not start, e.g.
+/// because a restricted login shell refuses to run `sftp-server`.
+pub(crate) fn probe_sftp(session: &ssh2::Session, timeout: Duration) -> Result<(), SessionError> {
+    let probe_err = |e: ssh2::Error| SessionError::SpawnFailed(format!("SFTP probe failed: {e}"));
+    let deadline = Instant::now() + timeout;
+
+    let mut channel = retry(REQUEST_TIMEOUT, || session.channel_session()).map_err(probe_err)?;
+    retry(REQUEST_TIMEOUT, || channel.subsystem("sftp")).map_err(probe_err)?;
+    write_all(&mut channel, &SFTP_INIT, timeout, || false)?;
+
+    let mut reply = Vec::new();
+    let result = loop {
+        if reply.len() > 4 {
+            break if reply[4] == SSH_FXP_VERSION {
+                Ok(())
+            } else {
+                Err(SessionError::SpawnFailed(format!(
+                    "SFTP probe failed: unexpected reply type {}",
+                    reply[4]
+                )))
+            };
+        }
+        if drain(&mut channel, &mut reply)? {
+            continue;
+        }
+        if channel.eof() {
+            break Err(SessionError::SpawnFailed(
+                "SFTP probe failed: subsystem closed without answering".to_string(),
+            ));
+        }
+        if Instant::now() >= deadline {
+            break Err(SessionError::Io(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("SFTP did not answer within {}s", timeout.as_secs()),
+            )));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+
+    let _ = retry(REQUEST_TIMEOUT, || channel.close());
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eagain() -> ssh2::Error {
+        ssh2::Error::new(
+            ssh2::ErrorCode::Session(LIBSSH2_ERROR_EAGAIN),
+            "would block",
+        )
+    }
+
+    #[test]
+    fn retry_waits_out_eagain() {
+        let mut attempts = 0;
+        let result = retry(Duration::from_secs(1), || {
+            attempts += 1;
+            if attempts < 3 {
+                Err(eagain())
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn retry_returns_other_errors_immediately() {
+        let mut attempts = 0;
+        let result: Result<(), _> = retry(Duration::from_secs(1), || {
+            attempts += 1;
+            Err(ssh2::Error::new(ssh2::ErrorCode::Session(-7), "failed"))
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn retry_gives_up_after_timeout() {
+        let result: Result<(), _> = retry(Duration::ZERO, || Err(eagain()));
+        assert!(is_eagain(&result.unwrap_err()));
+    }
+
+    /// Writer accepting at most `chunk` bytes per call, blocking on every
+    /// other call.
+    struct Choppy {
+        written: Vec<u8>,
+        chunk: usize,
+        calls: usize,
+    }
+
+    impl Write for Choppy {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.calls += 1;
+            if self.calls % 2 == 1 {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            let n = buf.len().min(self.chunk);
+            self.written.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_all_retries_would_block_and_partial_writes() {
+        let mut writer = Choppy {
+            written: Vec::new(),
+            chunk: 3,
+            calls: 0,
+        };
+        write_all(&mut writer, b"hello world", Duration::from_secs(1), || {
+            false
+        })
+        .unwrap();
+        assert_eq!(writer.written, b"hello world");
+    }
+
+    struct Stuck;
+
+    impl Write for Stuck {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::WouldBlock.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_all_times_out_without_progress() {
+        let mut asked = 0;
+        let err = write_all(&mut Stuck, b"x", Duration::from_millis(20), || {
+            asked += 1;
+            false
+        })
+        .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(asked, 1);
+    }
+
+    /// Writer blocking until `until`, then accepting everything.
+    struct Paused {
+        until: Instant,
+        written: Vec<u8>,
+    }
+
+    impl Write for Paused {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if Instant::now() < self.until {
+                return Err(io::ErrorKind::WouldBlock.into());
+            }
+            self.written.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn write_all_outlasts_stalls_while_told_to_keep_waiting() {
+        let mut writer = Paused {
+            until: Instant::now() + Duration::from_millis(100),
+            written: Vec::new(),
+        };
+        let mut asked = 0;
+        write_all(
+            &mut writer,
+            b"pasted text",
+            Duration::from_millis(10),
+            || {
+                asked += 1;
+                true
+            },
+        )
+        .unwrap();
+        assert_eq!(writer.written, b"pasted text");
+        assert!(asked > 1);
+    }
+}
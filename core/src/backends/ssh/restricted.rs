@@ -0,0 +1,113 @@
+//! Restricted shell (`rbash`) detection.
+//!
+//! Monitoring runs commands through the user's login shell, and a
+//! restricted shell rejects them with errors that say nothing about the
+//! cause. [`detect_restricted_shell`] probes the shell once on connect so
+//! the SSH backend can turn monitoring off instead. SFTP only goes through
+//! the shell for an external `sftp-server`, so [`sftp_available`] checks it
+//! separately.
+
+use tracing::debug;
+
+use crate::connection::SessionNotice;
+
+use super::connector::{ExecFn, SftpProbeFn};
+use super::exec::CommandOutput;
+
+/// Harmless command that restricted shells refuse: changing directory.
+pub const RESTRICTED_SHELL_PROBE: &str = "cd /";
+
+/// Code of the notice reported when a restricted shell is detected.
+pub const RESTRICTED_SHELL_NOTICE: &str = "restrictedShell";
+
+/// Notice reported for a session whose login shell is restricted, with
+/// file browsing off too when SFTP did not answer.
+pub fn restricted_shell_notice(sftp_unavailable: bool) -> SessionNotice {
+    let disabled = if sftp_unavailable {
+        "monitoring and file browsing are"
+    } else {
+        "monitoring is"
+    };
+    SessionNotice {
+        code: RESTRICTED_SHELL_NOTICE.to_string(),
+        message: format!(
+            "The remote login shell is restricted; {disabled} disabled for this session"
+        ),
+    }
+}
+
+/// Whether the output of [`RESTRICTED_SHELL_PROBE`] shows a restricted
+/// shell. `rbash` fails with `cd: restricted`; a failure for any other
+/// reason does not count.
+pub fn is_restricted_shell(output: &CommandOutput) -> bool {
+    output.exit_code != 0
+        && (output.stderr.contains("restricted") || output.stdout.contains("restricted"))
+}
+
+/// Run the probe on an exec channel. An exec that fails outright is not
+/// taken as a restricted shell.
+pub(crate) fn detect_restricted_shell(exec: &ExecFn) -> bool {
+    match exec(RESTRICTED_SHELL_PROBE) {
+        Ok(output) => is_restricted_shell(&output),
+        Err(e) => {
+            debug!("Restricted shell probe failed: {e}");
+            false
+        }
+    }
+}
+
+/// Run the SFTP probe on a restricted shell's session.
+pub(crate) fn sftp_available(probe: &SftpProbeFn) -> bool {
+    match probe() {
+        Ok(()) => true,
+        Err(e) => {
+            debug!("SFTP unavailable on restricted shell: {e}");
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(exit_code: i32, stderr: &str) -> CommandOutput {
+        CommandOutput {
+            stdout: String::new(),
+            stderr: stderr.to_string(),
+            exit_code,
+        }
+    }
+
+    #[test]
+    fn rbash_refusal_is_restricted() {
+        assert!(is_restricted_shell(&output(
+            1,
+            "rbash: line 1: cd: restricted\n"
+        )));
+    }
+
+    #[test]
+    fn success_is_not_restricted() {
+        assert!(!is_restricted_shell(&output(0, "")));
+    }
+
+    #[test]
+    fn other_failures_are_not_restricted() {
+        assert!(!is_restricted_shell(&output(
+            1,
+            "The system cannot find the path specified.\r\n"
+        )));
+        assert!(!is_restricted_shell(&output(0, "restricted")));
+    }
+
+    #[test]
+    fn exec_error_is_not_restricted() {
+        let exec: ExecFn = std::sync::Arc::new(|_: &str| {
+            Err(crate::errors::SessionError::SpawnFailed(
+                "channel refused".to_string(),
+            ))
+        });
+        assert!(!detect_restricted_shell(&exec));
+    }
+}
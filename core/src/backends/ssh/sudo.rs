@@ -0,0 +1,69 @@
+//! Opt-in privilege elevation for SSH sessions (`sudoPrefix`).
+//!
+//! When a connection sets a sudo prefix (e.g. `sudo` or `sudo -S`), the
+//! interactive shell is started as `<prefix> -i` on the PTY channel instead
+//! of the login shell, so sudo asks for its password in the terminal where
+//! `sudoAutoFill` can answer it. One-shot commands run with
+//! [`run_command`](super::exec::run_command) are wrapped as
+//! `<prefix> sh -c '<command>'`; they have no terminal to prompt on, so they
+//! need a prefix that does not ask (`sudo -n` with a `NOPASSWD` rule).
+//!
+//! Monitoring, the file browser and transfers are not affected and keep
+//! running as the login user.
+
+use std::borrow::Cow;
+
+use crate::files::utils::shell_quote;
+
+/// The configured prefix, or `None` when unset or blank.
+fn active_prefix(prefix: Option<&str>) -> Option<&str> {
+    prefix.map(str::trim).filter(|p| !p.is_empty())
+}
+
+/// Command to start the interactive shell with, or `None` to start the
+/// login shell as usual.
+pub fn shell_command(prefix: Option<&str>) -> Option<String> {
+    active_prefix(prefix).map(|prefix| format!("{prefix} -i"))
+}
+
+/// `command` wrapped to run through the prefix, or unchanged without one.
+pub fn wrap_command<'a>(prefix: Option<&str>, command: &'a str) -> Cow<'a, str> {
+    match active_prefix(prefix) {
+        Some(prefix) => Cow::Owned(format!("{prefix} sh -c {}", shell_quote(command))),
+        None => Cow::Borrowed(command),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_prefix_keeps_login_shell_and_command() {
+        for prefix in [None, Some(""), Some("   ")] {
+            assert_eq!(shell_command(prefix), None);
+            assert_eq!(wrap_command(prefix, "uptime"), "uptime");
+        }
+    }
+
+    #[test]
+    fn shell_starts_as_login_shell_through_prefix() {
+        assert_eq!(shell_command(Some("sudo")).as_deref(), Some("sudo -i"));
+        assert_eq!(
+            shell_command(Some(" sudo -S ")).as_deref(),
+            Some("sudo -S -i")
+        );
+    }
+
+    #[test]
+    fn commands_are_wrapped_in_a_quoted_shell() {
+        assert_eq!(
+            wrap_command(Some("sudo -n"), "systemctl restart nginx"),
+            "sudo -n sh -c 'systemctl restart nginx'"
+        );
+        assert_eq!(
+            wrap_command(Some("sudo"), "echo 'hi' && id -u"),
+            "sudo sh -c 'echo '\\''hi'\\'' && id -u'"
+        );
+    }
+}
@@ -0,0 +1,593 @@
+//! Resumable SFTP uploads and downloads with progress reporting.
+//!
+//! Transfers run in blocking mode on an SFTP session opened with
+//! [`connect_for_transfer`](super::auth::connect_for_transfer). When the
+//! connection enables `transferCompression`, SSH compresses the packets
+//! on the wire but SFTP still addresses files by their plain byte offsets.
+//! Progress is therefore reported in uncompressed bytes, and a resumed
+//! transfer continues at the size of the partial destination file.
+//!
+//! After a transfer, [`verify_checksum`] compares a SHA-256 of the local
+//! file with one computed on the server by `sha256sum`, which catches a
+//! resumed transfer whose partial destination was not a prefix of the
+//! source.
+//!
+//! [`download_dir`] and [`upload_dir`] copy whole directory trees file by
+//! file, continuing past failed entries and reporting them in a
+//! [`DirTransferSummary`].
+
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::fs::OpenOptions;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+use crate::errors::FileError;
+use crate::files::compare::{parse_sha256sum, sha256_command, sha256_file};
+
+/// Size of each read/write chunk.
+pub const TRANSFER_CHUNK_SIZE: usize = 32 * 1024;
+
+/// Progress of a single file transfer, in uncompressed bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferProgress {
+    /// Bytes present at the destination, including any resumed prefix.
+    pub transferred: u64,
+    /// Size of the source file.
+    pub total: u64,
+}
+
+/// A file or directory that could not be transferred.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferFailure {
+    /// Source path of the entry.
+    pub path: String,
+    pub error: String,
+}
+
+/// Outcome of a directory transfer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirTransferSummary {
+    /// Source paths of the files transferred.
+    pub succeeded: Vec<String>,
+    /// Entries that failed; the rest of the tree is still transferred.
+    pub failed: Vec<TransferFailure>,
+    /// Symlinks that were not followed, and followed links to a directory
+    /// already transferred.
+    pub skipped: Vec<String>,
+    /// Bytes transferred across all files.
+    pub bytes_transferred: u64,
+}
+
+impl DirTransferSummary {
+    fn record_success(&mut self, path: &Path, bytes: u64) {
+        self.succeeded.push(path.display().to_string());
+        self.bytes_transferred += bytes;
+    }
+
+    fn record_failure(&mut self, path: &Path, error: impl Display) {
+        self.failed.push(TransferFailure {
+            path: path.display().to_string(),
+            error: error.to_string(),
+        });
+    }
+
+    fn record_skipped(&mut self, path: &Path) {
+        self.skipped.push(path.display().to_string());
+    }
+}
+
+fn cancelled_error() -> FileError {
+    FileError::OperationFailed("Transfer cancelled".to_string())
+}
+
+fn sftp_error(context: &str, e: ssh2::Error) -> FileError {
+    FileError::OperationFailed(format!("{context} failed: {e}"))
+}
+
+/// Offset to resume at, given the size of the partial destination.
+///
+/// A destination larger than the source cannot be a prefix of it, so the
+/// transfer starts over.
+fn resume_offset(resume: bool, existing: Option<u64>, total: u64) -> u64 {
+    match existing {
+        Some(len) if resume && len <= total => len,
+        _ => 0,
+    }
+}
+
+/// Copy `src` to `dst` in chunks, starting at `offset` of `total` bytes.
+///
+/// Both streams must already be positioned at `offset`. Calls
+/// `on_progress` after every chunk and returns the number of bytes copied.
+fn copy_chunks<R: Read, W: Write>(
+    src: &mut R,
+    dst: &mut W,
+    offset: u64,
+    total: u64,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(TransferProgress),
+) -> Result<u64, FileError> {
+    let mut buf = vec![0u8; TRANSFER_CHUNK_SIZE];
+    let mut transferred = offset;
+    on_progress(TransferProgress { transferred, total });
+    loop {
+        if cancel.is_cancelled() {
+            dst.flush()?;
+            return Err(cancelled_error());
+        }
+        let n = src.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        dst.write_all(&buf[..n])?;
+        transferred += n as u64;
+        on_progress(TransferProgress { transferred, total });
+    }
+    dst.flush()?;
+    Ok(transferred - offset)
+}
+
+/// Download `remote` to `local`. Returns the number of bytes transferred.
+///
+/// With `resume`, an existing `local` file is treated as a partial
+/// download and only the remaining bytes are fetched.
+pub fn download(
+    sftp: &ssh2::Sftp,
+    remote: &Path,
+    local: &Path,
+    resume: bool,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(TransferProgress),
+) -> Result<u64, FileError> {
+    let total = sftp
+        .stat(remote)
+        .map_err(|e| sftp_error("stat", e))?
+        .size
+        .unwrap_or(0);
+    let existing = std::fs::metadata(local).ok().map(|m| m.len());
+    let offset = resume_offset(resume, existing, total);
+
+    let mut remote_file = sftp.open(remote).map_err(|e| sftp_error("open", e))?;
+    remote_file.seek(SeekFrom::Start(offset))?;
+
+    let mut local_file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(offset == 0)
+        .open(local)?;
+    local_file.set_len(offset)?;
+    local_file.seek(SeekFrom::Start(offset))?;
+
+    copy_chunks(
+        &mut remote_file,
+        &mut local_file,
+        offset,
+        total,
+        cancel,
+        on_progress,
+    )
+}
+
+/// Upload `local` to `remote`. Returns the number of bytes transferred.
+///
+/// With `resume`, an existing `remote` file is treated as a partial
+/// upload and only the remaining bytes are sent.
+pub fn upload(
+    sftp: &ssh2::Sftp,
+    local: &Path,
+    remote: &Path,
+    resume: bool,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(TransferProgress),
+) -> Result<u64, FileError> {
+    let mut local_file = std::fs::File::open(local)?;
+    let total = local_file.metadata()?.len();
+    let existing = if resume {
+        sftp.stat(remote).ok().and_then(|s| s.size)
+    } else {
+        None
+    };
+    let offset = resume_offset(resume, existing, total);
+
+    let mut remote_file = if offset > 0 {
+        let mut file = sftp
+            .open_mode(remote, ssh2::OpenFlags::WRITE, 0o644, ssh2::OpenType::File)
+            .map_err(|e| sftp_error("open", e))?;
+        file.seek(SeekFrom::Start(offset))?;
+        file
+    } else {
+        sftp.create(remote).map_err(|e| sftp_error("create", e))?
+    };
+    local_file.seek(SeekFrom::Start(offset))?;
+
+    copy_chunks(
+        &mut local_file,
+        &mut remote_file,
+        offset,
+        total,
+        cancel,
+        on_progress,
+    )
+}
+
+/// Lowercase hex SHA-256 of the local file at `path`, read in chunks.
+pub fn local_sha256(path: &Path) -> Result<String, FileError> {
+    Ok(sha256_file(path)?)
+}
+
+/// Lowercase hex SHA-256 of the remote file at `path`, computed on the
+/// server over an exec channel of `session`.
+///
+/// Uses `sha256sum` where available, falling back to `shasum -a 256`
+/// (macOS, BSD) and `openssl dgst -sha256`.
+pub fn remote_sha256(session: &ssh2::Session, path: &Path) -> Result<String, FileError> {
+    let command = sha256_command(&path.to_string_lossy());
+    let mut channel = session
+        .channel_session()
+        .map_err(|e| sftp_error("checksum exec", e))?;
+    channel
+        .exec(&command)
+        .map_err(|e| sftp_error("checksum exec", e))?;
+    let mut stdout = String::new();
+    channel.read_to_string(&mut stdout)?;
+    let mut stderr = String::new();
+    channel.stderr().read_to_string(&mut stderr)?;
+    channel
+        .wait_close()
+        .map_err(|e| sftp_error("checksum exec", e))?;
+    let exit_code = channel
+        .exit_status()
+        .map_err(|e| sftp_error("checksum exec", e))?;
+    if exit_code != 0 {
+        return Err(FileError::OperationFailed(format!(
+            "Remote checksum exited with {exit_code}: {}",
+            stderr.trim()
+        )));
+    }
+    parse_sha256sum(&stdout).ok_or_else(|| {
+        FileError::OperationFailed(format!("Unexpected checksum output: {}", stdout.trim()))
+    })
+}
+
+/// Check that `local` and `remote` have identical contents by comparing
+/// their SHA-256 checksums.
+pub fn verify_checksum(
+    session: &ssh2::Session,
+    local: &Path,
+    remote: &Path,
+) -> Result<(), FileError> {
+    let local_hash = local_sha256(local)?;
+    let remote_hash = remote_sha256(session, remote)?;
+    if local_hash != remote_hash {
+        return Err(FileError::OperationFailed(format!(
+            "Checksum mismatch: local {local_hash}, remote {remote_hash}"
+        )));
+    }
+    Ok(())
+}
+
+fn not_a_directory_error(path: &Path) -> FileError {
+    FileError::OperationFailed(format!("Not a directory: {}", path.display()))
+}
+
+/// Download the directory tree at `remote` into `local`, creating `local`
+/// and its subdirectories as needed.
+///
+/// Symlinks are skipped unless `follow_symlinks` is set; followed links to
+/// a directory already visited are skipped too, so link cycles terminate.
+/// A failing file or subdirectory is recorded in the summary and the rest
+/// of the tree is still transferred. Only an unreadable `remote` root and
+/// cancellation abort the transfer. `on_progress` receives the remote path
+/// of each file with its progress.
+pub fn download_dir(
+    sftp: &ssh2::Sftp,
+    remote: &Path,
+    local: &Path,
+    follow_symlinks: bool,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(&Path, TransferProgress),
+) -> Result<DirTransferSummary, FileError> {
+    if !sftp
+        .stat(remote)
+        .map_err(|e| sftp_error("stat", e))?
+        .is_dir()
+    {
+        return Err(not_a_directory_error(remote));
+    }
+
+    let mut summary = DirTransferSummary::default();
+    let mut visited = HashSet::new();
+    if follow_symlinks {
+        visited.insert(sftp.realpath(remote).unwrap_or_else(|_| remote.into()));
+    }
+    let mut pending = vec![(remote.to_path_buf(), local.to_path_buf())];
+
+    while let Some((remote_dir, local_dir)) = pending.pop() {
+        if let Err(e) = std::fs::create_dir_all(&local_dir) {
+            summary.record_failure(&remote_dir, e);
+            continue;
+        }
+        let entries = match sftp.readdir(&remote_dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                summary.record_failure(&remote_dir, sftp_error("readdir", e));
+                continue;
+            }
+        };
+        for (path, stat) in entries {
+            if cancel.is_cancelled() {
+                return Err(cancelled_error());
+            }
+            let Some(name) = path.file_name() else {
+                continue;
+            };
+            let local_path = local_dir.join(name);
+            let stat = if stat.file_type().is_symlink() {
+                if !follow_symlinks {
+                    summary.record_skipped(&path);
+                    continue;
+                }
+                match sftp.stat(&path) {
+                    Ok(target) => target,
+                    Err(e) => {
+                        summary.record_failure(&path, sftp_error("stat", e));
+                        continue;
+                    }
+                }
+            } else {
+                stat
+            };
+
+            if stat.is_dir() {
+                let first_visit = !follow_symlinks
+                    || visited.insert(sftp.realpath(&path).unwrap_or_else(|_| path.clone()));
+                if first_visit {
+                    pending.push((path, local_path));
+                } else {
+                    summary.record_skipped(&path);
+                }
+                continue;
+            }
+            let result = download(sftp, &path, &local_path, false, cancel, &mut |p| {
+                on_progress(&path, p)
+            });
+            match result {
+                Ok(bytes) => summary.record_success(&path, bytes),
+                Err(e) if cancel.is_cancelled() => return Err(e),
+                Err(e) => summary.record_failure(&path, e),
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// Upload the local directory tree at `local` into `remote`, creating
+/// `remote` and its subdirectories as needed.
+///
+/// Symlinks, failures and cancellation are handled as in
+/// [`download_dir`]. `on_progress` receives the remote path of each file
+/// with its progress.
+pub fn upload_dir(
+    sftp: &ssh2::Sftp,
+    local: &Path,
+    remote: &Path,
+    follow_symlinks: bool,
+    cancel: &CancellationToken,
+    on_progress: &mut dyn FnMut(&Path, TransferProgress),
+) -> Result<DirTransferSummary, FileError> {
+    if !std::fs::metadata(local)?.is_dir() {
+        return Err(not_a_directory_error(local));
+    }
+
+    let mut summary = DirTransferSummary::default();
+    let mut visited: HashSet<PathBuf> = HashSet::new();
+    if follow_symlinks {
+        visited.insert(std::fs::canonicalize(local)?);
+    }
+    let mut pending = vec![(local.to_path_buf(), remote.to_path_buf())];
+
+    while let Some((local_dir, remote_dir)) = pending.pop() {
+        if let Err(e) = super::sftp_mkdir_all(sftp, &remote_dir.to_string_lossy()) {
+            summary.record_failure(&local_dir, sftp_error("mkdir", e));
+            continue;
+        }
+        let entries = match std::fs::read_dir(&local_dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                summary.record_failure(&local_dir, e);
+                continue;
+            }
+        };
+        for entry in entries {
+            if cancel.is_cancelled() {
+                return Err(cancelled_error());
+            }
+            let entry = match entry {
+                Ok(entry) => entry,
+                Err(e) => {
+                    summary.record_failure(&local_dir, e);
+                    continue;
+                }
+            };
+            let path = entry.path();
+            let remote_path = remote_dir.join(entry.file_name());
+            let meta = match std::fs::symlink_metadata(&path) {
+                Ok(meta) if meta.file_type().is_symlink() => {
+                    if !follow_symlinks {
+                        summary.record_skipped(&path);
+                        continue;
+                    }
+                    std::fs::metadata(&path)
+                }
+                other => other,
+            };
+            let meta = match meta {
+                Ok(meta) => meta,
+                Err(e) => {
+                    summary.record_failure(&path, e);
+                    continue;
+                }
+            };
+
+            if meta.is_dir() {
+                let first_visit = !follow_symlinks
+                    || visited
+                        .insert(std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone()));
+                if first_visit {
+                    pending.push((path, remote_path));
+                } else {
+                    summary.record_skipped(&path);
+                }
+                continue;
+            }
+            let result = upload(sftp, &path, &remote_path, false, cancel, &mut |p| {
+                on_progress(&remote_path, p)
+            });
+            match result {
+                Ok(bytes) => summary.record_success(&path, bytes),
+                Err(e) if cancel.is_cancelled() => return Err(e),
+                Err(e) => summary.record_failure(&path, e),
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    #[test]
+    fn resume_offset_uses_partial_length() {
+        assert_eq!(resume_offset(true, Some(100), 1000), 100);
+        assert_eq!(resume_offset(true, Some(1000), 1000), 1000);
+    }
+
+    #[test]
+    fn resume_offset_restarts_when_not_resuming_or_oversized() {
+        assert_eq!(resume_offset(false, Some(100), 1000), 0);
+        assert_eq!(resume_offset(true, None, 1000), 0);
+        assert_eq!(resume_offset(true, Some(2000), 1000), 0);
+    }
+
+    #[test]
+    fn local_sha256_streams_file_contents() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.bin");
+        let data = sample(TRANSFER_CHUNK_SIZE * 2 + 5);
+        std::fs::write(&path, &data).unwrap();
+        assert_eq!(
+            local_sha256(&path).unwrap(),
+            crate::files::compare::sha256_hex(&data)
+        );
+        assert!(local_sha256(&dir.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn copy_chunks_reports_progress_up_to_total() {
+        let data = sample(TRANSFER_CHUNK_SIZE * 3 + 7);
+        let total = data.len() as u64;
+        let mut dst = Vec::new();
+        let mut seen = Vec::new();
+        let copied = copy_chunks(
+            &mut Cursor::new(&data),
+            &mut dst,
+            0,
+            total,
+            &CancellationToken::new(),
+            &mut |p| seen.push(p.transferred),
+        )
+        .unwrap();
+
+        assert_eq!(copied, total);
+        assert_eq!(dst, data);
+        assert_eq!(seen.first(), Some(&0));
+        assert_eq!(seen.last(), Some(&total));
+        assert!(seen.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn cancelled_copy_resumes_to_identical_result() {
+        let data = sample(TRANSFER_CHUNK_SIZE * 5 + 123);
+        let total = data.len() as u64;
+        let cancel = CancellationToken::new();
+        let mut dst = Vec::new();
+
+        let result = copy_chunks(
+            &mut Cursor::new(&data),
+            &mut dst,
+            0,
+            total,
+            &cancel,
+            &mut |p| {
+                if p.transferred >= 2 * TRANSFER_CHUNK_SIZE as u64 {
+                    cancel.cancel();
+                }
+            },
+        );
+        assert!(result.is_err());
+        assert_eq!(dst.len(), 2 * TRANSFER_CHUNK_SIZE);
+
+        let offset = resume_offset(true, Some(dst.len() as u64), total);
+        let mut src = Cursor::new(&data);
+        src.seek(SeekFrom::Start(offset)).unwrap();
+        let mut last = None;
+        let copied = copy_chunks(
+            &mut src,
+            &mut dst,
+            offset,
+            total,
+            &CancellationToken::new(),
+            &mut |p| last = Some(p),
+        )
+        .unwrap();
+
+        assert_eq!(copied, total - offset);
+        assert_eq!(
+            last,
+            Some(TransferProgress {
+                transferred: total,
+                total
+            })
+        );
+        assert_eq!(dst, data);
+    }
+
+    #[test]
+    fn dir_summary_records_outcomes() {
+        let mut summary = DirTransferSummary::default();
+        summary.record_success(Path::new("/src/a.txt"), 10);
+        summary.record_success(Path::new("/src/nested/b.txt"), 5);
+        summary.record_failure(Path::new("/src/locked"), "permission denied");
+        summary.record_skipped(Path::new("/src/link"));
+
+        assert_eq!(summary.succeeded, vec!["/src/a.txt", "/src/nested/b.txt"]);
+        assert_eq!(summary.bytes_transferred, 15);
+        assert_eq!(
+            summary.failed,
+            vec![TransferFailure {
+                path: "/src/locked".to_string(),
+                error: "permission denied".to_string(),
+            }]
+        );
+        assert_eq!(summary.skipped, vec!["/src/link"]);
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["bytesTransferred"], 15);
+        assert_eq!(json["failed"][0]["path"], "/src/locked");
+    }
+}
@@ -259,7 +259,7 @@ fn write_all_nonblocking(stream: &LocalStream, mut buf: &[u8]) -> std::io::Resul
 }
 
 /// Write all bytes to an SSH channel, retrying on WouldBlock.
-fn write_all_retry(channel: &mut ssh2::Channel, mut buf: &[u8]) -> std::io::Result<()> {
+pub(super) fn write_all_retry(channel: &mut ssh2::Channel, mut buf: &[u8]) -> std::io::Result<()> {
     while !buf.is_empty() {
         match channel.write(buf) {
             Ok(0) => {
@@ -6,37 +6,46 @@
 //! implementation in `src-tauri/src/terminal/telnet.rs`).
 
 use std::io::{Read, Write};
-use std::net::TcpStream;
+use std::net::{TcpStream, ToSocketAddrs};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
-use crate::config::TelnetConfig;
+use crate::config::{TelnetConfig, DEFAULT_TELNET_CONNECT_TIMEOUT_SECS};
 use crate::connection::{
-    Capabilities, ConnectionType, FieldType, OutputReceiver, OutputSender, SettingsField,
-    SettingsGroup, SettingsSchema,
+    cleanup_policy_field, Capabilities, ConnectionType, FieldType, OutputReceiver, OutputSender,
+    SettingsField, SettingsGroup, SettingsSchema,
 };
 use crate::errors::SessionError;
 use crate::files::FileBrowser;
 use crate::monitoring::MonitoringProvider;
+use crate::session::line_ending::{line_ending_options, parse_line_ending, LineEndingTranslator};
 
 /// Channel capacity for output data from the telnet reader thread.
 const OUTPUT_CHANNEL_CAPACITY: usize = 64;
 
-/// Connection timeout for TCP connect.
-const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
-
 /// Read timeout for the reader thread (allows periodic alive checks).
 const READ_TIMEOUT: Duration = Duration::from_millis(100);
 
+/// Reconnect attempts made after a dropped connection before giving up.
+const RECONNECT_MAX_ATTEMPTS: u32 = 10;
+
+/// Upper bound for the delay between reconnect attempts.
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// How often a reconnect backoff checks whether the session was closed.
+const RECONNECT_POLL: Duration = Duration::from_millis(100);
+
 // Telnet protocol constants.
 const IAC: u8 = 255;
 const WILL: u8 = 251;
 const WONT: u8 = 252;
 const DO: u8 = 253;
 const DONT: u8 = 254;
+/// Telnet BINARY transmission option (RFC 856).
+const BINARY: u8 = 0;
 
 /// Telnet backend using a raw TCP socket, implementing [`ConnectionType`].
 ///
@@ -59,6 +68,11 @@ pub struct Telnet {
 /// Internal state of an active telnet connection.
 struct ConnectedState {
     writer: Arc<Mutex<TcpStream>>,
+    /// Rewrites outgoing line breaks; locked while the writer is held.
+    line_ending: Mutex<LineEndingTranslator>,
+    /// Set during a binary file transfer, when the BINARY option is
+    /// requested in both directions and the peer's agreement accepted.
+    binary: Arc<AtomicBool>,
     alive: Arc<AtomicBool>,
 }
 
@@ -83,14 +97,19 @@ impl Default for Telnet {
 ///
 /// Returns a `Vec<u8>` containing only the user-visible data with all IAC
 /// sequences stripped. Negotiation responses (WONT for DO, DONT for WILL)
-/// are written directly to the provided stream.
-fn filter_telnet_commands(data: &[u8], stream: &mut TcpStream) -> Vec<u8> {
+/// are written directly to the provided stream. With `binary`, the peer's
+/// DO/WILL BINARY answers the request made for a binary transfer and is
+/// accepted without a reply.
+fn filter_telnet_commands(data: &[u8], stream: &mut TcpStream, binary: bool) -> Vec<u8> {
     let mut output = Vec::with_capacity(data.len());
     let mut i = 0;
 
     while i < data.len() {
         if data[i] == IAC && i + 1 < data.len() {
             match data[i + 1] {
+                DO | WILL if binary && data.get(i + 2) == Some(&BINARY) => {
+                    i += 3;
+                }
                 DO if i + 2 < data.len() => {
                     // Refuse all DO requests.
                     let _ = stream.write_all(&[IAC, WONT, data[i + 2]]);
@@ -124,6 +143,169 @@ fn filter_telnet_commands(data: &[u8], stream: &mut TcpStream) -> Vec<u8> {
     output
 }
 
+/// `data` with every `IAC` byte doubled, so the peer reads it as data.
+fn escape_iac(data: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(data.len());
+    for &byte in data {
+        escaped.push(byte);
+        if byte == IAC {
+            escaped.push(IAC);
+        }
+    }
+    escaped
+}
+
+/// Parse a settings JSON object into a [`TelnetConfig`].
+fn parse_telnet_settings(settings: &serde_json::Value) -> TelnetConfig {
+    let host = settings
+        .get("host")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let port: u16 = settings
+        .get("port")
+        .and_then(|v| {
+            v.as_u64()
+                .map(|n| n as u16)
+                .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
+        })
+        .unwrap_or(23);
+    let connect_timeout_secs = settings
+        .get("connectTimeoutSecs")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as u32)
+        .unwrap_or(DEFAULT_TELNET_CONNECT_TIMEOUT_SECS);
+    let auto_reconnect = settings
+        .get("autoReconnect")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    TelnetConfig {
+        host,
+        port,
+        connect_timeout_secs,
+        auto_reconnect,
+        line_ending: parse_line_ending(settings),
+    }
+}
+
+/// Open the TCP connection for `config`, trying each resolved address in
+/// turn within `connect_timeout_secs`.
+///
+/// Returns [`SessionError::Timeout`] when the last address tried did not
+/// answer in time.
+fn connect_stream(config: &TelnetConfig) -> Result<TcpStream, SessionError> {
+    let addrs: Vec<_> = (config.host.as_str(), config.port)
+        .to_socket_addrs()
+        .map_err(|e| SessionError::InvalidConfig(format!("Invalid address: {e}")))?
+        .collect();
+    let timeout = (config.connect_timeout_secs > 0)
+        .then(|| Duration::from_secs(u64::from(config.connect_timeout_secs)));
+
+    let mut last_err = None;
+    for addr in &addrs {
+        let result = match timeout {
+            Some(timeout) => TcpStream::connect_timeout(addr, timeout),
+            None => TcpStream::connect(addr),
+        };
+        match result {
+            Ok(stream) => {
+                stream.set_read_timeout(Some(READ_TIMEOUT)).map_err(|e| {
+                    SessionError::SpawnFailed(format!("Failed to set read timeout: {e}"))
+                })?;
+                return Ok(stream);
+            }
+            Err(e) => last_err = Some(e),
+        }
+    }
+    Err(match last_err {
+        Some(e) if e.kind() == std::io::ErrorKind::TimedOut => SessionError::Timeout(format!(
+            "Connecting to {}:{} took longer than {} s",
+            config.host, config.port, config.connect_timeout_secs
+        )),
+        Some(e) => SessionError::SpawnFailed(format!("TCP connect failed: {e}")),
+        None => SessionError::InvalidConfig(format!(
+            "Invalid address: {}:{} did not resolve",
+            config.host, config.port
+        )),
+    })
+}
+
+/// Forward filtered data from `reader` to the current output sender until
+/// the connection drops or the session is closed.
+fn pump_output(
+    reader: &mut TcpStream,
+    alive: &AtomicBool,
+    binary: &AtomicBool,
+    output_tx: &Mutex<Option<OutputSender>>,
+) {
+    let mut buf = [0u8; 4096];
+    while alive.load(Ordering::SeqCst) {
+        match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => {
+                let filtered =
+                    filter_telnet_commands(&buf[..n], reader, binary.load(Ordering::SeqCst));
+                if filtered.is_empty() {
+                    continue;
+                }
+                let Ok(guard) = output_tx.lock() else {
+                    break;
+                };
+                let Some(ref sender) = *guard else {
+                    // No sender — disconnected.
+                    break;
+                };
+                let _ = sender.blocking_send(filtered);
+            }
+            Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
+            Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => continue,
+            Err(_) => break,
+        }
+    }
+}
+
+/// Delay before reconnect attempt `attempt` (zero-based): one second,
+/// doubling each time up to [`RECONNECT_MAX_BACKOFF`].
+fn reconnect_delay(attempt: u32) -> Duration {
+    Duration::from_secs(2u64.saturating_pow(attempt)).min(RECONNECT_MAX_BACKOFF)
+}
+
+/// Call `connect` until it succeeds, waiting [`reconnect_delay`] before
+/// each attempt.
+///
+/// `wait` sleeps for the given delay and returns `false` if the session
+/// was closed meanwhile, which ends the loop. Gives up after
+/// [`RECONNECT_MAX_ATTEMPTS`] failed attempts.
+fn reconnect_with_backoff<T>(
+    mut connect: impl FnMut() -> Result<T, SessionError>,
+    mut wait: impl FnMut(Duration) -> bool,
+) -> Option<T> {
+    for attempt in 0..RECONNECT_MAX_ATTEMPTS {
+        if !wait(reconnect_delay(attempt)) {
+            return None;
+        }
+        match connect() {
+            Ok(conn) => return Some(conn),
+            Err(e) => warn!("Telnet reconnect attempt {} failed: {e}", attempt + 1),
+        }
+    }
+    None
+}
+
+/// Sleep for `delay` in short steps, returning `false` early once `alive`
+/// is cleared.
+fn wait_while_alive(alive: &AtomicBool, delay: Duration) -> bool {
+    let deadline = Instant::now() + delay;
+    while Instant::now() < deadline {
+        if !alive.load(Ordering::SeqCst) {
+            return false;
+        }
+        std::thread::sleep(RECONNECT_POLL.min(deadline - Instant::now()));
+    }
+    alive.load(Ordering::SeqCst)
+}
+
 #[async_trait::async_trait]
 impl ConnectionType for Telnet {
     fn type_id(&self) -> &str {
@@ -168,6 +350,59 @@ impl ConnectionType for Telnet {
                         supports_tilde_expansion: false,
                         visible_when: None,
                     },
+                    SettingsField {
+                        key: "connectTimeoutSecs".to_string(),
+                        label: "Connect Timeout (s)".to_string(),
+                        description: Some(
+                            "Seconds to wait for the server to accept the connection".to_string(),
+                        ),
+                        help_text: None,
+                        field_type: FieldType::Number {
+                            min: Some(1.0),
+                            max: Some(600.0),
+                        },
+                        required: false,
+                        default: Some(serde_json::json!(DEFAULT_TELNET_CONNECT_TIMEOUT_SECS)),
+                        placeholder: None,
+                        supports_env_expansion: false,
+                        supports_tilde_expansion: false,
+                        visible_when: None,
+                    },
+                    SettingsField {
+                        key: "autoReconnect".to_string(),
+                        label: "Reconnect Automatically".to_string(),
+                        description: Some(
+                            "Reconnect with increasing delays when the connection drops"
+                                .to_string(),
+                        ),
+                        help_text: None,
+                        field_type: FieldType::Boolean,
+                        required: false,
+                        default: Some(serde_json::json!(false)),
+                        placeholder: None,
+                        supports_env_expansion: false,
+                        supports_tilde_expansion: false,
+                        visible_when: None,
+                    },
+                    SettingsField {
+                        key: "lineEnding".to_string(),
+                        label: "Line Ending".to_string(),
+                        description: Some(
+                            "Sequence sent for Enter and for line breaks in pasted text"
+                                .to_string(),
+                        ),
+                        help_text: None,
+                        field_type: FieldType::Select {
+                            options: line_ending_options(),
+                        },
+                        required: false,
+                        default: Some(serde_json::json!("none")),
+                        placeholder: None,
+                        supports_env_expansion: false,
+                        supports_tilde_expansion: false,
+                        visible_when: None,
+                    },
+                    cleanup_policy_field(),
                 ],
             }],
         }
@@ -187,22 +422,7 @@ impl ConnectionType for Telnet {
             return Err(SessionError::AlreadyExists("Already connected".to_string()));
         }
 
-        // Parse settings JSON into TelnetConfig.
-        let host = settings
-            .get("host")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let port: u16 = settings
-            .get("port")
-            .and_then(|v| {
-                v.as_u64()
-                    .map(|n| n as u16)
-                    .or_else(|| v.as_str().and_then(|s| s.parse().ok()))
-            })
-            .unwrap_or(23);
-
-        let config = TelnetConfig { host, port };
+        let config = parse_telnet_settings(&settings);
 
         // Expand ${env:VAR} placeholders.
         let config = config.expand();
@@ -213,19 +433,9 @@ impl ConnectionType for Telnet {
             ));
         }
 
-        let addr = format!("{}:{}", config.host, config.port);
         info!(host = %config.host, port = config.port, "Connecting telnet session");
-
-        let socket_addr = addr.parse().map_err(|e: std::net::AddrParseError| {
-            SessionError::InvalidConfig(format!("Invalid address: {e}"))
-        })?;
-
-        let stream = TcpStream::connect_timeout(&socket_addr, CONNECT_TIMEOUT)
-            .map_err(|e| SessionError::SpawnFailed(format!("TCP connect failed: {e}")))?;
-
-        stream
-            .set_read_timeout(Some(READ_TIMEOUT))
-            .map_err(|e| SessionError::SpawnFailed(format!("Failed to set read timeout: {e}")))?;
+        let stream = connect_stream(&config)?;
+        let line_ending = Mutex::new(LineEndingTranslator::new(config.line_ending));
 
         // Clone for the reader thread.
         let mut reader = stream
@@ -233,6 +443,8 @@ impl ConnectionType for Telnet {
             .map_err(|e| SessionError::SpawnFailed(format!("Failed to clone TCP stream: {e}")))?;
 
         let alive = Arc::new(AtomicBool::new(true));
+        let binary = Arc::new(AtomicBool::new(false));
+        let writer = Arc::new(Mutex::new(stream));
 
         // Set up output channel.
         let (tx, _rx) = tokio::sync::mpsc::channel(OUTPUT_CHANNEL_CAPACITY);
@@ -244,41 +456,48 @@ impl ConnectionType for Telnet {
             *guard = Some(tx);
         }
 
-        // Spawn reader thread: bridges sync TCP reads to async tokio channel.
+        // Spawn reader thread: bridges sync TCP reads to async tokio channel,
+        // and re-establishes the connection when auto-reconnect is enabled.
         let alive_clone = alive.clone();
+        let binary_clone = binary.clone();
+        let writer_clone = writer.clone();
         let output_tx_clone = self.output_tx.clone();
         std::thread::spawn(move || {
-            let mut buf = [0u8; 4096];
-            while alive_clone.load(Ordering::SeqCst) {
-                match reader.read(&mut buf) {
-                    Ok(0) => break,
-                    Ok(n) => {
-                        let filtered = filter_telnet_commands(&buf[..n], &mut reader);
-                        if filtered.is_empty() {
-                            continue;
-                        }
-                        let guard = output_tx_clone.lock().ok();
-                        if let Some(ref guard) = guard {
-                            if let Some(ref sender) = **guard {
-                                let _ = sender.blocking_send(filtered);
-                            } else {
-                                // No sender — disconnected.
-                                break;
-                            }
-                        } else {
-                            break;
-                        }
-                    }
-                    Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
-                    Err(ref e) if e.kind() == std::io::ErrorKind::TimedOut => continue,
-                    Err(_) => break,
+            loop {
+                pump_output(&mut reader, &alive_clone, &binary_clone, &output_tx_clone);
+                if !config.auto_reconnect || !alive_clone.load(Ordering::SeqCst) {
+                    break;
                 }
+                info!(host = %config.host, port = config.port, "Telnet connection lost, reconnecting");
+                let Some(stream) = reconnect_with_backoff(
+                    || connect_stream(&config),
+                    |delay| wait_while_alive(&alive_clone, delay),
+                ) else {
+                    break;
+                };
+                let Ok(clone) = stream.try_clone() else {
+                    break;
+                };
+                let Ok(mut guard) = writer_clone.lock() else {
+                    break;
+                };
+                *guard = stream;
+                // A disconnect while reconnecting shut down the old stream.
+                if !alive_clone.load(Ordering::SeqCst) {
+                    let _ = guard.shutdown(std::net::Shutdown::Both);
+                    break;
+                }
+                drop(guard);
+                reader = clone;
+                info!(host = %config.host, port = config.port, "Telnet session reconnected");
             }
             alive_clone.store(false, Ordering::SeqCst);
         });
 
         self.state = Some(ConnectedState {
-            writer: Arc::new(Mutex::new(stream)),
+            writer,
+            line_ending,
+            binary,
             alive,
         });
 
@@ -315,7 +534,47 @@ impl ConnectionType for Telnet {
         let mut writer = state.writer.lock().map_err(|e| {
             SessionError::Io(std::io::Error::other(format!("Failed to lock writer: {e}")))
         })?;
-        writer.write_all(data).map_err(SessionError::Io)?;
+        let mut line_ending = state.line_ending.lock().map_err(|e| {
+            SessionError::Io(std::io::Error::other(format!(
+                "Failed to lock line ending: {e}"
+            )))
+        })?;
+        writer
+            .write_all(&line_ending.translate(data))
+            .map_err(SessionError::Io)?;
+        writer.flush().map_err(SessionError::Io)?;
+        Ok(())
+    }
+
+    fn write_binary(&self, data: &[u8]) -> Result<(), SessionError> {
+        let state = self
+            .state
+            .as_ref()
+            .ok_or_else(|| SessionError::NotRunning("Not connected".to_string()))?;
+        let mut writer = state.writer.lock().map_err(|e| {
+            SessionError::Io(std::io::Error::other(format!("Failed to lock writer: {e}")))
+        })?;
+        writer
+            .write_all(&escape_iac(data))
+            .map_err(SessionError::Io)?;
+        writer.flush().map_err(SessionError::Io)?;
+        Ok(())
+    }
+
+    fn set_binary_transfer(&self, active: bool) -> Result<(), SessionError> {
+        let state = self
+            .state
+            .as_ref()
+            .ok_or_else(|| SessionError::NotRunning("Not connected".to_string()))?;
+        // Set before asking, so the peer's agreement is not refused.
+        state.binary.store(active, Ordering::SeqCst);
+        let (will, r#do) = if active { (WILL, DO) } else { (WONT, DONT) };
+        let mut writer = state.writer.lock().map_err(|e| {
+            SessionError::Io(std::io::Error::other(format!("Failed to lock writer: {e}")))
+        })?;
+        writer
+            .write_all(&[IAC, will, BINARY, IAC, r#do, BINARY])
+            .map_err(SessionError::Io)?;
         writer.flush().map_err(SessionError::Io)?;
         Ok(())
     }
@@ -345,6 +604,7 @@ impl ConnectionType for Telnet {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::LineEnding;
     use crate::connection::validate_settings;
 
     #[test]
@@ -375,6 +635,25 @@ mod tests {
         assert!(!telnet.is_connected());
     }
 
+    #[test]
+    fn line_control_not_supported() {
+        let telnet = Telnet::new();
+        let err = telnet
+            .line_control(crate::connection::LineControl::Break { duration_ms: 250 })
+            .unwrap_err();
+        assert!(matches!(err, SessionError::NotSupported(_)), "{err}");
+        assert_eq!(
+            err.to_string(),
+            "Not supported: Telnet connections have no serial line control"
+        );
+    }
+
+    #[test]
+    fn modem_status_not_supported() {
+        let err = Telnet::new().modem_status().unwrap_err();
+        assert!(matches!(err, SessionError::NotSupported(_)), "{err}");
+    }
+
     #[test]
     fn schema_has_all_fields() {
         let telnet = Telnet::new();
@@ -386,7 +665,11 @@ mod tests {
         let keys: Vec<&str> = fields.iter().map(|f| f.key.as_str()).collect();
         assert!(keys.contains(&"host"));
         assert!(keys.contains(&"port"));
-        assert_eq!(keys.len(), 2);
+        assert!(keys.contains(&"connectTimeoutSecs"));
+        assert!(keys.contains(&"autoReconnect"));
+        assert!(keys.contains(&"lineEnding"));
+        assert!(keys.contains(&"cleanupPolicy"));
+        assert_eq!(keys.len(), 6);
     }
 
     #[test]
@@ -465,6 +748,229 @@ mod tests {
         assert!(!telnet.is_connected());
     }
 
+    #[test]
+    fn parse_settings_defaults() {
+        let config = parse_telnet_settings(&serde_json::json!({"host": "router"}));
+        assert_eq!(config.host, "router");
+        assert_eq!(config.port, 23);
+        assert_eq!(
+            config.connect_timeout_secs,
+            DEFAULT_TELNET_CONNECT_TIMEOUT_SECS
+        );
+        assert!(!config.auto_reconnect);
+    }
+
+    #[test]
+    fn parse_settings_timeout_and_reconnect() {
+        let config = parse_telnet_settings(&serde_json::json!({
+            "host": "router",
+            "port": "2323",
+            "connectTimeoutSecs": 4,
+            "autoReconnect": true,
+        }));
+        assert_eq!(config.port, 2323);
+        assert_eq!(config.connect_timeout_secs, 4);
+        assert!(config.auto_reconnect);
+    }
+
+    #[test]
+    fn parse_settings_line_ending() {
+        let config = parse_telnet_settings(&serde_json::json!({"host": "router"}));
+        assert_eq!(config.line_ending, LineEnding::None);
+        let config = parse_telnet_settings(&serde_json::json!({
+            "host": "router",
+            "lineEnding": "crlf",
+        }));
+        assert_eq!(config.line_ending, LineEnding::Crlf);
+    }
+
+    // --- Connect timeout and reconnect tests ---
+
+    #[test]
+    fn connect_stream_gives_up_after_connect_timeout() {
+        // Nothing answers on 10.255.255.1, so the connection attempt either
+        // times out or, without a route, fails outright; it must not hang.
+        let config = TelnetConfig {
+            host: "10.255.255.1".into(),
+            connect_timeout_secs: 1,
+            ..TelnetConfig::default()
+        };
+        let started = Instant::now();
+        let result = connect_stream(&config);
+        let elapsed = started.elapsed();
+        assert!(elapsed < Duration::from_secs(3), "took {elapsed:?}");
+        match result {
+            Err(SessionError::Timeout(msg)) => {
+                assert_eq!(msg, "Connecting to 10.255.255.1:23 took longer than 1 s")
+            }
+            Err(SessionError::SpawnFailed(msg)) => assert!(msg.starts_with("TCP connect failed")),
+            Err(other) => panic!("unexpected error: {other}"),
+            // A transparent proxy on the test network accepted the
+            // connection; only the time bound can be checked.
+            Ok(_) => {}
+        }
+    }
+
+    #[test]
+    fn reconnect_delay_doubles_up_to_cap() {
+        let delays: Vec<u64> = (0..7).map(|a| reconnect_delay(a).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 8, 16, 30, 30]);
+        assert_eq!(reconnect_delay(u32::MAX), RECONNECT_MAX_BACKOFF);
+    }
+
+    #[test]
+    fn reconnect_retries_until_connector_succeeds() {
+        let mut attempts = 0;
+        let mut waits = Vec::new();
+        let result = reconnect_with_backoff(
+            || {
+                attempts += 1;
+                if attempts < 3 {
+                    Err(SessionError::SpawnFailed("refused".into()))
+                } else {
+                    Ok(attempts)
+                }
+            },
+            |delay| {
+                waits.push(delay.as_secs());
+                true
+            },
+        );
+        assert_eq!(result, Some(3));
+        assert_eq!(waits, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn reconnect_gives_up_after_max_attempts() {
+        let mut attempts = 0;
+        let result: Option<()> = reconnect_with_backoff(
+            || {
+                attempts += 1;
+                Err(SessionError::SpawnFailed("refused".into()))
+            },
+            |_| true,
+        );
+        assert!(result.is_none());
+        assert_eq!(attempts, RECONNECT_MAX_ATTEMPTS);
+    }
+
+    #[test]
+    fn reconnect_stops_when_session_closes() {
+        let mut attempts = 0;
+        let mut waits = 0;
+        let result: Option<()> = reconnect_with_backoff(
+            || {
+                attempts += 1;
+                Err(SessionError::SpawnFailed("refused".into()))
+            },
+            |_| {
+                waits += 1;
+                waits < 3
+            },
+        );
+        assert!(result.is_none());
+        assert_eq!(attempts, 2);
+    }
+
+    #[test]
+    fn wait_while_alive_returns_early_when_closed() {
+        let alive = AtomicBool::new(false);
+        let started = Instant::now();
+        assert!(!wait_while_alive(&alive, Duration::from_secs(30)));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn auto_reconnect_resumes_output_after_drop() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut telnet = Telnet::new();
+        telnet
+            .connect(serde_json::json!({
+                "host": "127.0.0.1",
+                "port": port,
+                "autoReconnect": true,
+            }))
+            .await
+            .unwrap();
+        let mut rx = telnet.subscribe_output();
+
+        // Drop the first connection; the backend reconnects after 1 s.
+        let (first, _) = listener.accept().unwrap();
+        drop(first);
+        let (mut second, _) = listener.accept().unwrap();
+        second.write_all(b"welcome back").unwrap();
+
+        let data = tokio::time::timeout(Duration::from_secs(5), rx.recv())
+            .await
+            .expect("output after reconnect")
+            .unwrap();
+        assert_eq!(data, b"welcome back");
+        assert!(telnet.is_connected());
+        telnet.disconnect().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn write_translates_line_endings() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut telnet = Telnet::new();
+        telnet
+            .connect(serde_json::json!({
+                "host": "127.0.0.1",
+                "port": port,
+                "lineEnding": "crlf",
+            }))
+            .await
+            .unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+
+        // Enter, then a pasted line, then Enter split as CR | LF.
+        for keys in [&b"ls\r"[..], b"echo hi\n", b"pwd\r", b"\n"] {
+            telnet.write(keys).unwrap();
+        }
+        telnet.disconnect().await.unwrap();
+
+        let mut received = Vec::new();
+        server.read_to_end(&mut received).unwrap();
+        assert_eq!(received, b"ls\r\necho hi\r\npwd\r\n");
+    }
+
+    #[tokio::test]
+    async fn binary_transfer_escapes_iac_and_skips_translation() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut telnet = Telnet::new();
+        telnet
+            .connect(serde_json::json!({
+                "host": "127.0.0.1",
+                "port": port,
+                "lineEnding": "crlf",
+            }))
+            .await
+            .unwrap();
+        let (mut server, _) = listener.accept().unwrap();
+
+        telnet.set_binary_transfer(true).unwrap();
+        telnet
+            .write_binary(&[0x01, 0xFE, IAC, b'\r', b'\n'])
+            .unwrap();
+        telnet.set_binary_transfer(false).unwrap();
+        telnet.disconnect().await.unwrap();
+
+        let mut received = Vec::new();
+        server.read_to_end(&mut received).unwrap();
+        assert_eq!(
+            received,
+            [
+                &[IAC, WILL, BINARY, IAC, DO, BINARY][..],
+                &[0x01, 0xFE, IAC, IAC, b'\r', b'\n'],
+                &[IAC, WONT, BINARY, IAC, DONT, BINARY],
+            ]
+            .concat()
+        );
+    }
+
     // --- IAC filtering tests ---
 
     #[test]
@@ -472,7 +978,7 @@ mod tests {
         // No IAC bytes — data passes through unmodified.
         let data = b"Hello, world!";
         let mut stream = mock_tcp_stream();
-        let result = filter_telnet_commands(data, &mut stream);
+        let result = filter_telnet_commands(data, &mut stream, false);
         assert_eq!(result, data);
     }
 
@@ -481,7 +987,7 @@ mod tests {
         // IAC IAC → single 0xFF byte.
         let data = [IAC, IAC, b'A'];
         let mut stream = mock_tcp_stream();
-        let result = filter_telnet_commands(&data, &mut stream);
+        let result = filter_telnet_commands(&data, &mut stream, false);
         assert_eq!(result, vec![IAC, b'A']);
     }
 
@@ -490,7 +996,7 @@ mod tests {
         // IAC DO <option> should be stripped from output.
         let data = [b'A', IAC, DO, 1, b'B'];
         let mut stream = mock_tcp_stream();
-        let result = filter_telnet_commands(&data, &mut stream);
+        let result = filter_telnet_commands(&data, &mut stream, false);
         assert_eq!(result, vec![b'A', b'B']);
     }
 
@@ -499,7 +1005,7 @@ mod tests {
         // IAC WILL <option> should be stripped from output.
         let data = [b'A', IAC, WILL, 3, b'B'];
         let mut stream = mock_tcp_stream();
-        let result = filter_telnet_commands(&data, &mut stream);
+        let result = filter_telnet_commands(&data, &mut stream, false);
         assert_eq!(result, vec![b'A', b'B']);
     }
 
@@ -508,16 +1014,42 @@ mod tests {
         // IAC DONT/WONT should be silently acknowledged (stripped).
         let data = [IAC, DONT, 1, IAC, WONT, 2, b'X'];
         let mut stream = mock_tcp_stream();
-        let result = filter_telnet_commands(&data, &mut stream);
+        let result = filter_telnet_commands(&data, &mut stream, false);
         assert_eq!(result, vec![b'X']);
     }
 
+    #[test]
+    fn filter_accepts_binary_only_during_transfer() {
+        let data = [IAC, DO, BINARY, IAC, WILL, BINARY, b'C'];
+        let (mut stream, mut peer) = tcp_pair();
+        assert_eq!(filter_telnet_commands(&data, &mut stream, true), vec![b'C']);
+        assert_eq!(
+            filter_telnet_commands(&data, &mut stream, false),
+            vec![b'C']
+        );
+        drop(stream);
+
+        // Only the request outside a transfer is refused.
+        let mut replies = Vec::new();
+        peer.read_to_end(&mut replies).unwrap();
+        assert_eq!(replies, [IAC, WONT, BINARY, IAC, DONT, BINARY]);
+    }
+
+    #[test]
+    fn escape_iac_doubles_ff() {
+        assert_eq!(
+            escape_iac(&[1, IAC, 2, IAC]),
+            vec![1, IAC, IAC, 2, IAC, IAC]
+        );
+        assert_eq!(escape_iac(b"plain"), b"plain");
+    }
+
     #[test]
     fn filter_unknown_iac_command_stripped() {
         // Unknown IAC command byte should be stripped.
         let data = [IAC, 240, b'Y'];
         let mut stream = mock_tcp_stream();
-        let result = filter_telnet_commands(&data, &mut stream);
+        let result = filter_telnet_commands(&data, &mut stream, false);
         assert_eq!(result, vec![b'Y']);
     }
 
@@ -568,4 +1100,12 @@ mod tests {
         let _peer = listener.accept().unwrap();
         stream
     }
+
+    /// A connected pair, for tests that read the filter's replies.
+    fn tcp_pair() -> (TcpStream, TcpStream) {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (peer, _) = listener.accept().unwrap();
+        (stream, peer)
+    }
 }
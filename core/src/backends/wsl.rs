@@ -5,7 +5,9 @@
 //! This is a Windows-only backend — the entire module is gated with
 //! `#[cfg(windows)]` at the module declaration in `backends/mod.rs`.
 
+use std::future::Future;
 use std::io::{Read, Write};
+use std::pin::Pin;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -15,11 +17,15 @@ use tracing::{debug, info, warn};
 
 use crate::config::WslConfig;
 use crate::connection::{
-    Capabilities, ConnectionType, FieldType, FilePathKind, OutputReceiver, OutputSender,
-    SelectOption, SettingsField, SettingsGroup, SettingsSchema,
+    cleanup_policy_field, Capabilities, ConnectionType, FieldType, FilePathKind, OutputReceiver,
+    OutputSender, SelectOption, SettingsField, SettingsGroup, SettingsSchema,
 };
 use crate::errors::{FileError, SessionError};
-use crate::files::{FileBrowser, FileEntry};
+use tokio_util::sync::CancellationToken;
+
+use crate::files::dir_size::{local_dir_size, DIR_SIZE_MAX_ENTRIES};
+use crate::files::grep::local_grep;
+use crate::files::{FileBrowser, FileEntry, GrepMatch, GrepOptions};
 use crate::monitoring::MonitoringProvider;
 use crate::session::shell::{detect_wsl_distros, osc7_setup_command, shell_to_command};
 
@@ -78,6 +84,7 @@ fn wsl_unc_prefix(distribution: &str) -> String {
 /// older systems fall back to the legacy `\\wsl$\<distro>\` path.
 /// All paths presented to the user are Linux-style (e.g., `/home/user`);
 /// UNC path translation is handled internally.
+#[derive(Clone)]
 pub(crate) struct WslFileBrowser {
     /// Windows UNC prefix, e.g. `\\wsl.localhost\Ubuntu` or `\\wsl$\Ubuntu`.
     /// Detected at construction via [`wsl_unc_prefix`].
@@ -106,6 +113,12 @@ impl WslFileBrowser {
         format!("{}{}", self.unc_prefix, win_path)
     }
 
+    /// Convert a UNC path below the distro root back to a Linux path.
+    fn to_linux_path(&self, unc_path: &str) -> String {
+        let relative = unc_path.strip_prefix(&self.unc_prefix).unwrap_or(unc_path);
+        relative.replace('\\', "/")
+    }
+
     /// Build a Linux path from a parent directory and file name.
     ///
     /// Ensures a single `/` separator between parent and name.
@@ -199,6 +212,22 @@ impl FileBrowser for WslFileBrowser {
         .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
 
+    fn sha256<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, FileError>> + Send + 'a>> {
+        let unc_path = self.to_unc_path(path);
+        let linux_path = path.to_string();
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || {
+                crate::files::compare::sha256_file(std::path::Path::new(&unc_path))
+                    .map_err(|e| map_io_error(e, &linux_path))
+            })
+            .await
+            .map_err(|e| FileError::OperationFailed(e.to_string()))?
+        })
+    }
+
     async fn write_file(&self, path: &str, data: &[u8]) -> Result<(), FileError> {
         let unc_path = self.to_unc_path(path);
         let linux_path = path.to_string();
@@ -226,11 +255,15 @@ impl FileBrowser for WslFileBrowser {
         .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
 
-    async fn rename(&self, from: &str, to: &str) -> Result<(), FileError> {
+    async fn rename(&self, from: &str, to: &str, overwrite: bool) -> Result<(), FileError> {
         let unc_from = self.to_unc_path(from);
         let unc_to = self.to_unc_path(to);
         let linux_from = from.to_string();
+        let linux_to = to.to_string();
         tokio::task::spawn_blocking(move || {
+            if !overwrite && std::fs::symlink_metadata(&unc_to).is_ok() {
+                return Err(FileError::DestinationExists(linux_to));
+            }
             std::fs::rename(&unc_from, &unc_to).map_err(|e| map_io_error(e, &linux_from))
         })
         .await
@@ -253,15 +286,58 @@ impl FileBrowser for WslFileBrowser {
         .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
 
-    async fn mkdir(&self, path: &str) -> Result<(), FileError> {
+    async fn mkdir(&self, path: &str, recursive: bool) -> Result<(), FileError> {
         let unc_path = self.to_unc_path(path);
         let linux_path = path.to_string();
         tokio::task::spawn_blocking(move || {
-            std::fs::create_dir_all(&unc_path).map_err(|e| map_io_error(e, &linux_path))
+            let created = if recursive {
+                std::fs::create_dir_all(&unc_path)
+            } else {
+                std::fs::create_dir(&unc_path)
+            };
+            created.map_err(|e| map_io_error(e, &linux_path))
         })
         .await
         .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
+
+    async fn dir_size(&self, path: &str, cancel: &CancellationToken) -> Result<u64, FileError> {
+        let unc_path = self.to_unc_path(path);
+        let cancel = cancel.clone();
+        tokio::task::spawn_blocking(move || {
+            local_dir_size(
+                std::path::Path::new(&unc_path),
+                &cancel,
+                DIR_SIZE_MAX_ENTRIES,
+            )
+        })
+        .await
+        .map_err(|e| FileError::OperationFailed(e.to_string()))?
+    }
+
+    async fn grep(
+        &self,
+        root: &str,
+        pattern: &str,
+        opts: &GrepOptions,
+    ) -> Result<Vec<GrepMatch>, FileError> {
+        let unc_path = self.to_unc_path(root);
+        let pattern = pattern.to_string();
+        let opts = opts.clone();
+        let mut matches = tokio::task::spawn_blocking(move || {
+            local_grep(std::path::Path::new(&unc_path), &pattern, &opts)
+        })
+        .await
+        .map_err(|e| FileError::OperationFailed(e.to_string()))??;
+        for m in &mut matches {
+            m.path = self.to_linux_path(&m.path);
+        }
+        Ok(matches)
+    }
+
+    fn clone_box(&self) -> Box<dyn FileBrowser> {
+        Box::new(self.clone())
+    }
 }
 
 /// Convert a Windows absolute path to its WSL `/mnt/` equivalent.
@@ -537,6 +613,22 @@ impl ConnectionType for Wsl {
                         supports_tilde_expansion: false,
                         visible_when: None,
                     },
+                    SettingsField {
+                        key: "user".to_string(),
+                        label: "User".to_string(),
+                        description: Some(
+                            "Linux user to log in as (defaults to the distribution's default user)"
+                                .to_string(),
+                        ),
+                        help_text: None,
+                        field_type: FieldType::Text,
+                        required: false,
+                        default: None,
+                        placeholder: Some("default user".to_string()),
+                        supports_env_expansion: true,
+                        supports_tilde_expansion: false,
+                        visible_when: None,
+                    },
                     SettingsField {
                         key: "startingDirectory".to_string(),
                         label: "Starting Directory".to_string(),
@@ -591,6 +683,7 @@ impl ConnectionType for Wsl {
                         supports_tilde_expansion: false,
                         visible_when: None,
                     },
+                    cleanup_policy_field(),
                 ],
             }],
         }
@@ -620,6 +713,12 @@ impl ConnectionType for Wsl {
             })?
             .to_string();
 
+        let user = settings
+            .get("user")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.is_empty())
+            .map(String::from);
+
         let starting_directory = settings
             .get("startingDirectory")
             .and_then(|v| v.as_str())
@@ -638,6 +737,7 @@ impl ConnectionType for Wsl {
 
         let config = WslConfig {
             distribution: distribution.clone(),
+            user,
             starting_directory,
             initial_command: _initial_command,
             ..WslConfig::default()
@@ -647,6 +747,11 @@ impl ConnectionType for Wsl {
         let shell_key = format!("wsl:{}", config.distribution);
         let (program, mut args) = shell_to_command(&shell_key);
 
+        if let Some(ref user) = config.user {
+            args.push("-u".into());
+            args.push(user.clone());
+        }
+
         // Add starting directory as --cd argument if specified.
         if let Some(ref dir) = config.starting_directory {
             args.push("--cd".into());
@@ -932,6 +1037,17 @@ mod tests {
         assert!(f.supports_env_expansion);
     }
 
+    #[test]
+    fn schema_has_optional_user() {
+        let wsl = Wsl::new();
+        let schema = wsl.settings_schema();
+        let fields = &schema.groups[0].fields;
+        let f = fields.iter().find(|f| f.key == "user").unwrap();
+        assert!(!f.required);
+        assert!(matches!(f.field_type, FieldType::Text));
+        assert!(f.supports_env_expansion);
+    }
+
     #[test]
     fn schema_has_initial_command() {
         let wsl = Wsl::new();
@@ -1132,6 +1248,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn to_linux_path_round_trips_unc_path() {
+        let browser =
+            WslFileBrowser::new_with_prefix("Ubuntu".to_string(), r"\\wsl$\Ubuntu".to_string());
+        assert_eq!(
+            browser.to_linux_path(&browser.to_unc_path("/home/user/notes.txt")),
+            "/home/user/notes.txt"
+        );
+    }
+
     #[test]
     fn join_linux_path_no_trailing_slash() {
         assert_eq!(
@@ -1333,7 +1459,10 @@ mod tests {
                 .await
                 .expect("write failed");
 
-            browser.rename(from, to).await.expect("rename failed");
+            browser
+                .rename(from, to, false)
+                .await
+                .expect("rename failed");
 
             // Original should be gone
             assert!(browser.stat(from).await.is_err());
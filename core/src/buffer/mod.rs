@@ -1,6 +1,18 @@
 /// Default buffer capacity: 1 MiB.
 pub const DEFAULT_BUFFER_CAPACITY: usize = 1_048_576;
 
+/// A match found by [`RingBuffer::search`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BufferMatch {
+    /// Offset of the first matched byte from the oldest buffered byte.
+    pub offset: usize,
+    /// Zero-based line of the match, counting newlines from the oldest
+    /// buffered byte.
+    pub line: usize,
+    /// Byte offset of the match within its line.
+    pub column: usize,
+}
+
 /// Fixed-capacity circular byte buffer for continuous output storage.
 ///
 /// When the buffer is full, oldest data is silently overwritten.
@@ -39,21 +51,164 @@ impl RingBuffer {
 
     /// Read all buffered data in order (oldest to newest).
     pub fn read_all(&self) -> Vec<u8> {
-        let stored = self.len();
-        if stored == 0 {
-            return Vec::new();
+        let (older, newer) = self.as_slices();
+        let mut result = Vec::with_capacity(older.len() + newer.len());
+        result.extend_from_slice(older);
+        result.extend_from_slice(newer);
+        result
+    }
+
+    /// The most recent `max_bytes` of buffered data, oldest first.
+    ///
+    /// Returns everything buffered when less than `max_bytes` is held.
+    /// Used to replay scrollback to a client that attaches to a running
+    /// session without flooding it with the whole buffer.
+    pub fn replay_from(&self, max_bytes: usize) -> Vec<u8> {
+        let (older, newer) = self.as_slices();
+        let skip = self.len().saturating_sub(max_bytes);
+        let mut result = Vec::with_capacity(self.len() - skip);
+        if skip < older.len() {
+            result.extend_from_slice(&older[skip..]);
+            result.extend_from_slice(newer);
+        } else {
+            result.extend_from_slice(&newer[skip - older.len()..]);
         }
+        result
+    }
 
+    /// The buffered data as two slices, oldest first, without copying.
+    /// The second slice is empty until the buffer has wrapped.
+    pub fn as_slices(&self) -> (&[u8], &[u8]) {
         if self.total_written <= self.capacity {
             // Buffer has not wrapped — data starts at index 0
-            self.data[..stored].to_vec()
+            (&self.data[..self.total_written], &[])
         } else {
             // Buffer has wrapped — oldest data starts at write_pos
-            let mut result = Vec::with_capacity(self.capacity);
-            result.extend_from_slice(&self.data[self.write_pos..]);
-            result.extend_from_slice(&self.data[..self.write_pos]);
-            result
+            (&self.data[self.write_pos..], &self.data[..self.write_pos])
+        }
+    }
+
+    /// The byte at `offset` from the oldest buffered byte.
+    fn byte_at(&self, offset: usize) -> u8 {
+        let (older, newer) = self.as_slices();
+        match older.get(offset) {
+            Some(&byte) => byte,
+            None => newer[offset - older.len()],
+        }
+    }
+
+    /// Find every occurrence of `needle` in the buffered data.
+    ///
+    /// Matches may overlap (`"aa"` occurs three times in `"aaaa"`) and may
+    /// straddle the point where the buffer wraps. With `case_insensitive`,
+    /// ASCII letters match regardless of case. The data is searched in
+    /// place; only the returned matches are allocated. An empty needle
+    /// matches nothing.
+    pub fn search(&self, needle: &[u8], case_insensitive: bool) -> Vec<BufferMatch> {
+        self.search_limited(needle, case_insensitive, usize::MAX)
+    }
+
+    /// Like [`search`](Self::search), but stops after the first
+    /// `max_matches` matches.
+    pub fn search_limited(
+        &self,
+        needle: &[u8],
+        case_insensitive: bool,
+        max_matches: usize,
+    ) -> Vec<BufferMatch> {
+        let len = self.len();
+        let mut matches = Vec::new();
+        if needle.is_empty() || needle.len() > len {
+            return matches;
+        }
+        let eq = |a: u8, b: u8| {
+            if case_insensitive {
+                a.eq_ignore_ascii_case(&b)
+            } else {
+                a == b
+            }
+        };
+
+        let mut line = 0;
+        let mut line_start = 0;
+        for start in 0..=len - needle.len() {
+            if matches.len() == max_matches {
+                break;
+            }
+            if start > 0 && self.byte_at(start - 1) == b'\n' {
+                line += 1;
+                line_start = start;
+            }
+            if needle
+                .iter()
+                .enumerate()
+                .all(|(i, &b)| eq(self.byte_at(start + i), b))
+            {
+                matches.push(BufferMatch {
+                    offset: start,
+                    line,
+                    column: start - line_start,
+                });
+            }
+        }
+        matches
+    }
+
+    /// The logical line containing the byte at `offset`, without its line
+    /// terminator (`\n` or `\r\n`). Returns `None` if `offset` is past the
+    /// buffered data.
+    ///
+    /// Once the buffer has wrapped, the first line may be missing its
+    /// beginning.
+    pub fn line_at(&self, offset: usize) -> Option<Vec<u8>> {
+        let len = self.len();
+        if offset >= len {
+            return None;
+        }
+        let start = (0..offset)
+            .rev()
+            .find(|&i| self.byte_at(i) == b'\n')
+            .map_or(0, |i| i + 1);
+        let end = (offset..len)
+            .find(|&i| self.byte_at(i) == b'\n')
+            .unwrap_or(len);
+        let mut line: Vec<u8> = (start..end).map(|i| self.byte_at(i)).collect();
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        Some(line)
+    }
+
+    /// Split the buffered data into logical lines, without their line
+    /// terminators (`\n` or `\r\n`).
+    ///
+    /// Line `n` of the result is the line [`BufferMatch::line`] `n` refers
+    /// to. Text after the last newline is returned as a final line; a
+    /// trailing newline does not start an empty one.
+    pub fn snapshot_lines(&self) -> Vec<Vec<u8>> {
+        let (older, newer) = self.as_slices();
+        let mut lines = Vec::new();
+        let mut current = Vec::new();
+        for &byte in older.iter().chain(newer) {
+            if byte == b'\n' {
+                if current.last() == Some(&b'\r') {
+                    current.pop();
+                }
+                lines.push(std::mem::take(&mut current));
+            } else {
+                current.push(byte);
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
         }
+        lines
+    }
+
+    /// Whether older data has been overwritten, so the buffered data may
+    /// start in the middle of a line.
+    pub fn has_wrapped(&self) -> bool {
+        self.total_written > self.capacity
     }
 
     /// Return the number of bytes currently stored.
@@ -91,6 +246,17 @@ mod tests {
         assert!(!rb.is_empty());
     }
 
+    #[test]
+    fn has_wrapped_once_data_is_overwritten() {
+        let mut rb = RingBuffer::new(8);
+        rb.write(b"12345678");
+        assert!(!rb.has_wrapped());
+        rb.write(b"9");
+        assert!(rb.has_wrapped());
+        rb.clear();
+        assert!(!rb.has_wrapped());
+    }
+
     #[test]
     fn write_fills_buffer_exactly() {
         let mut rb = RingBuffer::new(8);
@@ -177,6 +343,162 @@ mod tests {
         assert_eq!(rb.capacity(), 256);
     }
 
+    /// A buffer of capacity 8 holding `b"ABCDEFGH"` with the write position
+    /// at 4, so its storage reads `EFGHABCD`.
+    fn wrapped() -> RingBuffer {
+        let mut rb = RingBuffer::new(8);
+        rb.write(b"xxxxABCDEFGH");
+        rb
+    }
+
+    #[test]
+    fn as_slices_splits_at_wrap() {
+        let rb = wrapped();
+        assert_eq!(rb.as_slices(), (&b"ABCD"[..], &b"EFGH"[..]));
+
+        let mut rb = RingBuffer::new(8);
+        rb.write(b"abc");
+        assert_eq!(rb.as_slices(), (&b"abc"[..], &b""[..]));
+    }
+
+    #[test]
+    fn replay_from_returns_newest_bytes_in_order() {
+        let rb = wrapped();
+        assert_eq!(rb.replay_from(8), b"ABCDEFGH");
+        assert_eq!(rb.replay_from(6), b"CDEFGH", "straddles the wrap");
+        assert_eq!(rb.replay_from(3), b"FGH", "within the newer slice");
+        assert_eq!(rb.replay_from(4), b"EFGH");
+    }
+
+    #[test]
+    fn replay_from_caps_bytes() {
+        let mut rb = RingBuffer::new(64);
+        rb.write(b"hello world");
+        assert_eq!(rb.replay_from(100), b"hello world");
+        assert_eq!(rb.replay_from(5), b"world");
+        assert!(rb.replay_from(0).is_empty());
+        assert!(RingBuffer::new(8).replay_from(4).is_empty());
+    }
+
+    #[test]
+    fn search_finds_match_across_wrap_boundary() {
+        let rb = wrapped();
+        let matches = rb.search(b"CDEF", false);
+        assert_eq!(
+            matches,
+            vec![BufferMatch {
+                offset: 2,
+                line: 0,
+                column: 2,
+            }]
+        );
+        assert!(rb.search(b"xx", false).is_empty(), "overwritten data");
+        assert!(rb.search(b"HA", false).is_empty(), "no wrap back to start");
+    }
+
+    #[test]
+    fn search_case_insensitive() {
+        let mut rb = RingBuffer::new(64);
+        rb.write(b"Error: disk full\nerror: retry\nERROR\n");
+        assert_eq!(rb.search(b"error", false).len(), 1);
+
+        let matches = rb.search(b"error", true);
+        let lines: Vec<usize> = matches.iter().map(|m| m.line).collect();
+        assert_eq!(lines, vec![0, 1, 2]);
+        assert!(matches.iter().all(|m| m.column == 0));
+    }
+
+    #[test]
+    fn search_reports_overlapping_matches() {
+        let mut rb = RingBuffer::new(16);
+        rb.write(b"aaaa");
+        let offsets: Vec<usize> = rb.search(b"aa", false).iter().map(|m| m.offset).collect();
+        assert_eq!(offsets, vec![0, 1, 2]);
+
+        rb.write(b"baba");
+        let offsets: Vec<usize> = rb.search(b"aba", false).iter().map(|m| m.offset).collect();
+        assert_eq!(offsets, vec![3, 5]);
+    }
+
+    #[test]
+    fn search_limited_stops_after_max_matches() {
+        let mut rb = RingBuffer::new(16);
+        rb.write(b"ab ab ab ab");
+        let offsets: Vec<usize> = rb
+            .search_limited(b"ab", false, 2)
+            .iter()
+            .map(|m| m.offset)
+            .collect();
+        assert_eq!(offsets, vec![0, 3]);
+        assert!(rb.search_limited(b"ab", false, 0).is_empty());
+        assert_eq!(rb.search_limited(b"ab", false, 10).len(), 4);
+    }
+
+    #[test]
+    fn search_edge_cases() {
+        let mut rb = RingBuffer::new(8);
+        assert!(rb.search(b"a", false).is_empty());
+        rb.write(b"abc");
+        assert!(rb.search(b"", false).is_empty());
+        assert!(rb.search(b"abcd", false).is_empty());
+        assert_eq!(rb.search(b"abc", false).len(), 1);
+    }
+
+    #[test]
+    fn search_lines_and_columns_match_snapshot() {
+        let mut rb = RingBuffer::new(32);
+        rb.write(b"old line\r\n$ make\r\nfailed: make\r\n$ ");
+        // The first line has been partly overwritten.
+        let lines = rb.snapshot_lines();
+        assert_eq!(
+            lines,
+            vec![
+                b"d line".to_vec(),
+                b"$ make".to_vec(),
+                b"failed: make".to_vec(),
+                b"$ ".to_vec(),
+            ]
+        );
+        for m in rb.search(b"make", false) {
+            assert_eq!(&lines[m.line][m.column..m.column + 4], b"make");
+            assert_eq!(rb.line_at(m.offset).unwrap(), lines[m.line]);
+        }
+    }
+
+    #[test]
+    fn line_at_strips_terminators() {
+        let mut rb = RingBuffer::new(64);
+        rb.write(b"first\r\nsecond\nthird");
+        assert_eq!(rb.line_at(0).unwrap(), b"first");
+        assert_eq!(rb.line_at(5).unwrap(), b"first", "on the CR");
+        assert_eq!(rb.line_at(6).unwrap(), b"first", "on the LF");
+        assert_eq!(rb.line_at(7).unwrap(), b"second");
+        assert_eq!(rb.line_at(rb.len() - 1).unwrap(), b"third");
+        assert_eq!(rb.line_at(rb.len()), None);
+    }
+
+    #[test]
+    fn line_at_across_wrap_boundary() {
+        let mut rb = RingBuffer::new(8);
+        rb.write(b"zzzzz\nab\ncdef");
+        assert_eq!(rb.read_all(), b"\nab\ncdef");
+        assert_ne!(rb.as_slices().1, b"", "data must straddle the wrap");
+        assert_eq!(rb.line_at(0).unwrap(), b"");
+        assert_eq!(rb.line_at(2).unwrap(), b"ab");
+        assert_eq!(rb.line_at(4).unwrap(), b"cdef");
+    }
+
+    #[test]
+    fn snapshot_lines_empty_and_trailing_newline() {
+        let mut rb = RingBuffer::new(16);
+        assert!(rb.snapshot_lines().is_empty());
+        rb.write(b"a\n\nb\n");
+        assert_eq!(
+            rb.snapshot_lines(),
+            vec![b"a".to_vec(), b"".to_vec(), b"b".to_vec()]
+        );
+    }
+
     #[test]
     fn default_capacity_constant() {
         assert_eq!(DEFAULT_BUFFER_CAPACITY, 1_048_576);
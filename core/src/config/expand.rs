@@ -1,5 +1,12 @@
+use std::collections::HashMap;
 use std::env;
 
+/// How many levels of placeholders inside substituted values are resolved.
+///
+/// Bounds self-referential definitions (e.g. `A = ${var:A}`): a placeholder
+/// still unresolved at this depth is left as-is.
+const MAX_EXPANSION_DEPTH: usize = 8;
+
 /// Expand a leading `~` or `~/` to the user's home directory.
 ///
 /// On Unix, uses `$HOME`. On Windows, uses `%USERPROFILE%`.
@@ -33,22 +40,107 @@ pub fn expand_tilde(input: &str) -> String {
     }
 }
 
+/// Expand a path on a remote host whose home directory is `remote_home`.
+///
+/// `${env:HOME}` and a leading `~` or `~/` resolve to `remote_home`; other
+/// `${env:...}` placeholders use the local environment, as for any other
+/// connection setting.
+pub fn expand_remote_path(input: &str, remote_home: &str) -> String {
+    let expanded = expand_placeholders(input, "${env:", |name| {
+        if name == "HOME" {
+            Some(remote_home.to_string())
+        } else {
+            env::var(name).ok()
+        }
+    });
+    if expanded == "~" {
+        return remote_home.to_string();
+    }
+    match expanded.strip_prefix("~/") {
+        Some(rest) => format!("{}/{rest}", remote_home.trim_end_matches('/')),
+        None => expanded,
+    }
+}
+
 /// Replace `${env:VAR_NAME}` placeholders with the value of the environment
 /// variable `VAR_NAME`. Unknown variables are left as-is.
+///
+/// Placeholders inside a variable's value are resolved as well, up to
+/// [`MAX_EXPANSION_DEPTH`] levels.
 pub fn expand_env_placeholders(input: &str) -> String {
+    expand_placeholders(input, "${env:", |name| env::var(name).ok())
+}
+
+/// Replace `${var:NAME}` placeholders with values from `vars` (e.g. the
+/// active environment profile). Unknown variables are left as-is.
+///
+/// A value may itself contain `${var:...}` or `${env:...}` placeholders,
+/// which are resolved up to [`MAX_EXPANSION_DEPTH`] levels.
+pub fn expand_var_placeholders(input: &str, vars: &HashMap<String, String>) -> String {
+    expand_placeholders(input, "${var:", |name| {
+        vars.get(name).map(|value| expand_env_placeholders(value))
+    })
+}
+
+/// Apply [`expand_var_placeholders`] to every string inside a settings JSON
+/// value, recursing into arrays and objects.
+pub fn expand_var_placeholders_in_value(
+    value: &mut serde_json::Value,
+    vars: &HashMap<String, String>,
+) {
+    match value {
+        serde_json::Value::String(s) if s.contains("${var:") => {
+            *s = expand_var_placeholders(s, vars);
+        }
+        serde_json::Value::Array(items) => {
+            for item in items {
+                expand_var_placeholders_in_value(item, vars);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for item in map.values_mut() {
+                expand_var_placeholders_in_value(item, vars);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replace `<prefix>NAME}` placeholders using `lookup`. Names that `lookup`
+/// does not resolve are left as-is, and substituted values are expanded in
+/// turn up to [`MAX_EXPANSION_DEPTH`] levels.
+fn expand_placeholders(
+    input: &str,
+    prefix: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> String {
+    expand_placeholders_nested(input, prefix, &lookup, MAX_EXPANSION_DEPTH)
+}
+
+/// One level of [`expand_placeholders`]; `depth` is how many more levels of
+/// substitution are allowed.
+fn expand_placeholders_nested(
+    input: &str,
+    prefix: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    depth: usize,
+) -> String {
     let mut result = String::with_capacity(input.len());
     let mut rest = input;
 
-    while let Some(start) = rest.find("${env:") {
+    while let Some(start) = rest.find(prefix) {
         result.push_str(&rest[..start]);
-        let after = &rest[start + 6..]; // skip "${env:"
+        let after = &rest[start + prefix.len()..];
         if let Some(end) = after.find('}') {
             let var_name = &after[..end];
-            match env::var(var_name) {
-                Ok(val) => result.push_str(&val),
-                Err(_) => {
-                    // Leave placeholder as-is when variable is not set
-                    result.push_str(&rest[start..start + 6 + end + 1]);
+            match lookup(var_name).filter(|_| depth > 0) {
+                Some(val) => {
+                    result.push_str(&expand_placeholders_nested(&val, prefix, lookup, depth - 1))
+                }
+                None => {
+                    // Leave placeholder as-is when variable is not set or
+                    // the depth limit is reached
+                    result.push_str(&rest[start..start + prefix.len() + end + 1]);
                 }
             }
             rest = &after[end + 1..];
@@ -121,6 +213,108 @@ mod tests {
         env::remove_var("TERMIHUB_TEST_USER");
     }
 
+    #[test]
+    fn expands_placeholder_nested_in_env_value() {
+        env::set_var(
+            "TERMIHUB_TEST_NESTED_OUTER",
+            "${env:TERMIHUB_TEST_NESTED_INNER}/bin",
+        );
+        env::set_var("TERMIHUB_TEST_NESTED_INNER", "/opt/tool");
+        assert_eq!(
+            expand_env_placeholders("${env:TERMIHUB_TEST_NESTED_OUTER}"),
+            "/opt/tool/bin"
+        );
+        env::remove_var("TERMIHUB_TEST_NESTED_OUTER");
+        env::remove_var("TERMIHUB_TEST_NESTED_INNER");
+    }
+
+    #[test]
+    fn self_referential_env_value_terminates() {
+        env::set_var("TERMIHUB_TEST_CYCLE", "${env:TERMIHUB_TEST_CYCLE}");
+        assert_eq!(
+            expand_env_placeholders("${env:TERMIHUB_TEST_CYCLE}"),
+            "${env:TERMIHUB_TEST_CYCLE}"
+        );
+        env::remove_var("TERMIHUB_TEST_CYCLE");
+    }
+
+    // --- expand_var_placeholders tests ---
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn expands_known_var() {
+        let vars = vars(&[("HOST", "dev.example.com"), ("USER", "deploy")]);
+        assert_eq!(
+            expand_var_placeholders("${var:USER}@${var:HOST}", &vars),
+            "deploy@dev.example.com"
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_var_and_env_placeholders_as_is() {
+        let vars = vars(&[("HOST", "h")]);
+        assert_eq!(
+            expand_var_placeholders("${var:PORT} ${env:HOME} ${var:HOST", &vars),
+            "${var:PORT} ${env:HOME} ${var:HOST"
+        );
+    }
+
+    #[test]
+    fn expands_env_placeholder_nested_in_var_value() {
+        env::set_var("TERMIHUB_TEST_VAR_USER", "alice");
+        let vars = vars(&[
+            ("LOGIN", "${var:USER}@${var:HOST}"),
+            ("USER", "${env:TERMIHUB_TEST_VAR_USER}"),
+            ("HOST", "dev.example.com"),
+        ]);
+        assert_eq!(
+            expand_var_placeholders("ssh ${var:LOGIN}", &vars),
+            "ssh alice@dev.example.com"
+        );
+        env::remove_var("TERMIHUB_TEST_VAR_USER");
+    }
+
+    #[test]
+    fn var_cycles_terminate_with_placeholder_intact() {
+        let cycles = vars(&[("A", "${var:A}"), ("B", "${var:C}"), ("C", "${var:B}")]);
+        assert_eq!(expand_var_placeholders("${var:A}", &cycles), "${var:A}");
+        assert_eq!(expand_var_placeholders("${var:B}", &cycles), "${var:B}");
+
+        // A growing cycle stops after MAX_EXPANSION_DEPTH substitutions.
+        let growing = vars(&[("X", "x${var:X}")]);
+        assert_eq!(
+            expand_var_placeholders("${var:X}", &growing),
+            format!("{}${{var:X}}", "x".repeat(MAX_EXPANSION_DEPTH))
+        );
+    }
+
+    #[test]
+    fn expands_vars_throughout_json_value() {
+        let vars = vars(&[("HOST", "staging.internal"), ("KEY", "~/.ssh/staging")]);
+        let mut settings = serde_json::json!({
+            "host": "${var:HOST}",
+            "port": 22,
+            "identityFiles": [{"path": "${var:KEY}"}],
+            "env": [{"key": "TARGET", "value": "${var:HOST}"}]
+        });
+        expand_var_placeholders_in_value(&mut settings, &vars);
+        assert_eq!(
+            settings,
+            serde_json::json!({
+                "host": "staging.internal",
+                "port": 22,
+                "identityFiles": [{"path": "~/.ssh/staging"}],
+                "env": [{"key": "TARGET", "value": "staging.internal"}]
+            })
+        );
+    }
+
     // --- expand_tilde tests ---
 
     #[test]
@@ -153,6 +347,33 @@ mod tests {
         assert_eq!(expand_tilde("/usr/local"), "/usr/local");
     }
 
+    // --- expand_remote_path tests ---
+
+    #[test]
+    fn remote_tilde_uses_remote_home() {
+        assert_eq!(expand_remote_path("~", "/home/alice"), "/home/alice");
+        assert_eq!(
+            expand_remote_path("~/logs", "/home/alice/"),
+            "/home/alice/logs"
+        );
+        assert_eq!(expand_remote_path("~bob/logs", "/home/alice"), "~bob/logs");
+    }
+
+    #[test]
+    fn remote_home_placeholder_uses_remote_home() {
+        assert_eq!(expand_remote_path("${env:HOME}/src", "/root"), "/root/src");
+    }
+
+    #[test]
+    fn remote_path_expands_other_placeholders_locally() {
+        env::set_var("TERMIHUB_TEST_REMOTE_DIR", "deploy");
+        assert_eq!(
+            expand_remote_path("/srv/${env:TERMIHUB_TEST_REMOTE_DIR}", "/home/alice"),
+            "/srv/deploy"
+        );
+        env::remove_var("TERMIHUB_TEST_REMOTE_DIR");
+    }
+
     // --- expand_config_value tests ---
 
     #[test]
@@ -1,7 +1,12 @@
 pub mod expand;
+pub mod ssh_config_file;
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
+
+use crate::errors::CoreError;
+use crate::monitoring::MetricGroup;
 
 /// Terminal dimensions (columns x rows).
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
@@ -38,6 +43,18 @@ pub struct VolumeMount {
     pub read_only: bool,
 }
 
+/// A local port forward bundled with an SSH terminal session.
+///
+/// Listens on `127.0.0.1:local_port` and forwards each accepted
+/// connection to `remote_host:remote_port` as seen from the SSH server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshForward {
+    pub local_port: u16,
+    pub remote_host: String,
+    pub remote_port: u16,
+}
+
 /// Unified shell session configuration.
 ///
 /// Superset of desktop `LocalShellConfig` and agent `ShellConfig`.
@@ -70,6 +87,30 @@ impl Default for ShellConfig {
     }
 }
 
+/// Line ending sent for each line break typed or pasted into a serial or
+/// telnet session.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum LineEnding {
+    /// Send line breaks as typed.
+    #[default]
+    None,
+    Cr,
+    Lf,
+    Crlf,
+}
+
+/// How output received on a serial connection is shown in the terminal.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayMode {
+    /// Pass output to the terminal unchanged.
+    #[default]
+    Text,
+    /// Render output as a hexdump.
+    Hex,
+}
+
 /// Unified serial port configuration.
 ///
 /// Shared between desktop and agent serial backends.
@@ -87,6 +128,30 @@ pub struct SerialConfig {
     pub parity: String,
     #[serde(default = "default_flow_control")]
     pub flow_control: String,
+    /// Pause between outgoing bytes, in milliseconds (0 = none).
+    #[serde(default)]
+    pub send_byte_delay_ms: u32,
+    /// Pause after each outgoing line ending, in milliseconds (0 = none).
+    #[serde(default)]
+    pub send_line_delay_ms: u32,
+    /// Reject unsupported baud rates instead of rounding them to the
+    /// nearest supported rate.
+    #[serde(default)]
+    pub strict_baud_rate: bool,
+    /// Level the DTR line is set to on connect; `None` keeps the driver's.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dtr: Option<bool>,
+    /// Level the RTS line is set to on connect; `None` keeps the driver's.
+    /// Hardware flow control drives RTS itself.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rts: Option<bool>,
+    #[serde(default)]
+    pub line_ending: LineEnding,
+    /// Echo typed input to the terminal, for devices that do not echo.
+    #[serde(default)]
+    pub local_echo: bool,
+    #[serde(default)]
+    pub display_mode: DisplayMode,
 }
 
 impl Default for SerialConfig {
@@ -98,6 +163,14 @@ impl Default for SerialConfig {
             stop_bits: default_stop_bits(),
             parity: default_parity(),
             flow_control: default_flow_control(),
+            send_byte_delay_ms: 0,
+            send_line_delay_ms: 0,
+            strict_baud_rate: false,
+            dtr: None,
+            rts: None,
+            line_ending: LineEnding::None,
+            local_echo: false,
+            display_mode: DisplayMode::Text,
         }
     }
 }
@@ -115,6 +188,34 @@ pub enum ContainerRuntime {
     Podman,
 }
 
+/// How a Docker session's terminal connects to the container.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DockerSessionMode {
+    /// Run the shell with `docker exec`, falling back to attaching when the
+    /// image has no such executable.
+    #[default]
+    Auto,
+    /// Always run the shell with `docker exec`.
+    Exec,
+    /// Attach to the container's main process (the image's own command),
+    /// like `docker attach`.
+    Attach,
+}
+
+/// Whether a Docker session creates its own container or uses one that is
+/// already running.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DockerContainerMode {
+    /// Create a container from `image` for the session.
+    #[default]
+    Create,
+    /// Exec a shell in the running container named by `container_name`,
+    /// which is never stopped or removed by termiHub.
+    Attach,
+}
+
 /// Unified Docker container session configuration.
 ///
 /// Superset of desktop `DockerConfig` and agent `DockerSessionConfig`.
@@ -123,7 +224,14 @@ pub enum ContainerRuntime {
 pub struct DockerConfig {
     #[serde(default)]
     pub runtime: ContainerRuntime,
+    /// Whether to create a container or attach to a running one.
+    #[serde(default)]
+    pub container_mode: DockerContainerMode,
+    #[serde(default)]
     pub image: String,
+    /// Name or ID of the running container used in attach mode.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container_name: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub shell: Option<String>,
     #[serde(default = "default_cols")]
@@ -138,15 +246,53 @@ pub struct DockerConfig {
     pub working_directory: Option<String>,
     #[serde(default = "default_remove_on_exit")]
     pub remove_on_exit: bool,
+    /// Seconds to wait for the container to stop before it is killed.
+    #[serde(default = "default_stop_timeout_secs")]
+    pub stop_timeout_secs: u32,
+    /// Seconds to keep retrying while the container daemon is unreachable.
+    /// `0` gives up after the first attempt.
+    #[serde(default = "default_daemon_connect_timeout_secs")]
+    pub daemon_connect_timeout_secs: u32,
+    /// Linux capabilities removed from the container (e.g. `ALL`, `NET_RAW`).
+    #[serde(default)]
+    pub drop_capabilities: Vec<String>,
+    /// Linux capabilities granted to the container on top of the defaults.
+    #[serde(default)]
+    pub add_capabilities: Vec<String>,
+    /// Run the container in privileged mode.
+    #[serde(default)]
+    pub privileged: bool,
+    /// Memory limit in MiB; `0` means unlimited.
+    #[serde(default)]
+    pub memory_limit_mb: i64,
+    /// Number of CPUs the container may use, e.g. `1.5`; `0` means unlimited.
+    #[serde(default)]
+    pub cpus: f64,
+    /// Relative CPU weight against other containers; `0` keeps Docker's
+    /// default of 1024.
+    #[serde(default)]
+    pub cpu_shares: i64,
+    /// Whether the terminal runs the shell via exec or attaches to the
+    /// container's main process.
+    #[serde(default)]
+    pub session_mode: DockerSessionMode,
     #[serde(default)]
     pub env: HashMap<String, String>,
+    /// termiHub session the container belongs to, recorded as a label.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Saved connection the session was opened from, recorded as a label.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_id: Option<String>,
 }
 
 impl Default for DockerConfig {
     fn default() -> Self {
         Self {
             runtime: ContainerRuntime::Auto,
+            container_mode: DockerContainerMode::Create,
             image: String::new(),
+            container_name: None,
             shell: None,
             cols: default_cols(),
             rows: default_rows(),
@@ -154,7 +300,18 @@ impl Default for DockerConfig {
             volumes: Vec::new(),
             working_directory: None,
             remove_on_exit: default_remove_on_exit(),
+            stop_timeout_secs: default_stop_timeout_secs(),
+            daemon_connect_timeout_secs: default_daemon_connect_timeout_secs(),
+            drop_capabilities: Vec::new(),
+            add_capabilities: Vec::new(),
+            privileged: false,
+            memory_limit_mb: 0,
+            cpus: 0.0,
+            cpu_shares: 0,
+            session_mode: DockerSessionMode::Auto,
             env: HashMap::new(),
+            session_id: None,
+            connection_id: None,
         }
     }
 }
@@ -173,7 +330,18 @@ pub struct SshConfig {
     pub username: String,
     pub auth_method: String,
     pub password: Option<String>,
+    /// Password for the `password` fallback method when `auth_method` is
+    /// another method, whose secret (e.g. the key passphrase) `password`
+    /// then holds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_password: Option<String>,
     pub key_path: Option<String>,
+    /// Additional private keys tried in order after `key_path` for key auth.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub identity_files: Vec<String>,
+    /// Authentication methods tried in order when `auth_method` fails.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auth_fallback: Vec<String>,
     pub shell: Option<String>,
     #[serde(default = "default_cols")]
     pub cols: u16,
@@ -181,6 +349,10 @@ pub struct SshConfig {
     pub rows: u16,
     #[serde(default)]
     pub env: HashMap<String, String>,
+    /// Locale (e.g. `en_US.UTF-8`) sent to the remote shell as `LANG` and
+    /// `LC_ALL`, unless `env` sets those itself (see [`SshConfig::locale_env`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
     #[serde(default)]
     pub enable_x11_forwarding: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -189,6 +361,71 @@ pub struct SshConfig {
     pub enable_file_browser: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub save_password: Option<bool>,
+    /// Local port forwards opened on connect and closed on disconnect.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub session_forwards: Vec<SshForward>,
+    /// Negotiate SSH (zlib) compression on SFTP transfer sessions.
+    #[serde(default)]
+    pub transfer_compression: bool,
+    /// Local IP address the outgoing TCP connection is bound to, for
+    /// multi-homed hosts. `None` lets the OS choose.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bind_address: Option<String>,
+    /// OpenSSH `ssh_config` file whose matching `Host` block supplies
+    /// defaults for unset fields (see [`SshConfig::apply_ssh_config_file`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ssh_config_file: Option<String>,
+    /// Comma-separated chain of `[user@]host[:port]` jump hosts the
+    /// connection is tunneled through, like OpenSSH's `ProxyJump`. Every
+    /// hop authenticates with this config's credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy_jump: Option<String>,
+    /// Fully configured jump hosts, outermost first, used instead of the
+    /// hops derived from `proxy_jump` when not empty. Filled in when hops
+    /// refer to saved connections so that each uses its own credentials.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub jump_hosts: Vec<SshConfig>,
+    /// Seconds to wait for the TCP connection to each server address, like
+    /// OpenSSH's `ConnectTimeout` (0 waits as long as the OS does). The
+    /// SSH handshake and authentication are not covered.
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u32,
+    /// Seconds between SSH keepalives (0 disables them), like OpenSSH's
+    /// `ServerAliveInterval`.
+    #[serde(default)]
+    pub keepalive_interval: u32,
+    /// Consecutive unanswered keepalives after which the connection is
+    /// considered lost, like OpenSSH's `ServerAliveCountMax`.
+    #[serde(default = "default_keepalive_count_max")]
+    pub keepalive_count_max: u32,
+    /// How the server's host key is checked against `known_hosts`, like
+    /// OpenSSH's `StrictHostKeyChecking`: `accept-new` records unknown
+    /// hosts and rejects changed keys, `strict` also rejects unknown hosts,
+    /// and `off` skips the check.
+    #[serde(default = "default_strict_host_key_checking")]
+    pub strict_host_key_checking: String,
+    /// `known_hosts` file host keys are checked against and recorded in.
+    /// `None` uses `~/.ssh/known_hosts`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub known_hosts_file: Option<String>,
+    /// Directory the file browser opens to. `~` and `${env:HOME}` refer to
+    /// the remote home; `None` opens the remote home.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_browser_root: Option<String>,
+    /// Comma-separated metric groups the monitoring command collects
+    /// (`cpu`, `mem`, `disk`, `net`, `processes`). `None` collects the
+    /// default groups.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub monitoring_metrics: Option<String>,
+    /// Command template for editing this connection's files locally
+    /// (e.g. `nvim {path}`). `None` uses the app-wide editor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor_command: Option<String>,
+    /// Privilege prefix (e.g. `sudo -S`) the shell and one-shot commands
+    /// run through; see [`crate::backends::ssh::sudo`]. `None` runs them
+    /// as the login user.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sudo_prefix: Option<String>,
 }
 
 impl Default for SshConfig {
@@ -199,15 +436,34 @@ impl Default for SshConfig {
             username: String::new(),
             auth_method: String::new(),
             password: None,
+            fallback_password: None,
             key_path: None,
+            identity_files: Vec::new(),
+            auth_fallback: Vec::new(),
             shell: None,
             cols: default_cols(),
             rows: default_rows(),
             env: HashMap::new(),
+            locale: None,
             enable_x11_forwarding: false,
             enable_monitoring: None,
             enable_file_browser: None,
             save_password: None,
+            session_forwards: Vec::new(),
+            transfer_compression: false,
+            bind_address: None,
+            ssh_config_file: None,
+            proxy_jump: None,
+            jump_hosts: Vec::new(),
+            keepalive_interval: 0,
+            connect_timeout_secs: default_connect_timeout_secs(),
+            keepalive_count_max: default_keepalive_count_max(),
+            strict_host_key_checking: default_strict_host_key_checking(),
+            known_hosts_file: None,
+            file_browser_root: None,
+            monitoring_metrics: None,
+            editor_command: None,
+            sudo_prefix: None,
         }
     }
 }
@@ -221,6 +477,9 @@ impl Default for SshConfig {
 pub struct WslConfig {
     /// WSL distribution name (e.g., `"Ubuntu"`, `"Debian"`).
     pub distribution: String,
+    /// Linux user to log in as (defaults to the distribution's default user).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
     /// Directory to start the shell in (within the WSL filesystem).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub starting_directory: Option<String>,
@@ -242,6 +501,7 @@ impl Default for WslConfig {
     fn default() -> Self {
         Self {
             distribution: String::new(),
+            user: None,
             starting_directory: None,
             initial_command: None,
             cols: default_cols(),
@@ -255,10 +515,19 @@ impl Default for WslConfig {
 ///
 /// Shared between desktop and agent telnet backends.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TelnetConfig {
     pub host: String,
     #[serde(default = "default_telnet_port")]
     pub port: u16,
+    /// Seconds to wait for the TCP connection; `0` waits for the OS timeout.
+    #[serde(default = "default_telnet_connect_timeout_secs")]
+    pub connect_timeout_secs: u32,
+    /// Reconnect with exponential backoff when the connection drops.
+    #[serde(default)]
+    pub auto_reconnect: bool,
+    #[serde(default)]
+    pub line_ending: LineEnding,
 }
 
 impl Default for TelnetConfig {
@@ -266,6 +535,9 @@ impl Default for TelnetConfig {
         Self {
             host: String::new(),
             port: default_telnet_port(),
+            connect_timeout_secs: default_telnet_connect_timeout_secs(),
+            auto_reconnect: false,
+            line_ending: LineEnding::None,
         }
     }
 }
@@ -292,6 +564,7 @@ impl WslConfig {
     /// Return a copy with all `${env:...}` placeholders and `~` expanded.
     pub fn expand(mut self) -> Self {
         self.distribution = expand::expand_env_placeholders(&self.distribution);
+        self.user = self.user.map(|s| expand::expand_env_placeholders(&s));
         self.starting_directory = self
             .starting_directory
             .map(|s| expand::expand_tilde(&expand::expand_env_placeholders(&s)));
@@ -319,24 +592,119 @@ impl SerialConfig {
 }
 
 impl SshConfig {
+    /// Metric groups selected by `monitoring_metrics`.
+    pub fn monitoring_groups(&self) -> Vec<MetricGroup> {
+        match self.monitoring_metrics.as_deref() {
+            Some(list) => MetricGroup::parse_list(list),
+            None => MetricGroup::DEFAULT.to_vec(),
+        }
+    }
+
+    /// Environment variables `locale` expands into: `LANG` and `LC_ALL`,
+    /// each skipped when `env` already sets it. Empty without a locale.
+    pub fn locale_env(&self) -> Vec<(String, String)> {
+        let Some(locale) = self.locale.as_deref().map(str::trim) else {
+            return Vec::new();
+        };
+        if locale.is_empty() {
+            return Vec::new();
+        }
+        ["LANG", "LC_ALL"]
+            .into_iter()
+            .filter(|key| !self.env.contains_key(*key))
+            .map(|key| (key.to_string(), locale.to_string()))
+            .collect()
+    }
+
     /// Return a copy with all `${env:...}` placeholders and `~` expanded.
     pub fn expand(mut self) -> Self {
         self.host = expand::expand_env_placeholders(&self.host);
         self.username = expand::expand_env_placeholders(&self.username);
-        self.key_path = self.key_path.map(|s| {
+        let expand_key_path = |s: String| {
             // Strip surrounding quotes — users often paste paths like "C:\...\key"
             let stripped = s.trim().trim_matches('"').trim_matches('\'');
             expand::expand_tilde(&expand::expand_env_placeholders(stripped))
-        });
+        };
+        self.key_path = self.key_path.map(expand_key_path);
+        self.identity_files = self
+            .identity_files
+            .into_iter()
+            .map(expand_key_path)
+            .collect();
         self.password = self.password.map(|s| expand::expand_env_placeholders(&s));
+        self.fallback_password = self
+            .fallback_password
+            .map(|s| expand::expand_env_placeholders(&s));
+        self.ssh_config_file = self
+            .ssh_config_file
+            .map(|s| expand::expand_tilde(&expand::expand_env_placeholders(s.trim())));
+        self.proxy_jump = self.proxy_jump.map(|s| expand::expand_env_placeholders(&s));
+        self.known_hosts_file = self
+            .known_hosts_file
+            .map(|s| expand::expand_tilde(&expand::expand_env_placeholders(s.trim())));
+        self.jump_hosts = self.jump_hosts.into_iter().map(Self::expand).collect();
         self
     }
+
+    /// Fill unset fields from the `ssh_config_file`, if one is configured.
+    ///
+    /// `host` is looked up as a `Host` alias and replaced by the block's
+    /// `HostName`. `Port` applies when the port is the default 22, `User`
+    /// when the username is empty, `ProxyJump` when no jump hosts are set,
+    /// and `IdentityFile` entries when no key is configured. Explicitly set
+    /// fields always take precedence. `ProxyJump` hops are used as written,
+    /// not looked up as `Host` aliases.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CoreError::Config`] when the file cannot be parsed.
+    pub fn apply_ssh_config_file(mut self) -> Result<Self, CoreError> {
+        let Some(path) = self.ssh_config_file.as_deref() else {
+            return Ok(self);
+        };
+        let resolved = ssh_config_file::resolve_ssh_host(Path::new(path), &self.host)?;
+
+        let has_jump = self
+            .proxy_jump
+            .as_deref()
+            .is_some_and(|j| !j.trim().is_empty());
+        if !has_jump {
+            self.proxy_jump = resolved
+                .proxy_jump
+                .filter(|j| !j.eq_ignore_ascii_case("none"));
+        }
+        if let Some(host_name) = resolved.host_name {
+            self.host = host_name;
+        }
+        if self.port == default_ssh_port() {
+            self.port = resolved.port.unwrap_or(self.port);
+        }
+        if self.username.trim().is_empty() {
+            self.username = resolved.user.unwrap_or_default();
+        }
+        let has_key = self
+            .key_path
+            .as_deref()
+            .is_some_and(|p| !p.trim().is_empty())
+            || self.identity_files.iter().any(|p| !p.trim().is_empty());
+        if !has_key && !resolved.identity_files.is_empty() {
+            self.identity_files = resolved
+                .identity_files
+                .iter()
+                .map(|p| expand::expand_tilde(p))
+                .collect();
+        }
+        Ok(self)
+    }
 }
 
 impl DockerConfig {
     /// Return a copy with all `${env:...}` placeholders and `~` expanded.
     pub fn expand(mut self) -> Self {
         self.image = expand::expand_env_placeholders(&self.image);
+        self.container_name = self
+            .container_name
+            .map(|s| expand::expand_env_placeholders(&s));
         self.shell = self.shell.map(|s| expand::expand_env_placeholders(&s));
         self.working_directory = self
             .working_directory
@@ -387,14 +755,50 @@ fn default_remove_on_exit() -> bool {
     true
 }
 
+fn default_stop_timeout_secs() -> u32 {
+    5
+}
+
+fn default_daemon_connect_timeout_secs() -> u32 {
+    10
+}
+
 fn default_ssh_port() -> u16 {
     22
 }
 
+/// Seconds an SSH connection attempt may take before it is abandoned.
+pub const DEFAULT_SSH_CONNECT_TIMEOUT_SECS: u32 = 15;
+
+fn default_connect_timeout_secs() -> u32 {
+    DEFAULT_SSH_CONNECT_TIMEOUT_SECS
+}
+
+/// Unanswered SSH keepalives tolerated before the connection is dropped.
+pub const DEFAULT_KEEPALIVE_COUNT_MAX: u32 = 3;
+
+fn default_keepalive_count_max() -> u32 {
+    DEFAULT_KEEPALIVE_COUNT_MAX
+}
+
+/// Host key checking mode used when none is configured.
+pub const DEFAULT_STRICT_HOST_KEY_CHECKING: &str = "accept-new";
+
+fn default_strict_host_key_checking() -> String {
+    DEFAULT_STRICT_HOST_KEY_CHECKING.to_string()
+}
+
 fn default_telnet_port() -> u16 {
     23
 }
 
+/// Seconds a telnet connection attempt may take before it is abandoned.
+pub const DEFAULT_TELNET_CONNECT_TIMEOUT_SECS: u32 = 10;
+
+fn default_telnet_connect_timeout_secs() -> u32 {
+    DEFAULT_TELNET_CONNECT_TIMEOUT_SECS
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -428,6 +832,9 @@ mod tests {
         assert_eq!(cfg.stop_bits, 1);
         assert_eq!(cfg.parity, "none");
         assert_eq!(cfg.flow_control, "none");
+        assert_eq!(cfg.line_ending, LineEnding::None);
+        assert!(!cfg.local_echo);
+        assert_eq!(cfg.display_mode, DisplayMode::Text);
     }
 
     #[test]
@@ -435,6 +842,9 @@ mod tests {
         let cfg = TelnetConfig::default();
         assert!(cfg.host.is_empty());
         assert_eq!(cfg.port, 23);
+        assert_eq!(cfg.connect_timeout_secs, 10);
+        assert!(!cfg.auto_reconnect);
+        assert_eq!(cfg.line_ending, LineEnding::None);
     }
 
     #[test]
@@ -507,6 +917,9 @@ mod tests {
         assert!(cfg.enable_monitoring.is_none());
         assert!(cfg.enable_file_browser.is_none());
         assert!(cfg.save_password.is_none());
+        assert_eq!(cfg.connect_timeout_secs, 15);
+        assert_eq!(cfg.strict_host_key_checking, "accept-new");
+        assert!(cfg.known_hosts_file.is_none());
     }
 
     // --- Serde round-trip tests ---
@@ -578,15 +991,33 @@ mod tests {
             stop_bits: 2,
             parity: "even".into(),
             flow_control: "hardware".into(),
+            send_byte_delay_ms: 2,
+            send_line_delay_ms: 50,
+            strict_baud_rate: true,
+            dtr: Some(false),
+            rts: Some(true),
+            line_ending: LineEnding::Crlf,
+            local_echo: true,
+            display_mode: DisplayMode::Hex,
         };
         let json = serde_json::to_string(&cfg).unwrap();
+        assert!(json.contains("\"lineEnding\":\"crlf\""));
+        assert!(json.contains("\"displayMode\":\"hex\""));
         let back: SerialConfig = serde_json::from_str(&json).unwrap();
         assert_eq!(back.port, "/dev/ttyUSB0");
         assert_eq!(back.baud_rate, 9600);
         assert_eq!(back.data_bits, 7);
         assert_eq!(back.stop_bits, 2);
         assert_eq!(back.parity, "even");
+        assert_eq!(back.send_byte_delay_ms, 2);
+        assert!(back.strict_baud_rate);
+        assert_eq!(back.send_line_delay_ms, 50);
         assert_eq!(back.flow_control, "hardware");
+        assert_eq!(back.dtr, Some(false));
+        assert_eq!(back.rts, Some(true));
+        assert_eq!(back.line_ending, LineEnding::Crlf);
+        assert!(back.local_echo);
+        assert_eq!(back.display_mode, DisplayMode::Hex);
     }
 
     #[test]
@@ -594,18 +1025,27 @@ mod tests {
         let cfg = TelnetConfig {
             host: "example.com".into(),
             port: 2323,
+            connect_timeout_secs: 3,
+            auto_reconnect: true,
+            line_ending: LineEnding::Cr,
         };
         let json = serde_json::to_string(&cfg).unwrap();
+        assert!(json.contains("\"connectTimeoutSecs\":3"));
         let back: TelnetConfig = serde_json::from_str(&json).unwrap();
         assert_eq!(back.host, "example.com");
         assert_eq!(back.port, 2323);
+        assert_eq!(back.connect_timeout_secs, 3);
+        assert!(back.auto_reconnect);
+        assert_eq!(back.line_ending, LineEnding::Cr);
     }
 
     #[test]
     fn docker_config_roundtrip() {
         let cfg = DockerConfig {
             runtime: ContainerRuntime::Podman,
+            container_mode: DockerContainerMode::Attach,
             image: "ubuntu:22.04".into(),
+            container_name: Some("web-1".into()),
             shell: Some("/bin/bash".into()),
             cols: 80,
             rows: 24,
@@ -620,23 +1060,48 @@ mod tests {
             }],
             working_directory: Some("/app".into()),
             remove_on_exit: false,
+            stop_timeout_secs: 30,
+            daemon_connect_timeout_secs: 3,
+            drop_capabilities: vec!["ALL".into()],
+            add_capabilities: vec!["NET_BIND_SERVICE".into()],
+            privileged: false,
+            memory_limit_mb: 512,
+            cpus: 1.5,
+            cpu_shares: 256,
+            session_mode: DockerSessionMode::Attach,
             env: HashMap::from([("LANG".into(), "en_US.UTF-8".into())]),
+            session_id: Some("sess-1".into()),
+            connection_id: None,
         };
         let json = serde_json::to_string(&cfg).unwrap();
         let back: DockerConfig = serde_json::from_str(&json).unwrap();
         assert_eq!(back.runtime, ContainerRuntime::Podman);
+        assert_eq!(back.container_mode, DockerContainerMode::Attach);
         assert_eq!(back.image, "ubuntu:22.04");
+        assert_eq!(back.container_name.as_deref(), Some("web-1"));
+        assert_eq!(back.memory_limit_mb, 512);
+        assert_eq!(back.cpus, 1.5);
+        assert_eq!(back.cpu_shares, 256);
         assert_eq!(back.shell.as_deref(), Some("/bin/bash"));
         assert_eq!(back.env_vars.len(), 1);
         assert_eq!(back.volumes.len(), 1);
         assert!(!back.remove_on_exit);
+        assert_eq!(back.stop_timeout_secs, 30);
+        assert_eq!(back.daemon_connect_timeout_secs, 3);
+        assert_eq!(back.drop_capabilities, vec!["ALL"]);
+        assert_eq!(back.add_capabilities, vec!["NET_BIND_SERVICE"]);
+        assert!(!back.privileged);
+        assert_eq!(back.session_mode, DockerSessionMode::Attach);
         assert_eq!(back.env.get("LANG").unwrap(), "en_US.UTF-8");
+        assert_eq!(back.session_id.as_deref(), Some("sess-1"));
+        assert!(back.connection_id.is_none());
     }
 
     #[test]
     fn wsl_config_roundtrip() {
         let cfg = WslConfig {
             distribution: "Ubuntu".into(),
+            user: Some("dev".into()),
             starting_directory: Some("/home/user".into()),
             initial_command: Some("ls".into()),
             cols: 100,
@@ -646,6 +1111,7 @@ mod tests {
         let json = serde_json::to_string(&cfg).unwrap();
         let back: WslConfig = serde_json::from_str(&json).unwrap();
         assert_eq!(back.distribution, "Ubuntu");
+        assert_eq!(back.user.as_deref(), Some("dev"));
         assert_eq!(back.starting_directory.as_deref(), Some("/home/user"));
         assert_eq!(back.initial_command.as_deref(), Some("ls"));
         assert_eq!(back.cols, 100);
@@ -661,7 +1127,10 @@ mod tests {
             username: "admin".into(),
             auth_method: "key".into(),
             password: None,
+            fallback_password: Some("fallback".into()),
             key_path: Some("/home/admin/.ssh/id_ed25519".into()),
+            identity_files: vec!["/home/admin/.ssh/id_rsa".into()],
+            auth_fallback: vec!["agent".into(), "password".into()],
             shell: Some("/bin/bash".into()),
             cols: 132,
             rows: 43,
@@ -670,9 +1139,36 @@ mod tests {
             enable_monitoring: Some(true),
             enable_file_browser: Some(false),
             save_password: None,
+            session_forwards: vec![SshForward {
+                local_port: 8080,
+                remote_host: "localhost".into(),
+                remote_port: 80,
+            }],
+            transfer_compression: true,
+            bind_address: Some("10.0.0.5".into()),
+            ssh_config_file: Some("~/.ssh/config".into()),
+            proxy_jump: Some("ops@bastion:2204".into()),
+            jump_hosts: vec![SshConfig {
+                host: "bastion".into(),
+                port: 2204,
+                username: "ops".into(),
+                auth_method: "agent".into(),
+                ..SshConfig::default()
+            }],
+            connect_timeout_secs: 5,
+            keepalive_interval: 15,
+            keepalive_count_max: 2,
+            strict_host_key_checking: "strict".into(),
+            known_hosts_file: Some("/etc/ssh/ssh_known_hosts".into()),
+            file_browser_root: Some("~/projects".into()),
+            monitoring_metrics: Some("cpu,net".into()),
+            editor_command: Some("nvim {path}".into()),
+            locale: Some("de_DE.UTF-8".into()),
+            sudo_prefix: Some("sudo -S".into()),
         };
         let json = serde_json::to_string(&cfg).unwrap();
         let back: SshConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.session_forwards, cfg.session_forwards);
         assert_eq!(back.host, "example.com");
         assert_eq!(back.port, 2222);
         assert_eq!(back.username, "admin");
@@ -682,10 +1178,67 @@ mod tests {
             back.key_path.as_deref(),
             Some("/home/admin/.ssh/id_ed25519")
         );
+        assert_eq!(back.identity_files, cfg.identity_files);
+        assert_eq!(back.auth_fallback, cfg.auth_fallback);
+        assert_eq!(back.fallback_password.as_deref(), Some("fallback"));
         assert!(back.enable_x11_forwarding);
         assert_eq!(back.enable_monitoring, Some(true));
         assert_eq!(back.enable_file_browser, Some(false));
         assert!(back.save_password.is_none());
+        assert!(back.transfer_compression);
+        assert_eq!(back.bind_address.as_deref(), Some("10.0.0.5"));
+        assert_eq!(back.proxy_jump.as_deref(), Some("ops@bastion:2204"));
+        assert_eq!(back.jump_hosts.len(), 1);
+        assert_eq!(back.jump_hosts[0].port, 2204);
+        assert_eq!(back.keepalive_interval, 15);
+        assert_eq!(back.connect_timeout_secs, 5);
+        assert_eq!(back.keepalive_count_max, 2);
+        assert_eq!(back.strict_host_key_checking, "strict");
+        assert_eq!(
+            back.known_hosts_file.as_deref(),
+            Some("/etc/ssh/ssh_known_hosts")
+        );
+        assert_eq!(back.file_browser_root.as_deref(), Some("~/projects"));
+        assert_eq!(
+            back.monitoring_groups(),
+            vec![MetricGroup::Cpu, MetricGroup::Network]
+        );
+        assert_eq!(back.locale.as_deref(), Some("de_DE.UTF-8"));
+        assert_eq!(back.sudo_prefix.as_deref(), Some("sudo -S"));
+    }
+
+    #[test]
+    fn ssh_locale_expands_to_lang_and_lc_all() {
+        let cfg = SshConfig {
+            locale: Some("en_US.UTF-8".into()),
+            ..SshConfig::default()
+        };
+        assert_eq!(
+            cfg.locale_env(),
+            vec![
+                ("LANG".to_string(), "en_US.UTF-8".to_string()),
+                ("LC_ALL".to_string(), "en_US.UTF-8".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn ssh_locale_env_yields_to_explicit_env() {
+        let cfg = SshConfig {
+            locale: Some("C.UTF-8".into()),
+            env: HashMap::from([("LC_ALL".into(), "POSIX".into())]),
+            ..SshConfig::default()
+        };
+        assert_eq!(
+            cfg.locale_env(),
+            vec![("LANG".to_string(), "C.UTF-8".to_string())]
+        );
+        assert!(SshConfig::default().locale_env().is_empty());
+        let blank = SshConfig {
+            locale: Some("  ".into()),
+            ..SshConfig::default()
+        };
+        assert!(blank.locale_env().is_empty());
     }
 
     // --- camelCase field name tests ---
@@ -814,6 +1367,7 @@ mod tests {
         let json = r#"{"distribution": "Debian"}"#;
         let cfg: WslConfig = serde_json::from_str(json).unwrap();
         assert_eq!(cfg.distribution, "Debian");
+        assert!(cfg.user.is_none());
         assert!(cfg.starting_directory.is_none());
         assert!(cfg.initial_command.is_none());
         assert_eq!(cfg.cols, 80);
@@ -994,4 +1548,82 @@ mod tests {
         std::env::remove_var("TERMIHUB_TEST_WSL_DISTRO");
         std::env::remove_var("TERMIHUB_TEST_WSL_CMD");
     }
+
+    fn ssh_config_with_file(contents: &str) -> (tempfile::TempDir, SshConfig) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config");
+        std::fs::write(&path, contents).unwrap();
+        let cfg = SshConfig {
+            host: "web".into(),
+            auth_method: "key".into(),
+            ssh_config_file: Some(path.to_string_lossy().into_owned()),
+            ..SshConfig::default()
+        };
+        (dir, cfg)
+    }
+
+    const WEB_HOST_BLOCK: &str = "\
+Host web
+    HostName web.example.com
+    Port 2222
+    User deploy
+    IdentityFile /keys/web_ed25519
+";
+
+    #[test]
+    fn ssh_config_file_supplies_defaults() {
+        let (_dir, cfg) = ssh_config_with_file(WEB_HOST_BLOCK);
+        let applied = cfg.apply_ssh_config_file().unwrap();
+        assert_eq!(applied.host, "web.example.com");
+        assert_eq!(applied.port, 2222);
+        assert_eq!(applied.username, "deploy");
+        assert_eq!(applied.identity_files, vec!["/keys/web_ed25519"]);
+    }
+
+    #[test]
+    fn explicit_ssh_fields_override_config_file() {
+        let (_dir, cfg) = ssh_config_with_file(WEB_HOST_BLOCK);
+        let cfg = SshConfig {
+            port: 2200,
+            username: "admin".into(),
+            key_path: Some("/keys/admin".into()),
+            ..cfg
+        };
+        let applied = cfg.apply_ssh_config_file().unwrap();
+        assert_eq!(applied.host, "web.example.com");
+        assert_eq!(applied.port, 2200);
+        assert_eq!(applied.username, "admin");
+        assert_eq!(applied.key_path.as_deref(), Some("/keys/admin"));
+        assert!(applied.identity_files.is_empty());
+    }
+
+    #[test]
+    fn ssh_config_file_without_matching_host_changes_nothing() {
+        let (_dir, cfg) = ssh_config_with_file("Host other\n    User nobody\n");
+        let applied = cfg.apply_ssh_config_file().unwrap();
+        assert_eq!(applied.host, "web");
+        assert_eq!(applied.port, 22);
+        assert!(applied.username.is_empty());
+    }
+
+    #[test]
+    fn ssh_config_file_supplies_proxy_jump() {
+        let (_dir, cfg) = ssh_config_with_file("Host web\n    ProxyJump ops@bastion:2204\n");
+        let cfg = cfg.apply_ssh_config_file().unwrap();
+        assert_eq!(cfg.proxy_jump.as_deref(), Some("ops@bastion:2204"));
+    }
+
+    #[test]
+    fn ssh_config_file_proxy_jump_does_not_override_explicit_value() {
+        let (_dir, mut cfg) = ssh_config_with_file("Host web\n    ProxyJump bastion\n");
+        cfg.proxy_jump = Some("gateway".into());
+        let cfg = cfg.apply_ssh_config_file().unwrap();
+        assert_eq!(cfg.proxy_jump.as_deref(), Some("gateway"));
+    }
+
+    #[test]
+    fn ssh_config_file_proxy_jump_none_is_ignored() {
+        let (_dir, cfg) = ssh_config_with_file("Host web\n    ProxyJump none\n");
+        assert!(cfg.apply_ssh_config_file().unwrap().proxy_jump.is_none());
+    }
 }
@@ -0,0 +1,368 @@
+//! Minimal OpenSSH `ssh_config` reader.
+//!
+//! Resolves the options for a single host alias the way `ssh` does: blocks
+//! are scanned top to bottom and the first value found for each keyword
+//! wins. Only the keywords termiHub can use are collected (`HostName`,
+//! `Port`, `User`, `IdentityFile`, `ProxyJump`); `Include` is followed and
+//! `Match` blocks are skipped.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::expand::expand_tilde;
+use crate::errors::CoreError;
+
+/// Maximum `Include` nesting, matching OpenSSH.
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Options resolved for one host from an `ssh_config` file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SshHostConfig {
+    pub host_name: Option<String>,
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    /// `IdentityFile` entries in the order they appear (the keyword is
+    /// cumulative in OpenSSH).
+    pub identity_files: Vec<String>,
+    pub proxy_jump: Option<String>,
+}
+
+/// Read `path` and resolve the options that apply to `host`.
+///
+/// # Errors
+///
+/// Returns [`CoreError::Config`] when the file (or an included file) cannot
+/// be read, a `Port` value is invalid, or includes nest too deeply.
+pub fn resolve_ssh_host(path: &Path, host: &str) -> Result<SshHostConfig, CoreError> {
+    let mut resolved = SshHostConfig::default();
+    read_file(path, host, &mut resolved, 0)?;
+    Ok(resolved)
+}
+
+/// Resolve the options for `host` from already-loaded config text.
+///
+/// Relative `Include` paths are resolved against `base_dir`.
+pub fn resolve_ssh_host_from_str(
+    text: &str,
+    base_dir: &Path,
+    host: &str,
+) -> Result<SshHostConfig, CoreError> {
+    let mut resolved = SshHostConfig::default();
+    apply_text(text, base_dir, host, &mut resolved, 0)?;
+    Ok(resolved)
+}
+
+fn read_file(
+    path: &Path,
+    host: &str,
+    resolved: &mut SshHostConfig,
+    depth: usize,
+) -> Result<(), CoreError> {
+    let text = fs::read_to_string(path).map_err(|e| {
+        CoreError::Config(format!(
+            "Cannot read SSH config file {}: {e}",
+            path.display()
+        ))
+    })?;
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    apply_text(&text, base_dir, host, resolved, depth)
+}
+
+fn apply_text(
+    text: &str,
+    base_dir: &Path,
+    host: &str,
+    resolved: &mut SshHostConfig,
+    depth: usize,
+) -> Result<(), CoreError> {
+    if depth > MAX_INCLUDE_DEPTH {
+        return Err(CoreError::Config(
+            "SSH config includes nest too deeply".to_string(),
+        ));
+    }
+
+    // Lines before the first Host/Match block apply to every host.
+    let mut active = true;
+    for line in text.lines() {
+        let Some((keyword, args)) = split_line(line) else {
+            continue;
+        };
+        match keyword.to_ascii_lowercase().as_str() {
+            "host" => active = host_matches(host, &args),
+            "match" => active = false,
+            "include" if active => {
+                for pattern in &args {
+                    for path in include_paths(base_dir, pattern) {
+                        read_file(&path, host, resolved, depth + 1)?;
+                    }
+                }
+            }
+            _ if !active => {}
+            "hostname" => set_once(&mut resolved.host_name, &args),
+            "user" => set_once(&mut resolved.user, &args),
+            "proxyjump" => set_once(&mut resolved.proxy_jump, &args),
+            "identityfile" => resolved.identity_files.extend(args.first().cloned()),
+            "port" if resolved.port.is_none() => {
+                if let Some(value) = args.first() {
+                    let port = value.parse().map_err(|_| {
+                        CoreError::Config(format!("Invalid Port in SSH config: {value}"))
+                    })?;
+                    resolved.port = Some(port);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn set_once(slot: &mut Option<String>, args: &[String]) {
+    if slot.is_none() {
+        *slot = args.first().cloned();
+    }
+}
+
+/// Split a config line into its keyword and arguments.
+///
+/// Accepts both `Keyword value` and `Keyword=value`, strips comments and
+/// honours double-quoted arguments.
+fn split_line(line: &str) -> Option<(String, Vec<String>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let end = line
+        .find(|c: char| c.is_whitespace() || c == '=')
+        .unwrap_or(line.len());
+    let keyword = line[..end].to_string();
+    let rest = line[end..].trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest);
+
+    let mut args = Vec::new();
+    let mut chars = rest.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '#' {
+            break;
+        } else if c == '"' {
+            chars.next();
+            args.push(chars.by_ref().take_while(|&c| c != '"').collect());
+        } else {
+            let mut arg = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                arg.push(c);
+                chars.next();
+            }
+            args.push(arg);
+        }
+    }
+    Some((keyword, args))
+}
+
+/// Whether `host` matches a `Host` line's pattern list.
+///
+/// A matching negated pattern (`!pattern`) excludes the host even if
+/// another pattern matches.
+fn host_matches(host: &str, patterns: &[String]) -> bool {
+    let mut matched = false;
+    for pattern in patterns {
+        if let Some(negated) = pattern.strip_prefix('!') {
+            if wildcard_match(negated, host) {
+                return false;
+            }
+        } else if wildcard_match(pattern, host) {
+            matched = true;
+        }
+    }
+    matched
+}
+
+/// Match `text` against a pattern using `*` and `?` wildcards.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((bp, bt)) => {
+                    p = bp + 1;
+                    t = bt + 1;
+                    backtrack = Some((bp, bt + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Expand an `Include` argument to the files it names, in sorted order.
+///
+/// Wildcards are supported in the file name component only; a pattern
+/// that matches nothing is silently ignored, as in OpenSSH.
+fn include_paths(base_dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    let expanded = PathBuf::from(expand_tilde(pattern));
+    let path = if expanded.is_absolute() {
+        expanded
+    } else {
+        base_dir.join(expanded)
+    };
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return Vec::new();
+    };
+    if !name.contains(['*', '?']) {
+        return if path.is_file() {
+            vec![path]
+        } else {
+            Vec::new()
+        };
+    }
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| {
+            entry
+                .file_name()
+                .to_str()
+                .is_some_and(|n| wildcard_match(name, n))
+        })
+        .map(|entry| entry.path())
+        .filter(|p| p.is_file())
+        .collect();
+    paths.sort();
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve(text: &str, host: &str) -> SshHostConfig {
+        resolve_ssh_host_from_str(text, Path::new("."), host).unwrap()
+    }
+
+    #[test]
+    fn resolves_matching_host_block() {
+        let text = "\
+Host other
+    HostName other.example.com
+
+Host web
+    HostName web.example.com
+    Port 2222
+    User deploy
+    IdentityFile ~/.ssh/web_ed25519
+    ProxyJump bastion
+";
+        let resolved = resolve(text, "web");
+        assert_eq!(resolved.host_name.as_deref(), Some("web.example.com"));
+        assert_eq!(resolved.port, Some(2222));
+        assert_eq!(resolved.user.as_deref(), Some("deploy"));
+        assert_eq!(resolved.identity_files, vec!["~/.ssh/web_ed25519"]);
+        assert_eq!(resolved.proxy_jump.as_deref(), Some("bastion"));
+    }
+
+    #[test]
+    fn first_value_wins_and_identity_files_accumulate() {
+        let text = "\
+Host web
+    User first
+    IdentityFile key_a
+Host *
+    User second
+    Port 2200
+    IdentityFile key_b
+";
+        let resolved = resolve(text, "web");
+        assert_eq!(resolved.user.as_deref(), Some("first"));
+        assert_eq!(resolved.port, Some(2200));
+        assert_eq!(resolved.identity_files, vec!["key_a", "key_b"]);
+    }
+
+    #[test]
+    fn host_patterns_support_wildcards_and_negation() {
+        let text = "\
+Host *.prod !db.prod
+    User ops
+Host db?
+    User dba
+";
+        assert_eq!(resolve(text, "web.prod").user.as_deref(), Some("ops"));
+        assert_eq!(resolve(text, "db.prod").user, None);
+        assert_eq!(resolve(text, "db1").user.as_deref(), Some("dba"));
+        assert_eq!(resolve(text, "db12").user, None);
+    }
+
+    #[test]
+    fn accepts_equals_syntax_quotes_and_comments() {
+        let text = "\
+# global defaults
+User=root
+Host \"web\"
+    HostName = web.example.com # trailing comment
+";
+        let resolved = resolve(text, "web");
+        assert_eq!(resolved.user.as_deref(), Some("root"));
+        assert_eq!(resolved.host_name.as_deref(), Some("web.example.com"));
+    }
+
+    #[test]
+    fn match_blocks_are_skipped() {
+        let text = "\
+Match user root
+    User ignored
+Host *
+    User used
+";
+        assert_eq!(resolve(text, "any").user.as_deref(), Some("used"));
+    }
+
+    #[test]
+    fn invalid_port_is_an_error() {
+        let err = resolve_ssh_host_from_str("Port ssh\n", Path::new("."), "web").unwrap_err();
+        assert!(err.to_string().contains("Invalid Port"), "{err}");
+    }
+
+    #[test]
+    fn follows_includes_relative_to_including_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let conf_d = dir.path().join("conf.d");
+        fs::create_dir(&conf_d).unwrap();
+        fs::write(conf_d.join("10-web.conf"), "Host web\n    Port 2022\n").unwrap();
+        fs::write(conf_d.join("20-all.conf"), "Host *\n    User shared\n").unwrap();
+        let main = dir.path().join("config");
+        fs::write(
+            &main,
+            "Include conf.d/*.conf\nHost web\n    HostName web.internal\n",
+        )
+        .unwrap();
+
+        let resolved = resolve_ssh_host(&main, "web").unwrap();
+        assert_eq!(resolved.port, Some(2022));
+        assert_eq!(resolved.user.as_deref(), Some("shared"));
+        assert_eq!(resolved.host_name.as_deref(), Some("web.internal"));
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(resolve_ssh_host(&dir.path().join("nope"), "web").is_err());
+    }
+}
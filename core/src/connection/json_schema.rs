@@ -0,0 +1,514 @@
+//! Export of a [`SettingsSchema`] as a standard JSON Schema document.
+//!
+//! The generated document (draft 2020-12) describes the settings object
+//! accepted by a connection type, for validation and editor support in
+//! external tooling. It mirrors [`validate_settings`](super::validate_settings):
+//!
+//! - Required fields must be present, and required strings non-empty.
+//! - Optional fields may also be `null`, which counts as unset.
+//! - Fields with a `visible_when` condition are only checked while the
+//!   condition holds, via an `if`/`then` entry in the top-level `allOf`.
+//! - Unknown properties are allowed.
+//!
+//! Labels, descriptions and defaults become `title`, `description` and
+//! `default` annotations.
+
+use serde_json::{json, Map, Value};
+
+use super::schema::*;
+
+/// JSON Schema dialect of the generated documents.
+pub const JSON_SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// Convert `schema` into a JSON Schema document titled `title`.
+pub fn to_json_schema(title: &str, schema: &SettingsSchema) -> Value {
+    let fields: Vec<&SettingsField> = schema.groups.iter().flat_map(|g| &g.fields).collect();
+    let mut doc = object_schema(&fields);
+    doc.insert("$schema".to_string(), json!(JSON_SCHEMA_DIALECT));
+    doc.insert("title".to_string(), json!(title));
+    Value::Object(doc)
+}
+
+/// Schema for an object holding `fields`, as in the top-level settings or
+/// an [`FieldType::ObjectList`] item.
+fn object_schema(fields: &[&SettingsField]) -> Map<String, Value> {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    let mut conditionals = Vec::new();
+
+    for field in fields {
+        match &field.visible_when {
+            None => {
+                properties.insert(field.key.clone(), field_schema(field));
+                if field.required {
+                    required.push(json!(field.key));
+                }
+            }
+            Some(condition) => conditionals.push(conditional_schema(field, condition)),
+        }
+    }
+
+    let mut schema = Map::new();
+    schema.insert("type".to_string(), json!("object"));
+    schema.insert("properties".to_string(), Value::Object(properties));
+    if !required.is_empty() {
+        schema.insert("required".to_string(), Value::Array(required));
+    }
+    if !conditionals.is_empty() {
+        schema.insert("allOf".to_string(), Value::Array(conditionals));
+    }
+    schema
+}
+
+/// `if`/`then` entry checking `field` only while `condition` holds.
+fn conditional_schema(field: &SettingsField, condition: &Condition) -> Value {
+    let mut then = json!({ "properties": { field.key.as_str(): field_schema(field) } });
+    if field.required {
+        then["required"] = json!([field.key]);
+    }
+    json!({
+        "if": {
+            "properties": { condition.field.as_str(): { "const": condition.equals } },
+            "required": [condition.field],
+        },
+        "then": then,
+    })
+}
+
+/// Schema for the value of `field`, with its annotations.
+fn field_schema(field: &SettingsField) -> Value {
+    let mut schema = type_schema(&field.field_type);
+    if field.required && is_string_type(&field.field_type) {
+        schema.insert("minLength".to_string(), json!(1));
+    }
+    if !field.required {
+        allow_null(&mut schema);
+    }
+    schema.insert("title".to_string(), json!(field.label));
+    if let Some(description) = &field.description {
+        schema.insert("description".to_string(), json!(description));
+    }
+    if let Some(default) = &field.default {
+        schema.insert("default".to_string(), default.clone());
+    }
+    Value::Object(schema)
+}
+
+/// Schema constraining a value of `field_type`.
+fn type_schema(field_type: &FieldType) -> Map<String, Value> {
+    let schema = match field_type {
+        FieldType::Text | FieldType::SerialPort | FieldType::FilePath { .. } => {
+            json!({ "type": "string" })
+        }
+        FieldType::Password => json!({ "type": "string", "writeOnly": true }),
+        FieldType::Number { min, max } => {
+            let mut schema = json!({ "type": "number" });
+            if let Some(min) = min {
+                schema["minimum"] = json!(min);
+            }
+            if let Some(max) = max {
+                schema["maximum"] = json!(max);
+            }
+            schema
+        }
+        FieldType::Boolean => json!({ "type": "boolean" }),
+        FieldType::Select { options } => json!({
+            "type": "string",
+            "enum": option_values(options),
+        }),
+        FieldType::MultiSelect { options } => json!({
+            "type": "array",
+            "items": { "type": "string", "enum": option_values(options) },
+        }),
+        FieldType::Port => json!({ "type": "integer", "minimum": 1, "maximum": 65535 }),
+        FieldType::KeyValueList => json!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "properties": {
+                    "key": { "type": "string" },
+                    "value": { "type": "string" },
+                },
+                "required": ["key", "value"],
+            },
+        }),
+        FieldType::ObjectList { fields } => {
+            let fields: Vec<&SettingsField> = fields.iter().collect();
+            json!({ "type": "array", "items": object_schema(&fields) })
+        }
+    };
+    match schema {
+        Value::Object(map) => map,
+        _ => unreachable!("type schemas are objects"),
+    }
+}
+
+fn option_values(options: &[SelectOption]) -> Vec<Value> {
+    options.iter().map(|o| json!(o.value)).collect()
+}
+
+/// Whether values of `field_type` are strings, for which `required` also
+/// rules out the empty string.
+fn is_string_type(field_type: &FieldType) -> bool {
+    matches!(
+        field_type,
+        FieldType::Text
+            | FieldType::Password
+            | FieldType::SerialPort
+            | FieldType::FilePath { .. }
+            | FieldType::Select { .. }
+    )
+}
+
+/// Widen `schema` to also accept `null`.
+fn allow_null(schema: &mut Map<String, Value>) {
+    if let Some(Value::String(ty)) = schema.get("type") {
+        let ty = ty.clone();
+        schema.insert("type".to_string(), json!([ty, "null"]));
+    }
+    if let Some(Value::Array(values)) = schema.get_mut("enum") {
+        values.push(Value::Null);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::validate_settings;
+
+    fn field(key: &str, field_type: FieldType, required: bool) -> SettingsField {
+        SettingsField {
+            key: key.to_string(),
+            label: key.to_string(),
+            description: None,
+            help_text: None,
+            field_type,
+            required,
+            default: None,
+            placeholder: None,
+            supports_env_expansion: false,
+            supports_tilde_expansion: false,
+            visible_when: None,
+        }
+    }
+
+    fn options(values: &[&str]) -> Vec<SelectOption> {
+        values
+            .iter()
+            .map(|v| SelectOption {
+                value: v.to_string(),
+                label: v.to_string(),
+            })
+            .collect()
+    }
+
+    /// A schema resembling SSH's, covering every field type.
+    fn sample_schema() -> SettingsSchema {
+        let mut key_path = field(
+            "keyPath",
+            FieldType::FilePath {
+                kind: FilePathKind::File,
+            },
+            true,
+        );
+        key_path.visible_when = Some(Condition {
+            field: "authMethod".to_string(),
+            equals: json!("key"),
+        });
+        let mut port = field("port", FieldType::Port, true);
+        port.default = Some(json!(22));
+        port.description = Some("TCP port".to_string());
+        SettingsSchema {
+            groups: vec![
+                SettingsGroup {
+                    key: "connection".to_string(),
+                    label: "Connection".to_string(),
+                    fields: vec![
+                        field("host", FieldType::Text, true),
+                        port,
+                        field(
+                            "timeout",
+                            FieldType::Number {
+                                min: Some(1.0),
+                                max: Some(600.0),
+                            },
+                            false,
+                        ),
+                        field("device", FieldType::SerialPort, false),
+                    ],
+                },
+                SettingsGroup {
+                    key: "auth".to_string(),
+                    label: "Authentication".to_string(),
+                    fields: vec![
+                        field(
+                            "authMethod",
+                            FieldType::Select {
+                                options: options(&["password", "key"]),
+                            },
+                            true,
+                        ),
+                        field(
+                            "fallback",
+                            FieldType::MultiSelect {
+                                options: options(&["agent", "password"]),
+                            },
+                            false,
+                        ),
+                        key_path,
+                        field("password", FieldType::Password, false),
+                        field("savePassword", FieldType::Boolean, false),
+                    ],
+                },
+                SettingsGroup {
+                    key: "advanced".to_string(),
+                    label: "Advanced".to_string(),
+                    fields: vec![
+                        field("env", FieldType::KeyValueList, false),
+                        field(
+                            "volumes",
+                            FieldType::ObjectList {
+                                fields: vec![
+                                    field("hostPath", FieldType::Text, true),
+                                    field("readOnly", FieldType::Boolean, false),
+                                ],
+                            },
+                            false,
+                        ),
+                    ],
+                },
+            ],
+        }
+    }
+
+    fn known_good() -> Value {
+        json!({
+            "host": "example.com",
+            "port": 2222,
+            "timeout": 30,
+            "device": null,
+            "authMethod": "key",
+            "fallback": ["agent"],
+            "keyPath": "~/.ssh/id_ed25519",
+            "savePassword": false,
+            "env": [{ "key": "TERM", "value": "xterm" }],
+            "volumes": [{ "hostPath": "/data", "readOnly": true }],
+            "extra": "ignored",
+        })
+    }
+
+    /// Minimal validator for the keywords [`to_json_schema`] emits.
+    fn validate(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+        let mut fail = |what: &str| errors.push(format!("{path}: {what}"));
+        if let Some(ty) = schema.get("type") {
+            let types: Vec<&str> = match ty {
+                Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+                other => vec![other.as_str().unwrap()],
+            };
+            let matches = types.iter().any(|t| match *t {
+                "string" => value.is_string(),
+                "number" => value.is_number(),
+                "integer" => value.is_i64() || value.is_u64(),
+                "boolean" => value.is_boolean(),
+                "array" => value.is_array(),
+                "object" => value.is_object(),
+                "null" => value.is_null(),
+                other => panic!("unexpected type {other}"),
+            });
+            if !matches {
+                fail("wrong type");
+                return;
+            }
+        }
+        if let Some(Value::Array(allowed)) = schema.get("enum") {
+            if !allowed.contains(value) {
+                fail("not in enum");
+            }
+        }
+        if let Some(expected) = schema.get("const") {
+            if expected != value {
+                fail("const mismatch");
+            }
+        }
+        if let (Some(min), Some(n)) = (schema.get("minimum"), value.as_f64()) {
+            if n < min.as_f64().unwrap() {
+                fail("below minimum");
+            }
+        }
+        if let (Some(max), Some(n)) = (schema.get("maximum"), value.as_f64()) {
+            if n > max.as_f64().unwrap() {
+                fail("above maximum");
+            }
+        }
+        if let (Some(min), Some(s)) = (schema.get("minLength"), value.as_str()) {
+            if (s.chars().count() as u64) < min.as_u64().unwrap() {
+                fail("too short");
+            }
+        }
+        if let (Some(items), Some(arr)) = (schema.get("items"), value.as_array()) {
+            for (i, item) in arr.iter().enumerate() {
+                validate(items, item, &format!("{path}.{i}"), errors);
+            }
+        }
+        if let Some(obj) = value.as_object() {
+            if let Some(Value::Array(required)) = schema.get("required") {
+                for key in required.iter().filter_map(Value::as_str) {
+                    if !obj.contains_key(key) {
+                        errors.push(format!("{path}.{key}: missing"));
+                    }
+                }
+            }
+            if let Some(Value::Object(properties)) = schema.get("properties") {
+                for (key, sub) in properties {
+                    if let Some(v) = obj.get(key) {
+                        validate(sub, v, &format!("{path}.{key}"), errors);
+                    }
+                }
+            }
+        }
+        if let Some(Value::Array(all)) = schema.get("allOf") {
+            for sub in all {
+                validate(sub, value, path, errors);
+            }
+        }
+        if let Some(condition) = schema.get("if") {
+            let mut cond_errors = Vec::new();
+            validate(condition, value, path, &mut cond_errors);
+            if cond_errors.is_empty() {
+                validate(&schema["then"], value, path, errors);
+            }
+        }
+    }
+
+    fn errors_for(value: &Value) -> Vec<String> {
+        let doc = to_json_schema("SSH", &sample_schema());
+        let mut errors = Vec::new();
+        validate(&doc, value, "$", &mut errors);
+        errors
+    }
+
+    #[test]
+    fn document_header_and_annotations() {
+        let doc = to_json_schema("SSH", &sample_schema());
+        assert_eq!(doc["$schema"], JSON_SCHEMA_DIALECT);
+        assert_eq!(doc["title"], "SSH");
+        assert_eq!(doc["type"], "object");
+        assert_eq!(doc["required"], json!(["host", "port", "authMethod"]));
+
+        let port = &doc["properties"]["port"];
+        assert_eq!(port["type"], "integer");
+        assert_eq!(port["default"], 22);
+        assert_eq!(port["description"], "TCP port");
+        assert_eq!(port["title"], "port");
+        assert_eq!(doc["properties"]["password"]["writeOnly"], true);
+    }
+
+    #[test]
+    fn field_types_map_to_json_schema() {
+        let doc = to_json_schema("SSH", &sample_schema());
+        let props = &doc["properties"];
+        assert_eq!(props["host"]["type"], "string");
+        assert_eq!(props["host"]["minLength"], 1);
+        assert_eq!(props["timeout"]["type"], json!(["number", "null"]));
+        assert_eq!(props["timeout"]["minimum"], 1.0);
+        assert_eq!(props["timeout"]["maximum"], 600.0);
+        assert_eq!(props["savePassword"]["type"], json!(["boolean", "null"]));
+        assert_eq!(props["authMethod"]["enum"], json!(["password", "key"]));
+        assert_eq!(
+            props["fallback"]["items"]["enum"],
+            json!(["agent", "password"])
+        );
+        assert_eq!(props["env"]["items"]["required"], json!(["key", "value"]));
+        assert_eq!(
+            props["volumes"]["items"]["properties"]["hostPath"]["type"],
+            "string"
+        );
+        assert_eq!(props["volumes"]["items"]["required"], json!(["hostPath"]));
+    }
+
+    #[test]
+    fn visible_when_becomes_conditional() {
+        let doc = to_json_schema("SSH", &sample_schema());
+        assert!(doc["properties"].get("keyPath").is_none());
+        assert_eq!(
+            doc["allOf"],
+            json!([{
+                "if": {
+                    "properties": { "authMethod": { "const": "key" } },
+                    "required": ["authMethod"],
+                },
+                "then": {
+                    "properties": {
+                        "keyPath": { "type": "string", "minLength": 1, "title": "keyPath" },
+                    },
+                    "required": ["keyPath"],
+                },
+            }])
+        );
+    }
+
+    #[test]
+    fn schema_without_conditions_has_no_all_of() {
+        let schema = SettingsSchema {
+            groups: vec![SettingsGroup {
+                key: "g".to_string(),
+                label: "G".to_string(),
+                fields: vec![field("name", FieldType::Text, false)],
+            }],
+        };
+        let doc = to_json_schema("Plain", &schema);
+        assert!(doc.get("allOf").is_none());
+        assert!(doc.get("required").is_none());
+    }
+
+    #[test]
+    fn known_good_settings_validate() {
+        assert_eq!(errors_for(&known_good()), Vec::<String>::new());
+        // Hidden fields are not checked.
+        let mut settings = known_good();
+        settings["authMethod"] = json!("password");
+        settings["keyPath"] = json!(42);
+        assert_eq!(errors_for(&settings), Vec::<String>::new());
+    }
+
+    #[test]
+    fn known_bad_settings_are_rejected() {
+        let settings = json!({
+            "host": "",
+            "port": 70000,
+            "timeout": "soon",
+            "authMethod": "key",
+            "fallback": ["kerberos"],
+            "env": [{ "key": "TERM" }],
+            "volumes": [{ "readOnly": "yes" }],
+        });
+        let errors = errors_for(&settings);
+        for expected in [
+            "$.host: too short",
+            "$.port: above maximum",
+            "$.timeout: wrong type",
+            "$.fallback.0: not in enum",
+            "$.keyPath: missing",
+            "$.env.0.value: missing",
+            "$.volumes.0.hostPath: missing",
+            "$.volumes.0.readOnly: wrong type",
+        ] {
+            assert!(
+                errors.iter().any(|e| e == expected),
+                "{expected} not in {errors:?}"
+            );
+        }
+        assert_eq!(errors.len(), 8, "{errors:?}");
+    }
+
+    #[test]
+    fn agrees_with_validate_settings() {
+        let schema = sample_schema();
+        let good = known_good();
+        assert!(validate_settings(&schema, &good).is_empty());
+
+        let bad = json!({ "host": "h", "port": 0, "authMethod": "password" });
+        assert!(!validate_settings(&schema, &bad).is_empty());
+        assert!(!errors_for(&bad).is_empty());
+    }
+}
@@ -3,8 +3,9 @@
 //! This module defines the unified [`ConnectionType`] trait that all
 //! connection backends (local shell, SSH, serial, telnet, Docker, WSL)
 //! implement. It also provides the [`SettingsSchema`] types for dynamic
-//! UI form generation and a [`ConnectionTypeRegistry`] for runtime
-//! discovery of available connection types.
+//! UI form generation, their export as JSON Schema, and a
+//! [`ConnectionTypeRegistry`] for runtime discovery of available
+//! connection types.
 //!
 //! # Architecture
 //!
@@ -13,10 +14,12 @@
 //! and agent (`agent/`) crates, which register their backends with a
 //! [`ConnectionTypeRegistry`] at startup.
 
+pub mod json_schema;
 pub mod registry;
 pub mod schema;
 pub mod validation;
 
+pub use json_schema::to_json_schema;
 pub use registry::{ConnectionFactory, ConnectionTypeInfo, ConnectionTypeRegistry};
 pub use schema::*;
 pub use validation::{validate_settings, ValidationError};
@@ -35,6 +38,168 @@ pub type OutputReceiver = tokio::sync::mpsc::Receiver<Vec<u8>>;
 /// Async sender for terminal output bytes (used by backend implementations).
 pub type OutputSender = tokio::sync::mpsc::Sender<Vec<u8>>;
 
+/// Why a connection closed without being asked to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CloseReason {
+    /// The peer stopped responding (e.g. unanswered keepalives).
+    ConnectionLost,
+}
+
+/// When a session's backend resources (child process, container, remote
+/// connection) are released, read from the `cleanupPolicy` setting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CleanupPolicy {
+    /// Release whenever the session is closed, including its tab.
+    #[default]
+    Always,
+    /// Release only on an explicit close; closing the tab keeps the
+    /// backend for reattaching.
+    OnExplicitClose,
+    /// Never release on close; the backend lives until it exits on its
+    /// own or the application quits.
+    Never,
+}
+
+/// What asked for a session to be closed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CloseTrigger {
+    /// The session's tab was closed.
+    #[default]
+    TabClosed,
+    /// The user explicitly ended the session.
+    Explicit,
+    /// The user killed the session, e.g. from the open connections list.
+    /// Always releases, whatever the policy.
+    Kill,
+}
+
+impl CleanupPolicy {
+    /// Read the policy from a session's settings, defaulting to
+    /// [`CleanupPolicy::Always`] when absent or unrecognized.
+    pub fn from_settings(settings: &serde_json::Value) -> Self {
+        settings
+            .get("cleanupPolicy")
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Whether closing for `trigger` should release backend resources.
+    pub fn releases_on(self, trigger: CloseTrigger) -> bool {
+        match self {
+            _ if trigger == CloseTrigger::Kill => true,
+            Self::Always => true,
+            Self::OnExplicitClose => trigger == CloseTrigger::Explicit,
+            Self::Never => false,
+        }
+    }
+}
+
+/// The `cleanupPolicy` settings field, shared by every backend's schema.
+pub fn cleanup_policy_field() -> SettingsField {
+    let option = |value: &str, label: &str| SelectOption {
+        value: value.to_string(),
+        label: label.to_string(),
+    };
+    SettingsField {
+        key: "cleanupPolicy".to_string(),
+        label: "Cleanup Policy".to_string(),
+        description: Some("When the session's backend is released".to_string()),
+        help_text: Some(
+            concat!(
+                "Controls what happens to the running session when it is closed.\n\n",
+                "Always: closing the tab ends the session.\n",
+                "On explicit close: closing the tab only detaches; the session keeps ",
+                "running until ended with End Session and can be reattached from ",
+                "Open Connections.\n",
+                "Never: the session keeps running until it exits on its own or is ",
+                "killed from Open Connections.",
+            )
+            .to_string(),
+        ),
+        field_type: FieldType::Select {
+            options: vec![
+                option("always", "Always"),
+                option("onExplicitClose", "On explicit close"),
+                option("never", "Never"),
+            ],
+        },
+        required: false,
+        default: Some(serde_json::json!("always")),
+        placeholder: None,
+        supports_env_expansion: false,
+        supports_tilde_expansion: false,
+        visible_when: None,
+    }
+}
+
+/// Whether a session runs in raw key mode, read from the `rawKeyMode`
+/// setting.
+///
+/// In raw key mode the frontend passes every keystroke to
+/// [`ConnectionType::write()`] instead of handling application shortcuts
+/// itself, for TUI programs that need keys the desktop would intercept.
+pub fn raw_key_mode(settings: &serde_json::Value) -> bool {
+    settings
+        .get("rawKeyMode")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// Out-of-band control of a serial line, applied by
+/// [`ConnectionType::line_control()`] instead of being written into the
+/// data stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum LineControl {
+    /// Hold the line in the break condition for `duration_ms`.
+    Break { duration_ms: u32 },
+    /// Set the Data Terminal Ready signal.
+    SetDtr { level: bool },
+    /// Set the Request To Send signal.
+    SetRts { level: bool },
+}
+
+/// Levels of the modem status input lines of a serial port, returned by
+/// [`ConnectionType::modem_status()`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModemStatus {
+    /// Clear To Send.
+    pub cts: bool,
+    /// Data Set Ready.
+    pub dsr: bool,
+    /// Data Carrier Detect.
+    pub dcd: bool,
+    /// Ring Indicator.
+    pub ri: bool,
+}
+
+/// Informational message about a connected session, such as a feature
+/// turned off because the remote side cannot support it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionNotice {
+    /// Stable identifier of the notice, e.g. `restrictedShell`.
+    pub code: String,
+    /// Human-readable description.
+    pub message: String,
+}
+
+/// Host key presented by a remote server, for manual verification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HostKeyFingerprint {
+    /// Key algorithm, e.g. `ssh-ed25519`.
+    pub key_type: String,
+    /// SHA-256 fingerprint in OpenSSH format (`SHA256:<base64>`).
+    pub fingerprint: String,
+}
+
 /// Capabilities declared by a connection type.
 ///
 /// The UI uses these flags to show or hide optional features
@@ -89,6 +254,9 @@ pub trait ConnectionType: Send {
     fn settings_schema(&self) -> SettingsSchema;
 
     /// Capabilities of this connection type.
+    ///
+    /// A connected instance may report fewer than a new one, when the
+    /// remote side turned out not to support a feature.
     fn capabilities(&self) -> Capabilities;
 
     // --- Lifecycle ---
@@ -135,6 +303,77 @@ pub trait ConnectionType: Send {
     ///
     /// Returns `None` when [`Capabilities::file_browser`] is `false`.
     fn file_browser(&self) -> Option<&dyn FileBrowser>;
+
+    /// OS process ID of the local child process backing this connection.
+    ///
+    /// Only connection types that spawn a local process (e.g. the local
+    /// shell) report one, and only while that process is running;
+    /// network- and device-backed types return `None`.
+    fn process_id(&self) -> Option<u32> {
+        None
+    }
+
+    /// Why the connection closed on its own, once it has.
+    ///
+    /// `None` while connected and for ordinary exits (the remote shell
+    /// exiting, EOF on a device).
+    fn close_reason(&self) -> Option<CloseReason> {
+        None
+    }
+
+    /// Fingerprint of the remote host key, for connection types that
+    /// authenticate the server by one (SSH).
+    fn host_key_fingerprint(&self) -> Option<HostKeyFingerprint> {
+        None
+    }
+
+    /// Informational notices gathered while connecting, reported to the
+    /// user once when the session starts.
+    fn notices(&self) -> Vec<SessionNotice> {
+        Vec::new()
+    }
+
+    /// Send a break or set a modem control line, for connection types
+    /// backed by a serial line.
+    ///
+    /// Returns [`SessionError::NotSupported`] for all other types.
+    fn line_control(&self, _control: LineControl) -> Result<(), SessionError> {
+        Err(SessionError::NotSupported(format!(
+            "{} connections have no serial line control",
+            self.display_name()
+        )))
+    }
+
+    /// Read the modem status input lines, for connection types backed by
+    /// a serial line.
+    ///
+    /// Returns [`SessionError::NotSupported`] for all other types.
+    fn modem_status(&self) -> Result<ModemStatus, SessionError> {
+        Err(SessionError::NotSupported(format!(
+            "{} connections have no modem status lines",
+            self.display_name()
+        )))
+    }
+
+    /// Write the bytes of a binary file transfer (e.g. XMODEM) unchanged.
+    ///
+    /// Unlike [`write()`](Self::write), which handles typed input, no line
+    /// ending translation, local echo or send pacing is applied, and bytes
+    /// with a meaning in the transport protocol are escaped (telnet doubles
+    /// `IAC`). Defaults to `write()`, for types that pass input through
+    /// unchanged.
+    fn write_binary(&self, data: &[u8]) -> Result<(), SessionError> {
+        self.write(data)
+    }
+
+    /// Start or end a binary file transfer.
+    ///
+    /// While active, output is delivered exactly as received, without
+    /// display transforms such as the serial hex dump, and telnet sessions
+    /// negotiate the BINARY option. Defaults to a no-op.
+    fn set_binary_transfer(&self, _active: bool) -> Result<(), SessionError> {
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -188,4 +427,98 @@ mod tests {
         // Ensure snake_case keys are NOT present.
         assert!(!obj.contains_key("file_browser"));
     }
+
+    #[test]
+    fn cleanup_policy_from_settings() {
+        let policy = |v: serde_json::Value| CleanupPolicy::from_settings(&v);
+        assert_eq!(
+            policy(serde_json::json!({"cleanupPolicy": "onExplicitClose"})),
+            CleanupPolicy::OnExplicitClose
+        );
+        assert_eq!(
+            policy(serde_json::json!({"cleanupPolicy": "never"})),
+            CleanupPolicy::Never
+        );
+        assert_eq!(policy(serde_json::json!({})), CleanupPolicy::Always);
+        assert_eq!(
+            policy(serde_json::json!({"cleanupPolicy": "sometimes"})),
+            CleanupPolicy::Always
+        );
+    }
+
+    #[test]
+    fn line_control_serde() {
+        let control: LineControl =
+            serde_json::from_value(serde_json::json!({"type": "break", "durationMs": 250}))
+                .unwrap();
+        assert_eq!(control, LineControl::Break { duration_ms: 250 });
+        assert_eq!(
+            serde_json::to_value(LineControl::SetDtr { level: false }).unwrap(),
+            serde_json::json!({"type": "setDtr", "level": false})
+        );
+        assert_eq!(
+            serde_json::to_value(LineControl::SetRts { level: true }).unwrap(),
+            serde_json::json!({"type": "setRts", "level": true})
+        );
+    }
+
+    #[test]
+    fn modem_status_serde() {
+        let status = ModemStatus {
+            cts: true,
+            dcd: true,
+            ..ModemStatus::default()
+        };
+        assert_eq!(
+            serde_json::to_value(status).unwrap(),
+            serde_json::json!({"cts": true, "dsr": false, "dcd": true, "ri": false})
+        );
+    }
+
+    #[test]
+    fn raw_key_mode_from_settings() {
+        assert!(raw_key_mode(&serde_json::json!({"rawKeyMode": true})));
+        assert!(!raw_key_mode(&serde_json::json!({"rawKeyMode": false})));
+        assert!(!raw_key_mode(&serde_json::json!({})));
+        assert!(!raw_key_mode(&serde_json::json!({"rawKeyMode": "yes"})));
+    }
+
+    #[test]
+    fn cleanup_policy_releases_on() {
+        use CloseTrigger::{Explicit, Kill, TabClosed};
+        assert!(CleanupPolicy::Always.releases_on(TabClosed));
+        assert!(CleanupPolicy::Always.releases_on(Explicit));
+        assert!(CleanupPolicy::Always.releases_on(Kill));
+        assert!(!CleanupPolicy::OnExplicitClose.releases_on(TabClosed));
+        assert!(CleanupPolicy::OnExplicitClose.releases_on(Explicit));
+        assert!(CleanupPolicy::OnExplicitClose.releases_on(Kill));
+        assert!(!CleanupPolicy::Never.releases_on(TabClosed));
+        assert!(!CleanupPolicy::Never.releases_on(Explicit));
+        assert!(CleanupPolicy::Never.releases_on(Kill));
+    }
+
+    #[test]
+    fn close_trigger_deserializes_camel_case() {
+        let trigger: CloseTrigger = serde_json::from_value(serde_json::json!("tabClosed")).unwrap();
+        assert_eq!(trigger, CloseTrigger::TabClosed);
+        let trigger: CloseTrigger = serde_json::from_value(serde_json::json!("kill")).unwrap();
+        assert_eq!(trigger, CloseTrigger::Kill);
+    }
+
+    #[test]
+    fn cleanup_policy_field_options_parse_as_policies() {
+        let field = cleanup_policy_field();
+        assert_eq!(field.key, "cleanupPolicy");
+        let FieldType::Select { options } = field.field_type else {
+            panic!("expected a select field");
+        };
+        for option in options {
+            let settings = serde_json::json!({ "cleanupPolicy": option.value });
+            let policy = CleanupPolicy::from_settings(&settings);
+            assert_eq!(
+                serde_json::to_value(policy).unwrap(),
+                settings["cleanupPolicy"]
+            );
+        }
+    }
 }
@@ -14,6 +14,7 @@
 //! let conn = registry.create("ssh")?;    // create a new instance
 //! ```
 
+use super::json_schema::to_json_schema;
 use super::schema::SettingsSchema;
 use super::Capabilities;
 use super::ConnectionType;
@@ -126,6 +127,21 @@ impl ConnectionTypeRegistry {
             .ok_or_else(|| CoreError::Config(format!("Unknown connection type: {type_id}")))
     }
 
+    /// JSON Schema document for the settings of a connection type.
+    ///
+    /// See [`to_json_schema`] for how the settings schema is mapped.
+    pub fn json_schema(&self, type_id: &str) -> Result<serde_json::Value, CoreError> {
+        self.factories
+            .get(type_id)
+            .map(|entry| to_json_schema(&entry.info.display_name, &entry.info.schema))
+            .ok_or_else(|| CoreError::Config(format!("Unknown connection type: {type_id}")))
+    }
+
+    /// Settings schema of a connection type, if it is registered.
+    pub fn settings_schema(&self, type_id: &str) -> Option<&SettingsSchema> {
+        self.factories.get(type_id).map(|entry| &entry.info.schema)
+    }
+
     /// Check whether a connection type is registered.
     pub fn has_type(&self, type_id: &str) -> bool {
         self.factories.contains_key(type_id)
@@ -245,6 +261,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn json_schema_for_registered_type() {
+        let mut registry = ConnectionTypeRegistry::new();
+        registry.register("mock", "Mock", "terminal", mock_factory("mock"));
+
+        let schema = registry.json_schema("mock").unwrap();
+        assert_eq!(schema["title"], "Mock");
+        assert_eq!(schema["properties"]["host"]["type"], "string");
+        assert_eq!(schema["required"], serde_json::json!(["host"]));
+
+        let err = registry.json_schema("nonexistent").unwrap_err();
+        assert!(err.to_string().contains("Unknown connection type"));
+    }
+
+    #[test]
+    fn settings_schema_for_registered_type() {
+        let mut registry = ConnectionTypeRegistry::new();
+        registry.register("mock", "Mock", "terminal", mock_factory("mock"));
+
+        let schema = registry.settings_schema("mock").unwrap();
+        assert_eq!(schema.groups[0].fields[0].key, "host");
+        assert!(registry.settings_schema("nonexistent").is_none());
+    }
+
     #[test]
     fn available_types_lists_registered() {
         let mut registry = ConnectionTypeRegistry::new();
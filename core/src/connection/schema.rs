@@ -105,6 +105,13 @@ pub enum FieldType {
         /// Available choices.
         options: Vec<SelectOption>,
     },
+    /// Ordered selection of any number of predefined options.
+    ///
+    /// The value is stored as an array of option values, in the order chosen.
+    MultiSelect {
+        /// Available choices.
+        options: Vec<SelectOption>,
+    },
     /// Port number input (constrained to 1..=65535).
     Port,
     /// Serial device path picker — renders a dropdown populated from `list_serial_ports`.
@@ -126,7 +133,7 @@ pub enum FieldType {
     },
 }
 
-/// An option in a [`FieldType::Select`] dropdown.
+/// An option in a [`FieldType::Select`] or [`FieldType::MultiSelect`] field.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SelectOption {
@@ -313,6 +320,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn field_type_multi_select_serialization() {
+        let ft = FieldType::MultiSelect {
+            options: vec![SelectOption {
+                value: "a".to_string(),
+                label: "Option A".to_string(),
+            }],
+        };
+        let json = serde_json::to_value(&ft).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "type": "multiSelect",
+                "options": [{"value": "a", "label": "Option A"}]
+            })
+        );
+    }
+
     #[test]
     fn field_type_port_serialization() {
         let ft = FieldType::Port;
@@ -142,6 +142,26 @@ fn validate_field_type(
                 });
             }
         }
+        FieldType::MultiSelect { options } => {
+            if let Some(arr) = value.as_array() {
+                for (i, item) in arr.iter().enumerate() {
+                    let known = item
+                        .as_str()
+                        .is_some_and(|s| options.iter().any(|o| o.value == s));
+                    if !known {
+                        errors.push(ValidationError {
+                            field: format!("{key}.{i}"),
+                            message: format!("{label} must only contain available options"),
+                        });
+                    }
+                }
+            } else {
+                errors.push(ValidationError {
+                    field: key.to_string(),
+                    message: format!("{label} must be an array"),
+                });
+            }
+        }
         FieldType::Port => {
             if let Some(n) = value.as_f64() {
                 let n = n as i64;
@@ -526,6 +546,60 @@ mod tests {
         assert!(errors[0].message.contains("must be a string"));
     }
 
+    /// Helper: build an optional multi-select field offering `values`.
+    fn multi_select(key: &str, values: &[&str]) -> SettingsField {
+        SettingsField {
+            key: key.to_string(),
+            label: key.to_string(),
+            description: None,
+            help_text: None,
+            field_type: FieldType::MultiSelect {
+                options: values
+                    .iter()
+                    .map(|v| SelectOption {
+                        value: v.to_string(),
+                        label: v.to_string(),
+                    })
+                    .collect(),
+            },
+            required: false,
+            default: None,
+            placeholder: None,
+            supports_env_expansion: false,
+            supports_tilde_expansion: false,
+            visible_when: None,
+        }
+    }
+
+    #[test]
+    fn multi_select_valid_options() {
+        let schema = schema_with_fields(vec![multi_select("methods", &["agent", "password"])]);
+        let settings = serde_json::json!({"methods": ["password", "agent"]});
+        assert!(validate_settings(&schema, &settings).is_empty());
+        let settings = serde_json::json!({"methods": []});
+        assert!(validate_settings(&schema, &settings).is_empty());
+    }
+
+    #[test]
+    fn multi_select_unknown_option() {
+        let schema = schema_with_fields(vec![multi_select("methods", &["agent", "password"])]);
+        let settings = serde_json::json!({"methods": ["agent", "token", 3]});
+        let errors = validate_settings(&schema, &settings);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].field, "methods.1");
+        assert_eq!(errors[1].field, "methods.2");
+        assert!(errors[0].message.contains("available options"));
+    }
+
+    #[test]
+    fn multi_select_not_array() {
+        let schema = schema_with_fields(vec![multi_select("methods", &["agent"])]);
+        let settings = serde_json::json!({"methods": "agent"});
+        let errors = validate_settings(&schema, &settings);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].message.contains("must be an array"));
+    }
+
     #[test]
     fn port_below_range() {
         let field = SettingsField {
@@ -21,6 +21,10 @@ pub enum CoreError {
     #[error("Config error: {0}")]
     Config(String),
 
+    /// A monitoring command ran on the remote host but failed.
+    #[error("Monitoring failed: {0}")]
+    Monitoring(#[from] MonitoringError),
+
     /// A low-level I/O error.
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
@@ -49,6 +53,23 @@ pub enum SessionError {
     #[error("Invalid config: {0}")]
     InvalidConfig(String),
 
+    /// Authentication with the remote host failed.
+    #[error("Authentication failed: {0}")]
+    AuthFailed(String),
+
+    /// An operation did not complete within its deadline.
+    #[error("Timed out: {0}")]
+    Timeout(String),
+
+    /// The server's host key differs from the one recorded in `known_hosts`.
+    #[error("Host key for {host} does not match known_hosts; the server presented {fingerprint}")]
+    HostKeyMismatch {
+        /// Host as recorded in `known_hosts`, e.g. `[example.com]:2222`.
+        host: String,
+        /// Key type and SHA-256 fingerprint of the key the server presented.
+        fingerprint: String,
+    },
+
     /// The maximum number of concurrent sessions has been reached.
     #[error("Session limit reached")]
     LimitReached,
@@ -57,6 +78,10 @@ pub enum SessionError {
     #[error("Session not running: {0}")]
     NotRunning(String),
 
+    /// The connection type does not support the requested operation.
+    #[error("Not supported: {0}")]
+    NotSupported(String),
+
     /// A low-level I/O error during session operations.
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
@@ -81,11 +106,64 @@ pub enum FileError {
     #[error("File browsing not supported for this connection type")]
     NotSupported,
 
+    /// A rename/move target already exists and overwriting was not requested.
+    #[error("Destination already exists: {0}")]
+    DestinationExists(String),
+
     /// A low-level I/O error during file operations.
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 }
 
+/// Maximum number of trailing stderr characters kept in a [`MonitoringError`].
+pub const MONITORING_STDERR_TAIL_CHARS: usize = 512;
+
+/// A remote monitoring command exited with a non-zero status.
+///
+/// Carries the command that was run and the tail of its stderr so the UI
+/// can explain why stats are unavailable instead of showing nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonitoringError {
+    /// The command that was executed on the remote host.
+    pub command: String,
+    /// The exit status reported by the remote host.
+    pub exit_code: i32,
+    /// The last [`MONITORING_STDERR_TAIL_CHARS`] characters of stderr, trimmed.
+    pub stderr: String,
+}
+
+impl MonitoringError {
+    /// Build an error from a failed command, keeping only the stderr tail.
+    pub fn new(command: &str, exit_code: i32, stderr: &str) -> Self {
+        let stderr = stderr.trim();
+        let skip = stderr
+            .chars()
+            .count()
+            .saturating_sub(MONITORING_STDERR_TAIL_CHARS);
+        Self {
+            command: command.to_string(),
+            exit_code,
+            stderr: stderr.chars().skip(skip).collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for MonitoringError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "`{}` exited with status {}",
+            self.command, self.exit_code
+        )?;
+        if !self.stderr.is_empty() {
+            write!(f, ": {}", self.stderr)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for MonitoringError {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -100,6 +178,22 @@ mod tests {
 
         let err = SessionError::NotRunning("xyz".into());
         assert_eq!(err.to_string(), "Session not running: xyz");
+
+        let err = SessionError::NotSupported("line control".into());
+        assert_eq!(err.to_string(), "Not supported: line control");
+
+        let err = SessionError::Timeout("connecting to example.com:22".into());
+        assert_eq!(err.to_string(), "Timed out: connecting to example.com:22");
+
+        let err = SessionError::HostKeyMismatch {
+            host: "[example.com]:2222".into(),
+            fingerprint: "ssh-ed25519 SHA256:abc".into(),
+        };
+        assert_eq!(
+            err.to_string(),
+            "Host key for [example.com]:2222 does not match known_hosts; \
+             the server presented ssh-ed25519 SHA256:abc"
+        );
     }
 
     #[test]
@@ -115,6 +209,9 @@ mod tests {
             err.to_string(),
             "File browsing not supported for this connection type"
         );
+
+        let err = FileError::DestinationExists("/tmp/b.txt".into());
+        assert_eq!(err.to_string(), "Destination already exists: /tmp/b.txt");
     }
 
     #[test]
@@ -131,6 +228,16 @@ mod tests {
         assert_eq!(core_err.to_string(), "File error: File not found: /missing");
     }
 
+    #[test]
+    fn core_error_from_monitoring_error() {
+        let err = MonitoringError::new("uptime", 127, "sh: uptime: not found\n");
+        let core_err: CoreError = err.into();
+        assert_eq!(
+            core_err.to_string(),
+            "Monitoring failed: `uptime` exited with status 127: sh: uptime: not found"
+        );
+    }
+
     #[test]
     fn core_error_from_io_error() {
         let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "gone");
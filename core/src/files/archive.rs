@@ -0,0 +1,160 @@
+//! Downloading a whole directory as a single archive.
+//!
+//! Remote backends pack the directory with a shell command (`tar` or
+//! `zip`) writing the archive to stdout, and stream that output into a
+//! local file. The archive size is not known in advance, so progress is
+//! reported as the number of archive bytes written so far.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+
+use crate::errors::FileError;
+use crate::files::utils::shell_quote;
+
+/// Archive format for a directory download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ArchiveFormat {
+    /// Gzip-compressed tarball.
+    #[serde(rename = "tar.gz")]
+    TarGz,
+    /// Zip archive.
+    #[serde(rename = "zip")]
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Conventional file extension, without a leading dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::TarGz => "tar.gz",
+            Self::Zip => "zip",
+        }
+    }
+}
+
+/// Downloads a remote directory as an archive.
+///
+/// Obtained from [`FileBrowser::archive_downloader()`](super::FileBrowser::archive_downloader).
+/// The downloader owns everything it needs, so a long download does not
+/// keep the file browser (or the session holding it) borrowed.
+#[async_trait::async_trait]
+pub trait ArchiveDownloader: Send + Sync {
+    /// Pack the directory at `path` in `format` and write the archive to
+    /// `local_path`. Returns the number of archive bytes written.
+    ///
+    /// Calls `on_progress` with the bytes written so far after every
+    /// chunk. A partially written file is removed when the download fails
+    /// or `cancel` fires.
+    async fn download(
+        &self,
+        path: &str,
+        format: ArchiveFormat,
+        local_path: &Path,
+        cancel: &CancellationToken,
+        on_progress: &mut (dyn FnMut(u64) + Send),
+    ) -> Result<u64, FileError>;
+}
+
+/// Shell command that writes an archive of the directory at `path` to stdout.
+///
+/// The archive contains the directory itself as its single top-level
+/// entry, so extracting it recreates the directory by name.
+pub fn archive_command(path: &str, format: ArchiveFormat) -> Result<String, FileError> {
+    let trimmed = path.trim_end_matches('/');
+    let (parent, name) = match trimmed.rsplit_once('/') {
+        Some(("", name)) => ("/", name),
+        Some((parent, name)) => (parent, name),
+        None => (".", trimmed),
+    };
+    if name.is_empty() {
+        return Err(FileError::OperationFailed(
+            "Cannot archive the root directory".to_string(),
+        ));
+    }
+    let (parent, name) = (shell_quote(parent), shell_quote(name));
+    Ok(match format {
+        ArchiveFormat::TarGz => format!("tar -czf - -C {parent} -- {name}"),
+        ArchiveFormat::Zip => format!("cd {parent} && zip -qr - {name}"),
+    })
+}
+
+/// Map a failed archive command's exit code and stderr to a [`FileError`].
+pub fn archive_command_error(exit_code: i64, stderr: &str) -> FileError {
+    let stderr = stderr.trim();
+    let lower = stderr.to_lowercase();
+    if lower.contains("no such file") {
+        FileError::NotFound(stderr.to_string())
+    } else if lower.contains("permission denied") {
+        FileError::PermissionDenied(stderr.to_string())
+    } else if exit_code == 127 {
+        FileError::OperationFailed(format!("Archive tool not available: {stderr}"))
+    } else {
+        FileError::OperationFailed(format!("Archive command exited with {exit_code}: {stderr}"))
+    }
+}
+
+/// Remove a partially written archive after a failed download.
+pub fn discard_partial(local_path: &Path) {
+    let _ = std::fs::remove_file(local_path);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tar_command_packs_directory_relative_to_parent() {
+        assert_eq!(
+            archive_command("/home/user/project", ArchiveFormat::TarGz).unwrap(),
+            "tar -czf - -C '/home/user' -- 'project'"
+        );
+    }
+
+    #[test]
+    fn zip_command_ignores_trailing_slash() {
+        assert_eq!(
+            archive_command("/srv/data/", ArchiveFormat::Zip).unwrap(),
+            "cd '/srv' && zip -qr - 'data'"
+        );
+    }
+
+    #[test]
+    fn command_quotes_special_characters() {
+        assert_eq!(
+            archive_command("/tmp/it's here", ArchiveFormat::TarGz).unwrap(),
+            "tar -czf - -C '/tmp' -- 'it'\\''s here'"
+        );
+    }
+
+    #[test]
+    fn top_level_directory_uses_root_as_parent() {
+        assert_eq!(
+            archive_command("/etc", ArchiveFormat::TarGz).unwrap(),
+            "tar -czf - -C '/' -- 'etc'"
+        );
+    }
+
+    #[test]
+    fn root_directory_is_rejected() {
+        assert!(archive_command("/", ArchiveFormat::Zip).is_err());
+    }
+
+    #[test]
+    fn format_serializes_as_extension() {
+        assert_eq!(
+            serde_json::to_string(&ArchiveFormat::TarGz).unwrap(),
+            "\"tar.gz\""
+        );
+        let zip: ArchiveFormat = serde_json::from_str("\"zip\"").unwrap();
+        assert_eq!(zip, ArchiveFormat::Zip);
+        assert_eq!(zip.extension(), "zip");
+    }
+
+    #[test]
+    fn missing_tool_is_reported() {
+        let err = archive_command_error(127, "sh: zip: not found");
+        assert!(err.to_string().contains("Archive tool not available"));
+    }
+}
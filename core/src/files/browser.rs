@@ -5,8 +5,13 @@
 //! interface returned by
 //! [`ConnectionType::file_browser()`](crate::connection::ConnectionType::file_browser).
 
+use std::future::Future;
+use std::pin::Pin;
+
+use tokio_util::sync::CancellationToken;
+
 use crate::errors::FileError;
-use crate::files::FileEntry;
+use crate::files::{ArchiveDownloader, FileEntry, GrepMatch, GrepOptions};
 
 /// Async file browsing capability exposed by connection types.
 ///
@@ -31,13 +36,63 @@ pub trait FileBrowser: Send {
     async fn delete(&self, path: &str) -> Result<(), FileError>;
 
     /// Rename or move a file or directory.
-    async fn rename(&self, from: &str, to: &str) -> Result<(), FileError>;
+    ///
+    /// Fails with [`FileError::DestinationExists`] if `to` already exists,
+    /// unless `overwrite` is set.
+    async fn rename(&self, from: &str, to: &str, overwrite: bool) -> Result<(), FileError>;
+
+    /// Lowercase hex SHA-256 of the file at `path`.
+    ///
+    /// The default reads the whole file; backends override it to hash on
+    /// their side or while streaming. Written out rather than `async` so
+    /// the default future holds the read request, not `&self`, and stays
+    /// `Send` for non-`Sync` browsers.
+    fn sha256<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, FileError>> + Send + 'a>> {
+        let contents = self.read_file(path);
+        Box::pin(async move { Ok(super::compare::sha256_hex(&contents.await?)) })
+    }
 
     /// Get metadata for a single file or directory.
     async fn stat(&self, path: &str) -> Result<FileEntry, FileError>;
 
-    /// Create a directory (and any missing parent directories) at the given path.
-    async fn mkdir(&self, path: &str) -> Result<(), FileError>;
+    /// Create a directory at the given path, along with any missing parent
+    /// directories when `recursive` is set.
+    async fn mkdir(&self, path: &str, recursive: bool) -> Result<(), FileError>;
+
+    /// Total size in bytes of all files below `path` (or of `path` itself
+    /// if it is a file).
+    ///
+    /// Stops early when `cancel` fires or after
+    /// [`DIR_SIZE_MAX_ENTRIES`](super::dir_size::DIR_SIZE_MAX_ENTRIES) entries.
+    async fn dir_size(&self, path: &str, cancel: &CancellationToken) -> Result<u64, FileError>;
+
+    /// Lines containing the literal string `pattern` in the files below
+    /// `root`, skipping binary files.
+    ///
+    /// Returns at most [`GrepOptions::max_results`] matches.
+    async fn grep(
+        &self,
+        root: &str,
+        pattern: &str,
+        opts: &GrepOptions,
+    ) -> Result<Vec<GrepMatch>, FileError>;
+
+    /// An owned handle to the same file browser.
+    ///
+    /// Lets long operations such as [`grep`](Self::grep) run without
+    /// borrowing the connection the browser came from.
+    fn clone_box(&self) -> Box<dyn FileBrowser>;
+
+    /// Downloader for packing whole directories into a single archive.
+    ///
+    /// Returns `None` (the default) when the backend cannot run the
+    /// archive tools on the remote side.
+    fn archive_downloader(&self) -> Option<Box<dyn ArchiveDownloader>> {
+        None
+    }
 }
 
 #[cfg(test)]
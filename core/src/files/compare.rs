@@ -0,0 +1,314 @@
+//! Comparison of two files, each read through a [`FileBrowser`].
+//!
+//! Used before overwriting a file to tell whether the local and remote
+//! copies differ. Without checksums, files are considered different when
+//! their size or modification time differs (the same quick check `rsync`
+//! uses); with checksums, the contents decide.
+
+use std::future::Future;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::errors::FileError;
+use crate::files::utils::shell_quote;
+use crate::files::FileBrowser;
+
+/// Size, modification time and optional checksum of one side of a comparison.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileSummary {
+    pub size: u64,
+    /// ISO 8601 timestamp, as reported by the file browser.
+    pub modified: String,
+    /// Lowercase hex SHA-256 of the contents, when requested.
+    pub checksum: Option<String>,
+}
+
+/// Result of [`compare_files`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileComparison {
+    pub local: FileSummary,
+    pub remote: FileSummary,
+    /// Whether the two files are considered different.
+    pub differs: bool,
+}
+
+/// Lowercase hex SHA-256 of `data`.
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Lowercase hex SHA-256 of the local file at `path`, read in chunks.
+pub fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect())
+}
+
+/// Shell command printing the SHA-256 of `path` with the first available
+/// tool, each in `<hex> <path>` form. Wrapped in `sh -c` so it also runs
+/// when the login shell is not POSIX.
+pub fn sha256_command(path: &str) -> String {
+    let path = shell_quote(path);
+    let script = format!(
+        "if command -v sha256sum >/dev/null 2>&1; then sha256sum -- {path}; \
+         elif command -v shasum >/dev/null 2>&1; then shasum -a 256 -- {path}; \
+         elif command -v openssl >/dev/null 2>&1; then openssl dgst -sha256 -r {path}; \
+         else echo 'no sha256sum, shasum or openssl' >&2; exit 127; fi"
+    );
+    format!("sh -c {}", shell_quote(&script))
+}
+
+/// The checksum from a line of `sha256sum` output (`<hex>  <path>`).
+pub fn parse_sha256sum(output: &str) -> Option<String> {
+    let hash = output.split_whitespace().next()?;
+    // A leading backslash marks an escaped file name, not part of the hash.
+    let hash = hash.strip_prefix('\\').unwrap_or(hash);
+    (hash.len() == 64 && hash.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then(|| hash.to_ascii_lowercase())
+}
+
+/// Summarize the file at `path`, hashing its contents if `checksum` is set.
+///
+/// The checksum comes from [`FileBrowser::sha256`], so backends that can
+/// hash on their side never transfer the contents. Fails if `path` is a
+/// directory. The browser's requests are created before the returned
+/// future is first polled, so the future does not borrow the (non-`Sync`)
+/// browser and stays `Send`.
+pub fn summarize_file<'a>(
+    browser: &'a dyn FileBrowser,
+    path: &'a str,
+    checksum: bool,
+) -> impl Future<Output = Result<FileSummary, FileError>> + Send + 'a {
+    let stat = browser.stat(path);
+    let hash = checksum.then(|| browser.sha256(path));
+    async move {
+        let entry = stat.await?;
+        if entry.is_directory {
+            return Err(FileError::OperationFailed(format!("Not a file: {path}")));
+        }
+        let checksum = match hash {
+            Some(hash) => Some(hash.await?),
+            None => None,
+        };
+        Ok(FileSummary {
+            size: entry.size,
+            modified: entry.modified,
+            checksum,
+        })
+    }
+}
+
+impl FileComparison {
+    /// Compare two summaries: by checksum when both have one, otherwise
+    /// by size and modification time.
+    pub fn new(local: FileSummary, remote: FileSummary) -> Self {
+        let differs = match (&local.checksum, &remote.checksum) {
+            (Some(a), Some(b)) => a != b,
+            _ => local.size != remote.size || local.modified != remote.modified,
+        };
+        Self {
+            local,
+            remote,
+            differs,
+        }
+    }
+}
+
+/// Compare `local_path` on `local` with `remote_path` on `remote`.
+///
+/// With `checksum`, both files are hashed and the verdict is
+/// based on their contents; otherwise on size and modification time.
+/// Like [`summarize_file`], the returned future is `Send`.
+pub fn compare_files<'a>(
+    local: &'a dyn FileBrowser,
+    local_path: &'a str,
+    remote: &'a dyn FileBrowser,
+    remote_path: &'a str,
+    checksum: bool,
+) -> impl Future<Output = Result<FileComparison, FileError>> + Send + 'a {
+    let local = summarize_file(local, local_path, checksum);
+    let remote = summarize_file(remote, remote_path, checksum);
+    async move { Ok(FileComparison::new(local.await?, remote.await?)) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::files::LocalFileBrowser;
+
+    fn write(dir: &tempfile::TempDir, name: &str, data: &[u8]) -> String {
+        let path = dir.path().join(name);
+        std::fs::write(&path, data).unwrap();
+        path.to_string_lossy().to_string()
+    }
+
+    /// Give `path` a fixed modification time, so files written at different
+    /// moments can share one.
+    fn set_mtime(path: &str, secs: u64) {
+        let time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs);
+        std::fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(time)
+            .unwrap();
+    }
+
+    #[test]
+    fn sha256_hex_known_value() {
+        assert_eq!(
+            sha256_hex(b"abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn parse_sha256sum_output() {
+        let hash = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert_eq!(
+            parse_sha256sum(&format!("{hash}  /tmp/abc.txt\n")).as_deref(),
+            Some(hash)
+        );
+        assert_eq!(
+            parse_sha256sum(&format!("\\{}  /tmp/a\\nb\n", hash.to_uppercase())).as_deref(),
+            Some(hash),
+            "escaped file name"
+        );
+        assert_eq!(
+            parse_sha256sum(&format!("{hash} */tmp/abc.txt\n")).as_deref(),
+            Some(hash),
+            "openssl dgst -r output"
+        );
+        assert_eq!(parse_sha256sum(""), None);
+        assert_eq!(parse_sha256sum("abc123  file\n"), None);
+    }
+
+    #[test]
+    fn sha256_command_falls_back_to_shasum_and_openssl() {
+        let command = sha256_command("/tmp/x.txt");
+        assert!(command.starts_with("sh -c '"));
+        let quoted = "'\\''/tmp/x.txt'\\''";
+        let sha256sum = command.find(&format!("sha256sum -- {quoted}")).unwrap();
+        let shasum = command.find(&format!("shasum -a 256 -- {quoted}")).unwrap();
+        let openssl = command
+            .find(&format!("openssl dgst -sha256 -r {quoted}"))
+            .unwrap();
+        assert!(sha256sum < shasum && shasum < openssl);
+    }
+
+    #[tokio::test]
+    async fn local_browser_hashes_by_streaming() {
+        let dir = tempfile::tempdir().unwrap();
+        let data = vec![7u8; 100_000];
+        let path = write(&dir, "big.bin", &data);
+        let browser = LocalFileBrowser::new();
+        assert_eq!(browser.sha256(&path).await.unwrap(), sha256_hex(&data));
+        assert_eq!(sha256_file(Path::new(&path)).unwrap(), sha256_hex(&data));
+    }
+
+    #[test]
+    fn comparison_future_is_send() {
+        fn assert_send<T: Send>(_: &T) {}
+        let browser = LocalFileBrowser::new();
+        let browser: &dyn FileBrowser = &browser;
+        assert_send(&compare_files(browser, "a", browser, "b", true));
+    }
+
+    #[tokio::test]
+    async fn identical_files_do_not_differ() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write(&dir, "a.txt", b"same content");
+        let b = write(&dir, "b.txt", b"same content");
+        set_mtime(&a, 1_700_000_000);
+        set_mtime(&b, 1_700_000_000);
+        let browser = LocalFileBrowser::new();
+
+        for checksum in [false, true] {
+            let result = compare_files(&browser, &a, &browser, &b, checksum)
+                .await
+                .unwrap();
+            assert!(!result.differs, "checksum: {checksum}");
+            assert_eq!(result.local.size, 12);
+            assert_eq!(result.local.checksum.is_some(), checksum);
+            assert_eq!(result.local.checksum, result.remote.checksum);
+        }
+    }
+
+    #[tokio::test]
+    async fn differing_contents_differ() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write(&dir, "a.txt", b"version 1");
+        let b = write(&dir, "b.txt", b"version 2");
+        set_mtime(&a, 1_700_000_000);
+        set_mtime(&b, 1_700_000_000);
+        let browser = LocalFileBrowser::new();
+
+        // Same size and mtime: only the checksum tells them apart.
+        let quick = compare_files(&browser, &a, &browser, &b, false)
+            .await
+            .unwrap();
+        assert!(!quick.differs);
+        let full = compare_files(&browser, &a, &browser, &b, true)
+            .await
+            .unwrap();
+        assert!(full.differs);
+        assert_ne!(full.local.checksum, full.remote.checksum);
+    }
+
+    #[tokio::test]
+    async fn size_or_mtime_mismatch_differs_without_checksum() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write(&dir, "a.txt", b"short");
+        let b = write(&dir, "b.txt", b"longer content");
+        let c = write(&dir, "c.txt", b"short");
+        set_mtime(&a, 1_700_000_000);
+        set_mtime(&b, 1_700_000_000);
+        set_mtime(&c, 1_700_000_100);
+        let browser = LocalFileBrowser::new();
+
+        let result = compare_files(&browser, &a, &browser, &b, false)
+            .await
+            .unwrap();
+        assert!(result.differs);
+        assert_ne!(result.local.size, result.remote.size);
+
+        let result = compare_files(&browser, &a, &browser, &c, false)
+            .await
+            .unwrap();
+        assert!(result.differs);
+
+        // Same contents touched at different times are equal by checksum.
+        let result = compare_files(&browser, &a, &browser, &c, true)
+            .await
+            .unwrap();
+        assert!(!result.differs);
+    }
+
+    #[tokio::test]
+    async fn directories_and_missing_files_are_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let a = write(&dir, "a.txt", b"data");
+        let root = dir.path().to_string_lossy().to_string();
+        let missing = dir.path().join("missing").to_string_lossy().to_string();
+        let browser = LocalFileBrowser::new();
+
+        assert!(compare_files(&browser, &a, &browser, &root, false)
+            .await
+            .is_err());
+        assert!(matches!(
+            compare_files(&browser, &missing, &browser, &a, false).await,
+            Err(FileError::NotFound(_))
+        ));
+    }
+}
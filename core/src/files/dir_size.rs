@@ -0,0 +1,287 @@
+//! Recursive directory size computation for file browsers.
+//!
+//! Traversal is bounded by an entry limit and can be cancelled through a
+//! [`CancellationToken`], so huge trees cannot stall a session.
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use tokio_util::sync::CancellationToken;
+
+use crate::errors::FileError;
+use crate::files::FileEntry;
+
+/// Maximum number of entries visited before a size computation is aborted.
+pub const DIR_SIZE_MAX_ENTRIES: usize = 500_000;
+
+fn cancelled_error() -> FileError {
+    FileError::OperationFailed("Directory size computation cancelled".to_string())
+}
+
+fn too_many_entries_error(max_entries: usize) -> FileError {
+    FileError::OperationFailed(format!(
+        "Directory size computation aborted after {max_entries} entries"
+    ))
+}
+
+/// Sum the sizes of all files below `root` on the local filesystem.
+///
+/// Symlinks are counted by their own size and never followed. On Unix,
+/// directories are also tracked by device and inode, so bind mounts that
+/// loop back into the tree are only counted once. Unreadable subdirectories
+/// are skipped; an unreadable `root` is an error.
+pub fn local_dir_size(
+    root: &Path,
+    cancel: &CancellationToken,
+    max_entries: usize,
+) -> Result<u64, FileError> {
+    let root_meta = std::fs::symlink_metadata(root).map_err(|e| map_root_error(e, root))?;
+    if !root_meta.is_dir() {
+        return Ok(root_meta.len());
+    }
+
+    let mut visited = std::collections::HashSet::new();
+    mark_visited(&mut visited, &root_meta);
+    let mut pending: Vec<PathBuf> = vec![root.to_path_buf()];
+    let mut total: u64 = 0;
+    let mut entries: usize = 0;
+
+    while let Some(dir) = pending.pop() {
+        let read_dir = match std::fs::read_dir(&dir) {
+            Ok(rd) => rd,
+            Err(e) if dir == root => return Err(map_root_error(e, root)),
+            Err(_) => continue,
+        };
+        for entry in read_dir.flatten() {
+            if cancel.is_cancelled() {
+                return Err(cancelled_error());
+            }
+            entries += 1;
+            if entries > max_entries {
+                return Err(too_many_entries_error(max_entries));
+            }
+            let Ok(meta) = std::fs::symlink_metadata(entry.path()) else {
+                continue;
+            };
+            if meta.is_dir() {
+                if mark_visited(&mut visited, &meta) {
+                    pending.push(entry.path());
+                }
+            } else {
+                total += meta.len();
+            }
+        }
+    }
+
+    Ok(total)
+}
+
+/// Record a directory as visited. Returns `false` if it was seen before.
+#[cfg(unix)]
+fn mark_visited(
+    visited: &mut std::collections::HashSet<(u64, u64)>,
+    meta: &std::fs::Metadata,
+) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    visited.insert((meta.dev(), meta.ino()))
+}
+
+/// Without inode numbers, loop protection relies on not following symlinks.
+#[cfg(not(unix))]
+fn mark_visited(
+    _visited: &mut std::collections::HashSet<(u64, u64)>,
+    _meta: &std::fs::Metadata,
+) -> bool {
+    true
+}
+
+fn map_root_error(e: std::io::Error, root: &Path) -> FileError {
+    let path = root.display().to_string();
+    match e.kind() {
+        std::io::ErrorKind::NotFound => FileError::NotFound(path),
+        std::io::ErrorKind::PermissionDenied => FileError::PermissionDenied(path),
+        _ => FileError::OperationFailed(format!("{path}: {e}")),
+    }
+}
+
+/// Sum file sizes below `root` using a directory `list` operation.
+///
+/// For backends without a cheaper native method (SFTP, remote agents).
+/// Listed entries are not expected to include symlinked directories as
+/// directories more than once; the entry limit bounds any loop that does.
+pub async fn walk_dir_size<F, Fut>(
+    root: &str,
+    cancel: &CancellationToken,
+    max_entries: usize,
+    list: F,
+) -> Result<u64, FileError>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Vec<FileEntry>, FileError>>,
+{
+    let mut pending = vec![root.to_string()];
+    let mut total: u64 = 0;
+    let mut entries: usize = 0;
+
+    while let Some(dir) = pending.pop() {
+        if cancel.is_cancelled() {
+            return Err(cancelled_error());
+        }
+        let listing = match list(dir.clone()).await {
+            Ok(listing) => listing,
+            Err(e) if dir == root => return Err(e),
+            Err(_) => continue,
+        };
+        for entry in listing {
+            entries += 1;
+            if entries > max_entries {
+                return Err(too_many_entries_error(max_entries));
+            }
+            if entry.is_directory {
+                pending.push(entry.path);
+            } else {
+                total += entry.size;
+            }
+        }
+    }
+
+    Ok(total)
+}
+
+/// Parse the output of `du -sb <path>` (`<bytes>\t<path>`).
+pub fn parse_du_output(output: &str) -> Result<u64, FileError> {
+    output
+        .split_whitespace()
+        .next()
+        .and_then(|n| n.parse().ok())
+        .ok_or_else(|| FileError::OperationFailed(format!("Unexpected du output: {output}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Build a tree with known file sizes: 5 + 10 + 100 + 1 = 116 bytes.
+    fn known_tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("a.txt"), [0u8; 5]).unwrap();
+        std::fs::create_dir_all(root.join("sub/deeper")).unwrap();
+        std::fs::create_dir(root.join("empty")).unwrap();
+        std::fs::write(root.join("sub/b.bin"), [0u8; 10]).unwrap();
+        std::fs::write(root.join("sub/deeper/c.bin"), [0u8; 100]).unwrap();
+        std::fs::write(root.join("sub/deeper/d"), [0u8; 1]).unwrap();
+        dir
+    }
+
+    #[test]
+    fn local_dir_size_sums_known_tree() {
+        let dir = known_tree();
+        let size =
+            local_dir_size(dir.path(), &CancellationToken::new(), DIR_SIZE_MAX_ENTRIES).unwrap();
+        assert_eq!(size, 116);
+    }
+
+    #[test]
+    fn local_dir_size_of_subdirectory() {
+        let dir = known_tree();
+        let size = local_dir_size(
+            &dir.path().join("sub/deeper"),
+            &CancellationToken::new(),
+            DIR_SIZE_MAX_ENTRIES,
+        )
+        .unwrap();
+        assert_eq!(size, 101);
+    }
+
+    #[test]
+    fn local_dir_size_of_file_is_its_length() {
+        let dir = known_tree();
+        let size = local_dir_size(
+            &dir.path().join("a.txt"),
+            &CancellationToken::new(),
+            DIR_SIZE_MAX_ENTRIES,
+        )
+        .unwrap();
+        assert_eq!(size, 5);
+    }
+
+    #[test]
+    fn local_dir_size_missing_root_is_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = local_dir_size(
+            &dir.path().join("missing"),
+            &CancellationToken::new(),
+            DIR_SIZE_MAX_ENTRIES,
+        )
+        .unwrap_err();
+        assert!(matches!(err, FileError::NotFound(_)));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn local_dir_size_does_not_follow_symlink_loops() {
+        let dir = known_tree();
+        // sub/loop -> root would recurse forever if followed.
+        std::os::unix::fs::symlink(dir.path(), dir.path().join("sub/loop")).unwrap();
+        let link_len = std::fs::symlink_metadata(dir.path().join("sub/loop"))
+            .unwrap()
+            .len();
+        let size =
+            local_dir_size(dir.path(), &CancellationToken::new(), DIR_SIZE_MAX_ENTRIES).unwrap();
+        assert_eq!(size, 116 + link_len);
+    }
+
+    #[test]
+    fn local_dir_size_honours_cancellation() {
+        let dir = known_tree();
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let err = local_dir_size(dir.path(), &cancel, DIR_SIZE_MAX_ENTRIES).unwrap_err();
+        assert!(err.to_string().contains("cancelled"));
+    }
+
+    #[test]
+    fn local_dir_size_is_bounded() {
+        let dir = known_tree();
+        let err = local_dir_size(dir.path(), &CancellationToken::new(), 3).unwrap_err();
+        assert!(err.to_string().contains("after 3 entries"));
+    }
+
+    #[tokio::test]
+    async fn walk_dir_size_matches_local_walk() {
+        let dir = known_tree();
+        let root = dir.path().to_str().unwrap();
+        let size = walk_dir_size(
+            root,
+            &CancellationToken::new(),
+            DIR_SIZE_MAX_ENTRIES,
+            |p| async move {
+                super::super::local::list_dir_sync(&p)
+                    .map_err(|e| FileError::OperationFailed(e.to_string()))
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(size, 116);
+    }
+
+    #[tokio::test]
+    async fn walk_dir_size_honours_cancellation() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let err = walk_dir_size("/", &cancel, DIR_SIZE_MAX_ENTRIES, |_| async {
+            Ok(Vec::new())
+        })
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("cancelled"));
+    }
+
+    #[test]
+    fn parse_du_output_reads_byte_count() {
+        assert_eq!(parse_du_output("4096\t/var/log\n").unwrap(), 4096);
+        assert!(parse_du_output("du: cannot access").is_err());
+        assert!(parse_du_output("").is_err());
+    }
+}
@@ -0,0 +1,613 @@
+//! Searching file contents below a directory for a literal string.
+//!
+//! The local filesystem is walked and scanned line by line; remote
+//! backends run `grep` on the remote side and parse its output with
+//! [`parse_grep_output`]. Either way, files that look binary are skipped
+//! and the number of results is capped, so a search across a huge tree
+//! returns quickly with the first matches.
+
+use std::future::Future;
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::FileError;
+use crate::files::utils::shell_quote;
+use crate::files::FileEntry;
+
+/// Default maximum number of matches returned by a search.
+pub const GREP_MAX_RESULTS: usize = 1000;
+
+/// Matched lines longer than this many characters are truncated.
+pub const GREP_MAX_LINE_CHARS: usize = 500;
+
+/// Bytes inspected for a NUL byte to decide whether a file is binary,
+/// the same heuristic `grep` and `git` use.
+const BINARY_SNIFF_BYTES: usize = 8000;
+
+/// Options for a content search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GrepOptions {
+    /// Match regardless of letter case.
+    pub case_insensitive: bool,
+    /// Stop after this many matches.
+    pub max_results: usize,
+}
+
+impl Default for GrepOptions {
+    fn default() -> Self {
+        Self {
+            case_insensitive: false,
+            max_results: GREP_MAX_RESULTS,
+        }
+    }
+}
+
+/// A line containing the search string.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepMatch {
+    /// Path of the file containing the match.
+    pub path: String,
+    /// 1-based line number.
+    pub line: u64,
+    /// Text of the matching line, without the line ending.
+    pub text: String,
+}
+
+/// Literal line matcher honoring [`GrepOptions::case_insensitive`].
+struct Matcher {
+    needle: String,
+    case_insensitive: bool,
+}
+
+impl Matcher {
+    fn new(pattern: &str, opts: &GrepOptions) -> Self {
+        let needle = if opts.case_insensitive {
+            pattern.to_lowercase()
+        } else {
+            pattern.to_string()
+        };
+        Self {
+            needle,
+            case_insensitive: opts.case_insensitive,
+        }
+    }
+
+    fn is_match(&self, line: &str) -> bool {
+        if self.case_insensitive {
+            line.to_lowercase().contains(&self.needle)
+        } else {
+            line.contains(&self.needle)
+        }
+    }
+}
+
+fn empty_pattern_error() -> FileError {
+    FileError::OperationFailed("Search string must not be empty".to_string())
+}
+
+fn is_binary(head: &[u8]) -> bool {
+    head[..head.len().min(BINARY_SNIFF_BYTES)].contains(&0)
+}
+
+fn truncate_line(line: &str) -> String {
+    match line.char_indices().nth(GREP_MAX_LINE_CHARS) {
+        Some((end, _)) => line[..end].to_string(),
+        None => line.to_string(),
+    }
+}
+
+/// Append the matching lines of `content` to `matches`, up to `max_results`.
+fn scan_lines(
+    path: &str,
+    content: impl BufRead,
+    matcher: &Matcher,
+    max_results: usize,
+    matches: &mut Vec<GrepMatch>,
+) {
+    for (index, line) in content.split(b'\n').enumerate() {
+        if matches.len() >= max_results {
+            return;
+        }
+        let Ok(line) = line else {
+            return;
+        };
+        let line = String::from_utf8_lossy(&line);
+        let line = line.strip_suffix('\r').unwrap_or(&line);
+        if matcher.is_match(line) {
+            matches.push(GrepMatch {
+                path: path.to_string(),
+                line: index as u64 + 1,
+                text: truncate_line(line),
+            });
+        }
+    }
+}
+
+/// Scan one local file, skipping it if it is binary or unreadable.
+fn scan_local_file(
+    path: &Path,
+    matcher: &Matcher,
+    max_results: usize,
+    matches: &mut Vec<GrepMatch>,
+) {
+    let Ok(file) = std::fs::File::open(path) else {
+        return;
+    };
+    let mut reader = BufReader::new(file);
+    let mut head = Vec::with_capacity(BINARY_SNIFF_BYTES);
+    if (&mut reader)
+        .take(BINARY_SNIFF_BYTES as u64)
+        .read_to_end(&mut head)
+        .is_err()
+        || is_binary(&head)
+    {
+        return;
+    }
+    let content = head.as_slice().chain(reader);
+    scan_lines(
+        &path.display().to_string(),
+        content,
+        matcher,
+        max_results,
+        matches,
+    );
+}
+
+/// Search the files below `root` on the local filesystem for lines
+/// containing `pattern`.
+///
+/// Symlinks are not followed, and unreadable files and subdirectories are
+/// skipped; an unreadable `root` is an error. If `root` is a file, only
+/// that file is searched.
+pub fn local_grep(
+    root: &Path,
+    pattern: &str,
+    opts: &GrepOptions,
+) -> Result<Vec<GrepMatch>, FileError> {
+    if pattern.is_empty() {
+        return Err(empty_pattern_error());
+    }
+    let matcher = Matcher::new(pattern, opts);
+    let mut matches = Vec::new();
+
+    let root_meta = std::fs::symlink_metadata(root).map_err(|e| map_root_error(e, root))?;
+    if !root_meta.is_dir() {
+        scan_local_file(root, &matcher, opts.max_results, &mut matches);
+        return Ok(matches);
+    }
+
+    let mut pending: Vec<PathBuf> = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let read_dir = match std::fs::read_dir(&dir) {
+            Ok(rd) => rd,
+            Err(e) if dir == root => return Err(map_root_error(e, root)),
+            Err(_) => continue,
+        };
+        let mut entries: Vec<_> = read_dir.flatten().map(|e| e.path()).collect();
+        entries.sort();
+        let mut subdirs = Vec::new();
+        for path in entries {
+            let Ok(meta) = std::fs::symlink_metadata(&path) else {
+                continue;
+            };
+            if meta.is_dir() {
+                subdirs.push(path);
+            } else if meta.is_file() {
+                scan_local_file(&path, &matcher, opts.max_results, &mut matches);
+                if matches.len() >= opts.max_results {
+                    return Ok(matches);
+                }
+            }
+        }
+        // Search subdirectories in name order after this directory's files.
+        pending.extend(subdirs.into_iter().rev());
+    }
+
+    Ok(matches)
+}
+
+/// Search the files below `root` using a directory `list` and a file
+/// `read` operation.
+///
+/// For backends without a way to search on the remote side. Every file
+/// is downloaded in full, so this is only suitable for small trees.
+pub async fn walk_grep<L, LFut, R, RFut>(
+    root: &str,
+    pattern: &str,
+    opts: &GrepOptions,
+    list: L,
+    read: R,
+) -> Result<Vec<GrepMatch>, FileError>
+where
+    L: Fn(String) -> LFut,
+    LFut: Future<Output = Result<Vec<FileEntry>, FileError>>,
+    R: Fn(String) -> RFut,
+    RFut: Future<Output = Result<Vec<u8>, FileError>>,
+{
+    if pattern.is_empty() {
+        return Err(empty_pattern_error());
+    }
+    let matcher = Matcher::new(pattern, opts);
+    let mut matches = Vec::new();
+    let mut pending = vec![root.to_string()];
+
+    while let Some(dir) = pending.pop() {
+        let listing = match list(dir.clone()).await {
+            Ok(listing) => listing,
+            Err(e) if dir == root => return Err(e),
+            Err(_) => continue,
+        };
+        for entry in listing {
+            if entry.is_directory {
+                pending.push(entry.path);
+                continue;
+            }
+            let Ok(content) = read(entry.path.clone()).await else {
+                continue;
+            };
+            if is_binary(&content) {
+                continue;
+            }
+            scan_lines(
+                &entry.path,
+                content.as_slice(),
+                &matcher,
+                opts.max_results,
+                &mut matches,
+            );
+            if matches.len() >= opts.max_results {
+                return Ok(matches);
+            }
+        }
+    }
+
+    Ok(matches)
+}
+
+/// Longest time a remote search may run before it is abandoned.
+pub const GREP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Prefix of the line [`grep_command`] appends with the search's exit
+/// status. Starts with `\x01` so it never looks like a match.
+const STATUS_PREFIX: &str = "\x01status ";
+
+/// Shell command that prints up to `max_results` matches of `pattern`
+/// below `root` in the format read by [`parse_grep_output`].
+///
+/// Uses `grep -rHnIF --null` (recursive, with file names and line
+/// numbers, skipping binary files, matching a fixed string) when the
+/// remote `grep` supports those flags. Otherwise — e.g. with BusyBox — each file found
+/// by `find` is searched on its own, and matching files with a NUL byte
+/// in their first 8000 bytes are skipped.
+///
+/// Unreadable files below `root` are ignored. The exit status of the
+/// search is printed after the matches, so it is not hidden by the
+/// `head` that caps the output: 0 or 1 as for `grep`, 2 if `root` is
+/// not readable, 127 if there is no `grep`.
+pub fn grep_command(root: &str, pattern: &str, opts: &GrepOptions) -> Result<String, FileError> {
+    if pattern.is_empty() {
+        return Err(empty_pattern_error());
+    }
+    let case_flag = if opts.case_insensitive { "i" } else { "" };
+    // Searches the files given as "$@" for "$p" one by one.
+    let per_file = format!(
+        r#"p=$1; shift
+for f do
+  m=$(grep -nFs{case_flag} -e "$p" -- "$f") || continue
+  [ "$(head -c {BINARY_SNIFF_BYTES} "$f" | tr -d "\000" | wc -c)" -eq "$(head -c {BINARY_SNIFF_BYTES} "$f" | wc -c)" ] || continue
+  printf "%s\n" "$m" | while IFS= read -r l; do printf "%s\000%s\n" "$f" "$l"; done
+done"#
+    );
+    let script = format!(
+        r#"r={root}; p={pattern}
+search() {{
+  command -v grep >/dev/null 2>&1 || return 127
+  if printf 'x\n' | grep -IFs --null -e x >/dev/null 2>&1; then
+    grep -rHnIFs{case_flag} --null -e "$p" -- "$r"
+  else
+    find "$r" -type f -exec sh -c {per_file} sh "$p" {{}} +
+  fi
+}}
+if [ -r "$r" ]; then
+  {{ search; s=$?; [ "$s" -eq 2 ] && s=1; printf '\001status %s\n' "$s"; }} | head -n {limit}
+else
+  printf '\001status 2\n'
+fi"#,
+        root = shell_quote(root),
+        pattern = shell_quote(pattern),
+        per_file = shell_quote(&per_file),
+        limit = opts.max_results + 1,
+    );
+    Ok(format!("sh -c {}", shell_quote(&script)))
+}
+
+/// Parse the output of [`grep_command`]: one `<path>\0<line>:<text>` per
+/// match, followed by the exit status of the search.
+///
+/// Fails if the search reported an exit status of 2 or higher, or if it
+/// stopped before reporting its status without having reached
+/// `max_results`. Lines that have neither shape are ignored.
+pub fn parse_grep_output(output: &str, max_results: usize) -> Result<Vec<GrepMatch>, FileError> {
+    let mut status = None;
+    let matches: Vec<GrepMatch> = output
+        .lines()
+        .filter_map(|line| {
+            if let Some(code) = line.strip_prefix(STATUS_PREFIX) {
+                status = code.trim().parse::<i32>().ok();
+                return None;
+            }
+            let (path, rest) = line.split_once('\0')?;
+            let (number, text) = rest.split_once(':')?;
+            Some(GrepMatch {
+                path: path.to_string(),
+                line: number.parse().ok()?,
+                text: truncate_line(text.strip_suffix('\r').unwrap_or(text)),
+            })
+        })
+        .take(max_results)
+        .collect();
+    match status {
+        Some(code) if code >= 2 => Err(FileError::OperationFailed(format!(
+            "grep failed with exit status {code}"
+        ))),
+        None if matches.len() < max_results => Err(FileError::OperationFailed(
+            "grep did not report an exit status".to_string(),
+        )),
+        _ => Ok(matches),
+    }
+}
+
+fn map_root_error(e: std::io::Error, root: &Path) -> FileError {
+    let path = root.display().to_string();
+    match e.kind() {
+        std::io::ErrorKind::NotFound => FileError::NotFound(path),
+        std::io::ErrorKind::PermissionDenied => FileError::PermissionDenied(path),
+        _ => FileError::OperationFailed(format!("{path}: {e}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(path: &Path, content: &[u8]) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    /// A tree with text files in two levels and a binary file that also
+    /// contains the search string.
+    fn known_tree() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            &dir.path().join("a.txt"),
+            b"first line\nneedle here\nthird\r\nNEEDLE upper\n",
+        );
+        write(&dir.path().join("sub/b.rs"), b"fn needle() {}\n");
+        write(&dir.path().join("image.bin"), b"\x89PNG\x00\x01needle\x00");
+        dir
+    }
+
+    fn relative(dir: &tempfile::TempDir, m: &GrepMatch) -> (String, u64, String) {
+        let path = Path::new(&m.path).strip_prefix(dir.path()).unwrap();
+        (path.display().to_string(), m.line, m.text.clone())
+    }
+
+    #[test]
+    fn local_grep_reports_matches_with_line_numbers() {
+        let dir = known_tree();
+        let matches = local_grep(dir.path(), "needle", &GrepOptions::default()).unwrap();
+        let found: Vec<_> = matches.iter().map(|m| relative(&dir, m)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("a.txt".to_string(), 2, "needle here".to_string()),
+                (
+                    Path::new("sub").join("b.rs").display().to_string(),
+                    1,
+                    "fn needle() {}".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn local_grep_skips_binary_files() {
+        let dir = known_tree();
+        let matches = local_grep(dir.path(), "needle", &GrepOptions::default()).unwrap();
+        assert!(matches.iter().all(|m| !m.path.ends_with("image.bin")));
+    }
+
+    #[test]
+    fn local_grep_case_insensitive() {
+        let dir = known_tree();
+        let opts = GrepOptions {
+            case_insensitive: true,
+            ..GrepOptions::default()
+        };
+        let matches = local_grep(&dir.path().join("a.txt"), "needle", &opts).unwrap();
+        let lines: Vec<u64> = matches.iter().map(|m| m.line).collect();
+        assert_eq!(lines, vec![2, 4]);
+    }
+
+    #[test]
+    fn local_grep_caps_results() {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("many.txt"), "hit\n".repeat(50).as_bytes());
+        let opts = GrepOptions {
+            max_results: 10,
+            ..GrepOptions::default()
+        };
+        assert_eq!(local_grep(dir.path(), "hit", &opts).unwrap().len(), 10);
+    }
+
+    #[test]
+    fn local_grep_strips_crlf_and_truncates_long_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let long = format!("x{}\r\n", "y".repeat(GREP_MAX_LINE_CHARS * 2));
+        write(&dir.path().join("long.txt"), long.as_bytes());
+        let matches = local_grep(dir.path(), "x", &GrepOptions::default()).unwrap();
+        assert_eq!(matches[0].text.chars().count(), GREP_MAX_LINE_CHARS);
+        assert!(!matches[0].text.ends_with('\r'));
+    }
+
+    #[test]
+    fn local_grep_missing_root_is_not_found() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = local_grep(&dir.path().join("missing"), "x", &GrepOptions::default());
+        assert!(matches!(result, Err(FileError::NotFound(_))));
+    }
+
+    #[test]
+    fn empty_pattern_is_rejected() {
+        let dir = known_tree();
+        assert!(local_grep(dir.path(), "", &GrepOptions::default()).is_err());
+        assert!(grep_command("/tmp", "", &GrepOptions::default()).is_err());
+    }
+
+    #[tokio::test]
+    async fn walk_grep_matches_local_grep() {
+        let dir = known_tree();
+        let root = dir.path().to_str().unwrap();
+        let mut walked = walk_grep(
+            root,
+            "needle",
+            &GrepOptions::default(),
+            |p| async move {
+                super::super::local::list_dir_sync(&p)
+                    .map_err(|e| FileError::OperationFailed(e.to_string()))
+            },
+            |p| async move { std::fs::read(&p).map_err(FileError::from) },
+        )
+        .await
+        .unwrap();
+        walked.sort_by(|a, b| a.path.cmp(&b.path));
+        let local = local_grep(dir.path(), "needle", &GrepOptions::default()).unwrap();
+        let lines = |m: &[GrepMatch]| -> Vec<(u64, String)> {
+            m.iter().map(|m| (m.line, m.text.clone())).collect()
+        };
+        assert_eq!(lines(&walked), lines(&local));
+    }
+
+    /// Run `grep_command` with `sh`, optionally with a `grep` on `PATH`
+    /// that rejects `-I` and `--null` the way BusyBox does.
+    #[cfg(unix)]
+    fn run_grep_command(root: &Path, pattern: &str, opts: &GrepOptions, busybox: bool) -> String {
+        use std::os::unix::fs::PermissionsExt;
+
+        let command = grep_command(root.to_str().unwrap(), pattern, opts).unwrap();
+        let mut sh = std::process::Command::new("sh");
+        sh.arg("-c").arg(&command);
+        let bin = tempfile::tempdir().unwrap();
+        if busybox {
+            let real = String::from_utf8(
+                std::process::Command::new("sh")
+                    .args(["-c", "command -v grep"])
+                    .output()
+                    .unwrap()
+                    .stdout,
+            )
+            .unwrap();
+            let wrapper = bin.path().join("grep");
+            std::fs::write(
+                &wrapper,
+                format!(
+                    "#!/bin/sh\nfor a; do case $a in --null|-*I*) exit 2;; esac; done\nexec {} \"$@\"\n",
+                    real.trim()
+                ),
+            )
+            .unwrap();
+            std::fs::set_permissions(&wrapper, std::fs::Permissions::from_mode(0o755)).unwrap();
+            let path = format!(
+                "{}:{}",
+                bin.path().display(),
+                std::env::var("PATH").unwrap()
+            );
+            sh.env("PATH", path);
+        }
+        String::from_utf8(sh.output().unwrap().stdout).unwrap()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn grep_command_output_matches_local_grep() {
+        let dir = known_tree();
+        std::fs::write(dir.path().join("it's here.txt"), "a 'needle'\n").unwrap();
+        let local = local_grep(dir.path(), "needle", &GrepOptions::default()).unwrap();
+        let lines = |m: &[GrepMatch]| -> Vec<(String, u64, String)> {
+            let mut found: Vec<_> = m.iter().map(|m| relative(&dir, m)).collect();
+            found.sort();
+            found
+        };
+        for busybox in [false, true] {
+            let output = run_grep_command(dir.path(), "needle", &GrepOptions::default(), busybox);
+            let remote = parse_grep_output(&output, GREP_MAX_RESULTS).unwrap();
+            assert_eq!(lines(&remote), lines(&local), "busybox: {busybox}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn grep_command_caps_results_without_losing_status() {
+        let dir = tempfile::tempdir().unwrap();
+        write(&dir.path().join("many.txt"), "hit\n".repeat(50).as_bytes());
+        let opts = GrepOptions {
+            max_results: 10,
+            ..GrepOptions::default()
+        };
+        for busybox in [false, true] {
+            let output = run_grep_command(dir.path(), "hit", &opts, busybox);
+            assert_eq!(parse_grep_output(&output, 10).unwrap().len(), 10);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn grep_command_reports_unreadable_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let output = run_grep_command(
+            &dir.path().join("missing"),
+            "x",
+            &GrepOptions::default(),
+            false,
+        );
+        assert!(parse_grep_output(&output, GREP_MAX_RESULTS).is_err());
+    }
+
+    #[test]
+    fn parse_grep_output_splits_path_line_and_text() {
+        let output =
+            "/srv/a b.txt\x002:key: value\n/srv/c.txt\x0010:x\r\nnot grep output\n\x01status 0\n";
+        assert_eq!(
+            parse_grep_output(output, 10).unwrap(),
+            vec![
+                GrepMatch {
+                    path: "/srv/a b.txt".to_string(),
+                    line: 2,
+                    text: "key: value".to_string(),
+                },
+                GrepMatch {
+                    path: "/srv/c.txt".to_string(),
+                    line: 10,
+                    text: "x".to_string(),
+                },
+            ]
+        );
+        assert_eq!(parse_grep_output(output, 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn parse_grep_output_checks_status() {
+        assert_eq!(parse_grep_output("\x01status 1\n", 10).unwrap(), vec![]);
+        assert!(parse_grep_output("\x01status 127\n", 10).is_err());
+        // Output that ends before the status line is only complete if it
+        // was cut off by the result cap.
+        assert!(parse_grep_output("/a\x001:x\n", 10).is_err());
+        assert_eq!(parse_grep_output("/a\x001:x\n", 1).unwrap().len(), 1);
+    }
+}
@@ -1,7 +1,13 @@
+use std::future::Future;
 use std::path::Path;
+use std::pin::Pin;
+
+use tokio_util::sync::CancellationToken;
 
 use crate::errors::FileError;
 
+use super::dir_size::{local_dir_size, DIR_SIZE_MAX_ENTRIES};
+use super::grep::{local_grep, GrepMatch, GrepOptions};
 use super::utils::{chrono_from_epoch, normalize_path_separators, normalize_platform_path};
 use super::{FileBackend, FileEntry};
 
@@ -82,6 +88,15 @@ fn map_io_error(e: std::io::Error, path: &str) -> FileError {
     }
 }
 
+/// Synchronous rename that refuses to replace an existing destination
+/// unless `overwrite` is set.
+fn rename_sync(old: &str, new: &str, overwrite: bool) -> Result<(), FileError> {
+    if !overwrite && std::fs::symlink_metadata(new).is_ok() {
+        return Err(FileError::DestinationExists(new.to_string()));
+    }
+    std::fs::rename(old, new).map_err(|e| map_io_error(e, old))
+}
+
 /// Synchronous stat for a single path.
 fn stat_sync(path: &str) -> Result<FileEntry, FileError> {
     let normalized = normalize_platform_path(path);
@@ -115,6 +130,31 @@ fn stat_sync(path: &str) -> Result<FileEntry, FileError> {
     })
 }
 
+/// Compute a local directory size without blocking the async runtime.
+async fn dir_size_blocking(path: &str, cancel: &CancellationToken) -> Result<u64, FileError> {
+    let normalized = normalize_platform_path(path);
+    let cancel = cancel.clone();
+    tokio::task::spawn_blocking(move || {
+        local_dir_size(Path::new(&normalized), &cancel, DIR_SIZE_MAX_ENTRIES)
+    })
+    .await
+    .map_err(|e| FileError::OperationFailed(e.to_string()))?
+}
+
+/// Search local files without blocking the async runtime.
+async fn grep_blocking(
+    root: &str,
+    pattern: &str,
+    opts: &GrepOptions,
+) -> Result<Vec<GrepMatch>, FileError> {
+    let normalized = normalize_platform_path(root);
+    let pattern = pattern.to_string();
+    let opts = opts.clone();
+    tokio::task::spawn_blocking(move || local_grep(Path::new(&normalized), &pattern, &opts))
+        .await
+        .map_err(|e| FileError::OperationFailed(e.to_string()))?
+}
+
 /// File backend that operates on the local filesystem.
 ///
 /// All blocking I/O is wrapped in `tokio::task::spawn_blocking` to avoid
@@ -177,14 +217,17 @@ impl FileBackend for LocalFileBackend {
         .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
 
-    async fn rename(&self, old_path: &str, new_path: &str) -> Result<(), FileError> {
+    async fn rename(
+        &self,
+        old_path: &str,
+        new_path: &str,
+        overwrite: bool,
+    ) -> Result<(), FileError> {
         let old = old_path.to_string();
         let new = new_path.to_string();
-        tokio::task::spawn_blocking(move || {
-            std::fs::rename(&old, &new).map_err(|e| map_io_error(e, &old))
-        })
-        .await
-        .map_err(|e| FileError::OperationFailed(e.to_string()))?
+        tokio::task::spawn_blocking(move || rename_sync(&old, &new, overwrite))
+            .await
+            .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
 
     async fn stat(&self, path: &str) -> Result<FileEntry, FileError> {
@@ -194,14 +237,36 @@ impl FileBackend for LocalFileBackend {
             .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
 
-    async fn mkdir(&self, path: &str) -> Result<(), FileError> {
+    async fn mkdir(&self, path: &str, recursive: bool) -> Result<(), FileError> {
         let path = path.to_string();
         tokio::task::spawn_blocking(move || {
-            std::fs::create_dir_all(&path).map_err(|e| map_io_error(e, &path))
+            let created = if recursive {
+                std::fs::create_dir_all(&path)
+            } else {
+                std::fs::create_dir(&path)
+            };
+            created.map_err(|e| map_io_error(e, &path))
         })
         .await
         .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
+
+    async fn dir_size(&self, path: &str, cancel: &CancellationToken) -> Result<u64, FileError> {
+        dir_size_blocking(path, cancel).await
+    }
+
+    async fn grep(
+        &self,
+        root: &str,
+        pattern: &str,
+        opts: &GrepOptions,
+    ) -> Result<Vec<GrepMatch>, FileError> {
+        grep_blocking(root, pattern, opts).await
+    }
+
+    fn is_local(&self) -> bool {
+        true
+    }
 }
 
 /// [`FileBrowser`] capability for the local filesystem.
@@ -245,6 +310,20 @@ impl super::browser::FileBrowser for LocalFileBrowser {
         .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
 
+    fn sha256<'a>(
+        &'a self,
+        path: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Result<String, FileError>> + Send + 'a>> {
+        let path = path.to_string();
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || {
+                super::compare::sha256_file(Path::new(&path)).map_err(|e| map_io_error(e, &path))
+            })
+            .await
+            .map_err(|e| FileError::OperationFailed(e.to_string()))?
+        })
+    }
+
     async fn write_file(&self, path: &str, data: &[u8]) -> Result<(), FileError> {
         let path = path.to_string();
         let data = data.to_vec();
@@ -269,14 +348,12 @@ impl super::browser::FileBrowser for LocalFileBrowser {
         .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
 
-    async fn rename(&self, from: &str, to: &str) -> Result<(), FileError> {
+    async fn rename(&self, from: &str, to: &str, overwrite: bool) -> Result<(), FileError> {
         let old = from.to_string();
         let new = to.to_string();
-        tokio::task::spawn_blocking(move || {
-            std::fs::rename(&old, &new).map_err(|e| map_io_error(e, &old))
-        })
-        .await
-        .map_err(|e| FileError::OperationFailed(e.to_string()))?
+        tokio::task::spawn_blocking(move || rename_sync(&old, &new, overwrite))
+            .await
+            .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
 
     async fn stat(&self, path: &str) -> Result<FileEntry, FileError> {
@@ -286,14 +363,36 @@ impl super::browser::FileBrowser for LocalFileBrowser {
             .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
 
-    async fn mkdir(&self, path: &str) -> Result<(), FileError> {
+    async fn mkdir(&self, path: &str, recursive: bool) -> Result<(), FileError> {
         let path = path.to_string();
         tokio::task::spawn_blocking(move || {
-            std::fs::create_dir_all(&path).map_err(|e| map_io_error(e, &path))
+            let created = if recursive {
+                std::fs::create_dir_all(&path)
+            } else {
+                std::fs::create_dir(&path)
+            };
+            created.map_err(|e| map_io_error(e, &path))
         })
         .await
         .map_err(|e| FileError::OperationFailed(e.to_string()))?
     }
+
+    async fn dir_size(&self, path: &str, cancel: &CancellationToken) -> Result<u64, FileError> {
+        dir_size_blocking(path, cancel).await
+    }
+
+    async fn grep(
+        &self,
+        root: &str,
+        pattern: &str,
+        opts: &GrepOptions,
+    ) -> Result<Vec<GrepMatch>, FileError> {
+        grep_blocking(root, pattern, opts).await
+    }
+
+    fn clone_box(&self) -> Box<dyn super::browser::FileBrowser> {
+        Box::new(Self)
+    }
 }
 
 #[cfg(test)]
@@ -397,6 +496,19 @@ mod tests {
         assert!(dir_entry.is_directory);
     }
 
+    #[tokio::test]
+    async fn backend_mkdir_creates_parents_only_when_recursive() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("a/b");
+        let nested = nested.to_str().unwrap();
+        let backend = LocalFileBackend::new();
+
+        assert!(backend.mkdir(nested, false).await.is_err());
+        assert!(!dir.path().join("a").exists());
+        backend.mkdir(nested, true).await.unwrap();
+        assert!(dir.path().join("a/b").is_dir());
+    }
+
     #[tokio::test]
     async fn backend_list_nonexistent_dir() {
         let backend = LocalFileBackend::new();
@@ -460,7 +572,7 @@ mod tests {
 
         let backend = LocalFileBackend::new();
         backend
-            .rename(old.to_str().unwrap(), new.to_str().unwrap())
+            .rename(old.to_str().unwrap(), new.to_str().unwrap(), false)
             .await
             .unwrap();
         assert!(!old.exists());
@@ -468,6 +580,42 @@ mod tests {
         assert_eq!(std::fs::read_to_string(&new).unwrap(), "content");
     }
 
+    #[tokio::test]
+    async fn backend_rename_refuses_existing_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.txt");
+        let new = dir.path().join("new.txt");
+        std::fs::write(&old, "old").unwrap();
+        std::fs::write(&new, "new").unwrap();
+
+        let backend = LocalFileBackend::new();
+        let err = backend
+            .rename(old.to_str().unwrap(), new.to_str().unwrap(), false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, FileError::DestinationExists(_)));
+        // Neither file was touched.
+        assert_eq!(std::fs::read_to_string(&old).unwrap(), "old");
+        assert_eq!(std::fs::read_to_string(&new).unwrap(), "new");
+    }
+
+    #[tokio::test]
+    async fn backend_rename_overwrites_when_requested() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.txt");
+        let new = dir.path().join("new.txt");
+        std::fs::write(&old, "old").unwrap();
+        std::fs::write(&new, "new").unwrap();
+
+        let backend = LocalFileBackend::new();
+        backend
+            .rename(old.to_str().unwrap(), new.to_str().unwrap(), true)
+            .await
+            .unwrap();
+        assert!(!old.exists());
+        assert_eq!(std::fs::read_to_string(&new).unwrap(), "old");
+    }
+
     #[tokio::test]
     async fn backend_stat_file() {
         let dir = tempfile::tempdir().unwrap();
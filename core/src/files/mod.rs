@@ -1,12 +1,20 @@
+pub mod archive;
 pub mod browser;
+pub mod compare;
+pub mod dir_size;
+pub mod grep;
 pub mod local;
 pub mod utils;
 
+pub use archive::{ArchiveDownloader, ArchiveFormat};
 pub use browser::FileBrowser;
+pub use compare::{FileComparison, FileSummary};
+pub use grep::{GrepMatch, GrepOptions};
 pub use local::{LocalFileBackend, LocalFileBrowser};
 
 use crate::errors::FileError;
 use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
 
 /// A file or directory entry returned by file browsing operations.
 ///
@@ -25,6 +33,19 @@ pub struct FileEntry {
     pub permissions: Option<String>,
 }
 
+/// Line-ending normalization applied to file content before it is written.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NewlineMode {
+    /// Write the content unchanged.
+    #[default]
+    Preserve,
+    /// Convert all line endings to `\n`.
+    Lf,
+    /// Convert all line endings to `\r\n`.
+    Crlf,
+}
+
 /// Trait for connection-scoped file operations.
 ///
 /// Each connection type (local, docker, ssh) provides its own implementation.
@@ -45,11 +66,69 @@ pub trait FileBackend: Send + Sync {
     async fn delete(&self, path: &str, is_directory: bool) -> Result<(), FileError>;
 
     /// Rename/move a file or directory.
-    async fn rename(&self, old_path: &str, new_path: &str) -> Result<(), FileError>;
+    ///
+    /// Fails with [`FileError::DestinationExists`] if `new_path` already
+    /// exists, unless `overwrite` is set.
+    async fn rename(
+        &self,
+        old_path: &str,
+        new_path: &str,
+        overwrite: bool,
+    ) -> Result<(), FileError>;
 
     /// Get metadata for a single file or directory.
     async fn stat(&self, path: &str) -> Result<FileEntry, FileError>;
 
-    /// Create a directory (and any missing parent directories) at the given path.
-    async fn mkdir(&self, path: &str) -> Result<(), FileError>;
+    /// Create a directory at the given path, along with any missing parent
+    /// directories when `recursive` is set.
+    async fn mkdir(&self, path: &str, recursive: bool) -> Result<(), FileError>;
+
+    /// Total size in bytes of all files below `path` (or of `path` itself
+    /// if it is a file).
+    ///
+    /// The default walks the tree with [`list`](Self::list); backends with a
+    /// cheaper native method override it. Stops early when `cancel` fires or
+    /// after [`dir_size::DIR_SIZE_MAX_ENTRIES`] entries.
+    async fn dir_size(&self, path: &str, cancel: &CancellationToken) -> Result<u64, FileError> {
+        let entry = self.stat(path).await?;
+        if !entry.is_directory {
+            return Ok(entry.size);
+        }
+        dir_size::walk_dir_size(
+            path,
+            cancel,
+            dir_size::DIR_SIZE_MAX_ENTRIES,
+            |p| async move { self.list(&p).await },
+        )
+        .await
+    }
+
+    /// Lines containing the literal string `pattern` in the files below
+    /// the directory `root`.
+    ///
+    /// Binary files are skipped and at most [`GrepOptions::max_results`]
+    /// matches are returned. The default walks the tree with
+    /// [`list`](Self::list) and [`read`](Self::read); backends that can
+    /// search natively override it.
+    async fn grep(
+        &self,
+        root: &str,
+        pattern: &str,
+        opts: &GrepOptions,
+    ) -> Result<Vec<GrepMatch>, FileError> {
+        grep::walk_grep(
+            root,
+            pattern,
+            opts,
+            |p| async move { self.list(&p).await },
+            |p| async move { self.read(&p).await },
+        )
+        .await
+    }
+
+    /// Whether paths refer to the local filesystem, so OS change
+    /// notifications can be used instead of polling.
+    fn is_local(&self) -> bool {
+        false
+    }
 }
@@ -1,3 +1,8 @@
+use std::borrow::Cow;
+
+use super::NewlineMode;
+use crate::config::expand::expand_remote_path;
+
 /// Format a Unix timestamp (seconds since epoch) as ISO 8601.
 pub fn chrono_from_epoch(secs: u64) -> String {
     use std::time::{Duration, UNIX_EPOCH};
@@ -107,10 +112,147 @@ pub fn normalize_platform_path(path: &str) -> String {
     }
 }
 
+/// Normalize line endings in `data` according to `mode`.
+///
+/// `Lf` turns every `\r\n` into `\n`; `Crlf` turns every bare `\n` into
+/// `\r\n` (existing `\r\n` pairs are kept, not doubled). Lone `\r` bytes
+/// are left alone. Borrows `data` when nothing changes.
+pub fn normalize_newlines(data: &[u8], mode: NewlineMode) -> Cow<'_, [u8]> {
+    match mode {
+        NewlineMode::Preserve => Cow::Borrowed(data),
+        NewlineMode::Lf => {
+            if !data.windows(2).any(|w| w == b"\r\n") {
+                return Cow::Borrowed(data);
+            }
+            let mut out = Vec::with_capacity(data.len());
+            for (i, &b) in data.iter().enumerate() {
+                if b == b'\r' && data.get(i + 1) == Some(&b'\n') {
+                    continue;
+                }
+                out.push(b);
+            }
+            Cow::Owned(out)
+        }
+        NewlineMode::Crlf => {
+            let bare_lf = |i: usize| data[i] == b'\n' && (i == 0 || data[i - 1] != b'\r');
+            if !(0..data.len()).any(bare_lf) {
+                return Cow::Borrowed(data);
+            }
+            let mut out = Vec::with_capacity(data.len() + data.len() / 16);
+            for (i, &b) in data.iter().enumerate() {
+                if bare_lf(i) {
+                    out.push(b'\r');
+                }
+                out.push(b);
+            }
+            Cow::Owned(out)
+        }
+    }
+}
+
+/// Directory a remote file browser opens to.
+///
+/// Uses the connection's configured `root` with `~` and `${env:...}`
+/// expanded against `remote_home`, or `remote_home` itself when no root
+/// is configured.
+pub fn resolve_file_browser_root(root: Option<&str>, remote_home: &str) -> String {
+    match root.map(str::trim).filter(|r| !r.is_empty()) {
+        Some(root) => expand_remote_path(root, remote_home),
+        None => remote_home.to_string(),
+    }
+}
+
+/// Quote `s` as a single word for a POSIX shell.
+///
+/// The word is wrapped in single quotes; embedded single quotes are
+/// closed, escaped and reopened (`'\''`).
+pub fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// The directories to create, outermost first, so that `path` exists.
+///
+/// Each entry is `path` cut off after one more component, e.g.
+/// `/srv/app/logs` gives `/srv`, `/srv/app` and `/srv/app/logs`. Empty
+/// components from repeated or trailing slashes are skipped.
+pub fn mkdir_prefixes(path: &str) -> Vec<String> {
+    let mut prefix = if path.starts_with('/') {
+        String::from("/")
+    } else {
+        String::new()
+    };
+    let mut prefixes = Vec::new();
+    for component in path.split('/').filter(|c| !c.is_empty()) {
+        if !prefix.is_empty() && !prefix.ends_with('/') {
+            prefix.push('/');
+        }
+        prefix.push_str(component);
+        prefixes.push(prefix.clone());
+    }
+    prefixes
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn shell_quote_escapes_single_quotes() {
+        assert_eq!(shell_quote("/tmp/it's"), "'/tmp/it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn mkdir_prefixes_of_absolute_path() {
+        assert_eq!(
+            mkdir_prefixes("/srv/app/logs"),
+            vec!["/srv", "/srv/app", "/srv/app/logs"]
+        );
+    }
+
+    #[test]
+    fn mkdir_prefixes_of_relative_path() {
+        assert_eq!(mkdir_prefixes("app/logs"), vec!["app", "app/logs"]);
+    }
+
+    #[test]
+    fn mkdir_prefixes_skip_empty_components() {
+        assert_eq!(mkdir_prefixes("//srv//app/"), vec!["/srv", "/srv/app"]);
+        assert!(mkdir_prefixes("/").is_empty());
+    }
+
+    #[test]
+    fn file_browser_root_defaults_to_remote_home() {
+        assert_eq!(
+            resolve_file_browser_root(None, "/home/alice"),
+            "/home/alice"
+        );
+        assert_eq!(
+            resolve_file_browser_root(Some("  "), "/home/alice"),
+            "/home/alice"
+        );
+    }
+
+    #[test]
+    fn file_browser_root_uses_configured_path() {
+        assert_eq!(
+            resolve_file_browser_root(Some("/var/www"), "/home/alice"),
+            "/var/www"
+        );
+    }
+
+    #[test]
+    fn file_browser_root_expands_against_remote_home() {
+        assert_eq!(
+            resolve_file_browser_root(Some("~/projects"), "/home/alice"),
+            "/home/alice/projects"
+        );
+        assert_eq!(
+            resolve_file_browser_root(Some("${env:HOME}/logs"), "/srv/bob"),
+            "/srv/bob/logs"
+        );
+    }
+
     #[test]
     fn chrono_from_epoch_zero() {
         assert_eq!(chrono_from_epoch(0), "1970-01-01T00:00:00Z");
@@ -249,4 +391,52 @@ mod tests {
             assert_eq!(normalize_platform_path(""), "");
         }
     }
+
+    const MIXED: &[u8] = b"one\r\ntwo\nthree\rfour\r\n\nend";
+
+    #[test]
+    fn normalize_newlines_preserve_keeps_mixed_input() {
+        let out = normalize_newlines(MIXED, NewlineMode::Preserve);
+        assert!(matches!(out, Cow::Borrowed(_)));
+        assert_eq!(&*out, MIXED);
+    }
+
+    #[test]
+    fn normalize_newlines_lf_on_mixed_input() {
+        let out = normalize_newlines(MIXED, NewlineMode::Lf);
+        assert_eq!(&*out, b"one\ntwo\nthree\rfour\n\nend");
+    }
+
+    #[test]
+    fn normalize_newlines_crlf_on_mixed_input() {
+        let out = normalize_newlines(MIXED, NewlineMode::Crlf);
+        assert_eq!(&*out, b"one\r\ntwo\r\nthree\rfour\r\n\r\nend");
+    }
+
+    #[test]
+    fn normalize_newlines_borrows_when_already_normalized() {
+        assert!(matches!(
+            normalize_newlines(b"a\nb\n", NewlineMode::Lf),
+            Cow::Borrowed(_)
+        ));
+        assert!(matches!(
+            normalize_newlines(b"a\r\nb\r\n", NewlineMode::Crlf),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn normalize_newlines_crlf_handles_leading_lf() {
+        assert_eq!(&*normalize_newlines(b"\nx", NewlineMode::Crlf), b"\r\nx");
+    }
+
+    #[test]
+    fn newline_mode_serde() {
+        assert_eq!(
+            serde_json::from_str::<NewlineMode>("\"crlf\"").unwrap(),
+            NewlineMode::Crlf
+        );
+        assert_eq!(serde_json::to_value(NewlineMode::Lf).unwrap(), "lf");
+        assert_eq!(NewlineMode::default(), NewlineMode::Preserve);
+    }
 }
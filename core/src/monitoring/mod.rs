@@ -5,11 +5,12 @@ pub mod provider;
 pub mod types;
 
 pub use parser::{
-    cpu_percent_from_delta, parse_cpu_line, parse_df_output, parse_meminfo_value, parse_stats,
+    check_exit_status, cpu_percent_from_delta, monitoring_command, parse_cpu_line, parse_df_output,
+    parse_meminfo, parse_meminfo_value, parse_net_dev, parse_stats, parse_stats_for,
     MONITORING_COMMAND,
 };
 pub use provider::{MonitoringProvider, MonitoringReceiver, MonitoringSender};
-pub use types::{CpuCounters, SystemStats};
+pub use types::{CpuCounters, MemoryInfo, MetricGroup, SystemStats};
 
 use crate::errors::CoreError;
 
@@ -20,7 +21,8 @@ use crate::errors::CoreError;
 pub trait StatsCollector: Send {
     /// Collect system stats for the given host label.
     ///
-    /// Implementations run [`MONITORING_COMMAND`] and parse the output.
+    /// Implementations run [`MONITORING_COMMAND`] (or [`monitoring_command`]
+    /// for a connection's selected metric groups) and parse the output.
     fn collect(&mut self, host_label: &str) -> Result<SystemStats, CoreError>;
 }
 
@@ -4,15 +4,32 @@
 //! shared between the desktop and agent crates. Both previously had
 //! their own copies of this logic.
 
-use crate::errors::CoreError;
+use crate::errors::{CoreError, MonitoringError};
 
-use super::types::{CpuCounters, SystemStats};
+use super::types::{CpuCounters, MemoryInfo, MetricGroup, SystemStats};
 
 /// The compound command executed on Linux hosts to gather all metrics
 /// in a single round-trip.
 pub const MONITORING_COMMAND: &str =
     "hostname && cat /proc/loadavg && head -1 /proc/stat && cat /proc/meminfo && cat /proc/uptime && df -Pk / && uname -sr";
 
+/// Check the exit status of a finished monitoring command.
+///
+/// Returns `stdout` when the command succeeded, or a [`MonitoringError`]
+/// carrying the command and the tail of `stderr` when it exited non-zero.
+pub fn check_exit_status(
+    command: &str,
+    stdout: String,
+    stderr: &str,
+    exit_code: i32,
+) -> Result<String, MonitoringError> {
+    if exit_code == 0 {
+        Ok(stdout)
+    } else {
+        Err(MonitoringError::new(command, exit_code, stderr))
+    }
+}
+
 /// Compute CPU usage percentage from the delta between two counter snapshots.
 /// Returns a value between 0.0 and 100.0.
 pub fn cpu_percent_from_delta(prev: &CpuCounters, curr: &CpuCounters) -> f64 {
@@ -43,6 +60,33 @@ pub fn parse_cpu_line(line: &str) -> CpuCounters {
     }
 }
 
+/// Build the monitoring command for the given metric groups.
+///
+/// The sections keep the order of [`MONITORING_COMMAND`], which is the
+/// command for [`MetricGroup::DEFAULT`]; sections of groups not in
+/// `groups` are left out. Parse the output with [`parse_stats_for`].
+pub fn monitoring_command(groups: &[MetricGroup]) -> String {
+    let mut parts = vec!["hostname"];
+    if groups.contains(&MetricGroup::Cpu) {
+        parts.extend(["cat /proc/loadavg", "head -1 /proc/stat"]);
+    }
+    if groups.contains(&MetricGroup::Memory) {
+        parts.push("cat /proc/meminfo");
+    }
+    parts.push("cat /proc/uptime");
+    if groups.contains(&MetricGroup::Disk) {
+        parts.push("df -Pk /");
+    }
+    if groups.contains(&MetricGroup::Network) {
+        parts.push("cat /proc/net/dev");
+    }
+    if groups.contains(&MetricGroup::Processes) {
+        parts.push("ls -d /proc/[0-9]* | wc -l");
+    }
+    parts.push("uname -sr");
+    parts.join(" && ")
+}
+
 /// Parse the combined command output into `SystemStats` and raw `CpuCounters`.
 ///
 /// Expected input is the output of [`MONITORING_COMMAND`]:
@@ -54,8 +98,21 @@ pub fn parse_cpu_line(line: &str) -> CpuCounters {
 /// `cpu_usage_percent` in the returned `SystemStats` is set to 0.0; the caller
 /// is responsible for computing the actual value from counter deltas.
 pub fn parse_stats(output: &str) -> Result<(SystemStats, CpuCounters), CoreError> {
+    parse_stats_for(output, &MetricGroup::DEFAULT)
+}
+
+/// Parse the output of [`monitoring_command`] for `groups`.
+///
+/// Fields of groups that were not collected are left at zero.
+pub fn parse_stats_for(
+    output: &str,
+    groups: &[MetricGroup],
+) -> Result<(SystemStats, CpuCounters), CoreError> {
     let lines: Vec<&str> = output.lines().collect();
-    if lines.len() < 6 {
+    let cpu = groups.contains(&MetricGroup::Cpu);
+    // hostname, uptime and uname, plus loadavg and the cpu line
+    let min_lines = if cpu { 6 } else { 3 };
+    if lines.len() < min_lines {
         return Err(CoreError::Other(
             "Unexpected monitoring output format (too few lines)".to_string(),
         ));
@@ -63,90 +120,64 @@ pub fn parse_stats(output: &str) -> Result<(SystemStats, CpuCounters), CoreError
 
     // Line 0: hostname
     let hostname = lines[0].trim().to_string();
-
-    // Line 1: /proc/loadavg — "0.15 0.10 0.05 1/234 5678"
-    let load_parts: Vec<&str> = lines[1].split_whitespace().collect();
-    let load_average = [
-        load_parts
-            .first()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(0.0),
-        load_parts
-            .get(1)
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(0.0),
-        load_parts
-            .get(2)
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(0.0),
-    ];
-
-    // Line 2: aggregate cpu line from /proc/stat
-    let cpu_counters = parse_cpu_line(lines[2]);
-
-    // Lines 3+: /proc/meminfo — find MemTotal and MemAvailable
-    let mut mem_total_kb: u64 = 0;
-    let mut mem_available_kb: u64 = 0;
-    let mut meminfo_end = 3;
-
-    for (i, line) in lines.iter().enumerate().skip(3) {
-        if line.starts_with("MemTotal:") {
-            mem_total_kb = parse_meminfo_value(line);
-        } else if line.starts_with("MemAvailable:") {
-            mem_available_kb = parse_meminfo_value(line);
-        }
-        // /proc/uptime line starts with a digit — signals end of meminfo
-        if !line.contains(':') && line.chars().next().is_some_and(|c| c.is_ascii_digit()) {
-            // Check if this looks like uptime (two floats)
-            let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() == 2 && parts[0].contains('.') && parts[1].contains('.') {
-                meminfo_end = i;
-                break;
-            }
+    let mut next = 1;
+
+    let mut load_average = [0.0; 3];
+    let mut cpu_counters = CpuCounters::default();
+    if cpu {
+        // /proc/loadavg — "0.15 0.10 0.05 1/234 5678"
+        let load_parts: Vec<&str> = lines[next].split_whitespace().collect();
+        for (i, value) in load_average.iter_mut().enumerate() {
+            *value = load_parts
+                .get(i)
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0);
         }
+        // aggregate cpu line from /proc/stat
+        cpu_counters = parse_cpu_line(lines[next + 1]);
+        next += 2;
     }
 
+    // /proc/meminfo, terminated by the /proc/uptime line
+    let uptime_index = lines[next..]
+        .iter()
+        .position(|line| is_uptime_line(line))
+        .map(|i| next + i);
+    let memory = if groups.contains(&MetricGroup::Memory) {
+        // Without an uptime line the meminfo section runs to the end of the output.
+        let meminfo_lines = &lines[next..uptime_index.unwrap_or(lines.len())];
+        parse_meminfo(&meminfo_lines.join("\n"))
+    } else {
+        MemoryInfo::default()
+    };
+
     // uptime line: "12345.67 89012.34"
-    let uptime_line = lines.get(meminfo_end).unwrap_or(&"0 0");
-    let uptime_parts: Vec<&str> = uptime_line.split_whitespace().collect();
-    let uptime_seconds: f64 = uptime_parts
-        .first()
+    let uptime_seconds: f64 = uptime_index
+        .and_then(|i| lines[i].split_whitespace().next())
         .and_then(|s| s.parse().ok())
         .unwrap_or(0.0);
+    let rest = uptime_index.map_or(&[][..], |i| &lines[i + 1..]);
 
-    let memory_used_percent = if mem_total_kb > 0 {
-        let used = mem_total_kb.saturating_sub(mem_available_kb);
-        (used as f64 / mem_total_kb as f64) * 100.0
+    let (disk_total_kb, disk_used_kb, disk_used_percent) = if groups.contains(&MetricGroup::Disk) {
+        parse_df_lines(rest)
     } else {
-        0.0
+        (0, 0, 0.0)
     };
 
-    // df output: find the data line (skip header)
-    let mut disk_total_kb: u64 = 0;
-    let mut disk_used_kb: u64 = 0;
-    let mut disk_used_percent: f64 = 0.0;
+    let (network_rx_bytes, network_tx_bytes) = if groups.contains(&MetricGroup::Network) {
+        parse_net_dev(&rest.join("\n"))
+    } else {
+        (0, 0)
+    };
 
-    for line in lines.iter().skip(meminfo_end + 1) {
-        if line.starts_with("Filesystem") || line.trim().is_empty() {
-            continue;
-        }
-        // uname line — skip it
-        if !line.starts_with('/')
-            && (line.starts_with("Linux")
-                || line.starts_with("Darwin")
-                || line.starts_with("FreeBSD"))
-        {
-            continue;
-        }
-        let parts: Vec<&str> = line.split_whitespace().collect();
-        if parts.len() >= 5 {
-            disk_total_kb = parts[1].parse().unwrap_or(0);
-            disk_used_kb = parts[2].parse().unwrap_or(0);
-            // parts[4] is like "42%"
-            disk_used_percent = parts[4].trim_end_matches('%').parse().unwrap_or(0.0);
-            break;
-        }
-    }
+    // ls | wc -l: the only line that is a bare number
+    let process_count = if groups.contains(&MetricGroup::Processes) {
+        rest.iter()
+            .find_map(|line| line.trim().parse().ok())
+            .unwrap_or(0)
+    } else {
+        0
+    };
 
     // uname -sr: last non-empty line
     let os_info = lines
@@ -164,18 +195,89 @@ pub fn parse_stats(output: &str) -> Result<(SystemStats, CpuCounters), CoreError
         uptime_seconds,
         load_average,
         cpu_usage_percent: 0.0,
-        memory_total_kb: mem_total_kb,
-        memory_available_kb: mem_available_kb,
-        memory_used_percent,
+        memory_total_kb: memory.total_kb,
+        memory_available_kb: memory.available_kb,
+        memory_used_percent: memory.used_percent(),
+        memory_buffers_kb: memory.buffers_kb,
+        memory_cached_kb: memory.cached_kb,
+        swap_total_kb: memory.swap_total_kb,
+        swap_used_kb: memory.swap_used_kb,
         disk_total_kb,
         disk_used_kb,
         disk_used_percent,
         os_info,
+        network_rx_bytes,
+        network_tx_bytes,
+        process_count,
     };
 
     Ok((stats, cpu_counters))
 }
 
+/// Whether `line` looks like `/proc/uptime` output: two decimal numbers.
+fn is_uptime_line(line: &str) -> bool {
+    if line.contains(':') || !line.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+        return false;
+    }
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    parts.len() == 2 && parts[0].contains('.') && parts[1].contains('.')
+}
+
+/// Find the `df -Pk` data line among the lines following the uptime line.
+fn parse_df_lines(lines: &[&str]) -> (u64, u64, f64) {
+    for line in lines {
+        if line.starts_with("Filesystem") || line.trim().is_empty() {
+            continue;
+        }
+        // uname line — skip it
+        if !line.starts_with('/')
+            && (line.starts_with("Linux")
+                || line.starts_with("Darwin")
+                || line.starts_with("FreeBSD"))
+        {
+            continue;
+        }
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() >= 5 {
+            let total = parts[1].parse().unwrap_or(0);
+            let used = parts[2].parse().unwrap_or(0);
+            // parts[4] is like "42%"
+            let percent = parts[4].trim_end_matches('%').parse().unwrap_or(0.0);
+            return (total, used, percent);
+        }
+    }
+    (0, 0, 0.0)
+}
+
+/// Sum received and transmitted bytes over all non-loopback interfaces in
+/// `/proc/net/dev` output.
+///
+/// Interface lines look like `  eth0: 1234 5 0 0 0 0 0 0 5678 ...`, with
+/// receive bytes in the first column and transmit bytes in the ninth.
+/// Other lines (the two headers, `df` or `uname` output) are ignored.
+pub fn parse_net_dev(output: &str) -> (u64, u64) {
+    let mut rx = 0u64;
+    let mut tx = 0u64;
+    for line in output.lines() {
+        let Some((iface, counters)) = line.split_once(':') else {
+            continue;
+        };
+        let iface = iface.trim();
+        if iface.is_empty() || iface.contains(' ') || iface == "lo" {
+            continue;
+        }
+        let fields: Vec<u64> = counters
+            .split_whitespace()
+            .map_while(|s| s.parse().ok())
+            .collect();
+        if fields.len() >= 9 {
+            rx = rx.saturating_add(fields[0]);
+            tx = tx.saturating_add(fields[8]);
+        }
+    }
+    (rx, tx)
+}
+
 /// Extract the numeric kB value from a `/proc/meminfo` line like
 /// `"MemTotal:       16384000 kB"`.
 pub fn parse_meminfo_value(line: &str) -> u64 {
@@ -185,6 +287,44 @@ pub fn parse_meminfo_value(line: &str) -> u64 {
         .unwrap_or(0)
 }
 
+/// Parse the contents of `/proc/meminfo` into a [`MemoryInfo`].
+///
+/// `cached_kb` includes `SReclaimable`, matching the "buff/cache" column of
+/// `free`. When `MemAvailable` is missing (kernels before 3.14), available
+/// memory is estimated as free + buffers + cached instead of reporting the
+/// cache as used.
+pub fn parse_meminfo(meminfo: &str) -> MemoryInfo {
+    let mut mem = MemoryInfo::default();
+    let mut available: Option<u64> = None;
+    let mut page_cache_kb = 0;
+    let mut reclaimable_kb = 0;
+    let mut swap_free_kb = 0;
+
+    for line in meminfo.lines() {
+        let Some((key, _)) = line.split_once(':') else {
+            continue;
+        };
+        match key {
+            "MemTotal" => mem.total_kb = parse_meminfo_value(line),
+            "MemFree" => mem.free_kb = parse_meminfo_value(line),
+            "MemAvailable" => available = Some(parse_meminfo_value(line)),
+            "Buffers" => mem.buffers_kb = parse_meminfo_value(line),
+            "Cached" => page_cache_kb = parse_meminfo_value(line),
+            "SReclaimable" => reclaimable_kb = parse_meminfo_value(line),
+            "SwapTotal" => mem.swap_total_kb = parse_meminfo_value(line),
+            "SwapFree" => swap_free_kb = parse_meminfo_value(line),
+            _ => {}
+        }
+    }
+
+    mem.cached_kb = page_cache_kb + reclaimable_kb;
+    mem.swap_used_kb = mem.swap_total_kb.saturating_sub(swap_free_kb);
+    mem.available_kb = available
+        .unwrap_or(mem.free_kb + mem.buffers_kb + mem.cached_kb)
+        .min(mem.total_kb);
+    mem
+}
+
 /// Parse `df -Pk` output to extract disk total, used, and used percent.
 ///
 /// Returns `(total_kb, used_kb, used_percent)`. Returns `(0, 0, 0.0)` if
@@ -257,6 +397,82 @@ Linux 5.15.0"
         assert_eq!(parse_meminfo_value("Invalid line"), 0);
     }
 
+    const SAMPLE_MEMINFO: &str = "\
+MemTotal:       16314260 kB
+MemFree:         1203440 kB
+MemAvailable:   11532112 kB
+Buffers:          412332 kB
+Cached:          9265884 kB
+SwapCached:        12004 kB
+Active:          8123456 kB
+Inactive:        5234567 kB
+SReclaimable:     654320 kB
+SUnreclaim:       123456 kB
+SwapTotal:       4194300 kB
+SwapFree:        3670012 kB
+Dirty:               448 kB
+HugePages_Total:       0";
+
+    #[test]
+    fn parse_meminfo_breakdown() {
+        let mem = parse_meminfo(SAMPLE_MEMINFO);
+        assert_eq!(mem.total_kb, 16314260);
+        assert_eq!(mem.free_kb, 1203440);
+        assert_eq!(mem.available_kb, 11532112);
+        assert_eq!(mem.buffers_kb, 412332);
+        // Cached + SReclaimable; SwapCached must not be mistaken for Cached.
+        assert_eq!(mem.cached_kb, 9265884 + 654320);
+        assert_eq!(mem.swap_total_kb, 4194300);
+        assert_eq!(mem.swap_used_kb, 4194300 - 3670012);
+    }
+
+    #[test]
+    fn parse_meminfo_without_mem_available_counts_cache_as_available() {
+        let meminfo = "\
+MemTotal:        8000000 kB
+MemFree:         1000000 kB
+Buffers:          500000 kB
+Cached:          2500000 kB";
+        let mem = parse_meminfo(meminfo);
+        assert_eq!(mem.available_kb, 4000000);
+        assert!((mem.used_percent() - 50.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn parse_meminfo_without_swap() {
+        let meminfo = "\
+MemTotal:        8000000 kB
+MemAvailable:    6000000 kB
+SwapTotal:             0 kB
+SwapFree:              0 kB";
+        let mem = parse_meminfo(meminfo);
+        assert_eq!(mem.swap_total_kb, 0);
+        assert_eq!(mem.swap_used_kb, 0);
+    }
+
+    #[test]
+    fn parse_meminfo_empty() {
+        assert_eq!(parse_meminfo(""), MemoryInfo::default());
+    }
+
+    #[test]
+    fn parse_stats_includes_memory_breakdown() {
+        let output = format!(
+            "myhost\n0.15 0.10 0.05 1/234 5678\ncpu  1 0 1 1 0 0 0 0\n{SAMPLE_MEMINFO}\n\
+12345.67 45678.90\n\
+Filesystem     1024-blocks      Used Available Capacity Mounted on\n\
+/dev/sda1        50000000  20000000  28000000      42% /\n\
+Linux 5.15.0"
+        );
+        let (stats, _) = parse_stats(&output).unwrap();
+        assert_eq!(stats.memory_buffers_kb, 412332);
+        assert_eq!(stats.memory_cached_kb, 9920204);
+        assert_eq!(stats.swap_total_kb, 4194300);
+        assert_eq!(stats.swap_used_kb, 524288);
+        assert!((stats.uptime_seconds - 12345.67).abs() < 0.01);
+        assert_eq!(stats.disk_total_kb, 50000000);
+    }
+
     #[test]
     fn parse_stats_too_few_lines() {
         let output = "myhost\n0.15 0.10 0.05\ncpu  0 0 0 0 0 0 0 0";
@@ -378,4 +594,127 @@ Filesystem     1024-blocks      Used Available Capacity Mounted on
         assert_eq!(used, 0);
         assert!((pct - 0.0).abs() < 0.001);
     }
+
+    #[test]
+    fn check_exit_status_passes_stdout_on_success() {
+        let out = check_exit_status(MONITORING_COMMAND, "myhost\n".to_string(), "", 0).unwrap();
+        assert_eq!(out, "myhost\n");
+    }
+
+    #[test]
+    fn check_exit_status_reports_stderr_on_failure() {
+        let err = check_exit_status(
+            MONITORING_COMMAND,
+            "myhost\n".to_string(),
+            "df: /: Permission denied\n",
+            1,
+        )
+        .unwrap_err();
+        assert_eq!(err.command, MONITORING_COMMAND);
+        assert_eq!(err.exit_code, 1);
+        assert_eq!(err.stderr, "df: /: Permission denied");
+        assert!(err
+            .to_string()
+            .ends_with("exited with status 1: df: /: Permission denied"));
+    }
+
+    #[test]
+    fn check_exit_status_keeps_stderr_tail() {
+        let stderr = format!("{}tail", "x".repeat(2000));
+        let err = check_exit_status("uptime", String::new(), &stderr, 127).unwrap_err();
+        assert_eq!(
+            err.stderr.chars().count(),
+            crate::errors::MONITORING_STDERR_TAIL_CHARS
+        );
+        assert!(err.stderr.ends_with("tail"));
+    }
+
+    #[test]
+    fn monitoring_command_for_default_groups_is_full_command() {
+        assert_eq!(
+            monitoring_command(&MetricGroup::DEFAULT),
+            MONITORING_COMMAND
+        );
+    }
+
+    #[test]
+    fn monitoring_command_trimmed_to_selection() {
+        let command = monitoring_command(&[MetricGroup::Memory]);
+        assert_eq!(
+            command,
+            "hostname && cat /proc/meminfo && cat /proc/uptime && uname -sr"
+        );
+
+        let command = monitoring_command(&[MetricGroup::Processes, MetricGroup::Network]);
+        assert!(command.contains("cat /proc/net/dev"));
+        assert!(command.contains("wc -l"));
+        assert!(!command.contains("/proc/stat"));
+        assert!(!command.contains("meminfo"));
+        assert!(!command.contains("df "));
+    }
+
+    #[test]
+    fn parse_stats_for_memory_only() {
+        let output = "\
+myhost
+MemTotal:        8000000 kB
+MemAvailable:    6000000 kB
+12345.67 45678.90
+Linux 6.1.0";
+        let (stats, counters) = parse_stats_for(output, &[MetricGroup::Memory]).unwrap();
+        assert_eq!(stats.hostname, "myhost");
+        assert_eq!(stats.memory_total_kb, 8000000);
+        assert!((stats.memory_used_percent - 25.0).abs() < 0.01);
+        assert!((stats.uptime_seconds - 12345.67).abs() < 0.01);
+        assert_eq!(stats.os_info, "Linux 6.1.0");
+        assert_eq!(stats.load_average, [0.0; 3]);
+        assert_eq!(counters.total(), 0);
+        assert_eq!(stats.disk_total_kb, 0);
+    }
+
+    #[test]
+    fn parse_stats_for_cpu_and_disk_without_memory() {
+        let output = "\
+myhost
+0.50 0.25 0.10 1/99 4321
+cpu  100 0 50 800 50 0 0 0
+500.00 900.00
+Filesystem     1024-blocks      Used Available Capacity Mounted on
+/dev/sda1        100000000  60000000  38000000      60% /
+Linux 6.1.0";
+        let groups = [MetricGroup::Cpu, MetricGroup::Disk];
+        let (stats, counters) = parse_stats_for(output, &groups).unwrap();
+        assert!((stats.load_average[0] - 0.50).abs() < 0.001);
+        assert_eq!(counters.idle, 800);
+        assert_eq!(stats.memory_total_kb, 0);
+        assert!((stats.uptime_seconds - 500.0).abs() < 0.01);
+        assert_eq!(stats.disk_total_kb, 100000000);
+        assert!((stats.disk_used_percent - 60.0).abs() < 0.1);
+        assert_eq!(stats.os_info, "Linux 6.1.0");
+    }
+
+    #[test]
+    fn parse_stats_for_network_and_processes() {
+        let output = "\
+myhost
+500.00 900.00
+Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo:  999999     100    0    0    0     0          0         0   999999     100    0    0    0     0       0          0
+  eth0: 1000 10 0 0 0 0 0 0 2000 20 0 0 0 0 0 0
+ wlan0:500 5 0 0 0 0 0 0 700 7 0 0 0 0 0 0
+     187
+Linux 6.1.0";
+        let groups = [MetricGroup::Network, MetricGroup::Processes];
+        let (stats, _) = parse_stats_for(output, &groups).unwrap();
+        assert_eq!(stats.network_rx_bytes, 1500);
+        assert_eq!(stats.network_tx_bytes, 2700);
+        assert_eq!(stats.process_count, 187);
+        assert_eq!(stats.os_info, "Linux 6.1.0");
+    }
+
+    #[test]
+    fn parse_stats_for_too_few_lines() {
+        assert!(parse_stats_for("myhost\nLinux 6.1.0", &[MetricGroup::Disk]).is_err());
+    }
 }
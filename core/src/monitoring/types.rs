@@ -16,10 +16,111 @@ pub struct SystemStats {
     pub memory_total_kb: u64,
     pub memory_available_kb: u64,
     pub memory_used_percent: f64,
+    /// Kernel buffer memory; reclaimable, counted as available.
+    #[serde(default)]
+    pub memory_buffers_kb: u64,
+    /// Page cache plus reclaimable slab; counted as available.
+    #[serde(default)]
+    pub memory_cached_kb: u64,
+    #[serde(default)]
+    pub swap_total_kb: u64,
+    #[serde(default)]
+    pub swap_used_kb: u64,
     pub disk_total_kb: u64,
     pub disk_used_kb: u64,
     pub disk_used_percent: f64,
     pub os_info: String,
+    /// Bytes received on all non-loopback interfaces since boot.
+    #[serde(default)]
+    pub network_rx_bytes: u64,
+    /// Bytes transmitted on all non-loopback interfaces since boot.
+    #[serde(default)]
+    pub network_tx_bytes: u64,
+    /// Number of running processes.
+    #[serde(default)]
+    pub process_count: u32,
+}
+
+/// A group of metrics the monitoring command can collect.
+///
+/// Hostname, uptime and OS information are always collected; each group
+/// adds its own section to the command. Fields of groups that were not
+/// collected are left at zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MetricGroup {
+    /// Load average and CPU counters.
+    #[serde(rename = "cpu")]
+    Cpu,
+    /// Memory and swap usage.
+    #[serde(rename = "mem")]
+    Memory,
+    /// Root filesystem usage.
+    #[serde(rename = "disk")]
+    Disk,
+    /// Network interface byte counters.
+    #[serde(rename = "net")]
+    Network,
+    /// Process count.
+    #[serde(rename = "processes")]
+    Processes,
+}
+
+impl MetricGroup {
+    /// Groups collected when a connection does not select any.
+    pub const DEFAULT: [MetricGroup; 3] = [Self::Cpu, Self::Memory, Self::Disk];
+
+    /// Parse a group name as used in settings (`cpu`, `mem`, `disk`,
+    /// `net`, `processes`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "cpu" => Some(Self::Cpu),
+            "mem" | "memory" => Some(Self::Memory),
+            "disk" => Some(Self::Disk),
+            "net" | "network" => Some(Self::Network),
+            "processes" | "procs" => Some(Self::Processes),
+            _ => None,
+        }
+    }
+
+    /// Parse a comma-separated list of group names.
+    ///
+    /// Unknown names are skipped. An empty selection falls back to
+    /// [`MetricGroup::DEFAULT`].
+    pub fn parse_list(list: &str) -> Vec<Self> {
+        let mut groups = Vec::new();
+        for group in list.split(',').filter_map(Self::from_name) {
+            if !groups.contains(&group) {
+                groups.push(group);
+            }
+        }
+        if groups.is_empty() {
+            groups.extend(Self::DEFAULT);
+        }
+        groups
+    }
+}
+
+/// Memory figures parsed from `/proc/meminfo`, all in kB.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemoryInfo {
+    pub total_kb: u64,
+    pub free_kb: u64,
+    pub available_kb: u64,
+    pub buffers_kb: u64,
+    pub cached_kb: u64,
+    pub swap_total_kb: u64,
+    pub swap_used_kb: u64,
+}
+
+impl MemoryInfo {
+    /// Percentage of total memory that is not available (0–100).
+    pub fn used_percent(&self) -> f64 {
+        if self.total_kb == 0 {
+            return 0.0;
+        }
+        let used = self.total_kb.saturating_sub(self.available_kb);
+        (used as f64 / self.total_kb as f64) * 100.0
+    }
 }
 
 /// Cumulative CPU time counters parsed from the aggregate `cpu` line in `/proc/stat`.
@@ -108,10 +209,17 @@ mod tests {
             memory_total_kb: 16384000,
             memory_available_kb: 12000000,
             memory_used_percent: 26.7,
+            memory_buffers_kb: 500000,
+            memory_cached_kb: 3000000,
+            swap_total_kb: 2000000,
+            swap_used_kb: 250000,
             disk_total_kb: 50000000,
             disk_used_kb: 20000000,
             disk_used_percent: 40.0,
             os_info: "Linux 5.15.0".to_string(),
+            network_rx_bytes: 0,
+            network_tx_bytes: 0,
+            process_count: 0,
         };
 
         let json = serde_json::to_string(&stats).unwrap();
@@ -122,6 +230,10 @@ mod tests {
         assert!(json.contains("\"memoryTotalKb\""));
         assert!(json.contains("\"memoryAvailableKb\""));
         assert!(json.contains("\"memoryUsedPercent\""));
+        assert!(json.contains("\"memoryBuffersKb\""));
+        assert!(json.contains("\"memoryCachedKb\""));
+        assert!(json.contains("\"swapTotalKb\""));
+        assert!(json.contains("\"swapUsedKb\""));
         assert!(json.contains("\"diskTotalKb\""));
         assert!(json.contains("\"diskUsedKb\""));
         assert!(json.contains("\"diskUsedPercent\""));
@@ -132,6 +244,54 @@ mod tests {
         assert!((deserialized.uptime_seconds - 12345.67).abs() < 0.01);
         assert!((deserialized.cpu_usage_percent - 42.5).abs() < 0.01);
         assert_eq!(deserialized.memory_total_kb, 16384000);
+        assert_eq!(deserialized.swap_used_kb, 250000);
         assert_eq!(deserialized.os_info, "Linux 5.15.0");
     }
+
+    #[test]
+    fn system_stats_without_memory_breakdown_deserializes() {
+        // Stats from agents that predate the breakdown fields.
+        let json = r#"{
+            "hostname": "old",
+            "uptimeSeconds": 1.0,
+            "loadAverage": [0.0, 0.0, 0.0],
+            "cpuUsagePercent": 0.0,
+            "memoryTotalKb": 1000,
+            "memoryAvailableKb": 500,
+            "memoryUsedPercent": 50.0,
+            "diskTotalKb": 0,
+            "diskUsedKb": 0,
+            "diskUsedPercent": 0.0,
+            "osInfo": "Linux"
+        }"#;
+        let stats: SystemStats = serde_json::from_str(json).unwrap();
+        assert_eq!(stats.memory_buffers_kb, 0);
+        assert_eq!(stats.memory_cached_kb, 0);
+        assert_eq!(stats.swap_total_kb, 0);
+        assert_eq!(stats.swap_used_kb, 0);
+    }
+
+    #[test]
+    fn memory_info_used_percent() {
+        let mem = MemoryInfo {
+            total_kb: 8000000,
+            available_kb: 2000000,
+            ..Default::default()
+        };
+        assert!((mem.used_percent() - 75.0).abs() < 0.01);
+        assert!((MemoryInfo::default().used_percent() - 0.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn metric_group_list_parses_names_and_skips_unknown() {
+        assert_eq!(
+            MetricGroup::parse_list("cpu, NET,bogus,cpu"),
+            vec![MetricGroup::Cpu, MetricGroup::Network]
+        );
+        assert_eq!(MetricGroup::parse_list(""), MetricGroup::DEFAULT.to_vec());
+        assert_eq!(
+            serde_json::to_string(&MetricGroup::Memory).unwrap(),
+            "\"mem\""
+        );
+    }
 }
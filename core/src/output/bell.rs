@@ -0,0 +1,155 @@
+//! Terminal bell (BEL, `0x07`) detection in output streams.
+//!
+//! A BEL that terminates an OSC sequence (`ESC ] ... BEL`, e.g. a window
+//! title or the OSC 7 working-directory report) is not a bell and is
+//! ignored. Sequences split across chunks are tracked, and repeated bells
+//! are debounced so a burst is reported once.
+
+use std::time::{Duration, Instant};
+
+/// Default quiet period: bells closer together than this are one burst.
+pub const DEFAULT_BELL_DEBOUNCE: Duration = Duration::from_secs(1);
+
+const BEL: u8 = 0x07;
+const ESC: u8 = 0x1b;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ground,
+    /// After `ESC`.
+    Escape,
+    /// Inside an OSC string (`ESC ]`), until `BEL` or `ESC \`.
+    Osc,
+    /// After `ESC` inside an OSC string.
+    OscEscape,
+}
+
+/// Detects bells in a stream of output chunks. Never modifies the output.
+pub struct BellDetector {
+    state: State,
+    debounce: Duration,
+    last_bell: Option<Instant>,
+}
+
+impl BellDetector {
+    pub fn new(debounce: Duration) -> Self {
+        Self {
+            state: State::Ground,
+            debounce,
+            last_bell: None,
+        }
+    }
+
+    /// Feed one output chunk received at `now`. Returns `true` if it
+    /// contains a bell that starts a new burst, i.e. no other bell was
+    /// seen within the debounce period before it.
+    pub fn feed(&mut self, chunk: &[u8], now: Instant) -> bool {
+        if !self.scan(chunk) {
+            return false;
+        }
+        let new_burst = self
+            .last_bell
+            .is_none_or(|last| now.saturating_duration_since(last) >= self.debounce);
+        self.last_bell = Some(now);
+        new_burst
+    }
+
+    /// Advance the escape-sequence state over `chunk`; returns whether it
+    /// contains a bell outside an OSC string.
+    fn scan(&mut self, chunk: &[u8]) -> bool {
+        let mut bell = false;
+        for &byte in chunk {
+            self.state = match (self.state, byte) {
+                (State::Ground, BEL) => {
+                    bell = true;
+                    State::Ground
+                }
+                (State::Ground, ESC) => State::Escape,
+                (State::Ground, _) => State::Ground,
+                (State::Escape, b']') => State::Osc,
+                (State::Escape, ESC) => State::Escape,
+                (State::Escape, BEL) => {
+                    bell = true;
+                    State::Ground
+                }
+                (State::Escape, _) => State::Ground,
+                (State::Osc, BEL) => State::Ground,
+                (State::Osc, ESC) => State::OscEscape,
+                (State::Osc, _) => State::Osc,
+                // `ESC \` is the string terminator; any other escape aborts
+                // the OSC string and starts a new sequence.
+                (State::OscEscape, b'\\') => State::Ground,
+                (State::OscEscape, b']') => State::Osc,
+                (State::OscEscape, ESC) => State::Escape,
+                (State::OscEscape, _) => State::Ground,
+            };
+        }
+        bell
+    }
+}
+
+impl Default for BellDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_BELL_DEBOUNCE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bel_triggers() {
+        let mut d = BellDetector::default();
+        assert!(d.feed(b"build finished\x07\r\n", Instant::now()));
+    }
+
+    #[test]
+    fn plain_output_does_not_trigger() {
+        let mut d = BellDetector::default();
+        assert!(!d.feed(b"total 0\r\n\x1b[1;32mok\x1b[0m", Instant::now()));
+    }
+
+    #[test]
+    fn burst_is_debounced_to_one() {
+        let mut d = BellDetector::default();
+        let start = Instant::now();
+        let fired: Vec<bool> = (0..5)
+            .map(|i| d.feed(b"\x07\x07", start + Duration::from_millis(100 * i)))
+            .collect();
+        assert_eq!(fired, [true, false, false, false, false]);
+    }
+
+    #[test]
+    fn bell_after_quiet_period_triggers_again() {
+        let mut d = BellDetector::new(Duration::from_millis(500));
+        let start = Instant::now();
+        assert!(d.feed(b"\x07", start));
+        assert!(!d.feed(b"\x07", start + Duration::from_millis(300)));
+        assert!(d.feed(b"\x07", start + Duration::from_millis(900)));
+    }
+
+    #[test]
+    fn osc_terminator_is_not_a_bell() {
+        let mut d = BellDetector::default();
+        let now = Instant::now();
+        assert!(!d.feed(b"\x1b]0;user@host: ~\x07$ ", now));
+        assert!(!d.feed(b"\x1b]7;file://host/tmp\x07", now));
+    }
+
+    #[test]
+    fn osc_split_across_chunks_is_not_a_bell() {
+        let mut d = BellDetector::default();
+        let now = Instant::now();
+        assert!(!d.feed(b"\x1b", now));
+        assert!(!d.feed(b"]2;tit", now));
+        assert!(!d.feed(b"le\x07", now));
+        assert!(d.feed(b"done\x07", now));
+    }
+
+    #[test]
+    fn bell_after_st_terminated_osc_triggers() {
+        let mut d = BellDetector::default();
+        assert!(d.feed(b"\x1b]0;title\x1b\\\x07", Instant::now()));
+    }
+}
@@ -0,0 +1,175 @@
+//! Guard against binary data written to the terminal.
+//!
+//! `cat`-ing a binary file sends arbitrary control bytes to the terminal,
+//! which can switch character sets, reprogram keys or leave the screen
+//! unusable. When enabled with a connection's `binaryOutputGuard` setting,
+//! [`BinaryGuard`] detects chunks that are mostly non-printable and
+//! replaces their control characters and invalid bytes with `U+FFFD`, so
+//! the data is still visible but can no longer drive the terminal. Chunks
+//! that look like text, including ones with colors and other escape
+//! sequences, pass through untouched.
+
+use super::transform::OutputTransform;
+
+/// Share of non-printable characters above which a chunk is treated as
+/// binary.
+pub const DEFAULT_BINARY_RATIO: f64 = 0.3;
+
+/// Chunks shorter than this are never treated as binary: a few control
+/// bytes on their own (a bell, a cursor query) are normal terminal output.
+const MIN_SAMPLE_LEN: usize = 32;
+
+const REPLACEMENT: char = '\u{FFFD}';
+
+/// Whether `c` is a control character that plain terminal output uses:
+/// tab, newline, carriage return, backspace, bell and the escape that
+/// starts color and cursor sequences.
+fn is_text_control(c: char) -> bool {
+    matches!(c, '\t' | '\n' | '\r' | '\x08' | '\x07' | '\x1b')
+}
+
+fn is_non_printable(c: char) -> bool {
+    c.is_control() && !is_text_control(c)
+}
+
+/// Share of `chunk`'s characters that are non-printable, from 0.0 to 1.0.
+///
+/// Invalid UTF-8 bytes and control characters other than those plain text
+/// output uses (see [`is_text_control`]) count as non-printable; each
+/// invalid byte counts as one character. An empty chunk has a ratio of 0.
+pub fn non_printable_ratio(chunk: &[u8]) -> f64 {
+    let mut total = 0usize;
+    let mut non_printable = 0usize;
+    for part in chunk.utf8_chunks() {
+        for c in part.valid().chars() {
+            total += 1;
+            if is_non_printable(c) {
+                non_printable += 1;
+            }
+        }
+        total += part.invalid().len();
+        non_printable += part.invalid().len();
+    }
+    if total == 0 {
+        0.0
+    } else {
+        non_printable as f64 / total as f64
+    }
+}
+
+/// Neutralizes output chunks that look like binary data.
+pub struct BinaryGuard {
+    threshold: f64,
+}
+
+impl BinaryGuard {
+    /// Create a guard treating chunks with a non-printable share of at
+    /// least `threshold` as binary.
+    pub fn new(threshold: f64) -> Self {
+        Self { threshold }
+    }
+
+    /// Whether `chunk` is treated as binary.
+    pub fn is_binary(&self, chunk: &[u8]) -> bool {
+        chunk.len() >= MIN_SAMPLE_LEN && non_printable_ratio(chunk) >= self.threshold
+    }
+}
+
+impl Default for BinaryGuard {
+    fn default() -> Self {
+        Self::new(DEFAULT_BINARY_RATIO)
+    }
+}
+
+impl OutputTransform for BinaryGuard {
+    fn apply(&self, chunk: &[u8]) -> Vec<u8> {
+        if !self.is_binary(chunk) {
+            return chunk.to_vec();
+        }
+        // Line breaks and tabs keep their layout; every other control
+        // character, escape included, is replaced.
+        let mut out = String::with_capacity(chunk.len());
+        for part in chunk.utf8_chunks() {
+            out.extend(part.valid().chars().map(|c| match c {
+                '\t' | '\n' | '\r' => c,
+                c if c.is_control() => REPLACEMENT,
+                c => c,
+            }));
+            out.extend(std::iter::repeat_n(REPLACEMENT, part.invalid().len()));
+        }
+        out.into_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bytes resembling the start of an ELF executable.
+    fn binary_chunk() -> Vec<u8> {
+        let mut data = b"\x7fELF\x02\x01\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00".to_vec();
+        data.extend_from_slice(b"\x02\x00\x3e\x00\x01\x00\x00\x00\x40\x10\x40\x00\x00\x00");
+        data.extend_from_slice(b"\x1b(0\x1b[?1049h\xff\xfe\x0e\x0f\x00\x00");
+        data
+    }
+
+    #[test]
+    fn ratio_of_text_is_zero() {
+        assert_eq!(non_printable_ratio(b"hello world\r\n\ttabbed\n"), 0.0);
+        assert_eq!(
+            non_printable_ratio("\x1b[1;31mgrüße\x1b[0m \u{2713}\x07\x08".as_bytes()),
+            0.0
+        );
+        assert_eq!(non_printable_ratio(b""), 0.0);
+    }
+
+    #[test]
+    fn ratio_counts_controls_and_invalid_bytes() {
+        assert_eq!(non_printable_ratio(b"\x00\x01ab"), 0.5);
+        assert_eq!(non_printable_ratio(b"\xff\xfeab"), 0.5);
+        assert_eq!(non_printable_ratio(b"\x00\x00\x00\x00"), 1.0);
+        assert!(non_printable_ratio(&binary_chunk()) > DEFAULT_BINARY_RATIO);
+    }
+
+    #[test]
+    fn text_passes_through_untouched() {
+        let guard = BinaryGuard::default();
+        let text = "\x1b[?1049h\x1b[32mbuild ok\x1b[0m — 42 files\r\n".repeat(4);
+        assert_eq!(guard.apply(text.as_bytes()), text.as_bytes());
+    }
+
+    #[test]
+    fn short_chunks_are_never_binary() {
+        let guard = BinaryGuard::default();
+        assert!(!guard.is_binary(b"\x00\x01\x02"));
+        assert_eq!(guard.apply(b"\x1b[6n"), b"\x1b[6n".to_vec());
+    }
+
+    #[test]
+    fn binary_chunk_is_neutralized() {
+        let guard = BinaryGuard::default();
+        let data = binary_chunk();
+        assert!(guard.is_binary(&data));
+
+        let out = String::from_utf8(guard.apply(&data)).unwrap();
+        assert!(out.starts_with("\u{FFFD}ELF"));
+        assert!(!out.contains('\x1b'));
+        assert!(!out.chars().any(|c| c.is_control()));
+    }
+
+    #[test]
+    fn binary_chunk_keeps_line_layout() {
+        let guard = BinaryGuard::default();
+        let mut data = binary_chunk();
+        data.extend_from_slice(b"\r\n\tend");
+        let out = String::from_utf8(guard.apply(&data)).unwrap();
+        assert!(out.ends_with("\r\n\tend"));
+    }
+
+    #[test]
+    fn threshold_is_configurable() {
+        let data = format!("{}{}", "a".repeat(30), "\x00".repeat(10));
+        assert!(!BinaryGuard::new(0.5).is_binary(data.as_bytes()));
+        assert!(BinaryGuard::new(0.2).is_binary(data.as_bytes()));
+    }
+}
@@ -0,0 +1,401 @@
+//! Whole-session recording in the asciicast v2 format.
+//!
+//! [`CastRecorder`] writes what a terminal showed — its output, optionally
+//! the input typed into it, and every resize — to a `.cast` file that
+//! `asciinema play` and the asciinema web player can replay. The file is a
+//! JSON header line followed by one event array per line:
+//!
+//! ```text
+//! {"version":2,"width":80,"height":24,"timestamp":1760000000}
+//! [0.25,"o","$ ls\r\n"]
+//! [1.5,"i","exit\r"]
+//! [2.0,"r","120x40"]
+//! ```
+//!
+//! The same file serves input replay: [`parse_cast`] reads it back, and
+//! [`input_recording`](crate::session::input_recording) re-sends its input
+//! events.
+//!
+//! Event data is text, so output or input ending in the middle of a UTF-8
+//! character is held back until the rest of the character arrives.
+
+use std::io::{self, BufRead, Write};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde_json::{json, Value};
+
+use super::transform::utf8_boundary;
+use crate::errors::CoreError;
+
+/// Appends timestamped output, input and resize events to an asciicast v2
+/// stream.
+pub struct CastRecorder<W: Write> {
+    writer: W,
+    started: Instant,
+    record_input: bool,
+    /// Time of the last event written; later events never go before it.
+    last: Duration,
+    /// Trailing bytes of an incomplete UTF-8 character in the last output.
+    pending_output: Vec<u8>,
+    /// Trailing bytes of an incomplete UTF-8 character in the last input.
+    pending_input: Vec<u8>,
+}
+
+impl<W: Write> CastRecorder<W> {
+    /// Start a recording, writing the asciicast header for a terminal of
+    /// the given size. Input events are only written with `record_input`.
+    pub fn new(mut writer: W, cols: u16, rows: u16, record_input: bool) -> io::Result<Self> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let header = json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": timestamp,
+        });
+        writeln!(writer, "{header}")?;
+        writer.flush()?;
+        Ok(Self {
+            writer,
+            started: Instant::now(),
+            record_input,
+            last: Duration::ZERO,
+            pending_output: Vec::new(),
+            pending_input: Vec::new(),
+        })
+    }
+
+    /// Whether input events are recorded.
+    pub fn records_input(&self) -> bool {
+        self.record_input
+    }
+
+    /// Record output shown now.
+    pub fn record_output(&mut self, data: &[u8]) -> io::Result<()> {
+        self.record_output_at(self.started.elapsed(), data)
+    }
+
+    /// Record output shown `at` after the recording started.
+    pub fn record_output_at(&mut self, at: Duration, data: &[u8]) -> io::Result<()> {
+        match take_complete(&mut self.pending_output, data) {
+            Some(complete) => self.write_event(at, "o", &complete),
+            None => Ok(()),
+        }
+    }
+
+    /// Record input written now. Does nothing unless input is recorded.
+    pub fn record_input(&mut self, data: &[u8]) -> io::Result<()> {
+        self.record_input_at(self.started.elapsed(), data)
+    }
+
+    /// Record input written `at` after the recording started. Does nothing
+    /// unless input is recorded.
+    pub fn record_input_at(&mut self, at: Duration, data: &[u8]) -> io::Result<()> {
+        if !self.record_input {
+            return Ok(());
+        }
+        match take_complete(&mut self.pending_input, data) {
+            Some(complete) => self.write_event(at, "i", &complete),
+            None => Ok(()),
+        }
+    }
+
+    /// Record a resize of the terminal to `cols` x `rows` now.
+    pub fn record_resize(&mut self, cols: u16, rows: u16) -> io::Result<()> {
+        self.record_resize_at(self.started.elapsed(), cols, rows)
+    }
+
+    /// Record a resize `at` after the recording started.
+    pub fn record_resize_at(&mut self, at: Duration, cols: u16, rows: u16) -> io::Result<()> {
+        self.write_event(at, "r", &format!("{cols}x{rows}"))
+    }
+
+    /// Write any held-back output and input and flush. Returns the writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        let at = self.started.elapsed();
+        let output = std::mem::take(&mut self.pending_output);
+        let input = std::mem::take(&mut self.pending_input);
+        for (kind, rest) in [("o", output), ("i", input)] {
+            if !rest.is_empty() {
+                self.write_event(at, kind, &String::from_utf8_lossy(&rest))?;
+            }
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    /// Write one event. Each event is flushed so the recording survives a
+    /// crash.
+    fn write_event(&mut self, at: Duration, kind: &str, data: &str) -> io::Result<()> {
+        // Events arrive from several tasks; keep timestamps monotonic even
+        // when one is taken slightly before an event that was written first.
+        let at = at.max(self.last);
+        self.last = at;
+        let event = json!([at.as_secs_f64(), kind, data]);
+        writeln!(self.writer, "{event}")?;
+        self.writer.flush()
+    }
+}
+
+/// Append `data` to `pending` and take the text up to the last complete
+/// UTF-8 character, or `None` if there is none yet.
+fn take_complete(pending: &mut Vec<u8>, data: &[u8]) -> Option<String> {
+    pending.extend_from_slice(data);
+    let boundary = utf8_boundary(pending);
+    if boundary == 0 {
+        return None;
+    }
+    let complete: Vec<u8> = pending.drain(..boundary).collect();
+    Some(String::from_utf8_lossy(&complete).into_owned())
+}
+
+/// The header of an asciicast v2 recording.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CastHeader {
+    pub width: u16,
+    pub height: u16,
+    /// Unix time the recording started, if present.
+    pub timestamp: Option<u64>,
+}
+
+/// The kind of an asciicast event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastEventKind {
+    /// `"o"`: output shown by the terminal.
+    Output,
+    /// `"i"`: input typed into the terminal.
+    Input,
+    /// `"r"`: the terminal was resized; data is `"COLSxROWS"`.
+    Resize,
+}
+
+/// A single recorded event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CastEvent {
+    /// Time since the recording started.
+    pub at: Duration,
+    pub kind: CastEventKind,
+    pub data: String,
+}
+
+/// Parse an asciicast v2 recording. Events of other kinds (such as `"m"`
+/// markers) are skipped.
+///
+/// # Errors
+///
+/// Returns [`CoreError::Config`] when the header is missing, not version 2
+/// or has no terminal size, or when an event line is malformed.
+pub fn parse_cast(reader: impl BufRead) -> Result<(CastHeader, Vec<CastEvent>), CoreError> {
+    let mut lines = reader.lines();
+    let header = lines
+        .next()
+        .transpose()?
+        .ok_or_else(|| CoreError::Config("Recording is empty".to_string()))?;
+    let header: Value = serde_json::from_str(&header)
+        .map_err(|e| CoreError::Config(format!("Invalid recording header: {e}")))?;
+    if header.get("version").and_then(Value::as_u64) != Some(2) {
+        return Err(CoreError::Config(
+            "Unsupported recording format (expected asciicast v2)".to_string(),
+        ));
+    }
+    let size = |key: &str| {
+        header
+            .get(key)
+            .and_then(Value::as_u64)
+            .and_then(|v| u16::try_from(v).ok())
+            .ok_or_else(|| CoreError::Config(format!("Recording header has no valid {key}")))
+    };
+    let header = CastHeader {
+        width: size("width")?,
+        height: size("height")?,
+        timestamp: header.get("timestamp").and_then(Value::as_u64),
+    };
+
+    let mut events = Vec::new();
+    for (index, line) in lines.enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let malformed = || CoreError::Config(format!("Malformed event on line {}", index + 2));
+        let event: Value = serde_json::from_str(&line).map_err(|_| malformed())?;
+        let (Some(time), Some(kind), Some(data)) = (
+            event.get(0).and_then(Value::as_f64),
+            event.get(1).and_then(Value::as_str),
+            event.get(2).and_then(Value::as_str),
+        ) else {
+            return Err(malformed());
+        };
+        let kind = match kind {
+            "o" => CastEventKind::Output,
+            "i" => CastEventKind::Input,
+            "r" => CastEventKind::Resize,
+            _ => continue,
+        };
+        if !time.is_finite() || time < 0.0 {
+            return Err(malformed());
+        }
+        events.push(CastEvent {
+            // Round to whole microseconds to undo float noise from the text form.
+            at: Duration::from_micros((time * 1_000_000.0).round() as u64),
+            kind,
+            data: data.to_string(),
+        });
+    }
+    Ok((header, events))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn parse(buf: &[u8]) -> (CastHeader, Vec<CastEvent>) {
+        parse_cast(buf).expect("recording parses")
+    }
+
+    fn event(at: u64, kind: CastEventKind, data: &str) -> CastEvent {
+        CastEvent {
+            at: ms(at),
+            kind,
+            data: data.to_string(),
+        }
+    }
+
+    #[test]
+    fn timed_chunks_round_trip() {
+        let mut recorder = CastRecorder::new(Vec::new(), 80, 24, true).unwrap();
+        recorder.record_output_at(ms(0), b"$ ").unwrap();
+        recorder.record_input_at(ms(400), b"ls\r").unwrap();
+        recorder
+            .record_output_at(ms(450), b"ls\r\nREADME.md\r\n")
+            .unwrap();
+        recorder.record_resize_at(ms(1200), 120, 40).unwrap();
+        recorder.record_output_at(ms(1250), b"$ ").unwrap();
+        let buf = recorder.finish().unwrap();
+
+        let (header, events) = parse(&buf);
+        assert_eq!((header.width, header.height), (80, 24));
+        assert!(header.timestamp.is_some());
+        assert_eq!(
+            events,
+            vec![
+                event(0, CastEventKind::Output, "$ "),
+                event(400, CastEventKind::Input, "ls\r"),
+                event(450, CastEventKind::Output, "ls\r\nREADME.md\r\n"),
+                event(1200, CastEventKind::Resize, "120x40"),
+                event(1250, CastEventKind::Output, "$ "),
+            ]
+        );
+    }
+
+    #[test]
+    fn timestamps_are_monotonic() {
+        let mut recorder = CastRecorder::new(Vec::new(), 80, 24, true).unwrap();
+        recorder.record_output_at(ms(100), b"a").unwrap();
+        // An input timed just before output that was written first.
+        recorder.record_input_at(ms(90), b"b").unwrap();
+        recorder.record_output_at(ms(300), b"c").unwrap();
+        recorder.record_resize_at(ms(200), 100, 30).unwrap();
+        let (_, events) = parse(&recorder.finish().unwrap());
+
+        let times: Vec<Duration> = events.iter().map(|e| e.at).collect();
+        assert_eq!(times, vec![ms(100), ms(100), ms(300), ms(300)]);
+    }
+
+    #[test]
+    fn live_recording_is_monotonic() {
+        let mut recorder = CastRecorder::new(Vec::new(), 80, 24, false).unwrap();
+        for i in 0..20 {
+            recorder
+                .record_output(format!("line {i}\r\n").as_bytes())
+                .unwrap();
+        }
+        let (_, events) = parse(&recorder.finish().unwrap());
+        assert_eq!(events.len(), 20);
+        assert!(events.windows(2).all(|w| w[0].at <= w[1].at));
+    }
+
+    #[test]
+    fn input_is_skipped_unless_enabled() {
+        let mut recorder = CastRecorder::new(Vec::new(), 80, 24, false).unwrap();
+        assert!(!recorder.records_input());
+        recorder.record_input_at(ms(10), b"secret\r").unwrap();
+        recorder.record_output_at(ms(20), b"ok").unwrap();
+        let (_, events) = parse(&recorder.finish().unwrap());
+        assert_eq!(events, vec![event(20, CastEventKind::Output, "ok")]);
+    }
+
+    #[test]
+    fn split_utf8_character_is_held_back() {
+        let text = "grüße";
+        let bytes = text.as_bytes();
+        let split = text.find('ü').unwrap() + 1;
+
+        let mut recorder = CastRecorder::new(Vec::new(), 80, 24, false).unwrap();
+        recorder.record_output_at(ms(0), &bytes[..split]).unwrap();
+        recorder.record_output_at(ms(5), &bytes[split..]).unwrap();
+        let (_, events) = parse(&recorder.finish().unwrap());
+        assert_eq!(
+            events,
+            vec![
+                event(0, CastEventKind::Output, "gr"),
+                event(5, CastEventKind::Output, "üße"),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_utf8_input_is_held_back() {
+        let euro = "€".as_bytes();
+        let mut recorder = CastRecorder::new(Vec::new(), 80, 24, true).unwrap();
+        recorder.record_input_at(ms(10), &euro[..1]).unwrap();
+        recorder.record_output_at(ms(15), b"x").unwrap();
+        recorder.record_input_at(ms(20), &euro[1..]).unwrap();
+        let (_, events) = parse(&recorder.finish().unwrap());
+        assert_eq!(
+            events,
+            vec![
+                event(15, CastEventKind::Output, "x"),
+                event(20, CastEventKind::Input, "€"),
+            ]
+        );
+    }
+
+    #[test]
+    fn finish_writes_incomplete_tail() {
+        let mut recorder = CastRecorder::new(Vec::new(), 80, 24, false).unwrap();
+        recorder.record_output_at(ms(0), b"end\xc3").unwrap();
+        let (_, events) = parse(&recorder.finish().unwrap());
+        let data: String = events.iter().map(|e| e.data.as_str()).collect();
+        assert_eq!(data, "end\u{FFFD}");
+
+        let mut recorder = CastRecorder::new(Vec::new(), 80, 24, true).unwrap();
+        recorder.record_input_at(ms(0), &[0xE2]).unwrap();
+        let (_, events) = parse(&recorder.finish().unwrap());
+        assert_eq!(events[0].kind, CastEventKind::Input);
+        assert_eq!(events[0].data, "\u{FFFD}");
+    }
+
+    #[test]
+    fn parse_skips_markers_and_rejects_bad_input() {
+        let cast = "{\"version\":2,\"width\":80,\"height\":24}\n\
+                    [0.1,\"m\",\"chapter\"]\n\
+                    [0.2,\"o\",\"x\"]\n";
+        let (header, events) = parse(cast.as_bytes());
+        assert_eq!(header.timestamp, None);
+        assert_eq!(events, vec![event(200, CastEventKind::Output, "x")]);
+
+        assert!(parse_cast("".as_bytes()).is_err());
+        assert!(parse_cast("{\"version\":1,\"width\":80,\"height\":24}\n".as_bytes()).is_err());
+        assert!(parse_cast("{\"version\":2}\n".as_bytes()).is_err());
+        assert!(
+            parse_cast("{\"version\":2,\"width\":80,\"height\":24}\n[\"x\"]\n".as_bytes()).is_err()
+        );
+    }
+}
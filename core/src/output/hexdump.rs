@@ -0,0 +1,243 @@
+//! Hexdump rendering of terminal output.
+//!
+//! Binary protocols on a serial line show up as garbage in a terminal.
+//! With a connection's `displayMode` set to `"hex"`, [`HexDump`] renders
+//! the received bytes in the layout of `hexdump -C` instead: a running
+//! offset, sixteen hex columns and a gutter with the printable characters.
+//!
+//! ```text
+//! 00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0d 0a 00  |Hello, world!...|
+//! ```
+//!
+//! Reads rarely end on a row boundary, so bytes are buffered until a row
+//! of sixteen is complete; [`HexDump::flush`] renders what is left when
+//! the stream ends, and [`HexDump::flush_idle`] once the line goes quiet,
+//! so a short reply does not wait for more bytes.
+
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::transform::OutputTransform;
+use crate::config::DisplayMode;
+use crate::connection::SelectOption;
+
+/// Bytes shown per row.
+pub const ROW_LEN: usize = 16;
+
+/// How long an incomplete row waits for more bytes before it is shown.
+pub const IDLE_FLUSH: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+struct HexDumpState {
+    /// Offset of the first byte in `row`.
+    offset: u64,
+    /// Bytes of the current, incomplete row.
+    row: Vec<u8>,
+    /// When the last byte was added.
+    last_input: Option<Instant>,
+}
+
+/// Renders output chunks as hexdump rows of [`ROW_LEN`] bytes.
+#[derive(Default)]
+pub struct HexDump {
+    state: Mutex<HexDumpState>,
+}
+
+impl HexDump {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render the buffered bytes of an incomplete row, if any.
+    pub fn flush(&self) -> Vec<u8> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        flush_row(&mut state)
+    }
+
+    /// Render the buffered bytes of an incomplete row once no byte has
+    /// been added for `idle`. The next row then starts at the following
+    /// offset, so offsets stay exact.
+    pub fn flush_idle(&self, idle: Duration) -> Vec<u8> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if state.last_input.is_some_and(|t| t.elapsed() < idle) {
+            return Vec::new();
+        }
+        flush_row(&mut state)
+    }
+}
+
+fn flush_row(state: &mut HexDumpState) -> Vec<u8> {
+    let mut out = String::new();
+    if !state.row.is_empty() {
+        let row = std::mem::take(&mut state.row);
+        format_row(&mut out, state.offset, &row);
+        state.offset += row.len() as u64;
+    }
+    out.into_bytes()
+}
+
+impl OutputTransform for HexDump {
+    fn apply(&self, chunk: &[u8]) -> Vec<u8> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if !chunk.is_empty() {
+            state.last_input = Some(Instant::now());
+        }
+        let mut out = String::new();
+        for &byte in chunk {
+            state.row.push(byte);
+            if state.row.len() == ROW_LEN {
+                let row = std::mem::take(&mut state.row);
+                format_row(&mut out, state.offset, &row);
+                state.offset += ROW_LEN as u64;
+            }
+        }
+        out.into_bytes()
+    }
+}
+
+/// Append one row to `out`. A short row is padded so its gutter lines up
+/// with the rows above it.
+fn format_row(out: &mut String, offset: u64, row: &[u8]) {
+    let _ = write!(out, "{offset:08x} ");
+    for i in 0..ROW_LEN {
+        if i == ROW_LEN / 2 {
+            out.push(' ');
+        }
+        match row.get(i) {
+            Some(byte) => {
+                let _ = write!(out, " {byte:02x}");
+            }
+            None => out.push_str("   "),
+        }
+    }
+    out.push_str("  |");
+    out.extend(row.iter().map(|&b| {
+        if b.is_ascii_graphic() || b == b' ' {
+            b as char
+        } else {
+            '.'
+        }
+    }));
+    out.push_str("|\r\n");
+}
+
+/// Options for a `displayMode` select field.
+pub fn display_mode_options() -> Vec<SelectOption> {
+    [("text", "Text"), ("hex", "Hex dump")]
+        .into_iter()
+        .map(|(value, label)| SelectOption {
+            value: value.to_string(),
+            label: label.to_string(),
+        })
+        .collect()
+}
+
+/// Parse a `displayMode` setting; unknown or missing values mean
+/// [`DisplayMode::Text`].
+pub fn parse_display_mode(settings: &serde_json::Value) -> DisplayMode {
+    settings
+        .get("displayMode")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render(chunks: &[&[u8]]) -> String {
+        let dump = HexDump::new();
+        let mut out: Vec<u8> = chunks.iter().flat_map(|c| dump.apply(c)).collect();
+        out.extend(dump.flush());
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn known_buffer_renders_exact_hexdump() {
+        let data = b"Hello, world!\r\n\x00\xff\x7fABC";
+        assert_eq!(
+            render(&[data]),
+            concat!(
+                "00000000  48 65 6c 6c 6f 2c 20 77  6f 72 6c 64 21 0d 0a 00  |Hello, world!...|\r\n",
+                "00000010  ff 7f 41 42 43                                    |..ABC|\r\n",
+            )
+        );
+    }
+
+    #[test]
+    fn rows_are_identical_across_chunk_splits() {
+        let data: Vec<u8> = (0u8..=40).collect();
+        let whole = render(&[&data]);
+        assert_eq!(
+            render(&[&data[..1], &data[1..7], &data[7..33], &data[33..]]),
+            whole
+        );
+        let bytes: Vec<&[u8]> = data.chunks(1).collect();
+        assert_eq!(render(&bytes), whole);
+        assert_eq!(whole.lines().count(), 3);
+        assert!(whole.starts_with("00000000  00 01"));
+        assert!(whole.contains("\r\n00000020  20 21 22 23 24 25 26 27  28 "));
+    }
+
+    #[test]
+    fn incomplete_row_is_held_until_flushed() {
+        let dump = HexDump::new();
+        assert!(dump.apply(b"0123456789").is_empty());
+        let row = String::from_utf8(dump.apply(b"abcdefXY")).unwrap();
+        assert_eq!(
+            row,
+            "00000000  30 31 32 33 34 35 36 37  38 39 61 62 63 64 65 66  |0123456789abcdef|\r\n"
+        );
+        let rest = String::from_utf8(dump.flush()).unwrap();
+        assert!(rest.starts_with("00000010  58 59 "));
+        assert!(dump.flush().is_empty());
+    }
+
+    #[test]
+    fn idle_flush_waits_for_quiet_line() {
+        let dump = HexDump::new();
+        assert!(dump.apply(b"OK\r\n").is_empty());
+        assert!(dump.flush_idle(Duration::from_secs(60)).is_empty());
+
+        let row = String::from_utf8(dump.flush_idle(Duration::ZERO)).unwrap();
+        assert!(row.starts_with("00000000  4f 4b 0d 0a "), "{row}");
+        assert!(dump.flush_idle(Duration::ZERO).is_empty());
+
+        // The next row continues at the following offset.
+        dump.apply(b"x");
+        let next = String::from_utf8(dump.flush()).unwrap();
+        assert!(next.starts_with("00000004  78 "), "{next}");
+    }
+
+    #[test]
+    fn gutter_is_aligned_for_short_rows() {
+        let full = render(&[&[b'a'; ROW_LEN]]);
+        for len in 1..ROW_LEN {
+            let short = render(&[&vec![b'a'; len]]);
+            assert_eq!(short.find('|'), full.find('|'), "row of {len} bytes");
+        }
+    }
+
+    #[test]
+    fn parse_display_mode_values() {
+        let parse =
+            |v: serde_json::Value| parse_display_mode(&serde_json::json!({"displayMode": v}));
+        assert_eq!(parse(serde_json::json!("hex")), DisplayMode::Hex);
+        assert_eq!(parse(serde_json::json!("text")), DisplayMode::Text);
+        assert_eq!(parse(serde_json::json!("binary")), DisplayMode::Text);
+        assert_eq!(
+            parse_display_mode(&serde_json::json!({})),
+            DisplayMode::Text
+        );
+    }
+
+    #[test]
+    fn options_match_enum_values() {
+        for option in display_mode_options() {
+            let parsed: DisplayMode =
+                serde_json::from_value(serde_json::json!(option.value)).unwrap();
+            assert_eq!(serde_json::to_value(parsed).unwrap(), option.value);
+        }
+    }
+}
@@ -1,2 +1,10 @@
+pub mod bell;
+pub mod binary_guard;
+pub mod cast_recorder;
 pub mod coalescer;
+pub mod hexdump;
+pub mod output_log;
 pub mod screen_clear;
+pub mod sudo_prompt;
+pub mod text_extract;
+pub mod transform;
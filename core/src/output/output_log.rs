@@ -0,0 +1,167 @@
+//! Capturing session output to a log file.
+//!
+//! The log receives the same bytes as the terminal. Optionally, lines
+//! redrawn with a bare carriage return (progress bars, spinners) are
+//! collapsed so only their final state ends up in the log, which keeps
+//! captured logs readable.
+
+use std::io::{self, Write};
+
+const CR: u8 = b'\r';
+const LF: u8 = b'\n';
+
+/// Lines longer than this are written out unfinished, so output without
+/// line endings cannot grow the line buffer without bound.
+const MAX_LINE_BYTES: usize = 64 * 1024;
+
+/// Collapses carriage-return-overwritten lines in a stream of chunks.
+///
+/// A bare `\r` discards the text written since the start of the line, so
+/// each line keeps only what was written after its last carriage return.
+/// `\r\n` is a normal line ending and is preserved. A `\r\n` split across
+/// chunks is handled.
+#[derive(Debug, Default)]
+pub struct CarriageReturnCollapser {
+    line: Vec<u8>,
+    pending_cr: bool,
+}
+
+impl CarriageReturnCollapser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Process a chunk, returning the completed lines it produced.
+    pub fn feed(&mut self, chunk: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for &byte in chunk {
+            if std::mem::take(&mut self.pending_cr) {
+                if byte == LF {
+                    out.append(&mut self.line);
+                    out.extend_from_slice(b"\r\n");
+                    continue;
+                }
+                self.line.clear();
+            }
+            match byte {
+                CR => self.pending_cr = true,
+                LF => {
+                    out.append(&mut self.line);
+                    out.push(LF);
+                }
+                _ => {
+                    self.line.push(byte);
+                    if self.line.len() >= MAX_LINE_BYTES {
+                        out.append(&mut self.line);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Return the unfinished last line, if any.
+    pub fn flush(&mut self) -> Vec<u8> {
+        self.pending_cr = false;
+        std::mem::take(&mut self.line)
+    }
+}
+
+/// Writes session output to a log, optionally collapsing `\r`-overwritten
+/// lines.
+pub struct OutputLog<W: Write> {
+    writer: W,
+    collapser: Option<CarriageReturnCollapser>,
+}
+
+impl<W: Write> OutputLog<W> {
+    pub fn new(writer: W, collapse_carriage_returns: bool) -> Self {
+        Self {
+            writer,
+            collapser: collapse_carriage_returns.then(CarriageReturnCollapser::new),
+        }
+    }
+
+    /// Append an output chunk. Each chunk is flushed so the log survives
+    /// a crash.
+    pub fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        match &mut self.collapser {
+            Some(collapser) => self.writer.write_all(&collapser.feed(data))?,
+            None => self.writer.write_all(data)?,
+        }
+        self.writer.flush()
+    }
+
+    /// Write any unfinished line and return the underlying writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if let Some(collapser) = &mut self.collapser {
+            self.writer.write_all(&collapser.flush())?;
+        }
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collapse(chunks: &[&[u8]]) -> Vec<u8> {
+        let mut collapser = CarriageReturnCollapser::new();
+        let mut out: Vec<u8> = chunks.iter().flat_map(|c| collapser.feed(c)).collect();
+        out.extend(collapser.flush());
+        out
+    }
+
+    #[test]
+    fn progress_bar_keeps_final_state() {
+        assert_eq!(
+            collapse(&[b"\rProgress: 10%\rProgress: 100%\n"]),
+            b"Progress: 100%\n"
+        );
+    }
+
+    #[test]
+    fn progress_bar_split_across_chunks() {
+        assert_eq!(
+            collapse(&[b"Progress: 1", b"0%\r", b"Progress: 100%", b"\ndone\n"]),
+            b"Progress: 100%\ndone\n"
+        );
+    }
+
+    #[test]
+    fn crlf_line_endings_are_preserved() {
+        assert_eq!(
+            collapse(&[b"one\r\ntwo\r", b"\nthree"]),
+            b"one\r\ntwo\r\nthree"
+        );
+    }
+
+    #[test]
+    fn trailing_carriage_return_keeps_line() {
+        assert_eq!(collapse(&[b"50%\r"]), b"50%");
+    }
+
+    #[test]
+    fn very_long_lines_are_written_out() {
+        let mut collapser = CarriageReturnCollapser::new();
+        let out = collapser.feed(&vec![b'x'; MAX_LINE_BYTES]);
+        assert_eq!(out.len(), MAX_LINE_BYTES);
+        assert!(collapser.flush().is_empty());
+    }
+
+    #[test]
+    fn output_log_collapses_when_enabled() {
+        let mut log = OutputLog::new(Vec::new(), true);
+        log.write(b"\rProgress: 10%").unwrap();
+        log.write(b"\rProgress: 100%\n$ ").unwrap();
+        assert_eq!(log.finish().unwrap(), b"Progress: 100%\n$ ");
+    }
+
+    #[test]
+    fn output_log_keeps_raw_stream_by_default() {
+        let mut log = OutputLog::new(Vec::new(), false);
+        log.write(b"\rProgress: 10%\rProgress: 100%\n").unwrap();
+        assert_eq!(log.finish().unwrap(), b"\rProgress: 10%\rProgress: 100%\n");
+    }
+}
@@ -0,0 +1,257 @@
+//! Opt-in detection of sudo password prompts in terminal output.
+//!
+//! A connection that sets `sudoAutoFill` has its output watched for a
+//! prompt matching `sudoPromptPattern`. The pattern must match the whole
+//! text from the start of the prompt line (after its last carriage return)
+//! up to the cursor, so the same words quoted inside other output are not
+//! taken for a prompt. On a match the session answers with the login
+//! password and a carriage return, as if typed, at most `sudoAutoFillMax`
+//! times per session, so a rejected password is not sent over and over.
+
+use regex::bytes::Regex;
+
+/// Default prompt pattern, matching e.g. `[sudo] password for alice: `.
+pub const DEFAULT_SUDO_PROMPT_PATTERN: &str = r"\[sudo\] password for [^\s:]+:\s*";
+
+/// Default number of prompts answered per session.
+pub const DEFAULT_SUDO_MAX_ANSWERS: u32 = 1;
+
+/// Longest partial line kept while waiting for a prompt to complete.
+const MAX_LINE_BYTES: usize = 1024;
+
+/// Detects password prompts in a stream of output chunks.
+///
+/// Only the current (unterminated) line is matched, from its last carriage
+/// return to its end, so a prompt split across chunks is still detected, a
+/// prompt is matched only once, and text merely containing the prompt is
+/// ignored.
+pub struct SudoPromptMatcher {
+    regex: Regex,
+    line: Vec<u8>,
+    answered: u32,
+    max_answers: u32,
+}
+
+impl SudoPromptMatcher {
+    pub fn new(pattern: &str, max_answers: u32) -> Result<Self, regex::Error> {
+        Ok(Self {
+            regex: Regex::new(&format!("^(?:{pattern})$"))?,
+            line: Vec::new(),
+            answered: 0,
+            max_answers,
+        })
+    }
+
+    /// Feed one output chunk. Returns `true` if a prompt was detected and
+    /// should be answered; once `max_answers` prompts were answered, further
+    /// prompts return `false`.
+    pub fn feed(&mut self, chunk: &[u8]) -> bool {
+        match chunk.iter().rposition(|&b| b == b'\n') {
+            Some(pos) => {
+                self.line.clear();
+                self.line.extend_from_slice(&chunk[pos + 1..]);
+            }
+            None => self.line.extend_from_slice(chunk),
+        }
+        if self.line.len() > MAX_LINE_BYTES {
+            self.line.drain(..self.line.len() - MAX_LINE_BYTES);
+        }
+
+        // A prompt starts at the beginning of the line; shells may redraw it
+        // after a carriage return, e.g. when leaving bracketed-paste mode.
+        let prompt_start = self
+            .line
+            .iter()
+            .rposition(|&b| b == b'\r')
+            .map_or(0, |pos| pos + 1);
+        let prompt = &self.line[prompt_start..];
+        if prompt.is_empty() || !self.regex.is_match(prompt) {
+            return false;
+        }
+        self.line.clear();
+        if self.answered >= self.max_answers {
+            return false;
+        }
+        self.answered += 1;
+        true
+    }
+
+    /// Number of prompts answered so far.
+    pub fn answered(&self) -> u32 {
+        self.answered
+    }
+}
+
+/// A prompt matcher paired with the password it answers with.
+pub struct SudoAutoFill {
+    matcher: SudoPromptMatcher,
+    password: String,
+}
+
+impl SudoAutoFill {
+    /// Build from connection settings.
+    ///
+    /// Returns `Ok(None)` unless `sudoAutoFill` is `true` and the connection
+    /// authenticates with a non-empty password (the credential resolved for
+    /// the connection at connect time).
+    pub fn from_settings(settings: &serde_json::Value) -> Result<Option<Self>, regex::Error> {
+        if settings.get("sudoAutoFill").and_then(|v| v.as_bool()) != Some(true) {
+            return Ok(None);
+        }
+        if settings.get("authMethod").and_then(|v| v.as_str()) != Some("password") {
+            return Ok(None);
+        }
+        let Some(password) = settings
+            .get("password")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+        else {
+            return Ok(None);
+        };
+
+        let pattern = settings
+            .get("sudoPromptPattern")
+            .and_then(|v| v.as_str())
+            .filter(|p| !p.is_empty())
+            .unwrap_or(DEFAULT_SUDO_PROMPT_PATTERN);
+        let max_answers = settings
+            .get("sudoAutoFillMax")
+            .and_then(|v| v.as_u64())
+            .map(|n| n.min(u64::from(u32::MAX)) as u32)
+            .unwrap_or(DEFAULT_SUDO_MAX_ANSWERS);
+
+        Ok(Some(Self {
+            matcher: SudoPromptMatcher::new(pattern, max_answers)?,
+            password: password.to_string(),
+        }))
+    }
+
+    /// Feed one output chunk. Returns the input to send when it ends in an
+    /// unanswered prompt: the password followed by a carriage return, which
+    /// is what the Enter key sends to a terminal.
+    pub fn answer(&mut self, chunk: &[u8]) -> Option<String> {
+        self.matcher
+            .feed(chunk)
+            .then(|| format!("{}\r", self.password))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(max_answers: u32) -> SudoPromptMatcher {
+        SudoPromptMatcher::new(DEFAULT_SUDO_PROMPT_PATTERN, max_answers).unwrap()
+    }
+
+    #[test]
+    fn detects_default_sudo_prompt() {
+        let mut m = matcher(1);
+        assert!(m.feed(b"$ sudo apt update\r\n[sudo] password for alice: "));
+        assert_eq!(m.answered(), 1);
+    }
+
+    #[test]
+    fn detects_prompt_split_across_chunks() {
+        let mut m = matcher(1);
+        assert!(!m.feed(b"$ sudo ls\r\n[sudo] pass"));
+        assert!(m.feed(b"word for alice: "));
+    }
+
+    #[test]
+    fn ignores_ordinary_output() {
+        let mut m = matcher(1);
+        assert!(!m.feed(b"total 0\r\n"));
+        assert!(!m.feed(b"password for nothing\r\n"));
+        // A prompt that already scrolled past is not answered.
+        assert!(!m.feed(b"[sudo] password for alice: \r\nSorry\r\n"));
+        assert_eq!(m.answered(), 0);
+    }
+
+    #[test]
+    fn prompt_must_start_the_line() {
+        let mut m = matcher(3);
+        assert!(!m.feed(b"$ echo '[sudo] password for alice: "));
+        assert!(!m.feed(b"\r\nnotes: [sudo] password for alice: "));
+        assert!(!m.feed(b"\r\n[sudo] password for alice: extra"));
+        assert!(!m.feed(b"\r\n[sudo] password for two words: "));
+        assert_eq!(m.answered(), 0);
+    }
+
+    #[test]
+    fn prompt_redrawn_after_carriage_return() {
+        let mut m = matcher(1);
+        assert!(m.feed(b"$ sudo ls\r\n\x1b[?2004l\r[sudo] password for alice: "));
+    }
+
+    #[test]
+    fn same_prompt_is_answered_once() {
+        let mut m = matcher(3);
+        assert!(m.feed(b"[sudo] password for alice: "));
+        // Echo-free input produces no output; an empty chunk must not re-match.
+        assert!(!m.feed(b""));
+        assert_eq!(m.answered(), 1);
+    }
+
+    #[test]
+    fn loop_guard_stops_after_max_answers() {
+        let mut m = matcher(2);
+        let reprompt = b"\r\nSorry, try again.\r\n[sudo] password for alice: ";
+        assert!(m.feed(b"[sudo] password for alice: "));
+        assert!(m.feed(reprompt));
+        assert!(!m.feed(reprompt));
+        assert!(!m.feed(reprompt));
+        assert_eq!(m.answered(), 2);
+    }
+
+    #[test]
+    fn custom_pattern() {
+        let mut m = SudoPromptMatcher::new(r"(?i)password:\s*", 1).unwrap();
+        assert!(!m.feed(b"Enter password: "));
+        assert!(m.feed(b"\r\nPassword: "));
+    }
+
+    #[test]
+    fn from_settings_is_opt_in() {
+        let settings = serde_json::json!({"authMethod": "password", "password": "pw"});
+        assert!(SudoAutoFill::from_settings(&settings).unwrap().is_none());
+    }
+
+    #[test]
+    fn from_settings_requires_password_auth() {
+        let settings = serde_json::json!({
+            "sudoAutoFill": true,
+            "authMethod": "key",
+            "password": "key-passphrase"
+        });
+        assert!(SudoAutoFill::from_settings(&settings).unwrap().is_none());
+    }
+
+    #[test]
+    fn from_settings_rejects_invalid_pattern() {
+        let settings = serde_json::json!({
+            "sudoAutoFill": true,
+            "authMethod": "password",
+            "password": "pw",
+            "sudoPromptPattern": "("
+        });
+        assert!(SudoAutoFill::from_settings(&settings).is_err());
+    }
+
+    #[test]
+    fn auto_fill_answers_with_password() {
+        let settings = serde_json::json!({
+            "sudoAutoFill": true,
+            "authMethod": "password",
+            "password": "hunter2",
+            "sudoAutoFillMax": 1
+        });
+        let mut fill = SudoAutoFill::from_settings(&settings).unwrap().unwrap();
+        assert_eq!(fill.answer(b"plain output"), None);
+        assert_eq!(
+            fill.answer(b"\r\n[sudo] password for alice: ").as_deref(),
+            Some("hunter2\r")
+        );
+        assert_eq!(fill.answer(b"\r\n[sudo] password for alice: "), None);
+    }
+}
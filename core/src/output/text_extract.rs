@@ -0,0 +1,323 @@
+//! Plain-text extraction from raw terminal output, e.g. for copying a
+//! selected region of the scrollback.
+//!
+//! Escape sequences (CSI such as SGR colors, OSC such as window titles,
+//! and other `ESC`-introduced sequences) are removed and lines redrawn
+//! with a bare carriage return keep only their final state. The text is
+//! then split into display rows by wrapping each line at the terminal
+//! width the way xterm does, so row ranges match what the terminal shows:
+//! wide (CJK, fullwidth) characters take two columns and move to the next
+//! row rather than straddle the edge, combining marks take none, and tabs
+//! advance to the next stop without wrapping.
+
+use super::output_log::CarriageReturnCollapser;
+
+const BEL: u8 = 0x07;
+const ESC: u8 = 0x1b;
+
+/// Distance between tab stops, xterm's default.
+const TAB_WIDTH: usize = 8;
+
+/// Zero-width code points: combining marks, zero-width spaces and joiners,
+/// bidi controls and variation selectors.
+const ZERO_WIDTH: &[(u32, u32)] = &[
+    (0x0300, 0x036f),
+    (0x0483, 0x0489),
+    (0x0591, 0x05bd),
+    (0x0610, 0x061a),
+    (0x064b, 0x065f),
+    (0x0e31, 0x0e31),
+    (0x0e34, 0x0e3a),
+    (0x0e47, 0x0e4e),
+    (0x1160, 0x11ff),
+    (0x1ab0, 0x1aff),
+    (0x1dc0, 0x1dff),
+    (0x200b, 0x200f),
+    (0x202a, 0x202e),
+    (0x2060, 0x2064),
+    (0x20d0, 0x20ff),
+    (0xfe00, 0xfe0f),
+    (0xfe20, 0xfe2f),
+    (0xfeff, 0xfeff),
+    (0xe0100, 0xe01ef),
+];
+
+/// Double-width code points, following xterm.js' default (Unicode 6)
+/// width table: CJK, Hangul syllables, fullwidth forms and the
+/// supplementary ideographic planes.
+const DOUBLE_WIDTH: &[(u32, u32)] = &[
+    (0x1100, 0x115f),
+    (0x2329, 0x232a),
+    (0x2e80, 0x303e),
+    (0x3040, 0xa4cf),
+    (0xac00, 0xd7a3),
+    (0xf900, 0xfaff),
+    (0xfe10, 0xfe19),
+    (0xfe30, 0xfe6f),
+    (0xff00, 0xff60),
+    (0xffe0, 0xffe6),
+    (0x20000, 0x2fffd),
+    (0x30000, 0x3fffd),
+];
+
+/// Number of terminal columns `c` occupies.
+fn char_width(c: char) -> usize {
+    let in_table = |table: &[(u32, u32)]| {
+        table
+            .iter()
+            .any(|&(lo, hi)| (lo..=hi).contains(&(c as u32)))
+    };
+    if in_table(ZERO_WIDTH) {
+        0
+    } else if in_table(DOUBLE_WIDTH) {
+        2
+    } else {
+        1
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ground,
+    /// After `ESC`.
+    Escape,
+    /// Inside a CSI sequence (`ESC [`), until its final byte.
+    Csi,
+    /// Inside an OSC, DCS, SOS, PM or APC string, until `BEL` or `ESC \`.
+    String,
+    /// After `ESC` inside a string.
+    StringEscape,
+}
+
+/// Remove escape sequences and control characters from `data`, collapse
+/// carriage-return redraws, and decode it as UTF-8 (lossily). Line endings
+/// are normalized to `\n`.
+pub fn strip_ansi(data: &[u8]) -> String {
+    let mut plain = Vec::with_capacity(data.len());
+    let mut state = State::Ground;
+    for &byte in data {
+        state = match state {
+            State::Ground => match byte {
+                ESC => State::Escape,
+                b'\n' | b'\r' | b'\t' => {
+                    plain.push(byte);
+                    State::Ground
+                }
+                0x00..=0x1f | 0x7f => State::Ground,
+                _ => {
+                    plain.push(byte);
+                    State::Ground
+                }
+            },
+            State::Escape => match byte {
+                b'[' => State::Csi,
+                b']' | b'P' | b'X' | b'^' | b'_' => State::String,
+                // Intermediate bytes, e.g. `ESC ( B`.
+                0x20..=0x2f => State::Escape,
+                _ => State::Ground,
+            },
+            State::Csi => match byte {
+                0x40..=0x7e => State::Ground,
+                _ => State::Csi,
+            },
+            State::String => match byte {
+                BEL => State::Ground,
+                ESC => State::StringEscape,
+                _ => State::String,
+            },
+            State::StringEscape => match byte {
+                b'\\' => State::Ground,
+                _ => State::String,
+            },
+        };
+    }
+
+    let mut collapser = CarriageReturnCollapser::new();
+    let mut collapsed = collapser.feed(&plain);
+    collapsed.extend(collapser.flush());
+    String::from_utf8_lossy(&collapsed).replace("\r\n", "\n")
+}
+
+/// One display row of text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Row {
+    text: String,
+    /// Whether this row continues the previous one (a soft wrap).
+    wrapped: bool,
+}
+
+/// Split plain text into display rows of at most `cols` columns.
+///
+/// Like xterm, a row that ends exactly at the last column only wraps once
+/// more text follows, so a line of exactly `cols` columns is one row.
+fn display_rows(text: &str, cols: usize) -> Vec<Row> {
+    let text = text.strip_suffix('\n').unwrap_or(text);
+    let mut rows = Vec::new();
+    for line in text.split('\n') {
+        let mut row = Row {
+            text: String::new(),
+            wrapped: false,
+        };
+        let mut col = 0;
+        for c in line.chars() {
+            if c == '\t' {
+                // Tabs stop at the last column instead of wrapping.
+                let stop = ((col / TAB_WIDTH + 1) * TAB_WIDTH).min(cols - 1);
+                if stop > col {
+                    row.text.extend(std::iter::repeat_n(' ', stop - col));
+                    col = stop;
+                }
+                continue;
+            }
+            let width = char_width(c);
+            if col > 0 && col + width > cols {
+                // A wide character that does not fit leaves the last
+                // column empty and starts the next row.
+                rows.push(std::mem::replace(
+                    &mut row,
+                    Row {
+                        text: String::new(),
+                        wrapped: true,
+                    },
+                ));
+                col = 0;
+            }
+            row.text.push(c);
+            col += width;
+        }
+        rows.push(row);
+    }
+    rows
+}
+
+/// Extract the plain text of display rows `start..end` (0-based, end
+/// exclusive) of raw terminal output rendered `cols` columns wide.
+///
+/// Rows split by a soft wrap are joined without a line break, so a wrapped
+/// line copies as a single line. A `cols` of `0` disables wrapping. Ranges
+/// past the end of the output are clamped.
+pub fn extract_rows(data: &[u8], cols: u16, start: usize, end: usize) -> String {
+    let cols = if cols == 0 { usize::MAX } else { cols as usize };
+    let rows = display_rows(&strip_ansi(data), cols);
+    let end = end.min(rows.len());
+    if start >= end {
+        return String::new();
+    }
+    let mut text = String::new();
+    for (i, row) in rows[start..end].iter().enumerate() {
+        if i > 0 && !row.wrapped {
+            text.push('\n');
+        }
+        text.push_str(&row.text);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_sgr_codes() {
+        assert_eq!(
+            strip_ansi(b"\x1b[1;31mred\x1b[0m and \x1b[38;5;82mgreen\x1b[m\r\n"),
+            "red and green\n"
+        );
+    }
+
+    #[test]
+    fn strips_osc_and_charset_sequences() {
+        assert_eq!(
+            strip_ansi(b"\x1b]0;title\x07\x1b(Bplain\x1b]8;;http://x\x1b\\link\x1b]8;;\x1b\\"),
+            "plainlink"
+        );
+    }
+
+    #[test]
+    fn keeps_final_state_of_carriage_return_redraws() {
+        assert_eq!(strip_ansi(b" 10%\r 50%\r100%\r\ndone"), "100%\ndone");
+    }
+
+    #[test]
+    fn drops_control_characters_but_keeps_tabs() {
+        assert_eq!(strip_ansi(b"a\x07b\tc\x00"), "ab\tc");
+    }
+
+    #[test]
+    fn keeps_utf8_text() {
+        assert_eq!(strip_ansi("\x1b[32mgrün ✓\x1b[0m".as_bytes()), "grün ✓");
+    }
+
+    #[test]
+    fn extracts_clean_row_range() {
+        let data = b"\x1b[32mone\x1b[0m\r\n\x1b[1mtwo\x1b[0m\r\nthree\r\nfour\r\n";
+        assert_eq!(extract_rows(data, 80, 1, 3), "two\nthree");
+        assert_eq!(extract_rows(data, 80, 0, 1), "one");
+        assert_eq!(extract_rows(data, 80, 3, 100), "four");
+    }
+
+    #[test]
+    fn joins_soft_wrapped_rows() {
+        // "abcdefghij" is 10 columns, wrapped at 4: "abcd" "efgh" "ij".
+        let data = b"\x1b[31mabcd\x1b[0mefghij\r\nnext\r\n";
+        assert_eq!(extract_rows(data, 4, 0, 3), "abcdefghij");
+        assert_eq!(extract_rows(data, 4, 1, 4), "efghij\nnext");
+        assert_eq!(extract_rows(data, 4, 2, 3), "ij");
+    }
+
+    #[test]
+    fn wraps_by_characters_not_bytes() {
+        assert_eq!(extract_rows("äöüß\r\n".as_bytes(), 2, 1, 2), "üß");
+    }
+
+    #[test]
+    fn wide_characters_take_two_columns() {
+        // "日本語" is 6 columns: "日本" fills a 5-column row short by one,
+        // since "語" cannot straddle the edge.
+        let data = "ab日本語\r\n".as_bytes();
+        assert_eq!(extract_rows(data, 5, 0, 1), "ab日");
+        assert_eq!(extract_rows(data, 5, 1, 2), "本語");
+        assert_eq!(extract_rows(data, 5, 0, 2), "ab日本語");
+    }
+
+    #[test]
+    fn combining_marks_take_no_column() {
+        // "e\u{301}" renders as one cell.
+        let data = "abce\u{301}de\r\n".as_bytes();
+        assert_eq!(extract_rows(data, 4, 0, 1), "abce\u{301}");
+        assert_eq!(extract_rows(data, 4, 1, 2), "de");
+    }
+
+    #[test]
+    fn tabs_advance_to_stops_without_wrapping() {
+        assert_eq!(extract_rows(b"a\tb\r\n", 80, 0, 1), "a       b");
+        // A tab near the edge stops at the last column.
+        let rows = display_rows("abcdefghij\tkl", 12);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].text, "abcdefghij k");
+        assert_eq!(rows[1].text, "l");
+    }
+
+    #[test]
+    fn full_row_does_not_add_an_empty_row() {
+        let data = b"abcd\r\nef\r\n";
+        assert_eq!(extract_rows(data, 4, 0, 2), "abcd\nef");
+    }
+
+    #[test]
+    fn keeps_empty_lines() {
+        assert_eq!(extract_rows(b"a\r\n\r\nb\r\n", 80, 0, 3), "a\n\nb");
+    }
+
+    #[test]
+    fn empty_or_inverted_range_is_empty() {
+        assert_eq!(extract_rows(b"a\r\nb\r\n", 80, 1, 1), "");
+        assert_eq!(extract_rows(b"a\r\nb\r\n", 80, 2, 1), "");
+        assert_eq!(extract_rows(b"a\r\nb\r\n", 80, 5, 9), "");
+    }
+
+    #[test]
+    fn zero_columns_disables_wrapping() {
+        assert_eq!(extract_rows(b"abcdef", 0, 0, 1), "abcdef");
+    }
+}
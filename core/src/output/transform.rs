@@ -0,0 +1,380 @@
+//! Ordered, per-connection transforms applied to terminal output.
+//!
+//! A connection may list transforms under its `outputTransforms` setting
+//! (e.g. regex replacement or a per-line prefix). They run in order on each
+//! output chunk before it reaches the terminal. The pipeline is empty — and
+//! passes bytes through untouched — unless transforms are configured.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::binary_guard::BinaryGuard;
+
+/// A single output transform.
+pub trait OutputTransform: Send + Sync {
+    /// Transform one chunk of output.
+    ///
+    /// The pipeline only passes chunks that end on a UTF-8 character
+    /// boundary, so implementations never see a split multi-byte character.
+    fn apply(&self, chunk: &[u8]) -> Vec<u8>;
+}
+
+/// Serialized configuration of one transform, as stored in connection settings.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TransformConfig {
+    /// Replace every match of `pattern` with `replacement` (`$1` etc. expand
+    /// capture groups).
+    RegexReplace {
+        pattern: String,
+        replacement: String,
+    },
+    /// Insert `prefix` at the start of every output line.
+    LinePrefix { prefix: String },
+}
+
+/// Errors building a pipeline from configuration.
+#[derive(Debug, thiserror::Error)]
+pub enum TransformError {
+    #[error("Invalid output transform configuration: {0}")]
+    InvalidConfig(String),
+
+    #[error("Invalid regex pattern '{pattern}': {source}")]
+    InvalidPattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// Replaces regex matches in each chunk.
+///
+/// Matching is per chunk, so a match split across two chunks is not replaced.
+pub struct RegexReplace {
+    regex: Regex,
+    replacement: Vec<u8>,
+}
+
+impl RegexReplace {
+    pub fn new(pattern: &str, replacement: &str) -> Result<Self, TransformError> {
+        let regex = Regex::new(pattern).map_err(|source| TransformError::InvalidPattern {
+            pattern: pattern.to_string(),
+            source,
+        })?;
+        Ok(Self {
+            regex,
+            replacement: replacement.as_bytes().to_vec(),
+        })
+    }
+}
+
+impl OutputTransform for RegexReplace {
+    fn apply(&self, chunk: &[u8]) -> Vec<u8> {
+        self.regex
+            .replace_all(chunk, self.replacement.as_slice())
+            .into_owned()
+    }
+}
+
+/// Prefixes every output line, tracking line starts across chunks.
+pub struct LinePrefix {
+    prefix: Vec<u8>,
+    at_line_start: AtomicBool,
+}
+
+impl LinePrefix {
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.as_bytes().to_vec(),
+            at_line_start: AtomicBool::new(true),
+        }
+    }
+}
+
+impl OutputTransform for LinePrefix {
+    fn apply(&self, chunk: &[u8]) -> Vec<u8> {
+        let mut at_line_start = self.at_line_start.load(Ordering::Relaxed);
+        let mut out = Vec::with_capacity(chunk.len() + self.prefix.len());
+        for &byte in chunk {
+            if at_line_start {
+                out.extend_from_slice(&self.prefix);
+            }
+            out.push(byte);
+            at_line_start = byte == b'\n';
+        }
+        self.at_line_start.store(at_line_start, Ordering::Relaxed);
+        out
+    }
+}
+
+/// An ordered list of transforms plus any incomplete UTF-8 sequence held
+/// back from the previous chunk.
+#[derive(Default)]
+pub struct OutputPipeline {
+    transforms: Vec<Box<dyn OutputTransform>>,
+    pending: Vec<u8>,
+}
+
+impl OutputPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a pipeline from transform configurations, in order.
+    pub fn from_configs(configs: &[TransformConfig]) -> Result<Self, TransformError> {
+        let mut pipeline = Self::new();
+        for config in configs {
+            match config {
+                TransformConfig::RegexReplace {
+                    pattern,
+                    replacement,
+                } => pipeline.push(Box::new(RegexReplace::new(pattern, replacement)?)),
+                TransformConfig::LinePrefix { prefix } => {
+                    pipeline.push(Box::new(LinePrefix::new(prefix)))
+                }
+            }
+        }
+        Ok(pipeline)
+    }
+
+    /// Build a pipeline from the `outputTransforms` key of connection settings.
+    ///
+    /// A missing or `null` key yields an empty pipeline. With
+    /// `binaryOutputGuard` set, a [`BinaryGuard`] runs after the configured
+    /// transforms.
+    pub fn from_settings(settings: &serde_json::Value) -> Result<Self, TransformError> {
+        let mut pipeline = match settings.get("outputTransforms") {
+            None | Some(serde_json::Value::Null) => Self::new(),
+            Some(value) => {
+                let configs: Vec<TransformConfig> = serde_json::from_value(value.clone())
+                    .map_err(|e| TransformError::InvalidConfig(e.to_string()))?;
+                Self::from_configs(&configs)?
+            }
+        };
+        let guard = settings
+            .get("binaryOutputGuard")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        if guard {
+            pipeline.push(Box::new(BinaryGuard::default()));
+        }
+        Ok(pipeline)
+    }
+
+    /// Append a transform to the end of the pipeline.
+    pub fn push(&mut self, transform: Box<dyn OutputTransform>) {
+        self.transforms.push(transform);
+    }
+
+    /// Whether the pipeline has no transforms.
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
+    /// Run `chunk` through all transforms.
+    ///
+    /// A trailing incomplete UTF-8 sequence is held back and prepended to
+    /// the next chunk. An empty pipeline returns the chunk unchanged.
+    pub fn process(&mut self, chunk: &[u8]) -> Vec<u8> {
+        if self.is_empty() {
+            return chunk.to_vec();
+        }
+        let mut data = std::mem::take(&mut self.pending);
+        data.extend_from_slice(chunk);
+        let boundary = utf8_boundary(&data);
+        self.pending = data.split_off(boundary);
+        if data.is_empty() {
+            return data;
+        }
+        self.transforms
+            .iter()
+            .fold(data, |acc, transform| transform.apply(&acc))
+    }
+
+    /// Return any bytes still held back, e.g. when the output stream ends.
+    pub fn flush(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.pending)
+    }
+}
+
+/// Length of the prefix of `data` that does not end in an incomplete UTF-8
+/// sequence.
+///
+/// Invalid bytes are not held back; only a lead byte followed by fewer
+/// continuation bytes than it announces is.
+pub(crate) fn utf8_boundary(data: &[u8]) -> usize {
+    let len = data.len();
+    // A UTF-8 character is at most 4 bytes, so only the last 3 can start an
+    // incomplete one.
+    for back in 1..=len.min(3) {
+        let byte = data[len - back];
+        if byte & 0b1100_0000 == 0b1000_0000 {
+            continue; // continuation byte
+        }
+        let expected = match byte {
+            0xC0..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF7 => 4,
+            _ => 1,
+        };
+        return if back < expected { len - back } else { len };
+    }
+    len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pipeline(configs: &[TransformConfig]) -> OutputPipeline {
+        OutputPipeline::from_configs(configs).unwrap()
+    }
+
+    #[test]
+    fn empty_pipeline_is_byte_identical() {
+        let mut p = OutputPipeline::new();
+        let data = b"plain \x1b[31mred\x1b[0m \xe2\x82 \xff\n";
+        assert_eq!(p.process(data), data.to_vec());
+        // Nothing is held back, even for an incomplete trailing sequence.
+        assert_eq!(p.process(b"\xe2\x82"), b"\xe2\x82".to_vec());
+        assert!(p.flush().is_empty());
+    }
+
+    #[test]
+    fn regex_replace_rewrites_matches() {
+        let mut p = pipeline(&[TransformConfig::RegexReplace {
+            pattern: r"\d{2}:\d{2}:\d{2} ".to_string(),
+            replacement: String::new(),
+        }]);
+        assert_eq!(
+            p.process(b"12:00:01 boot\n12:00:02 ready\n"),
+            b"boot\nready\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn regex_replace_expands_capture_groups() {
+        let mut p = pipeline(&[TransformConfig::RegexReplace {
+            pattern: r"(ERROR)".to_string(),
+            replacement: "\x1b[31m$1\x1b[0m".to_string(),
+        }]);
+        assert_eq!(
+            p.process(b"an ERROR here"),
+            b"an \x1b[31mERROR\x1b[0m here".to_vec()
+        );
+    }
+
+    #[test]
+    fn invalid_regex_is_rejected() {
+        let result = OutputPipeline::from_configs(&[TransformConfig::RegexReplace {
+            pattern: "(".to_string(),
+            replacement: String::new(),
+        }]);
+        assert!(matches!(result, Err(TransformError::InvalidPattern { .. })));
+    }
+
+    #[test]
+    fn line_prefix_tracks_line_starts_across_chunks() {
+        let mut p = pipeline(&[TransformConfig::LinePrefix {
+            prefix: "> ".to_string(),
+        }]);
+        assert_eq!(p.process(b"one\ntw"), b"> one\n> tw".to_vec());
+        assert_eq!(p.process(b"o\n"), b"o\n".to_vec());
+        assert_eq!(p.process(b"three"), b"> three".to_vec());
+    }
+
+    #[test]
+    fn transforms_run_in_order() {
+        let mut p = pipeline(&[
+            TransformConfig::RegexReplace {
+                pattern: "a".to_string(),
+                replacement: "b".to_string(),
+            },
+            TransformConfig::RegexReplace {
+                pattern: "b".to_string(),
+                replacement: "c".to_string(),
+            },
+        ]);
+        assert_eq!(p.process(b"ab"), b"cc".to_vec());
+    }
+
+    #[test]
+    fn split_multibyte_character_is_held_back() {
+        let mut p = pipeline(&[TransformConfig::RegexReplace {
+            pattern: "€".to_string(),
+            replacement: "EUR".to_string(),
+        }]);
+        // "€" is E2 82 AC; split it across two chunks.
+        assert_eq!(p.process(b"1 \xe2\x82"), b"1 ".to_vec());
+        assert_eq!(p.process(b"\xac"), b"EUR".to_vec());
+        assert!(p.flush().is_empty());
+    }
+
+    #[test]
+    fn flush_returns_incomplete_tail() {
+        let mut p = pipeline(&[TransformConfig::LinePrefix {
+            prefix: String::new(),
+        }]);
+        assert_eq!(p.process(b"x\xf0\x9f"), b"x".to_vec());
+        assert_eq!(p.flush(), b"\xf0\x9f".to_vec());
+    }
+
+    #[test]
+    fn utf8_boundary_passes_invalid_bytes_through() {
+        assert_eq!(utf8_boundary(b"abc"), 3);
+        assert_eq!(utf8_boundary(b"a\xff"), 2);
+        assert_eq!(utf8_boundary(b"a\x80\x80\x80"), 4);
+        assert_eq!(utf8_boundary("é".as_bytes()), 2);
+        assert_eq!(utf8_boundary(b"a\xc3"), 1);
+        assert_eq!(utf8_boundary(b""), 0);
+    }
+
+    #[test]
+    fn from_settings_parses_configs() {
+        let settings = serde_json::json!({
+            "shell": "bash",
+            "outputTransforms": [
+                {"type": "linePrefix", "prefix": "[dev] "},
+                {"type": "regexReplace", "pattern": "x", "replacement": "y"}
+            ]
+        });
+        let mut p = OutputPipeline::from_settings(&settings).unwrap();
+        assert_eq!(p.process(b"x\n"), b"[dev] y\n".to_vec());
+    }
+
+    #[test]
+    fn from_settings_defaults_to_empty() {
+        let p = OutputPipeline::from_settings(&serde_json::json!({"shell": "bash"})).unwrap();
+        assert!(p.is_empty());
+        let p =
+            OutputPipeline::from_settings(&serde_json::json!({"outputTransforms": null})).unwrap();
+        assert!(p.is_empty());
+    }
+
+    #[test]
+    fn from_settings_adds_binary_guard_when_enabled() {
+        let settings = serde_json::json!({
+            "outputTransforms": [{"type": "linePrefix", "prefix": "> "}],
+            "binaryOutputGuard": true
+        });
+        let mut p = OutputPipeline::from_settings(&settings).unwrap();
+        assert_eq!(p.process(b"ok\n"), b"> ok\n".to_vec());
+        let binary = p.process(&[0u8; 40]);
+        assert!(String::from_utf8(binary).unwrap().contains('\u{FFFD}'));
+
+        let p = OutputPipeline::from_settings(&serde_json::json!({"binaryOutputGuard": false}))
+            .unwrap();
+        assert!(p.is_empty());
+    }
+
+    #[test]
+    fn from_settings_rejects_unknown_type() {
+        let settings = serde_json::json!({"outputTransforms": [{"type": "upperCase"}]});
+        assert!(matches!(
+            OutputPipeline::from_settings(&settings),
+            Err(TransformError::InvalidConfig(_))
+        ));
+    }
+}
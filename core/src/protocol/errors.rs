@@ -62,6 +62,22 @@ pub const MONITORING_ERROR: i64 = -32014;
 /// An error occurred during agent shutdown.
 pub const SHUTDOWN_ERROR: i64 = -32015;
 
+/// The request exceeded the agent's maximum request size and was dropped.
+pub const REQUEST_TOO_LARGE: i64 = -32016;
+
+/// A rename/move target already exists and `overwrite` was not set.
+pub const DESTINATION_EXISTS: i64 = -32017;
+
+/// The request did not complete within the agent's request timeout.
+pub const REQUEST_TIMEOUT: i64 = -32018;
+
+/// The maximum number of concurrent file watches has been reached.
+pub const WATCH_LIMIT_REACHED: i64 = -32019;
+
+/// The method belongs to an optional feature that was not negotiated in
+/// `initialize`.
+pub const FEATURE_NOT_NEGOTIATED: i64 = -32020;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -89,6 +105,11 @@ mod tests {
             FILE_BROWSING_NOT_SUPPORTED,
             MONITORING_ERROR,
             SHUTDOWN_ERROR,
+            REQUEST_TOO_LARGE,
+            DESTINATION_EXISTS,
+            REQUEST_TIMEOUT,
+            WATCH_LIMIT_REACHED,
+            FEATURE_NOT_NEGOTIATED,
         ];
         for code in codes {
             assert!(code < 0, "Error code {code} should be negative");
@@ -131,6 +152,11 @@ mod tests {
             FILE_BROWSING_NOT_SUPPORTED,
             MONITORING_ERROR,
             SHUTDOWN_ERROR,
+            REQUEST_TOO_LARGE,
+            DESTINATION_EXISTS,
+            REQUEST_TIMEOUT,
+            WATCH_LIMIT_REACHED,
+            FEATURE_NOT_NEGOTIATED,
         ];
         for code in app_codes {
             assert!(
@@ -5,13 +5,83 @@
 //! (`docker_shell.rs`) and agent (`docker/backend.rs`) crates into shared,
 //! testable pure functions with no I/O, no process spawning, and no async.
 
-use crate::config::DockerConfig;
+use std::collections::HashMap;
+
+use crate::config::{DockerConfig, DockerContainerMode};
 use crate::errors::SessionError;
 
+/// Label carrying the termiHub session ID. Present on every container
+/// termiHub creates, so it doubles as the marker for finding them.
+pub const LABEL_SESSION_ID: &str = "com.termihub.session-id";
+
+/// Label carrying the ID of the saved connection the session came from.
+pub const LABEL_CONNECTION_ID: &str = "com.termihub.connection-id";
+
+/// Label carrying the creation time in seconds since the Unix epoch.
+pub const LABEL_CREATED: &str = "com.termihub.created";
+
+/// Linux capability names accepted by `dropCapabilities`/`addCapabilities`,
+/// without the `CAP_` prefix. `ALL` stands for every capability.
+pub const LINUX_CAPABILITIES: &[&str] = &[
+    "ALL",
+    "AUDIT_CONTROL",
+    "AUDIT_READ",
+    "AUDIT_WRITE",
+    "BLOCK_SUSPEND",
+    "BPF",
+    "CHECKPOINT_RESTORE",
+    "CHOWN",
+    "DAC_OVERRIDE",
+    "DAC_READ_SEARCH",
+    "FOWNER",
+    "FSETID",
+    "IPC_LOCK",
+    "IPC_OWNER",
+    "KILL",
+    "LEASE",
+    "LINUX_IMMUTABLE",
+    "MAC_ADMIN",
+    "MAC_OVERRIDE",
+    "MKNOD",
+    "NET_ADMIN",
+    "NET_BIND_SERVICE",
+    "NET_BROADCAST",
+    "NET_RAW",
+    "PERFMON",
+    "SETFCAP",
+    "SETGID",
+    "SETPCAP",
+    "SETUID",
+    "SYSLOG",
+    "SYS_ADMIN",
+    "SYS_BOOT",
+    "SYS_CHROOT",
+    "SYS_MODULE",
+    "SYS_NICE",
+    "SYS_PACCT",
+    "SYS_PTRACE",
+    "SYS_RAWIO",
+    "SYS_RESOURCE",
+    "SYS_TIME",
+    "SYS_TTY_CONFIG",
+    "WAKE_ALARM",
+];
+
+/// Normalize a capability name: trimmed, upper-case, without `CAP_` prefix.
+pub fn normalize_capability(name: &str) -> String {
+    let upper = name.trim().to_ascii_uppercase();
+    match upper.strip_prefix("CAP_") {
+        Some(rest) => rest.to_string(),
+        None => upper,
+    }
+}
+
 /// Build the shared portion of `docker run` arguments from a [`DockerConfig`].
 ///
 /// Returns environment variables (`-e KEY=VALUE`), volume mounts
-/// (`-v HOST:CONTAINER[:ro]`), working directory (`-w DIR`), the image
+/// (`-v HOST:CONTAINER[:ro]`), capability flags (`--privileged`,
+/// `--cap-drop`, `--cap-add`), resource limits (`--memory`, `--cpus`,
+/// `--cpu-shares`), working directory (`-w DIR`), the image
 /// name, and an optional shell command — but **not** consumer-specific
 /// flags like `-it`, `--rm`, `-d`, `--init`, or `--name`.
 ///
@@ -38,6 +108,28 @@ pub fn build_docker_run_args(config: &DockerConfig) -> Vec<String> {
         args.push(mount);
     }
 
+    // Capabilities
+    if config.privileged {
+        args.push("--privileged".to_string());
+    }
+    for cap in &config.drop_capabilities {
+        args.push(format!("--cap-drop={cap}"));
+    }
+    for cap in &config.add_capabilities {
+        args.push(format!("--cap-add={cap}"));
+    }
+
+    // Resource limits
+    if config.memory_limit_mb > 0 {
+        args.push(format!("--memory={}m", config.memory_limit_mb));
+    }
+    if config.cpus > 0.0 {
+        args.push(format!("--cpus={}", config.cpus));
+    }
+    if config.cpu_shares > 0 {
+        args.push(format!("--cpu-shares={}", config.cpu_shares));
+    }
+
     // Working directory
     if let Some(ref workdir) = config.working_directory {
         if !workdir.is_empty() {
@@ -59,6 +151,60 @@ pub fn build_docker_run_args(config: &DockerConfig) -> Vec<String> {
     args
 }
 
+/// Labels applied to a container created for a session.
+///
+/// The session ID falls back to `container_name` when the caller did not
+/// supply one, so the session label is always set. The connection label
+/// is only set when the session was opened from a saved connection.
+pub fn container_labels(
+    config: &DockerConfig,
+    container_name: &str,
+    created_unix_secs: u64,
+) -> HashMap<String, String> {
+    let mut labels = HashMap::from([
+        (
+            LABEL_SESSION_ID.to_string(),
+            config
+                .session_id
+                .clone()
+                .unwrap_or_else(|| container_name.to_string()),
+        ),
+        (LABEL_CREATED.to_string(), created_unix_secs.to_string()),
+    ]);
+    if let Some(ref connection_id) = config.connection_id {
+        labels.insert(LABEL_CONNECTION_ID.to_string(), connection_id.clone());
+    }
+    labels
+}
+
+/// Name of the container kept for a saved connection across sessions.
+///
+/// A connection that creates its own container and has `removeOnExit`
+/// turned off keeps using one container, named `termihub-conn-<id>` after
+/// the connection. Returns `None` for other connections, which get a new
+/// container per session. Characters Docker does not allow in names are
+/// replaced with `-`.
+pub fn persistent_container_name(config: &DockerConfig) -> Option<String> {
+    if config.container_mode != DockerContainerMode::Create || config.remove_on_exit {
+        return None;
+    }
+    let connection_id = config
+        .connection_id
+        .as_deref()
+        .filter(|id| !id.is_empty())?;
+    let id: String = connection_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    Some(format!("termihub-conn-{id}"))
+}
+
 /// Build `docker exec` arguments for attaching to an existing container.
 ///
 /// Returns `["exec", "-it", container, shell]`.
@@ -73,18 +219,31 @@ pub fn build_docker_exec_args(container: &str, shell: &str) -> Vec<String> {
 
 /// Validate a [`DockerConfig`] before session creation.
 ///
-/// Checks that the image is non-empty, all environment variable keys are
-/// non-empty, and all volume mount paths (host and container) are non-empty.
+/// Checks that the image is non-empty (or, in attach mode, that a
+/// container is named), all environment variable keys are
+/// non-empty, all volume mount paths (host and container) are non-empty,
+/// all added or dropped capabilities are known Linux capabilities, and
+/// no resource limit is negative.
 ///
 /// # Errors
 ///
 /// Returns [`SessionError::InvalidConfig`] with a descriptive message if
 /// validation fails.
 pub fn validate_docker_config(config: &DockerConfig) -> Result<(), SessionError> {
-    if config.image.is_empty() {
-        return Err(SessionError::InvalidConfig(
-            "Docker image must not be empty".to_string(),
-        ));
+    match config.container_mode {
+        DockerContainerMode::Create if config.image.is_empty() => {
+            return Err(SessionError::InvalidConfig(
+                "Docker image must not be empty".to_string(),
+            ));
+        }
+        DockerContainerMode::Attach
+            if config.container_name.as_deref().unwrap_or("").is_empty() =>
+        {
+            return Err(SessionError::InvalidConfig(
+                "Container to attach to must not be empty".to_string(),
+            ));
+        }
+        _ => {}
     }
 
     for env_var in &config.env_vars {
@@ -108,6 +267,43 @@ pub fn validate_docker_config(config: &DockerConfig) -> Result<(), SessionError>
         }
     }
 
+    for cap in config
+        .drop_capabilities
+        .iter()
+        .chain(&config.add_capabilities)
+    {
+        if !LINUX_CAPABILITIES.contains(&normalize_capability(cap).as_str()) {
+            return Err(SessionError::InvalidConfig(format!(
+                "Unknown Linux capability: {cap}"
+            )));
+        }
+    }
+
+    if config.memory_limit_mb < 0 {
+        return Err(SessionError::InvalidConfig(format!(
+            "Memory limit must be a positive number of MiB: {}",
+            config.memory_limit_mb
+        )));
+    }
+    if config.memory_limit_mb.checked_mul(1024 * 1024).is_none() {
+        return Err(SessionError::InvalidConfig(format!(
+            "Memory limit is too large: {} MiB",
+            config.memory_limit_mb
+        )));
+    }
+    if !config.cpus.is_finite() || config.cpus < 0.0 {
+        return Err(SessionError::InvalidConfig(format!(
+            "CPU limit must be a positive number: {}",
+            config.cpus
+        )));
+    }
+    if config.cpu_shares < 0 {
+        return Err(SessionError::InvalidConfig(format!(
+            "CPU shares must be a positive number: {}",
+            config.cpu_shares
+        )));
+    }
+
     Ok(())
 }
 
@@ -207,6 +403,43 @@ mod tests {
         assert_eq!(args, vec!["-e", "FOO=bar", "-e", "BAZ=qux", "alpine"]);
     }
 
+    #[test]
+    fn build_docker_run_args_with_capabilities() {
+        let config = DockerConfig {
+            image: "alpine".to_string(),
+            drop_capabilities: vec!["ALL".to_string()],
+            add_capabilities: vec!["NET_BIND_SERVICE".to_string()],
+            privileged: true,
+            ..Default::default()
+        };
+        let args = build_docker_run_args(&config);
+        assert_eq!(
+            args,
+            vec![
+                "--privileged",
+                "--cap-drop=ALL",
+                "--cap-add=NET_BIND_SERVICE",
+                "alpine"
+            ]
+        );
+    }
+
+    #[test]
+    fn build_docker_run_args_with_resource_limits() {
+        let config = DockerConfig {
+            image: "alpine".to_string(),
+            memory_limit_mb: 512,
+            cpus: 1.5,
+            cpu_shares: 256,
+            ..Default::default()
+        };
+        let args = build_docker_run_args(&config);
+        assert_eq!(
+            args,
+            vec!["--memory=512m", "--cpus=1.5", "--cpu-shares=256", "alpine"]
+        );
+    }
+
     #[test]
     fn build_docker_run_args_with_volumes_rw() {
         let config = DockerConfig {
@@ -368,6 +601,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_docker_config_attach_needs_container_not_image() {
+        let config = DockerConfig {
+            container_mode: DockerContainerMode::Attach,
+            container_name: Some("web-1".to_string()),
+            ..Default::default()
+        };
+        assert!(validate_docker_config(&config).is_ok());
+
+        let config = DockerConfig {
+            container_mode: DockerContainerMode::Attach,
+            image: "alpine".to_string(),
+            ..Default::default()
+        };
+        let err = validate_docker_config(&config).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Container to attach to must not be empty"),
+            "unexpected error: {err}"
+        );
+    }
+
     #[test]
     fn validate_docker_config_empty_env_var_key() {
         let config = DockerConfig {
@@ -424,6 +679,110 @@ mod tests {
         );
     }
 
+    #[test]
+    fn validate_docker_config_known_capabilities() {
+        let config = DockerConfig {
+            image: "alpine".to_string(),
+            drop_capabilities: vec!["ALL".to_string()],
+            add_capabilities: vec!["cap_net_raw".to_string(), "CHOWN".to_string()],
+            ..Default::default()
+        };
+        assert!(validate_docker_config(&config).is_ok());
+    }
+
+    #[test]
+    fn validate_docker_config_unknown_capability() {
+        let config = DockerConfig {
+            image: "alpine".to_string(),
+            add_capabilities: vec!["NET_EVERYTHING".to_string()],
+            ..Default::default()
+        };
+        let err = validate_docker_config(&config).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("Unknown Linux capability: NET_EVERYTHING"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn validate_docker_config_zero_limits_mean_unlimited() {
+        let config = DockerConfig {
+            image: "alpine".to_string(),
+            memory_limit_mb: 0,
+            cpus: 0.0,
+            cpu_shares: 0,
+            ..Default::default()
+        };
+        assert!(validate_docker_config(&config).is_ok());
+    }
+
+    #[test]
+    fn validate_docker_config_rejects_negative_limits() {
+        let cases = [
+            (
+                DockerConfig {
+                    memory_limit_mb: -1,
+                    ..Default::default()
+                },
+                "Memory limit must be a positive number of MiB: -1",
+            ),
+            (
+                DockerConfig {
+                    cpus: -0.5,
+                    ..Default::default()
+                },
+                "CPU limit must be a positive number: -0.5",
+            ),
+            (
+                DockerConfig {
+                    cpus: f64::NAN,
+                    ..Default::default()
+                },
+                "CPU limit must be a positive number",
+            ),
+            (
+                DockerConfig {
+                    cpu_shares: -2,
+                    ..Default::default()
+                },
+                "CPU shares must be a positive number: -2",
+            ),
+        ];
+        for (config, expected) in cases {
+            let config = DockerConfig {
+                image: "alpine".to_string(),
+                ..config
+            };
+            let err = validate_docker_config(&config).unwrap_err();
+            assert!(
+                err.to_string().contains(expected),
+                "unexpected error: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn validate_docker_config_rejects_overflowing_memory_limit() {
+        let config = DockerConfig {
+            image: "alpine".to_string(),
+            memory_limit_mb: i64::MAX / 1024,
+            ..Default::default()
+        };
+        let err = validate_docker_config(&config).unwrap_err();
+        assert!(
+            err.to_string().contains("Memory limit is too large"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn normalize_capability_strips_prefix_and_case() {
+        assert_eq!(normalize_capability(" cap_sys_admin "), "SYS_ADMIN");
+        assert_eq!(normalize_capability("net_raw"), "NET_RAW");
+        assert_eq!(normalize_capability("ALL"), "ALL");
+    }
+
     // -----------------------------------------------------------------------
     // DockerContainer
     // -----------------------------------------------------------------------
@@ -454,4 +813,83 @@ mod tests {
         assert_eq!(commands.len(), 1);
         assert_eq!(commands[0], vec!["stop", "-t", "5", "test-ctr"]);
     }
+
+    // -----------------------------------------------------------------------
+    // container_labels
+    // -----------------------------------------------------------------------
+
+    #[test]
+    fn container_labels_include_session_connection_and_created() {
+        let config = DockerConfig {
+            image: "alpine".to_string(),
+            session_id: Some("sess-1".to_string()),
+            connection_id: Some("conn-1".to_string()),
+            ..Default::default()
+        };
+        let labels = container_labels(&config, "termihub-1-2", 1_700_000_000);
+        assert_eq!(labels[LABEL_SESSION_ID], "sess-1");
+        assert_eq!(labels[LABEL_CONNECTION_ID], "conn-1");
+        assert_eq!(labels[LABEL_CREATED], "1700000000");
+    }
+
+    #[test]
+    fn container_labels_fall_back_to_container_name() {
+        let config = DockerConfig {
+            image: "alpine".to_string(),
+            ..Default::default()
+        };
+        let labels = container_labels(&config, "termihub-1-2", 0);
+        assert_eq!(labels[LABEL_SESSION_ID], "termihub-1-2");
+        assert!(!labels.contains_key(LABEL_CONNECTION_ID));
+    }
+
+    // -----------------------------------------------------------------------
+    // persistent_container_name
+    // -----------------------------------------------------------------------
+
+    fn kept_container_config(connection_id: Option<&str>) -> DockerConfig {
+        DockerConfig {
+            image: "alpine".to_string(),
+            remove_on_exit: false,
+            connection_id: connection_id.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn persistent_container_name_derives_from_connection_id() {
+        let config = kept_container_config(Some("3f2b8c1e-77aa-4c1d-9e0f-1a2b3c4d5e6f"));
+        assert_eq!(
+            persistent_container_name(&config).as_deref(),
+            Some("termihub-conn-3f2b8c1e-77aa-4c1d-9e0f-1a2b3c4d5e6f")
+        );
+    }
+
+    #[test]
+    fn persistent_container_name_replaces_invalid_characters() {
+        let config = kept_container_config(Some("team/dev box:1"));
+        assert_eq!(
+            persistent_container_name(&config).as_deref(),
+            Some("termihub-conn-team-dev-box-1")
+        );
+    }
+
+    #[test]
+    fn persistent_container_name_requires_kept_own_container() {
+        assert!(persistent_container_name(&kept_container_config(None)).is_none());
+        assert!(persistent_container_name(&kept_container_config(Some(""))).is_none());
+
+        let removed = DockerConfig {
+            remove_on_exit: true,
+            ..kept_container_config(Some("conn-1"))
+        };
+        assert!(persistent_container_name(&removed).is_none());
+
+        let attached = DockerConfig {
+            container_mode: DockerContainerMode::Attach,
+            container_name: Some("web-1".to_string()),
+            ..kept_container_config(Some("conn-1"))
+        };
+        assert!(persistent_container_name(&attached).is_none());
+    }
 }
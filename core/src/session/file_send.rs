@@ -0,0 +1,411 @@
+//! Sending a file's contents as session input.
+//!
+//! Used to upload files or flash firmware over a console (serial, telnet)
+//! whose receiving end reads from its terminal. [`ascii_send`] streams the
+//! raw bytes, optionally pausing after each line. [`xmodem_send`] runs the
+//! XMODEM protocol: 128-byte blocks, acknowledged one by one, with the
+//! arithmetic checksum or CRC-16 variant chosen by the receiver's
+//! handshake (`NAK` or `C`).
+
+use std::io;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::errors::SessionError;
+
+/// How a file is sent to a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileSendProtocol {
+    /// The raw bytes, as if typed.
+    Ascii,
+    /// The XMODEM send protocol.
+    Xmodem,
+}
+
+/// Bytes written at once by [`ascii_send`] without a line delay.
+pub const ASCII_CHUNK_SIZE: usize = 4096;
+
+/// Payload bytes per XMODEM block.
+pub const XMODEM_BLOCK_SIZE: usize = 128;
+
+const SOH: u8 = 0x01;
+const EOT: u8 = 0x04;
+const ACK: u8 = 0x06;
+const NAK: u8 = 0x15;
+const CAN: u8 = 0x18;
+/// Pads the last block.
+const SUB: u8 = 0x1a;
+/// Sent by a receiver that wants CRC-16 instead of the checksum.
+const CRC_REQUEST: u8 = b'C';
+
+/// How long to wait for each receiver response.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Response timeouts to wait for the receiver to start, as receivers
+/// repeat their handshake every 10 seconds for about a minute.
+const HANDSHAKE_ATTEMPTS: u32 = 6;
+/// Attempts to send a block (or `EOT`) before giving up.
+const MAX_RETRIES: u32 = 10;
+/// Unexpected bytes (e.g. a prompt echoed by the receiver) skipped while
+/// waiting for one response before it counts as a timeout.
+const MAX_NOISE_BYTES: usize = 1024;
+
+/// The session channel an XMODEM transfer runs over.
+pub trait XmodemLink {
+    /// Write `data` to the session.
+    fn write(&mut self, data: &[u8]) -> io::Result<()>;
+    /// Next byte of session output, or `None` if none arrives within
+    /// `timeout`.
+    fn read_byte(&mut self, timeout: Duration) -> io::Result<Option<u8>>;
+}
+
+/// Block check appended to each XMODEM block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XmodemChecksum {
+    /// 8-bit sum of the payload, requested with `NAK`.
+    Sum,
+    /// CRC-16/XMODEM of the payload, requested with `C`.
+    Crc16,
+}
+
+/// Stream `data` through `write`.
+///
+/// Without a `line_delay`, data is written in [`ASCII_CHUNK_SIZE`] pieces;
+/// with one, line by line with `sleep(line_delay)` after each line.
+pub fn ascii_send(
+    mut write: impl FnMut(&[u8]) -> io::Result<()>,
+    data: &[u8],
+    line_delay: Duration,
+    mut sleep: impl FnMut(Duration),
+) -> io::Result<u64> {
+    if line_delay.is_zero() {
+        for chunk in data.chunks(ASCII_CHUNK_SIZE) {
+            write(chunk)?;
+        }
+    } else {
+        for line in data.split_inclusive(|&b| b == b'\n') {
+            write(line)?;
+            sleep(line_delay);
+        }
+    }
+    Ok(data.len() as u64)
+}
+
+/// CRC-16/XMODEM (polynomial `0x1021`, initial value 0) of `data`.
+pub fn crc16_xmodem(data: &[u8]) -> u16 {
+    data.iter().fold(0u16, |mut crc, &byte| {
+        crc ^= u16::from(byte) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+        crc
+    })
+}
+
+/// Frame `data` (at most [`XMODEM_BLOCK_SIZE`] bytes) as XMODEM block
+/// `block`: `SOH`, the block number and its complement, the payload padded
+/// with `SUB`, then the block check.
+pub fn xmodem_packet(block: u8, data: &[u8], checksum: XmodemChecksum) -> Vec<u8> {
+    let mut payload = [SUB; XMODEM_BLOCK_SIZE];
+    payload[..data.len()].copy_from_slice(data);
+
+    let mut packet = Vec::with_capacity(XMODEM_BLOCK_SIZE + 5);
+    packet.extend_from_slice(&[SOH, block, !block]);
+    packet.extend_from_slice(&payload);
+    match checksum {
+        XmodemChecksum::Sum => {
+            packet.push(payload.iter().fold(0u8, |sum, &b| sum.wrapping_add(b)));
+        }
+        XmodemChecksum::Crc16 => {
+            packet.extend_from_slice(&crc16_xmodem(&payload).to_be_bytes());
+        }
+    }
+    packet
+}
+
+/// Send `data` with XMODEM over `link`. Returns the number of file bytes
+/// sent; the receiver gets them padded to a whole block.
+///
+/// Waits for the receiver's handshake, ignoring other output, then sends
+/// each block until it is acknowledged, and finally `EOT`. Fails if the
+/// receiver never starts, a block is not acknowledged after
+/// [`MAX_RETRIES`] attempts, or the receiver cancels with `CAN CAN`.
+pub fn xmodem_send(link: &mut dyn XmodemLink, data: &[u8]) -> Result<u64, SessionError> {
+    let checksum = wait_for_handshake(link)?;
+    for (index, block) in data.chunks(XMODEM_BLOCK_SIZE).enumerate() {
+        // Block numbers start at 1 and wrap around after 255.
+        let number = (index + 1) as u8;
+        let packet = xmodem_packet(number, block, checksum);
+        send_until_acked(link, &packet, &format!("block {}", index + 1))?;
+    }
+    send_until_acked(link, &[EOT], "end of transmission")?;
+    Ok(data.len() as u64)
+}
+
+/// A receiver response, as seen by [`wait_for_reply`].
+enum Reply {
+    Byte(u8),
+    Timeout,
+    Cancelled,
+}
+
+/// Wait for one of the `expected` bytes, skipping other output.
+fn wait_for_reply(link: &mut dyn XmodemLink, expected: &[u8]) -> io::Result<Reply> {
+    let mut noise = 0;
+    loop {
+        match link.read_byte(RESPONSE_TIMEOUT)? {
+            None => return Ok(Reply::Timeout),
+            Some(CAN) => {
+                // A single CAN may be line noise; two in a row cancel.
+                if link.read_byte(RESPONSE_TIMEOUT)? == Some(CAN) {
+                    return Ok(Reply::Cancelled);
+                }
+            }
+            Some(byte) if expected.contains(&byte) => return Ok(Reply::Byte(byte)),
+            Some(_) => {
+                noise += 1;
+                if noise > MAX_NOISE_BYTES {
+                    return Ok(Reply::Timeout);
+                }
+            }
+        }
+    }
+}
+
+fn cancelled_error() -> SessionError {
+    SessionError::Io(io::Error::new(
+        io::ErrorKind::ConnectionAborted,
+        "XMODEM transfer cancelled by the receiver",
+    ))
+}
+
+/// Wait for the receiver to request the first block.
+fn wait_for_handshake(link: &mut dyn XmodemLink) -> Result<XmodemChecksum, SessionError> {
+    for _ in 0..HANDSHAKE_ATTEMPTS {
+        match wait_for_reply(link, &[NAK, CRC_REQUEST])? {
+            Reply::Byte(NAK) => return Ok(XmodemChecksum::Sum),
+            Reply::Byte(_) => return Ok(XmodemChecksum::Crc16),
+            Reply::Cancelled => return Err(cancelled_error()),
+            Reply::Timeout => {}
+        }
+    }
+    Err(SessionError::Timeout(
+        "XMODEM receiver did not start the transfer".to_string(),
+    ))
+}
+
+/// Write `packet` until the receiver acknowledges it.
+fn send_until_acked(
+    link: &mut dyn XmodemLink,
+    packet: &[u8],
+    what: &str,
+) -> Result<(), SessionError> {
+    for _ in 0..MAX_RETRIES {
+        link.write(packet)?;
+        match wait_for_reply(link, &[ACK, NAK])? {
+            Reply::Byte(ACK) => return Ok(()),
+            Reply::Cancelled => return Err(cancelled_error()),
+            Reply::Byte(_) | Reply::Timeout => {}
+        }
+    }
+    Err(SessionError::Timeout(format!(
+        "XMODEM {what} not acknowledged after {MAX_RETRIES} attempts"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Link replaying scripted receiver output; `None` is a timeout.
+    #[derive(Default)]
+    struct MockReceiver {
+        output: VecDeque<Option<u8>>,
+        written: Vec<Vec<u8>>,
+    }
+
+    impl MockReceiver {
+        fn new(output: &[u8]) -> Self {
+            Self {
+                output: output.iter().map(|&b| Some(b)).collect(),
+                written: Vec::new(),
+            }
+        }
+    }
+
+    impl XmodemLink for MockReceiver {
+        fn write(&mut self, data: &[u8]) -> io::Result<()> {
+            self.written.push(data.to_vec());
+            Ok(())
+        }
+
+        fn read_byte(&mut self, _timeout: Duration) -> io::Result<Option<u8>> {
+            Ok(self.output.pop_front().flatten())
+        }
+    }
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 % 256) as u8).collect()
+    }
+
+    #[test]
+    fn protocol_deserializes_lowercase() {
+        let protocol: FileSendProtocol = serde_json::from_str("\"xmodem\"").unwrap();
+        assert_eq!(protocol, FileSendProtocol::Xmodem);
+        let protocol: FileSendProtocol = serde_json::from_str("\"ascii\"").unwrap();
+        assert_eq!(protocol, FileSendProtocol::Ascii);
+    }
+
+    #[test]
+    fn ascii_send_streams_all_bytes_in_chunks() {
+        let data = sample(ASCII_CHUNK_SIZE * 2 + 10);
+        let mut written = Vec::new();
+        let mut sizes = Vec::new();
+        let sent = ascii_send(
+            |chunk| {
+                sizes.push(chunk.len());
+                written.extend_from_slice(chunk);
+                Ok(())
+            },
+            &data,
+            Duration::ZERO,
+            |_| panic!("no delay configured"),
+        )
+        .unwrap();
+
+        assert_eq!(sent, data.len() as u64);
+        assert_eq!(written, data);
+        assert_eq!(sizes, vec![ASCII_CHUNK_SIZE, ASCII_CHUNK_SIZE, 10]);
+    }
+
+    #[test]
+    fn ascii_send_paces_line_by_line() {
+        let mut lines = Vec::new();
+        let mut sleeps = Vec::new();
+        ascii_send(
+            |line| {
+                lines.push(line.to_vec());
+                Ok(())
+            },
+            b"setenv a 1\r\nsetenv b 2\nboot",
+            Duration::from_millis(50),
+            |delay| sleeps.push(delay),
+        )
+        .unwrap();
+
+        assert_eq!(
+            lines,
+            vec![
+                b"setenv a 1\r\n".to_vec(),
+                b"setenv b 2\n".to_vec(),
+                b"boot".to_vec()
+            ]
+        );
+        assert_eq!(sleeps, vec![Duration::from_millis(50); 3]);
+    }
+
+    #[test]
+    fn ascii_send_stops_on_write_error() {
+        let mut calls = 0;
+        let result = ascii_send(
+            |_| {
+                calls += 1;
+                Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+            },
+            b"a\nb\n",
+            Duration::from_millis(1),
+            |_| {},
+        );
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn crc16_known_value() {
+        assert_eq!(crc16_xmodem(b"123456789"), 0x31c3);
+        assert_eq!(crc16_xmodem(&[]), 0);
+    }
+
+    #[test]
+    fn checksum_packet_framing() {
+        let packet = xmodem_packet(1, b"hi", XmodemChecksum::Sum);
+        assert_eq!(packet.len(), 3 + XMODEM_BLOCK_SIZE + 1);
+        assert_eq!(&packet[..5], &[SOH, 1, 0xfe, b'h', b'i']);
+        assert!(packet[5..3 + XMODEM_BLOCK_SIZE].iter().all(|&b| b == SUB));
+        let sum = (u32::from(b'h') + u32::from(b'i') + 126 * u32::from(SUB)) % 256;
+        assert_eq!(packet[3 + XMODEM_BLOCK_SIZE], sum as u8);
+    }
+
+    #[test]
+    fn crc_packet_framing() {
+        let data = sample(XMODEM_BLOCK_SIZE);
+        let packet = xmodem_packet(0, &data, XmodemChecksum::Crc16);
+        assert_eq!(packet.len(), 3 + XMODEM_BLOCK_SIZE + 2);
+        // Block 0 follows block 255 and complements to 0xff.
+        assert_eq!(&packet[..3], &[SOH, 0, 0xff]);
+        assert_eq!(&packet[3..3 + XMODEM_BLOCK_SIZE], data.as_slice());
+        assert_eq!(
+            &packet[3 + XMODEM_BLOCK_SIZE..],
+            &crc16_xmodem(&data).to_be_bytes()
+        );
+    }
+
+    #[test]
+    fn sends_blocks_after_captured_crc_handshake() {
+        // `rx` (lrzsz) prints a banner, then requests CRC mode.
+        let mut receiver = MockReceiver::new(b"rx: ready to receive fw.bin\r\nCCC");
+        receiver.output.extend([Some(ACK), Some(ACK), Some(ACK)]);
+        let data = sample(200);
+
+        let sent = xmodem_send(&mut receiver, &data).unwrap();
+
+        assert_eq!(sent, 200);
+        assert_eq!(
+            receiver.written,
+            vec![
+                xmodem_packet(1, &data[..128], XmodemChecksum::Crc16),
+                xmodem_packet(2, &data[128..], XmodemChecksum::Crc16),
+                vec![EOT],
+            ]
+        );
+    }
+
+    #[test]
+    fn nak_handshake_uses_checksum_and_resends_on_nak() {
+        let mut receiver = MockReceiver::new(&[NAK, NAK, ACK, ACK]);
+        xmodem_send(&mut receiver, b"firmware").unwrap();
+
+        let block = xmodem_packet(1, b"firmware", XmodemChecksum::Sum);
+        assert_eq!(receiver.written, vec![block.clone(), block, vec![EOT]]);
+    }
+
+    #[test]
+    fn receiver_cancel_aborts() {
+        let mut receiver = MockReceiver::new(&[CRC_REQUEST, CAN, CAN]);
+        let err = xmodem_send(&mut receiver, b"data").unwrap_err();
+        assert!(err.to_string().contains("cancelled by the receiver"));
+        assert_eq!(receiver.written.len(), 1);
+    }
+
+    #[test]
+    fn silent_receiver_times_out() {
+        let mut receiver = MockReceiver::default();
+        let err = xmodem_send(&mut receiver, b"data").unwrap_err();
+        assert!(matches!(err, SessionError::Timeout(_)));
+        assert!(receiver.written.is_empty());
+    }
+
+    #[test]
+    fn unacknowledged_block_gives_up_after_retries() {
+        let mut receiver = MockReceiver::new(&[NAK]);
+        let err = xmodem_send(&mut receiver, b"data").unwrap_err();
+        assert!(err.to_string().contains("block 1 not acknowledged"));
+        assert_eq!(receiver.written.len(), MAX_RETRIES as usize);
+    }
+}
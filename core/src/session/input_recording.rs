@@ -0,0 +1,159 @@
+//! Replay of recorded terminal input.
+//!
+//! Input is recorded by [`CastRecorder`](crate::output::cast_recorder::CastRecorder)
+//! with input events enabled, so a terminal issue can be reproduced later
+//! by re-sending the same keystrokes with the same timing. Replay reads
+//! the `"i"` events of such an asciicast v2 file with
+//! [`parse_cast`] and ignores its output and resize events.
+
+use std::io::BufRead;
+use std::time::Duration;
+
+use crate::errors::CoreError;
+use crate::output::cast_recorder::{parse_cast, CastEventKind};
+
+/// A single recorded write.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputEvent {
+    /// Time since the recording started.
+    pub at: Duration,
+    /// The bytes that were written.
+    pub data: Vec<u8>,
+}
+
+/// Parse the input events of an asciicast v2 recording.
+///
+/// # Errors
+///
+/// Returns [`CoreError::Config`] when the recording is not a valid
+/// asciicast v2 file (see [`parse_cast`]).
+pub fn parse_input_recording(reader: impl BufRead) -> Result<Vec<InputEvent>, CoreError> {
+    let (_, events) = parse_cast(reader)?;
+    Ok(events
+        .into_iter()
+        .filter(|event| event.kind == CastEventKind::Input)
+        .map(|event| InputEvent {
+            at: event.at,
+            data: event.data.into_bytes(),
+        })
+        .collect())
+}
+
+/// Compute the delay to wait before sending each event on replay.
+///
+/// The first event is sent immediately; every later event waits for the
+/// recorded gap to its predecessor divided by `speed` (`2.0` replays twice
+/// as fast, `0.5` at half speed).
+///
+/// # Errors
+///
+/// Returns [`CoreError::Config`] when `speed` is not a positive, finite number.
+pub fn replay_delays(events: &[InputEvent], speed: f64) -> Result<Vec<Duration>, CoreError> {
+    if !speed.is_finite() || speed <= 0.0 {
+        return Err(CoreError::Config(format!(
+            "Replay speed must be a positive number, got {speed}"
+        )));
+    }
+    let mut previous = events.first().map_or(Duration::ZERO, |e| e.at);
+    Ok(events
+        .iter()
+        .map(|event| {
+            let gap = event.at.saturating_sub(previous);
+            previous = event.at;
+            gap.div_f64(speed)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::cast_recorder::CastRecorder;
+
+    fn record(events: &[(u64, &str)]) -> Vec<u8> {
+        let mut recorder = CastRecorder::new(Vec::new(), 80, 24, true).unwrap();
+        for (ms, data) in events {
+            recorder
+                .record_input_at(Duration::from_millis(*ms), data.as_bytes())
+                .unwrap();
+        }
+        recorder.finish().unwrap()
+    }
+
+    #[test]
+    fn recorded_input_round_trips() {
+        let buf = record(&[(0, "ls -la\r"), (250, "\x1b[A"), (1500, "exit\r")]);
+        let events = parse_input_recording(buf.as_slice()).unwrap();
+        assert_eq!(
+            events,
+            vec![
+                InputEvent {
+                    at: Duration::ZERO,
+                    data: b"ls -la\r".to_vec(),
+                },
+                InputEvent {
+                    at: Duration::from_millis(250),
+                    data: b"\x1b[A".to_vec(),
+                },
+                InputEvent {
+                    at: Duration::from_millis(1500),
+                    data: b"exit\r".to_vec(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_skips_output_events() {
+        let cast = "{\"version\":2,\"width\":80,\"height\":24}\n\
+                    [0.1,\"o\",\"$ \"]\n\
+                    [0.5,\"i\",\"pwd\\r\"]\n\
+                    [0.6,\"o\",\"/home\\r\\n\"]\n";
+        let events = parse_input_recording(cast.as_bytes()).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, b"pwd\r");
+        assert_eq!(events[0].at, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn replay_delays_follow_recorded_gaps() {
+        let buf = record(&[(1000, "a"), (1200, "b"), (2200, "c")]);
+        let events = parse_input_recording(buf.as_slice()).unwrap();
+        let delays = replay_delays(&events, 1.0).unwrap();
+        assert_eq!(
+            delays,
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(200),
+                Duration::from_millis(1000)
+            ]
+        );
+    }
+
+    #[test]
+    fn replay_speed_scales_delays() {
+        let buf = record(&[(0, "a"), (200, "b"), (1200, "c")]);
+        let events = parse_input_recording(buf.as_slice()).unwrap();
+
+        let fast = replay_delays(&events, 2.0).unwrap();
+        assert_eq!(
+            fast,
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(100),
+                Duration::from_millis(500)
+            ]
+        );
+
+        let slow = replay_delays(&events, 0.5).unwrap();
+        assert_eq!(slow[1], Duration::from_millis(400));
+        assert_eq!(slow[2], Duration::from_millis(2000));
+    }
+
+    #[test]
+    fn replay_rejects_invalid_speed() {
+        for speed in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(replay_delays(&[], speed).is_err(), "speed {speed}");
+        }
+    }
+}
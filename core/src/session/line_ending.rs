@@ -0,0 +1,172 @@
+//! Line-ending translation for outgoing terminal input.
+//!
+//! Terminals send Enter as `\r`, while pasted text usually carries `\n` or
+//! `\r\n`. Serial devices and telnet servers each expect one of these, so
+//! [`LineEndingTranslator`] rewrites every line break in the input to the
+//! connection's configured [`LineEnding`].
+
+use std::borrow::Cow;
+
+use crate::config::LineEnding;
+use crate::connection::SelectOption;
+
+/// Rewrites line breaks in outgoing data to a configured sequence.
+///
+/// A line break is `\r`, `\n` or `\r\n`; each becomes one configured
+/// ending. A `\r\n` split across two writes still counts once, so the
+/// translator is kept for the lifetime of a connection.
+pub struct LineEndingTranslator {
+    ending: LineEnding,
+    /// The previous write ended with `\r`, so a leading `\n` completes it.
+    after_cr: bool,
+}
+
+impl LineEndingTranslator {
+    pub fn new(ending: LineEnding) -> Self {
+        Self {
+            ending,
+            after_cr: false,
+        }
+    }
+
+    /// Translate the line breaks in `data`. With [`LineEnding::None`] the
+    /// data is returned unchanged.
+    pub fn translate<'a>(&mut self, data: &'a [u8]) -> Cow<'a, [u8]> {
+        let sequence: &[u8] = match self.ending {
+            LineEnding::None => return Cow::Borrowed(data),
+            LineEnding::Cr => b"\r",
+            LineEnding::Lf => b"\n",
+            LineEnding::Crlf => b"\r\n",
+        };
+        if !data.iter().any(|&b| b == b'\r' || b == b'\n') {
+            if !data.is_empty() {
+                self.after_cr = false;
+            }
+            return Cow::Borrowed(data);
+        }
+
+        let mut out = Vec::with_capacity(data.len() + data.len() / 8);
+        for &b in data {
+            match b {
+                b'\n' if self.after_cr => self.after_cr = false,
+                b'\r' | b'\n' => {
+                    out.extend_from_slice(sequence);
+                    self.after_cr = b == b'\r';
+                }
+                _ => {
+                    out.push(b);
+                    self.after_cr = false;
+                }
+            }
+        }
+        Cow::Owned(out)
+    }
+}
+
+/// Options for a `lineEnding` select field.
+pub fn line_ending_options() -> Vec<SelectOption> {
+    [
+        ("none", "None (send as typed)"),
+        ("cr", "CR"),
+        ("lf", "LF"),
+        ("crlf", "CR+LF"),
+    ]
+    .into_iter()
+    .map(|(value, label)| SelectOption {
+        value: value.to_string(),
+        label: label.to_string(),
+    })
+    .collect()
+}
+
+/// Parse a `lineEnding` setting; unknown or missing values mean
+/// [`LineEnding::None`].
+pub fn parse_line_ending(settings: &serde_json::Value) -> LineEnding {
+    settings
+        .get("lineEnding")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate(ending: LineEnding, writes: &[&[u8]]) -> Vec<u8> {
+        let mut translator = LineEndingTranslator::new(ending);
+        writes
+            .iter()
+            .flat_map(|w| translator.translate(w).into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn none_passes_through() {
+        let data: &[u8] = b"ls\r\nexit\n\r";
+        assert_eq!(translate(LineEnding::None, &[data]), data);
+    }
+
+    #[test]
+    fn enter_key_becomes_configured_ending() {
+        // xterm sends Enter as a lone CR.
+        assert_eq!(translate(LineEnding::Cr, &[b"AT\r"]), b"AT\r");
+        assert_eq!(translate(LineEnding::Lf, &[b"AT\r"]), b"AT\n");
+        assert_eq!(translate(LineEnding::Crlf, &[b"AT\r"]), b"AT\r\n");
+    }
+
+    #[test]
+    fn pasted_lines_become_configured_ending() {
+        assert_eq!(translate(LineEnding::Cr, &[b"a\nb\n"]), b"a\rb\r");
+        assert_eq!(translate(LineEnding::Crlf, &[b"a\nb\n"]), b"a\r\nb\r\n");
+        assert_eq!(translate(LineEnding::Lf, &[b"a\r\nb\r\n"]), b"a\nb\n");
+    }
+
+    #[test]
+    fn crlf_counts_as_one_break() {
+        assert_eq!(translate(LineEnding::Crlf, &[b"a\r\nb"]), b"a\r\nb");
+        assert_eq!(translate(LineEnding::Cr, &[b"a\r\n\r\nb"]), b"a\r\rb");
+    }
+
+    #[test]
+    fn crlf_split_across_writes_counts_once() {
+        assert_eq!(translate(LineEnding::Lf, &[b"a\r", b"\nb"]), b"a\nb");
+        assert_eq!(translate(LineEnding::Crlf, &[b"a\r", b"\n"]), b"a\r\n");
+    }
+
+    #[test]
+    fn lf_after_other_input_is_a_new_break() {
+        assert_eq!(
+            translate(LineEnding::Crlf, &[b"\r", b"x", b"\n"]),
+            b"\r\nx\r\n"
+        );
+        assert_eq!(translate(LineEnding::Crlf, &[b"\n\n"]), b"\r\n\r\n");
+        assert_eq!(translate(LineEnding::Lf, &[b"\r\r"]), b"\n\n");
+    }
+
+    #[test]
+    fn other_control_bytes_are_kept() {
+        // Ctrl+C, an arrow key and a tab completion.
+        let keys: &[u8] = b"\x03\x1b[A\t";
+        assert_eq!(translate(LineEnding::Crlf, &[keys]), keys);
+    }
+
+    #[test]
+    fn parse_line_ending_values() {
+        let parse = |v: serde_json::Value| parse_line_ending(&serde_json::json!({"lineEnding": v}));
+        assert_eq!(parse(serde_json::json!("cr")), LineEnding::Cr);
+        assert_eq!(parse(serde_json::json!("lf")), LineEnding::Lf);
+        assert_eq!(parse(serde_json::json!("crlf")), LineEnding::Crlf);
+        assert_eq!(parse(serde_json::json!("none")), LineEnding::None);
+        assert_eq!(parse(serde_json::json!("cr-lf")), LineEnding::None);
+        assert_eq!(parse_line_ending(&serde_json::json!({})), LineEnding::None);
+    }
+
+    #[test]
+    fn options_match_enum_values() {
+        for option in line_ending_options() {
+            let parsed: LineEnding =
+                serde_json::from_value(serde_json::json!(option.value)).unwrap();
+            assert_eq!(serde_json::to_value(parsed).unwrap(), option.value);
+        }
+    }
+}
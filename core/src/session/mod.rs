@@ -1,4 +1,7 @@
 pub mod docker;
+pub mod file_send;
+pub mod input_recording;
+pub mod line_ending;
 #[cfg(feature = "serial")]
 pub mod serial;
 pub mod shell;
@@ -6,13 +6,14 @@
 //! (`agent/src/serial/backend.rs`) delegate to these helpers so the
 //! logic lives in one place.
 
-use std::io::Read;
+use std::io::{self, Read, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use crate::buffer::RingBuffer;
 use crate::config::SerialConfig;
+use crate::connection::{ModemStatus, SessionNotice};
 use crate::errors::SessionError;
 
 /// Pre-parsed serial port configuration cached for reconnection.
@@ -83,6 +84,202 @@ pub fn parse_serial_config(config: &SerialConfig) -> Result<ParsedSerialConfig,
     })
 }
 
+/// Baud rates commonly supported by serial adapters and their drivers,
+/// in ascending order. Besides the POSIX rates this includes the ones
+/// common devices use: 14400/28800/128000/256000 (Windows COM ports),
+/// 31250 (MIDI), 74880 (ESP8266 boot ROM) and 250000 (3D printers).
+pub const STANDARD_BAUD_RATES: &[u32] = &[
+    50, 75, 110, 134, 150, 200, 300, 600, 1200, 1800, 2400, 4800, 9600, 14400, 19200, 28800, 31250,
+    38400, 57600, 74880, 115200, 128000, 230400, 250000, 256000, 460800, 500000, 576000, 921600,
+    1000000, 1152000, 1500000, 2000000, 2500000, 3000000, 3500000, 4000000,
+];
+
+/// Code of the notice reported when an unsupported baud rate was rounded.
+pub const BAUD_RATE_ROUNDED_NOTICE: &str = "baudRateRounded";
+
+/// Outcome of [`check_baud_rate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaudRateCheck {
+    /// The requested rate is supported as-is.
+    Supported(u32),
+    /// The requested rate is unsupported; `rate` is the nearest supported one.
+    Rounded { requested: u32, rate: u32 },
+}
+
+impl BaudRateCheck {
+    /// The rate to open the port with.
+    pub fn rate(self) -> u32 {
+        match self {
+            Self::Supported(rate) | Self::Rounded { rate, .. } => rate,
+        }
+    }
+
+    /// Notice telling the user the port was opened at a different rate
+    /// than configured, if it was.
+    pub fn notice(self) -> Option<SessionNotice> {
+        match self {
+            Self::Supported(_) => None,
+            Self::Rounded { requested, rate } => Some(SessionNotice {
+                code: BAUD_RATE_ROUNDED_NOTICE.to_string(),
+                message: format!(
+                    "Baud rate {requested} is not supported; the port was opened at {rate} \
+                     (enable Strict Baud Rate to fail instead)"
+                ),
+            }),
+        }
+    }
+}
+
+/// The rate in `rates` closest to `requested`, preferring the lower rate
+/// on a tie. Returns `None` when `rates` is empty.
+pub fn nearest_baud_rate(requested: u32, rates: &[u32]) -> Option<u32> {
+    rates
+        .iter()
+        .copied()
+        .min_by_key(|&rate| (rate.abs_diff(requested), rate))
+}
+
+/// Check `requested` against the `supported` baud rates.
+///
+/// An unsupported rate is rounded to the nearest supported one, or rejected
+/// when `strict` is set.
+///
+/// Returns [`SessionError::InvalidConfig`] for a zero rate, an empty
+/// `supported` list, or an unsupported rate in strict mode.
+pub fn check_baud_rate(
+    requested: u32,
+    supported: &[u32],
+    strict: bool,
+) -> Result<BaudRateCheck, SessionError> {
+    if requested == 0 {
+        return Err(SessionError::InvalidConfig(
+            "baud rate must be greater than zero".into(),
+        ));
+    }
+    if supported.contains(&requested) {
+        return Ok(BaudRateCheck::Supported(requested));
+    }
+    let nearest = nearest_baud_rate(requested, supported).ok_or_else(|| {
+        SessionError::InvalidConfig("no supported baud rates to choose from".into())
+    })?;
+    if strict {
+        return Err(SessionError::InvalidConfig(format!(
+            "baud rate {requested} is not supported (nearest supported rate: {nearest})"
+        )));
+    }
+    Ok(BaudRateCheck::Rounded {
+        requested,
+        rate: nearest,
+    })
+}
+
+/// Time source for [`SendPacer`], injectable so pacing can be tested
+/// without real sleeps.
+pub trait PacingClock {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+/// [`PacingClock`] backed by the system clock and `thread::sleep`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl PacingClock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Throttles outgoing serial writes for devices that drop characters when
+/// fed too fast.
+///
+/// Waits `byte_delay` between bytes and `line_delay` after each line
+/// ending (`\r`, `\n` or `\r\n`, counted once). The pause is measured from
+/// the previous byte sent, so it also holds across separate writes and
+/// costs nothing when input arrives slowly (e.g. typing).
+pub struct SendPacer<C: PacingClock = SystemClock> {
+    byte_delay: Duration,
+    line_delay: Duration,
+    clock: C,
+    /// Earliest time the next byte may be sent.
+    next_send_at: Option<Instant>,
+}
+
+impl SendPacer<SystemClock> {
+    /// Pacer using the delays from `config`.
+    pub fn from_config(config: &SerialConfig) -> Self {
+        Self::with_clock(
+            Duration::from_millis(u64::from(config.send_byte_delay_ms)),
+            Duration::from_millis(u64::from(config.send_line_delay_ms)),
+            SystemClock,
+        )
+    }
+}
+
+impl<C: PacingClock> SendPacer<C> {
+    pub fn with_clock(byte_delay: Duration, line_delay: Duration, clock: C) -> Self {
+        Self {
+            byte_delay,
+            line_delay,
+            clock,
+            next_send_at: None,
+        }
+    }
+
+    /// Write `data` to `writer`, pausing as configured. Without any delay
+    /// the data is written in one piece.
+    pub fn write(&mut self, writer: &mut impl Write, data: &[u8]) -> io::Result<()> {
+        if self.byte_delay.is_zero() && self.line_delay.is_zero() {
+            writer.write_all(data)?;
+            return writer.flush();
+        }
+
+        let mut start = 0;
+        while start < data.len() {
+            // One byte at a time with a byte delay; otherwise whole lines.
+            let end = if self.byte_delay.is_zero() {
+                (start..data.len())
+                    .find(|&i| ends_line(data, i))
+                    .map_or(data.len(), |i| i + 1)
+            } else {
+                start + 1
+            };
+
+            if let Some(at) = self.next_send_at {
+                let now = self.clock.now();
+                if at > now {
+                    self.clock.sleep(at - now);
+                }
+            }
+            writer.write_all(&data[start..end])?;
+            writer.flush()?;
+
+            let delay = if ends_line(data, end - 1) {
+                self.line_delay.max(self.byte_delay)
+            } else {
+                self.byte_delay
+            };
+            self.next_send_at = (!delay.is_zero()).then(|| self.clock.now() + delay);
+            start = end;
+        }
+        Ok(())
+    }
+}
+
+/// Whether `data[i]` ends a line. In `\r\n` only the `\n` does, so the
+/// pair gets a single line delay.
+fn ends_line(data: &[u8], i: usize) -> bool {
+    match data[i] {
+        b'\n' => true,
+        b'\r' => data.get(i + 1) != Some(&b'\n'),
+        _ => false,
+    }
+}
+
 /// Open a serial port using a pre-parsed configuration.
 ///
 /// The port is opened with a 100 ms read timeout, matching both
@@ -128,6 +325,100 @@ pub fn open_serial_port(
         })
 }
 
+/// Longest break a [`LineControl::Break`](crate::connection::LineControl)
+/// may hold the line for, in milliseconds.
+///
+/// The break holds the port for its whole duration, and writes and line
+/// controls made meanwhile wait for it while their caller may hold the
+/// session table, so it is kept short. Devices need far less: 250 ms is the
+/// usual break for console servers and boot loaders.
+pub const MAX_BREAK_MS: u32 = 500;
+
+/// Validate a requested break duration.
+///
+/// Returns [`SessionError::InvalidConfig`] for zero or more than
+/// [`MAX_BREAK_MS`].
+pub fn break_duration(duration_ms: u32) -> Result<Duration, SessionError> {
+    if duration_ms == 0 || duration_ms > MAX_BREAK_MS {
+        return Err(SessionError::InvalidConfig(format!(
+            "break duration must be 1 to {MAX_BREAK_MS} ms, got {duration_ms}"
+        )));
+    }
+    Ok(Duration::from_millis(u64::from(duration_ms)))
+}
+
+/// Set DTR and RTS to the levels in `config`; unset lines keep the
+/// driver's level.
+pub fn apply_modem_lines(
+    port: &mut dyn serialport::SerialPort,
+    config: &SerialConfig,
+) -> Result<(), SessionError> {
+    if let Some(level) = config.dtr {
+        port.write_data_terminal_ready(level)
+            .map_err(|e| modem_line_error("DTR", e))?;
+    }
+    if let Some(level) = config.rts {
+        port.write_request_to_send(level)
+            .map_err(|e| modem_line_error("RTS", e))?;
+    }
+    Ok(())
+}
+
+/// Source of the modem status input lines, injectable so status reads can
+/// be tested without a serial port.
+pub trait ModemStatusSource {
+    fn read_clear_to_send(&mut self) -> serialport::Result<bool>;
+    fn read_data_set_ready(&mut self) -> serialport::Result<bool>;
+    fn read_carrier_detect(&mut self) -> serialport::Result<bool>;
+    fn read_ring_indicator(&mut self) -> serialport::Result<bool>;
+}
+
+impl<T: serialport::SerialPort + ?Sized> ModemStatusSource for T {
+    fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+        serialport::SerialPort::read_clear_to_send(self)
+    }
+
+    fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+        serialport::SerialPort::read_data_set_ready(self)
+    }
+
+    fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+        serialport::SerialPort::read_carrier_detect(self)
+    }
+
+    fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+        serialport::SerialPort::read_ring_indicator(self)
+    }
+}
+
+/// Read CTS, DSR, DCD and RI from `source`.
+pub fn read_modem_status<S: ModemStatusSource + ?Sized>(
+    source: &mut S,
+) -> Result<ModemStatus, SessionError> {
+    let read_error = |line: &str, e: serialport::Error| {
+        SessionError::Io(io::Error::other(format!("Failed to read {line}: {e}")))
+    };
+    Ok(ModemStatus {
+        cts: source
+            .read_clear_to_send()
+            .map_err(|e| read_error("CTS", e))?,
+        dsr: source
+            .read_data_set_ready()
+            .map_err(|e| read_error("DSR", e))?,
+        dcd: source
+            .read_carrier_detect()
+            .map_err(|e| read_error("DCD", e))?,
+        ri: source
+            .read_ring_indicator()
+            .map_err(|e| read_error("RI", e))?,
+    })
+}
+
+/// Error for a failed modem control line change.
+pub(crate) fn modem_line_error(line: &str, e: serialport::Error) -> SessionError {
+    SessionError::Io(io::Error::other(format!("Failed to set {line}: {e}")))
+}
+
 /// List available serial port names on the system.
 ///
 /// Returns an empty vector if enumeration fails (e.g. on platforms
@@ -374,6 +665,218 @@ fn reconnect_loop_inner(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::{Cell, RefCell};
+
+    // --- Modem status tests ------------------------------------------------
+
+    /// Modem status lines with fixed levels; `None` makes the read fail.
+    struct FakeModemLines([Option<bool>; 4]);
+
+    impl FakeModemLines {
+        fn read(&self, index: usize) -> serialport::Result<bool> {
+            self.0[index].ok_or_else(|| {
+                serialport::Error::new(serialport::ErrorKind::NoDevice, "device unplugged")
+            })
+        }
+    }
+
+    impl ModemStatusSource for FakeModemLines {
+        fn read_clear_to_send(&mut self) -> serialport::Result<bool> {
+            self.read(0)
+        }
+
+        fn read_data_set_ready(&mut self) -> serialport::Result<bool> {
+            self.read(1)
+        }
+
+        fn read_carrier_detect(&mut self) -> serialport::Result<bool> {
+            self.read(2)
+        }
+
+        fn read_ring_indicator(&mut self) -> serialport::Result<bool> {
+            self.read(3)
+        }
+    }
+
+    #[test]
+    fn read_modem_status_maps_each_line() {
+        let mut lines = FakeModemLines([Some(true), Some(false), Some(true), Some(false)]);
+        assert_eq!(
+            read_modem_status(&mut lines).unwrap(),
+            ModemStatus {
+                cts: true,
+                dsr: false,
+                dcd: true,
+                ri: false,
+            }
+        );
+    }
+
+    #[test]
+    fn read_modem_status_reports_failed_line() {
+        let mut lines = FakeModemLines([Some(true), Some(true), None, Some(false)]);
+        let err = read_modem_status(&mut lines).unwrap_err();
+        assert!(err.to_string().contains("Failed to read DCD"), "{err}");
+    }
+
+    // --- SendPacer tests ---------------------------------------------------
+
+    /// Clock that only advances when slept on.
+    struct MockClock {
+        start: Instant,
+        elapsed: Cell<Duration>,
+    }
+
+    impl MockClock {
+        fn new() -> Self {
+            Self {
+                start: Instant::now(),
+                elapsed: Cell::new(Duration::ZERO),
+            }
+        }
+
+        fn advance(&self, by: Duration) {
+            self.elapsed.set(self.elapsed.get() + by);
+        }
+
+        fn elapsed_ms(&self) -> u64 {
+            self.elapsed.get().as_millis() as u64
+        }
+    }
+
+    impl PacingClock for &MockClock {
+        fn now(&self) -> Instant {
+            self.start + self.elapsed.get()
+        }
+
+        fn sleep(&self, duration: Duration) {
+            self.advance(duration);
+        }
+    }
+
+    /// Writer recording each flushed write with the mock time it was sent.
+    struct TimedWriter<'a> {
+        clock: &'a MockClock,
+        pending: Vec<u8>,
+        sent: RefCell<Vec<(u64, String)>>,
+    }
+
+    impl<'a> TimedWriter<'a> {
+        fn new(clock: &'a MockClock) -> Self {
+            Self {
+                clock,
+                pending: Vec::new(),
+                sent: RefCell::new(Vec::new()),
+            }
+        }
+
+        fn sent(&self) -> Vec<(u64, String)> {
+            self.sent.borrow().clone()
+        }
+    }
+
+    impl Write for TimedWriter<'_> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.pending.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            if !self.pending.is_empty() {
+                let data = String::from_utf8_lossy(&self.pending).into_owned();
+                self.sent.borrow_mut().push((self.clock.elapsed_ms(), data));
+                self.pending.clear();
+            }
+            Ok(())
+        }
+    }
+
+    fn pacer(clock: &MockClock, byte_ms: u64, line_ms: u64) -> SendPacer<&MockClock> {
+        SendPacer::with_clock(
+            Duration::from_millis(byte_ms),
+            Duration::from_millis(line_ms),
+            clock,
+        )
+    }
+
+    fn sent(entries: &[(u64, &str)]) -> Vec<(u64, String)> {
+        entries.iter().map(|&(t, s)| (t, s.to_string())).collect()
+    }
+
+    #[test]
+    fn pacer_without_delays_writes_at_once() {
+        let clock = MockClock::new();
+        let mut writer = TimedWriter::new(&clock);
+        pacer(&clock, 0, 0)
+            .write(&mut writer, b"ls\rpwd\r")
+            .unwrap();
+        assert_eq!(writer.sent(), sent(&[(0, "ls\rpwd\r")]));
+    }
+
+    #[test]
+    fn pacer_byte_delay_spaces_every_byte() {
+        let clock = MockClock::new();
+        let mut writer = TimedWriter::new(&clock);
+        pacer(&clock, 5, 0).write(&mut writer, b"abc").unwrap();
+        assert_eq!(writer.sent(), sent(&[(0, "a"), (5, "b"), (10, "c")]));
+    }
+
+    #[test]
+    fn pacer_line_delay_pauses_after_line_endings() {
+        let clock = MockClock::new();
+        let mut writer = TimedWriter::new(&clock);
+        pacer(&clock, 0, 100)
+            .write(&mut writer, b"one\ntwo\r\nthree\rfour")
+            .unwrap();
+        // `\r\n` counts as a single line ending.
+        assert_eq!(
+            writer.sent(),
+            sent(&[
+                (0, "one\n"),
+                (100, "two\r\n"),
+                (200, "three\r"),
+                (300, "four")
+            ])
+        );
+    }
+
+    #[test]
+    fn pacer_combines_byte_and_line_delays() {
+        let clock = MockClock::new();
+        let mut writer = TimedWriter::new(&clock);
+        pacer(&clock, 2, 20).write(&mut writer, b"a\rb").unwrap();
+        assert_eq!(writer.sent(), sent(&[(0, "a"), (2, "\r"), (22, "b")]));
+    }
+
+    #[test]
+    fn pacer_delay_carries_across_writes() {
+        let clock = MockClock::new();
+        let mut writer = TimedWriter::new(&clock);
+        let mut pacer = pacer(&clock, 0, 50);
+        pacer.write(&mut writer, b"reset\r").unwrap();
+        // The next write comes 10 ms later and still waits out the line delay.
+        clock.advance(Duration::from_millis(10));
+        pacer.write(&mut writer, b"x").unwrap();
+        // After a long idle period nothing is delayed.
+        clock.advance(Duration::from_secs(1));
+        pacer.write(&mut writer, b"y").unwrap();
+        assert_eq!(
+            writer.sent(),
+            sent(&[(0, "reset\r"), (50, "x"), (1050, "y")])
+        );
+    }
+
+    #[test]
+    fn pacer_from_config_uses_millisecond_settings() {
+        let cfg = SerialConfig {
+            send_byte_delay_ms: 3,
+            send_line_delay_ms: 40,
+            ..make_config("/dev/ttyUSB0")
+        };
+        let pacer = SendPacer::from_config(&cfg);
+        assert_eq!(pacer.byte_delay, Duration::from_millis(3));
+        assert_eq!(pacer.line_delay, Duration::from_millis(40));
+    }
 
     // --- parse_serial_config tests ---------------------------------------
 
@@ -605,6 +1108,7 @@ mod tests {
             stop_bits: 2,
             parity: "even".into(),
             flow_control: "hardware".into(),
+            ..SerialConfig::default()
         };
         let parsed = parse_serial_config(&cfg).unwrap();
         assert_eq!(parsed.port, "/dev/ttyS0");
@@ -615,6 +1119,113 @@ mod tests {
         assert_eq!(parsed.flow_control, serialport::FlowControl::Hardware);
     }
 
+    // --- baud rate validation tests --------------------------------------
+
+    #[test]
+    fn nearest_baud_rate_rounds_to_closest_standard_rate() {
+        assert_eq!(nearest_baud_rate(115000, STANDARD_BAUD_RATES), Some(115200));
+        assert_eq!(nearest_baud_rate(10000, STANDARD_BAUD_RATES), Some(9600));
+        assert_eq!(nearest_baud_rate(1, STANDARD_BAUD_RATES), Some(50));
+        assert_eq!(
+            nearest_baud_rate(u32::MAX, STANDARD_BAUD_RATES),
+            Some(4000000)
+        );
+    }
+
+    #[test]
+    fn device_specific_baud_rates_are_not_rounded() {
+        assert!(STANDARD_BAUD_RATES.windows(2).all(|w| w[0] < w[1]));
+        for rate in [14400, 28800, 31250, 74880, 128000, 250000, 256000] {
+            assert_eq!(
+                check_baud_rate(rate, STANDARD_BAUD_RATES, false).unwrap(),
+                BaudRateCheck::Supported(rate)
+            );
+        }
+    }
+
+    #[test]
+    fn nearest_baud_rate_prefers_lower_rate_on_tie() {
+        assert_eq!(nearest_baud_rate(150, &[100, 200]), Some(100));
+        assert_eq!(nearest_baud_rate(150, &[]), None);
+    }
+
+    #[test]
+    fn check_baud_rate_accepts_supported_rate() {
+        assert_eq!(
+            check_baud_rate(9600, STANDARD_BAUD_RATES, true).unwrap(),
+            BaudRateCheck::Supported(9600)
+        );
+    }
+
+    #[test]
+    fn check_baud_rate_rounds_unsupported_rate() {
+        let check = check_baud_rate(9500, STANDARD_BAUD_RATES, false).unwrap();
+        assert_eq!(
+            check,
+            BaudRateCheck::Rounded {
+                requested: 9500,
+                rate: 9600
+            }
+        );
+        assert_eq!(check.rate(), 9600);
+    }
+
+    #[test]
+    fn rounded_baud_rate_is_reported_as_notice() {
+        assert_eq!(BaudRateCheck::Supported(9600).notice(), None);
+        let notice = BaudRateCheck::Rounded {
+            requested: 9500,
+            rate: 9600,
+        }
+        .notice()
+        .unwrap();
+        assert_eq!(notice.code, BAUD_RATE_ROUNDED_NOTICE);
+        assert!(notice.message.contains("9500"), "{}", notice.message);
+        assert!(notice.message.contains("9600"), "{}", notice.message);
+    }
+
+    #[test]
+    fn check_baud_rate_strict_rejects_unsupported_rate() {
+        let err = check_baud_rate(9500, STANDARD_BAUD_RATES, true).unwrap_err();
+        assert!(err.to_string().contains("9500"), "{err}");
+        assert!(err.to_string().contains("9600"), "{err}");
+    }
+
+    #[test]
+    fn check_baud_rate_uses_given_supported_rates() {
+        let device_rates = [9600, 57600];
+        assert_eq!(
+            check_baud_rate(115200, &device_rates, false)
+                .unwrap()
+                .rate(),
+            57600
+        );
+    }
+
+    #[test]
+    fn check_baud_rate_rejects_zero() {
+        assert!(check_baud_rate(0, STANDARD_BAUD_RATES, false).is_err());
+    }
+
+    #[test]
+    fn break_duration_accepts_range() {
+        assert_eq!(break_duration(1).unwrap(), Duration::from_millis(1));
+        assert_eq!(
+            break_duration(MAX_BREAK_MS).unwrap(),
+            Duration::from_millis(u64::from(MAX_BREAK_MS))
+        );
+    }
+
+    #[test]
+    fn break_duration_rejects_zero_and_too_long() {
+        assert!(matches!(
+            break_duration(0),
+            Err(SessionError::InvalidConfig(_))
+        ));
+        let err = break_duration(MAX_BREAK_MS + 1).unwrap_err();
+        assert!(err.to_string().contains("501"), "{err}");
+    }
+
     // --- list_serial_ports tests -----------------------------------------
 
     #[test]
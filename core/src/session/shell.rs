@@ -242,6 +242,20 @@ pub fn detect_wsl_distros() -> Vec<String> {
     }
 }
 
+/// List installed WSL distributions.
+///
+/// Delegates to [`detect_wsl_distros()`] on Windows.
+#[cfg(windows)]
+pub fn list_wsl_distributions() -> Vec<String> {
+    detect_wsl_distros()
+}
+
+/// List installed WSL distributions — always empty off Windows.
+#[cfg(not(windows))]
+pub fn list_wsl_distributions() -> Vec<String> {
+    Vec::new()
+}
+
 /// Detect available shells on the current platform.
 ///
 /// On Unix, checks standard paths (`/bin/zsh`, `/usr/bin/bash`, etc.).
@@ -1081,6 +1095,23 @@ mod tests {
         assert_eq!(result, vec!["U"]);
     }
 
+    #[test]
+    fn parse_wsl_output_typical_listing() {
+        // `wsl.exe --list --quiet` with a BOM, CRLF line endings, a blank
+        // line and a distribution name containing dots and dashes
+        let text = "\u{FEFF}Ubuntu-22.04\r\ndocker-desktop\r\n\r\nkali-linux\r\n";
+        let raw: Vec<u8> = text.encode_utf16().flat_map(|c| c.to_le_bytes()).collect();
+
+        let result = parse_wsl_output(&raw);
+        assert_eq!(result, vec!["Ubuntu-22.04", "docker-desktop", "kali-linux"]);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn list_wsl_distributions_empty_off_windows() {
+        assert!(list_wsl_distributions().is_empty());
+    }
+
     #[test]
     fn parse_wsl_output_with_null_bytes_in_text() {
         // Some WSL versions emit trailing null characters
@@ -5,6 +5,8 @@
 //! (`ssh/backend.rs`) and provide shared, testable pure functions
 //! with no network I/O.
 
+use std::net::IpAddr;
+
 use crate::config::SshConfig;
 use crate::errors::SessionError;
 
@@ -20,7 +22,10 @@ const DEFAULT_SSH_PORT: u16 = 22;
 /// - `-tt` for forced TTY allocation
 /// - `-o ServerAliveInterval=30` and `-o ServerAliveCountMax=3` for keepalive
 /// - `-p <port>` when port differs from the default (22)
-/// - `-i <key_path>` when auth method is `"key"` and a key path is provided
+/// - `-b <bind_address>` when a source address is configured
+/// - `-J <proxy_jump>` when jump hosts are configured
+/// - `-i <key_path>` when auth method is `"key"` and a key path is provided,
+///   followed by one `-i` per entry in `identity_files`
 /// - `user@host` destination
 /// - Optional remote shell command
 pub fn build_ssh_args(config: &SshConfig) -> Vec<String> {
@@ -40,9 +45,25 @@ pub fn build_ssh_args(config: &SshConfig) -> Vec<String> {
         args.push(config.port.to_string());
     }
 
+    if let Some(bind) = config.bind_address.as_deref().map(str::trim) {
+        args.push("-b".to_string());
+        args.push(bind.to_string());
+    }
+
+    if let Some(jump) = config
+        .proxy_jump
+        .as_deref()
+        .map(str::trim)
+        .filter(|j| !j.is_empty())
+    {
+        args.push("-J".to_string());
+        args.push(jump.to_string());
+    }
+
     // Key-based auth
     if config.auth_method == "key" {
-        if let Some(ref key_path) = config.key_path {
+        let identities = config.key_path.iter().chain(config.identity_files.iter());
+        for key_path in identities.filter(|p| !p.trim().is_empty()) {
             args.push("-i".to_string());
             args.push(key_path.clone());
         }
@@ -64,7 +85,11 @@ pub fn build_ssh_args(config: &SshConfig) -> Vec<String> {
 /// Checks:
 /// - `host` is not empty
 /// - `username` is not empty
-/// - When `auth_method` is `"key"`, `key_path` must be present and non-empty
+/// - When `auth_method` is `"key"`, `key_path` must be present and non-empty,
+///   unless at least one non-empty `identity_files` entry is configured
+/// - `bind_address`, when set, is an IPv4 or IPv6 address
+/// - `proxy_jump`, when set, is a valid jump host chain
+/// - `locale`, when set, is a locale name (see [`is_valid_locale`])
 pub fn validate_ssh_config(config: &SshConfig) -> Result<(), SessionError> {
     if config.host.trim().is_empty() {
         return Err(SessionError::InvalidConfig(
@@ -78,7 +103,8 @@ pub fn validate_ssh_config(config: &SshConfig) -> Result<(), SessionError> {
         ));
     }
 
-    if config.auth_method == "key" {
+    let has_identity_files = config.identity_files.iter().any(|p| !p.trim().is_empty());
+    if config.auth_method == "key" && !has_identity_files {
         match &config.key_path {
             None => {
                 return Err(SessionError::InvalidConfig(
@@ -94,9 +120,138 @@ pub fn validate_ssh_config(config: &SshConfig) -> Result<(), SessionError> {
         }
     }
 
+    if let Some(addr) = config.bind_address.as_deref() {
+        if addr.trim().parse::<IpAddr>().is_err() {
+            return Err(SessionError::InvalidConfig(format!(
+                "SSH source address \"{addr}\" is not a valid IP address"
+            )));
+        }
+    }
+
+    if let Some(jump) = config.proxy_jump.as_deref() {
+        parse_proxy_jump(jump)?;
+    }
+
+    if let Some(locale) = config.locale.as_deref().map(str::trim) {
+        if !locale.is_empty() && !is_valid_locale(locale) {
+            return Err(SessionError::InvalidConfig(format!(
+                "SSH locale \"{locale}\" is not a valid locale name (e.g. en_US.UTF-8)"
+            )));
+        }
+    }
+
     Ok(())
 }
 
+/// Whether `locale` is a POSIX locale name:
+/// `language[_TERRITORY][.codeset][@modifier]`, or `C`/`POSIX` with an
+/// optional codeset (`C.UTF-8`).
+///
+/// The language is two or three lowercase letters and the territory two
+/// uppercase letters or three digits; the codeset and modifier are
+/// alphanumeric (the codeset may also contain `-` and `_`). Names that
+/// pass are safe to send unquoted to a remote shell.
+pub fn is_valid_locale(locale: &str) -> bool {
+    let (rest, modifier) = match locale.split_once('@') {
+        Some((rest, modifier)) => (rest, Some(modifier)),
+        None => (locale, None),
+    };
+    let (name, codeset) = match rest.split_once('.') {
+        Some((name, codeset)) => (name, Some(codeset)),
+        None => (rest, None),
+    };
+    let (language, territory) = match name.split_once('_') {
+        Some((language, territory)) => (language, Some(territory)),
+        None => (name, None),
+    };
+
+    let language_ok = if matches!(language, "C" | "POSIX") {
+        territory.is_none() && modifier.is_none()
+    } else {
+        (2..=3).contains(&language.len()) && language.bytes().all(|b| b.is_ascii_lowercase())
+    };
+    let territory_ok = territory.is_none_or(|t| {
+        t.len() == 2 && t.bytes().all(|b| b.is_ascii_uppercase())
+            || t.len() == 3 && t.bytes().all(|b| b.is_ascii_digit())
+    });
+    let codeset_ok = codeset.is_none_or(|c| {
+        !c.is_empty()
+            && c.bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+    });
+    let modifier_ok =
+        modifier.is_none_or(|m| !m.is_empty() && m.bytes().all(|b| b.is_ascii_alphanumeric()));
+
+    language_ok && territory_ok && codeset_ok && modifier_ok
+}
+
+/// One hop of a `proxy_jump` chain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpHost {
+    /// Login name; `None` uses the target's username.
+    pub username: Option<String>,
+    pub host: String,
+    pub port: u16,
+}
+
+/// Parse a comma-separated `proxy_jump` chain of `[user@]host[:port]`
+/// hops, outermost first.
+///
+/// IPv6 addresses are written in brackets (`[::1]:2222`). A blank chain
+/// or OpenSSH's `none` yields no hops.
+pub fn parse_proxy_jump(spec: &str) -> Result<Vec<JumpHost>, SessionError> {
+    let spec = spec.trim();
+    if spec.is_empty() || spec.eq_ignore_ascii_case("none") {
+        return Ok(Vec::new());
+    }
+    spec.split(',')
+        .map(|hop| parse_jump_host(hop.trim()))
+        .collect()
+}
+
+fn parse_jump_host(hop: &str) -> Result<JumpHost, SessionError> {
+    let invalid = || {
+        SessionError::InvalidConfig(format!(
+            "Invalid jump host \"{hop}\": expected [user@]host[:port]"
+        ))
+    };
+    let (username, address) = match hop.rsplit_once('@') {
+        Some(("", _)) => return Err(invalid()),
+        Some((user, address)) => (Some(user.to_string()), address),
+        None => (None, hop),
+    };
+    let (host, port) = match address.strip_prefix('[') {
+        Some(bracketed) => {
+            let (host, rest) = bracketed.split_once(']').ok_or_else(invalid)?;
+            let port = match rest {
+                "" => None,
+                _ => Some(rest.strip_prefix(':').ok_or_else(invalid)?),
+            };
+            (host, port)
+        }
+        None => match address.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (address, None),
+        },
+    };
+    if host.is_empty() || host.contains(char::is_whitespace) {
+        return Err(invalid());
+    }
+    let port = match port {
+        Some(port) => port
+            .parse::<u16>()
+            .ok()
+            .filter(|&p| p != 0)
+            .ok_or_else(invalid)?,
+        None => DEFAULT_SSH_PORT,
+    };
+    Ok(JumpHost {
+        username,
+        host: host.to_string(),
+        port,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,6 +260,21 @@ mod tests {
     // build_ssh_args
     // -----------------------------------------------------------------------
 
+    #[test]
+    fn build_ssh_args_bind_address() {
+        let config = SshConfig {
+            host: "build.internal".into(),
+            username: "dev".into(),
+            auth_method: "agent".into(),
+            bind_address: Some("10.20.0.7".into()),
+            ..Default::default()
+        };
+        let args = build_ssh_args(&config);
+        let pos = args.iter().position(|a| a == "-b").unwrap();
+        assert_eq!(args[pos + 1], "10.20.0.7");
+        assert_eq!(args.last().unwrap(), "dev@build.internal");
+    }
+
     #[test]
     fn build_ssh_args_minimal() {
         let config = SshConfig {
@@ -241,6 +411,28 @@ mod tests {
         assert!(!args.contains(&"-i".to_string()));
     }
 
+    #[test]
+    fn build_ssh_args_key_auth_with_identity_files() {
+        let config = SshConfig {
+            host: "example.com".into(),
+            username: "user".into(),
+            auth_method: "key".into(),
+            key_path: Some("~/.ssh/id_ed25519".into()),
+            identity_files: vec!["~/.ssh/work".into(), "~/.ssh/personal".into()],
+            ..Default::default()
+        };
+        let args = build_ssh_args(&config);
+        let identities: Vec<&str> = args
+            .windows(2)
+            .filter(|w| w[0] == "-i")
+            .map(|w| w[1].as_str())
+            .collect();
+        assert_eq!(
+            identities,
+            vec!["~/.ssh/id_ed25519", "~/.ssh/work", "~/.ssh/personal"]
+        );
+    }
+
     // -----------------------------------------------------------------------
     // validate_ssh_config
     // -----------------------------------------------------------------------
@@ -366,4 +558,185 @@ mod tests {
         let err = validate_ssh_config(&config).unwrap_err();
         assert!(err.to_string().contains("key path"));
     }
+
+    #[test]
+    fn validate_key_auth_identity_files_without_key_path() {
+        let config = SshConfig {
+            host: "example.com".into(),
+            username: "admin".into(),
+            auth_method: "key".into(),
+            key_path: None,
+            identity_files: vec!["~/.ssh/work".into()],
+            ..Default::default()
+        };
+        assert!(validate_ssh_config(&config).is_ok());
+    }
+
+    #[test]
+    fn validate_bind_address() {
+        let config = |bind: &str| SshConfig {
+            host: "example.com".into(),
+            username: "admin".into(),
+            auth_method: "agent".into(),
+            bind_address: Some(bind.into()),
+            ..Default::default()
+        };
+        assert!(validate_ssh_config(&config("10.20.0.7")).is_ok());
+        assert!(validate_ssh_config(&config("fe80::1")).is_ok());
+
+        let err = validate_ssh_config(&config("mgmt0")).unwrap_err();
+        assert!(err.to_string().contains("not a valid IP address"));
+        assert!(validate_ssh_config(&config("10.20.0.7:22")).is_err());
+    }
+
+    #[test]
+    fn validate_proxy_jump() {
+        let config = |jump: &str| SshConfig {
+            host: "example.com".into(),
+            username: "admin".into(),
+            auth_method: "agent".into(),
+            proxy_jump: Some(jump.into()),
+            ..Default::default()
+        };
+        assert!(validate_ssh_config(&config("ops@bastion:2204")).is_ok());
+
+        let err = validate_ssh_config(&config("bastion:ssh")).unwrap_err();
+        assert!(err.to_string().contains("Invalid jump host"), "{err}");
+    }
+
+    #[test]
+    fn validate_locale() {
+        let config = |locale: &str| SshConfig {
+            host: "example.com".into(),
+            username: "admin".into(),
+            auth_method: "agent".into(),
+            locale: Some(locale.into()),
+            ..Default::default()
+        };
+        assert!(validate_ssh_config(&config("en_US.UTF-8")).is_ok());
+        assert!(validate_ssh_config(&config("")).is_ok());
+
+        let err = validate_ssh_config(&config("en_US.UTF-8; rm -rf ~")).unwrap_err();
+        assert!(err.to_string().contains("not a valid locale name"), "{err}");
+    }
+
+    #[test]
+    fn is_valid_locale_accepts_posix_names() {
+        for locale in [
+            "C",
+            "POSIX",
+            "C.UTF-8",
+            "de",
+            "de_DE",
+            "en_US.UTF-8",
+            "en_US.utf8",
+            "sr_RS@latin",
+            "de_DE.ISO-8859-15@euro",
+            "es_419.UTF-8",
+            "ast_ES.UTF-8",
+        ] {
+            assert!(is_valid_locale(locale), "{locale}");
+        }
+    }
+
+    #[test]
+    fn is_valid_locale_rejects_malformed_names() {
+        for locale in [
+            "",
+            "english",
+            "EN_us",
+            "en-US",
+            "en_USA",
+            "en_US.",
+            "en_US.UTF 8",
+            "en_US@",
+            "C_US",
+            "en_US.UTF-8\nexport X=1",
+            "$(id)",
+        ] {
+            assert!(!is_valid_locale(locale), "{locale:?}");
+        }
+    }
+
+    // -----------------------------------------------------------------------
+    // parse_proxy_jump
+    // -----------------------------------------------------------------------
+
+    fn hop(username: Option<&str>, host: &str, port: u16) -> JumpHost {
+        JumpHost {
+            username: username.map(String::from),
+            host: host.to_string(),
+            port,
+        }
+    }
+
+    #[test]
+    fn parse_proxy_jump_single_hop_forms() {
+        assert_eq!(
+            parse_proxy_jump("bastion").unwrap(),
+            vec![hop(None, "bastion", 22)]
+        );
+        assert_eq!(
+            parse_proxy_jump("ops@bastion:2204").unwrap(),
+            vec![hop(Some("ops"), "bastion", 2204)]
+        );
+        assert_eq!(
+            parse_proxy_jump("ops@[fe80::1]:2222").unwrap(),
+            vec![hop(Some("ops"), "fe80::1", 2222)]
+        );
+        assert_eq!(
+            parse_proxy_jump("[::1]").unwrap(),
+            vec![hop(None, "::1", 22)]
+        );
+    }
+
+    #[test]
+    fn parse_proxy_jump_multi_hop_chain_in_order() {
+        assert_eq!(
+            parse_proxy_jump("edge.example.com:2200, ops@inner").unwrap(),
+            vec![
+                hop(None, "edge.example.com", 2200),
+                hop(Some("ops"), "inner", 22),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_proxy_jump_blank_or_none_is_empty() {
+        assert!(parse_proxy_jump("").unwrap().is_empty());
+        assert!(parse_proxy_jump("  ").unwrap().is_empty());
+        assert!(parse_proxy_jump("none").unwrap().is_empty());
+    }
+
+    #[test]
+    fn parse_proxy_jump_rejects_malformed_hops() {
+        for spec in [
+            "bastion,",
+            "@bastion",
+            "ops@",
+            "bastion:0",
+            "bastion:70000",
+            "fe80::1",
+            "[fe80::1",
+            "[fe80::1]2222",
+            "bas tion",
+        ] {
+            assert!(parse_proxy_jump(spec).is_err(), "{spec} should be rejected");
+        }
+    }
+
+    #[test]
+    fn build_ssh_args_proxy_jump() {
+        let config = SshConfig {
+            host: "app.internal".into(),
+            username: "dev".into(),
+            auth_method: "agent".into(),
+            proxy_jump: Some("ops@bastion:2204,inner".into()),
+            ..Default::default()
+        };
+        let args = build_ssh_args(&config);
+        let pos = args.iter().position(|a| a == "-J").unwrap();
+        assert_eq!(args[pos + 1], "ops@bastion:2204,inner");
+        assert_eq!(args.last().unwrap(), "dev@app.internal");
+    }
 }
@@ -145,6 +145,8 @@ pub struct SpawnedShell {
     pub resize: Box<dyn Fn(u16, u16) -> Result<(), SessionError> + Send + Sync>,
     /// Kill the shell process.
     pub kill: Box<dyn Fn() + Send + Sync>,
+    /// OS process ID of the shell, when the platform reports one.
+    pub process_id: Option<u32>,
 }
 
 /// PTY / process spawn abstraction for the local shell backend.
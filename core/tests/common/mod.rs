@@ -28,6 +28,7 @@ pub fn is_port_reachable(host: &str, port: u16) -> bool {
 ///
 /// Prints a message to stderr and returns early. This follows the agent crate's
 /// existing skip convention (runtime check instead of `#[ignore]`).
+#[allow(unused_macros)]
 macro_rules! require_docker {
     ($port:expr) => {
         if !common::is_port_reachable("127.0.0.1", $port) {
@@ -40,8 +41,31 @@ macro_rules! require_docker {
         }
     };
 }
+#[allow(unused_imports)]
 pub(crate) use require_docker;
 
+/// Whether the `docker` CLI can reach a daemon.
+pub fn docker_available() -> bool {
+    std::process::Command::new("docker")
+        .arg("info")
+        .output()
+        .is_ok_and(|o| o.status.success())
+}
+
+/// Skip the current test if no Docker daemon is available, for tests that
+/// start their own containers instead of using the compose setup.
+#[allow(unused_macros)]
+macro_rules! require_docker_daemon {
+    () => {
+        if !common::docker_available() {
+            eprintln!("SKIPPED: Docker daemon not available");
+            return;
+        }
+    };
+}
+#[allow(unused_imports)]
+pub(crate) use require_docker_daemon;
+
 /// Path to the `tests/fixtures/ssh-keys/` directory.
 pub fn ssh_keys_dir() -> PathBuf {
     PathBuf::from(env!("CARGO_MANIFEST_DIR"))
@@ -59,7 +83,8 @@ pub fn ssh_password_settings(port: u16) -> serde_json::Value {
         "port": port,
         "username": "testuser",
         "authMethod": "password",
-        "password": "testpass"
+        "password": "testpass",
+        "strictHostKeyChecking": "off"
     })
 }
 
@@ -71,7 +96,8 @@ pub fn ssh_key_settings(port: u16, key_name: &str) -> serde_json::Value {
         "port": port,
         "username": "testuser",
         "authMethod": "key",
-        "keyPath": key_path.to_str().unwrap()
+        "keyPath": key_path.to_str().unwrap(),
+        "strictHostKeyChecking": "off"
     })
 }
 
@@ -86,7 +112,8 @@ pub fn ssh_key_passphrase_settings(port: u16, key_name: &str) -> serde_json::Val
         "username": "testuser",
         "authMethod": "key",
         "keyPath": key_path.to_str().unwrap(),
-        "password": "testpass123"
+        "password": "testpass123",
+        "strictHostKeyChecking": "off"
     })
 }
 
@@ -100,6 +127,7 @@ pub fn ssh_password_config(port: u16) -> termihub_core::config::SshConfig {
         username: "testuser".to_string(),
         auth_method: "password".to_string(),
         password: Some("testpass".to_string()),
+        strict_host_key_checking: "off".to_string(),
         ..Default::default()
     }
 }
@@ -113,6 +141,7 @@ pub fn ssh_key_config(port: u16, key_name: &str) -> termihub_core::config::SshCo
         username: "testuser".to_string(),
         auth_method: "key".to_string(),
         key_path: Some(key_path.to_str().unwrap().to_string()),
+        strict_host_key_checking: "off".to_string(),
         ..Default::default()
     }
 }
@@ -127,6 +156,7 @@ pub fn ssh_key_passphrase_config(port: u16, key_name: &str) -> termihub_core::co
         auth_method: "key".to_string(),
         key_path: Some(key_path.to_str().unwrap().to_string()),
         password: Some("testpass123".to_string()),
+        strict_host_key_checking: "off".to_string(),
         ..Default::default()
     }
 }
@@ -0,0 +1,170 @@
+//! Docker Attach Integration Tests (DOCKER-ATTACH-01 through DOCKER-ATTACH-04).
+//!
+//! Tests the Docker backend's attach mode against containers started
+//! outside termiHub with `docker run`. Each test starts its own `alpine`
+//! container and force-removes it afterwards.
+//!
+//! Requires: a running Docker daemon reachable by the `docker` CLI.
+//! Skips gracefully if it is not available.
+
+mod common;
+
+use std::process::Command;
+use std::time::Duration;
+
+use common::require_docker_daemon;
+use termihub_core::backends::docker::Docker;
+use termihub_core::connection::ConnectionType;
+use termihub_core::errors::SessionError;
+
+/// A container started with `docker run`, force-removed when dropped.
+struct ExternalContainer {
+    name: String,
+}
+
+impl ExternalContainer {
+    fn start(test: &str) -> Self {
+        let name = format!("termihub-attach-{test}-{}", std::process::id());
+        let output = Command::new("docker")
+            .args(["run", "-d", "--name", &name, "alpine", "sleep", "300"])
+            .output()
+            .expect("Failed to run docker");
+        assert!(
+            output.status.success(),
+            "docker run failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Self { name }
+    }
+
+    fn is_running(&self) -> bool {
+        Command::new("docker")
+            .args(["inspect", "-f", "{{.State.Running}}", &self.name])
+            .output()
+            .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).trim() == "true")
+    }
+
+    /// The command lines of the processes running in the container.
+    fn processes(&self) -> Vec<String> {
+        let output = Command::new("docker")
+            .args(["top", &self.name, "-o", "args"])
+            .output()
+            .expect("Failed to run docker top");
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .skip(1)
+            .map(str::to_string)
+            .collect()
+    }
+
+    fn stop(&self) {
+        let _ = Command::new("docker")
+            .args(["stop", "-t", "0", &self.name])
+            .output();
+    }
+}
+
+impl Drop for ExternalContainer {
+    fn drop(&mut self) {
+        let _ = Command::new("docker")
+            .args(["rm", "-f", &self.name])
+            .output();
+    }
+}
+
+fn attach_settings(container: &str) -> serde_json::Value {
+    serde_json::json!({
+        "mode": "attach",
+        "containerName": container,
+        "removeOnExit": true,
+    })
+}
+
+// ── DOCKER-ATTACH-01: Shell in a running container ───────────────────
+
+#[tokio::test]
+async fn docker_attach_01_exec_into_running_container() {
+    require_docker_daemon!();
+    let container = ExternalContainer::start("01");
+
+    let mut docker = Docker::new();
+    docker
+        .connect(attach_settings(&container.name))
+        .await
+        .expect("DOCKER-ATTACH-01: Attach should succeed");
+    let mut rx = docker.subscribe_output();
+
+    docker.write(b"echo attach-$((40 + 2))\n").unwrap();
+    let mut output = String::new();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    while !output.contains("attach-42") {
+        match tokio::time::timeout_at(deadline, rx.recv()).await {
+            Ok(Some(data)) => output.push_str(&String::from_utf8_lossy(&data)),
+            _ => panic!("DOCKER-ATTACH-01: Expected echo output, got: {output}"),
+        }
+    }
+
+    docker.disconnect().await.unwrap();
+    assert!(
+        container.is_running(),
+        "DOCKER-ATTACH-01: Attached container must keep running despite removeOnExit"
+    );
+    assert_eq!(
+        container.processes(),
+        vec!["sleep 300".to_string()],
+        "DOCKER-ATTACH-01: The exec'd shell must exit on disconnect"
+    );
+}
+
+// ── DOCKER-ATTACH-02: Missing container ──────────────────────────────
+
+#[tokio::test]
+async fn docker_attach_02_missing_container_is_not_running() {
+    require_docker_daemon!();
+
+    let mut docker = Docker::new();
+    let name = format!("termihub-attach-missing-{}", std::process::id());
+    let err = docker.connect(attach_settings(&name)).await.unwrap_err();
+    assert!(
+        matches!(err, SessionError::NotRunning(ref msg) if msg.contains("does not exist")),
+        "DOCKER-ATTACH-02: unexpected error: {err}"
+    );
+    assert!(!docker.is_connected());
+}
+
+// ── DOCKER-ATTACH-03: Stopped container ──────────────────────────────
+
+#[tokio::test]
+async fn docker_attach_03_stopped_container_is_not_running() {
+    require_docker_daemon!();
+    let container = ExternalContainer::start("03");
+    container.stop();
+
+    let mut docker = Docker::new();
+    let err = docker
+        .connect(attach_settings(&container.name))
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(err, SessionError::NotRunning(ref msg) if msg.contains("is not running")),
+        "DOCKER-ATTACH-03: unexpected error: {err}"
+    );
+}
+
+// ── DOCKER-ATTACH-04: Missing shell ──────────────────────────────────
+
+#[tokio::test]
+async fn docker_attach_04_missing_shell_fails_to_connect() {
+    require_docker_daemon!();
+    let container = ExternalContainer::start("04");
+
+    let mut settings = attach_settings(&container.name);
+    settings["shell"] = serde_json::json!("/no/such/shell");
+    let mut docker = Docker::new();
+    let err = docker.connect(settings).await.unwrap_err();
+    assert!(
+        matches!(err, SessionError::SpawnFailed(ref msg) if msg.contains("executable file not found")),
+        "DOCKER-ATTACH-04: unexpected error: {err}"
+    );
+    assert!(!docker.is_connected());
+}
@@ -0,0 +1,88 @@
+//! Docker Persistent Container Integration Tests (DOCKER-PERSIST-01).
+//!
+//! Tests the container a saved Docker connection keeps across sessions
+//! (`removeOnExit` off) when several sessions of the connection use it.
+//! Each test force-removes its container afterwards.
+//!
+//! Requires: a running Docker daemon reachable by the `docker` CLI.
+//! Skips gracefully if it is not available.
+
+mod common;
+
+use std::process::Command;
+
+use common::require_docker_daemon;
+use termihub_core::backends::docker::Docker;
+use termihub_core::connection::ConnectionType;
+
+/// The persistent container of a test connection, force-removed when
+/// dropped.
+struct PersistentContainer {
+    connection_id: String,
+}
+
+impl PersistentContainer {
+    fn new(test: &str) -> Self {
+        Self {
+            connection_id: format!("persist-{test}-{}", std::process::id()),
+        }
+    }
+
+    fn name(&self) -> String {
+        format!("termihub-conn-{}", self.connection_id)
+    }
+
+    fn settings(&self) -> serde_json::Value {
+        serde_json::json!({
+            "image": "alpine",
+            "removeOnExit": false,
+            "connectionId": self.connection_id,
+        })
+    }
+
+    fn is_running(&self) -> bool {
+        Command::new("docker")
+            .args(["inspect", "-f", "{{.State.Running}}", &self.name()])
+            .output()
+            .is_ok_and(|o| String::from_utf8_lossy(&o.stdout).trim() == "true")
+    }
+}
+
+impl Drop for PersistentContainer {
+    fn drop(&mut self) {
+        let _ = Command::new("docker")
+            .args(["rm", "-f", &self.name()])
+            .output();
+    }
+}
+
+// ── DOCKER-PERSIST-01: Sessions share the container ──────────────────
+
+#[tokio::test]
+async fn docker_persist_01_sessions_share_container() {
+    require_docker_daemon!();
+    let container = PersistentContainer::new("01");
+
+    // Both find no container and race to create it.
+    let mut first = Docker::new();
+    let mut second = Docker::new();
+    let (first_result, second_result) = tokio::join!(
+        first.connect(container.settings()),
+        second.connect(container.settings())
+    );
+    first_result.expect("DOCKER-PERSIST-01: First connect should succeed");
+    second_result.expect("DOCKER-PERSIST-01: Concurrent connect should reuse the container");
+
+    first.disconnect().await.unwrap();
+    assert!(
+        container.is_running(),
+        "DOCKER-PERSIST-01: Container should keep running for the other session"
+    );
+    assert!(second.is_connected());
+
+    second.disconnect().await.unwrap();
+    assert!(
+        !container.is_running(),
+        "DOCKER-PERSIST-01: Container should stop with its last session"
+    );
+}
@@ -0,0 +1,70 @@
+//! Docker Resource Limit Integration Tests (DOCKER-RES-01).
+//!
+//! Tests that memory and CPU limits from the settings reach the container
+//! the Docker backend creates, checked with `docker inspect`.
+//!
+//! Requires: a running Docker daemon reachable by the `docker` CLI.
+//! Skips gracefully if it is not available.
+
+mod common;
+
+use std::process::Command;
+
+use common::require_docker_daemon;
+use termihub_core::backends::docker::Docker;
+use termihub_core::connection::ConnectionType;
+use termihub_core::session::docker::LABEL_SESSION_ID;
+
+/// `docker inspect` `format` applied to the container labelled with
+/// `session_id`.
+fn inspect_session_container(session_id: &str, format: &str) -> String {
+    let output = Command::new("docker")
+        .args([
+            "ps",
+            "-q",
+            "--filter",
+            &format!("label={LABEL_SESSION_ID}={session_id}"),
+        ])
+        .output()
+        .expect("Failed to run docker ps");
+    let id = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    assert!(!id.is_empty(), "No container for session {session_id}");
+
+    let output = Command::new("docker")
+        .args(["inspect", "-f", format, &id])
+        .output()
+        .expect("Failed to run docker inspect");
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+// ── DOCKER-RES-01: Limits are applied to the container ───────────────
+
+#[tokio::test]
+async fn docker_res_01_limits_applied() {
+    require_docker_daemon!();
+
+    let session_id = format!("docker-res-01-{}", std::process::id());
+    let mut docker = Docker::new();
+    docker
+        .connect(serde_json::json!({
+            "image": "alpine",
+            "memoryLimitMb": 128,
+            "cpus": 0.5,
+            "cpuShares": 512,
+            "sessionId": session_id,
+        }))
+        .await
+        .expect("DOCKER-RES-01: Connect should succeed");
+
+    let limits = inspect_session_container(
+        &session_id,
+        "{{.HostConfig.Memory}} {{.HostConfig.NanoCpus}} {{.HostConfig.CpuShares}}",
+    );
+    docker.disconnect().await.unwrap();
+
+    assert_eq!(
+        limits,
+        format!("{} 500000000 512", 128 * 1024 * 1024),
+        "DOCKER-RES-01: Memory, NanoCpus and CpuShares should match the settings"
+    );
+}
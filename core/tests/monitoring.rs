@@ -26,6 +26,7 @@ async fn connect_with_monitoring() -> Ssh {
         "username": "testuser",
         "authMethod": "password",
         "password": "testpass",
+        "strictHostKeyChecking": "off",
         "enableMonitoring": true
     });
     ssh.connect(settings)
@@ -225,3 +226,67 @@ async fn mon_04_stats_under_load() {
         .await
         .expect("Unsubscribe should work");
 }
+
+// ── MON-05: Monitoring alongside interactive typing ─────────────────
+
+#[tokio::test]
+async fn mon_05_monitoring_does_not_stall_shell() {
+    require_docker!(PORT_SSH_PASSWORD);
+
+    let ssh = connect_with_monitoring().await;
+    let mut output = ssh.subscribe_output();
+    let provider = ssh
+        .monitoring()
+        .expect("Monitoring provider should be available");
+    let mut rx = provider
+        .subscribe()
+        .await
+        .expect("Subscribe should succeed");
+
+    // Type commands while monitoring execs run on the same session. Each
+    // keystroke batch must be accepted promptly and its output must arrive,
+    // and stats must keep flowing in the meantime.
+    let mut received = String::new();
+    let mut sample_count = 0;
+    for i in 0..10 {
+        let started = std::time::Instant::now();
+        ssh.write(format!("echo $((1000+{i}))done\n").as_bytes())
+            .expect("Write should succeed");
+        assert!(
+            started.elapsed() < Duration::from_secs(1),
+            "MON-05: write {i} stalled for {:?}",
+            started.elapsed()
+        );
+
+        let marker = format!("{}done", 1000 + i);
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while !received.contains(&marker) {
+            let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+            match tokio::time::timeout(remaining, output.recv()).await {
+                Ok(Some(chunk)) => received.push_str(&String::from_utf8_lossy(&chunk)),
+                _ => panic!("MON-05: output of command {i} did not arrive"),
+            }
+        }
+
+        while rx.try_recv().is_ok() {
+            sample_count += 1;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while sample_count < 2 && tokio::time::Instant::now() < deadline {
+        if let Ok(Some(_)) = tokio::time::timeout(Duration::from_secs(5), rx.recv()).await {
+            sample_count += 1;
+        }
+    }
+    assert!(
+        sample_count >= 2,
+        "MON-05: Should receive stats while typing, got {sample_count}"
+    );
+
+    provider
+        .unsubscribe()
+        .await
+        .expect("Unsubscribe should work");
+}
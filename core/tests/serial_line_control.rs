@@ -0,0 +1,150 @@
+//! Serial Line Control Integration Tests (SERIAL-LC-01 through SERIAL-LC-02).
+//!
+//! Tests BREAK and DTR/RTS control on the serial backend against a virtual
+//! serial port pair created with `socat`. A pty has no modem lines and
+//! ignores breaks, so these tests check that the controls can be issued
+//! and leave the session usable, not their electrical effect.
+//!
+//! Requires: `socat` on the `PATH` (Linux/macOS).
+//! Skips gracefully if it is not available.
+
+#![cfg(all(feature = "serial", unix))]
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use termihub_core::backends::serial::Serial;
+use termihub_core::connection::{ConnectionType, LineControl};
+
+/// A linked pair of virtual serial ports, killed when dropped.
+struct VirtualPortPair {
+    child: Child,
+    _dir: tempfile::TempDir,
+    a: PathBuf,
+    b: PathBuf,
+}
+
+impl VirtualPortPair {
+    /// Start `socat` linking two ptys, or `None` if it is not available.
+    fn start() -> Option<Self> {
+        let dir = tempfile::tempdir().ok()?;
+        let a = dir.path().join("tty-a");
+        let b = dir.path().join("tty-b");
+        let child = Command::new("socat")
+            .arg(format!("pty,raw,echo=0,link={}", a.display()))
+            .arg(format!("pty,raw,echo=0,link={}", b.display()))
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .ok()?;
+        let mut pair = Self {
+            child,
+            _dir: dir,
+            a,
+            b,
+        };
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !(pair.a.exists() && pair.b.exists()) {
+            if Instant::now() > deadline || pair.child.try_wait().ok().flatten().is_some() {
+                return None;
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        Some(pair)
+    }
+}
+
+impl Drop for VirtualPortPair {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+macro_rules! require_virtual_ports {
+    () => {
+        match VirtualPortPair::start() {
+            Some(pair) => pair,
+            None => {
+                eprintln!("SKIPPED: socat not available to create a virtual serial port");
+                return;
+            }
+        }
+    };
+}
+
+fn settings(port: &Path) -> serde_json::Value {
+    serde_json::json!({
+        "port": port.display().to_string(),
+        "baudRate": "115200",
+    })
+}
+
+/// Read from the far end of the pair until `expected` arrives.
+fn read_until(port: &Path, expected: &[u8]) -> Vec<u8> {
+    let mut far = serialport::new(port.display().to_string(), 115200)
+        .timeout(Duration::from_millis(200))
+        .open()
+        .expect("Failed to open far end");
+    let mut received = Vec::new();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let mut buf = [0u8; 256];
+    while !received.windows(expected.len()).any(|w| w == expected) {
+        assert!(
+            Instant::now() < deadline,
+            "Expected {expected:?}, got {received:?}"
+        );
+        if let Ok(n) = far.read(&mut buf) {
+            received.extend_from_slice(&buf[..n]);
+        }
+    }
+    received
+}
+
+// ── SERIAL-LC-01: Break keeps the session usable ─────────────────────
+
+#[tokio::test]
+async fn serial_lc_01_break_then_write() {
+    let pair = require_virtual_ports!();
+
+    let mut serial = Serial::new();
+    serial
+        .connect(settings(&pair.a))
+        .await
+        .expect("SERIAL-LC-01: Connect should succeed");
+
+    serial
+        .line_control(LineControl::Break { duration_ms: 50 })
+        .expect("SERIAL-LC-01: Break should start");
+    serial.write(b"after-break").unwrap();
+    read_until(&pair.b, b"after-break");
+
+    serial.disconnect().await.unwrap();
+}
+
+// ── SERIAL-LC-02: Modem lines on a port without them ─────────────────
+
+#[tokio::test]
+async fn serial_lc_02_modem_lines_on_pty() {
+    let pair = require_virtual_ports!();
+
+    let mut serial = Serial::new();
+    let mut settings = settings(&pair.a);
+    settings["dtr"] = serde_json::json!("off");
+    settings["rts"] = serde_json::json!("on");
+    serial
+        .connect(settings)
+        .await
+        .expect("SERIAL-LC-02: Unsupported initial line levels must not fail connect");
+
+    // A pty may refuse the ioctl; either way the session keeps working.
+    let _ = serial.line_control(LineControl::SetDtr { level: true });
+    let _ = serial.line_control(LineControl::SetRts { level: false });
+    assert!(serial.is_connected());
+    serial.write(b"lines-ok").unwrap();
+    read_until(&pair.b, b"lines-ok");
+
+    serial.disconnect().await.unwrap();
+}
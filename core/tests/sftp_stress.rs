@@ -1,4 +1,4 @@
-//! SFTP Stress Integration Tests (SFTP-STRESS-01 through SFTP-STRESS-16).
+//! SFTP Stress Integration Tests (SFTP-STRESS-01 through SFTP-STRESS-21).
 //!
 //! Tests termiHub's SFTP file browser against the pre-populated
 //! `sftp-stress` container with large files, deep trees, symlinks,
@@ -11,9 +11,16 @@
 
 mod common;
 
+use std::path::Path;
+
 use common::{require_docker, PORT_SFTP_STRESS};
-use termihub_core::backends::ssh::Ssh;
+use termihub_core::backends::ssh::archive::download_archive;
+use termihub_core::backends::ssh::auth::connect_for_transfer;
+use termihub_core::backends::ssh::transfer::{self, TransferProgress};
+use termihub_core::backends::ssh::{parse_ssh_settings, Ssh};
 use termihub_core::connection::ConnectionType;
+use termihub_core::files::ArchiveFormat;
+use tokio_util::sync::CancellationToken;
 
 /// Connect to the SFTP stress container and return an Ssh instance
 /// with file browser enabled.
@@ -25,6 +32,7 @@ async fn connect_sftp() -> Ssh {
         "username": "testuser",
         "authMethod": "password",
         "password": "testpass",
+        "strictHostKeyChecking": "off",
         "enableFileBrowser": true
     });
     ssh.connect(settings)
@@ -423,3 +431,503 @@ async fn sftp_stress_16_permission_000_directory() {
         "SFTP-STRESS-16: Permission 000 directory should return access denied error"
     );
 }
+
+// ── SFTP-STRESS-17: Compressed transfer with progress and resume ────
+
+/// Open a compressed SFTP session to the stress container.
+fn open_compressed_sftp() -> (ssh2::Session, ssh2::Sftp) {
+    let config = parse_ssh_settings(&serde_json::json!({
+        "host": "127.0.0.1",
+        "port": PORT_SFTP_STRESS,
+        "username": "testuser",
+        "authMethod": "password",
+        "password": "testpass",
+        "strictHostKeyChecking": "off",
+        "transferCompression": true
+    }));
+    let session = connect_for_transfer(&config).expect("compressed SSH session should open");
+    let sftp = session.sftp().expect("SFTP subsystem should start");
+    (session, sftp)
+}
+
+/// Progress callback that cancels the transfer once `limit` bytes are
+/// at the destination, recording every update.
+fn cancel_after<'a>(
+    limit: u64,
+    cancel: &CancellationToken,
+    seen: &'a mut Vec<TransferProgress>,
+) -> impl FnMut(TransferProgress) + 'a {
+    let cancel = cancel.clone();
+    move |p| {
+        seen.push(p);
+        if p.transferred >= limit {
+            cancel.cancel();
+        }
+    }
+}
+
+fn assert_progress_consistent(seen: &[TransferProgress], total: u64, label: &str) {
+    assert!(
+        seen.iter()
+            .all(|p| p.total == total && p.transferred <= total),
+        "{label}: progress must be reported against the uncompressed size"
+    );
+    assert!(
+        seen.windows(2)
+            .all(|w| w[0].transferred <= w[1].transferred),
+        "{label}: progress must not go backwards"
+    );
+}
+
+#[test]
+fn sftp_stress_17_compressed_transfer_resume() {
+    require_docker!(PORT_SFTP_STRESS);
+
+    const TOTAL: u64 = 10_485_760;
+    const CUT: u64 = 4 * 1_048_576;
+    let source = Path::new("/home/testuser/sftp-test/large-files/10mb.bin");
+    let upload_path = Path::new("/home/testuser/sftp-test/resume-upload.bin");
+    let dir = tempfile::tempdir().unwrap();
+    let (_session, sftp) = open_compressed_sftp();
+
+    // Download: interrupt part-way, then resume.
+    let partial = dir.path().join("download.bin");
+    let cancel = CancellationToken::new();
+    let mut seen = Vec::new();
+    let result = transfer::download(
+        &sftp,
+        source,
+        &partial,
+        false,
+        &cancel,
+        &mut cancel_after(CUT, &cancel, &mut seen),
+    );
+    assert!(
+        result.is_err(),
+        "SFTP-STRESS-17: download should be cancelled"
+    );
+    let partial_len = std::fs::metadata(&partial).unwrap().len();
+    assert!(
+        (CUT..TOTAL).contains(&partial_len),
+        "SFTP-STRESS-17: partial download should stop mid-file, got {partial_len} bytes"
+    );
+
+    let resumed = transfer::download(
+        &sftp,
+        source,
+        &partial,
+        true,
+        &CancellationToken::new(),
+        &mut |p| seen.push(p),
+    )
+    .expect("SFTP-STRESS-17: resumed download should succeed");
+    assert_eq!(resumed, TOTAL - partial_len);
+    assert_eq!(seen.last().unwrap().transferred, TOTAL);
+    assert_progress_consistent(&seen, TOTAL, "SFTP-STRESS-17 download");
+
+    let full = dir.path().join("full.bin");
+    transfer::download(
+        &sftp,
+        source,
+        &full,
+        false,
+        &CancellationToken::new(),
+        &mut |_| {},
+    )
+    .expect("SFTP-STRESS-17: reference download should succeed");
+    assert!(
+        std::fs::read(&partial).unwrap() == std::fs::read(&full).unwrap(),
+        "SFTP-STRESS-17: resumed download must be byte-identical"
+    );
+
+    // Upload: interrupt part-way, then resume.
+    let cancel = CancellationToken::new();
+    let mut seen = Vec::new();
+    let result = transfer::upload(
+        &sftp,
+        &full,
+        upload_path,
+        false,
+        &cancel,
+        &mut cancel_after(CUT, &cancel, &mut seen),
+    );
+    assert!(
+        result.is_err(),
+        "SFTP-STRESS-17: upload should be cancelled"
+    );
+
+    transfer::upload(
+        &sftp,
+        &full,
+        upload_path,
+        true,
+        &CancellationToken::new(),
+        &mut |p| seen.push(p),
+    )
+    .expect("SFTP-STRESS-17: resumed upload should succeed");
+    assert_eq!(seen.last().unwrap().transferred, TOTAL);
+    assert_progress_consistent(&seen, TOTAL, "SFTP-STRESS-17 upload");
+
+    let round_trip = dir.path().join("round-trip.bin");
+    transfer::download(
+        &sftp,
+        upload_path,
+        &round_trip,
+        false,
+        &CancellationToken::new(),
+        &mut |_| {},
+    )
+    .expect("SFTP-STRESS-17: download of resumed upload should succeed");
+    assert!(
+        std::fs::read(&round_trip).unwrap() == std::fs::read(&full).unwrap(),
+        "SFTP-STRESS-17: resumed upload must be byte-identical"
+    );
+
+    // Clean up.
+    let _ = sftp.unlink(upload_path);
+}
+
+// ── SFTP-STRESS-18: Directory downloaded as a tar.gz archive ────────
+
+#[test]
+fn sftp_stress_18_download_directory_archive() {
+    require_docker!(PORT_SFTP_STRESS);
+
+    let (session, sftp) = open_compressed_sftp();
+    let root = Path::new("/tmp/termihub-archive-test");
+    let files: [(&str, Vec<u8>); 4] = [
+        ("top.txt", b"top level\n".to_vec()),
+        ("with space.txt", b"spaced name\n".to_vec()),
+        (
+            "nested/inner.bin",
+            (0..100_000u32).map(|i| (i % 251) as u8).collect(),
+        ),
+        ("nested/deeper/leaf.txt", b"leaf\n".to_vec()),
+    ];
+
+    common::ssh_exec(
+        &session,
+        &format!(
+            "rm -rf {0} && mkdir -p {0}/src/nested/deeper",
+            root.display()
+        ),
+    )
+    .expect("SFTP-STRESS-18: remote setup should succeed");
+    for (name, data) in &files {
+        let mut file = sftp
+            .create(&root.join("src").join(name))
+            .expect("SFTP-STRESS-18: remote file should be created");
+        std::io::Write::write_all(&mut file, data).unwrap();
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let archive = dir.path().join("src.tar.gz");
+    let mut seen = Vec::new();
+    let written = download_archive(
+        &session,
+        "/tmp/termihub-archive-test/src",
+        ArchiveFormat::TarGz,
+        &archive,
+        &CancellationToken::new(),
+        &mut |n| seen.push(n),
+    )
+    .expect("SFTP-STRESS-18: archive download should succeed");
+
+    assert_eq!(written, std::fs::metadata(&archive).unwrap().len());
+    assert_eq!(seen.last(), Some(&written));
+
+    let extract = dir.path().join("extract");
+    std::fs::create_dir(&extract).unwrap();
+    let status = std::process::Command::new("tar")
+        .arg("-xzf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(&extract)
+        .status()
+        .expect("tar should be available locally");
+    assert!(status.success(), "SFTP-STRESS-18: archive should extract");
+
+    for (name, data) in &files {
+        let local = std::fs::read(extract.join("src").join(name))
+            .unwrap_or_else(|e| panic!("SFTP-STRESS-18: {name} missing from archive: {e}"));
+        assert!(
+            &local == data,
+            "SFTP-STRESS-18: {name} must be byte-identical after extraction"
+        );
+    }
+
+    // Clean up.
+    let _ = common::ssh_exec(&session, &format!("rm -rf {}", root.display()));
+}
+
+// ── SFTP-STRESS-19: Nested directory tree round-trip ────────────────
+
+/// Relative path and contents of every regular file below `root`.
+fn read_tree(root: &Path) -> Vec<(String, Vec<u8>)> {
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            let meta = std::fs::symlink_metadata(&path).unwrap();
+            if meta.is_dir() {
+                pending.push(path);
+            } else if meta.is_file() {
+                let name = path
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string();
+                files.push((name, std::fs::read(&path).unwrap()));
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+#[test]
+#[cfg(unix)]
+fn sftp_stress_19_directory_tree_roundtrip() {
+    require_docker!(PORT_SFTP_STRESS);
+
+    let (session, sftp) = open_compressed_sftp();
+    let remote_root = Path::new("/tmp/termihub-dir-transfer-test");
+    common::ssh_exec(&session, &format!("rm -rf {}", remote_root.display()))
+        .expect("SFTP-STRESS-19: remote cleanup should succeed");
+
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("src");
+    let files: [(&str, Vec<u8>); 5] = [
+        ("top.txt", b"top level\n".to_vec()),
+        ("with space.txt", b"spaced name\n".to_vec()),
+        ("empty.txt", Vec::new()),
+        (
+            "nested/inner.bin",
+            (0..100_000u32).map(|i| (i % 251) as u8).collect(),
+        ),
+        ("nested/deeper/leaf.txt", b"leaf\n".to_vec()),
+    ];
+    for (name, data) in &files {
+        let path = source.join(name);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, data).unwrap();
+    }
+    std::fs::create_dir(source.join("nested/empty-dir")).unwrap();
+    std::os::unix::fs::symlink(source.join("top.txt"), source.join("link-to-top")).unwrap();
+    std::os::unix::fs::symlink(&source, source.join("nested/loop")).unwrap();
+
+    // Upload without following symlinks.
+    let mut uploaded_paths = Vec::new();
+    let upload = transfer::upload_dir(
+        &sftp,
+        &source,
+        &remote_root.join("tree"),
+        false,
+        &CancellationToken::new(),
+        &mut |path, p| {
+            if p.transferred == p.total {
+                uploaded_paths.push(path.to_path_buf());
+            }
+        },
+    )
+    .expect("SFTP-STRESS-19: directory upload should succeed");
+    assert!(
+        upload.failed.is_empty(),
+        "SFTP-STRESS-19: upload failures: {:?}",
+        upload.failed
+    );
+    assert_eq!(upload.succeeded.len(), files.len());
+    assert_eq!(upload.skipped.len(), 2, "both symlinks should be skipped");
+    assert_eq!(
+        upload.bytes_transferred,
+        files.iter().map(|(_, d)| d.len() as u64).sum::<u64>()
+    );
+    assert!(uploaded_paths
+        .iter()
+        .all(|p| p.starts_with(remote_root.join("tree"))));
+
+    // Download the uploaded tree and compare.
+    let copy = dir.path().join("copy");
+    let download = transfer::download_dir(
+        &sftp,
+        &remote_root.join("tree"),
+        &copy,
+        false,
+        &CancellationToken::new(),
+        &mut |_, _| {},
+    )
+    .expect("SFTP-STRESS-19: directory download should succeed");
+    assert!(
+        download.failed.is_empty(),
+        "SFTP-STRESS-19: download failures: {:?}",
+        download.failed
+    );
+    let mut expected: Vec<(String, Vec<u8>)> = files
+        .iter()
+        .map(|(name, data)| (name.to_string(), data.clone()))
+        .collect();
+    expected.sort();
+    assert_eq!(
+        read_tree(&copy),
+        expected,
+        "SFTP-STRESS-19: tree must round-trip byte-identical"
+    );
+    assert!(copy.join("nested/empty-dir").is_dir());
+
+    // Following symlinks copies the link target and stops at the loop.
+    common::ssh_exec(
+        &session,
+        &format!(
+            "cd {}/tree && ln -s top.txt link-to-top && ln -s .. nested/loop && ln -s /nonexistent broken",
+            remote_root.display()
+        ),
+    )
+    .expect("SFTP-STRESS-19: remote symlinks should be created");
+    let followed_copy = dir.path().join("followed");
+    let followed = transfer::download_dir(
+        &sftp,
+        &remote_root.join("tree"),
+        &followed_copy,
+        true,
+        &CancellationToken::new(),
+        &mut |_, _| {},
+    )
+    .expect("SFTP-STRESS-19: following download should succeed");
+    assert_eq!(
+        std::fs::read(followed_copy.join("link-to-top")).unwrap(),
+        b"top level\n"
+    );
+    assert_eq!(followed.skipped.len(), 1, "the loop should be skipped once");
+    assert_eq!(followed.failed.len(), 1, "the broken link should fail");
+    assert!(followed.failed[0].path.ends_with("broken"));
+    assert_eq!(followed.succeeded.len(), files.len() + 1);
+
+    // Clean up.
+    let _ = common::ssh_exec(&session, &format!("rm -rf {}", remote_root.display()));
+}
+
+// ── SFTP-STRESS-20: Resume from a truncated file, then verify ───────
+
+#[test]
+fn sftp_stress_20_resume_truncated_and_verify() {
+    require_docker!(PORT_SFTP_STRESS);
+
+    const TOTAL: u64 = 10_485_760;
+    let source = Path::new("/home/testuser/sftp-test/large-files/10mb.bin");
+    let upload_path = Path::new("/home/testuser/sftp-test/truncated-upload.bin");
+    let dir = tempfile::tempdir().unwrap();
+    let (session, sftp) = open_compressed_sftp();
+    let no_cancel = CancellationToken::new();
+
+    // Download: truncate the local copy, then resume it.
+    let local = dir.path().join("download.bin");
+    transfer::download(&sftp, source, &local, false, &no_cancel, &mut |_| {})
+        .expect("SFTP-STRESS-20: initial download should succeed");
+    let reference = std::fs::read(&local).unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(&local)
+        .unwrap()
+        .set_len(TOTAL / 3)
+        .unwrap();
+
+    let resumed = transfer::download(&sftp, source, &local, true, &no_cancel, &mut |_| {})
+        .expect("SFTP-STRESS-20: resumed download should succeed");
+    assert_eq!(resumed, TOTAL - TOTAL / 3);
+    assert!(
+        std::fs::read(&local).unwrap() == reference,
+        "SFTP-STRESS-20: resumed download must be byte-identical"
+    );
+    transfer::verify_checksum(&session, &local, source)
+        .expect("SFTP-STRESS-20: resumed download should verify");
+
+    // Upload: truncate the remote copy, then resume it.
+    transfer::upload(&sftp, &local, upload_path, false, &no_cancel, &mut |_| {})
+        .expect("SFTP-STRESS-20: initial upload should succeed");
+    sftp.setstat(
+        upload_path,
+        ssh2::FileStat {
+            size: Some(TOTAL / 2),
+            uid: None,
+            gid: None,
+            perm: None,
+            atime: None,
+            mtime: None,
+        },
+    )
+    .expect("SFTP-STRESS-20: remote truncate should succeed");
+
+    let resumed = transfer::upload(&sftp, &local, upload_path, true, &no_cancel, &mut |_| {})
+        .expect("SFTP-STRESS-20: resumed upload should succeed");
+    assert_eq!(resumed, TOTAL - TOTAL / 2);
+    transfer::verify_checksum(&session, &local, upload_path)
+        .expect("SFTP-STRESS-20: resumed upload should verify");
+
+    let round_trip = dir.path().join("round-trip.bin");
+    transfer::download(
+        &sftp,
+        upload_path,
+        &round_trip,
+        false,
+        &no_cancel,
+        &mut |_| {},
+    )
+    .expect("SFTP-STRESS-20: download of resumed upload should succeed");
+    assert!(
+        std::fs::read(&round_trip).unwrap() == reference,
+        "SFTP-STRESS-20: resumed upload must be byte-identical"
+    );
+
+    // A partial file that is not a prefix of the source resumes into a
+    // corrupt copy, which verification catches.
+    std::fs::write(&local, vec![0u8; (TOTAL / 4) as usize]).unwrap();
+    transfer::download(&sftp, source, &local, true, &no_cancel, &mut |_| {})
+        .expect("SFTP-STRESS-20: download onto a bad prefix should succeed");
+    let err = transfer::verify_checksum(&session, &local, source)
+        .expect_err("SFTP-STRESS-20: a corrupt copy must fail verification");
+    assert!(err.to_string().contains("Checksum mismatch"), "{err}");
+
+    // Clean up.
+    let _ = sftp.unlink(upload_path);
+}
+
+// ── SFTP-STRESS-21: mkdir creates missing parents only when recursive ─
+
+#[tokio::test]
+async fn sftp_stress_21_mkdir_needs_recursive_for_missing_parents() {
+    require_docker!(PORT_SFTP_STRESS);
+
+    let root = "/home/testuser/sftp-test/mkdir-recursive";
+    let nested = format!("{root}/a/b/c");
+    let ssh = connect_sftp().await;
+    let browser = ssh
+        .file_browser()
+        .expect("File browser should be available");
+
+    assert!(
+        browser.mkdir(&nested, false).await.is_err(),
+        "SFTP-STRESS-21: non-recursive mkdir under a missing parent should fail"
+    );
+    assert!(
+        browser.stat(&format!("{root}/a")).await.is_err(),
+        "SFTP-STRESS-21: a failed mkdir should not create parents"
+    );
+
+    browser
+        .mkdir(&nested, true)
+        .await
+        .expect("SFTP-STRESS-21: recursive mkdir should create missing parents");
+    let entry = browser
+        .stat(&nested)
+        .await
+        .expect("SFTP-STRESS-21: nested directory should exist");
+    assert!(
+        entry.is_directory,
+        "SFTP-STRESS-21: {nested} should be a directory"
+    );
+
+    // Clean up.
+    let (session, _sftp) = open_compressed_sftp();
+    let _ = common::ssh_exec(&session, &format!("rm -rf {root}"));
+}
@@ -2,7 +2,10 @@
 //!
 //! Tests termiHub's SSH backend for advanced scenarios:
 //! - SSH-JUMP-01: 2-hop ProxyJump via bastion (port 2204 → internal target)
+//! - SSH-JUMP-02..05: `proxy_jump` connections through the bastion, per-hop
+//!   credentials, multi-hop chains, and bastion vs target auth failures
 //! - SSH-SHELL-01/02: Restricted shell (rbash) on port 2205
+//! - SSH-SHELL-03/04: Capabilities of SSH sessions with and without rbash
 //! - SSH-TUNNEL-01/02: Port forwarding through SSH tunnel on port 2207
 //!
 //! Requires: `docker compose -f tests/docker/docker-compose.yml up -d`
@@ -11,10 +14,23 @@
 mod common;
 
 use common::{
-    require_docker, ssh_exec, ssh_key_config, ssh_password_config, PORT_SSH_BASTION,
-    PORT_SSH_RESTRICTED, PORT_SSH_TUNNEL,
+    require_docker, ssh_exec, ssh_key_config, ssh_password_config, ssh_password_settings,
+    PORT_SSH_BASTION, PORT_SSH_RESTRICTED, PORT_SSH_TUNNEL,
 };
 use termihub_core::backends::ssh::auth::connect_and_authenticate;
+use termihub_core::backends::ssh::Ssh;
+use termihub_core::config::SshConfig;
+use termihub_core::connection::ConnectionType;
+use termihub_core::errors::SessionError;
+
+/// Key-auth config for `host:22` as seen from inside the Docker networks.
+fn internal_key_config(host: &str) -> SshConfig {
+    SshConfig {
+        host: host.to_string(),
+        port: 22,
+        ..ssh_key_config(PORT_SSH_BASTION, "ed25519")
+    }
+}
 
 // ── SSH-JUMP-01: 2-hop ProxyJump chain ───────────────────────────────
 
@@ -55,6 +71,118 @@ fn ssh_jump_01_two_hop_proxy_jump() {
         .expect("SSH-JUMP-01: Direct-tcpip channel to target should succeed");
 }
 
+// ── SSH-JUMP-02: ProxyJump through the bastion ───────────────────────
+
+#[test]
+fn ssh_jump_02_proxy_jump_reaches_internal_target() {
+    require_docker!(PORT_SSH_BASTION);
+
+    // The target is only reachable from the bastion's internal network.
+    let config = SshConfig {
+        proxy_jump: Some(format!("127.0.0.1:{PORT_SSH_BASTION}")),
+        ..internal_key_config("termihub-ssh-target")
+    };
+    let session = connect_and_authenticate(&config)
+        .expect("SSH-JUMP-02: Connection through the bastion should succeed");
+
+    let output = ssh_exec(&session, "cat /home/testuser/marker.txt").expect("Command should run");
+    assert!(
+        output.contains("JUMPHOST_TARGET_REACHED"),
+        "SSH-JUMP-02: Expected marker 'JUMPHOST_TARGET_REACHED', got: {output}"
+    );
+}
+
+// ── SSH-JUMP-03: Per-hop credentials ─────────────────────────────────
+
+#[test]
+fn ssh_jump_03_jump_hosts_use_their_own_credentials() {
+    require_docker!(PORT_SSH_BASTION);
+    require_docker!(PORT_SSH_TUNNEL);
+
+    // The bastion only accepts keys, the tunnel target is reached with a
+    // password.
+    let config = SshConfig {
+        host: "termihub-ssh-tunnel".to_string(),
+        port: 22,
+        jump_hosts: vec![ssh_key_config(PORT_SSH_BASTION, "ed25519")],
+        ..ssh_password_config(PORT_SSH_TUNNEL)
+    };
+    let session = connect_and_authenticate(&config)
+        .expect("SSH-JUMP-03: Password login through a key-auth bastion should succeed");
+
+    let output = ssh_exec(&session, "echo JUMP03_OK").expect("Command should run");
+    assert!(output.contains("JUMP03_OK"), "SSH-JUMP-03: got: {output}");
+}
+
+// ── SSH-JUMP-04: Multi-hop chain ─────────────────────────────────────
+
+#[test]
+fn ssh_jump_04_multi_hop_chain() {
+    require_docker!(PORT_SSH_BASTION);
+
+    // Hop 1 is the bastion's published port, hop 2 the bastion again by
+    // its internal name, then on to the internal target.
+    let config = SshConfig {
+        proxy_jump: Some(format!(
+            "127.0.0.1:{PORT_SSH_BASTION},testuser@termihub-ssh-bastion:22"
+        )),
+        ..internal_key_config("termihub-ssh-target")
+    };
+    let session = connect_and_authenticate(&config)
+        .expect("SSH-JUMP-04: Connection through two hops should succeed");
+
+    let output = ssh_exec(&session, "cat /home/testuser/marker.txt").expect("Command should run");
+    assert!(
+        output.contains("JUMPHOST_TARGET_REACHED"),
+        "SSH-JUMP-04: Expected marker 'JUMPHOST_TARGET_REACHED', got: {output}"
+    );
+}
+
+// ── SSH-JUMP-05: Bastion vs target auth failures ─────────────────────
+
+#[test]
+fn ssh_jump_05_bastion_and_target_auth_failures_differ() {
+    require_docker!(PORT_SSH_BASTION);
+    require_docker!(PORT_SSH_TUNNEL);
+
+    let bastion_label = format!("Jump host 127.0.0.1:{PORT_SSH_BASTION}");
+
+    // The bastion rejects passwords.
+    let bastion_rejects = SshConfig {
+        host: "termihub-ssh-tunnel".to_string(),
+        port: 22,
+        proxy_jump: Some(format!("127.0.0.1:{PORT_SSH_BASTION}")),
+        ..ssh_password_config(PORT_SSH_TUNNEL)
+    };
+    let Err(err) = connect_and_authenticate(&bastion_rejects) else {
+        panic!("SSH-JUMP-05: Password auth at the bastion should fail");
+    };
+    assert!(
+        err.to_string().contains(&bastion_label),
+        "SSH-JUMP-05: Bastion failure should name the bastion, got: {err}"
+    );
+
+    // The bastion accepts the key, the target rejects the password.
+    let target_rejects = SshConfig {
+        host: "termihub-ssh-tunnel".to_string(),
+        port: 22,
+        password: Some("wrongpass".to_string()),
+        jump_hosts: vec![ssh_key_config(PORT_SSH_BASTION, "ed25519")],
+        ..ssh_password_config(PORT_SSH_TUNNEL)
+    };
+    let Err(err) = connect_and_authenticate(&target_rejects) else {
+        panic!("SSH-JUMP-05: Wrong password at the target should fail");
+    };
+    assert!(
+        matches!(err, SessionError::SpawnFailed(_)),
+        "SSH-JUMP-05: unexpected error: {err}"
+    );
+    assert!(
+        !err.to_string().contains("Jump host"),
+        "SSH-JUMP-05: Target failure should not blame a jump host, got: {err}"
+    );
+}
+
 // ── SSH-SHELL-01: Restricted shell (rbash) ───────────────────────────
 
 #[test]
@@ -91,6 +219,7 @@ fn ssh_shell_02_unrestricted_shell() {
         username: "freeuser".to_string(),
         auth_method: "password".to_string(),
         password: Some("testpass".to_string()),
+        strict_host_key_checking: "off".to_string(),
         ..Default::default()
     };
 
@@ -107,6 +236,54 @@ fn ssh_shell_02_unrestricted_shell() {
     );
 }
 
+// ── SSH-SHELL-03: Restricted shell downgrades capabilities ───────────
+
+#[tokio::test]
+async fn ssh_shell_03_restricted_shell_downgrades_capabilities() {
+    require_docker!(PORT_SSH_RESTRICTED);
+
+    let mut ssh = Ssh::new();
+    ssh.connect(ssh_password_settings(PORT_SSH_RESTRICTED))
+        .await
+        .expect("SSH-SHELL-03: Restricted shell connection should succeed");
+
+    let caps = ssh.capabilities();
+    assert!(
+        !caps.monitoring && !caps.file_browser,
+        "SSH-SHELL-03: monitoring and file browsing should be off under rbash with an external sftp-server"
+    );
+    assert!(ssh.monitoring().is_none());
+    assert!(ssh.file_browser().is_none());
+    let codes: Vec<String> = ssh.notices().into_iter().map(|n| n.code).collect();
+    assert_eq!(codes, vec!["restrictedShell"]);
+    assert!(
+        ssh.is_connected(),
+        "SSH-SHELL-03: terminal must stay usable"
+    );
+
+    ssh.disconnect().await.unwrap();
+}
+
+// ── SSH-SHELL-04: Unrestricted shell keeps capabilities ──────────────
+
+#[tokio::test]
+async fn ssh_shell_04_unrestricted_shell_keeps_capabilities() {
+    require_docker!(PORT_SSH_RESTRICTED);
+
+    let mut settings = ssh_password_settings(PORT_SSH_RESTRICTED);
+    settings["username"] = serde_json::json!("freeuser");
+    let mut ssh = Ssh::new();
+    ssh.connect(settings)
+        .await
+        .expect("SSH-SHELL-04: Unrestricted shell connection should succeed");
+
+    let caps = ssh.capabilities();
+    assert!(caps.monitoring && caps.file_browser);
+    assert!(ssh.notices().is_empty());
+
+    ssh.disconnect().await.unwrap();
+}
+
 // ── SSH-TUNNEL-01: Local port forward (HTTP) ─────────────────────────
 
 #[test]
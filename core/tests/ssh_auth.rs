@@ -1,4 +1,4 @@
-//! SSH Authentication Integration Tests (SSH-AUTH-01 through SSH-AUTH-15).
+//! SSH Authentication Integration Tests (SSH-AUTH-01 through SSH-AUTH-17).
 //!
 //! Tests termiHub's SSH authentication handling against Docker containers:
 //! - `ssh-password` on port 2201 (password auth)
@@ -14,6 +14,7 @@ use common::{
     ssh_password_config, PORT_SSH_KEYS, PORT_SSH_PASSWORD,
 };
 use termihub_core::backends::ssh::auth::connect_and_authenticate;
+use termihub_core::backends::ssh::host_key::host_key_fingerprint;
 
 // ── SSH-AUTH-01: Password authentication ─────────────────────────────
 
@@ -264,6 +265,7 @@ fn ssh_auth_11_wrong_password_rejected() {
         username: "testuser".to_string(),
         auth_method: "password".to_string(),
         password: Some("wrongpassword".to_string()),
+        strict_host_key_checking: "off".to_string(),
         ..Default::default()
     };
 
@@ -304,6 +306,7 @@ fn ssh_auth_12_wrong_key_rejected() {
         username: "testuser".to_string(),
         auth_method: "key".to_string(),
         key_path: Some(key_path.to_str().unwrap().to_string()),
+        strict_host_key_checking: "off".to_string(),
         ..Default::default()
     };
 
@@ -338,6 +341,7 @@ fn ssh_auth_15_wrong_passphrase_rejected() {
                 .to_string(),
         ),
         password: Some("this-is-not-the-passphrase".to_string()),
+        strict_host_key_checking: "off".to_string(),
         ..Default::default()
     };
 
@@ -347,3 +351,69 @@ fn ssh_auth_15_wrong_passphrase_rejected() {
         "SSH-AUTH-15: Wrong passphrase should be rejected"
     );
 }
+
+// ── SSH-AUTH-16: Identity files tried in order ───────────────────────
+
+/// A non-matching `keyPath` followed by an authorized key in
+/// `identityFiles` should authenticate with the second key.
+#[test]
+fn ssh_auth_16_identity_files_tried_in_order() {
+    require_docker!(PORT_SSH_KEYS);
+
+    let temp_dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let wrong_key_path = temp_dir.path().join("wrong_key");
+    let key =
+        ssh_key::PrivateKey::random(&mut rand::thread_rng(), ssh_key::Algorithm::Ed25519).unwrap();
+    let openssh_pem = key.to_openssh(ssh_key::LineEnding::LF).unwrap();
+    std::fs::write(&wrong_key_path, openssh_pem.as_bytes()).expect("Failed to write temp key");
+
+    let config = termihub_core::config::SshConfig {
+        host: "127.0.0.1".to_string(),
+        port: PORT_SSH_KEYS,
+        username: "testuser".to_string(),
+        auth_method: "key".to_string(),
+        key_path: Some(wrong_key_path.to_str().unwrap().to_string()),
+        identity_files: vec![ssh_keys_dir().join("ed25519").to_str().unwrap().to_string()],
+        strict_host_key_checking: "off".to_string(),
+        ..Default::default()
+    };
+
+    let session = connect_and_authenticate(&config)
+        .expect("SSH-AUTH-16: Second identity file should authenticate");
+    assert!(session.authenticated());
+
+    let output = ssh_exec(&session, "whoami").expect("whoami should succeed");
+    assert!(
+        output.trim().contains("testuser"),
+        "Expected 'testuser', got: {output}"
+    );
+}
+
+// ── SSH-AUTH-17: Host key fingerprint ────────────────────────────────
+
+#[test]
+fn ssh_auth_17_host_key_fingerprint() {
+    require_docker!(PORT_SSH_PASSWORD);
+
+    let config = ssh_password_config(PORT_SSH_PASSWORD);
+    let session =
+        connect_and_authenticate(&config).expect("SSH-AUTH-17: Password auth should succeed");
+
+    let key = host_key_fingerprint(&session).expect("Server should present a host key");
+    assert!(!key.key_type.is_empty());
+    assert_ne!(key.key_type, "unknown");
+    let encoded = key
+        .fingerprint
+        .strip_prefix("SHA256:")
+        .unwrap_or_else(|| panic!("Expected OpenSSH format, got: {}", key.fingerprint));
+    // 32 digest bytes encode to 43 unpadded Base64 characters.
+    assert_eq!(
+        encoded.len(),
+        43,
+        "Unexpected fingerprint: {}",
+        key.fingerprint
+    );
+    assert!(encoded
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '/'));
+}
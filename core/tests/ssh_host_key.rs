@@ -0,0 +1,191 @@
+//! SSH Host Key Verification Integration Tests (SSH-HK-01 through SSH-HK-05).
+//!
+//! Tests that the server's host key is checked against a `known_hosts`
+//! file in each `strictHostKeyChecking` mode. Every test uses its own
+//! temporary `known_hosts` file, so the user's is never touched.
+//!
+//! Container: `ssh-keys` on port 2203.
+//!
+//! Requires: `docker compose -f tests/docker/docker-compose.yml up -d`
+//! Skips gracefully if containers are not running.
+
+mod common;
+
+use std::path::Path;
+
+use common::{require_docker, ssh_key_config, PORT_SSH_KEYS};
+use termihub_core::backends::ssh::auth::connect_and_authenticate;
+use termihub_core::config::SshConfig;
+use termihub_core::errors::SessionError;
+
+/// Key auth config for the ssh-keys container using `known_hosts` in `mode`.
+fn host_key_config(mode: &str, known_hosts: &Path) -> SshConfig {
+    SshConfig {
+        strict_host_key_checking: mode.to_string(),
+        known_hosts_file: Some(known_hosts.to_str().unwrap().to_string()),
+        ..ssh_key_config(PORT_SSH_KEYS, "ed25519")
+    }
+}
+
+/// The container's host key in SSH wire format, fetched without checking.
+fn server_host_key() -> Vec<u8> {
+    let session = connect_and_authenticate(&ssh_key_config(PORT_SSH_KEYS, "ed25519"))
+        .expect("Connection without host key checking should succeed");
+    let (key, _) = session
+        .host_key()
+        .expect("Server should present a host key");
+    key.to_vec()
+}
+
+/// The container's host key of the given host key `method` in SSH wire
+/// format, or `None` if the server has no key of that type.
+fn server_host_key_of_type(method: &str) -> Option<Vec<u8>> {
+    let tcp = std::net::TcpStream::connect(("127.0.0.1", PORT_SSH_KEYS)).unwrap();
+    let mut session = ssh2::Session::new().unwrap();
+    session.set_tcp_stream(tcp);
+    session
+        .method_pref(ssh2::MethodType::HostKey, method)
+        .unwrap();
+    session.handshake().ok()?;
+    session.host_key().map(|(key, _)| key.to_vec())
+}
+
+/// Write a `known_hosts` file at `path` recording a key of the server's
+/// type that differs from its real one in the last byte. Returns the
+/// file's contents.
+fn write_forged_known_hosts(path: &Path) -> String {
+    let mut key = server_host_key();
+    *key.last_mut().unwrap() ^= 0xff;
+    let forged = ssh_key::PublicKey::from_bytes(&key)
+        .and_then(|k| k.to_openssh())
+        .expect("Forged key should encode");
+    let contents = format!("[127.0.0.1]:{PORT_SSH_KEYS} {forged}\n");
+    std::fs::write(path, &contents).unwrap();
+    contents
+}
+
+// ── SSH-HK-01: accept-new records an unknown host ────────────────────
+
+#[test]
+fn ssh_hk_01_accept_new_records_unknown_host() {
+    require_docker!(PORT_SSH_KEYS);
+
+    let dir = tempfile::tempdir().unwrap();
+    let known_hosts = dir.path().join("known_hosts");
+    let config = host_key_config("accept-new", &known_hosts);
+
+    connect_and_authenticate(&config).expect("SSH-HK-01: Unknown host should be accepted");
+    let recorded = std::fs::read_to_string(&known_hosts).expect("known_hosts should be created");
+    assert!(
+        recorded.starts_with(&format!("[127.0.0.1]:{PORT_SSH_KEYS} ")),
+        "SSH-HK-01: Host should be recorded, got: {recorded}"
+    );
+
+    connect_and_authenticate(&config).expect("SSH-HK-01: Recorded host should match");
+    assert_eq!(
+        std::fs::read_to_string(&known_hosts).unwrap(),
+        recorded,
+        "SSH-HK-01: A matching host must not be recorded twice"
+    );
+}
+
+// ── SSH-HK-02: strict rejects an unknown host ────────────────────────
+
+#[test]
+fn ssh_hk_02_strict_rejects_unknown_host() {
+    require_docker!(PORT_SSH_KEYS);
+
+    let dir = tempfile::tempdir().unwrap();
+    let known_hosts = dir.path().join("known_hosts");
+
+    let Err(err) = connect_and_authenticate(&host_key_config("strict", &known_hosts)) else {
+        panic!("SSH-HK-02: Unknown host should be rejected in strict mode");
+    };
+    let message = err.to_string();
+    assert!(message.contains("SHA256:"), "SSH-HK-02: {message}");
+    assert!(
+        !known_hosts.exists(),
+        "SSH-HK-02: Strict mode must not record the host"
+    );
+
+    // Once recorded, strict mode connects.
+    connect_and_authenticate(&host_key_config("accept-new", &known_hosts))
+        .expect("SSH-HK-02: accept-new should record the host");
+    connect_and_authenticate(&host_key_config("strict", &known_hosts))
+        .expect("SSH-HK-02: Recorded host should connect in strict mode");
+}
+
+// ── SSH-HK-03: Changed host key is a mismatch ────────────────────────
+
+#[test]
+fn ssh_hk_03_changed_key_is_rejected() {
+    require_docker!(PORT_SSH_KEYS);
+
+    let dir = tempfile::tempdir().unwrap();
+    let known_hosts = dir.path().join("known_hosts");
+    write_forged_known_hosts(&known_hosts);
+
+    for mode in ["accept-new", "strict"] {
+        let Err(err) = connect_and_authenticate(&host_key_config(mode, &known_hosts)) else {
+            panic!("SSH-HK-03: Changed key should be rejected in {mode} mode");
+        };
+        let SessionError::HostKeyMismatch { host, fingerprint } = err else {
+            panic!("SSH-HK-03: Expected HostKeyMismatch in {mode} mode, got: {err}");
+        };
+        assert_eq!(host, format!("[127.0.0.1]:{PORT_SSH_KEYS}"));
+        assert!(fingerprint.contains("SHA256:"), "SSH-HK-03: {fingerprint}");
+    }
+}
+
+// ── SSH-HK-04: off accepts any host key ──────────────────────────────
+
+#[test]
+fn ssh_hk_04_off_skips_check() {
+    require_docker!(PORT_SSH_KEYS);
+
+    let dir = tempfile::tempdir().unwrap();
+    let known_hosts = dir.path().join("known_hosts");
+    let contents = write_forged_known_hosts(&known_hosts);
+
+    let session = connect_and_authenticate(&host_key_config("off", &known_hosts))
+        .expect("SSH-HK-04: Host key checking off should connect despite a changed key");
+    assert!(session.authenticated());
+    assert_eq!(
+        std::fs::read_to_string(&known_hosts).unwrap(),
+        contents,
+        "SSH-HK-04: known_hosts must be left alone"
+    );
+}
+
+// ── SSH-HK-05: A host recorded with one key type connects ────────────
+
+#[test]
+fn ssh_hk_05_recorded_key_type_is_negotiated() {
+    require_docker!(PORT_SSH_KEYS);
+
+    let mut checked = 0;
+    for method in ["ssh-ed25519", "ecdsa-sha2-nistp256", "rsa-sha2-256"] {
+        let Some(key) = server_host_key_of_type(method) else {
+            continue;
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let known_hosts = dir.path().join("known_hosts");
+        let recorded = ssh_key::PublicKey::from_bytes(&key)
+            .and_then(|k| k.to_openssh())
+            .unwrap();
+        std::fs::write(
+            &known_hosts,
+            format!("[127.0.0.1]:{PORT_SSH_KEYS} {recorded}\n"),
+        )
+        .unwrap();
+
+        connect_and_authenticate(&host_key_config("strict", &known_hosts)).unwrap_or_else(|e| {
+            panic!("SSH-HK-05: Host recorded only with its {method} key should connect: {e}")
+        });
+        checked += 1;
+    }
+    assert!(
+        checked > 0,
+        "SSH-HK-05: Server offered none of the key types"
+    );
+}
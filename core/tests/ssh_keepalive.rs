@@ -0,0 +1,100 @@
+//! SSH Keepalive Integration Tests (SSH-KA-01 through SSH-KA-02).
+//!
+//! Tests that keepalive probes keep an idle interactive session usable
+//! and that a server answering them is never mistaken for a lost peer.
+//!
+//! Container: `ssh-password` on port 2201.
+//!
+//! Requires: `docker compose -f tests/docker/docker-compose.yml up -d`
+//! Skips gracefully if containers are not running.
+
+mod common;
+
+use std::time::Duration;
+
+use common::{require_docker, ssh_password_settings, PORT_SSH_PASSWORD};
+use termihub_core::backends::ssh::Ssh;
+use termihub_core::connection::{ConnectionType, OutputReceiver};
+
+/// How long the session sits idle before it is checked.
+const IDLE: Duration = Duration::from_secs(30);
+
+/// Connect to the SSH container with keepalives every `interval` seconds.
+async fn connect_with_keepalive(interval: u32) -> Ssh {
+    let mut settings = ssh_password_settings(PORT_SSH_PASSWORD);
+    settings["keepaliveInterval"] = serde_json::json!(interval);
+    settings["keepaliveCountMax"] = serde_json::json!(2);
+    let mut ssh = Ssh::new();
+    ssh.connect(settings)
+        .await
+        .expect("SSH connection with keepalive should succeed");
+    ssh
+}
+
+/// Type `echo <marker>` and wait for the marker to come back.
+async fn assert_echo(ssh: &Ssh, output: &mut OutputReceiver, marker: &str) {
+    ssh.write(format!("echo {marker}\n").as_bytes())
+        .expect("Write should succeed");
+    let mut received = String::new();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while !received.contains(marker) {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        match tokio::time::timeout(remaining, output.recv()).await {
+            Ok(Some(chunk)) => received.push_str(&String::from_utf8_lossy(&chunk)),
+            _ => panic!("Output of `echo {marker}` did not arrive, got: {received:?}"),
+        }
+    }
+}
+
+/// Drain output until `idle` has passed, failing if the stream ends.
+async fn idle_for(output: &mut OutputReceiver, idle: Duration) {
+    let deadline = tokio::time::Instant::now() + idle;
+    loop {
+        let remaining = deadline.saturating_duration_since(tokio::time::Instant::now());
+        if remaining.is_zero() {
+            return;
+        }
+        if let Ok(None) = tokio::time::timeout(remaining, output.recv()).await {
+            panic!("Output stream ended while the session was idle");
+        }
+    }
+}
+
+// ── SSH-KA-01: Idle session survives with keepalives ────────────────
+
+#[tokio::test]
+async fn ssh_ka_01_idle_session_survives() {
+    require_docker!(PORT_SSH_PASSWORD);
+
+    let ssh = connect_with_keepalive(5).await;
+    let mut output = ssh.subscribe_output();
+    assert_echo(&ssh, &mut output, "KA01_BEFORE").await;
+
+    idle_for(&mut output, IDLE).await;
+
+    assert!(
+        ssh.is_connected(),
+        "SSH-KA-01: Session should still be connected after idling"
+    );
+    assert_echo(&ssh, &mut output, "KA01_AFTER").await;
+}
+
+// ── SSH-KA-02: Probes at the minimum interval are all answered ──────
+
+#[tokio::test]
+async fn ssh_ka_02_frequent_probes_keep_session() {
+    require_docker!(PORT_SSH_PASSWORD);
+
+    // One probe per second with a count max of 2: two consecutive missed
+    // replies would tear the session down well within the idle period.
+    let ssh = connect_with_keepalive(1).await;
+    let mut output = ssh.subscribe_output();
+
+    idle_for(&mut output, IDLE).await;
+
+    assert!(
+        ssh.is_connected(),
+        "SSH-KA-02: Answered keepalives must not close the session"
+    );
+    assert_echo(&ssh, &mut output, "KA02_AFTER").await;
+}
@@ -0,0 +1,52 @@
+//! Integration tests for one-shot SSH command execution (`run_command`).
+//!
+//! Requires: `docker compose -f tests/docker/docker-compose.yml up -d`
+//! Skips gracefully if containers are not running.
+
+mod common;
+
+use common::{require_docker, ssh_password_config, PORT_SSH_PASSWORD};
+use termihub_core::backends::ssh::exec::run_command;
+
+#[test]
+fn run_command_captures_stdout_and_exit_code() {
+    require_docker!(PORT_SSH_PASSWORD);
+
+    let config = ssh_password_config(PORT_SSH_PASSWORD);
+    let output = run_command(&config, "echo hello; exit 7").expect("command should run");
+
+    assert_eq!(output.stdout, "hello\n");
+    assert_eq!(output.stderr, "");
+    assert_eq!(output.exit_code, 7);
+}
+
+#[test]
+fn run_command_separates_stderr() {
+    require_docker!(PORT_SSH_PASSWORD);
+
+    let config = ssh_password_config(PORT_SSH_PASSWORD);
+    let output = run_command(&config, "echo out; echo err >&2").expect("command should run");
+
+    assert_eq!(output.stdout, "out\n");
+    assert_eq!(output.stderr, "err\n");
+    assert_eq!(output.exit_code, 0);
+}
+
+#[test]
+fn run_command_has_no_tty() {
+    require_docker!(PORT_SSH_PASSWORD);
+
+    let config = ssh_password_config(PORT_SSH_PASSWORD);
+    let output = run_command(&config, "test -t 0 && echo tty || echo notty").unwrap();
+
+    assert_eq!(output.stdout, "notty\n");
+}
+
+#[test]
+fn run_command_wrong_password_fails() {
+    require_docker!(PORT_SSH_PASSWORD);
+
+    let mut config = ssh_password_config(PORT_SSH_PASSWORD);
+    config.password = Some("wrong".to_string());
+    assert!(run_command(&config, "true").is_err());
+}
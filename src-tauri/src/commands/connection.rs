@@ -9,8 +9,9 @@ use crate::connection::config::{
 };
 use crate::connection::manager::{self, ConnectionManager};
 use crate::connection::recovery::RecoveryWarning;
-use crate::connection::settings::AppSettings;
+use crate::connection::settings::{AppSettings, FileBookmark, SessionLayout, SessionLayoutEntry};
 use crate::credential::CredentialManager;
+use crate::session::manager::SessionManager;
 
 /// Response containing all connections (unified), folders, and agents.
 #[derive(Serialize)]
@@ -147,10 +148,75 @@ pub fn get_settings(manager: State<'_, ConnectionManager>) -> Result<AppSettings
 pub fn save_settings(
     settings: AppSettings,
     manager: State<'_, ConnectionManager>,
+    sessions: State<'_, SessionManager>,
 ) -> Result<(), String> {
+    sessions.set_resize_debounce(settings.resize_debounce());
     manager.save_settings(settings).map_err(|e| e.to_string())
 }
 
+/// Switch the active environment profile (`None` to deactivate).
+#[tauri::command]
+pub fn set_active_profile(
+    name: Option<String>,
+    manager: State<'_, ConnectionManager>,
+) -> Result<(), String> {
+    manager.set_active_profile(name).map_err(|e| e.to_string())
+}
+
+/// Bookmark a file browser path, globally or for one saved connection.
+#[tauri::command]
+pub fn add_file_bookmark(
+    path: String,
+    label: Option<String>,
+    connection_id: Option<String>,
+    manager: State<'_, ConnectionManager>,
+) -> Result<(), String> {
+    manager
+        .add_file_bookmark(FileBookmark {
+            path,
+            label,
+            connection_id,
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// List the global bookmarks plus those of `connection_id`.
+#[tauri::command]
+pub fn list_file_bookmarks(
+    connection_id: Option<String>,
+    manager: State<'_, ConnectionManager>,
+) -> Vec<FileBookmark> {
+    manager
+        .get_settings()
+        .file_bookmarks_for(connection_id.as_deref())
+}
+
+/// Remove a file browser bookmark.
+#[tauri::command]
+pub fn remove_file_bookmark(
+    path: String,
+    connection_id: Option<String>,
+    manager: State<'_, ConnectionManager>,
+) -> Result<(), String> {
+    manager
+        .remove_file_bookmark(&path, connection_id.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Save the given open sessions as a named layout, replacing any layout
+/// with the same name. The frontend passes the sessions in tab order.
+#[tauri::command]
+pub fn save_session_layout(
+    name: String,
+    sessions: Vec<SessionLayoutEntry>,
+    manager: State<'_, ConnectionManager>,
+) -> Result<(), String> {
+    info!(name, sessions = sessions.len(), "Saving session layout");
+    manager
+        .save_session_layout(SessionLayout { name, sessions })
+        .map_err(|e| e.to_string())
+}
+
 /// Save an external connection file to disk.
 #[tauri::command]
 pub fn save_external_file(
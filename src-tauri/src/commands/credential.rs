@@ -5,6 +5,7 @@ use tauri::{AppHandle, Emitter, State};
 use tracing::{debug, info, warn};
 
 use crate::connection::manager::ConnectionManager;
+use crate::credential::backup;
 use crate::credential::types::{build_status_info, CredentialStoreStatusInfo};
 use crate::credential::{
     CredentialKey, CredentialManager, CredentialStore, CredentialType, StorageMode,
@@ -355,6 +356,53 @@ pub fn remove_credential(
     manager.remove(&key).map_err(|e| e.to_string())
 }
 
+/// List which credential types are stored for a connection.
+///
+/// Reports presence only (e.g. `["password"]`); secret values are never
+/// returned. Fails when the store is locked.
+#[tauri::command]
+pub fn list_connection_credentials(
+    connection_id: String,
+    manager: State<'_, Arc<CredentialManager>>,
+) -> Result<Vec<String>, String> {
+    debug!(connection_id = %connection_id, "Listing stored credential types");
+    manager
+        .list_for_connection(&connection_id)
+        .map(|types| types.iter().map(ToString::to_string).collect())
+        .map_err(|e| e.to_string())
+}
+
+/// Export every stored credential as an encrypted backup.
+///
+/// The credentials are sealed with `password` (Argon2id + AES-256-GCM).
+/// Fails when the store is locked or unavailable.
+///
+/// This is async because Argon2id key derivation is CPU-intensive.
+#[tauri::command]
+pub async fn export_credentials_encrypted(
+    password: String,
+    manager: State<'_, Arc<CredentialManager>>,
+) -> Result<String, String> {
+    info!("Exporting encrypted credential backup");
+    backup::export_encrypted(&**manager, &password).map_err(|e| e.to_string())
+}
+
+/// Import credentials from an encrypted backup into the active store.
+///
+/// Returns the number of credentials imported. Fails when the store is
+/// locked or unavailable, or when the password is wrong.
+///
+/// This is async because Argon2id key derivation is CPU-intensive.
+#[tauri::command]
+pub async fn import_credentials_encrypted(
+    json: String,
+    password: String,
+    manager: State<'_, Arc<CredentialManager>>,
+) -> Result<usize, String> {
+    info!("Importing encrypted credential backup");
+    backup::import_encrypted(&**manager, &json, &password).map_err(|e| e.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,24 +1,34 @@
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
 use serde::Serialize;
 use tauri::{Emitter, Manager, State};
 use termihub_core::backends::ssh::parse_ssh_settings;
+use termihub_core::backends::ssh::transfer::{DirTransferSummary, TransferProgress};
+use termihub_core::files::NewlineMode;
 use tracing::{debug, info};
 
-use crate::files::sftp::SftpManager;
+use crate::connection::manager::ConnectionManager;
+use crate::files::sftp::{SftpManager, SftpSession};
 use crate::files::FileEntry;
 use crate::utils::errors::TerminalError;
-use crate::utils::vscode;
+use crate::utils::{editor, vscode};
 
 /// Open a new SFTP session. Returns the session ID.
 ///
 /// Accepts raw JSON settings (same shape the frontend stores) and parses
 /// them with `parse_ssh_settings` so that array-encoded `env` fields
-/// (from the `keyValueList` schema type) are handled correctly.
+/// (from the `keyValueList` schema type) are handled correctly. The host
+/// is resolved through the connection's `sshConfigFile`, if any.
 #[tauri::command]
 pub fn sftp_open(
     config: serde_json::Value,
     manager: State<'_, SftpManager>,
 ) -> Result<String, TerminalError> {
-    let config = parse_ssh_settings(&config);
+    let config = parse_ssh_settings(&config)
+        .expand()
+        .apply_ssh_config_file()
+        .map_err(|e| TerminalError::SshError(e.to_string()))?;
     info!(host = %config.host, port = config.port, "Opening SFTP session");
     manager.open_session(&config)
 }
@@ -43,44 +53,242 @@ pub fn sftp_list_dir(
     session.list_dir(&path)
 }
 
+/// Directory the file browser opens to for an SFTP session.
+///
+/// Resolves the connection's `fileBrowserRoot` (with `~` and `${env:HOME}`
+/// referring to the remote home), defaulting to the remote home.
+#[tauri::command]
+pub fn sftp_initial_dir(
+    session_id: String,
+    manager: State<'_, SftpManager>,
+) -> Result<String, TerminalError> {
+    let session = manager.get_session(&session_id)?;
+    let session = session.lock().unwrap();
+    session.initial_dir()
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SftpTransferProgressEvent {
+    session_id: String,
+    path: String,
+    transferred: u64,
+    total: u64,
+}
+
+/// Progress step for transfers of unknown size, such as archive downloads.
+const UNKNOWN_TOTAL_PROGRESS_STEP: u64 = 1024 * 1024;
+
+/// Build a progress callback that emits `sftp-transfer-progress` events,
+/// at most once per percent of the file plus the final update.
+///
+/// A `total` of zero means the size is unknown; updates are then emitted
+/// once per MiB transferred.
+pub(crate) fn progress_emitter(
+    app_handle: tauri::AppHandle,
+    session_id: String,
+    path: String,
+) -> impl FnMut(TransferProgress) {
+    let mut last_emitted: Option<u64> = None;
+    move |p: TransferProgress| {
+        let step = if p.total == 0 {
+            UNKNOWN_TOTAL_PROGRESS_STEP
+        } else {
+            (p.total / 100).max(1)
+        };
+        let due = match last_emitted {
+            None => true,
+            Some(last) => p.transferred == p.total || p.transferred - last >= step,
+        };
+        if due {
+            last_emitted = Some(p.transferred);
+            let _ = app_handle.emit(
+                "sftp-transfer-progress",
+                SftpTransferProgressEvent {
+                    session_id: session_id.clone(),
+                    path: path.clone(),
+                    transferred: p.transferred,
+                    total: p.total,
+                },
+            );
+        }
+    }
+}
+
+/// A boxed single-file progress callback, as built by [`progress_emitter`].
+type ProgressCallback = Box<dyn FnMut(TransferProgress)>;
+
+/// Build a progress callback for directory transfers that emits
+/// `sftp-transfer-progress` events per file, as [`progress_emitter`] does
+/// for a single file.
+fn dir_progress_emitter(
+    app_handle: tauri::AppHandle,
+    session_id: String,
+) -> impl FnMut(&Path, TransferProgress) {
+    let mut current: Option<(PathBuf, ProgressCallback)> = None;
+    move |path: &Path, p: TransferProgress| {
+        if current.as_ref().map(|(file, _)| file.as_path()) != Some(path) {
+            let emit = progress_emitter(
+                app_handle.clone(),
+                session_id.clone(),
+                path.to_string_lossy().to_string(),
+            );
+            current = Some((path.to_path_buf(), Box::new(emit)));
+        }
+        if let Some((_, emit)) = current.as_mut() {
+            emit(p);
+        }
+    }
+}
+
 /// Download a remote file to a local path. Returns bytes transferred.
+///
+/// With `resume`, a partial local file is continued instead of replaced.
+/// With `verify`, the downloaded file's SHA-256 is compared with one
+/// computed on the server. Emits `sftp-transfer-progress` events while
+/// transferring.
 #[tauri::command]
 pub fn sftp_download(
     session_id: String,
     remote_path: String,
     local_path: String,
+    resume: Option<bool>,
+    verify: Option<bool>,
     manager: State<'_, SftpManager>,
+    app_handle: tauri::AppHandle,
 ) -> Result<u64, TerminalError> {
-    debug!(session_id, remote_path, local_path, "SFTP download");
+    debug!(
+        session_id,
+        remote_path,
+        local_path,
+        ?resume,
+        ?verify,
+        "SFTP download"
+    );
     let session = manager.get_session(&session_id)?;
     let session = session.lock().unwrap();
-    session.read_file(&remote_path, &local_path)
+    let mut on_progress = progress_emitter(app_handle, session_id, remote_path.clone());
+    session.read_file(
+        &remote_path,
+        &local_path,
+        resume.unwrap_or(false),
+        verify.unwrap_or(false),
+        &mut on_progress,
+    )
 }
 
 /// Upload a local file to a remote path. Returns bytes transferred.
+///
+/// With `resume`, a partial remote file is continued instead of replaced.
+/// With `verify`, the uploaded file's SHA-256, computed on the server, is
+/// compared with the local one. Emits `sftp-transfer-progress` events
+/// while transferring.
 #[tauri::command]
 pub fn sftp_upload(
     session_id: String,
     local_path: String,
     remote_path: String,
+    resume: Option<bool>,
+    verify: Option<bool>,
     manager: State<'_, SftpManager>,
+    app_handle: tauri::AppHandle,
 ) -> Result<u64, TerminalError> {
-    debug!(session_id, local_path, remote_path, "SFTP upload");
+    debug!(
+        session_id,
+        local_path,
+        remote_path,
+        ?resume,
+        ?verify,
+        "SFTP upload"
+    );
     let session = manager.get_session(&session_id)?;
     let session = session.lock().unwrap();
-    session.write_file(&local_path, &remote_path)
+    let mut on_progress = progress_emitter(app_handle, session_id, remote_path.clone());
+    session.write_file(
+        &local_path,
+        &remote_path,
+        resume.unwrap_or(false),
+        verify.unwrap_or(false),
+        &mut on_progress,
+    )
 }
 
-/// Create a directory on the remote host.
+/// Download a remote directory tree into a local directory.
+///
+/// Symlinks are skipped unless `follow_symlinks` is set. Files that fail
+/// are listed in the returned summary; the rest of the tree is still
+/// transferred. Emits `sftp-transfer-progress` events for each file.
+#[tauri::command]
+pub fn sftp_download_dir(
+    session_id: String,
+    remote_path: String,
+    local_path: String,
+    follow_symlinks: Option<bool>,
+    manager: State<'_, SftpManager>,
+    app_handle: tauri::AppHandle,
+) -> Result<DirTransferSummary, TerminalError> {
+    debug!(
+        session_id,
+        remote_path,
+        local_path,
+        ?follow_symlinks,
+        "SFTP directory download"
+    );
+    let session = manager.get_session(&session_id)?;
+    let session = session.lock().unwrap();
+    let mut on_progress = dir_progress_emitter(app_handle, session_id);
+    session.download_dir(
+        &remote_path,
+        &local_path,
+        follow_symlinks.unwrap_or(false),
+        &mut on_progress,
+    )
+}
+
+/// Upload a local directory tree into a remote directory.
+///
+/// Symlinks are skipped unless `follow_symlinks` is set. Files that fail
+/// are listed in the returned summary; the rest of the tree is still
+/// transferred. Emits `sftp-transfer-progress` events for each file.
+#[tauri::command]
+pub fn sftp_upload_dir(
+    session_id: String,
+    local_path: String,
+    remote_path: String,
+    follow_symlinks: Option<bool>,
+    manager: State<'_, SftpManager>,
+    app_handle: tauri::AppHandle,
+) -> Result<DirTransferSummary, TerminalError> {
+    debug!(
+        session_id,
+        local_path,
+        remote_path,
+        ?follow_symlinks,
+        "SFTP directory upload"
+    );
+    let session = manager.get_session(&session_id)?;
+    let session = session.lock().unwrap();
+    let mut on_progress = dir_progress_emitter(app_handle, session_id);
+    session.upload_dir(
+        &local_path,
+        &remote_path,
+        follow_symlinks.unwrap_or(false),
+        &mut on_progress,
+    )
+}
+
+/// Create a directory on the remote host, with its missing parents when
+/// `recursive` is set.
 #[tauri::command]
 pub fn sftp_mkdir(
     session_id: String,
     path: String,
+    recursive: Option<bool>,
     manager: State<'_, SftpManager>,
 ) -> Result<(), TerminalError> {
     let session = manager.get_session(&session_id)?;
     let session = session.lock().unwrap();
-    session.mkdir(&path)
+    session.mkdir(&path, recursive.unwrap_or(false))
 }
 
 /// Delete a file or empty directory on the remote host.
@@ -101,16 +309,19 @@ pub fn sftp_delete(
 }
 
 /// Rename a file or directory on the remote host.
+///
+/// Refuses to replace an existing destination unless `overwrite` is `true`.
 #[tauri::command]
 pub fn sftp_rename(
     session_id: String,
     old_path: String,
     new_path: String,
+    overwrite: Option<bool>,
     manager: State<'_, SftpManager>,
 ) -> Result<(), TerminalError> {
     let session = manager.get_session(&session_id)?;
     let session = session.lock().unwrap();
-    session.rename(&old_path, &new_path)
+    session.rename(&old_path, &new_path, overwrite.unwrap_or(false))
 }
 
 // --- Local filesystem commands ---
@@ -137,10 +348,11 @@ pub fn local_list_dir(path: String) -> Result<Vec<FileEntry>, TerminalError> {
     crate::files::local::list_dir(&path)
 }
 
-/// Create a directory on the local filesystem.
+/// Create a directory on the local filesystem, with its missing parents
+/// when `recursive` is set.
 #[tauri::command]
-pub fn local_mkdir(path: String) -> Result<(), TerminalError> {
-    crate::files::local::mkdir(&path)
+pub fn local_mkdir(path: String, recursive: Option<bool>) -> Result<(), TerminalError> {
+    crate::files::local::mkdir(&path, recursive.unwrap_or(false))
 }
 
 /// Delete a file or directory on the local filesystem.
@@ -151,8 +363,12 @@ pub fn local_delete(path: String, is_directory: bool) -> Result<(), TerminalErro
 
 /// Rename a file or directory on the local filesystem.
 #[tauri::command]
-pub fn local_rename(old_path: String, new_path: String) -> Result<(), TerminalError> {
-    crate::files::local::rename(&old_path, &new_path)
+pub fn local_rename(
+    old_path: String,
+    new_path: String,
+    overwrite: Option<bool>,
+) -> Result<(), TerminalError> {
+    crate::files::local::rename(&old_path, &new_path, overwrite.unwrap_or(false))
 }
 
 /// Read a local file's contents as a UTF-8 string.
@@ -161,10 +377,14 @@ pub fn local_read_file(path: String) -> Result<String, TerminalError> {
     crate::files::local::read_file_content(&path)
 }
 
-/// Write a string to a local file.
+/// Write a string to a local file, optionally normalizing line endings.
 #[tauri::command]
-pub fn local_write_file(path: String, content: String) -> Result<(), TerminalError> {
-    crate::files::local::write_file_content(&path, &content)
+pub fn local_write_file(
+    path: String,
+    content: String,
+    newline: Option<NewlineMode>,
+) -> Result<(), TerminalError> {
+    crate::files::local::write_file_content(&path, &content, newline.unwrap_or_default())
 }
 
 /// Read a remote file's contents as a UTF-8 string via SFTP.
@@ -179,29 +399,33 @@ pub fn sftp_read_file_content(
     session.read_file_content(&remote_path)
 }
 
-/// Write a string to a remote file via SFTP.
+/// Write a string to a remote file via SFTP, optionally normalizing line endings.
 #[tauri::command]
 pub fn sftp_write_file_content(
     session_id: String,
     remote_path: String,
     content: String,
+    newline: Option<NewlineMode>,
     manager: State<'_, SftpManager>,
 ) -> Result<(), TerminalError> {
     let session = manager.get_session(&session_id)?;
     let session = session.lock().unwrap();
-    session.write_file_content(&remote_path, &content)
+    session.write_file_content(&remote_path, &content, newline.unwrap_or_default())
 }
 
-// --- VS Code integration ---
+// --- External editor integration ---
 
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
-struct VscodeEditCompleteEvent {
+struct EditCompleteEvent {
     remote_path: String,
     success: bool,
     error: Option<String>,
 }
 
+/// Template used by the VS Code-specific commands.
+const VSCODE_EDITOR_COMMAND: &str = "code --wait {path}";
+
 /// Check if VS Code CLI (`code`) is available on PATH.
 #[tauri::command]
 pub fn vscode_available() -> bool {
@@ -222,66 +446,144 @@ pub fn vscode_open_remote(
     manager: State<'_, SftpManager>,
     app_handle: tauri::AppHandle,
 ) -> Result<(), TerminalError> {
-    // Get a clone of the session Arc before spawning the background thread
     let session_arc = manager.get_session(&session_id)?;
+    edit_remote_file(
+        session_arc,
+        remote_path,
+        VSCODE_EDITOR_COMMAND,
+        app_handle,
+        "vscode-edit-complete",
+    )
+}
 
-    // Extract the filename from the remote path
-    let filename = std::path::Path::new(&remote_path)
-        .file_name()
-        .map(|n| n.to_string_lossy().to_string())
-        .unwrap_or_else(|| "untitled".to_string());
+/// Open a local file in the external editor (fire-and-forget).
+///
+/// `editor_command` overrides the configured editor template.
+#[tauri::command]
+pub fn open_in_editor(
+    path: String,
+    editor_command: Option<String>,
+    connections: State<'_, ConnectionManager>,
+) -> Result<(), TerminalError> {
+    let settings = connections.get_settings();
+    let template = editor::resolve_editor_command(
+        editor_command.as_deref(),
+        settings.editor_command.as_deref(),
+    )
+    .map_err(TerminalError::EditorError)?;
+    info!(path, template, "Opening file in editor");
+    editor::spawn_editor(&template, &path)
+        .map(|_| ())
+        .map_err(|e| TerminalError::EditorError(e.to_string()))
+}
+
+/// Open a remote file in the external editor: download it to a temp file,
+/// upload it back whenever it is saved, and clean up when the editor exits.
+///
+/// The editor template is `editor_command`, else the connection's
+/// `editorCommand`, else the app-wide setting.
+#[tauri::command]
+pub fn sftp_open_in_editor(
+    session_id: String,
+    remote_path: String,
+    editor_command: Option<String>,
+    manager: State<'_, SftpManager>,
+    connections: State<'_, ConnectionManager>,
+    app_handle: tauri::AppHandle,
+) -> Result<(), TerminalError> {
+    let session_arc = manager.get_session(&session_id)?;
+    let connection_editor = session_arc
+        .lock()
+        .unwrap()
+        .editor_command()
+        .map(String::from);
+    let settings = connections.get_settings();
+    let template = editor::resolve_editor_command(
+        editor_command.as_deref().or(connection_editor.as_deref()),
+        settings.editor_command.as_deref(),
+    )
+    .map_err(TerminalError::EditorError)?;
+    edit_remote_file(
+        session_arc,
+        remote_path,
+        &template,
+        app_handle,
+        "editor-edit-complete",
+    )
+}
 
+/// Download `remote_path`, open it with `template`, and upload changes in
+/// a background thread until the editor exits. Emits `event` when done.
+fn edit_remote_file(
+    session_arc: Arc<Mutex<SftpSession>>,
+    remote_path: String,
+    template: &str,
+    app_handle: tauri::AppHandle,
+    event: &'static str,
+) -> Result<(), TerminalError> {
     // Create a temp directory for editing
     let temp_dir = std::env::temp_dir().join("termihub-edit");
     std::fs::create_dir_all(&temp_dir)
         .map_err(|e| TerminalError::EditorError(format!("Failed to create temp dir: {}", e)))?;
 
-    let temp_path = temp_dir.join(format!("{}-{}", uuid::Uuid::new_v4(), filename));
+    let temp_path = editor::edit_temp_path(&temp_dir, &remote_path);
     let temp_path_str = temp_path.to_string_lossy().to_string();
 
     // Download the remote file to temp
     {
         let session = session_arc.lock().unwrap();
-        session.read_file(&remote_path, &temp_path_str)?;
+        session.read_file(&remote_path, &temp_path_str, false, false, &mut |_| {})?;
     }
 
-    // Spawn a background thread to wait for VS Code to close
-    let remote_path_clone = remote_path.clone();
+    let cleanup = |e: String| {
+        let _ = std::fs::remove_file(&temp_path);
+        TerminalError::EditorError(e)
+    };
+    let mut file =
+        editor::EditedFile::new(temp_path.clone()).map_err(|e| cleanup(e.to_string()))?;
+    let child = editor::spawn_editor(template, &temp_path_str)
+        .map_err(|e| cleanup(format!("Failed to launch editor: {}", e)))?;
+    info!(remote_path, template, "Editing remote file");
+
+    // Upload saves in the background until the editor exits
     std::thread::spawn(move || {
-        let result = vscode::open_in_vscode_wait(&temp_path_str);
-
-        let event = match result {
-            Ok(()) => {
-                // Re-upload the edited file
-                let upload_result = {
-                    let session = session_arc.lock().unwrap();
-                    session.write_file(&temp_path_str, &remote_path_clone)
-                };
-                match upload_result {
-                    Ok(_) => VscodeEditCompleteEvent {
-                        remote_path: remote_path_clone,
-                        success: true,
-                        error: None,
-                    },
-                    Err(e) => VscodeEditCompleteEvent {
-                        remote_path: remote_path_clone,
-                        success: false,
-                        error: Some(format!("Upload failed: {}", e)),
-                    },
-                }
-            }
-            Err(e) => VscodeEditCompleteEvent {
-                remote_path: remote_path_clone,
+        let result = editor::watch_editor_process(child, &mut file, |path| {
+            let session = session_arc.lock().unwrap();
+            session
+                .write_file(
+                    &path.to_string_lossy(),
+                    &remote_path,
+                    false,
+                    false,
+                    &mut |_| {},
+                )
+                .map(|_| ())
+        });
+        // Keep the temp file while a detached editor may still have it
+        // open, and after a failed upload, as it holds the only copy of the
+        // edits.
+        if matches!(result, Ok(ref summary) if !summary.detached) {
+            let _ = std::fs::remove_file(&temp_path);
+        }
+        let payload = match result {
+            Ok(_) => EditCompleteEvent {
+                remote_path,
+                success: true,
+                error: None,
+            },
+            Err(e) => EditCompleteEvent {
+                remote_path,
                 success: false,
-                error: Some(format!("VS Code error: {}", e)),
+                error: Some(format!(
+                    "Upload failed: {}; the edited copy is kept at {}",
+                    e,
+                    temp_path.display()
+                )),
             },
         };
 
-        // Clean up temp file (best-effort)
-        let _ = std::fs::remove_file(&temp_path);
-
         // Emit event to frontend
-        let _ = app_handle.emit("vscode-edit-complete", event);
+        let _ = app_handle.emit(event, payload);
     });
 
     Ok(())
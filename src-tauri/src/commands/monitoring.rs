@@ -8,7 +8,8 @@ use crate::utils::errors::TerminalError;
 /// Open a new monitoring session. Returns the session ID.
 ///
 /// Accepts raw JSON settings and parses them with `parse_ssh_settings`
-/// so that array-encoded `env` fields are handled correctly.
+/// so that array-encoded `env` fields are handled correctly. The host is
+/// resolved through the connection's `sshConfigFile`, if any.
 ///
 /// Uses `spawn_blocking` so the SSH handshake (which can take ~75 s on a
 /// dead host waiting for TCP SYN timeout) does not occupy a tokio worker
@@ -18,7 +19,10 @@ pub async fn monitoring_open(
     config: serde_json::Value,
     manager: State<'_, MonitoringManager>,
 ) -> Result<String, TerminalError> {
-    let config = parse_ssh_settings(&config);
+    let config = parse_ssh_settings(&config)
+        .expand()
+        .apply_ssh_config_file()
+        .map_err(|e| TerminalError::SshError(e.to_string()))?;
     info!(host = %config.host, port = config.port, "Opening monitoring session");
     let manager = (*manager).clone();
     tokio::task::spawn_blocking(move || manager.open_session(&config))
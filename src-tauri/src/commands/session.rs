@@ -4,15 +4,35 @@
 //! entry point and uniform I/O commands. File browsing and monitoring are
 //! accessed through the session's connection capabilities.
 
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use tauri::State;
-use tracing::{debug, info};
+use tauri::{Emitter, Manager, State};
+use tracing::{debug, info, warn};
 
-use termihub_core::connection::ConnectionTypeInfo;
-use termihub_core::files::FileEntry;
+use termihub_core::backends::ssh::auth::SshAgentStatus;
+use termihub_core::backends::ssh::exec::{run_command, CommandOutput};
+use termihub_core::backends::ssh::keygen::{generate_key, SshKeyType};
+use termihub_core::backends::ssh::parse_ssh_settings;
+use termihub_core::backends::ssh::transfer::TransferProgress;
+use termihub_core::config::expand::{expand_tilde, expand_var_placeholders_in_value};
+use termihub_core::connection::{CloseTrigger, ConnectionTypeInfo, LineControl, ModemStatus};
+use termihub_core::files::{
+    ArchiveFormat, FileComparison, FileEntry, GrepMatch, GrepOptions, NewlineMode,
+};
+use termihub_core::session::file_send::FileSendProtocol;
+use termihub_core::session::ssh::validate_ssh_config;
 
-use crate::session::manager::{SessionInfo, SessionManager};
+use crate::commands::files::progress_emitter;
+use crate::connection::group::{
+    launch_group, restore_layout, GroupMemberResult, LayoutRestoreResult,
+};
+use crate::connection::manager::ConnectionManager;
+use crate::session::manager::{
+    ActiveSessionInfo, SessionInfo, SessionManager, SessionSearchResult,
+};
 use crate::utils::errors::TerminalError;
 use crate::utils::shell_detect;
 
@@ -21,26 +41,178 @@ use crate::utils::shell_detect;
 /// For local connections, pass `type_id` (e.g., "local", "ssh", "serial")
 /// and `settings` (JSON matching the type's settings schema). For remote
 /// (agent-mediated) connections, also pass `agent_id`.
+///
+/// `${var:NAME}` placeholders in `settings` are resolved against the
+/// active environment profile before connecting. SSH jump hosts that name
+/// saved connections are resolved to those connections' settings.
 #[tauri::command]
 pub async fn create_connection(
     type_id: String,
-    settings: Value,
+    mut settings: Value,
     agent_id: Option<String>,
     app_handle: tauri::AppHandle,
     manager: State<'_, SessionManager>,
 ) -> Result<String, TerminalError> {
     info!(type_id, agent_id = ?agent_id, "Creating connection");
+    if let Some(connections) = app_handle.try_state::<ConnectionManager>() {
+        if type_id == "ssh" {
+            connections.resolve_jump_hosts(&mut settings);
+        }
+        let vars = connections.active_profile_variables();
+        expand_var_placeholders_in_value(&mut settings, &vars);
+    }
     manager
         .create_connection(&type_id, settings, agent_id.as_deref(), app_handle)
         .await
 }
 
+/// Connect every saved connection in a connection group.
+///
+/// Members launch one after another unless `parallel` is `true`. Stored
+/// credentials and `${var:NAME}` placeholders are resolved per member as
+/// in [`create_connection`]. A failing member is reported in its result
+/// and does not abort the others.
+#[tauri::command]
+pub async fn connect_group(
+    group_id: String,
+    parallel: Option<bool>,
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<GroupMemberResult>, TerminalError> {
+    let members = app_handle
+        .state::<ConnectionManager>()
+        .get_settings()
+        .connection_group(&group_id)
+        .map(|group| group.connection_ids.clone())
+        .ok_or_else(|| {
+            TerminalError::ConnectionFailed(format!("Connection group not found: {group_id}"))
+        })?;
+    info!(group_id, members = members.len(), parallel = ?parallel, "Connecting group");
+
+    let launch = |connection_id| launch_saved_connection(app_handle.clone(), connection_id);
+    Ok(launch_group(&members, parallel.unwrap_or(false), launch).await)
+}
+
+/// Reopen the sessions of a saved session layout, one after another.
+///
+/// Connections that no longer exist are skipped and reported in the
+/// result; a session that fails to connect does not stop the others.
+#[tauri::command]
+pub async fn restore_session_layout(
+    name: String,
+    app_handle: tauri::AppHandle,
+) -> Result<LayoutRestoreResult, TerminalError> {
+    let layout = app_handle
+        .state::<ConnectionManager>()
+        .get_settings()
+        .session_layout(&name)
+        .cloned()
+        .ok_or_else(|| {
+            TerminalError::ConnectionFailed(format!("Session layout not found: {name}"))
+        })?;
+    info!(
+        name,
+        sessions = layout.sessions.len(),
+        "Restoring session layout"
+    );
+
+    let connections = app_handle.state::<ConnectionManager>();
+    let exists = |id: &str| connections.connection_exists(id);
+    let launch = |connection_id| launch_saved_connection(app_handle.clone(), connection_id);
+    let result = restore_layout(&layout, exists, launch).await;
+    if !result.missing_connection_ids.is_empty() {
+        warn!(name, missing = ?result.missing_connection_ids, "Skipped deleted connections");
+    }
+    Ok(result)
+}
+
+/// Event emitted for each connection opened by [`connect_auto_connections`],
+/// carrying a [`GroupMemberResult`] with the new session ID or the error.
+pub const AUTO_CONNECT_EVENT: &str = "connection-auto-connect";
+
+/// Set once [`connect_auto_connections`] has run, so a reloaded frontend
+/// does not open the connections a second time.
+static AUTO_CONNECT_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// Open every saved connection marked `autoConnect`, one after another,
+/// emitting [`AUTO_CONNECT_EVENT`] per connection. A connection that fails
+/// does not stop the others.
+///
+/// Called by the frontend once it listens for the events; only the first
+/// call per app launch connects anything.
+#[tauri::command]
+pub async fn connect_auto_connections(app_handle: tauri::AppHandle) {
+    if AUTO_CONNECT_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let Some(connections) = app_handle.try_state::<ConnectionManager>() else {
+        return;
+    };
+    let ids = connections.auto_connect_ids();
+    if ids.is_empty() {
+        return;
+    }
+    info!(count = ids.len(), "Auto-connecting saved connections");
+
+    for connection_id in ids {
+        let result = launch_saved_connection(app_handle.clone(), connection_id.clone()).await;
+        if let Err(e) = &result {
+            warn!(connection_id, "Failed to auto-connect: {e}");
+        }
+        let event = GroupMemberResult::new(connection_id, result);
+        if let Err(e) = app_handle.emit(AUTO_CONNECT_EVENT, &event) {
+            warn!("Failed to emit {AUTO_CONNECT_EVENT}: {e}");
+        }
+    }
+}
+
+/// Connect a saved connection by ID, resolving stored credentials, jump
+/// hosts and `${var:NAME}` placeholders as in [`create_connection`]. Connections with
+/// `viaAgent` set are opened through that agent.
+async fn launch_saved_connection(
+    app_handle: tauri::AppHandle,
+    connection_id: String,
+) -> Result<String, String> {
+    let connections = app_handle.state::<ConnectionManager>();
+    let connection = connections
+        .connection_for_launch(&connection_id)
+        .ok_or_else(|| format!("Connection not found: {connection_id}"))?;
+    let mut settings = connection.config.settings;
+    if connection.config.type_id == "ssh" {
+        connections.resolve_jump_hosts(&mut settings);
+    }
+    expand_var_placeholders_in_value(&mut settings, &connections.active_profile_variables());
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert("connectionId".to_string(), serde_json::json!(connection_id));
+    }
+    app_handle
+        .state::<SessionManager>()
+        .create_connection(
+            &connection.config.type_id,
+            settings,
+            connection.via_agent.as_deref(),
+            app_handle.clone(),
+        )
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Get the list of available connection types with their schemas.
 #[tauri::command]
 pub fn get_connection_types(manager: State<'_, SessionManager>) -> Vec<ConnectionTypeInfo> {
     manager.available_types()
 }
 
+/// Get a JSON Schema document describing a connection type's settings.
+#[tauri::command]
+pub fn get_connection_json_schema(
+    type_id: String,
+    manager: State<'_, SessionManager>,
+) -> Result<Value, String> {
+    manager
+        .connection_json_schema(&type_id)
+        .map_err(|e| e.to_string())
+}
+
 /// Send input data to a session.
 #[tauri::command]
 pub async fn send_input(
@@ -64,14 +236,239 @@ pub async fn resize_terminal(
     manager.resize(&session_id, cols, rows).await
 }
 
+/// Send a BREAK or set the DTR/RTS line of a serial session.
+#[tauri::command]
+pub async fn send_line_control(
+    session_id: String,
+    control: LineControl,
+    manager: State<'_, SessionManager>,
+) -> Result<(), TerminalError> {
+    debug!(session_id, ?control, "Sending line control");
+    manager.line_control(&session_id, control).await
+}
+
+/// Read the CTS/DSR/DCD/RI modem status lines of a serial session.
+#[tauri::command]
+pub async fn serial_modem_status(
+    session_id: String,
+    manager: State<'_, SessionManager>,
+) -> Result<ModemStatus, TerminalError> {
+    manager.modem_status(&session_id).await
+}
+
 /// Close a session.
+///
+/// Closing a tab passes no trigger (`tabClosed`); whether the backend is
+/// then released depends on the session's `cleanupPolicy`. A `kill`
+/// always releases it.
 #[tauri::command]
 pub async fn close_terminal(
     session_id: String,
+    trigger: Option<CloseTrigger>,
     manager: State<'_, SessionManager>,
 ) -> Result<(), TerminalError> {
-    info!(session_id, "Closing session");
-    manager.close_session(&session_id).await
+    let trigger = trigger.unwrap_or_default();
+    info!(session_id, trigger = ?trigger, "Closing session");
+    manager.close_session(&session_id, trigger).await
+}
+
+/// Reattach a session whose backend was kept alive after its tab closed.
+#[tauri::command]
+pub async fn reattach_terminal(
+    session_id: String,
+    manager: State<'_, SessionManager>,
+) -> Result<(), TerminalError> {
+    info!(session_id, "Reattaching session");
+    manager.reattach_session(&session_id).await
+}
+
+/// Restart a local or Docker session's shell without closing its tab.
+///
+/// The session keeps its ID; the scrollback is cleared unless
+/// `clear_scrollback` is `false`.
+#[tauri::command]
+pub async fn session_restart(
+    session_id: String,
+    clear_scrollback: Option<bool>,
+    app_handle: tauri::AppHandle,
+    manager: State<'_, SessionManager>,
+) -> Result<(), TerminalError> {
+    info!(session_id, clear_scrollback = ?clear_scrollback, "Restarting session");
+    manager
+        .restart_session(&session_id, clear_scrollback.unwrap_or(true), app_handle)
+        .await
+}
+
+/// Start recording the input written to a session into an asciicast file.
+/// This is a session recording with input events, so it shares its slot:
+/// starting one replaces the other.
+#[tauri::command]
+pub async fn session_start_input_recording(
+    session_id: String,
+    path: String,
+    manager: State<'_, SessionManager>,
+) -> Result<(), TerminalError> {
+    manager
+        .start_session_recording(&session_id, std::path::Path::new(&path), true)
+        .await
+}
+
+/// Stop recording a session's input. Returns `false` if none was running.
+#[tauri::command]
+pub async fn session_stop_input_recording(
+    session_id: String,
+    manager: State<'_, SessionManager>,
+) -> Result<bool, TerminalError> {
+    Ok(manager.stop_session_recording(&session_id))
+}
+
+/// Start logging a session's output to a file. With
+/// `collapse_carriage_returns`, progress-bar style `\r` redraws are reduced
+/// to their final state in the log.
+#[tauri::command]
+pub async fn session_start_output_log(
+    session_id: String,
+    path: String,
+    collapse_carriage_returns: Option<bool>,
+    manager: State<'_, SessionManager>,
+) -> Result<(), TerminalError> {
+    manager
+        .start_output_log(
+            &session_id,
+            std::path::Path::new(&path),
+            collapse_carriage_returns.unwrap_or(false),
+        )
+        .await
+}
+
+/// Stop logging a session's output. Returns `false` if no log was running.
+#[tauri::command]
+pub async fn session_stop_output_log(
+    session_id: String,
+    manager: State<'_, SessionManager>,
+) -> Result<bool, TerminalError> {
+    Ok(manager.stop_output_log(&session_id))
+}
+
+/// Start recording a session to an asciicast v2 file: its output, resizes
+/// and, with `record_input`, the input written to it.
+#[tauri::command]
+pub async fn session_start_recording(
+    session_id: String,
+    path: String,
+    record_input: Option<bool>,
+    manager: State<'_, SessionManager>,
+) -> Result<(), TerminalError> {
+    manager
+        .start_session_recording(
+            &session_id,
+            std::path::Path::new(&path),
+            record_input.unwrap_or(false),
+        )
+        .await
+}
+
+/// Stop recording a session. Returns `false` if no recording was running.
+#[tauri::command]
+pub async fn session_stop_recording(
+    session_id: String,
+    manager: State<'_, SessionManager>,
+) -> Result<bool, TerminalError> {
+    Ok(manager.stop_session_recording(&session_id))
+}
+
+/// Stop forwarding a session's output to the terminal while the backend
+/// keeps running. Returns `false` if the output was already paused.
+#[tauri::command]
+pub async fn session_pause_output(
+    session_id: String,
+    manager: State<'_, SessionManager>,
+) -> Result<bool, TerminalError> {
+    manager.pause_output(&session_id).await
+}
+
+/// Resume a session's output, delivering what arrived while it was paused.
+/// Returns `false` if the output was not paused.
+#[tauri::command]
+pub async fn session_resume_output(
+    session_id: String,
+    app_handle: tauri::AppHandle,
+    manager: State<'_, SessionManager>,
+) -> Result<bool, TerminalError> {
+    Ok(manager.resume_output(&session_id, &app_handle))
+}
+
+/// Plain text of display rows `start..end` (0-based, end exclusive) of a
+/// session's output, with escape sequences removed and wrapped lines joined.
+#[tauri::command]
+pub async fn session_extract_text(
+    session_id: String,
+    start: usize,
+    end: usize,
+    manager: State<'_, SessionManager>,
+) -> Result<String, TerminalError> {
+    manager.extract_text(&session_id, start, end).await
+}
+
+/// Search the recent output of every open session for `needle` and report
+/// the sessions that contain it.
+#[tauri::command]
+pub async fn search_all_sessions(
+    needle: String,
+    case_insensitive: Option<bool>,
+    manager: State<'_, SessionManager>,
+) -> Result<Vec<SessionSearchResult>, TerminalError> {
+    Ok(manager.search_all_sessions(&needle, case_insensitive.unwrap_or(false)))
+}
+
+/// OS process ID of a session's local child process, if it has one.
+#[tauri::command]
+pub async fn session_pid(
+    session_id: String,
+    manager: State<'_, SessionManager>,
+) -> Result<Option<u32>, TerminalError> {
+    manager.session_pid(&session_id).await
+}
+
+/// Replay recorded input into a session with the recorded timing divided
+/// by `speed` (default 1.0). Returns the number of writes replayed.
+#[tauri::command]
+pub async fn session_replay_input(
+    session_id: String,
+    path: String,
+    speed: Option<f64>,
+    manager: State<'_, SessionManager>,
+) -> Result<usize, TerminalError> {
+    info!(session_id, path, speed = ?speed, "Replaying recorded input");
+    manager
+        .replay_input(
+            &session_id,
+            std::path::Path::new(&path),
+            speed.unwrap_or(1.0),
+        )
+        .await
+}
+
+/// Send the contents of a file to a session as input, raw (`ascii`) or
+/// with XMODEM. `line_delay_ms` pauses after each line of an ASCII send.
+/// Returns the number of file bytes sent.
+#[tauri::command]
+pub async fn session_send_file(
+    session_id: String,
+    path: String,
+    protocol: FileSendProtocol,
+    line_delay_ms: Option<u32>,
+    manager: State<'_, SessionManager>,
+) -> Result<u64, TerminalError> {
+    info!(session_id, path, ?protocol, "Sending file to session");
+    manager
+        .send_file(
+            &session_id,
+            Path::new(&path),
+            protocol,
+            std::time::Duration::from_millis(u64::from(line_delay_ms.unwrap_or(0))),
+        )
+        .await
 }
 
 /// List all active local sessions.
@@ -82,6 +479,14 @@ pub async fn list_local_sessions(
     Ok(manager.list_sessions().await)
 }
 
+/// List live desktop sessions with their type, title, size and capabilities.
+#[tauri::command]
+pub async fn list_active_sessions(
+    manager: State<'_, SessionManager>,
+) -> Result<Vec<ActiveSessionInfo>, String> {
+    Ok(manager.list_active_sessions().await)
+}
+
 /// List available shells on this platform.
 #[tauri::command]
 pub fn list_available_shells() -> Vec<String> {
@@ -94,6 +499,12 @@ pub fn get_default_shell() -> Option<String> {
     shell_detect::detect_default_shell()
 }
 
+/// List installed WSL distributions.
+#[tauri::command]
+pub fn list_wsl_distributions() -> Vec<String> {
+    shell_detect::list_wsl_distributions()
+}
+
 /// List available serial ports.
 #[tauri::command]
 pub fn list_serial_ports() -> Vec<String> {
@@ -106,10 +517,10 @@ pub fn check_x11_available() -> bool {
     crate::utils::x11_detect::is_x_server_likely_running()
 }
 
-/// Check whether the SSH agent is running, stopped, or not installed.
+/// Check whether the SSH agent is running and how many keys it holds.
 #[tauri::command]
-pub fn check_ssh_agent_status() -> String {
-    crate::utils::ssh_auth::check_ssh_agent_status()
+pub fn check_ssh_agent_status() -> SshAgentStatus {
+    termihub_core::backends::ssh::auth::ssh_agent_status()
 }
 
 /// Check if Docker is available on the local system.
@@ -150,6 +561,56 @@ pub async fn validate_ssh_key(path: String) -> crate::utils::ssh_key_validate::S
     })
 }
 
+/// Generate an SSH keypair: the private key at `out_path` (mode `0600`)
+/// and the public key at `out_path.pub`.
+///
+/// `bits` applies to RSA only. Returns the public key line for copying to
+/// the server's `authorized_keys`.
+#[tauri::command]
+pub async fn generate_ssh_key(
+    key_type: SshKeyType,
+    bits: Option<usize>,
+    passphrase: Option<String>,
+    comment: Option<String>,
+    out_path: String,
+) -> Result<String, TerminalError> {
+    info!(?key_type, bits, out_path, "Generating SSH key");
+    let out_path = expand_tilde(&out_path);
+    tauri::async_runtime::spawn_blocking(move || {
+        generate_key(
+            key_type,
+            bits,
+            passphrase.as_deref(),
+            comment.as_deref().unwrap_or(""),
+            Path::new(&out_path),
+        )
+    })
+    .await
+    .map_err(|e| TerminalError::SshError(format!("Key generation task failed: {e}")))?
+    .map_err(|e| TerminalError::SshError(e.to_string()))
+}
+
+/// Run a single command over SSH without a PTY and capture its output.
+///
+/// Opens a dedicated connection from `config` (SSH settings JSON, as for
+/// `create_connection`), waits for the command to exit, and disconnects.
+#[tauri::command]
+pub async fn ssh_run_command(
+    config: Value,
+    command: String,
+) -> Result<CommandOutput, TerminalError> {
+    let config = parse_ssh_settings(&config)
+        .expand()
+        .apply_ssh_config_file()
+        .map_err(|e| TerminalError::SshError(e.to_string()))?;
+    validate_ssh_config(&config).map_err(|e| TerminalError::SshError(e.to_string()))?;
+    info!(host = %config.host, port = config.port, "Running SSH command");
+    tauri::async_runtime::spawn_blocking(move || run_command(&config, &command))
+        .await
+        .map_err(|e| TerminalError::SshError(format!("Command task failed: {e}")))?
+        .map_err(|e| TerminalError::SshError(e.to_string()))
+}
+
 // --- Session-based file browsing commands ---
 
 /// List directory contents via a session's file browser capability.
@@ -174,16 +635,20 @@ pub async fn session_read_file(
     manager.read_file(&session_id, &path).await
 }
 
-/// Write a file via a session's file browser capability.
+/// Write a file via a session's file browser capability, optionally
+/// normalizing line endings.
 #[tauri::command]
 pub async fn session_write_file(
     session_id: String,
     path: String,
     data: Vec<u8>,
+    newline: Option<NewlineMode>,
     manager: State<'_, SessionManager>,
 ) -> Result<(), TerminalError> {
-    debug!(session_id, path, "Session file write");
-    manager.write_file(&session_id, &path, &data).await
+    debug!(session_id, path, ?newline, "Session file write");
+    manager
+        .write_file(&session_id, &path, &data, newline.unwrap_or_default())
+        .await
 }
 
 /// Delete a file via a session's file browser capability.
@@ -198,26 +663,160 @@ pub async fn session_delete_file(
 }
 
 /// Rename a file via a session's file browser capability.
+///
+/// Refuses to replace an existing destination unless `overwrite` is `true`.
 #[tauri::command]
 pub async fn session_rename_file(
     session_id: String,
     old_path: String,
     new_path: String,
+    overwrite: Option<bool>,
     manager: State<'_, SessionManager>,
 ) -> Result<(), TerminalError> {
-    debug!(session_id, old_path, new_path, "Session file rename");
-    manager.rename_file(&session_id, &old_path, &new_path).await
+    debug!(
+        session_id,
+        old_path,
+        new_path,
+        ?overwrite,
+        "Session file rename"
+    );
+    manager
+        .rename_file(
+            &session_id,
+            &old_path,
+            &new_path,
+            overwrite.unwrap_or(false),
+        )
+        .await
 }
 
-/// Create a directory via a session's file browser capability.
+/// Create a directory via a session's file browser capability, with its
+/// missing parents when `recursive` is set.
 #[tauri::command]
 pub async fn session_mkdir(
     session_id: String,
     path: String,
+    recursive: Option<bool>,
     manager: State<'_, SessionManager>,
 ) -> Result<(), TerminalError> {
     debug!(session_id, path, "Session mkdir");
-    manager.mkdir_file(&session_id, &path).await
+    manager
+        .mkdir_file(&session_id, &path, recursive.unwrap_or(false))
+        .await
+}
+
+/// Download a directory as a `tar.gz` or `zip` archive via a session's
+/// file browser. Returns the number of archive bytes written.
+///
+/// Emits `sftp-transfer-progress` events with a `total` of zero, since the
+/// archive size is not known until it is complete. A final event carries
+/// the archive size as both `transferred` and `total`.
+#[tauri::command]
+pub async fn session_download_archive(
+    session_id: String,
+    path: String,
+    format: ArchiveFormat,
+    local_path: String,
+    manager: State<'_, SessionManager>,
+    app_handle: tauri::AppHandle,
+) -> Result<u64, TerminalError> {
+    info!(
+        session_id,
+        path,
+        ?format,
+        local_path,
+        "Session download archive"
+    );
+    let mut emit = progress_emitter(app_handle, session_id.clone(), path.clone());
+    let written = manager
+        .download_archive(
+            &session_id,
+            &path,
+            format,
+            Path::new(&local_path),
+            &mut |transferred| {
+                emit(TransferProgress {
+                    transferred,
+                    total: 0,
+                })
+            },
+        )
+        .await?;
+    // The size is known once the archive is complete; report it as the
+    // final update so listeners see the transfer finish.
+    emit(TransferProgress {
+        transferred: written,
+        total: written,
+    });
+    Ok(written)
+}
+
+/// Compute the total size of a file or directory tree via a session's file browser.
+#[tauri::command]
+pub async fn session_dir_size(
+    session_id: String,
+    path: String,
+    manager: State<'_, SessionManager>,
+) -> Result<u64, TerminalError> {
+    debug!(session_id, path, "Session dir size");
+    manager.dir_size(&session_id, &path).await
+}
+
+/// Search file contents below a directory via a session's file browser.
+///
+/// `options` defaults to a case-sensitive search capped at
+/// [`GREP_MAX_RESULTS`](termihub_core::files::grep::GREP_MAX_RESULTS) matches.
+#[tauri::command]
+pub async fn session_grep(
+    session_id: String,
+    root: String,
+    pattern: String,
+    options: Option<GrepOptions>,
+    manager: State<'_, SessionManager>,
+) -> Result<Vec<GrepMatch>, TerminalError> {
+    debug!(session_id, root, "Session grep");
+    manager
+        .grep(&session_id, &root, &pattern, &options.unwrap_or_default())
+        .await
+}
+
+/// Compare a local file with a file in a session's file browser.
+///
+/// Reports size, modification time and, when `checksum` is `true`, the
+/// SHA-256 of both files, plus whether they differ.
+#[tauri::command]
+pub async fn session_compare_files(
+    local_path: String,
+    session_id: String,
+    remote_path: String,
+    checksum: Option<bool>,
+    manager: State<'_, SessionManager>,
+) -> Result<FileComparison, TerminalError> {
+    debug!(
+        session_id,
+        local_path,
+        remote_path,
+        ?checksum,
+        "Session compare files"
+    );
+    manager
+        .compare_files(
+            &session_id,
+            &local_path,
+            &remote_path,
+            checksum.unwrap_or(false),
+        )
+        .await
+}
+
+/// Cancel an in-flight directory size computation for a session.
+#[tauri::command]
+pub async fn session_cancel_dir_size(
+    session_id: String,
+    manager: State<'_, SessionManager>,
+) -> Result<(), TerminalError> {
+    manager.cancel_dir_size(&session_id).await;
+    Ok(())
 }
 
 // --- Session-based monitoring commands ---
@@ -1,6 +1,6 @@
 use tauri::State;
 
-use crate::tunnel::config::{TunnelConfig, TunnelState};
+use crate::tunnel::config::{TunnelConfig, TunnelMetrics, TunnelState};
 use crate::tunnel::tunnel_manager::TunnelManager;
 use crate::utils::errors::TerminalError;
 
@@ -36,6 +36,14 @@ pub fn get_tunnel_statuses(
     manager.get_statuses()
 }
 
+/// Get traffic totals and throughput for all tunnels.
+#[tauri::command]
+pub fn get_tunnel_metrics(
+    manager: State<'_, TunnelManager>,
+) -> Result<Vec<TunnelMetrics>, TerminalError> {
+    manager.get_metrics()
+}
+
 /// Start a tunnel by ID.
 #[tauri::command]
 pub fn start_tunnel(
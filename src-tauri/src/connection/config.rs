@@ -3,6 +3,9 @@ use serde::{Deserialize, Serialize};
 use crate::credential::crypto::EncryptedEnvelope;
 use crate::terminal::backend::{ConnectionConfig, RemoteAgentConfig};
 
+/// Maximum size (in bytes) of the markdown notes attached to a connection.
+pub const MAX_NOTES_BYTES: usize = 64 * 1024;
+
 fn default_true() -> bool {
     true
 }
@@ -43,6 +46,14 @@ pub struct AgentSettings {
     /// Enable verbose JSON-RPC protocol tracing in agent logs.
     #[serde(default)]
     pub verbose_tracing: bool,
+    /// Close detached, idle daemon sessions after this many seconds.
+    /// `0` keeps them running indefinitely.
+    #[serde(default)]
+    pub daemon_idle_ttl_secs: u64,
+    /// Fail agent requests that take longer than this many seconds.
+    /// `0` waits indefinitely.
+    #[serde(default)]
+    pub request_timeout_secs: u64,
 }
 
 impl Default for AgentSettings {
@@ -55,6 +66,8 @@ impl Default for AgentSettings {
             starting_directory: "~".to_string(),
             log_level: "info".to_string(),
             verbose_tracing: false,
+            daemon_idle_ttl_secs: 0,
+            request_timeout_secs: 0,
         }
     }
 }
@@ -106,6 +119,15 @@ pub enum ConnectionTreeNode {
         config: ConnectionConfig,
         #[serde(skip_serializing_if = "Option::is_none")]
         terminal_options: Option<TerminalOptions>,
+        /// Free-form markdown notes (e.g. runbook steps) attached by the user.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        notes: Option<String>,
+        /// ID of a saved remote agent to open this connection through.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        via_agent: Option<String>,
+        /// Whether to connect automatically when the app launches.
+        #[serde(default)]
+        auto_connect: bool,
     },
 }
 
@@ -200,6 +222,21 @@ pub struct SavedConnection {
     pub folder_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub terminal_options: Option<TerminalOptions>,
+    /// Free-form markdown notes attached to the connection.
+    ///
+    /// Limited to [`MAX_NOTES_BYTES`]; enforced on save.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// ID of a saved remote agent to open this connection through.
+    ///
+    /// When set, the session is created on that agent (`session.create`)
+    /// and its terminal I/O is proxied over the agent transport, so the
+    /// connection originates from the agent's network.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub via_agent: Option<String>,
+    /// Whether to connect automatically when the app launches.
+    #[serde(default)]
+    pub auto_connect: bool,
     /// Runtime-only: which external file this connection was loaded from.
     /// `None` = main connections.json, `Some(path)` = external file.
     #[serde(skip_serializing_if = "Option::is_none", default)]
@@ -229,6 +266,18 @@ pub struct FlatConnectionStore {
     pub agents: Vec<SavedRemoteAgent>,
 }
 
+impl FlatConnectionStore {
+    /// IDs of the connections marked to connect when the app launches, in
+    /// store order.
+    pub fn auto_connect_ids(&self) -> Vec<String> {
+        self.connections
+            .iter()
+            .filter(|c| c.auto_connect)
+            .map(|c| c.id.clone())
+            .collect()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -263,6 +312,9 @@ mod tests {
                 name: "My SSH".to_string(),
                 config: make_ssh_config(),
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
             }],
         };
         let json = serde_json::to_string(&node).unwrap();
@@ -290,6 +342,9 @@ mod tests {
                 horizontal_scrolling: Some(true),
                 ..Default::default()
             }),
+            notes: Some("# Runbook\n\n- restart `nginx`".to_string()),
+            via_agent: Some("gateway".to_string()),
+            auto_connect: true,
         };
         let json = serde_json::to_string(&node).unwrap();
         let deserialized: ConnectionTreeNode = serde_json::from_str(&json).unwrap();
@@ -298,10 +353,16 @@ mod tests {
                 name,
                 config,
                 terminal_options,
+                notes,
+                via_agent,
+                auto_connect,
             } => {
                 assert_eq!(name, "Local Shell");
                 assert_eq!(config.type_id, "local");
                 assert!(terminal_options.is_some());
+                assert_eq!(notes.as_deref(), Some("# Runbook\n\n- restart `nginx`"));
+                assert_eq!(via_agent.as_deref(), Some("gateway"));
+                assert!(auto_connect);
             }
             _ => panic!("Expected Connection"),
         }
@@ -319,12 +380,18 @@ mod tests {
                         name: "Prod SSH".to_string(),
                         config: make_ssh_config(),
                         terminal_options: None,
+                        notes: None,
+                        via_agent: None,
+                        auto_connect: false,
                     }],
                 },
                 ConnectionTreeNode::Connection {
                     name: "Local".to_string(),
                     config: make_local_config(),
                     terminal_options: None,
+                    notes: None,
+                    via_agent: None,
+                    auto_connect: false,
                 },
             ],
             agents: vec![],
@@ -414,6 +481,8 @@ mod tests {
         assert_eq!(settings.starting_directory, "~");
         assert_eq!(settings.log_level, "info");
         assert!(!settings.verbose_tracing);
+        assert_eq!(settings.daemon_idle_ttl_secs, 0);
+        assert_eq!(settings.request_timeout_secs, 0);
     }
 
     #[test]
@@ -426,12 +495,75 @@ mod tests {
                 color: None,
                 ..Default::default()
             }),
+            notes: None,
+            via_agent: None,
+            auto_connect: false,
         };
         let json: serde_json::Value = serde_json::to_value(&node).unwrap();
         assert_eq!(json.get("type").unwrap(), "connection");
         assert_eq!(json.get("name").unwrap(), "Test");
         assert!(json.get("config").is_some());
         assert!(json.get("terminalOptions").is_some());
+        assert!(json.get("notes").is_none());
+    }
+
+    #[test]
+    fn connection_node_without_notes_backward_compat() {
+        // Connections saved before notes existed should load with `None`
+        let json = r#"{"type":"connection","name":"Old","config":{"type":"local","config":{}}}"#;
+        let node: ConnectionTreeNode = serde_json::from_str(json).unwrap();
+        match node {
+            ConnectionTreeNode::Connection { notes, .. } => assert!(notes.is_none()),
+            _ => panic!("Expected Connection"),
+        }
+    }
+
+    #[test]
+    fn connection_node_without_auto_connect_defaults_false() {
+        let json = r#"{"type":"connection","name":"Old","config":{"type":"local","config":{}}}"#;
+        let node: ConnectionTreeNode = serde_json::from_str(json).unwrap();
+        match node {
+            ConnectionTreeNode::Connection { auto_connect, .. } => assert!(!auto_connect),
+            _ => panic!("Expected Connection"),
+        }
+    }
+
+    fn saved_connection(id: &str, auto_connect: bool) -> SavedConnection {
+        SavedConnection {
+            id: id.to_string(),
+            name: id.to_string(),
+            config: make_local_config(),
+            folder_id: None,
+            terminal_options: None,
+            notes: None,
+            via_agent: None,
+            auto_connect,
+            source_file: None,
+        }
+    }
+
+    #[test]
+    fn auto_connect_ids_selects_marked_connections_in_order() {
+        let store = FlatConnectionStore {
+            connections: vec![
+                saved_connection("Work/db", true),
+                saved_connection("scratch", false),
+                saved_connection("router", true),
+            ],
+            folders: Vec::new(),
+            agents: Vec::new(),
+        };
+        assert_eq!(store.auto_connect_ids(), vec!["Work/db", "router"]);
+    }
+
+    #[test]
+    fn auto_connect_ids_empty_without_marked_connections() {
+        let store = FlatConnectionStore {
+            connections: vec![saved_connection("scratch", false)],
+            folders: Vec::new(),
+            agents: Vec::new(),
+        };
+        assert!(store.auto_connect_ids().is_empty());
     }
 
     #[test]
@@ -0,0 +1,280 @@
+//! Launching a [`ConnectionGroup`](super::settings::ConnectionGroup) or
+//! restoring a [`SessionLayout`].
+//!
+//! Every member is launched independently: a member that fails to connect
+//! is reported in its [`GroupMemberResult`] and does not stop the others.
+
+use std::future::Future;
+
+use serde::Serialize;
+use tokio::task::JoinSet;
+
+use super::settings::SessionLayout;
+
+/// Outcome of launching one member of a connection group.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupMemberResult {
+    pub connection_id: String,
+    /// Session ID when the member connected.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// Error message when the member failed to connect.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl GroupMemberResult {
+    pub fn new(connection_id: String, result: Result<String, String>) -> Self {
+        match result {
+            Ok(session_id) => Self {
+                connection_id,
+                session_id: Some(session_id),
+                error: None,
+            },
+            Err(error) => Self {
+                connection_id,
+                session_id: None,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+/// Outcome of reopening one session of a [`SessionLayout`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoredSession {
+    #[serde(flatten)]
+    pub result: GroupMemberResult,
+    /// Whether the session's tab was the active one when saved.
+    pub active: bool,
+}
+
+/// Result of restoring a [`SessionLayout`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutRestoreResult {
+    /// Sessions whose connection still exists, in tab order.
+    pub sessions: Vec<RestoredSession>,
+    /// Saved connections that no longer exist and were skipped.
+    pub missing_connection_ids: Vec<String>,
+}
+
+/// Reopen the sessions of `layout` one after another with `launch`.
+///
+/// Entries whose connection no longer exists according to `exists` are
+/// skipped and reported in [`LayoutRestoreResult::missing_connection_ids`].
+pub async fn restore_layout<F, Fut>(
+    layout: &SessionLayout,
+    exists: impl Fn(&str) -> bool,
+    launch: F,
+) -> LayoutRestoreResult
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<String, String>> + Send + 'static,
+{
+    let (present, missing): (Vec<_>, Vec<_>) = layout
+        .sessions
+        .iter()
+        .partition(|entry| exists(&entry.connection_id));
+    let ids: Vec<String> = present.iter().map(|e| e.connection_id.clone()).collect();
+    let results = launch_group(&ids, false, launch).await;
+
+    LayoutRestoreResult {
+        sessions: results
+            .into_iter()
+            .zip(present)
+            .map(|(result, entry)| RestoredSession {
+                result,
+                active: entry.active,
+            })
+            .collect(),
+        missing_connection_ids: missing
+            .into_iter()
+            .map(|e| e.connection_id.clone())
+            .collect(),
+    }
+}
+
+/// Launch every connection in `connection_ids` with `launch`.
+///
+/// Sequential launches wait for each member before starting the next;
+/// parallel launches start all members at once. Results are returned in
+/// group order either way.
+pub async fn launch_group<F, Fut>(
+    connection_ids: &[String],
+    parallel: bool,
+    launch: F,
+) -> Vec<GroupMemberResult>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<String, String>> + Send + 'static,
+{
+    if !parallel {
+        let mut results = Vec::with_capacity(connection_ids.len());
+        for id in connection_ids {
+            let result = launch(id.clone()).await;
+            results.push(GroupMemberResult::new(id.clone(), result));
+        }
+        return results;
+    }
+
+    let mut tasks = JoinSet::new();
+    for (index, id) in connection_ids.iter().enumerate() {
+        let fut = launch(id.clone());
+        tasks.spawn(async move { (index, fut.await) });
+    }
+
+    let mut results: Vec<Option<Result<String, String>>> = vec![None; connection_ids.len()];
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((index, result)) = joined {
+            results[index] = Some(result);
+        }
+    }
+
+    connection_ids
+        .iter()
+        .zip(results)
+        .map(|(id, result)| {
+            let result = result.unwrap_or_else(|| Err("Launch task failed".to_string()));
+            GroupMemberResult::new(id.clone(), result)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    /// Launcher that fails for `"bad"` and records every launched ID.
+    fn fake_launcher(
+        launched: Arc<Mutex<Vec<String>>>,
+    ) -> impl Fn(String) -> std::pin::Pin<Box<dyn Future<Output = Result<String, String>> + Send>>
+    {
+        move |id: String| {
+            let launched = launched.clone();
+            Box::pin(async move {
+                // Later members finish first, so parallel ordering is exercised.
+                if id == "a" {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+                launched.lock().unwrap().push(id.clone());
+                if id == "bad" {
+                    Err("Connection refused".to_string())
+                } else {
+                    Ok(format!("session-{id}"))
+                }
+            })
+        }
+    }
+
+    async fn assert_launches_all(parallel: bool) {
+        let launched = Arc::new(Mutex::new(Vec::new()));
+        let members = ids(&["a", "bad", "c"]);
+        let results = launch_group(&members, parallel, fake_launcher(launched.clone())).await;
+
+        let mut seen = launched.lock().unwrap().clone();
+        seen.sort();
+        assert_eq!(seen, ["a", "bad", "c"]);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].connection_id, "a");
+        assert_eq!(results[0].session_id.as_deref(), Some("session-a"));
+        assert_eq!(results[1].connection_id, "bad");
+        assert_eq!(results[1].session_id, None);
+        assert_eq!(results[1].error.as_deref(), Some("Connection refused"));
+        assert_eq!(results[2].connection_id, "c");
+        assert_eq!(results[2].session_id.as_deref(), Some("session-c"));
+    }
+
+    #[tokio::test]
+    async fn sequential_launch_continues_past_failures() {
+        assert_launches_all(false).await;
+    }
+
+    #[tokio::test]
+    async fn parallel_launch_continues_past_failures() {
+        assert_launches_all(true).await;
+    }
+
+    #[tokio::test]
+    async fn sequential_launch_preserves_order() {
+        let launched = Arc::new(Mutex::new(Vec::new()));
+        launch_group(
+            &ids(&["a", "bad", "c"]),
+            false,
+            fake_launcher(launched.clone()),
+        )
+        .await;
+        assert_eq!(*launched.lock().unwrap(), ["a", "bad", "c"]);
+    }
+
+    #[tokio::test]
+    async fn restore_layout_skips_missing_connections() {
+        use super::super::settings::SessionLayoutEntry;
+
+        let entry = |id: &str, active: bool| SessionLayoutEntry {
+            connection_id: id.to_string(),
+            active,
+        };
+        let layout = SessionLayout {
+            name: "Morning".to_string(),
+            sessions: vec![
+                entry("a", false),
+                entry("deleted", false),
+                entry("c", true),
+                entry("bad", false),
+            ],
+        };
+        let launched = Arc::new(Mutex::new(Vec::new()));
+        let result = restore_layout(
+            &layout,
+            |id| id != "deleted",
+            fake_launcher(launched.clone()),
+        )
+        .await;
+
+        assert_eq!(*launched.lock().unwrap(), ["a", "c", "bad"]);
+        assert_eq!(result.missing_connection_ids, ["deleted"]);
+        let sessions: Vec<(&str, Option<&str>, bool)> = result
+            .sessions
+            .iter()
+            .map(|s| {
+                (
+                    s.result.connection_id.as_str(),
+                    s.result.session_id.as_deref(),
+                    s.active,
+                )
+            })
+            .collect();
+        assert_eq!(
+            sessions,
+            [
+                ("a", Some("session-a"), false),
+                ("c", Some("session-c"), true),
+                ("bad", None, false),
+            ]
+        );
+
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["missingConnectionIds"][0], "deleted");
+        assert_eq!(json["sessions"][1]["sessionId"], "session-c");
+        assert_eq!(json["sessions"][1]["active"], true);
+    }
+
+    #[test]
+    fn result_serializes_camel_case() {
+        let result = GroupMemberResult::new("conn-1".to_string(), Ok("s1".to_string()));
+        let json = serde_json::to_value(&result).unwrap();
+        assert_eq!(json["connectionId"], "conn-1");
+        assert_eq!(json["sessionId"], "s1");
+        assert!(json.get("error").is_none());
+    }
+}
@@ -3,14 +3,17 @@ use std::sync::{Arc, Mutex};
 
 use anyhow::{Context, Result};
 use tauri::AppHandle;
+use termihub_core::backends::ssh::jump::jump_host_config;
+use termihub_core::backends::ssh::parse_ssh_settings;
+use termihub_core::session::ssh::parse_proxy_jump;
 
 use super::config::{
     AgentSettings, ConnectionFolder, ConnectionStore, EncryptedConnectionExport,
     ExternalConnectionStore, FlatConnectionStore, ImportPreview, ImportResult, SavedConnection,
-    SavedRemoteAgent,
+    SavedRemoteAgent, MAX_NOTES_BYTES,
 };
 use super::recovery::RecoveryWarning;
-use super::settings::{AppSettings, SettingsStorage};
+use super::settings::{AppSettings, FileBookmark, SessionLayout, SettingsStorage};
 use super::storage::ConnectionStorage;
 use super::tree::{
     build_tree, compute_connection_id, compute_folder_id, count_tree_items,
@@ -52,6 +55,99 @@ pub(crate) fn prepare_for_storage(
     Ok(connection)
 }
 
+/// Inject the stored credential into a saved connection's settings so it
+/// can be launched without prompting.
+///
+/// Mirrors the frontend lookup: password auth uses the stored password,
+/// key auth with `savePassword` uses the stored key passphrase. Store
+/// errors (e.g. a locked store) are treated as "not found".
+pub(crate) fn prepare_for_launch(
+    mut connection: SavedConnection,
+    store: &dyn CredentialStore,
+) -> SavedConnection {
+    let settings = &mut connection.config.settings;
+    let auth_method = settings.get("authMethod").and_then(|v| v.as_str());
+    let save_password = settings.get("savePassword").and_then(|v| v.as_bool()) == Some(true);
+    let cred_type = match auth_method {
+        Some("password") => CredentialType::Password,
+        Some("key") if save_password => CredentialType::KeyPassphrase,
+        _ => return connection,
+    };
+    let key = CredentialKey::new(&connection.id, cred_type);
+    if let Ok(Some(value)) = store.get(&key) {
+        if !value.is_empty() {
+            if let Some(obj) = settings.as_object_mut() {
+                obj.insert("password".to_string(), serde_json::Value::String(value));
+            }
+        }
+    }
+    connection
+}
+
+/// Resolve `proxyJump` hops that name saved SSH connections.
+///
+/// When at least one hop is the ID of a saved SSH connection, `jumpHosts`
+/// is set to the settings of every hop in order: saved connections launch
+/// with their own settings and credentials (looked up via `lookup`), the
+/// other hops with the target's key files and agent but none of its
+/// passwords (see [`jump_host_config`]). Settings without such hops,
+/// or with a malformed hop, are left unchanged.
+pub(crate) fn resolve_jump_hosts(
+    settings: &mut serde_json::Value,
+    lookup: impl Fn(&str) -> Option<SavedConnection>,
+) {
+    let Some(spec) = settings.get("proxyJump").and_then(|v| v.as_str()) else {
+        return;
+    };
+    let hops: Vec<(String, Option<SavedConnection>)> = spec
+        .split(',')
+        .map(|hop| {
+            let hop = hop.trim().to_string();
+            let saved = lookup(&hop).filter(|c| c.config.type_id == "ssh");
+            (hop, saved)
+        })
+        .collect();
+    if hops.iter().all(|(_, saved)| saved.is_none()) {
+        return;
+    }
+
+    let target = parse_ssh_settings(settings);
+    let mut jump_hosts = Vec::with_capacity(hops.len());
+    for (hop, saved) in hops {
+        let hop_settings = match saved {
+            Some(connection) => connection.config.settings,
+            None => match parse_proxy_jump(&hop).as_deref() {
+                Ok([parsed]) => match serde_json::to_value(jump_host_config(&target, parsed)) {
+                    Ok(value) => value,
+                    Err(_) => return,
+                },
+                _ => return,
+            },
+        };
+        jump_hosts.push(hop_settings);
+    }
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert(
+            "jumpHosts".to_string(),
+            serde_json::Value::Array(jump_hosts),
+        );
+    }
+}
+
+/// Reject a connection whose user-provided content exceeds storage limits.
+pub(crate) fn validate_connection(connection: &SavedConnection) -> Result<()> {
+    if let Some(notes) = &connection.notes {
+        if notes.len() > MAX_NOTES_BYTES {
+            anyhow::bail!(
+                "Connection notes are too large ({} bytes, maximum is {} bytes)",
+                notes.len(),
+                MAX_NOTES_BYTES
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Route agent credentials to the active store, then strip the password.
 pub(crate) fn prepare_agent_for_storage(
     mut agent: SavedRemoteAgent,
@@ -147,6 +243,61 @@ impl ConnectionManager {
         })
     }
 
+    /// Look up a saved connection (main store first, then external files)
+    /// with its stored credential injected, ready to launch.
+    pub fn connection_for_launch(&self, id: &str) -> Option<SavedConnection> {
+        let found = self
+            .store
+            .lock()
+            .unwrap()
+            .connections
+            .iter()
+            .find(|c| c.id == id)
+            .cloned()
+            .or_else(|| {
+                self.load_external_sources()
+                    .into_iter()
+                    .flat_map(|source| source.connections)
+                    .find(|c| c.id == id)
+            })?;
+        Some(prepare_for_launch(found, &*self.credential_store))
+    }
+
+    /// Resolve `proxyJump` hops in SSH `settings` that name saved
+    /// connections, see [`resolve_jump_hosts`].
+    pub fn resolve_jump_hosts(&self, settings: &mut serde_json::Value) {
+        resolve_jump_hosts(settings, |id| self.connection_for_launch(id));
+    }
+
+    /// Whether a saved connection exists in the main store or an enabled
+    /// external file.
+    pub fn connection_exists(&self, id: &str) -> bool {
+        self.store
+            .lock()
+            .unwrap()
+            .connections
+            .iter()
+            .any(|c| c.id == id)
+            || self
+                .load_external_sources()
+                .iter()
+                .any(|source| source.connections.iter().any(|c| c.id == id))
+    }
+
+    /// IDs of the connections to open when the app launches: those marked
+    /// `autoConnect` in the main store, then in enabled external files.
+    pub fn auto_connect_ids(&self) -> Vec<String> {
+        let mut ids = self.store.lock().unwrap().auto_connect_ids();
+        ids.extend(
+            self.load_external_sources()
+                .into_iter()
+                .flat_map(|source| source.connections)
+                .filter(|c| c.auto_connect)
+                .map(|c| c.id),
+        );
+        ids
+    }
+
     /// Save (add or update) a remote agent. Passwords are stripped before persisting.
     pub fn save_agent(&self, agent: SavedRemoteAgent) -> Result<()> {
         let agent = prepare_agent_for_storage(agent, &*self.credential_store)?;
@@ -217,6 +368,7 @@ impl ConnectionManager {
     /// names within the folder. If the ID changes (due to move or dedup rename),
     /// credentials are migrated to the new path-based ID.
     pub fn save_connection(&self, connection: SavedConnection) -> Result<()> {
+        validate_connection(&connection)?;
         let connection = prepare_for_storage(connection, &*self.credential_store)?;
         let old_id = connection.id.clone();
         let mut store = self.store.lock().unwrap();
@@ -477,6 +629,53 @@ impl ConnectionManager {
         Ok(())
     }
 
+    /// Switch the active environment profile and persist the change.
+    ///
+    /// `None` deactivates profiles. Placeholders are resolved against the
+    /// new profile on the next connect; open sessions are unaffected.
+    pub fn set_active_profile(&self, name: Option<String>) -> Result<()> {
+        let mut settings = self.get_settings();
+        if let Some(ref name) = name {
+            if !settings.profiles.contains_key(name) {
+                anyhow::bail!("Profile '{name}' not found");
+            }
+        }
+        settings.active_profile = name;
+        self.save_settings(settings)
+    }
+
+    /// Add a file browser bookmark and persist it. Adding an existing
+    /// bookmark again is a no-op.
+    pub fn add_file_bookmark(&self, bookmark: FileBookmark) -> Result<()> {
+        let mut settings = self.get_settings();
+        if settings.add_file_bookmark(bookmark)? {
+            self.save_settings(settings)?;
+        }
+        Ok(())
+    }
+
+    /// Remove a file browser bookmark and persist the change.
+    pub fn remove_file_bookmark(&self, path: &str, connection_id: Option<&str>) -> Result<()> {
+        let mut settings = self.get_settings();
+        if settings.remove_file_bookmark(path, connection_id) {
+            self.save_settings(settings)?;
+        }
+        Ok(())
+    }
+
+    /// Save a session layout under its name and persist it, replacing any
+    /// layout with the same name.
+    pub fn save_session_layout(&self, layout: SessionLayout) -> Result<()> {
+        let mut settings = self.get_settings();
+        settings.save_session_layout(layout)?;
+        self.save_settings(settings)
+    }
+
+    /// Variables of the active environment profile.
+    pub fn active_profile_variables(&self) -> HashMap<String, String> {
+        self.settings.lock().unwrap().active_profile_variables()
+    }
+
     /// Load all enabled external connection files and return flattened connections.
     pub fn load_external_sources(&self) -> Vec<ExternalSource> {
         let settings = self.settings.lock().unwrap().clone();
@@ -510,6 +709,7 @@ impl ConnectionManager {
         match &connection.source_file {
             None => self.save_connection(connection),
             Some(file_path) => {
+                validate_connection(&connection)?;
                 let file_path = file_path.clone();
                 let mut conn = prepare_for_storage(connection, &*self.credential_store)?;
                 conn.source_file = None; // Strip before writing to disk
@@ -1019,8 +1219,14 @@ mod tests {
     }
 
     impl CredentialStore for MockStore {
-        fn get(&self, _key: &CredentialKey) -> Result<Option<String>> {
-            Ok(None)
+        fn get(&self, key: &CredentialKey) -> Result<Option<String>> {
+            Ok(self
+                .stored
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone()))
         }
         fn set(&self, key: &CredentialKey, value: &str) -> Result<()> {
             self.stored
@@ -1032,6 +1238,9 @@ mod tests {
         fn remove(&self, _key: &CredentialKey) -> Result<()> {
             Ok(())
         }
+        fn has_credential(&self, key: &CredentialKey) -> Result<bool> {
+            Ok(self.stored.lock().unwrap().iter().any(|(k, _)| k == key))
+        }
         fn remove_all_for_connection(&self, connection_id: &str) -> Result<()> {
             self.removed_connections
                 .lock()
@@ -1075,6 +1284,9 @@ mod tests {
             },
             folder_id: None,
             terminal_options: None,
+            notes: None,
+            via_agent: None,
+            auto_connect: false,
             source_file: None,
         }
     }
@@ -1089,6 +1301,9 @@ mod tests {
             },
             folder_id: None,
             terminal_options: None,
+            notes: None,
+            via_agent: None,
+            auto_connect: false,
             source_file: None,
         }
     }
@@ -1117,6 +1332,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn validate_connection_accepts_notes_within_limit() {
+        let mut conn = make_local_conn("c1");
+        conn.notes = Some("a".repeat(MAX_NOTES_BYTES));
+        assert!(validate_connection(&conn).is_ok());
+    }
+
+    #[test]
+    fn validate_connection_rejects_oversized_notes() {
+        let mut conn = make_local_conn("c1");
+        conn.notes = Some("a".repeat(MAX_NOTES_BYTES + 1));
+        let err = validate_connection(&conn).unwrap_err();
+        assert!(err.to_string().contains("too large"));
+    }
+
+    #[test]
+    fn saved_connection_notes_serde_round_trip() {
+        let mut conn = make_local_conn("c1");
+        conn.notes = Some("## Restart\n\n`systemctl restart app`".to_string());
+        let json = serde_json::to_string(&conn).unwrap();
+        let restored: SavedConnection = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.notes, conn.notes);
+    }
+
     #[test]
     fn prepare_for_storage_strips_password_when_save_false() {
         let store = MockStore::new();
@@ -1181,6 +1420,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn prepare_for_launch_injects_stored_password() {
+        let store = MockStore::new();
+        let conn = make_ssh_conn("c5", "password", Some("secret"), Some(true));
+        let stored = prepare_for_storage(conn, &store).unwrap();
+        let launched = prepare_for_launch(stored, &store);
+        assert_eq!(launched.config.settings["password"], "secret");
+    }
+
+    #[test]
+    fn prepare_for_launch_skips_key_passphrase_without_save_password() {
+        let store = MockStore::new();
+        store
+            .set(
+                &CredentialKey::new("c6", CredentialType::KeyPassphrase),
+                "passphrase",
+            )
+            .unwrap();
+        let conn = make_ssh_conn("c6", "key", None, None);
+        let launched = prepare_for_launch(conn, &store);
+        assert!(launched.config.settings.get("password").is_none());
+
+        let conn = make_ssh_conn("c6", "key", None, Some(true));
+        let launched = prepare_for_launch(conn, &store);
+        assert_eq!(launched.config.settings["password"], "passphrase");
+    }
+
+    #[test]
+    fn resolve_jump_hosts_uses_saved_connection_settings() {
+        let mut bastion = make_ssh_conn("bastion-id", "password", Some("bastion-pw"), None);
+        bastion.config.settings["host"] = serde_json::json!("bastion.example.com");
+        let lookup = |id: &str| (id == "bastion-id").then(|| bastion.clone());
+
+        let mut settings = serde_json::json!({
+            "host": "app.internal",
+            "username": "dev",
+            "authMethod": "key",
+            "keyPath": "/keys/dev",
+            "proxyJump": "bastion-id, ops@inner:2222"
+        });
+        resolve_jump_hosts(&mut settings, lookup);
+
+        let hops = settings["jumpHosts"].as_array().unwrap();
+        assert_eq!(hops.len(), 2);
+        assert_eq!(hops[0]["host"], "bastion.example.com");
+        assert_eq!(hops[0]["password"], "bastion-pw");
+        assert_eq!(hops[1]["host"], "inner");
+        assert_eq!(hops[1]["port"], 2222);
+        assert_eq!(hops[1]["username"], "ops");
+        assert_eq!(hops[1]["keyPath"], "/keys/dev");
+    }
+
+    #[test]
+    fn resolve_jump_hosts_leaves_plain_hops_alone() {
+        let local = make_local_conn("local-id");
+        let lookup = |id: &str| (id == "local-id").then(|| local.clone());
+        for spec in ["ops@bastion:2204", "local-id", "local-id,bastion:"] {
+            let mut settings = serde_json::json!({"host": "app", "proxyJump": spec});
+            resolve_jump_hosts(&mut settings, lookup);
+            assert!(settings.get("jumpHosts").is_none(), "{spec}");
+        }
+
+        let saved = make_ssh_conn("bastion-id", "agent", None, None);
+        let mut settings = serde_json::json!({"host": "app", "proxyJump": "bastion-id,bad:port"});
+        resolve_jump_hosts(&mut settings, |id: &str| {
+            (id == "bastion-id").then(|| saved.clone())
+        });
+        assert!(settings.get("jumpHosts").is_none());
+    }
+
     #[test]
     fn prepare_agent_for_storage_stores_and_strips() {
         let store = MockStore::new();
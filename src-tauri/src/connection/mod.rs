@@ -1,4 +1,5 @@
 pub mod config;
+pub mod group;
 pub mod manager;
 pub mod recovery;
 pub mod settings;
@@ -1,11 +1,14 @@
+use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager};
 
 use super::recovery::{RecoveryResult, RecoveryWarning};
+use crate::session::manager::DEFAULT_RESIZE_DEBOUNCE;
 
 const FILE_NAME: &str = "settings.json";
 
@@ -40,6 +43,49 @@ fn default_true() -> bool {
     true
 }
 
+/// A named, ordered list of saved connections launched together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionGroup {
+    pub id: String,
+    pub name: String,
+    /// Saved connection IDs, in launch order.
+    pub connection_ids: Vec<String>,
+}
+
+/// One open session recorded in a [`SessionLayout`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionLayoutEntry {
+    /// Saved connection the session was opened from.
+    pub connection_id: String,
+    /// Whether the session's tab was the active one.
+    #[serde(default)]
+    pub active: bool,
+}
+
+/// A named set of open sessions, saved to be reopened together.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionLayout {
+    pub name: String,
+    /// Sessions in tab order.
+    pub sessions: Vec<SessionLayoutEntry>,
+}
+
+/// A file browser path saved for quick navigation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileBookmark {
+    pub path: String,
+    /// Display name; the UI shows the path when unset.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Saved connection the bookmark belongs to. None = shown for every connection.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection_id: Option<String>,
+}
+
 /// Layout configuration for UI section positioning and visibility.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -150,6 +196,30 @@ pub struct AppSettings {
     /// Update checker configuration and state.
     #[serde(default)]
     pub updates: UpdateSettings,
+    /// Named environment profiles (e.g. "dev", "prod"), each a set of
+    /// variables substituted for `${var:NAME}` in connection settings.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub profiles: HashMap<String, HashMap<String, String>>,
+    /// Name of the profile whose variables are used on connect. None = no profile.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+    /// Quiet period in milliseconds before a terminal resize is applied
+    /// (0 applies every resize immediately). None = default (50 ms).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resize_debounce_ms: Option<u64>,
+    /// Connection groups launched together with `connect_group`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub connection_groups: Vec<ConnectionGroup>,
+    /// File browser bookmarks, global and per connection.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub file_bookmarks: Vec<FileBookmark>,
+    /// Saved session layouts, reopened with `restore_session_layout`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub session_layouts: Vec<SessionLayout>,
+    /// External editor command template (e.g. `nvim {path}`). None = first
+    /// default editor found on `PATH`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub editor_command: Option<String>,
 }
 
 impl Default for AppSettings {
@@ -181,7 +251,105 @@ impl Default for AppSettings {
             custom_language_grammars: None,
             experimental_features_enabled: None,
             updates: UpdateSettings::default(),
+            profiles: HashMap::new(),
+            active_profile: None,
+            resize_debounce_ms: None,
+            connection_groups: Vec::new(),
+            file_bookmarks: Vec::new(),
+            session_layouts: Vec::new(),
+            editor_command: None,
+        }
+    }
+}
+
+impl AppSettings {
+    /// Variables of the active profile, or an empty map when no profile is
+    /// active or the active profile no longer exists.
+    pub fn active_profile_variables(&self) -> HashMap<String, String> {
+        self.active_profile
+            .as_ref()
+            .and_then(|name| self.profiles.get(name))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Quiet period before a terminal resize is applied.
+    pub fn resize_debounce(&self) -> Duration {
+        self.resize_debounce_ms
+            .map(Duration::from_millis)
+            .unwrap_or(DEFAULT_RESIZE_DEBOUNCE)
+    }
+
+    /// Look up a connection group by ID.
+    pub fn connection_group(&self, id: &str) -> Option<&ConnectionGroup> {
+        self.connection_groups.iter().find(|g| g.id == id)
+    }
+
+    /// Look up a saved session layout by name.
+    pub fn session_layout(&self, name: &str) -> Option<&SessionLayout> {
+        self.session_layouts.iter().find(|l| l.name == name)
+    }
+
+    /// Save a session layout, replacing any existing layout with the same
+    /// name. The name is trimmed and must not be empty.
+    pub fn save_session_layout(&mut self, mut layout: SessionLayout) -> Result<()> {
+        layout.name = layout.name.trim().to_string();
+        if layout.name.is_empty() {
+            anyhow::bail!("Layout name must not be empty");
+        }
+        match self
+            .session_layouts
+            .iter_mut()
+            .find(|l| l.name == layout.name)
+        {
+            Some(existing) => *existing = layout,
+            None => self.session_layouts.push(layout),
+        }
+        Ok(())
+    }
+
+    /// Bookmarks offered for `connection_id`: global bookmarks first, then
+    /// the connection's own. `None` lists global bookmarks only.
+    pub fn file_bookmarks_for(&self, connection_id: Option<&str>) -> Vec<FileBookmark> {
+        let global = self
+            .file_bookmarks
+            .iter()
+            .filter(|b| b.connection_id.is_none());
+        let own = self
+            .file_bookmarks
+            .iter()
+            .filter(|b| connection_id.is_some() && b.connection_id.as_deref() == connection_id);
+        global.chain(own).cloned().collect()
+    }
+
+    /// Add a bookmark. The path is trimmed and must not be empty; a bookmark
+    /// with the same path and connection is not added twice. Returns whether
+    /// the bookmark was added.
+    pub fn add_file_bookmark(&mut self, mut bookmark: FileBookmark) -> Result<bool> {
+        bookmark.path = bookmark.path.trim().to_string();
+        if bookmark.path.is_empty() {
+            anyhow::bail!("Bookmark path must not be empty");
+        }
+        bookmark.label = bookmark.label.filter(|l| !l.trim().is_empty());
+        if self
+            .file_bookmarks
+            .iter()
+            .any(|b| b.path == bookmark.path && b.connection_id == bookmark.connection_id)
+        {
+            return Ok(false);
         }
+        self.file_bookmarks.push(bookmark);
+        Ok(true)
+    }
+
+    /// Remove the bookmark for `path` and `connection_id`. Returns whether
+    /// one was removed.
+    pub fn remove_file_bookmark(&mut self, path: &str, connection_id: Option<&str>) -> bool {
+        let path = path.trim();
+        let before = self.file_bookmarks.len();
+        self.file_bookmarks
+            .retain(|b| !(b.path == path && b.connection_id.as_deref() == connection_id));
+        self.file_bookmarks.len() != before
     }
 }
 
@@ -283,6 +451,251 @@ mod tests {
         }
     }
 
+    fn profile_settings(active: Option<&str>) -> AppSettings {
+        let profile = |host: &str, user: &str| {
+            HashMap::from([
+                ("HOST".to_string(), host.to_string()),
+                ("USER".to_string(), user.to_string()),
+            ])
+        };
+        AppSettings {
+            profiles: HashMap::from([
+                ("dev".to_string(), profile("dev.example.com", "developer")),
+                ("prod".to_string(), profile("prod.example.com", "deploy")),
+            ]),
+            active_profile: active.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn same_connection_expands_per_active_profile() {
+        use termihub_core::config::expand::expand_var_placeholders_in_value;
+
+        let connection = serde_json::json!({
+            "host": "${var:HOST}",
+            "username": "${var:USER}",
+            "port": 22
+        });
+
+        let mut dev = connection.clone();
+        expand_var_placeholders_in_value(
+            &mut dev,
+            &profile_settings(Some("dev")).active_profile_variables(),
+        );
+        assert_eq!(dev["host"], "dev.example.com");
+        assert_eq!(dev["username"], "developer");
+
+        let mut prod = connection.clone();
+        expand_var_placeholders_in_value(
+            &mut prod,
+            &profile_settings(Some("prod")).active_profile_variables(),
+        );
+        assert_eq!(prod["host"], "prod.example.com");
+        assert_eq!(prod["username"], "deploy");
+    }
+
+    #[test]
+    fn no_active_profile_has_no_variables() {
+        assert!(profile_settings(None).active_profile_variables().is_empty());
+        assert!(profile_settings(Some("gone"))
+            .active_profile_variables()
+            .is_empty());
+    }
+
+    #[test]
+    fn resize_debounce_defaults_and_overrides() {
+        let mut settings = AppSettings::default();
+        assert_eq!(settings.resize_debounce(), DEFAULT_RESIZE_DEBOUNCE);
+        settings.resize_debounce_ms = Some(0);
+        assert_eq!(settings.resize_debounce(), Duration::ZERO);
+
+        let json = serde_json::to_value(&settings).unwrap();
+        assert_eq!(json["resizeDebounceMs"], 0);
+    }
+
+    #[test]
+    fn connection_groups_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let storage = create_test_storage(&dir);
+        let settings = AppSettings {
+            connection_groups: vec![ConnectionGroup {
+                id: "group-1".to_string(),
+                name: "Cluster".to_string(),
+                connection_ids: vec!["conn-b".to_string(), "conn-a".to_string()],
+            }],
+            ..Default::default()
+        };
+        storage.save(&settings).unwrap();
+
+        let loaded = storage.load_with_recovery().unwrap().data;
+        let group = loaded.connection_group("group-1").unwrap();
+        assert_eq!(group.name, "Cluster");
+        assert_eq!(group.connection_ids, ["conn-b", "conn-a"]);
+        assert!(loaded.connection_group("missing").is_none());
+    }
+
+    fn layout(name: &str, connection_ids: &[&str]) -> SessionLayout {
+        SessionLayout {
+            name: name.to_string(),
+            sessions: connection_ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| SessionLayoutEntry {
+                    connection_id: id.to_string(),
+                    active: i == 0,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn session_layouts_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let storage = create_test_storage(&dir);
+        let mut settings = AppSettings::default();
+        settings
+            .save_session_layout(layout(" Morning ", &["conn-a", "conn-b"]))
+            .unwrap();
+        storage.save(&settings).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.path().join(FILE_NAME)).unwrap()).unwrap();
+        assert_eq!(json["sessionLayouts"][0]["name"], "Morning");
+        assert_eq!(
+            json["sessionLayouts"][0]["sessions"][0],
+            serde_json::json!({ "connectionId": "conn-a", "active": true })
+        );
+
+        let loaded = storage.load_with_recovery().unwrap().data;
+        assert_eq!(
+            loaded.session_layout("Morning"),
+            Some(&layout("Morning", &["conn-a", "conn-b"]))
+        );
+        assert!(loaded.session_layout("missing").is_none());
+    }
+
+    #[test]
+    fn saving_session_layout_replaces_same_name() {
+        let mut settings = AppSettings::default();
+        settings
+            .save_session_layout(layout("Morning", &["conn-a"]))
+            .unwrap();
+        settings
+            .save_session_layout(layout("Evening", &["conn-c"]))
+            .unwrap();
+        settings
+            .save_session_layout(layout("Morning", &["conn-b"]))
+            .unwrap();
+
+        assert_eq!(settings.session_layouts.len(), 2);
+        assert_eq!(
+            settings.session_layout("Morning").unwrap().sessions[0].connection_id,
+            "conn-b"
+        );
+        assert!(settings.save_session_layout(layout("  ", &[])).is_err());
+    }
+
+    fn bookmark(path: &str, connection_id: Option<&str>) -> FileBookmark {
+        FileBookmark {
+            path: path.to_string(),
+            label: None,
+            connection_id: connection_id.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn file_bookmarks_add_list_remove() {
+        let mut settings = AppSettings::default();
+        assert!(settings
+            .add_file_bookmark(bookmark("/var/log", None))
+            .unwrap());
+        assert!(settings
+            .add_file_bookmark(bookmark(" /srv/app/releases ", Some("conn-1")))
+            .unwrap());
+        assert!(settings
+            .add_file_bookmark(bookmark("/etc/nginx", Some("conn-2")))
+            .unwrap());
+
+        let paths =
+            |list: Vec<FileBookmark>| -> Vec<String> { list.into_iter().map(|b| b.path).collect() };
+        assert_eq!(
+            paths(settings.file_bookmarks_for(Some("conn-1"))),
+            ["/var/log", "/srv/app/releases"]
+        );
+        assert_eq!(paths(settings.file_bookmarks_for(None)), ["/var/log"]);
+
+        assert!(settings.remove_file_bookmark("/srv/app/releases", Some("conn-1")));
+        assert!(!settings.remove_file_bookmark("/srv/app/releases", Some("conn-1")));
+        // A global bookmark is not removed through a connection.
+        assert!(!settings.remove_file_bookmark("/var/log", Some("conn-1")));
+        assert!(settings.remove_file_bookmark("/var/log", None));
+        assert_eq!(
+            paths(settings.file_bookmarks_for(Some("conn-2"))),
+            ["/etc/nginx"]
+        );
+    }
+
+    #[test]
+    fn file_bookmarks_are_deduplicated() {
+        let mut settings = AppSettings::default();
+        assert!(settings
+            .add_file_bookmark(bookmark("/home/me/src", Some("conn-1")))
+            .unwrap());
+        assert!(!settings
+            .add_file_bookmark(bookmark("/home/me/src ", Some("conn-1")))
+            .unwrap());
+        // The same path for another connection or globally is distinct.
+        assert!(settings
+            .add_file_bookmark(bookmark("/home/me/src", Some("conn-2")))
+            .unwrap());
+        assert!(settings
+            .add_file_bookmark(bookmark("/home/me/src", None))
+            .unwrap());
+        assert_eq!(settings.file_bookmarks.len(), 3);
+    }
+
+    #[test]
+    fn file_bookmark_rejects_empty_path() {
+        let mut settings = AppSettings::default();
+        assert!(settings.add_file_bookmark(bookmark("", None)).is_err());
+        assert!(settings.add_file_bookmark(bookmark("   ", None)).is_err());
+        assert!(settings.file_bookmarks.is_empty());
+    }
+
+    #[test]
+    fn file_bookmarks_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let storage = create_test_storage(&dir);
+        let mut settings = AppSettings::default();
+        settings
+            .add_file_bookmark(FileBookmark {
+                label: Some("Logs".to_string()),
+                ..bookmark("/var/log", Some("conn-1"))
+            })
+            .unwrap();
+        storage.save(&settings).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.path().join(FILE_NAME)).unwrap()).unwrap();
+        assert_eq!(json["fileBookmarks"][0]["connectionId"], "conn-1");
+
+        let loaded = storage.load_with_recovery().unwrap().data;
+        assert_eq!(loaded.file_bookmarks, settings.file_bookmarks);
+    }
+
+    #[test]
+    fn profiles_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let storage = create_test_storage(&dir);
+        storage.save(&profile_settings(Some("dev"))).unwrap();
+
+        let loaded = storage.load_with_recovery().unwrap().data;
+        assert_eq!(loaded.active_profile.as_deref(), Some("dev"));
+        assert_eq!(loaded.profiles.len(), 2);
+        assert_eq!(loaded.profiles["prod"]["HOST"], "prod.example.com");
+    }
+
     #[test]
     fn load_with_recovery_missing_file_returns_defaults() {
         let dir = TempDir::new().unwrap();
@@ -303,6 +303,9 @@ mod tests {
                     settings: serde_json::json!({"shell": "bash"}),
                 },
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
             }],
             agents: vec![],
         };
@@ -333,6 +336,9 @@ mod tests {
                         settings: serde_json::json!({"host": "example.com"}),
                     },
                     terminal_options: None,
+                    notes: None,
+                    via_agent: None,
+                    auto_connect: false,
                 }],
             }],
             agents: vec![],
@@ -434,6 +440,9 @@ mod tests {
                 },
                 folder_id: Some("Work".to_string()),
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             }],
             folders: vec![ConnectionFolder {
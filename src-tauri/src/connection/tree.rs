@@ -58,6 +58,9 @@ pub fn flatten_tree(
                 name,
                 config,
                 terminal_options,
+                notes,
+                via_agent,
+                auto_connect,
             } => {
                 let conn_id = compute_connection_id(parent_path, name);
                 connections.push(SavedConnection {
@@ -66,6 +69,9 @@ pub fn flatten_tree(
                     config: config.clone(),
                     folder_id: parent_folder_id.clone(),
                     terminal_options: terminal_options.clone(),
+                    notes: notes.clone(),
+                    via_agent: via_agent.clone(),
+                    auto_connect: *auto_connect,
                     source_file: None,
                 });
             }
@@ -115,6 +121,9 @@ fn build_tree_for_parent(
                 name: conn.name.clone(),
                 config: conn.config.clone(),
                 terminal_options: conn.terminal_options.clone(),
+                notes: conn.notes.clone(),
+                via_agent: conn.via_agent.clone(),
+                auto_connect: conn.auto_connect,
             });
         }
     }
@@ -330,11 +339,17 @@ mod tests {
                 name: "Local".to_string(),
                 config: make_local_config(),
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
             },
             ConnectionTreeNode::Connection {
                 name: "SSH".to_string(),
                 config: make_ssh_config(),
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
             },
         ];
 
@@ -358,11 +373,17 @@ mod tests {
                     name: "Prod".to_string(),
                     config: make_ssh_config(),
                     terminal_options: None,
+                    notes: None,
+                    via_agent: None,
+                    auto_connect: false,
                 },
                 ConnectionTreeNode::Connection {
                     name: "Dev".to_string(),
                     config: make_ssh_config(),
                     terminal_options: None,
+                    notes: None,
+                    via_agent: None,
+                    auto_connect: false,
                 },
             ],
         }];
@@ -392,6 +413,9 @@ mod tests {
                     name: "Deep SSH".to_string(),
                     config: make_ssh_config(),
                     terminal_options: None,
+                    notes: None,
+                    via_agent: None,
+                    auto_connect: false,
                 }],
             }],
         }];
@@ -417,6 +441,9 @@ mod tests {
             name: "A/B".to_string(),
             config: make_local_config(),
             terminal_options: None,
+            notes: None,
+            via_agent: None,
+            auto_connect: false,
         }];
 
         let (conns, _) = flatten_tree(&tree, None);
@@ -443,6 +470,9 @@ mod tests {
                 config: make_local_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
             SavedConnection {
@@ -451,6 +481,9 @@ mod tests {
                 config: make_ssh_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
         ];
@@ -480,6 +513,9 @@ mod tests {
                 config: make_ssh_config(),
                 folder_id: Some("Work".to_string()),
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
             SavedConnection {
@@ -488,6 +524,9 @@ mod tests {
                 config: make_local_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
         ];
@@ -528,12 +567,18 @@ mod tests {
                             name: "Dev SSH".to_string(),
                             config: make_ssh_config(),
                             terminal_options: None,
+                            notes: None,
+                            via_agent: None,
+                            auto_connect: false,
                         }],
                     },
                     ConnectionTreeNode::Connection {
                         name: "Prod SSH".to_string(),
                         config: make_ssh_config(),
                         terminal_options: None,
+                        notes: None,
+                        via_agent: None,
+                        auto_connect: false,
                     },
                 ],
             },
@@ -541,6 +586,9 @@ mod tests {
                 name: "Local".to_string(),
                 config: make_local_config(),
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
             },
         ];
 
@@ -583,6 +631,9 @@ mod tests {
                 config: make_local_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
             SavedConnection {
@@ -591,6 +642,9 @@ mod tests {
                 config: make_local_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
         ];
@@ -610,6 +664,9 @@ mod tests {
                 config: make_ssh_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
             SavedConnection {
@@ -618,6 +675,9 @@ mod tests {
                 config: make_ssh_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
         ];
@@ -637,6 +697,9 @@ mod tests {
                 config: make_local_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
             SavedConnection {
@@ -645,6 +708,9 @@ mod tests {
                 config: make_local_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
             SavedConnection {
@@ -653,6 +719,9 @@ mod tests {
                 config: make_local_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
         ];
@@ -673,6 +742,9 @@ mod tests {
                 config: make_local_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
             SavedConnection {
@@ -681,6 +753,9 @@ mod tests {
                 config: make_local_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
             SavedConnection {
@@ -689,6 +764,9 @@ mod tests {
                 config: make_local_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
         ];
@@ -724,6 +802,9 @@ mod tests {
                 config: make_ssh_config(),
                 folder_id: Some("F1".to_string()),
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
             SavedConnection {
@@ -732,6 +813,9 @@ mod tests {
                 config: make_ssh_config(),
                 folder_id: Some("F2".to_string()),
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
         ];
@@ -756,6 +840,9 @@ mod tests {
             config: make_local_config(),
             folder_id: None,
             terminal_options: None,
+            notes: None,
+            via_agent: None,
+            auto_connect: false,
             source_file: None,
         }];
 
@@ -813,6 +900,9 @@ mod tests {
                 config: make_local_config(),
                 folder_id: Some("TestDir".to_string()),
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
             // Moved connection: ID recomputed to match new folder
@@ -822,6 +912,9 @@ mod tests {
                 config: make_local_config(),
                 folder_id: Some("TestDir".to_string()),
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
         ];
@@ -855,6 +948,9 @@ mod tests {
                 config: make_ssh_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
             // Connection reparented from deleted folder to root
@@ -864,6 +960,9 @@ mod tests {
                 config: make_ssh_config(),
                 folder_id: None,
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
                 source_file: None,
             },
         ];
@@ -926,11 +1025,17 @@ mod tests {
                         name: "C1".to_string(),
                         config: make_local_config(),
                         terminal_options: None,
+                        notes: None,
+                        via_agent: None,
+                        auto_connect: false,
                     },
                     ConnectionTreeNode::Connection {
                         name: "C2".to_string(),
                         config: make_local_config(),
                         terminal_options: None,
+                        notes: None,
+                        via_agent: None,
+                        auto_connect: false,
                     },
                 ],
             },
@@ -938,6 +1043,9 @@ mod tests {
                 name: "C3".to_string(),
                 config: make_local_config(),
                 terminal_options: None,
+                notes: None,
+                via_agent: None,
+                auto_connect: false,
             },
         ];
         assert_eq!(count_tree_items(&tree), (3, 1));
@@ -0,0 +1,191 @@
+use std::collections::BTreeMap;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::crypto::{decrypt_with_password, encrypt_with_password, EncryptedEnvelope};
+use super::types::{CredentialKey, CredentialStoreStatus};
+use super::CredentialStore;
+
+/// Current credential backup format version.
+const BACKUP_VERSION: &str = "1";
+
+/// A standalone backup of every stored credential.
+///
+/// The credentials are serialized as a `"connection_id:credential_type" ->
+/// "value"` map (the same layout as the `$encrypted` section of connection
+/// exports) and sealed with the export password.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CredentialBackup {
+    version: String,
+    #[serde(rename = "$encrypted")]
+    encrypted: EncryptedEnvelope,
+}
+
+/// Fail unless `store` can currently read and write credentials.
+fn ensure_unlocked(store: &dyn CredentialStore) -> Result<()> {
+    match store.status() {
+        CredentialStoreStatus::Unlocked => Ok(()),
+        CredentialStoreStatus::Locked => anyhow::bail!("Credential store is locked"),
+        CredentialStoreStatus::Unavailable => anyhow::bail!("Credential store is not available"),
+    }
+}
+
+/// Export every credential in `store`, encrypted with `password`.
+///
+/// Returns the backup as pretty-printed JSON.
+pub fn export_encrypted(store: &dyn CredentialStore, password: &str) -> Result<String> {
+    ensure_unlocked(store)?;
+
+    let mut cred_map: BTreeMap<String, String> = BTreeMap::new();
+    for key in store.list_keys()? {
+        if let Some(value) = store.get(&key)? {
+            cred_map.insert(key.to_string(), value);
+        }
+    }
+
+    let plaintext = serde_json::to_vec(&cred_map).context("Failed to serialize credential map")?;
+    let encrypted =
+        encrypt_with_password(password, &plaintext).context("Failed to encrypt credentials")?;
+    let backup = CredentialBackup {
+        version: BACKUP_VERSION.to_string(),
+        encrypted,
+    };
+    serde_json::to_string_pretty(&backup).context("Failed to serialize credential backup")
+}
+
+/// Restore the credentials in an encrypted backup into `store`.
+///
+/// Existing credentials with the same key are overwritten; others are
+/// left untouched. Returns the number of credentials imported.
+pub fn import_encrypted(store: &dyn CredentialStore, json: &str, password: &str) -> Result<usize> {
+    ensure_unlocked(store)?;
+
+    let backup: CredentialBackup =
+        serde_json::from_str(json).context("Failed to parse credential backup")?;
+    let plaintext = decrypt_with_password(password, &backup.encrypted)
+        .context("Failed to decrypt credentials — wrong password?")?;
+    let cred_map: BTreeMap<String, String> =
+        serde_json::from_slice(&plaintext).context("Invalid credential data format")?;
+
+    let mut imported = 0;
+    for (map_key, value) in &cred_map {
+        if let Some(key) = CredentialKey::from_map_key(map_key) {
+            store.set(&key, value)?;
+            imported += 1;
+        }
+    }
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credential::types::CredentialType;
+    use crate::credential::{MasterPasswordStore, NullStore};
+
+    fn unlocked_store(dir: &std::path::Path) -> MasterPasswordStore {
+        let store = MasterPasswordStore::new(dir.join("credentials.enc"));
+        store.setup("master-pw").unwrap();
+        store
+    }
+
+    fn sample_keys() -> Vec<(CredentialKey, &'static str)> {
+        vec![
+            (
+                CredentialKey::new("conn-1", CredentialType::Password),
+                "ssh-secret",
+            ),
+            (
+                CredentialKey::new("conn-1", CredentialType::KeyPassphrase),
+                "key-secret",
+            ),
+            (
+                CredentialKey::new("agent-1", CredentialType::Password),
+                "agent-secret",
+            ),
+        ]
+    }
+
+    #[test]
+    fn round_trip_with_correct_password() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let source = unlocked_store(source_dir.path());
+        for (key, value) in sample_keys() {
+            source.set(&key, value).unwrap();
+        }
+
+        let json = export_encrypted(&source, "backup-pw").unwrap();
+        assert!(!json.contains("ssh-secret"));
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target = unlocked_store(target_dir.path());
+        assert_eq!(import_encrypted(&target, &json, "backup-pw").unwrap(), 3);
+        for (key, value) in sample_keys() {
+            assert_eq!(target.get(&key).unwrap().as_deref(), Some(value));
+        }
+    }
+
+    #[test]
+    fn import_with_wrong_password_fails_and_stores_nothing() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let source = unlocked_store(source_dir.path());
+        for (key, value) in sample_keys() {
+            source.set(&key, value).unwrap();
+        }
+        let json = export_encrypted(&source, "backup-pw").unwrap();
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target = unlocked_store(target_dir.path());
+        let err = import_encrypted(&target, &json, "wrong-pw").unwrap_err();
+        assert!(err.to_string().contains("wrong password"));
+        assert!(target.list_keys().unwrap().is_empty());
+    }
+
+    #[test]
+    fn import_overwrites_matching_keys_only() {
+        let source_dir = tempfile::tempdir().unwrap();
+        let source = unlocked_store(source_dir.path());
+        let key = CredentialKey::new("conn-1", CredentialType::Password);
+        source.set(&key, "new").unwrap();
+        let json = export_encrypted(&source, "backup-pw").unwrap();
+
+        let target_dir = tempfile::tempdir().unwrap();
+        let target = unlocked_store(target_dir.path());
+        let other = CredentialKey::new("conn-2", CredentialType::Password);
+        target.set(&key, "old").unwrap();
+        target.set(&other, "kept").unwrap();
+
+        import_encrypted(&target, &json, "backup-pw").unwrap();
+        assert_eq!(target.get(&key).unwrap().as_deref(), Some("new"));
+        assert_eq!(target.get(&other).unwrap().as_deref(), Some("kept"));
+    }
+
+    #[test]
+    fn export_and_import_require_unlocked_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = unlocked_store(dir.path());
+        let json = export_encrypted(&store, "backup-pw").unwrap();
+
+        store.lock();
+        let err = export_encrypted(&store, "backup-pw").unwrap_err();
+        assert!(err.to_string().contains("locked"));
+        let err = import_encrypted(&store, &json, "backup-pw").unwrap_err();
+        assert!(err.to_string().contains("locked"));
+
+        let err = export_encrypted(&NullStore, "backup-pw").unwrap_err();
+        assert!(err.to_string().contains("not available"));
+    }
+
+    #[test]
+    fn backup_uses_encrypted_envelope() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = unlocked_store(dir.path());
+        let json = export_encrypted(&store, "backup-pw").unwrap();
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], "1");
+        assert_eq!(value["$encrypted"]["kdf"]["algorithm"], "argon2id");
+    }
+}
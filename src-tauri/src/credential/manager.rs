@@ -217,6 +217,17 @@ impl CredentialStore for CredentialManager {
         result
     }
 
+    fn has_credential(&self, key: &CredentialKey) -> Result<bool> {
+        let inner = self.inner.read().expect("credential manager lock poisoned");
+        let result = match *inner {
+            StoreBackend::Null(ref s) => s.has_credential(key),
+            StoreBackend::MasterPassword(ref s) => s.has_credential(key),
+        };
+        drop(inner);
+        self.record_activity();
+        result
+    }
+
     fn list_keys(&self) -> Result<Vec<CredentialKey>> {
         let inner = self.inner.read().expect("credential manager lock poisoned");
         let result = match *inner {
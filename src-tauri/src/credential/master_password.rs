@@ -332,6 +332,14 @@ impl CredentialStore for MasterPasswordStore {
         Ok(())
     }
 
+    fn has_credential(&self, key: &CredentialKey) -> Result<bool> {
+        let creds_guard = self.credentials.read().expect("credentials lock poisoned");
+        let map = creds_guard
+            .as_ref()
+            .context("Store is locked — unlock before accessing credentials")?;
+        Ok(map.contains_key(&key.to_string()))
+    }
+
     fn list_keys(&self) -> Result<Vec<CredentialKey>> {
         let creds_guard = self.credentials.read().expect("credentials lock poisoned");
         let map = creds_guard
@@ -561,6 +569,47 @@ mod tests {
         assert_eq!(keys[1], kp_key);
     }
 
+    #[test]
+    fn list_for_connection_reports_stored_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = make_store(dir.path());
+        store.setup("pw").unwrap();
+
+        store
+            .set(
+                &CredentialKey::new("conn-1", CredentialType::KeyPassphrase),
+                "a",
+            )
+            .unwrap();
+        store
+            .set(&CredentialKey::new("conn-2", CredentialType::Password), "b")
+            .unwrap();
+
+        let key = CredentialKey::new("conn-1", CredentialType::KeyPassphrase);
+        assert!(store.has_credential(&key).unwrap());
+        assert!(!store
+            .has_credential(&CredentialKey::new("conn-1", CredentialType::Password))
+            .unwrap());
+        assert_eq!(
+            store.list_for_connection("conn-1").unwrap(),
+            vec![CredentialType::KeyPassphrase]
+        );
+        assert!(store.list_for_connection("conn-3").unwrap().is_empty());
+    }
+
+    #[test]
+    fn has_credential_fails_when_locked() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = make_store(dir.path());
+        store.setup("pw").unwrap();
+        let key = CredentialKey::new("conn-1", CredentialType::Password);
+        store.set(&key, "secret").unwrap();
+        store.lock();
+
+        assert!(store.has_credential(&key).is_err());
+        assert!(store.list_for_connection("conn-1").is_err());
+    }
+
     #[test]
     fn status_reflects_lock_state() {
         let dir = tempfile::tempdir().unwrap();
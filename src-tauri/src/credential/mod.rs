@@ -1,4 +1,5 @@
 pub mod auto_lock;
+pub mod backup;
 pub mod crypto;
 pub mod manager;
 pub mod master_password;
@@ -31,9 +32,24 @@ pub trait CredentialStore: Send + Sync {
     /// Remove all credentials associated with a connection.
     fn remove_all_for_connection(&self, connection_id: &str) -> Result<()>;
 
+    /// Check whether a credential is stored, without reading its value.
+    fn has_credential(&self, key: &CredentialKey) -> Result<bool>;
+
     /// List all stored credential keys.
     fn list_keys(&self) -> Result<Vec<CredentialKey>>;
 
+    /// List the credential types stored for a connection, without reading
+    /// their values.
+    fn list_for_connection(&self, connection_id: &str) -> Result<Vec<CredentialType>> {
+        let mut types = Vec::new();
+        for credential_type in CredentialType::ALL {
+            if self.has_credential(&CredentialKey::new(connection_id, credential_type.clone()))? {
+                types.push(credential_type);
+            }
+        }
+        Ok(types)
+    }
+
     /// Return the current status of the credential store.
     fn status(&self) -> CredentialStoreStatus;
 }
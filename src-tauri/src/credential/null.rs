@@ -27,6 +27,10 @@ impl CredentialStore for NullStore {
         Ok(())
     }
 
+    fn has_credential(&self, _key: &CredentialKey) -> Result<bool> {
+        Ok(false)
+    }
+
     fn list_keys(&self) -> Result<Vec<CredentialKey>> {
         Ok(Vec::new())
     }
@@ -68,6 +72,15 @@ mod tests {
         assert!(store.remove_all_for_connection("conn-1").is_ok());
     }
 
+    #[test]
+    fn has_credential_is_false_after_set() {
+        let store = NullStore;
+        let key = CredentialKey::new("conn-1", CredentialType::Password);
+        store.set(&key, "secret").unwrap();
+        assert!(!store.has_credential(&key).unwrap());
+        assert!(store.list_for_connection("conn-1").unwrap().is_empty());
+    }
+
     #[test]
     fn list_keys_returns_empty() {
         let store = NullStore;
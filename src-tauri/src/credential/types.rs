@@ -11,6 +11,11 @@ pub enum CredentialType {
     KeyPassphrase,
 }
 
+impl CredentialType {
+    /// Every credential type, in display order.
+    pub const ALL: [CredentialType; 2] = [CredentialType::Password, CredentialType::KeyPassphrase];
+}
+
 impl fmt::Display for CredentialType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
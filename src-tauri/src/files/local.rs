@@ -1,5 +1,6 @@
 use crate::utils::errors::TerminalError;
-use termihub_core::files::FileEntry;
+use termihub_core::files::utils::normalize_newlines;
+use termihub_core::files::{FileEntry, NewlineMode};
 
 /// List directory contents, filtering out `.` and `..`.
 ///
@@ -9,9 +10,14 @@ pub fn list_dir(path: &str) -> Result<Vec<FileEntry>, TerminalError> {
     Ok(termihub_core::files::local::list_dir_sync(path)?)
 }
 
-/// Create a directory.
-pub fn mkdir(path: &str) -> Result<(), TerminalError> {
-    std::fs::create_dir(path)?;
+/// Create a directory. With `recursive`, missing parent directories are
+/// created too; otherwise the parent must exist.
+pub fn mkdir(path: &str, recursive: bool) -> Result<(), TerminalError> {
+    if recursive {
+        std::fs::create_dir_all(path)?;
+    } else {
+        std::fs::create_dir(path)?;
+    }
     Ok(())
 }
 
@@ -26,7 +32,13 @@ pub fn delete(path: &str, is_directory: bool) -> Result<(), TerminalError> {
 }
 
 /// Rename a file or directory.
-pub fn rename(old_path: &str, new_path: &str) -> Result<(), TerminalError> {
+///
+/// Fails with [`TerminalError::DestinationExists`] if `new_path` already
+/// exists, unless `overwrite` is set.
+pub fn rename(old_path: &str, new_path: &str, overwrite: bool) -> Result<(), TerminalError> {
+    if !overwrite && std::fs::symlink_metadata(new_path).is_ok() {
+        return Err(TerminalError::DestinationExists(new_path.to_string()));
+    }
     std::fs::rename(old_path, new_path)?;
     Ok(())
 }
@@ -79,9 +91,14 @@ pub fn read_file_content(path: &str) -> Result<String, TerminalError> {
     std::fs::read_to_string(path).map_err(TerminalError::Io)
 }
 
-/// Write a string to a file, creating or overwriting it.
-pub fn write_file_content(path: &str, content: &str) -> Result<(), TerminalError> {
-    std::fs::write(path, content).map_err(TerminalError::Io)
+/// Write a string to a file, creating or overwriting it, after normalizing
+/// its line endings per `newline`.
+pub fn write_file_content(
+    path: &str,
+    content: &str,
+    newline: NewlineMode,
+) -> Result<(), TerminalError> {
+    std::fs::write(path, normalize_newlines(content.as_bytes(), newline)).map_err(TerminalError::Io)
 }
 
 #[cfg(test)]
@@ -116,10 +133,29 @@ mod tests {
     fn mkdir_creates_directory() {
         let dir = tempfile::tempdir().unwrap();
         let new_dir = dir.path().join("new_dir");
-        mkdir(new_dir.to_str().unwrap()).unwrap();
+        mkdir(new_dir.to_str().unwrap(), false).unwrap();
         assert!(new_dir.is_dir());
     }
 
+    #[test]
+    fn mkdir_recursive_creates_missing_parents() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("a").join("b").join("c");
+        mkdir(nested.to_str().unwrap(), true).unwrap();
+        assert!(nested.is_dir());
+
+        // Existing directories are fine when recursive.
+        mkdir(nested.to_str().unwrap(), true).unwrap();
+    }
+
+    #[test]
+    fn mkdir_without_recursive_fails_on_missing_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("missing").join("child");
+        assert!(mkdir(nested.to_str().unwrap(), false).is_err());
+        assert!(!dir.path().join("missing").exists());
+    }
+
     #[test]
     fn delete_removes_file() {
         let dir = tempfile::tempdir().unwrap();
@@ -149,12 +185,29 @@ mod tests {
         let new_path = dir.path().join("new.txt");
         std::fs::write(&old, "content").unwrap();
 
-        rename(old.to_str().unwrap(), new_path.to_str().unwrap()).unwrap();
+        rename(old.to_str().unwrap(), new_path.to_str().unwrap(), false).unwrap();
         assert!(!old.exists());
         assert!(new_path.exists());
         assert_eq!(std::fs::read_to_string(&new_path).unwrap(), "content");
     }
 
+    #[test]
+    fn rename_refuses_existing_destination_without_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let old = dir.path().join("old.txt");
+        let new_path = dir.path().join("new.txt");
+        std::fs::write(&old, "old").unwrap();
+        std::fs::write(&new_path, "new").unwrap();
+
+        let err = rename(old.to_str().unwrap(), new_path.to_str().unwrap(), false).unwrap_err();
+        assert!(matches!(err, TerminalError::DestinationExists(_)));
+        assert_eq!(std::fs::read_to_string(&new_path).unwrap(), "new");
+
+        rename(old.to_str().unwrap(), new_path.to_str().unwrap(), true).unwrap();
+        assert!(!old.exists());
+        assert_eq!(std::fs::read_to_string(&new_path).unwrap(), "old");
+    }
+
     #[test]
     fn copy_file_preserves_content() {
         let dir = tempfile::tempdir().unwrap();
@@ -218,8 +271,21 @@ mod tests {
         let file = dir.path().join("roundtrip.txt");
         let path = file.to_str().unwrap();
 
-        write_file_content(path, "Hello, World!").unwrap();
+        write_file_content(path, "Hello, World!", NewlineMode::Preserve).unwrap();
         let content = read_file_content(path).unwrap();
         assert_eq!(content, "Hello, World!");
     }
+
+    #[test]
+    fn write_file_content_normalizes_newlines() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("script.sh");
+        let path = file.to_str().unwrap();
+
+        write_file_content(path, "echo a\r\necho b\n", NewlineMode::Lf).unwrap();
+        assert_eq!(read_file_content(path).unwrap(), "echo a\necho b\n");
+
+        write_file_content(path, "echo a\r\necho b\n", NewlineMode::Crlf).unwrap();
+        assert_eq!(read_file_content(path).unwrap(), "echo a\r\necho b\r\n");
+    }
 }
@@ -2,15 +2,20 @@ use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::sync::{Arc, Mutex};
 
-use ssh2::{Session, Sftp};
+use ssh2::{RenameFlags, Session, Sftp};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info};
 
 use crate::terminal::backend::SshConfig;
 use crate::utils::errors::TerminalError;
-use crate::utils::ssh_auth::connect_and_authenticate;
+use crate::utils::ssh_auth::connect_for_transfer;
+use termihub_core::backends::ssh::sftp_mkdir_all;
+use termihub_core::backends::ssh::transfer::{self, DirTransferSummary, TransferProgress};
 use termihub_core::errors::FileError;
-use termihub_core::files::utils::{chrono_from_epoch, format_permissions};
-use termihub_core::files::{FileBackend, FileEntry};
+use termihub_core::files::utils::{
+    chrono_from_epoch, format_permissions, normalize_newlines, resolve_file_browser_root,
+};
+use termihub_core::files::{FileBackend, FileEntry, NewlineMode};
 
 /// Legacy SFTP session wrapping a dedicated SSH connection.
 ///
@@ -22,15 +27,19 @@ use termihub_core::files::{FileBackend, FileEntry};
 /// which implements the unified `FileBrowser` trait. This struct will be
 /// removed once file browsing is migrated to use `ConnectionType`.
 pub struct SftpSession {
-    _session: Session,
+    session: Session,
     sftp: Sftp,
+    /// Configured `fileBrowserRoot`, unexpanded.
+    file_browser_root: Option<String>,
+    /// Configured `editorCommand` override for this connection.
+    editor_command: Option<String>,
 }
 
 impl SftpSession {
     /// Open a new SFTP session to the given SSH host.
     pub fn new(config: &SshConfig) -> Result<Self, TerminalError> {
         info!(host = %config.host, port = config.port, "Opening SFTP connection");
-        let session = connect_and_authenticate(config)?;
+        let session = connect_for_transfer(config)?;
         // Keep blocking mode for SFTP operations
         session.set_blocking(true);
 
@@ -39,11 +48,31 @@ impl SftpSession {
             .map_err(|e| TerminalError::SshError(format!("SFTP init failed: {}", e)))?;
 
         Ok(Self {
-            _session: session,
+            session,
             sftp,
+            file_browser_root: config.file_browser_root.clone(),
+            editor_command: config.editor_command.clone(),
         })
     }
 
+    /// The connection's `editorCommand` override, if any.
+    pub fn editor_command(&self) -> Option<&str> {
+        self.editor_command.as_deref()
+    }
+
+    /// Directory the file browser should open to: the configured root
+    /// expanded against the remote home, or the remote home itself.
+    pub fn initial_dir(&self) -> Result<String, TerminalError> {
+        let home = self
+            .sftp
+            .realpath(std::path::Path::new("."))
+            .map_err(|e| TerminalError::SshError(format!("realpath failed: {}", e)))?;
+        Ok(resolve_file_browser_root(
+            self.file_browser_root.as_deref(),
+            &home.to_string_lossy(),
+        ))
+    }
+
     /// List directory contents, filtering out `.` and `..`.
     pub fn list_dir(&self, path: &str) -> Result<Vec<FileEntry>, TerminalError> {
         debug!(path, "SFTP listing directory");
@@ -82,70 +111,133 @@ impl SftpSession {
         Ok(result)
     }
 
-    /// Download a remote file to a local path. Returns bytes written.
-    pub fn read_file(&self, remote_path: &str, local_path: &str) -> Result<u64, TerminalError> {
+    /// Download a remote file to a local path. Returns bytes transferred.
+    ///
+    /// With `resume`, an existing local file is continued from its current
+    /// size. With `verify`, the result is checked against a SHA-256 of the
+    /// remote file computed by `sha256sum` on the server. `on_progress`
+    /// receives uncompressed byte counts.
+    pub fn read_file(
+        &self,
+        remote_path: &str,
+        local_path: &str,
+        resume: bool,
+        verify: bool,
+        on_progress: &mut dyn FnMut(TransferProgress),
+    ) -> Result<u64, TerminalError> {
         let remote = std::path::Path::new(remote_path);
-        let mut remote_file = self
-            .sftp
-            .open(remote)
-            .map_err(|e| TerminalError::SshError(format!("open remote file failed: {}", e)))?;
-
-        let mut local_file = std::fs::File::create(local_path)
-            .map_err(|e| TerminalError::SshError(format!("create local file failed: {}", e)))?;
-
-        let mut buf = [0u8; 32768];
-        let mut total: u64 = 0;
-        loop {
-            let n = remote_file
-                .read(&mut buf)
-                .map_err(|e| TerminalError::SshError(format!("read failed: {}", e)))?;
-            if n == 0 {
-                break;
-            }
-            local_file
-                .write_all(&buf[..n])
-                .map_err(|e| TerminalError::SshError(format!("write failed: {}", e)))?;
-            total += n as u64;
+        let local = std::path::Path::new(local_path);
+        let transferred = transfer::download(
+            &self.sftp,
+            remote,
+            local,
+            resume,
+            &CancellationToken::new(),
+            on_progress,
+        )
+        .map_err(|e| TerminalError::SshError(format!("download failed: {e}")))?;
+        if verify {
+            self.verify(local, remote)?;
         }
-
-        Ok(total)
+        Ok(transferred)
     }
 
-    /// Upload a local file to a remote path. Returns bytes written.
-    pub fn write_file(&self, local_path: &str, remote_path: &str) -> Result<u64, TerminalError> {
+    /// Upload a local file to a remote path. Returns bytes transferred.
+    ///
+    /// With `resume`, an existing remote file is continued from its current
+    /// size. With `verify`, the result is checked against a SHA-256 of the
+    /// local file. `on_progress` receives uncompressed byte counts.
+    pub fn write_file(
+        &self,
+        local_path: &str,
+        remote_path: &str,
+        resume: bool,
+        verify: bool,
+        on_progress: &mut dyn FnMut(TransferProgress),
+    ) -> Result<u64, TerminalError> {
+        let local = std::path::Path::new(local_path);
         let remote = std::path::Path::new(remote_path);
-        let mut remote_file = self
-            .sftp
-            .create(remote)
-            .map_err(|e| TerminalError::SshError(format!("create remote file failed: {}", e)))?;
-
-        let mut local_file = std::fs::File::open(local_path)
-            .map_err(|e| TerminalError::SshError(format!("open local file failed: {}", e)))?;
-
-        let mut buf = [0u8; 32768];
-        let mut total: u64 = 0;
-        loop {
-            let n = local_file
-                .read(&mut buf)
-                .map_err(|e| TerminalError::SshError(format!("read failed: {}", e)))?;
-            if n == 0 {
-                break;
-            }
-            remote_file
-                .write_all(&buf[..n])
-                .map_err(|e| TerminalError::SshError(format!("write failed: {}", e)))?;
-            total += n as u64;
+        let transferred = transfer::upload(
+            &self.sftp,
+            local,
+            remote,
+            resume,
+            &CancellationToken::new(),
+            on_progress,
+        )
+        .map_err(|e| TerminalError::SshError(format!("upload failed: {e}")))?;
+        if verify {
+            self.verify(local, remote)?;
         }
+        Ok(transferred)
+    }
 
-        Ok(total)
+    /// Compare the SHA-256 of a local file with that of a remote file.
+    fn verify(
+        &self,
+        local: &std::path::Path,
+        remote: &std::path::Path,
+    ) -> Result<(), TerminalError> {
+        debug!(local = %local.display(), remote = %remote.display(), "Verifying transfer");
+        transfer::verify_checksum(&self.session, local, remote)
+            .map_err(|e| TerminalError::SshError(format!("verification failed: {e}")))
     }
 
-    /// Create a directory on the remote host.
-    pub fn mkdir(&self, path: &str) -> Result<(), TerminalError> {
-        let dir = std::path::Path::new(path);
-        self.sftp
-            .mkdir(dir, 0o755)
-            .map_err(|e| TerminalError::SshError(format!("mkdir failed: {}", e)))
+    /// Download a remote directory tree into a local directory.
+    ///
+    /// Symlinks are followed only with `follow_symlinks`. Failed entries are
+    /// reported in the summary instead of aborting the transfer.
+    /// `on_progress` receives the remote path of each file.
+    pub fn download_dir(
+        &self,
+        remote_path: &str,
+        local_path: &str,
+        follow_symlinks: bool,
+        on_progress: &mut dyn FnMut(&std::path::Path, TransferProgress),
+    ) -> Result<DirTransferSummary, TerminalError> {
+        transfer::download_dir(
+            &self.sftp,
+            std::path::Path::new(remote_path),
+            std::path::Path::new(local_path),
+            follow_symlinks,
+            &CancellationToken::new(),
+            on_progress,
+        )
+        .map_err(|e| TerminalError::SshError(format!("directory download failed: {e}")))
+    }
+
+    /// Upload a local directory tree into a remote directory.
+    ///
+    /// Symlinks are followed only with `follow_symlinks`. Failed entries are
+    /// reported in the summary instead of aborting the transfer.
+    /// `on_progress` receives the remote path of each file.
+    pub fn upload_dir(
+        &self,
+        local_path: &str,
+        remote_path: &str,
+        follow_symlinks: bool,
+        on_progress: &mut dyn FnMut(&std::path::Path, TransferProgress),
+    ) -> Result<DirTransferSummary, TerminalError> {
+        transfer::upload_dir(
+            &self.sftp,
+            std::path::Path::new(local_path),
+            std::path::Path::new(remote_path),
+            follow_symlinks,
+            &CancellationToken::new(),
+            on_progress,
+        )
+        .map_err(|e| TerminalError::SshError(format!("directory upload failed: {e}")))
+    }
+
+    /// Create a directory on the remote host. With `recursive`, missing
+    /// parent directories are created too.
+    pub fn mkdir(&self, path: &str, recursive: bool) -> Result<(), TerminalError> {
+        let result = if recursive {
+            sftp_mkdir_all(&self.sftp, path)
+        } else {
+            self.sftp.mkdir(std::path::Path::new(path), 0o755)
+        };
+        result.map_err(|e| TerminalError::SshError(format!("mkdir failed: {}", e)))
     }
 
     /// Remove a file on the remote host.
@@ -180,11 +272,13 @@ impl SftpSession {
         Ok(content)
     }
 
-    /// Write a string to a remote file, creating or overwriting it.
+    /// Write a string to a remote file, creating or overwriting it, after
+    /// normalizing its line endings per `newline`.
     pub fn write_file_content(
         &self,
         remote_path: &str,
         content: &str,
+        newline: NewlineMode,
     ) -> Result<(), TerminalError> {
         let remote = std::path::Path::new(remote_path);
         let mut remote_file = self
@@ -193,19 +287,47 @@ impl SftpSession {
             .map_err(|e| TerminalError::SshError(format!("create remote file failed: {}", e)))?;
 
         remote_file
-            .write_all(content.as_bytes())
+            .write_all(&normalize_newlines(content.as_bytes(), newline))
             .map_err(|e| TerminalError::SshError(format!("write failed: {}", e)))?;
 
         Ok(())
     }
 
     /// Rename a file or directory on the remote host.
-    pub fn rename(&self, old_path: &str, new_path: &str) -> Result<(), TerminalError> {
+    ///
+    /// Fails with [`TerminalError::DestinationExists`] if `new_path` already
+    /// exists, unless `overwrite` is set. SFTP v3 servers ignore the
+    /// overwrite flag, so an overwriting rename of a file removes the
+    /// target first when the plain rename is refused.
+    pub fn rename(
+        &self,
+        old_path: &str,
+        new_path: &str,
+        overwrite: bool,
+    ) -> Result<(), TerminalError> {
         let old = std::path::Path::new(old_path);
         let new = std::path::Path::new(new_path);
-        self.sftp
-            .rename(old, new, None)
-            .map_err(|e| TerminalError::SshError(format!("rename failed: {}", e)))
+        let existing = self.sftp.lstat(new).ok();
+        if existing.is_some() && !overwrite {
+            return Err(TerminalError::DestinationExists(new_path.to_string()));
+        }
+        let flags = if overwrite {
+            RenameFlags::ATOMIC | RenameFlags::OVERWRITE | RenameFlags::NATIVE
+        } else {
+            RenameFlags::ATOMIC | RenameFlags::NATIVE
+        };
+        match self.sftp.rename(old, new, Some(flags)) {
+            Ok(()) => Ok(()),
+            Err(_) if existing.is_some_and(|stat| !stat.is_dir()) => {
+                self.sftp
+                    .unlink(new)
+                    .map_err(|e| TerminalError::SshError(format!("unlink failed: {}", e)))?;
+                self.sftp
+                    .rename(old, new, Some(flags))
+                    .map_err(|e| TerminalError::SshError(format!("rename failed: {}", e)))
+            }
+            Err(e) => Err(TerminalError::SshError(format!("rename failed: {}", e))),
+        }
     }
 
     /// Get metadata for a single file or directory.
@@ -273,7 +395,10 @@ impl SftpSession {
 /// Map a `TerminalError` to a `FileError::OperationFailed`.
 #[allow(dead_code)]
 fn terminal_error_to_file_error(e: TerminalError) -> FileError {
-    FileError::OperationFailed(e.to_string())
+    match e {
+        TerminalError::DestinationExists(path) => FileError::DestinationExists(path),
+        other => FileError::OperationFailed(other.to_string()),
+    }
 }
 
 /// Async file backend implementation backed by an SFTP session.
@@ -356,7 +481,12 @@ impl FileBackend for SftpFileBackend {
         .map_err(|e| FileError::OperationFailed(format!("Task join failed: {e}")))?
     }
 
-    async fn rename(&self, old_path: &str, new_path: &str) -> Result<(), FileError> {
+    async fn rename(
+        &self,
+        old_path: &str,
+        new_path: &str,
+        overwrite: bool,
+    ) -> Result<(), FileError> {
         let session = self.session.clone();
         let old_path = old_path.to_string();
         let new_path = new_path.to_string();
@@ -364,7 +494,7 @@ impl FileBackend for SftpFileBackend {
             let sftp = session.lock().map_err(|e| {
                 FileError::OperationFailed(format!("Failed to lock SFTP session: {e}"))
             })?;
-            sftp.rename(&old_path, &new_path)
+            sftp.rename(&old_path, &new_path, overwrite)
                 .map_err(terminal_error_to_file_error)
         })
         .await
@@ -384,14 +514,15 @@ impl FileBackend for SftpFileBackend {
         .map_err(|e| FileError::OperationFailed(format!("Task join failed: {e}")))?
     }
 
-    async fn mkdir(&self, path: &str) -> Result<(), FileError> {
+    async fn mkdir(&self, path: &str, recursive: bool) -> Result<(), FileError> {
         let session = self.session.clone();
         let path = path.to_string();
         tauri::async_runtime::spawn_blocking(move || {
             let sftp = session.lock().map_err(|e| {
                 FileError::OperationFailed(format!("Failed to lock SFTP session: {e}"))
             })?;
-            sftp.mkdir(&path).map_err(terminal_error_to_file_error)
+            sftp.mkdir(&path, recursive)
+                .map_err(terminal_error_to_file_error)
         })
         .await
         .map_err(|e| FileError::OperationFailed(format!("Task join failed: {e}")))?
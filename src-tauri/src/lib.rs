@@ -206,6 +206,9 @@ pub fn run() {
             // Build the desktop ConnectionType registry and create the SessionManager.
             let registry = build_desktop_registry();
             let session_manager = SessionManager::new(registry, agent_manager.clone());
+            if let Some(connections) = app.try_state::<ConnectionManager>() {
+                session_manager.set_resize_debounce(connections.get_settings().resize_debounce());
+            }
             app.manage(session_manager);
             app.manage(agent_manager);
 
@@ -331,13 +334,36 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             // Session commands (replaces old terminal commands)
             commands::session::create_connection,
+            commands::session::connect_group,
+            commands::session::connect_auto_connections,
+            commands::session::restore_session_layout,
             commands::session::get_connection_types,
+            commands::session::get_connection_json_schema,
             commands::session::send_input,
             commands::session::resize_terminal,
+            commands::session::send_line_control,
+            commands::session::serial_modem_status,
             commands::session::close_terminal,
+            commands::session::reattach_terminal,
+            commands::session::session_restart,
             commands::session::list_local_sessions,
+            commands::session::list_active_sessions,
+            commands::session::session_start_input_recording,
+            commands::session::session_stop_input_recording,
+            commands::session::session_replay_input,
+            commands::session::session_send_file,
+            commands::session::session_extract_text,
+            commands::session::search_all_sessions,
+            commands::session::session_pid,
+            commands::session::session_start_output_log,
+            commands::session::session_stop_output_log,
+            commands::session::session_start_recording,
+            commands::session::session_stop_recording,
+            commands::session::session_pause_output,
+            commands::session::session_resume_output,
             commands::session::list_available_shells,
             commands::session::get_default_shell,
+            commands::session::list_wsl_distributions,
             commands::session::list_serial_ports,
             commands::session::check_x11_available,
             commands::session::check_ssh_agent_status,
@@ -346,6 +372,8 @@ pub fn run() {
             commands::session::check_podman_available,
             commands::session::list_podman_images,
             commands::session::validate_ssh_key,
+            commands::session::generate_ssh_key,
+            commands::session::ssh_run_command,
             // Session-based file browsing
             commands::session::session_list_files,
             commands::session::session_read_file,
@@ -353,6 +381,11 @@ pub fn run() {
             commands::session::session_delete_file,
             commands::session::session_rename_file,
             commands::session::session_mkdir,
+            commands::session::session_download_archive,
+            commands::session::session_dir_size,
+            commands::session::session_cancel_dir_size,
+            commands::session::session_grep,
+            commands::session::session_compare_files,
             // Session-based monitoring
             commands::session::session_get_capabilities,
             commands::session::session_monitoring_open,
@@ -367,6 +400,11 @@ pub fn run() {
             commands::connection::import_connections,
             commands::connection::get_settings,
             commands::connection::save_settings,
+            commands::connection::set_active_profile,
+            commands::connection::add_file_bookmark,
+            commands::connection::list_file_bookmarks,
+            commands::connection::remove_file_bookmark,
+            commands::connection::save_session_layout,
             commands::connection::move_connection_to_file,
             commands::connection::save_external_file,
             commands::connection::reload_external_connections,
@@ -381,8 +419,11 @@ pub fn run() {
             commands::files::sftp_open,
             commands::files::sftp_close,
             commands::files::sftp_list_dir,
+            commands::files::sftp_initial_dir,
             commands::files::sftp_download,
             commands::files::sftp_upload,
+            commands::files::sftp_download_dir,
+            commands::files::sftp_upload_dir,
             commands::files::sftp_mkdir,
             commands::files::sftp_delete,
             commands::files::sftp_rename,
@@ -399,6 +440,8 @@ pub fn run() {
             commands::files::vscode_available,
             commands::files::vscode_open_local,
             commands::files::vscode_open_remote,
+            commands::files::open_in_editor,
+            commands::files::sftp_open_in_editor,
             commands::files::write_cheatsheet,
             // Monitoring (kept temporarily — will migrate to session-based monitoring)
             commands::monitoring::monitoring_open,
@@ -433,6 +476,7 @@ pub fn run() {
             commands::tunnel::save_tunnel,
             commands::tunnel::delete_tunnel,
             commands::tunnel::get_tunnel_statuses,
+            commands::tunnel::get_tunnel_metrics,
             commands::tunnel::start_tunnel,
             commands::tunnel::stop_tunnel,
             // Workspaces
@@ -480,6 +524,9 @@ pub fn run() {
             commands::credential::store_credential,
             commands::credential::resolve_credential,
             commands::credential::remove_credential,
+            commands::credential::list_connection_credentials,
+            commands::credential::export_credentials_encrypted,
+            commands::credential::import_credentials_encrypted,
             commands::credential::set_auto_lock_timeout,
             // Portable mode
             commands::portable::get_app_mode,
@@ -6,8 +6,8 @@ use ssh2::Session;
 
 use termihub_core::errors::CoreError;
 use termihub_core::monitoring::{
-    cpu_percent_from_delta, parse_stats, CpuCounters, StatsCollector, SystemStats,
-    MONITORING_COMMAND,
+    check_exit_status, cpu_percent_from_delta, monitoring_command, parse_stats_for, CpuCounters,
+    MetricGroup, StatsCollector, SystemStats,
 };
 
 use crate::terminal::backend::SshConfig;
@@ -26,6 +26,10 @@ pub struct MonitoringSession {
     session: Session,
     /// Previous `/proc/stat` CPU counters for delta-based CPU% calculation.
     prev_cpu: Option<CpuCounters>,
+    /// Metric groups selected for this connection.
+    groups: Vec<MetricGroup>,
+    /// Monitoring command trimmed to `groups`.
+    command: String,
 }
 
 impl MonitoringSession {
@@ -34,13 +38,19 @@ impl MonitoringSession {
         let session = connect_and_authenticate(config)?;
         session.set_blocking(true);
 
+        let groups = config.monitoring_groups();
         Ok(Self {
             session,
             prev_cpu: None,
+            command: monitoring_command(&groups),
+            groups,
         })
     }
 
     /// Execute a command over SSH and return stdout as a string.
+    ///
+    /// A non-zero exit status is reported as [`TerminalError::MonitoringFailed`]
+    /// with the tail of the command's stderr.
     fn exec(&self, command: &str) -> Result<String, TerminalError> {
         let mut channel = self
             .session
@@ -56,9 +66,16 @@ impl MonitoringSession {
             .read_to_string(&mut output)
             .map_err(|e| TerminalError::SshError(format!("Read failed: {}", e)))?;
 
+        let mut stderr = String::new();
+        channel.stderr().read_to_string(&mut stderr).ok();
+
         channel.wait_close().ok();
+        let exit_code = channel
+            .exit_status()
+            .map_err(|e| TerminalError::SshError(format!("Exit status unavailable: {}", e)))?;
 
-        Ok(output)
+        check_exit_status(command, output, &stderr, exit_code)
+            .map_err(|e| TerminalError::MonitoringFailed(e.to_string()))
     }
 
     /// Fetch system statistics from the remote host.
@@ -66,10 +83,10 @@ impl MonitoringSession {
     /// CPU usage is computed from `/proc/stat` deltas between consecutive calls.
     /// The first call returns 0% since there is no previous snapshot to compare against.
     pub fn fetch_stats(&mut self) -> Result<SystemStats, TerminalError> {
-        let output = self.exec(MONITORING_COMMAND)?;
+        let output = self.exec(&self.command)?;
 
-        let (mut stats, counters) =
-            parse_stats(&output).map_err(|e| TerminalError::SshError(e.to_string()))?;
+        let (mut stats, counters) = parse_stats_for(&output, &self.groups)
+            .map_err(|e| TerminalError::SshError(e.to_string()))?;
 
         // Compute CPU% from the delta with the previous snapshot.
         if let Some(prev) = &self.prev_cpu {
@@ -136,7 +153,7 @@ impl MonitoringManager {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use termihub_core::monitoring::{parse_cpu_line, parse_meminfo_value};
+    use termihub_core::monitoring::{parse_cpu_line, parse_meminfo_value, parse_stats};
 
     /// Compile-time verification that `MonitoringSession` satisfies `StatsCollector`.
     fn _assert_stats_collector<T: StatsCollector>() {}
@@ -5,7 +5,10 @@
 //! connections. Local connections use the core backend implementations;
 //! remote connections use [`RemoteProxy`](super::remote_proxy::RemoteProxy).
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering as AtomicOrdering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -13,13 +16,30 @@ use tokio::sync::Mutex;
 
 use serde::Serialize;
 use tauri::Emitter;
+use termihub_core::buffer::RingBuffer;
 use termihub_core::connection::{
-    Capabilities, ConnectionType, ConnectionTypeInfo, ConnectionTypeRegistry,
+    raw_key_mode, Capabilities, CleanupPolicy, CloseReason, CloseTrigger, ConnectionType,
+    ConnectionTypeInfo, ConnectionTypeRegistry, LineControl, ModemStatus,
+};
+use termihub_core::errors::{CoreError, FileError};
+use termihub_core::files::compare::summarize_file;
+use termihub_core::files::utils::normalize_newlines;
+use termihub_core::files::{
+    ArchiveFormat, FileBrowser, FileComparison, FileEntry, GrepMatch, GrepOptions,
+    LocalFileBrowser, NewlineMode,
 };
-use termihub_core::files::FileEntry;
 use termihub_core::monitoring::SystemStats;
+use termihub_core::output::bell::BellDetector;
+use termihub_core::output::cast_recorder::CastRecorder;
 use termihub_core::output::coalescer::OutputCoalescer;
+use termihub_core::output::output_log::OutputLog;
 use termihub_core::output::screen_clear::contains_screen_clear;
+use termihub_core::output::sudo_prompt::SudoAutoFill;
+use termihub_core::output::text_extract::{extract_rows, strip_ansi};
+use termihub_core::output::transform::OutputPipeline;
+use termihub_core::session::file_send::{ascii_send, xmodem_send, FileSendProtocol, XmodemLink};
+use termihub_core::session::input_recording::{parse_input_recording, replay_delays};
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
 use crate::terminal::agent_manager::AgentRpcClient;
@@ -37,6 +57,43 @@ const MAX_COALESCE_BYTES: usize = 32 * 1024;
 /// buffered output anyway.
 const CLEAR_WAIT_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// Default quiet period after the last resize before it is applied.
+pub const DEFAULT_RESIZE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// Source of unique tags identifying the latest pending resize.
+static RESIZE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Local connection types whose child process can be respawned in place.
+const RESPAWNABLE_TYPES: &[&str] = &["local", "docker"];
+
+/// Emitted before a restarted child's output when the scrollback is cleared:
+/// cursor home, erase screen, erase scrollback.
+const CLEAR_SCROLLBACK: &[u8] = b"\x1b[H\x1b[2J\x1b[3J";
+
+/// Terminal size assumed for a session until the frontend first resizes it.
+const DEFAULT_SIZE: (u16, u16) = (80, 24);
+
+/// Maximum input held for a session whose backend is not connected yet.
+const MAX_PENDING_INPUT_BYTES: usize = 64 * 1024;
+
+/// How often a session holding queued input is checked for its backend
+/// having connected.
+const PENDING_INPUT_POLL: Duration = Duration::from_millis(50);
+
+/// Raw output kept per session for plain-text extraction.
+const SCROLLBACK_BYTES: usize = 256 * 1024;
+
+/// Matches counted per session by [`SessionManager::search_all_sessions`];
+/// the search of a session stops here, so the reported count saturates.
+const MAX_SEARCH_MATCHES: usize = 1000;
+
+/// Characters of the first matching line returned as a search preview.
+const SEARCH_PREVIEW_CHARS: usize = 120;
+
+/// Maximum output held for a session while its output is paused; older
+/// output is dropped beyond this.
+const PAUSED_OUTPUT_BYTES: usize = 4 * 1024 * 1024;
+
 /// Output event emitted via Tauri events.
 #[derive(Debug, Clone, Serialize)]
 pub struct TerminalOutputEvent {
@@ -51,6 +108,51 @@ pub struct TerminalExitEvent {
     pub exit_code: Option<i32>,
 }
 
+/// Emitted alongside the exit event when a session closed on its own for
+/// a known reason, e.g. the SSH peer stopped answering keepalives.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionClosedEvent {
+    pub session_id: String,
+    pub reason: CloseReason,
+}
+
+/// Emitted once after an SSH session connects, carrying the server's host
+/// key fingerprint so the user can verify it manually.
+#[derive(Debug, Clone, Serialize)]
+pub struct SshHostFingerprintEvent {
+    pub session_id: String,
+    pub key_type: String,
+    pub fingerprint: String,
+}
+
+/// Emitted once after a session connects for each informational notice
+/// its connection reported, e.g. features disabled on a restricted shell.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionNoticeEvent {
+    pub session_id: String,
+    pub code: String,
+    pub message: String,
+}
+
+/// A session whose scrollback contains the text searched for by
+/// [`SessionManager::search_all_sessions`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSearchResult {
+    pub session_id: String,
+    /// Number of matches, at most [`MAX_SEARCH_MATCHES`].
+    pub match_count: usize,
+    /// Plain text of the line holding the first match, trimmed and cut to
+    /// [`SEARCH_PREVIEW_CHARS`] characters.
+    pub first_match_preview: String,
+}
+
+/// Bell event emitted when a session's output rings the terminal bell.
+#[derive(Debug, Clone, Serialize)]
+pub struct TerminalBellEvent {
+    pub session_id: String,
+}
+
 /// Error event emitted when a session-level error occurs.
 #[derive(Debug, Clone, Serialize)]
 #[allow(dead_code)]
@@ -73,6 +175,184 @@ pub trait EventEmitter: Clone + Send + Sync + 'static {
 
     /// Emit a session exit notification.
     fn emit_exit(&self, event: &TerminalExitEvent);
+
+    /// Emit a terminal bell notification.
+    fn emit_bell(&self, event: &TerminalBellEvent);
+
+    /// Emit the reason a session closed on its own.
+    fn emit_closed(&self, event: &SessionClosedEvent);
+
+    /// Emit the host key fingerprint of a newly connected SSH session.
+    fn emit_host_fingerprint(&self, event: &SshHostFingerprintEvent);
+
+    /// Emit an informational notice about a newly connected session.
+    fn emit_notice(&self, event: &SessionNoticeEvent);
+}
+
+/// Active output logs, keyed by session ID.
+type OutputLogs =
+    Arc<std::sync::Mutex<HashMap<String, OutputLog<std::io::BufWriter<std::fs::File>>>>>;
+
+/// Active session recordings, keyed by session ID.
+type SessionRecordings =
+    Arc<std::sync::Mutex<HashMap<String, CastRecorder<std::io::BufWriter<std::fs::File>>>>>;
+
+/// Recent raw output per session, keyed by session ID.
+type Scrollbacks = Arc<std::sync::Mutex<HashMap<String, RingBuffer>>>;
+
+/// Output held back from the frontend for paused sessions.
+type PausedOutputs = Arc<PausedState>;
+
+/// Held output, keyed by the ID of a paused session.
+#[derive(Default)]
+struct PausedState {
+    held: std::sync::Mutex<HashMap<String, RingBuffer>>,
+    /// Set while any session is paused; output of other sessions skips
+    /// the `held` lock while it is clear.
+    any: AtomicBool,
+}
+
+impl PausedState {
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, RingBuffer>> {
+        self.held
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Bring `any` up to date after `held` changed, with its lock held.
+    fn update_any(&self, held: &HashMap<String, RingBuffer>) {
+        self.any.store(!held.is_empty(), AtomicOrdering::SeqCst);
+    }
+}
+
+/// Output diverted from the terminal to a running file transfer, keyed by
+/// session ID.
+type OutputTaps = Arc<std::sync::Mutex<HashMap<String, std::sync::mpsc::Sender<Vec<u8>>>>>;
+
+/// Tees emitted terminal output into the session's scrollback, output log
+/// and recording, if running, so all see exactly what the terminal shows.
+///
+/// Output of a paused session is held in its [`PausedOutputs`] buffer
+/// instead of being forwarded, so the reader keeps draining the backend.
+#[derive(Clone)]
+struct LoggingEmitter<E> {
+    inner: E,
+    logs: OutputLogs,
+    recordings: SessionRecordings,
+    scrollbacks: Scrollbacks,
+    paused: PausedOutputs,
+}
+
+impl<E: EventEmitter> EventEmitter for LoggingEmitter<E> {
+    fn emit_output(&self, event: &TerminalOutputEvent) -> bool {
+        if let Some(scrollback) = self
+            .scrollbacks
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get_mut(&event.session_id)
+        {
+            scrollback.write(&event.data);
+        }
+        let mut logs = self
+            .logs
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(log) = logs.get_mut(&event.session_id) {
+            if let Err(e) = log.write(&event.data) {
+                warn!(session_id = %event.session_id, "Output log failed, stopping it: {e}");
+                logs.remove(&event.session_id);
+            }
+        }
+        drop(logs);
+        record_session(&self.recordings, &event.session_id, |recorder| {
+            recorder.record_output(&event.data)
+        });
+        if !self.paused.any.load(AtomicOrdering::SeqCst) {
+            return self.inner.emit_output(event);
+        }
+        // Held while forwarding so a concurrent resume cannot slip its
+        // flush in between and reorder the output.
+        let mut paused = self.paused.lock();
+        if let Some(held) = paused.get_mut(&event.session_id) {
+            held.write(&event.data);
+            return true;
+        }
+        self.inner.emit_output(event)
+    }
+
+    fn emit_exit(&self, event: &TerminalExitEvent) {
+        // Deliver output still held for a paused session before it ends.
+        flush_paused_output(&self.paused, &event.session_id, &self.inner);
+        self.inner.emit_exit(event);
+    }
+
+    fn emit_bell(&self, event: &TerminalBellEvent) {
+        self.inner.emit_bell(event);
+    }
+
+    fn emit_closed(&self, event: &SessionClosedEvent) {
+        self.inner.emit_closed(event);
+    }
+
+    fn emit_host_fingerprint(&self, event: &SshHostFingerprintEvent) {
+        self.inner.emit_host_fingerprint(event);
+    }
+
+    fn emit_notice(&self, event: &SessionNoticeEvent) {
+        self.inner.emit_notice(event);
+    }
+}
+
+/// Apply `record` to a session's recording, if one is running, stopping
+/// the recording when it fails.
+fn record_session(
+    recordings: &SessionRecordings,
+    session_id: &str,
+    record: impl FnOnce(&mut CastRecorder<std::io::BufWriter<std::fs::File>>) -> std::io::Result<()>,
+) {
+    let mut recordings = recordings
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some(recorder) = recordings.get_mut(session_id) {
+        if let Err(e) = record(recorder) {
+            warn!(session_id, "Session recording failed, stopping it: {e}");
+            recordings.remove(session_id);
+        }
+    }
+}
+
+/// Stop holding output for a paused session and emit what was held, in
+/// order. If older output was dropped, the partial line it left at the
+/// start is skipped. Returns `false` if the session was not paused.
+fn flush_paused_output<E: EventEmitter>(
+    paused: &PausedOutputs,
+    session_id: &str,
+    emitter: &E,
+) -> bool {
+    let mut held_outputs = paused.lock();
+    let Some(held) = held_outputs.remove(session_id) else {
+        return false;
+    };
+    let (mut older, mut newer) = held.as_slices();
+    if held.has_wrapped() {
+        if let Some(i) = older.iter().position(|&b| b == b'\n') {
+            older = &older[i + 1..];
+        } else if let Some(i) = newer.iter().position(|&b| b == b'\n') {
+            older = &[];
+            newer = &newer[i + 1..];
+        }
+    }
+    for chunk in older
+        .chunks(MAX_COALESCE_BYTES)
+        .chain(newer.chunks(MAX_COALESCE_BYTES))
+    {
+        emitter.emit_output(&TerminalOutputEvent {
+            session_id: session_id.to_string(),
+            data: chunk.to_vec(),
+        });
+    }
+    paused.update_any(&held_outputs);
+    true
 }
 
 impl<R: tauri::Runtime> EventEmitter for tauri::AppHandle<R> {
@@ -83,6 +363,22 @@ impl<R: tauri::Runtime> EventEmitter for tauri::AppHandle<R> {
     fn emit_exit(&self, event: &TerminalExitEvent) {
         let _ = self.emit("terminal-exit", event);
     }
+
+    fn emit_bell(&self, event: &TerminalBellEvent) {
+        let _ = self.emit("terminal-bell", event);
+    }
+
+    fn emit_closed(&self, event: &SessionClosedEvent) {
+        let _ = self.emit("session-closed", event);
+    }
+
+    fn emit_host_fingerprint(&self, event: &SshHostFingerprintEvent) {
+        let _ = self.emit("ssh-host-fingerprint", event);
+    }
+
+    fn emit_notice(&self, event: &SessionNoticeEvent) {
+        let _ = self.emit("session-notice", event);
+    }
 }
 
 /// Information about an active session.
@@ -96,12 +392,134 @@ pub struct SessionInfo {
     /// Set when the session is a remote proxy; identifies the agent it runs on.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub agent_id: Option<String>,
+    /// Whether the frontend passes all keystrokes to the session instead
+    /// of handling application shortcuts (the `rawKeyMode` setting).
+    pub raw_key_mode: bool,
+    /// Whether the session's tab was closed but its backend kept running
+    /// by its cleanup policy, so it can be reattached.
+    pub detached: bool,
+}
+
+/// Metadata about a live session, used to build session-switcher UIs.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveSessionInfo {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub session_type: String,
+    pub title: String,
+    /// RFC 3339 timestamp of when the session's connection was established.
+    pub connected_since: String,
+    pub cols: u16,
+    pub rows: u16,
+    pub capabilities: Capabilities,
+    /// Whether the session's tab was closed while its backend was kept
+    /// alive by its cleanup policy, so it can be reattached.
+    pub detached: bool,
+    /// Whether all keystrokes go to the session, bypassing app shortcuts.
+    pub raw_key_mode: bool,
+}
+
+/// Latest size requested for a session while its resize is debounced.
+struct PendingResize {
+    cols: u16,
+    rows: u16,
+    generation: u64,
+}
+
+/// Input that arrived before a session's backend was connected, kept in
+/// arrival order until it can be written.
+#[derive(Default)]
+struct PendingInput {
+    chunks: Vec<Vec<u8>>,
+    bytes: usize,
+    /// Set once input was rejected; everything after it is rejected too,
+    /// so what is written is always a prefix of what was typed.
+    full: bool,
+}
+
+impl PendingInput {
+    /// Queue `data`. Returns `false`, queuing nothing, if it would push the
+    /// queue past [`MAX_PENDING_INPUT_BYTES`] or earlier input was
+    /// rejected.
+    fn push(&mut self, data: &[u8]) -> bool {
+        if self.full || self.bytes + data.len() > MAX_PENDING_INPUT_BYTES {
+            self.full = true;
+            return false;
+        }
+        self.bytes += data.len();
+        self.chunks.push(data.to_vec());
+        true
+    }
 }
 
 /// Internal session entry held by the manager.
 struct SessionEntry {
     connection: Box<dyn ConnectionType>,
     info: SessionInfo,
+    connected_since: chrono::DateTime<chrono::Utc>,
+    /// Last size successfully applied to the backend.
+    size: (u16, u16),
+    /// Whether closing the session releases the backend.
+    cleanup_policy: CleanupPolicy,
+    /// Set when a close kept the backend alive; cleared on reattach.
+    detached: bool,
+}
+
+impl SessionEntry {
+    fn new(connection: Box<dyn ConnectionType>, info: SessionInfo) -> Self {
+        Self {
+            connection,
+            info,
+            connected_since: chrono::Utc::now(),
+            size: DEFAULT_SIZE,
+            cleanup_policy: CleanupPolicy::Always,
+            detached: false,
+        }
+    }
+}
+
+/// What is needed to respawn a session's child process in place.
+struct RespawnSpec {
+    type_id: String,
+    settings: serde_json::Value,
+    /// Output reader of the current child; stopped before respawning.
+    reader: tokio::task::JoinHandle<()>,
+}
+
+/// [`XmodemLink`] over a session: packets are written to the backend and
+/// the receiver's replies read from the session output diverted by
+/// [`OutputTaps`].
+struct SessionXmodemLink<'a> {
+    manager: &'a SessionManager,
+    runtime: &'a tokio::runtime::Handle,
+    session_id: &'a str,
+    output: std::sync::mpsc::Receiver<Vec<u8>>,
+    /// Output received but not read yet.
+    received: VecDeque<u8>,
+}
+
+impl XmodemLink for SessionXmodemLink<'_> {
+    fn write(&mut self, data: &[u8]) -> io::Result<()> {
+        self.manager
+            .write_blocking(self.runtime, self.session_id, data, true)
+    }
+
+    fn read_byte(&mut self, timeout: Duration) -> io::Result<Option<u8>> {
+        if self.received.is_empty() {
+            match self.output.recv_timeout(timeout) {
+                Ok(chunk) => self.received.extend(chunk),
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout) => return Ok(None),
+                Err(std::sync::mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::BrokenPipe,
+                        "session output closed",
+                    ))
+                }
+            }
+        }
+        Ok(self.received.pop_front())
+    }
 }
 
 /// Push event emitted via Tauri when session-based monitoring delivers stats.
@@ -123,6 +541,28 @@ pub struct SessionManager {
     agent_manager: Arc<dyn AgentRpcClient>,
     /// Abort handles for active session-monitoring push tasks, keyed by session ID.
     monitoring_tasks: Arc<Mutex<HashMap<String, tokio::task::AbortHandle>>>,
+    /// Cancellation tokens for in-flight directory size computations, keyed by session ID.
+    dir_size_cancels: Arc<Mutex<HashMap<String, CancellationToken>>>,
+    /// Quiet period in milliseconds before a resize is applied; 0 applies immediately.
+    resize_debounce_ms: Arc<AtomicU64>,
+    /// Latest requested size per session, waiting for the debounce period to pass.
+    pending_resizes: Arc<Mutex<HashMap<String, PendingResize>>>,
+    /// Input for sessions whose backend is still connecting (or being
+    /// respawned), flushed once it reports connected.
+    pending_input: Arc<Mutex<HashMap<String, PendingInput>>>,
+    /// Respawnable sessions, keyed by session ID. Kept after the child exits
+    /// so a crashed shell can be restarted until its session is closed.
+    respawn_specs: Arc<Mutex<HashMap<String, RespawnSpec>>>,
+    /// Active output logs, keyed by session ID.
+    output_logs: OutputLogs,
+    /// Active asciicast session recordings, keyed by session ID.
+    session_recordings: SessionRecordings,
+    /// Recent raw output, keyed by session ID, for text extraction.
+    scrollbacks: Scrollbacks,
+    /// Output held back from paused sessions, keyed by session ID.
+    paused_outputs: PausedOutputs,
+    /// Output diverted to running XMODEM sends, keyed by session ID.
+    output_taps: OutputTaps,
 }
 
 impl SessionManager {
@@ -133,9 +573,31 @@ impl SessionManager {
             registry: Arc::new(registry),
             agent_manager,
             monitoring_tasks: Arc::new(Mutex::new(HashMap::new())),
+            dir_size_cancels: Arc::new(Mutex::new(HashMap::new())),
+            resize_debounce_ms: Arc::new(
+                AtomicU64::new(DEFAULT_RESIZE_DEBOUNCE.as_millis() as u64),
+            ),
+            pending_resizes: Arc::new(Mutex::new(HashMap::new())),
+            pending_input: Arc::new(Mutex::new(HashMap::new())),
+            respawn_specs: Arc::new(Mutex::new(HashMap::new())),
+            output_logs: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            session_recordings: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            scrollbacks: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            paused_outputs: Arc::default(),
+            output_taps: Arc::new(std::sync::Mutex::new(HashMap::new())),
         }
     }
 
+    /// Set the quiet period before a resize is applied. Zero disables debouncing.
+    pub fn set_resize_debounce(&self, debounce: Duration) {
+        self.resize_debounce_ms
+            .store(debounce.as_millis() as u64, AtomicOrdering::Relaxed);
+    }
+
+    fn resize_debounce(&self) -> Duration {
+        Duration::from_millis(self.resize_debounce_ms.load(AtomicOrdering::Relaxed))
+    }
+
     /// Create a new connection session.
     ///
     /// If `agent_id` is `Some`, creates a [`RemoteProxy`] that forwards
@@ -160,8 +622,151 @@ impl SessionManager {
             }
         }
 
+        // Validate output transforms before opening the connection.
+        let pipeline = OutputPipeline::from_settings(&settings)
+            .map_err(|e| TerminalError::SpawnFailed(e.to_string()))?;
+        let sudo_auto_fill = if type_id == "ssh" {
+            SudoAutoFill::from_settings(&settings)
+                .map_err(|e| TerminalError::SpawnFailed(format!("Invalid sudo prompt: {e}")))?
+        } else {
+            None
+        };
+
         let session_id = uuid::Uuid::new_v4().to_string();
+        let mut settings = settings;
+        if agent_id.is_none() {
+            // Lets backends tag what they create (e.g. Docker container labels).
+            if let Some(obj) = settings.as_object_mut() {
+                obj.entry("sessionId")
+                    .or_insert_with(|| serde_json::json!(session_id));
+            }
+        }
+        let connection = self.open_connection(type_id, &settings, agent_id).await?;
+        let reader = self
+            .start_session(
+                &session_id,
+                type_id,
+                connection,
+                &settings,
+                agent_id,
+                emitter,
+                pipeline,
+                sudo_auto_fill,
+            )
+            .await;
+
+        if agent_id.is_none() && RESPAWNABLE_TYPES.contains(&type_id) {
+            self.respawn_specs.lock().await.insert(
+                session_id.clone(),
+                RespawnSpec {
+                    type_id: type_id.to_string(),
+                    settings,
+                    reader,
+                },
+            );
+        }
+
+        info!(session_id = %session_id, type_id, "Created session");
+        Ok(session_id)
+    }
+
+    /// Restart a local or Docker session's child process in place.
+    ///
+    /// The current child (if still running) is torn down and a new one is
+    /// spawned from the same settings under the same session ID, so the
+    /// frontend tab stays attached. Also works after the child has exited,
+    /// until the session is closed. With `clear_scrollback`, the terminal's
+    /// screen and scrollback are cleared before the new child's output.
+    pub async fn restart_session<E: EventEmitter>(
+        &self,
+        session_id: &str,
+        clear_scrollback: bool,
+        emitter: E,
+    ) -> Result<(), TerminalError> {
+        let (type_id, settings, pipeline) = {
+            let mut specs = self.respawn_specs.lock().await;
+            let Some(spec) = specs.get_mut(session_id) else {
+                return Err(if self.sessions.lock().await.contains_key(session_id) {
+                    TerminalError::SpawnFailed(format!("Session {session_id} cannot be restarted"))
+                } else {
+                    TerminalError::SessionNotFound(session_id.to_string())
+                });
+            };
+            let pipeline = OutputPipeline::from_settings(&spec.settings)
+                .map_err(|e| TerminalError::SpawnFailed(e.to_string()))?;
+            // Stop the old reader first so its exit handling cannot remove
+            // the respawned session.
+            spec.reader.abort();
+            let _ = (&mut spec.reader).await;
+            (spec.type_id.clone(), spec.settings.clone(), pipeline)
+        };
+
+        self.cancel_dir_size(session_id).await;
+        self.pending_resizes.lock().await.remove(session_id);
+        // Hold keystrokes typed while the new child is spawning.
+        self.pending_input
+            .lock()
+            .await
+            .entry(session_id.to_string())
+            .or_default();
+        let old = self.sessions.lock().await.remove(session_id);
+        if let Some(mut entry) = old {
+            entry.connection.disconnect().await.ok();
+        }
+
+        let connection = match self.open_connection(&type_id, &settings, None).await {
+            Ok(connection) => connection,
+            Err(e) => {
+                self.respawn_specs.lock().await.remove(session_id);
+                self.pending_input.lock().await.remove(session_id);
+                emitter.emit_exit(&TerminalExitEvent {
+                    session_id: session_id.to_string(),
+                    exit_code: None,
+                });
+                return Err(e);
+            }
+        };
+
+        if clear_scrollback {
+            if let Some(scrollback) = self
+                .scrollbacks
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .get_mut(session_id)
+            {
+                scrollback.clear();
+            }
+            emitter.emit_output(&TerminalOutputEvent {
+                session_id: session_id.to_string(),
+                data: CLEAR_SCROLLBACK.to_vec(),
+            });
+        }
+        let reader = self
+            .start_session(
+                session_id, &type_id, connection, &settings, None, emitter, pipeline, None,
+            )
+            .await;
+        self.respawn_specs.lock().await.insert(
+            session_id.to_string(),
+            RespawnSpec {
+                type_id: type_id.clone(),
+                settings,
+                reader,
+            },
+        );
+
+        info!(session_id, type_id, "Restarted session");
+        Ok(())
+    }
 
+    /// Open a connection, through the agent when `agent_id` is set or from
+    /// the local registry otherwise.
+    async fn open_connection(
+        &self,
+        type_id: &str,
+        settings: &serde_json::Value,
+        agent_id: Option<&str>,
+    ) -> Result<Box<dyn ConnectionType>, TerminalError> {
         let connection: Box<dyn ConnectionType> = if let Some(aid) = agent_id {
             // Remote: create proxy to agent.
             let mut proxy = RemoteProxy::new(aid.to_string(), self.agent_manager.clone());
@@ -186,31 +791,65 @@ impl SessionManager {
                 .map_err(|e| TerminalError::SpawnFailed(e.to_string()))?;
             conn
         };
+        Ok(connection)
+    }
 
+    /// Register an opened connection under `session_id` and start streaming
+    /// its output. Returns the handle of the output reader task.
+    #[allow(clippy::too_many_arguments)]
+    async fn start_session<E: EventEmitter>(
+        &self,
+        session_id: &str,
+        type_id: &str,
+        connection: Box<dyn ConnectionType>,
+        settings: &serde_json::Value,
+        agent_id: Option<&str>,
+        emitter: E,
+        pipeline: OutputPipeline,
+        sudo_auto_fill: Option<SudoAutoFill>,
+    ) -> tokio::task::JoinHandle<()> {
         // Build a human-readable title.
-        let title = Self::build_title(type_id, &settings, agent_id);
+        let title = Self::build_title(type_id, settings, agent_id);
 
         // Subscribe to output.
         let output_rx = connection.subscribe_output();
+        let host_key = connection.host_key_fingerprint();
+        let notices = connection.notices();
 
         let info = SessionInfo {
-            id: session_id.clone(),
+            id: session_id.to_string(),
             title,
             connection_type: type_id.to_string(),
             alive: true,
             agent_id: agent_id.map(|s| s.to_string()),
+            raw_key_mode: raw_key_mode(settings),
+            detached: false,
         };
 
-        // Store session.
+        // Store session. Input sent before the backend reports connected is
+        // queued and flushed on the first write after it does.
+        let connected = connection.is_connected();
         {
             let mut sessions = self.sessions.lock().await;
-            sessions.insert(
-                session_id.clone(),
-                SessionEntry {
-                    connection,
-                    info: info.clone(),
-                },
-            );
+            let mut entry = SessionEntry::new(connection, info.clone());
+            entry.cleanup_policy = CleanupPolicy::from_settings(settings);
+            sessions.insert(session_id.to_string(), entry);
+            if !connected {
+                self.pending_input
+                    .lock()
+                    .await
+                    .entry(session_id.to_string())
+                    .or_default();
+            }
+        }
+        if !connected {
+            self.flush_input_when_connected(session_id);
+        }
+        if connected {
+            // Deliver input held while a restarted session was respawning.
+            if let Err(e) = self.send_input(session_id, &[]).await {
+                warn!(session_id, "Failed to flush queued input: {e}");
+            }
         }
 
         // Determine if we should wait for screen clear (initial command).
@@ -221,10 +860,47 @@ impl SessionManager {
 
         // Spawn output streaming task.
         let sessions_clone = self.sessions.clone();
-        let sid = session_id.clone();
-        tokio::spawn(async move {
-            Self::run_output_reader(sid, output_rx, emitter, sessions_clone, has_initial_command)
-                .await;
+        let sid = session_id.to_string();
+        self.scrollbacks
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .entry(session_id.to_string())
+            .or_insert_with(|| RingBuffer::new(SCROLLBACK_BYTES));
+        let emitter = LoggingEmitter {
+            inner: emitter,
+            logs: self.output_logs.clone(),
+            recordings: self.session_recordings.clone(),
+            scrollbacks: self.scrollbacks.clone(),
+            paused: self.paused_outputs.clone(),
+        };
+        if let Some(key) = host_key {
+            emitter.emit_host_fingerprint(&SshHostFingerprintEvent {
+                session_id: session_id.to_string(),
+                key_type: key.key_type,
+                fingerprint: key.fingerprint,
+            });
+        }
+        for notice in notices {
+            info!(session_id, code = %notice.code, "{}", notice.message);
+            emitter.emit_notice(&SessionNoticeEvent {
+                session_id: session_id.to_string(),
+                code: notice.code,
+                message: notice.message,
+            });
+        }
+        let taps = self.output_taps.clone();
+        let reader = tokio::spawn(async move {
+            Self::run_output_reader(
+                sid,
+                output_rx,
+                emitter,
+                sessions_clone,
+                has_initial_command,
+                pipeline,
+                sudo_auto_fill,
+                taps,
+            )
+            .await;
         });
 
         // Send initial command after a short delay.
@@ -234,7 +910,7 @@ impl SessionManager {
             .filter(|s| !s.is_empty())
         {
             let sessions = self.sessions.clone();
-            let sid = session_id.clone();
+            let sid = session_id.to_string();
             let cmd = cmd.to_string();
             tokio::spawn(async move {
                 tokio::time::sleep(Duration::from_millis(200)).await;
@@ -246,76 +922,668 @@ impl SessionManager {
             });
         }
 
-        info!(session_id = %session_id, type_id, "Created session");
-        Ok(session_id)
+        reader
+    }
+
+    /// Write a session's queued input as soon as its backend reports
+    /// connected, without waiting for more input. Stops once the queue is
+    /// gone: flushed by [`send_input`](Self::send_input) or dropped with
+    /// the session.
+    fn flush_input_when_connected(&self, session_id: &str) {
+        let manager = self.clone();
+        let session_id = session_id.to_string();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(PENDING_INPUT_POLL).await;
+                if !manager.pending_input.lock().await.contains_key(&session_id) {
+                    return;
+                }
+                let connected = match manager.sessions.lock().await.get(&session_id) {
+                    Some(entry) => entry.connection.is_connected(),
+                    None => return,
+                };
+                if connected {
+                    if let Err(e) = manager.send_input(&session_id, &[]).await {
+                        warn!(session_id, "Failed to flush queued input: {e}");
+                    }
+                    return;
+                }
+            }
+        });
     }
 
     /// Send input data to a session.
+    ///
+    /// Input for a session whose backend has not reported connected yet
+    /// (still connecting, or being restarted) is queued and written, in
+    /// order, once it connects. Input that would overflow the queue is
+    /// rejected with an error, as is all input after it until the queue
+    /// has been written.
     pub async fn send_input(&self, session_id: &str, data: &[u8]) -> Result<(), TerminalError> {
         let sessions = self.sessions.lock().await;
-        let entry = sessions
-            .get(session_id)
-            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
-        // Fast-path: skip the blocking write entirely for sessions already
-        // known to be dead (alive flag cleared by a previous write failure or
-        // by the reader thread).  This prevents a cascade of IPC calls from
-        // rapid keystrokes all blocking for SO_SNDTIMEO before giving up.
-        if !entry.connection.is_connected() {
-            return Err(TerminalError::WriteFailed(
-                "session disconnected".to_string(),
-            ));
-        }
-        let data = data.to_vec();
+        let mut pending = self.pending_input.lock().await;
+        let entry = match sessions.get(session_id) {
+            Some(entry) if entry.connection.is_connected() => entry,
+            entry => {
+                if let Some(queue) = pending.get_mut(session_id) {
+                    if !queue.push(data) {
+                        warn!(
+                            session_id,
+                            bytes = data.len(),
+                            "Input queue full, rejecting input"
+                        );
+                        return Err(TerminalError::WriteFailed(
+                            "session is still connecting and its input queue is full".to_string(),
+                        ));
+                    }
+                    return Ok(());
+                }
+                // Fast-path: skip the blocking write entirely for sessions
+                // already known to be dead (alive flag cleared by a previous
+                // write failure or by the reader thread). This prevents a
+                // cascade of IPC calls from rapid keystrokes all blocking for
+                // SO_SNDTIMEO before giving up.
+                return Err(match entry {
+                    Some(_) => TerminalError::WriteFailed("session disconnected".to_string()),
+                    None => TerminalError::SessionNotFound(session_id.to_string()),
+                });
+            }
+        };
+        let mut chunks = pending
+            .remove(session_id)
+            .map(|queue| queue.chunks)
+            .unwrap_or_default();
+        drop(pending);
+        if !data.is_empty() {
+            chunks.push(data.to_vec());
+        }
+        if chunks.is_empty() {
+            return Ok(());
+        }
         // block_in_place lets tokio keep processing other tasks while this
         // thread blocks on the potentially-slow synchronous write (e.g. SSH
         // write on a dead connection waiting for SO_SNDTIMEO to fire).
-        tokio::task::block_in_place(|| entry.connection.write(&data))
-            .map_err(|e| TerminalError::WriteFailed(e.to_string()))
+        tokio::task::block_in_place(|| {
+            chunks
+                .iter()
+                .try_for_each(|chunk| entry.connection.write(chunk))
+        })
+        .map_err(|e| TerminalError::WriteFailed(e.to_string()))?;
+        drop(sessions);
+
+        record_session(&self.session_recordings, session_id, |recorder| {
+            chunks
+                .iter()
+                .try_for_each(|chunk| recorder.record_input(chunk))
+        });
+        Ok(())
     }
 
-    /// Resize a session's terminal.
-    pub async fn resize(
+    /// Start recording a session to an asciicast v2 file at `path`,
+    /// replacing any recording already running for it. The recording
+    /// captures output and resizes, and with `record_input` also the input
+    /// written to the session, which [`replay_input`](Self::replay_input)
+    /// can re-send.
+    pub async fn start_session_recording(
         &self,
         session_id: &str,
-        cols: u16,
-        rows: u16,
+        path: &std::path::Path,
+        record_input: bool,
     ) -> Result<(), TerminalError> {
-        let sessions = self.sessions.lock().await;
-        let entry = sessions
+        let (cols, rows) = self
+            .sessions
+            .lock()
+            .await
             .get(session_id)
+            .map(|entry| entry.size)
             .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
-        tokio::task::block_in_place(|| entry.connection.resize(cols, rows))
-            .map_err(|e| TerminalError::ResizeFailed(e.to_string()))
-    }
-
-    /// Close a session.
-    ///
-    /// Explicitly calls [`ConnectionType::disconnect`] before dropping the entry
-    /// so that backends that release resources in `disconnect()` (not just `Drop`)
-    /// — notably Serial, which clears `output_tx` to stop its reader thread —
-    /// are cleaned up immediately.
-    pub async fn close_session(&self, session_id: &str) -> Result<(), TerminalError> {
-        let mut sessions = self.sessions.lock().await;
-        if let Some(mut entry) = sessions.remove(session_id) {
-            entry.connection.disconnect().await.ok();
-            info!(session_id, "Closed session");
+        let file = std::fs::File::create(path)?;
+        let recorder = CastRecorder::new(std::io::BufWriter::new(file), cols, rows, record_input)?;
+        let previous = self
+            .session_recordings
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(session_id.to_string(), recorder);
+        if let Some(previous) = previous {
+            Self::finish_session_recording(session_id, previous);
         }
+        info!(session_id, path = %path.display(), record_input, "Started session recording");
         Ok(())
     }
 
-    /// List all active sessions.
-    pub async fn list_sessions(&self) -> Vec<SessionInfo> {
+    /// Stop recording a session. Returns `false` if no recording was running.
+    pub fn stop_session_recording(&self, session_id: &str) -> bool {
+        let recorder = self
+            .session_recordings
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(session_id);
+        match recorder {
+            Some(recorder) => {
+                Self::finish_session_recording(session_id, recorder);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn finish_session_recording(
+        session_id: &str,
+        recorder: CastRecorder<std::io::BufWriter<std::fs::File>>,
+    ) {
+        if let Err(e) = recorder.finish() {
+            warn!(session_id, "Failed to finish session recording: {e}");
+        }
+    }
+
+    /// Stop forwarding a session's output to the frontend. The backend is
+    /// still read, so the remote process is not blocked; its output is
+    /// held (up to [`PAUSED_OUTPUT_BYTES`], dropping the oldest beyond
+    /// that) until [`resume_output`](Self::resume_output). Returns `false`
+    /// if the output was already paused.
+    pub async fn pause_output(&self, session_id: &str) -> Result<bool, TerminalError> {
+        if !self.sessions.lock().await.contains_key(session_id) {
+            return Err(TerminalError::SessionNotFound(session_id.to_string()));
+        }
+        let mut paused = self.paused_outputs.lock();
+        if paused.contains_key(session_id) {
+            return Ok(false);
+        }
+        paused.insert(session_id.to_string(), RingBuffer::new(PAUSED_OUTPUT_BYTES));
+        self.paused_outputs.update_any(&paused);
+        info!(session_id, "Paused output");
+        Ok(true)
+    }
+
+    /// Resume forwarding a session's output, first emitting everything held
+    /// while it was paused. Returns `false` if the output was not paused.
+    pub fn resume_output<E: EventEmitter>(&self, session_id: &str, emitter: &E) -> bool {
+        let resumed = flush_paused_output(&self.paused_outputs, session_id, emitter);
+        if resumed {
+            info!(session_id, "Resumed output");
+        }
+        resumed
+    }
+
+    /// Start logging a session's output to the file at `path`, replacing
+    /// any log already running for it. With `collapse_carriage_returns`,
+    /// lines redrawn with `\r` (progress bars) keep only their final state
+    /// in the log; the terminal still receives the raw stream.
+    pub async fn start_output_log(
+        &self,
+        session_id: &str,
+        path: &std::path::Path,
+        collapse_carriage_returns: bool,
+    ) -> Result<(), TerminalError> {
+        if !self.sessions.lock().await.contains_key(session_id) {
+            return Err(TerminalError::SessionNotFound(session_id.to_string()));
+        }
+        let file = std::fs::File::create(path)?;
+        let log = OutputLog::new(std::io::BufWriter::new(file), collapse_carriage_returns);
+        let previous = self
+            .output_logs
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(session_id.to_string(), log);
+        if let Some(previous) = previous {
+            Self::finish_output_log(session_id, previous);
+        }
+        info!(session_id, path = %path.display(), "Started output log");
+        Ok(())
+    }
+
+    /// Stop logging a session's output. Returns `false` if no log was running.
+    pub fn stop_output_log(&self, session_id: &str) -> bool {
+        let log = self
+            .output_logs
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(session_id);
+        match log {
+            Some(log) => {
+                Self::finish_output_log(session_id, log);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn finish_output_log(session_id: &str, log: OutputLog<std::io::BufWriter<std::fs::File>>) {
+        if let Err(e) = log.finish() {
+            warn!(session_id, "Failed to finish output log: {e}");
+        }
+    }
+
+    /// Re-send the input recorded in `path` to a session, keeping the
+    /// recorded gaps between writes divided by `speed`. Returns the number
+    /// of writes replayed.
+    pub async fn replay_input(
+        &self,
+        session_id: &str,
+        path: &std::path::Path,
+        speed: f64,
+    ) -> Result<usize, TerminalError> {
+        let file = std::fs::File::open(path)?;
+        let events = parse_input_recording(std::io::BufReader::new(file))
+            .map_err(|e| TerminalError::InternalError(e.to_string()))?;
+        let delays = replay_delays(&events, speed)
+            .map_err(|e| TerminalError::InternalError(e.to_string()))?;
+        for (event, delay) in events.iter().zip(delays) {
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+            self.send_input(session_id, &event.data).await?;
+        }
+        Ok(events.len())
+    }
+
+    /// Send the contents of the file at `path` to a session as input.
+    ///
+    /// With [`FileSendProtocol::Ascii`] the raw bytes are written, pausing
+    /// `line_delay` after each line if set. With [`FileSendProtocol::Xmodem`]
+    /// a receiver must already be waiting in the session (e.g. `rx` or a
+    /// bootloader's `loadx`); the session's output is diverted to the
+    /// transfer while it runs. Returns the number of file bytes sent.
+    pub async fn send_file(
+        &self,
+        session_id: &str,
+        path: &Path,
+        protocol: FileSendProtocol,
+        line_delay: Duration,
+    ) -> Result<u64, TerminalError> {
+        let data = std::fs::read(path)?;
+        if !self.sessions.lock().await.contains_key(session_id) {
+            return Err(TerminalError::SessionNotFound(session_id.to_string()));
+        }
+        let runtime = tokio::runtime::Handle::current();
+
+        let result = match protocol {
+            FileSendProtocol::Ascii => tokio::task::block_in_place(|| {
+                ascii_send(
+                    |chunk| self.write_blocking(&runtime, session_id, chunk, false),
+                    &data,
+                    line_delay,
+                    std::thread::sleep,
+                )
+            })
+            .map_err(|e| e.to_string()),
+            FileSendProtocol::Xmodem => {
+                let (tx, rx) = std::sync::mpsc::channel();
+                {
+                    let mut taps = self
+                        .output_taps
+                        .lock()
+                        .unwrap_or_else(std::sync::PoisonError::into_inner);
+                    if taps.contains_key(session_id) {
+                        return Err(TerminalError::WriteFailed(
+                            "a file transfer is already running for this session".to_string(),
+                        ));
+                    }
+                    taps.insert(session_id.to_string(), tx);
+                }
+                let untap = || {
+                    self.output_taps
+                        .lock()
+                        .unwrap_or_else(std::sync::PoisonError::into_inner)
+                        .remove(session_id);
+                };
+                if let Err(e) = self.set_binary_transfer(session_id, true).await {
+                    untap();
+                    return Err(e);
+                }
+                let mut link = SessionXmodemLink {
+                    manager: self,
+                    runtime: &runtime,
+                    session_id,
+                    output: rx,
+                    received: VecDeque::new(),
+                };
+                let result = tokio::task::block_in_place(|| xmodem_send(&mut link, &data));
+                drop(link);
+                if let Err(e) = self.set_binary_transfer(session_id, false).await {
+                    warn!(session_id, "Failed to end binary transfer mode: {e}");
+                }
+                untap();
+                result.map_err(|e| e.to_string())
+            }
+        };
+        result.map_err(TerminalError::WriteFailed)
+    }
+
+    /// Switch a session's backend into or out of binary transfer mode.
+    async fn set_binary_transfer(
+        &self,
+        session_id: &str,
+        active: bool,
+    ) -> Result<(), TerminalError> {
+        let sessions = self.sessions.lock().await;
+        let entry = sessions
+            .get(session_id)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        entry
+            .connection
+            .set_binary_transfer(active)
+            .map_err(|e| TerminalError::WriteFailed(e.to_string()))
+    }
+
+    /// Write `data` to a session's backend from blocking code, bypassing the
+    /// pending input queue and input recordings. With `binary`, the data is
+    /// written through [`ConnectionType::write_binary`].
+    fn write_blocking(
+        &self,
+        runtime: &tokio::runtime::Handle,
+        session_id: &str,
+        data: &[u8],
+        binary: bool,
+    ) -> io::Result<()> {
+        let sessions = runtime.block_on(self.sessions.lock());
+        let entry = sessions
+            .get(session_id)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "session closed"))?;
+        let result = if binary {
+            entry.connection.write_binary(data)
+        } else {
+            entry.connection.write(data)
+        };
+        result.map_err(io::Error::other)
+    }
+
+    /// Resize a session's terminal.
+    ///
+    /// Rapid resizes (e.g. while dragging the window) are coalesced: the
+    /// size is applied once no further resize arrived for the debounce
+    /// period, and the last requested size always wins. Errors from the
+    /// deferred resize are logged rather than returned.
+    pub async fn resize(
+        &self,
+        session_id: &str,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(), TerminalError> {
+        let debounce = self.resize_debounce();
+        if debounce.is_zero() {
+            return self.apply_resize(session_id, cols, rows).await;
+        }
+        if !self.sessions.lock().await.contains_key(session_id) {
+            return Err(TerminalError::SessionNotFound(session_id.to_string()));
+        }
+
+        let generation = RESIZE_GENERATION.fetch_add(1, AtomicOrdering::Relaxed);
+        self.pending_resizes.lock().await.insert(
+            session_id.to_string(),
+            PendingResize {
+                cols,
+                rows,
+                generation,
+            },
+        );
+
+        let manager = self.clone();
+        let session_id = session_id.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep(debounce).await;
+            let latest = {
+                let mut pending = manager.pending_resizes.lock().await;
+                match pending.get(&session_id) {
+                    Some(p) if p.generation == generation => pending.remove(&session_id),
+                    // Superseded by a newer resize, which has its own timer.
+                    _ => None,
+                }
+            };
+            if let Some(p) = latest {
+                if let Err(e) = manager.apply_resize(&session_id, p.cols, p.rows).await {
+                    warn!(session_id = %session_id, "Deferred resize failed: {e}");
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Apply a resize to the session's backend immediately.
+    async fn apply_resize(
+        &self,
+        session_id: &str,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(), TerminalError> {
+        let mut sessions = self.sessions.lock().await;
+        let entry = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        tokio::task::block_in_place(|| entry.connection.resize(cols, rows))
+            .map_err(|e| TerminalError::ResizeFailed(e.to_string()))?;
+        entry.size = (cols, rows);
+        drop(sessions);
+        record_session(&self.session_recordings, session_id, |recorder| {
+            recorder.record_resize(cols, rows)
+        });
+        Ok(())
+    }
+
+    /// Send a BREAK or set a modem control line on a serial session.
+    pub async fn line_control(
+        &self,
+        session_id: &str,
+        control: LineControl,
+    ) -> Result<(), TerminalError> {
+        let sessions = self.sessions.lock().await;
+        let entry = sessions
+            .get(session_id)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        tokio::task::block_in_place(|| entry.connection.line_control(control))
+            .map_err(|e| TerminalError::SerialError(e.to_string()))
+    }
+
+    /// Read the modem status input lines of a serial session.
+    pub async fn modem_status(&self, session_id: &str) -> Result<ModemStatus, TerminalError> {
+        let sessions = self.sessions.lock().await;
+        let entry = sessions
+            .get(session_id)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        tokio::task::block_in_place(|| entry.connection.modem_status())
+            .map_err(|e| TerminalError::SerialError(e.to_string()))
+    }
+
+    /// Close a session.
+    ///
+    /// If the session's [`CleanupPolicy`] does not release on `trigger`, the
+    /// backend is kept running and the session is only marked detached, so
+    /// it can be picked up again with [`reattach_session`](Self::reattach_session).
+    ///
+    /// Otherwise explicitly calls [`ConnectionType::disconnect`] before
+    /// dropping the entry so that backends that release resources in
+    /// `disconnect()` (not just `Drop`) — notably Serial, which clears
+    /// `output_tx` to stop its reader thread — are cleaned up immediately.
+    pub async fn close_session(
+        &self,
+        session_id: &str,
+        trigger: CloseTrigger,
+    ) -> Result<(), TerminalError> {
+        if let Some(entry) = self.sessions.lock().await.get_mut(session_id) {
+            if !entry.cleanup_policy.releases_on(trigger) {
+                entry.detached = true;
+                info!(session_id, policy = ?entry.cleanup_policy, "Kept session for reattach");
+                return Ok(());
+            }
+        }
+        // Stop a size walk still running on the closing session.
+        self.cancel_dir_size(session_id).await;
+        self.pending_resizes.lock().await.remove(session_id);
+        self.pending_input.lock().await.remove(session_id);
+        self.respawn_specs.lock().await.remove(session_id);
+        self.stop_output_log(session_id);
+        self.stop_session_recording(session_id);
+        {
+            let mut paused = self.paused_outputs.lock();
+            paused.remove(session_id);
+            self.paused_outputs.update_any(&paused);
+        }
+        self.scrollbacks
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(session_id);
+        let mut sessions = self.sessions.lock().await;
+        if let Some(mut entry) = sessions.remove(session_id) {
+            entry.connection.disconnect().await.ok();
+            info!(session_id, "Closed session");
+        }
+        Ok(())
+    }
+
+    /// Plain text of display rows `start..end` of a session's recent output,
+    /// wrapped at the session's current width, with escape sequences removed.
+    ///
+    /// Only the last [`SCROLLBACK_BYTES`] of output are kept; once older
+    /// output has been dropped, the first partial line is skipped.
+    pub async fn extract_text(
+        &self,
+        session_id: &str,
+        start: usize,
+        end: usize,
+    ) -> Result<String, TerminalError> {
+        let cols = self
+            .sessions
+            .lock()
+            .await
+            .get(session_id)
+            .map(|entry| entry.size.0)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        let data = self
+            .scrollbacks
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(session_id)
+            .map(|scrollback| {
+                let data = scrollback.read_all();
+                if scrollback.len() < scrollback.capacity() {
+                    return data;
+                }
+                match data.iter().position(|&b| b == b'\n') {
+                    Some(newline) => data[newline + 1..].to_vec(),
+                    None => data,
+                }
+            })
+            .unwrap_or_default();
+        Ok(extract_rows(&data, cols, start, end))
+    }
+
+    /// Search the recent output of every live session for `needle`.
+    ///
+    /// Returns the sessions with at least one match, ordered by session ID.
+    /// Only the last [`SCROLLBACK_BYTES`] of each session's raw output are
+    /// searched, and each search stops after [`MAX_SEARCH_MATCHES`] matches,
+    /// so the work is bounded by the session limit. The scrollbacks are
+    /// locked one session at a time, so output keeps flowing meanwhile.
+    pub fn search_all_sessions(
+        &self,
+        needle: &str,
+        case_insensitive: bool,
+    ) -> Vec<SessionSearchResult> {
+        if needle.is_empty() {
+            return Vec::new();
+        }
+        let lock = || {
+            self.scrollbacks
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+        };
+        let mut session_ids: Vec<String> = lock().keys().cloned().collect();
+        session_ids.sort();
+
+        session_ids
+            .into_iter()
+            .filter_map(|session_id| {
+                let scrollbacks = lock();
+                // Closed since the IDs were collected.
+                let scrollback = scrollbacks.get(&session_id)?;
+                let matches = scrollback.search_limited(
+                    needle.as_bytes(),
+                    case_insensitive,
+                    MAX_SEARCH_MATCHES,
+                );
+                let first = matches.first()?;
+                let line = scrollback.line_at(first.offset).unwrap_or_default();
+                let preview = strip_ansi(&line)
+                    .trim()
+                    .chars()
+                    .take(SEARCH_PREVIEW_CHARS)
+                    .collect();
+                Some(SessionSearchResult {
+                    session_id,
+                    match_count: matches.len(),
+                    first_match_preview: preview,
+                })
+            })
+            .collect()
+    }
+
+    /// OS process ID of the local child process behind a session.
+    ///
+    /// `None` for sessions without a local child (SSH, serial, remote agent
+    /// sessions) and once the child has exited or been disconnected.
+    pub async fn session_pid(&self, session_id: &str) -> Result<Option<u32>, TerminalError> {
+        let sessions = self.sessions.lock().await;
+        sessions
+            .get(session_id)
+            .map(|entry| entry.connection.process_id())
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))
+    }
+
+    /// Reattach a session whose backend was kept alive after its tab closed.
+    pub async fn reattach_session(&self, session_id: &str) -> Result<(), TerminalError> {
+        let mut sessions = self.sessions.lock().await;
+        let entry = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        entry.detached = false;
+        info!(session_id, "Reattached session");
+        Ok(())
+    }
+
+    /// List all active sessions.
+    pub async fn list_sessions(&self) -> Vec<SessionInfo> {
         let sessions = self.sessions.lock().await;
         sessions
             .values()
             .map(|entry| {
                 let mut info = entry.info.clone();
                 info.alive = entry.connection.is_connected();
+                info.detached = entry.detached;
                 info
             })
             .collect()
     }
 
+    /// List live sessions with their metadata, oldest connection first.
+    ///
+    /// Sessions whose connection has dropped are left out.
+    pub async fn list_active_sessions(&self) -> Vec<ActiveSessionInfo> {
+        let sessions = self.sessions.lock().await;
+        let mut entries: Vec<&SessionEntry> = sessions
+            .values()
+            .filter(|entry| entry.connection.is_connected())
+            .collect();
+        entries.sort_by(|a, b| {
+            a.connected_since
+                .cmp(&b.connected_since)
+                .then_with(|| a.info.id.cmp(&b.info.id))
+        });
+        entries
+            .into_iter()
+            .map(|entry| ActiveSessionInfo {
+                id: entry.info.id.clone(),
+                session_type: entry.info.connection_type.clone(),
+                title: entry.info.title.clone(),
+                connected_since: entry.connected_since.to_rfc3339(),
+                cols: entry.size.0,
+                rows: entry.size.1,
+                capabilities: entry.connection.capabilities(),
+                detached: entry.detached,
+                raw_key_mode: entry.info.raw_key_mode,
+            })
+            .collect()
+    }
+
     /// List directory contents via a session's file browser capability.
     pub async fn list_files(
         &self,
@@ -352,12 +1620,14 @@ impl SessionManager {
             .map_err(|e| TerminalError::RemoteError(e.to_string()))
     }
 
-    /// Write a file via a session's file browser capability.
+    /// Write a file via a session's file browser capability, normalizing
+    /// its line endings per `newline` first.
     pub async fn write_file(
         &self,
         session_id: &str,
         path: &str,
         data: &[u8],
+        newline: NewlineMode,
     ) -> Result<(), TerminalError> {
         let sessions = self.sessions.lock().await;
         let entry = sessions
@@ -368,7 +1638,7 @@ impl SessionManager {
             .file_browser()
             .ok_or_else(|| TerminalError::RemoteError("No file browser capability".to_string()))?;
         browser
-            .write_file(path, data)
+            .write_file(path, &normalize_newlines(data, newline))
             .await
             .map_err(|e| TerminalError::RemoteError(e.to_string()))
     }
@@ -395,6 +1665,7 @@ impl SessionManager {
         session_id: &str,
         from: &str,
         to: &str,
+        overwrite: bool,
     ) -> Result<(), TerminalError> {
         let sessions = self.sessions.lock().await;
         let entry = sessions
@@ -405,13 +1676,22 @@ impl SessionManager {
             .file_browser()
             .ok_or_else(|| TerminalError::RemoteError("No file browser capability".to_string()))?;
         browser
-            .rename(from, to)
+            .rename(from, to, overwrite)
             .await
-            .map_err(|e| TerminalError::RemoteError(e.to_string()))
+            .map_err(|e| match e {
+                FileError::DestinationExists(path) => TerminalError::DestinationExists(path),
+                other => TerminalError::RemoteError(other.to_string()),
+            })
     }
 
-    /// Create a directory via a session's file browser capability.
-    pub async fn mkdir_file(&self, session_id: &str, path: &str) -> Result<(), TerminalError> {
+    /// Create a directory via a session's file browser capability, with its
+    /// missing parents when `recursive` is set.
+    pub async fn mkdir_file(
+        &self,
+        session_id: &str,
+        path: &str,
+        recursive: bool,
+    ) -> Result<(), TerminalError> {
         let sessions = self.sessions.lock().await;
         let entry = sessions
             .get(session_id)
@@ -421,16 +1701,160 @@ impl SessionManager {
             .file_browser()
             .ok_or_else(|| TerminalError::RemoteError("No file browser capability".to_string()))?;
         browser
-            .mkdir(path)
+            .mkdir(path, recursive)
+            .await
+            .map_err(|e| TerminalError::RemoteError(e.to_string()))
+    }
+
+    /// An owned handle to a session's file browser.
+    ///
+    /// The session table is only locked while the handle is obtained, so
+    /// long operations on it leave other sessions responsive.
+    async fn owned_file_browser(
+        &self,
+        session_id: &str,
+    ) -> Result<Box<dyn FileBrowser>, TerminalError> {
+        let sessions = self.sessions.lock().await;
+        let entry = sessions
+            .get(session_id)
+            .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+        entry
+            .connection
+            .file_browser()
+            .map(|browser| browser.clone_box())
+            .ok_or_else(|| TerminalError::RemoteError("No file browser capability".to_string()))
+    }
+
+    /// Search the files below `root` for lines containing `pattern`.
+    pub async fn grep(
+        &self,
+        session_id: &str,
+        root: &str,
+        pattern: &str,
+        opts: &GrepOptions,
+    ) -> Result<Vec<GrepMatch>, TerminalError> {
+        let browser = self.owned_file_browser(session_id).await?;
+        browser
+            .grep(root, pattern, opts)
+            .await
+            .map_err(|e| TerminalError::RemoteError(e.to_string()))
+    }
+
+    /// Compare the local file at `local_path` with `remote_path` in a
+    /// session's file browser, hashing both when `checksum` is set.
+    pub async fn compare_files(
+        &self,
+        session_id: &str,
+        local_path: &str,
+        remote_path: &str,
+        checksum: bool,
+    ) -> Result<FileComparison, TerminalError> {
+        // Neither side is awaited while holding the session table.
+        let browser = self.owned_file_browser(session_id).await?;
+        let remote = summarize_file(browser.as_ref(), remote_path, checksum).await;
+        let local = summarize_file(&LocalFileBrowser::new(), local_path, checksum).await;
+        let to_error = |e: FileError| TerminalError::RemoteError(e.to_string());
+        Ok(FileComparison::new(
+            local.map_err(to_error)?,
+            remote.map_err(to_error)?,
+        ))
+    }
+
+    /// Download the directory at `path` as a single archive written to
+    /// `local_path`. Returns the number of archive bytes written.
+    ///
+    /// The session table is only locked while the downloader is obtained,
+    /// so other sessions stay responsive during a long download.
+    pub async fn download_archive(
+        &self,
+        session_id: &str,
+        path: &str,
+        format: ArchiveFormat,
+        local_path: &Path,
+        on_progress: &mut (dyn FnMut(u64) + Send),
+    ) -> Result<u64, TerminalError> {
+        let downloader = {
+            let sessions = self.sessions.lock().await;
+            let entry = sessions
+                .get(session_id)
+                .ok_or_else(|| TerminalError::SessionNotFound(session_id.to_string()))?;
+            let browser = entry.connection.file_browser().ok_or_else(|| {
+                TerminalError::RemoteError("No file browser capability".to_string())
+            })?;
+            browser.archive_downloader().ok_or_else(|| {
+                TerminalError::RemoteError(
+                    "Archive download not supported for this session".to_string(),
+                )
+            })?
+        };
+        downloader
+            .download(
+                path,
+                format,
+                local_path,
+                &CancellationToken::new(),
+                on_progress,
+            )
+            .await
+            .map_err(|e| TerminalError::RemoteError(e.to_string()))
+    }
+
+    /// Compute the total size in bytes of a file or directory tree.
+    ///
+    /// Only one computation runs per session; starting a new one cancels
+    /// the previous one. Use [`Self::cancel_dir_size`] to abort it.
+    pub async fn dir_size(&self, session_id: &str, path: &str) -> Result<u64, TerminalError> {
+        let cancel = CancellationToken::new();
+        if let Some(previous) = self
+            .dir_size_cancels
+            .lock()
+            .await
+            .insert(session_id.to_string(), cancel.clone())
+        {
+            previous.cancel();
+        }
+
+        let result = self.dir_size_with(session_id, path, &cancel).await;
+
+        // A cancelled token was either cancelled by the caller or replaced by a
+        // newer computation; only an uncancelled one is still ours to remove.
+        if !cancel.is_cancelled() {
+            self.dir_size_cancels.lock().await.remove(session_id);
+        }
+        result
+    }
+
+    async fn dir_size_with(
+        &self,
+        session_id: &str,
+        path: &str,
+        cancel: &CancellationToken,
+    ) -> Result<u64, TerminalError> {
+        // The walk can take long; it must not hold the session table.
+        let browser = self.owned_file_browser(session_id).await?;
+        browser
+            .dir_size(path, cancel)
             .await
             .map_err(|e| TerminalError::RemoteError(e.to_string()))
     }
 
+    /// Cancel an in-flight directory size computation for a session, if any.
+    pub async fn cancel_dir_size(&self, session_id: &str) {
+        if let Some(cancel) = self.dir_size_cancels.lock().await.remove(session_id) {
+            cancel.cancel();
+        }
+    }
+
     /// Get the list of available connection types from the registry.
     pub fn available_types(&self) -> Vec<ConnectionTypeInfo> {
         self.registry.available_types()
     }
 
+    /// JSON Schema document for the settings of a registered connection type.
+    pub fn connection_json_schema(&self, type_id: &str) -> Result<serde_json::Value, CoreError> {
+        self.registry.json_schema(type_id)
+    }
+
     /// Return the capabilities of an active session.
     pub async fn session_capabilities(&self, session_id: &str) -> Option<Capabilities> {
         let sessions = self.sessions.lock().await;
@@ -572,33 +1996,61 @@ impl SessionManager {
         let mut sessions = self.sessions.lock().await;
         sessions.insert(
             session_id.to_string(),
-            SessionEntry {
+            SessionEntry::new(
                 connection,
-                info: SessionInfo {
+                SessionInfo {
                     id: session_id.to_string(),
                     title: "test".to_string(),
                     connection_type: "mock".to_string(),
                     alive: true,
                     agent_id: None,
+                    raw_key_mode: false,
+                    detached: false,
                 },
-            },
+            ),
         );
     }
 
     /// Read output from a connection and emit Tauri events.
     ///
     /// Coalesces pending output chunks into a single event (up to
-    /// `MAX_COALESCE_BYTES`) to reduce IPC overhead.
+    /// `MAX_COALESCE_BYTES`) to reduce IPC overhead. Emitted data is run
+    /// through the connection's output transform `pipeline`. With `sudo`
+    /// set, detected sudo prompts are answered by writing the password back
+    /// to the connection. Bells in the raw output emit a debounced
+    /// `terminal-bell` event. Output of a session with an [`OutputTaps`]
+    /// entry goes only to the tap, exactly as the backend delivered it.
+    #[allow(clippy::too_many_arguments)]
     async fn run_output_reader<E: EventEmitter>(
         session_id: String,
         mut output_rx: tokio::sync::mpsc::Receiver<Vec<u8>>,
         emitter: E,
         sessions: Arc<Mutex<HashMap<String, SessionEntry>>>,
         wait_for_clear: bool,
+        mut pipeline: OutputPipeline,
+        mut sudo: Option<SudoAutoFill>,
+        taps: OutputTaps,
     ) {
-        // Phase 1: optionally buffer until the screen-clear sequence.
-        if wait_for_clear {
-            let deadline = Instant::now() + CLEAR_WAIT_TIMEOUT;
+        // During an XMODEM send the output is the receiver's protocol
+        // replies, meant for the transfer rather than the terminal.
+        let divert = |data: &[u8]| {
+            taps.lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .get(&session_id)
+                .is_some_and(|tap| tap.send(data.to_vec()).is_ok())
+        };
+        let mut bell = BellDetector::default();
+        let ring = |bell: &mut BellDetector, data: &[u8]| {
+            if bell.feed(data, std::time::Instant::now()) {
+                emitter.emit_bell(&TerminalBellEvent {
+                    session_id: session_id.clone(),
+                });
+            }
+        };
+
+        // Phase 1: optionally buffer until the screen-clear sequence.
+        if wait_for_clear {
+            let deadline = Instant::now() + CLEAR_WAIT_TIMEOUT;
 
             let mut buffer = Vec::new();
 
@@ -616,7 +2068,10 @@ impl SessionManager {
                     }
                     Ok(None) => {
                         // Channel closed during startup.
-                        Self::emit_and_cleanup(&session_id, buffer, &emitter, &sessions).await;
+                        ring(&mut bell, &buffer);
+                        let mut data = pipeline.process(&buffer);
+                        data.extend(pipeline.flush());
+                        Self::emit_and_cleanup(&session_id, data, &emitter, &sessions).await;
                         return;
                     }
                     Err(_) => break, // Timeout
@@ -624,10 +2079,16 @@ impl SessionManager {
             }
 
             // Flush the buffered output as a single event.
-            if !buffer.is_empty() {
+            let data = if divert(&buffer) {
+                Vec::new()
+            } else {
+                ring(&mut bell, &buffer);
+                pipeline.process(&buffer)
+            };
+            if !data.is_empty() {
                 let event = TerminalOutputEvent {
                     session_id: session_id.clone(),
-                    data: buffer,
+                    data,
                 };
                 if !emitter.emit_output(&event) {
                     return;
@@ -649,6 +2110,21 @@ impl SessionManager {
             }
 
             if let Some(data) = coalescer.flush() {
+                if divert(&data) {
+                    continue;
+                }
+                ring(&mut bell, &data);
+                if let Some(input) = sudo.as_mut().and_then(|fill| fill.answer(&data)) {
+                    let sessions = sessions.lock().await;
+                    if let Some(entry) = sessions.get(&session_id) {
+                        info!(session_id = %session_id, "Answering sudo prompt");
+                        let _ = entry.connection.write(input.as_bytes());
+                    }
+                }
+                let data = pipeline.process(&data);
+                if data.is_empty() {
+                    continue;
+                }
                 let event = TerminalOutputEvent {
                     session_id: session_id.clone(),
                     data,
@@ -660,7 +2136,7 @@ impl SessionManager {
             }
         }
 
-        Self::emit_and_cleanup(&session_id, Vec::new(), &emitter, &sessions).await;
+        Self::emit_and_cleanup(&session_id, pipeline.flush(), &emitter, &sessions).await;
     }
 
     /// Emit remaining data (if any), send the exit event, and remove the session.
@@ -678,6 +2154,19 @@ impl SessionManager {
             emitter.emit_output(&event);
         }
 
+        let reason = sessions
+            .lock()
+            .await
+            .get(session_id)
+            .and_then(|entry| entry.connection.close_reason());
+        if let Some(reason) = reason {
+            info!(session_id, ?reason, "Session closed");
+            emitter.emit_closed(&SessionClosedEvent {
+                session_id: session_id.to_string(),
+                reason,
+            });
+        }
+
         let exit_event = TerminalExitEvent {
             session_id: session_id.to_string(),
             exit_code: None,
@@ -697,17 +2186,21 @@ impl SessionManager {
 mod tests {
     use super::*;
 
-    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 
     use serde_json::Value;
-    use termihub_core::connection::{Capabilities, OutputReceiver, SettingsSchema};
+    use termihub_core::connection::{
+        Capabilities, HostKeyFingerprint, OutputReceiver, SessionNotice, SettingsSchema,
+    };
     use termihub_core::errors::SessionError;
     use termihub_core::files::FileBrowser;
     use termihub_core::monitoring::MonitoringProvider;
+    use termihub_core::output::cast_recorder::{parse_cast, CastEventKind};
 
     use termihub_core::monitoring::MonitoringSender;
 
     use crate::connection::config::AgentSettings;
+    use crate::session::remote_proxy::tests::MockAgentRpcClient;
     use crate::terminal::agent_manager::{
         AgentCapabilities, AgentConnectResult, AgentConnectionsData, AgentDefinitionInfo,
         AgentFolderInfo, AgentRpcClient, AgentSessionInfo,
@@ -769,16 +2262,18 @@ mod tests {
         let mut map = sessions.lock().await;
         map.insert(
             session_id.to_string(),
-            SessionEntry {
-                connection: Box::new(MockConnection),
-                info: SessionInfo {
+            SessionEntry::new(
+                Box::new(MockConnection),
+                SessionInfo {
                     id: session_id.to_string(),
                     title: "Mock".to_string(),
                     connection_type: "mock".to_string(),
                     alive: true,
                     agent_id: None,
+                    raw_key_mode: false,
+                    detached: false,
                 },
-            },
+            ),
         );
         drop(map);
         sessions
@@ -790,6 +2285,10 @@ mod tests {
     struct MockEventEmitter {
         outputs: std::sync::Arc<std::sync::Mutex<Vec<TerminalOutputEvent>>>,
         exits: std::sync::Arc<std::sync::Mutex<Vec<TerminalExitEvent>>>,
+        bells: std::sync::Arc<std::sync::Mutex<Vec<TerminalBellEvent>>>,
+        closed: std::sync::Arc<std::sync::Mutex<Vec<SessionClosedEvent>>>,
+        fingerprints: std::sync::Arc<std::sync::Mutex<Vec<SshHostFingerprintEvent>>>,
+        notices: std::sync::Arc<std::sync::Mutex<Vec<SessionNoticeEvent>>>,
         fail_output: bool,
     }
 
@@ -816,6 +2315,18 @@ mod tests {
         fn emit_exit(&self, event: &TerminalExitEvent) {
             self.exits.lock().unwrap().push(event.clone());
         }
+        fn emit_bell(&self, event: &TerminalBellEvent) {
+            self.bells.lock().unwrap().push(event.clone());
+        }
+        fn emit_closed(&self, event: &SessionClosedEvent) {
+            self.closed.lock().unwrap().push(event.clone());
+        }
+        fn emit_host_fingerprint(&self, event: &SshHostFingerprintEvent) {
+            self.fingerprints.lock().unwrap().push(event.clone());
+        }
+        fn emit_notice(&self, event: &SessionNoticeEvent) {
+            self.notices.lock().unwrap().push(event.clone());
+        }
     }
 
     /// Test that file browser access returns an error when the connection
@@ -931,6 +2442,259 @@ mod tests {
         }
     }
 
+    /// A connection that reports why it closed gets a session-closed event
+    /// before the exit event; an ordinary exit gets none.
+    #[tokio::test]
+    async fn emit_and_cleanup_reports_close_reason() {
+        let emitter = MockEventEmitter::new();
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let lost = DisconnectSpy::new(Arc::new(AtomicBool::new(false)))
+            .with_close_reason(CloseReason::ConnectionLost);
+        manager.insert_test_session("lost", Box::new(lost)).await;
+        let spy = DisconnectSpy::new(Arc::new(AtomicBool::new(false)));
+        manager.insert_test_session("exited", Box::new(spy)).await;
+
+        SessionManager::emit_and_cleanup("lost", Vec::new(), &emitter, &manager.sessions).await;
+        SessionManager::emit_and_cleanup("exited", Vec::new(), &emitter, &manager.sessions).await;
+
+        let closed = emitter.closed.lock().unwrap();
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].session_id, "lost");
+        assert_eq!(closed[0].reason, CloseReason::ConnectionLost);
+        assert_eq!(
+            serde_json::to_value(&closed[0]).unwrap()["reason"],
+            "ConnectionLost"
+        );
+        assert_eq!(emitter.exits.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn pending_input_rejects_overflow_and_everything_after_it() {
+        let mut queue = PendingInput::default();
+        assert!(queue.push(b"ls"));
+        assert!(!queue.push(&vec![b'x'; MAX_PENDING_INPUT_BYTES]));
+        assert!(!queue.push(b"\r"));
+        assert_eq!(queue.chunks, vec![b"ls".to_vec()]);
+        assert_eq!(queue.bytes, 2);
+    }
+
+    /// Input sent while the backend is still connecting is written in order
+    /// as soon as it connects; input overflowing the queue, and all input
+    /// after it, is rejected.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn input_before_connected_is_flushed_in_order() {
+        let not_yet_connected = Arc::new(AtomicBool::new(true));
+        let written = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut registry = ConnectionTypeRegistry::new();
+        let (flag, log) = (not_yet_connected.clone(), written.clone());
+        registry.register(
+            "slow",
+            "Slow",
+            "terminal",
+            Box::new(move || {
+                Box::new(DisconnectSpy::new(flag.clone()).with_write_log(log.clone()))
+            }),
+        );
+        let manager = SessionManager::new(registry, Arc::new(NullAgent));
+        let id = manager
+            .create_connection("slow", serde_json::json!({}), None, MockEventEmitter::new())
+            .await
+            .unwrap();
+
+        manager.send_input(&id, b"ab").await.unwrap();
+        manager.send_input(&id, b"cd").await.unwrap();
+        assert!(manager
+            .send_input(&id, &vec![b'x'; MAX_PENDING_INPUT_BYTES])
+            .await
+            .is_err());
+        assert!(manager.send_input(&id, b"ef").await.is_err());
+        assert!(written.lock().unwrap().is_empty());
+
+        not_yet_connected.store(false, Ordering::SeqCst);
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while written.lock().unwrap().is_empty() {
+            assert!(
+                tokio::time::Instant::now() < deadline,
+                "queued input not flushed"
+            );
+            tokio::time::sleep(PENDING_INPUT_POLL).await;
+        }
+        assert_eq!(written.lock().unwrap().as_slice(), b"abcd");
+
+        manager.send_input(&id, b"gh").await.unwrap();
+        assert_eq!(written.lock().unwrap().as_slice(), b"abcdgh");
+    }
+
+    /// The `rawKeyMode` setting shows up in both session listings, and
+    /// control sequences reach the backend unchanged.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn raw_key_mode_round_trips_through_session_metadata() {
+        let written = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut registry = ConnectionTypeRegistry::new();
+        let log = written.clone();
+        registry.register(
+            "spy",
+            "Spy",
+            "terminal",
+            Box::new(move || {
+                Box::new(
+                    DisconnectSpy::new(Arc::new(AtomicBool::new(false)))
+                        .with_write_log(log.clone()),
+                )
+            }),
+        );
+        let manager = SessionManager::new(registry, Arc::new(NullAgent));
+        let raw = manager
+            .create_connection(
+                "spy",
+                serde_json::json!({ "rawKeyMode": true }),
+                None,
+                MockEventEmitter::new(),
+            )
+            .await
+            .unwrap();
+        let plain = manager
+            .create_connection("spy", serde_json::json!({}), None, MockEventEmitter::new())
+            .await
+            .unwrap();
+
+        let active: HashMap<String, bool> = manager
+            .list_active_sessions()
+            .await
+            .into_iter()
+            .map(|s| (s.id, s.raw_key_mode))
+            .collect();
+        let listed: HashMap<String, bool> = manager
+            .list_sessions()
+            .await
+            .into_iter()
+            .map(|s| (s.id, s.raw_key_mode))
+            .collect();
+        for flags in [&active, &listed] {
+            assert!(flags[&raw]);
+            assert!(!flags[&plain]);
+        }
+
+        // Ctrl+W, Ctrl+Up and Ctrl+C as a TUI program would receive them.
+        let keys = b"\x17\x1b[1;5A\x03";
+        manager.send_input(&raw, keys).await.unwrap();
+        assert_eq!(written.lock().unwrap().as_slice(), keys);
+    }
+
+    /// A connection routed through an agent is created there with
+    /// `session.create`, and its input and output travel over the agent.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn create_connection_via_agent_proxies_io() {
+        let agent = Arc::new(MockAgentRpcClient::new());
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), agent.clone());
+        let emitter = MockEventEmitter::new();
+        let id = manager
+            .create_connection(
+                "ssh",
+                serde_json::json!({ "host": "10.0.0.5" }),
+                Some("gateway"),
+                emitter.clone(),
+            )
+            .await
+            .unwrap();
+
+        {
+            let created = agent.created_sessions.lock().unwrap();
+            assert_eq!(created.len(), 1);
+            assert_eq!(created[0].0, "gateway");
+            assert_eq!(created[0].1, "ssh");
+            assert_eq!(created[0].2["host"], "10.0.0.5");
+        }
+
+        manager.send_input(&id, b"uptime\r").await.unwrap();
+        assert_eq!(
+            *agent.sent_input.lock().unwrap(),
+            vec![("mock-session-1".to_string(), b"uptime\r".to_vec())]
+        );
+
+        let output = agent.output_senders.lock().unwrap()[0].clone();
+        output.send(b"up 3 days".to_vec()).unwrap();
+        for _ in 0..100 {
+            if !emitter.outputs.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let outputs = emitter.outputs.lock().unwrap();
+        assert_eq!(outputs.len(), 1);
+        assert_eq!(outputs[0].session_id, id);
+        assert_eq!(outputs[0].data, b"up 3 days");
+    }
+
+    /// A connection that reports a host key gets one ssh-host-fingerprint
+    /// event when its session starts.
+    #[tokio::test]
+    async fn create_connection_emits_host_fingerprint() {
+        let mut registry = ConnectionTypeRegistry::new();
+        registry.register(
+            "ssh",
+            "SSH",
+            "ssh",
+            Box::new(|| {
+                Box::new(
+                    DisconnectSpy::new(Arc::new(AtomicBool::new(false))).with_host_key(
+                        HostKeyFingerprint {
+                            key_type: "ssh-ed25519".to_string(),
+                            fingerprint: "SHA256:abc".to_string(),
+                        },
+                    ),
+                )
+            }),
+        );
+        let manager = SessionManager::new(registry, Arc::new(NullAgent));
+        let emitter = MockEventEmitter::new();
+
+        let id = manager
+            .create_connection("ssh", serde_json::json!({}), None, emitter.clone())
+            .await
+            .unwrap();
+
+        let fingerprints = emitter.fingerprints.lock().unwrap();
+        assert_eq!(fingerprints.len(), 1);
+        assert_eq!(fingerprints[0].session_id, id);
+        assert_eq!(fingerprints[0].key_type, "ssh-ed25519");
+        assert_eq!(fingerprints[0].fingerprint, "SHA256:abc");
+    }
+
+    /// Notices reported by a connection are emitted once each when its
+    /// session starts.
+    #[tokio::test]
+    async fn create_connection_emits_notices() {
+        let mut registry = ConnectionTypeRegistry::new();
+        registry.register(
+            "ssh",
+            "SSH",
+            "ssh",
+            Box::new(|| {
+                Box::new(
+                    DisconnectSpy::new(Arc::new(AtomicBool::new(false))).with_notice(
+                        SessionNotice {
+                            code: "restrictedShell".to_string(),
+                            message: "restricted".to_string(),
+                        },
+                    ),
+                )
+            }),
+        );
+        let manager = SessionManager::new(registry, Arc::new(NullAgent));
+        let emitter = MockEventEmitter::new();
+
+        let id = manager
+            .create_connection("ssh", serde_json::json!({}), None, emitter.clone())
+            .await
+            .unwrap();
+
+        let notices = emitter.notices.lock().unwrap();
+        assert_eq!(notices.len(), 1);
+        assert_eq!(notices[0].session_id, id);
+        assert_eq!(notices[0].code, "restrictedShell");
+    }
+
     #[tokio::test]
     async fn run_output_reader_emits_chunks_and_exit() {
         let emitter = MockEventEmitter::new();
@@ -947,6 +2711,9 @@ mod tests {
             emitter.clone(),
             sessions.clone(),
             false,
+            OutputPipeline::new(),
+            None,
+            OutputTaps::default(),
         )
         .await;
 
@@ -967,6 +2734,118 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn run_output_reader_applies_output_transforms() {
+        let emitter = MockEventEmitter::new();
+        let sessions = sessions_with_mock("sess-transform").await;
+        let (tx, rx) = tokio::sync::mpsc::channel::<Vec<u8>>(10);
+        let pipeline = OutputPipeline::from_settings(&serde_json::json!({
+            "outputTransforms": [{"type": "linePrefix", "prefix": "> "}]
+        }))
+        .unwrap();
+
+        tx.send(b"one\ntwo\n".to_vec()).await.unwrap();
+        drop(tx);
+
+        SessionManager::run_output_reader(
+            "sess-transform".to_string(),
+            rx,
+            emitter.clone(),
+            sessions,
+            false,
+            pipeline,
+            None,
+            OutputTaps::default(),
+        )
+        .await;
+
+        let outputs = emitter.outputs.lock().unwrap();
+        let combined: Vec<u8> = outputs
+            .iter()
+            .flat_map(|e| e.data.iter().copied())
+            .collect();
+        assert_eq!(combined, b"> one\n> two\n");
+    }
+
+    #[tokio::test]
+    async fn run_output_reader_answers_sudo_prompt_once() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let written = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let spy =
+            DisconnectSpy::new(Arc::new(AtomicBool::new(false))).with_write_log(written.clone());
+        manager
+            .insert_test_session("sess-sudo", Box::new(spy))
+            .await;
+
+        let sudo = SudoAutoFill::from_settings(&serde_json::json!({
+            "authMethod": "password",
+            "password": "hunter2",
+            "sudoAutoFill": true
+        }))
+        .unwrap();
+        assert!(sudo.is_some());
+
+        let (tx, rx) = tokio::sync::mpsc::channel::<Vec<u8>>(10);
+        tx.send(b"$ sudo ls\r\n[sudo] password for alice: ".to_vec())
+            .await
+            .unwrap();
+        tx.send(b"\r\nSorry, try again.\r\n[sudo] password for alice: ".to_vec())
+            .await
+            .unwrap();
+        drop(tx);
+
+        SessionManager::run_output_reader(
+            "sess-sudo".to_string(),
+            rx,
+            MockEventEmitter::new(),
+            manager.sessions.clone(),
+            false,
+            OutputPipeline::new(),
+            sudo,
+            OutputTaps::default(),
+        )
+        .await;
+
+        assert_eq!(written.lock().unwrap().as_slice(), b"hunter2\r");
+    }
+
+    #[tokio::test]
+    async fn run_output_reader_emits_debounced_bell() {
+        let emitter = MockEventEmitter::new();
+        let sessions = sessions_with_mock("sess-bell").await;
+        let (tx, rx) = tokio::sync::mpsc::channel::<Vec<u8>>(10);
+        tx.send(b"\x1b]0;title\x07make\r\n".to_vec()).await.unwrap();
+        tx.send(b"done\x07".to_vec()).await.unwrap();
+        tx.send(b"\x07\x07".to_vec()).await.unwrap();
+        drop(tx);
+
+        SessionManager::run_output_reader(
+            "sess-bell".to_string(),
+            rx,
+            emitter.clone(),
+            sessions,
+            false,
+            OutputPipeline::new(),
+            None,
+            OutputTaps::default(),
+        )
+        .await;
+
+        let bells = emitter.bells.lock().unwrap();
+        assert_eq!(bells.len(), 1);
+        assert_eq!(bells[0].session_id, "sess-bell");
+
+        // Output is forwarded unchanged, bells included.
+        let combined: Vec<u8> = emitter
+            .outputs
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|e| e.data.iter().copied())
+            .collect();
+        assert_eq!(combined, b"\x1b]0;title\x07make\r\ndone\x07\x07\x07");
+    }
+
     #[tokio::test]
     async fn run_output_reader_stops_on_emitter_failure() {
         let emitter = MockEventEmitter::failing();
@@ -982,6 +2861,9 @@ mod tests {
             emitter.clone(),
             sessions,
             false,
+            OutputPipeline::new(),
+            None,
+            OutputTaps::default(),
         )
         .await;
 
@@ -992,14 +2874,66 @@ mod tests {
 
     // ── DisconnectSpy ─────────────────────────────────────────────────
 
-    /// A connection that records whether `disconnect()` was called.
+    /// A connection that records whether `disconnect()` was called, what
+    /// was written to it (typed and binary), and the sizes it was resized to.
     struct DisconnectSpy {
         disconnected: Arc<AtomicBool>,
+        written: Arc<std::sync::Mutex<Vec<u8>>>,
+        binary_written: Arc<std::sync::Mutex<Vec<u8>>>,
+        binary_transfer: Arc<AtomicBool>,
+        resized: Arc<std::sync::Mutex<Vec<(u16, u16)>>>,
+        close_reason: Option<CloseReason>,
+        host_key: Option<HostKeyFingerprint>,
+        notices: Vec<SessionNotice>,
     }
 
     impl DisconnectSpy {
         fn new(flag: Arc<AtomicBool>) -> Self {
-            Self { disconnected: flag }
+            Self {
+                disconnected: flag,
+                written: Arc::new(std::sync::Mutex::new(Vec::new())),
+                binary_written: Arc::new(std::sync::Mutex::new(Vec::new())),
+                binary_transfer: Arc::new(AtomicBool::new(false)),
+                resized: Arc::new(std::sync::Mutex::new(Vec::new())),
+                close_reason: None,
+                host_key: None,
+                notices: Vec::new(),
+            }
+        }
+
+        fn with_close_reason(mut self, reason: CloseReason) -> Self {
+            self.close_reason = Some(reason);
+            self
+        }
+
+        fn with_host_key(mut self, key: HostKeyFingerprint) -> Self {
+            self.host_key = Some(key);
+            self
+        }
+
+        fn with_notice(mut self, notice: SessionNotice) -> Self {
+            self.notices.push(notice);
+            self
+        }
+
+        fn with_write_log(mut self, written: Arc<std::sync::Mutex<Vec<u8>>>) -> Self {
+            self.written = written;
+            self
+        }
+
+        fn with_binary_log(
+            mut self,
+            written: Arc<std::sync::Mutex<Vec<u8>>>,
+            active: Arc<AtomicBool>,
+        ) -> Self {
+            self.binary_written = written;
+            self.binary_transfer = active;
+            self
+        }
+
+        fn with_resize_log(mut self, resized: Arc<std::sync::Mutex<Vec<(u16, u16)>>>) -> Self {
+            self.resized = resized;
+            self
         }
     }
 
@@ -1030,12 +2964,23 @@ mod tests {
             Ok(())
         }
         fn is_connected(&self) -> bool {
-            true
+            !self.disconnected.load(Ordering::SeqCst)
         }
-        fn write(&self, _: &[u8]) -> Result<(), SessionError> {
+        fn write(&self, data: &[u8]) -> Result<(), SessionError> {
+            self.written.lock().unwrap().extend_from_slice(data);
             Ok(())
         }
-        fn resize(&self, _: u16, _: u16) -> Result<(), SessionError> {
+        fn write_binary(&self, data: &[u8]) -> Result<(), SessionError> {
+            assert!(self.binary_transfer.load(Ordering::SeqCst));
+            self.binary_written.lock().unwrap().extend_from_slice(data);
+            Ok(())
+        }
+        fn set_binary_transfer(&self, active: bool) -> Result<(), SessionError> {
+            self.binary_transfer.store(active, Ordering::SeqCst);
+            Ok(())
+        }
+        fn resize(&self, cols: u16, rows: u16) -> Result<(), SessionError> {
+            self.resized.lock().unwrap().push((cols, rows));
             Ok(())
         }
         fn subscribe_output(&self) -> OutputReceiver {
@@ -1048,6 +2993,15 @@ mod tests {
         fn file_browser(&self) -> Option<&dyn FileBrowser> {
             None
         }
+        fn close_reason(&self) -> Option<CloseReason> {
+            self.close_reason
+        }
+        fn host_key_fingerprint(&self) -> Option<HostKeyFingerprint> {
+            self.host_key.clone()
+        }
+        fn notices(&self) -> Vec<SessionNotice> {
+            self.notices.clone()
+        }
     }
 
     // ── NullAgent ────────────────────────────────────────────────────
@@ -1183,7 +3137,10 @@ mod tests {
 
         manager.insert_test_session("spy-1", Box::new(spy)).await;
 
-        manager.close_session("spy-1").await.unwrap();
+        manager
+            .close_session("spy-1", CloseTrigger::TabClosed)
+            .await
+            .unwrap();
 
         assert!(
             disconnected.load(Ordering::SeqCst),
@@ -1191,6 +3148,879 @@ mod tests {
         );
     }
 
+    /// A burst of resizes is coalesced into one backend resize with the
+    /// final dimensions.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn resize_burst_applies_only_final_size() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        manager.set_resize_debounce(Duration::from_millis(30));
+        let resized = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let spy =
+            DisconnectSpy::new(Arc::new(AtomicBool::new(false))).with_resize_log(resized.clone());
+        manager.insert_test_session("spy-1", Box::new(spy)).await;
+
+        for cols in 80..100 {
+            manager.resize("spy-1", cols, 24 + cols / 10).await.unwrap();
+        }
+        assert!(
+            resized.lock().unwrap().is_empty(),
+            "resize must be deferred"
+        );
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(resized.lock().unwrap().as_slice(), &[(99, 33)]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn resize_without_debounce_applies_immediately() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        manager.set_resize_debounce(Duration::ZERO);
+        let resized = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let spy =
+            DisconnectSpy::new(Arc::new(AtomicBool::new(false))).with_resize_log(resized.clone());
+        manager.insert_test_session("spy-1", Box::new(spy)).await;
+
+        manager.resize("spy-1", 100, 30).await.unwrap();
+        manager.resize("spy-1", 120, 40).await.unwrap();
+        assert_eq!(resized.lock().unwrap().as_slice(), &[(100, 30), (120, 40)]);
+    }
+
+    #[tokio::test]
+    async fn resize_unknown_session_fails_immediately() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let result = manager.resize("missing", 80, 24).await;
+        assert!(matches!(result, Err(TerminalError::SessionNotFound(_))));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn list_active_sessions_reports_metadata() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        manager.set_resize_debounce(Duration::ZERO);
+        manager
+            .insert_test_session("mock-1", Box::new(MockConnection))
+            .await;
+        let spy = DisconnectSpy::new(Arc::new(AtomicBool::new(false)));
+        manager.insert_test_session("spy-1", Box::new(spy)).await;
+        manager.resize("mock-1", 132, 43).await.unwrap();
+
+        let mut listed = manager.list_active_sessions().await;
+        listed.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(listed.len(), 2);
+
+        let mock = &listed[0];
+        assert_eq!(mock.id, "mock-1");
+        assert_eq!(mock.session_type, "mock");
+        assert_eq!(mock.title, "test");
+        assert_eq!((mock.cols, mock.rows), (132, 43));
+        assert!(mock.capabilities.resize);
+        assert!(chrono::DateTime::parse_from_rfc3339(&mock.connected_since).is_ok());
+
+        let spy = &listed[1];
+        assert_eq!(spy.id, "spy-1");
+        assert_eq!((spy.cols, spy.rows), DEFAULT_SIZE);
+        assert!(!spy.capabilities.resize);
+    }
+
+    #[tokio::test]
+    async fn list_active_sessions_skips_dead_sessions() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let dead = DisconnectSpy::new(Arc::new(AtomicBool::new(true)));
+        manager.insert_test_session("dead-1", Box::new(dead)).await;
+        manager
+            .insert_test_session("mock-1", Box::new(MockConnection))
+            .await;
+
+        let listed = manager.list_active_sessions().await;
+        let ids: Vec<&str> = listed.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(ids, vec!["mock-1"]);
+    }
+
+    #[test]
+    fn active_session_info_serializes_camel_case() {
+        let info = ActiveSessionInfo {
+            id: "s1".to_string(),
+            session_type: "local".to_string(),
+            title: "bash".to_string(),
+            connected_since: "2026-01-01T00:00:00+00:00".to_string(),
+            cols: 80,
+            rows: 24,
+            capabilities: Capabilities {
+                monitoring: false,
+                file_browser: true,
+                resize: true,
+                persistent: false,
+            },
+            detached: false,
+            raw_key_mode: true,
+        };
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["type"], "local");
+        assert_eq!(json["rawKeyMode"], true);
+        assert_eq!(json["connectedSince"], "2026-01-01T00:00:00+00:00");
+        assert_eq!(json["capabilities"]["fileBrowser"], true);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn recorded_input_replays_to_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("input.cast");
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+
+        let recorded = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let spy =
+            DisconnectSpy::new(Arc::new(AtomicBool::new(false))).with_write_log(recorded.clone());
+        manager.insert_test_session("spy-1", Box::new(spy)).await;
+        manager
+            .start_session_recording("spy-1", &path, true)
+            .await
+            .unwrap();
+        manager.send_input("spy-1", b"ls\r").await.unwrap();
+        manager.send_input("spy-1", b"exit\r").await.unwrap();
+        assert!(manager.stop_session_recording("spy-1"));
+
+        let replayed = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let target =
+            DisconnectSpy::new(Arc::new(AtomicBool::new(false))).with_write_log(replayed.clone());
+        manager.insert_test_session("spy-2", Box::new(target)).await;
+        let count = manager.replay_input("spy-2", &path, 100.0).await.unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(replayed.lock().unwrap().as_slice(), b"ls\rexit\r");
+    }
+
+    #[tokio::test]
+    async fn session_recording_requires_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let result = manager
+            .start_session_recording("missing", &dir.path().join("x.cast"), true)
+            .await;
+        assert!(matches!(result, Err(TerminalError::SessionNotFound(_))));
+    }
+
+    /// A session recording captures output, input and resizes in order.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn session_recording_captures_output_input_and_resize() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("session.cast");
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        manager.set_resize_debounce(Duration::ZERO);
+        let spy = DisconnectSpy::new(Arc::new(AtomicBool::new(false)));
+        manager.insert_test_session("spy-1", Box::new(spy)).await;
+        manager
+            .start_session_recording("spy-1", &path, true)
+            .await
+            .unwrap();
+
+        let emitter = LoggingEmitter {
+            inner: MockEventEmitter::new(),
+            logs: manager.output_logs.clone(),
+            recordings: manager.session_recordings.clone(),
+            scrollbacks: manager.scrollbacks.clone(),
+            paused: manager.paused_outputs.clone(),
+        };
+        emitter.emit_output(&TerminalOutputEvent {
+            session_id: "spy-1".to_string(),
+            data: b"$ ".to_vec(),
+        });
+        manager.send_input("spy-1", b"ls\r").await.unwrap();
+        manager.resize("spy-1", 120, 40).await.unwrap();
+        assert!(manager.stop_session_recording("spy-1"));
+        assert!(!manager.stop_session_recording("spy-1"));
+
+        let file = std::io::BufReader::new(std::fs::File::open(&path).unwrap());
+        let (header, events) = parse_cast(file).unwrap();
+        assert_eq!((header.width, header.height), (80, 24));
+        let kinds: Vec<(CastEventKind, &str)> =
+            events.iter().map(|e| (e.kind, e.data.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (CastEventKind::Output, "$ "),
+                (CastEventKind::Input, "ls\r"),
+                (CastEventKind::Resize, "120x40"),
+            ]
+        );
+    }
+
+    fn emitted(emitter: &MockEventEmitter) -> Vec<u8> {
+        emitter
+            .outputs
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|e| e.data.clone())
+            .collect()
+    }
+
+    /// Output emitted while paused is held, still accepted from the reader,
+    /// and delivered in order on resume.
+    #[tokio::test]
+    async fn paused_output_is_held_and_delivered_in_order_on_resume() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let spy = DisconnectSpy::new(Arc::new(AtomicBool::new(false)));
+        manager.insert_test_session("spy-1", Box::new(spy)).await;
+
+        let terminal = MockEventEmitter::new();
+        let emitter = LoggingEmitter {
+            inner: terminal.clone(),
+            logs: manager.output_logs.clone(),
+            recordings: manager.session_recordings.clone(),
+            scrollbacks: manager.scrollbacks.clone(),
+            paused: manager.paused_outputs.clone(),
+        };
+        let emit = |data: &[u8]| {
+            emitter.emit_output(&TerminalOutputEvent {
+                session_id: "spy-1".to_string(),
+                data: data.to_vec(),
+            })
+        };
+
+        assert!(emit(b"one "));
+        assert!(manager.pause_output("spy-1").await.unwrap());
+        assert!(!manager.pause_output("spy-1").await.unwrap());
+        assert!(emit(b"two "), "the reader must keep draining");
+        assert!(emit(b"three "));
+        assert_eq!(emitted(&terminal), b"one ");
+
+        assert!(manager.resume_output("spy-1", &terminal));
+        assert!(!manager.resume_output("spy-1", &terminal));
+        assert!(emit(b"four"));
+        assert_eq!(emitted(&terminal), b"one two three four");
+    }
+
+    /// Once held output overflows, resume skips the partial line left at
+    /// its start.
+    #[tokio::test]
+    async fn overflowing_paused_output_resumes_at_a_line_boundary() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let spy = DisconnectSpy::new(Arc::new(AtomicBool::new(false)));
+        manager.insert_test_session("spy-1", Box::new(spy)).await;
+        manager.pause_output("spy-1").await.unwrap();
+
+        let terminal = MockEventEmitter::new();
+        let emitter = LoggingEmitter {
+            inner: terminal.clone(),
+            logs: manager.output_logs.clone(),
+            recordings: manager.session_recordings.clone(),
+            scrollbacks: manager.scrollbacks.clone(),
+            paused: manager.paused_outputs.clone(),
+        };
+        let line = b"0123456789abcde\n";
+        let lines = line.repeat(PAUSED_OUTPUT_BYTES / line.len());
+        for data in [&lines[..], &b"tail"[..]] {
+            emitter.emit_output(&TerminalOutputEvent {
+                session_id: "spy-1".to_string(),
+                data: data.to_vec(),
+            });
+        }
+
+        assert!(manager.resume_output("spy-1", &terminal));
+        let delivered = emitted(&terminal);
+        assert!(delivered.starts_with(line));
+        assert!(delivered.ends_with(b"\ntail"));
+        assert_eq!(delivered.len(), PAUSED_OUTPUT_BYTES - line.len() + 4);
+    }
+
+    #[tokio::test]
+    async fn exit_delivers_output_held_while_paused() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let spy = DisconnectSpy::new(Arc::new(AtomicBool::new(false)));
+        manager.insert_test_session("spy-1", Box::new(spy)).await;
+        manager.pause_output("spy-1").await.unwrap();
+
+        let terminal = MockEventEmitter::new();
+        let emitter = LoggingEmitter {
+            inner: terminal.clone(),
+            logs: manager.output_logs.clone(),
+            recordings: manager.session_recordings.clone(),
+            scrollbacks: manager.scrollbacks.clone(),
+            paused: manager.paused_outputs.clone(),
+        };
+        emitter.emit_output(&TerminalOutputEvent {
+            session_id: "spy-1".to_string(),
+            data: b"last words".to_vec(),
+        });
+        emitter.emit_exit(&TerminalExitEvent {
+            session_id: "spy-1".to_string(),
+            exit_code: None,
+        });
+
+        assert_eq!(emitted(&terminal), b"last words");
+        assert_eq!(terminal.exits.lock().unwrap().len(), 1);
+        assert!(!manager.resume_output("spy-1", &terminal));
+    }
+
+    #[tokio::test]
+    async fn pause_output_requires_session() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let result = manager.pause_output("missing").await;
+        assert!(matches!(result, Err(TerminalError::SessionNotFound(_))));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ascii_file_is_sent_as_raw_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("commands.txt");
+        std::fs::write(&path, b"setenv a 1\nsaveenv\n").unwrap();
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let written = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let spy =
+            DisconnectSpy::new(Arc::new(AtomicBool::new(false))).with_write_log(written.clone());
+        manager.insert_test_session("spy-1", Box::new(spy)).await;
+
+        let sent = manager
+            .send_file(
+                "spy-1",
+                &path,
+                FileSendProtocol::Ascii,
+                Duration::from_millis(1),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(sent, 19);
+        assert_eq!(written.lock().unwrap().as_slice(), b"setenv a 1\nsaveenv\n");
+    }
+
+    /// Packets go through the binary write path, and the receiver's replies
+    /// are taken from the raw session output, before any output transform,
+    /// and kept from the terminal during the transfer.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn xmodem_file_send_uses_binary_path_and_raw_replies() {
+        use termihub_core::session::file_send::{xmodem_packet, XmodemChecksum};
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("firmware.bin");
+        let data: Vec<u8> = (0..200u8).collect();
+        std::fs::write(&path, &data).unwrap();
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let written = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let binary_written = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let binary_transfer = Arc::new(AtomicBool::new(false));
+        let spy = DisconnectSpy::new(Arc::new(AtomicBool::new(false)))
+            .with_write_log(written.clone())
+            .with_binary_log(binary_written.clone(), binary_transfer.clone());
+        manager.insert_test_session("spy-1", Box::new(spy)).await;
+
+        // A transform that would hide every reply if applied to the tap.
+        let pipeline = OutputPipeline::from_settings(&serde_json::json!({
+            "outputTransforms": [{"type": "linePrefix", "prefix": "> "}]
+        }))
+        .unwrap();
+        let terminal = MockEventEmitter::new();
+        let (tx, rx) = tokio::sync::mpsc::channel::<Vec<u8>>(10);
+        let reader = tokio::spawn(SessionManager::run_output_reader(
+            "spy-1".to_string(),
+            rx,
+            terminal.clone(),
+            manager.sessions.clone(),
+            false,
+            pipeline,
+            None,
+            manager.output_taps.clone(),
+        ));
+        let taps = manager.output_taps.clone();
+        let receiver = std::thread::spawn(move || {
+            while !taps.lock().unwrap().contains_key("spy-1") {
+                std::thread::sleep(Duration::from_millis(5));
+            }
+            // CRC handshake, then acknowledge two blocks and EOT.
+            tx.blocking_send(vec![b'C', 0x06, 0x06, 0x06]).unwrap();
+            // Kept open so the session outlives the transfer.
+            tx
+        });
+
+        let sent = manager
+            .send_file("spy-1", &path, FileSendProtocol::Xmodem, Duration::ZERO)
+            .await
+            .unwrap();
+        drop(receiver.join().unwrap());
+        reader.await.unwrap();
+
+        assert_eq!(sent, 200);
+        let mut expected = xmodem_packet(1, &data[..128], XmodemChecksum::Crc16);
+        expected.extend(xmodem_packet(2, &data[128..], XmodemChecksum::Crc16));
+        expected.push(0x04);
+        assert_eq!(*binary_written.lock().unwrap(), expected);
+        assert!(written.lock().unwrap().is_empty());
+        assert!(!binary_transfer.load(Ordering::SeqCst));
+        assert!(emitted(&terminal).is_empty());
+        assert!(manager.output_taps.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn send_file_requires_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+        std::fs::write(&path, b"data").unwrap();
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let result = manager
+            .send_file("missing", &path, FileSendProtocol::Ascii, Duration::ZERO)
+            .await;
+        assert!(matches!(result, Err(TerminalError::SessionNotFound(_))));
+    }
+
+    /// The output log collapses progress redraws while the terminal still
+    /// receives the raw stream.
+    #[tokio::test]
+    async fn output_log_collapses_carriage_returns() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("output.log");
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let spy = DisconnectSpy::new(Arc::new(AtomicBool::new(false)));
+        manager.insert_test_session("spy-1", Box::new(spy)).await;
+        manager
+            .start_output_log("spy-1", &path, true)
+            .await
+            .unwrap();
+
+        let terminal = MockEventEmitter::new();
+        let emitter = LoggingEmitter {
+            inner: terminal.clone(),
+            logs: manager.output_logs.clone(),
+            recordings: manager.session_recordings.clone(),
+            scrollbacks: manager.scrollbacks.clone(),
+            paused: manager.paused_outputs.clone(),
+        };
+        for chunk in [&b"\rProgress: 10%"[..], b"\rProgress: 100%\n"] {
+            emitter.emit_output(&TerminalOutputEvent {
+                session_id: "spy-1".to_string(),
+                data: chunk.to_vec(),
+            });
+        }
+        assert!(manager.stop_output_log("spy-1"));
+        assert!(!manager.stop_output_log("spy-1"));
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "Progress: 100%\n");
+        let raw: Vec<u8> = terminal
+            .outputs
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|e| e.data.clone())
+            .collect();
+        assert_eq!(raw, b"\rProgress: 10%\rProgress: 100%\n");
+    }
+
+    #[tokio::test]
+    async fn extract_text_returns_clean_rows_of_scrollback() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let spy = DisconnectSpy::new(Arc::new(AtomicBool::new(false)));
+        manager.insert_test_session("spy-1", Box::new(spy)).await;
+        manager
+            .scrollbacks
+            .lock()
+            .unwrap()
+            .insert("spy-1".to_string(), RingBuffer::new(SCROLLBACK_BYTES));
+        manager.sessions.lock().await.get_mut("spy-1").unwrap().size = (10, 24);
+
+        let emitter = LoggingEmitter {
+            inner: MockEventEmitter::new(),
+            logs: manager.output_logs.clone(),
+            recordings: manager.session_recordings.clone(),
+            scrollbacks: manager.scrollbacks.clone(),
+            paused: manager.paused_outputs.clone(),
+        };
+        for chunk in [
+            &b"\x1b[1;32m$\x1b[0m ls\r\n"[..],
+            b"\x1b[34mdirectory\x1b[0m  notes.txt\r\n",
+            b"done\r\n",
+        ] {
+            emitter.emit_output(&TerminalOutputEvent {
+                session_id: "spy-1".to_string(),
+                data: chunk.to_vec(),
+            });
+        }
+
+        // Rows at 10 columns: "$ ls", "directory ", " notes.txt", "done".
+        assert_eq!(
+            manager.extract_text("spy-1", 1, 4).await.unwrap(),
+            "directory  notes.txt\ndone"
+        );
+        assert_eq!(manager.extract_text("spy-1", 0, 1).await.unwrap(), "$ ls");
+        assert!(matches!(
+            manager.extract_text("missing", 0, 1).await,
+            Err(TerminalError::SessionNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn search_all_sessions_reports_matches_per_session() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        {
+            let mut scrollbacks = manager.scrollbacks.lock().unwrap();
+            for (session_id, output) in [
+                (
+                    "build",
+                    &b"$ make\r\n\x1b[31merror: missing ;\x1b[0m\r\nerror: 2 errors\r\n"[..],
+                ),
+                ("logs", b"GET /health 200\r\nGET /api 500 Error\r\n"),
+                ("idle", b"$ \r\n"),
+            ] {
+                let mut scrollback = RingBuffer::new(SCROLLBACK_BYTES);
+                scrollback.write(output);
+                scrollbacks.insert(session_id.to_string(), scrollback);
+            }
+        }
+
+        assert_eq!(
+            manager.search_all_sessions("error", false),
+            vec![SessionSearchResult {
+                session_id: "build".to_string(),
+                match_count: 3,
+                first_match_preview: "error: missing ;".to_string(),
+            }]
+        );
+
+        let results = manager.search_all_sessions("ERROR", true);
+        let counts: Vec<(&str, usize)> = results
+            .iter()
+            .map(|r| (r.session_id.as_str(), r.match_count))
+            .collect();
+        assert_eq!(counts, vec![("build", 3), ("logs", 1)]);
+        assert_eq!(results[1].first_match_preview, "GET /api 500 Error");
+
+        assert!(manager.search_all_sessions("timeout", true).is_empty());
+        assert!(manager.search_all_sessions("", false).is_empty());
+    }
+
+    #[tokio::test]
+    async fn search_all_sessions_caps_match_count_and_preview() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let mut scrollback = RingBuffer::new(SCROLLBACK_BYTES);
+        scrollback.write(&b"x".repeat(MAX_SEARCH_MATCHES * 2));
+        manager
+            .scrollbacks
+            .lock()
+            .unwrap()
+            .insert("flood".to_string(), scrollback);
+
+        let results = manager.search_all_sessions("x", false);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].match_count, MAX_SEARCH_MATCHES);
+        assert_eq!(results[0].first_match_preview.len(), SEARCH_PREVIEW_CHARS);
+    }
+
+    #[tokio::test]
+    async fn session_pid_is_none_without_local_child() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        manager
+            .insert_test_session("mock-1", Box::new(MockConnection))
+            .await;
+
+        assert_eq!(manager.session_pid("mock-1").await.unwrap(), None);
+        assert!(matches!(
+            manager.session_pid("missing").await,
+            Err(TerminalError::SessionNotFound(_))
+        ));
+    }
+
+    /// A directory size request for a session without a file browser fails,
+    /// and leaves no cancellation token behind.
+    #[tokio::test]
+    async fn dir_size_without_file_browser_fails_and_cleans_up() {
+        let manager = SessionManager::new(ConnectionTypeRegistry::new(), Arc::new(NullAgent));
+        let spy = DisconnectSpy::new(Arc::new(AtomicBool::new(false)));
+        manager.insert_test_session("spy-1", Box::new(spy)).await;
+
+        let result = manager.dir_size("spy-1", "/tmp").await;
+        assert!(matches!(result, Err(TerminalError::RemoteError(_))));
+        assert!(manager.dir_size_cancels.lock().await.is_empty());
+
+        let result = manager.dir_size("missing", "/tmp").await;
+        assert!(matches!(result, Err(TerminalError::SessionNotFound(_))));
+
+        // Cancelling with nothing in flight is a no-op.
+        manager.cancel_dir_size("spy-1").await;
+    }
+
+    // ── Session restart ──────────────────────────────────────────────
+
+    /// A shell stand-in that counts spawns and disconnects. Its output
+    /// sender lives in a slot shared with the test; taking it out of the
+    /// slot simulates the child exiting.
+    struct RespawnSpy {
+        spawned: Arc<AtomicUsize>,
+        disconnected: Arc<AtomicUsize>,
+        output_tx: Arc<std::sync::Mutex<Option<tokio::sync::mpsc::Sender<Vec<u8>>>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ConnectionType for RespawnSpy {
+        fn type_id(&self) -> &str {
+            "local"
+        }
+        fn display_name(&self) -> &str {
+            "Local"
+        }
+        fn settings_schema(&self) -> SettingsSchema {
+            SettingsSchema { groups: vec![] }
+        }
+        fn capabilities(&self) -> Capabilities {
+            Capabilities {
+                monitoring: false,
+                file_browser: false,
+                resize: true,
+                persistent: false,
+            }
+        }
+        async fn connect(&mut self, _: serde_json::Value) -> Result<(), SessionError> {
+            self.spawned.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+        async fn disconnect(&mut self) -> Result<(), SessionError> {
+            self.disconnected.fetch_add(1, Ordering::SeqCst);
+            self.output_tx.lock().unwrap().take();
+            Ok(())
+        }
+        fn is_connected(&self) -> bool {
+            self.output_tx.lock().unwrap().is_some()
+        }
+        fn write(&self, _: &[u8]) -> Result<(), SessionError> {
+            Ok(())
+        }
+        fn resize(&self, _: u16, _: u16) -> Result<(), SessionError> {
+            Ok(())
+        }
+        fn subscribe_output(&self) -> OutputReceiver {
+            let (tx, rx) = tokio::sync::mpsc::channel(16);
+            *self.output_tx.lock().unwrap() = Some(tx);
+            rx
+        }
+        fn monitoring(&self) -> Option<&dyn MonitoringProvider> {
+            None
+        }
+        fn file_browser(&self) -> Option<&dyn FileBrowser> {
+            None
+        }
+    }
+
+    struct RespawnFixture {
+        manager: SessionManager,
+        spawned: Arc<AtomicUsize>,
+        disconnected: Arc<AtomicUsize>,
+        output_tx: Arc<std::sync::Mutex<Option<tokio::sync::mpsc::Sender<Vec<u8>>>>>,
+    }
+
+    /// A manager whose `local` type is backed by [`RespawnSpy`].
+    fn respawn_fixture() -> RespawnFixture {
+        let spawned = Arc::new(AtomicUsize::new(0));
+        let disconnected = Arc::new(AtomicUsize::new(0));
+        let output_tx = Arc::new(std::sync::Mutex::new(None));
+        let mut registry = ConnectionTypeRegistry::new();
+        let (s, d, o) = (spawned.clone(), disconnected.clone(), output_tx.clone());
+        registry.register(
+            "local",
+            "Local",
+            "terminal",
+            Box::new(move || {
+                Box::new(RespawnSpy {
+                    spawned: s.clone(),
+                    disconnected: d.clone(),
+                    output_tx: o.clone(),
+                })
+            }),
+        );
+        RespawnFixture {
+            manager: SessionManager::new(registry, Arc::new(NullAgent)),
+            spawned,
+            disconnected,
+            output_tx,
+        }
+    }
+
+    #[tokio::test]
+    async fn restart_session_keeps_id_and_spawns_new_child() {
+        let f = respawn_fixture();
+        let emitter = MockEventEmitter::new();
+        let id = f
+            .manager
+            .create_connection("local", serde_json::json!({}), None, emitter.clone())
+            .await
+            .unwrap();
+        assert_eq!(f.spawned.load(Ordering::SeqCst), 1);
+
+        f.manager
+            .restart_session(&id, true, emitter.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(f.spawned.load(Ordering::SeqCst), 2);
+        assert_eq!(f.disconnected.load(Ordering::SeqCst), 1);
+        let sessions = f.manager.list_sessions().await;
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].id, id);
+        assert!(sessions[0].alive);
+
+        // The new child's output is streamed under the same session ID.
+        let tx = f.output_tx.lock().unwrap().clone().unwrap();
+        tx.send(b"fresh shell".to_vec()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let outputs = emitter.outputs.lock().unwrap();
+        assert_eq!(outputs[0].data, CLEAR_SCROLLBACK);
+        assert_eq!(outputs.last().unwrap().session_id, id);
+        assert_eq!(outputs.last().unwrap().data, b"fresh shell");
+        assert!(
+            emitter.exits.lock().unwrap().is_empty(),
+            "the torn-down child must not end the session"
+        );
+    }
+
+    #[tokio::test]
+    async fn restart_session_after_child_exit_preserving_scrollback() {
+        let f = respawn_fixture();
+        let emitter = MockEventEmitter::new();
+        let id = f
+            .manager
+            .create_connection("local", serde_json::json!({}), None, emitter.clone())
+            .await
+            .unwrap();
+
+        // The shell crashes: its output ends and the session is cleaned up.
+        f.output_tx.lock().unwrap().take();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(emitter.exits.lock().unwrap().len(), 1);
+        assert!(f.manager.list_sessions().await.is_empty());
+
+        f.manager
+            .restart_session(&id, false, emitter.clone())
+            .await
+            .unwrap();
+        assert_eq!(f.spawned.load(Ordering::SeqCst), 2);
+        assert_eq!(f.manager.list_sessions().await[0].id, id);
+        assert!(emitter.outputs.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn restart_session_rejects_unknown_and_non_respawnable() {
+        let f = respawn_fixture();
+        let emitter = MockEventEmitter::new();
+        let spy = DisconnectSpy::new(Arc::new(AtomicBool::new(false)));
+        f.manager.insert_test_session("spy-1", Box::new(spy)).await;
+
+        let result = f
+            .manager
+            .restart_session("spy-1", true, emitter.clone())
+            .await;
+        assert!(matches!(result, Err(TerminalError::SpawnFailed(_))));
+        let result = f.manager.restart_session("missing", true, emitter).await;
+        assert!(matches!(result, Err(TerminalError::SessionNotFound(_))));
+
+        // A closed session cannot be restarted.
+        let id = f
+            .manager
+            .create_connection(
+                "local",
+                serde_json::json!({}),
+                None,
+                MockEventEmitter::new(),
+            )
+            .await
+            .unwrap();
+        f.manager
+            .close_session(&id, CloseTrigger::Explicit)
+            .await
+            .unwrap();
+        let result = f
+            .manager
+            .restart_session(&id, true, MockEventEmitter::new())
+            .await;
+        assert!(matches!(result, Err(TerminalError::SessionNotFound(_))));
+    }
+
+    // ── Cleanup policy ───────────────────────────────────────────────
+
+    /// Open a session on a [`respawn_fixture`] with the given cleanup policy.
+    async fn session_with_policy(f: &RespawnFixture, policy: &str) -> String {
+        f.manager
+            .create_connection(
+                "local",
+                serde_json::json!({ "cleanupPolicy": policy }),
+                None,
+                MockEventEmitter::new(),
+            )
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn cleanup_policy_always_releases_on_tab_close() {
+        let f = respawn_fixture();
+        let id = session_with_policy(&f, "always").await;
+
+        f.manager
+            .close_session(&id, CloseTrigger::TabClosed)
+            .await
+            .unwrap();
+        assert_eq!(f.disconnected.load(Ordering::SeqCst), 1);
+        assert!(f.manager.list_active_sessions().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cleanup_policy_on_explicit_close_keeps_backend_after_tab_close() {
+        let f = respawn_fixture();
+        let id = session_with_policy(&f, "onExplicitClose").await;
+
+        f.manager
+            .close_session(&id, CloseTrigger::TabClosed)
+            .await
+            .unwrap();
+        assert_eq!(f.disconnected.load(Ordering::SeqCst), 0);
+        let listed = f.manager.list_active_sessions().await;
+        assert_eq!(listed.len(), 1);
+        assert!(listed[0].detached);
+
+        f.manager.reattach_session(&id).await.unwrap();
+        assert!(!f.manager.list_active_sessions().await[0].detached);
+
+        f.manager
+            .close_session(&id, CloseTrigger::Explicit)
+            .await
+            .unwrap();
+        assert_eq!(f.disconnected.load(Ordering::SeqCst), 1);
+        assert!(f.manager.list_active_sessions().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cleanup_policy_never_keeps_backend_on_any_close() {
+        let f = respawn_fixture();
+        let id = session_with_policy(&f, "never").await;
+
+        for trigger in [CloseTrigger::TabClosed, CloseTrigger::Explicit] {
+            f.manager.close_session(&id, trigger).await.unwrap();
+        }
+        assert_eq!(f.disconnected.load(Ordering::SeqCst), 0);
+        assert_eq!(f.manager.list_active_sessions().await.len(), 1);
+        assert!(f.manager.list_sessions().await[0].detached);
+    }
+
+    #[tokio::test]
+    async fn kill_releases_backend_under_any_policy() {
+        for policy in ["always", "onExplicitClose", "never"] {
+            let f = respawn_fixture();
+            let id = session_with_policy(&f, policy).await;
+
+            f.manager
+                .close_session(&id, CloseTrigger::TabClosed)
+                .await
+                .unwrap();
+            f.manager
+                .close_session(&id, CloseTrigger::Kill)
+                .await
+                .unwrap();
+            assert_eq!(f.disconnected.load(Ordering::SeqCst), 1, "{policy}");
+            assert!(f.manager.list_sessions().await.is_empty(), "{policy}");
+        }
+    }
+
+    #[tokio::test]
+    async fn reattach_unknown_session_fails() {
+        let f = respawn_fixture();
+        let result = f.manager.reattach_session("missing").await;
+        assert!(matches!(result, Err(TerminalError::SessionNotFound(_))));
+    }
+
     /// Tauri events are consumed by the TypeScript frontend which uses snake_case
     /// property names in the payload interface.  Verify that `SessionMonitoringStatsEvent`
     /// serialises `session_id` as `session_id` (not `sessionId`) so the frontend's
@@ -1208,10 +4038,17 @@ mod tests {
                 memory_total_kb: 0,
                 memory_available_kb: 0,
                 memory_used_percent: 0.0,
+                memory_buffers_kb: 0,
+                memory_cached_kb: 0,
+                swap_total_kb: 0,
+                swap_used_kb: 0,
                 disk_total_kb: 0,
                 disk_used_kb: 0,
                 disk_used_percent: 0.0,
                 os_info: String::new(),
+                network_rx_bytes: 0,
+                network_tx_bytes: 0,
+                process_count: 0,
             },
         };
         let json = serde_json::to_string(&event).unwrap();
@@ -15,11 +15,15 @@ use std::sync::{mpsc, Arc, Mutex};
 // `disconnect(&mut self)`, so mutable access is guaranteed.
 
 use serde_json::Value;
+use tokio_util::sync::CancellationToken;
 use tracing::debug;
 
-use termihub_core::connection::{Capabilities, ConnectionType, OutputReceiver, SettingsSchema};
+use termihub_core::connection::{
+    Capabilities, ConnectionType, LineControl, OutputReceiver, SettingsSchema,
+};
 use termihub_core::errors::{CoreError, FileError, SessionError};
-use termihub_core::files::{FileBrowser, FileEntry};
+use termihub_core::files::dir_size::{walk_dir_size, DIR_SIZE_MAX_ENTRIES};
+use termihub_core::files::{FileBrowser, FileEntry, GrepMatch, GrepOptions};
 use termihub_core::monitoring::{MonitoringProvider, MonitoringReceiver};
 
 use crate::terminal::agent_manager::AgentRpcClient;
@@ -291,6 +295,20 @@ impl ConnectionType for RemoteProxy {
             .map_err(|e| SessionError::Io(std::io::Error::other(e.to_string())))
     }
 
+    fn line_control(&self, control: LineControl) -> Result<(), SessionError> {
+        let remote_sid = self
+            .remote_session_id()
+            .ok_or_else(|| SessionError::NotRunning("Not connected".to_string()))?;
+        self.agent_manager
+            .send_request(
+                self.agent_id(),
+                "connection.line_control",
+                serde_json::json!({ "session_id": remote_sid, "control": control }),
+            )
+            .map(|_| ())
+            .map_err(|e| SessionError::Io(std::io::Error::other(e.to_string())))
+    }
+
     fn subscribe_output(&self) -> OutputReceiver {
         let (tokio_tx, tokio_rx) = tokio::sync::mpsc::channel(OUTPUT_CHANNEL_CAPACITY);
 
@@ -326,6 +344,7 @@ impl ConnectionType for RemoteProxy {
 /// File browser proxy that forwards operations to a remote agent.
 ///
 /// Returned by `ConnectionType::file_browser()` on `RemoteProxy`.
+#[derive(Clone)]
 pub struct RemoteFileBrowserProxy {
     agent_id: String,
     remote_session_id: String,
@@ -402,15 +421,16 @@ impl FileBrowser for RemoteFileBrowserProxy {
         Ok(())
     }
 
-    async fn rename(&self, from: &str, to: &str) -> Result<(), FileError> {
+    async fn rename(&self, from: &str, to: &str, overwrite: bool) -> Result<(), FileError> {
         self.agent_manager
             .send_request(
                 &self.agent_id,
                 "connection.files.rename",
                 serde_json::json!({
                     "connection_id": self.remote_session_id,
-                    "from": from,
-                    "to": to,
+                    "old_path": from,
+                    "new_path": to,
+                    "overwrite": overwrite,
                 }),
             )
             .map_err(|e| FileError::OperationFailed(e.to_string()))?;
@@ -433,7 +453,7 @@ impl FileBrowser for RemoteFileBrowserProxy {
         serde_json::from_value(result).map_err(|e| FileError::OperationFailed(e.to_string()))
     }
 
-    async fn mkdir(&self, path: &str) -> Result<(), FileError> {
+    async fn mkdir(&self, path: &str, recursive: bool) -> Result<(), FileError> {
         self.agent_manager
             .send_request(
                 &self.agent_id,
@@ -441,11 +461,53 @@ impl FileBrowser for RemoteFileBrowserProxy {
                 serde_json::json!({
                     "connection_id": self.remote_session_id,
                     "path": path,
+                    "recursive": recursive,
                 }),
             )
             .map_err(|e| FileError::OperationFailed(e.to_string()))?;
         Ok(())
     }
+    async fn dir_size(&self, path: &str, cancel: &CancellationToken) -> Result<u64, FileError> {
+        let entry = self.stat(path).await?;
+        if !entry.is_directory {
+            return Ok(entry.size);
+        }
+        walk_dir_size(path, cancel, DIR_SIZE_MAX_ENTRIES, |p| async move {
+            self.list_dir(&p).await
+        })
+        .await
+    }
+    async fn grep(
+        &self,
+        root: &str,
+        pattern: &str,
+        opts: &GrepOptions,
+    ) -> Result<Vec<GrepMatch>, FileError> {
+        // Searching on the agent avoids downloading every file in the tree.
+        let result = self
+            .agent_manager
+            .send_request(
+                &self.agent_id,
+                "connection.files.grep",
+                serde_json::json!({
+                    "connection_id": self.remote_session_id,
+                    "path": root,
+                    "pattern": pattern,
+                    "options": opts,
+                }),
+            )
+            .map_err(|e| FileError::OperationFailed(e.to_string()))?;
+
+        let matches = result
+            .get("matches")
+            .cloned()
+            .unwrap_or(Value::Array(vec![]));
+        serde_json::from_value(matches).map_err(|e| FileError::OperationFailed(e.to_string()))
+    }
+
+    fn clone_box(&self) -> Box<dyn FileBrowser> {
+        Box::new(self.clone())
+    }
 }
 
 /// Monitoring proxy that forwards operations to a remote agent.
@@ -510,7 +572,7 @@ fn base64_decode(input: &str) -> Result<Vec<u8>, FileError> {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use std::sync::Mutex;
 
     use serde_json::json;
@@ -532,31 +594,36 @@ mod tests {
     /// `create_session` records calls and returns a canned session.
     /// All other mutating methods succeed silently. `is_connected` returns
     /// `true` once at least one `create_session` call has been recorded.
-    struct MockAgentRpcClient {
-        created_sessions: Mutex<Vec<(String, String, serde_json::Value)>>,
+    /// Also used by the session manager tests as the agent transport.
+    pub(crate) struct MockAgentRpcClient {
+        pub(crate) created_sessions: Mutex<Vec<(String, String, serde_json::Value)>>,
         send_request_result: Option<serde_json::Value>,
         /// Records (method, params) for every send_request call.
         sent_requests: Mutex<Vec<(String, serde_json::Value)>>,
         /// Records remote_session_id for every register_monitoring_output call.
         registered_monitoring_hosts: Mutex<Vec<String>>,
+        /// Records (remote_session_id, data) for every send_session_input call.
+        pub(crate) sent_input: Mutex<Vec<(String, Vec<u8>)>>,
+        /// Output senders kept alive so tests can push remote output.
+        pub(crate) output_senders: Mutex<Vec<OutputSender>>,
     }
 
     impl MockAgentRpcClient {
-        fn new() -> Self {
+        pub(crate) fn new() -> Self {
             Self {
                 created_sessions: Mutex::new(Vec::new()),
                 send_request_result: None,
                 sent_requests: Mutex::new(Vec::new()),
                 registered_monitoring_hosts: Mutex::new(Vec::new()),
+                sent_input: Mutex::new(Vec::new()),
+                output_senders: Mutex::new(Vec::new()),
             }
         }
 
         fn with_capabilities(capabilities_result: serde_json::Value) -> Self {
             Self {
-                created_sessions: Mutex::new(Vec::new()),
                 send_request_result: Some(capabilities_result),
-                sent_requests: Mutex::new(Vec::new()),
-                registered_monitoring_hosts: Mutex::new(Vec::new()),
+                ..Self::new()
             }
         }
     }
@@ -753,8 +820,9 @@ mod tests {
             &self,
             _agent_id: &str,
             _remote_session_id: &str,
-            _output_tx: OutputSender,
+            output_tx: OutputSender,
         ) -> Result<(), TerminalError> {
+            self.output_senders.lock().unwrap().push(output_tx);
             Ok(())
         }
 
@@ -790,9 +858,13 @@ mod tests {
         fn send_session_input(
             &self,
             _agent_id: &str,
-            _remote_session_id: &str,
-            _data: &[u8],
+            remote_session_id: &str,
+            data: &[u8],
         ) -> Result<(), TerminalError> {
+            self.sent_input
+                .lock()
+                .unwrap()
+                .push((remote_session_id.to_string(), data.to_vec()));
             Ok(())
         }
 
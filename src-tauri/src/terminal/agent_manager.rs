@@ -1147,7 +1147,8 @@ fn build_initialize_params(settings: &AgentSettings, external_files: &[&str]) ->
         "client": "termihub-desktop",
         "clientVersion": "0.1.0",
         "agentSettings": settings,
-        "externalConnectionFiles": external_files
+        "externalConnectionFiles": external_files,
+        "features": ["batch", "watch"]
     })
 }
 
@@ -48,6 +48,9 @@ pub struct DynamicForwardConfig {
     pub local_host: String,
     /// Local port for the SOCKS5 proxy.
     pub local_port: u16,
+    /// Whether to accept SOCKS5 UDP ASSOCIATE requests.
+    #[serde(default)]
+    pub enable_udp: bool,
 }
 
 /// A saved tunnel configuration.
@@ -68,6 +71,28 @@ pub struct TunnelConfig {
     /// Whether to reconnect automatically on disconnect.
     #[serde(default)]
     pub reconnect_on_disconnect: bool,
+    /// Reconnect attempts before the tunnel is given up.
+    #[serde(default = "default_reconnect_max_attempts")]
+    pub reconnect_max_attempts: u32,
+    /// Delay before the first reconnect attempt, in seconds. Doubles after
+    /// each failed attempt.
+    #[serde(default = "default_reconnect_initial_backoff_secs")]
+    pub reconnect_initial_backoff_secs: u64,
+    /// Upper bound on the delay between reconnect attempts, in seconds.
+    #[serde(default = "default_reconnect_max_backoff_secs")]
+    pub reconnect_max_backoff_secs: u64,
+}
+
+fn default_reconnect_max_attempts() -> u32 {
+    10
+}
+
+fn default_reconnect_initial_backoff_secs() -> u64 {
+    1
+}
+
+fn default_reconnect_max_backoff_secs() -> u64 {
+    30
 }
 
 /// Current status of a tunnel.
@@ -95,6 +120,21 @@ pub struct TunnelStats {
     pub total_connections: u64,
 }
 
+/// Traffic totals and throughput for a tunnel, as returned by
+/// `get_tunnel_metrics`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TunnelMetrics {
+    /// Tunnel ID these metrics belong to.
+    pub tunnel_id: String,
+    /// Totals since the tunnel was last (re)started.
+    pub stats: TunnelStats,
+    /// Rolling estimate of the send rate in bytes per second.
+    pub bytes_sent_per_sec: f64,
+    /// Rolling estimate of the receive rate in bytes per second.
+    pub bytes_received_per_sec: f64,
+}
+
 /// Combined runtime state for a tunnel.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -144,6 +184,9 @@ mod tests {
             }),
             auto_start: true,
             reconnect_on_disconnect: false,
+            reconnect_max_attempts: 10,
+            reconnect_initial_backoff_secs: 1,
+            reconnect_max_backoff_secs: 30,
         };
         let json = serde_json::to_string(&config).unwrap();
         let deserialized: TunnelConfig = serde_json::from_str(&json).unwrap();
@@ -172,6 +215,9 @@ mod tests {
             }),
             auto_start: false,
             reconnect_on_disconnect: true,
+            reconnect_max_attempts: 10,
+            reconnect_initial_backoff_secs: 1,
+            reconnect_max_backoff_secs: 30,
         };
         let json = serde_json::to_string(&config).unwrap();
         let deserialized: TunnelConfig = serde_json::from_str(&json).unwrap();
@@ -194,19 +240,32 @@ mod tests {
             tunnel_type: TunnelType::Dynamic(DynamicForwardConfig {
                 local_host: "127.0.0.1".to_string(),
                 local_port: 1080,
+                enable_udp: true,
             }),
             auto_start: false,
             reconnect_on_disconnect: false,
+            reconnect_max_attempts: 10,
+            reconnect_initial_backoff_secs: 1,
+            reconnect_max_backoff_secs: 30,
         };
         let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("\"enableUdp\":true"));
         let deserialized: TunnelConfig = serde_json::from_str(&json).unwrap();
         if let TunnelType::Dynamic(dynamic) = &deserialized.tunnel_type {
             assert_eq!(dynamic.local_port, 1080);
+            assert!(dynamic.enable_udp);
         } else {
             panic!("Expected Dynamic tunnel type");
         }
     }
 
+    #[test]
+    fn dynamic_forward_config_udp_defaults_to_disabled() {
+        let json = r#"{"localHost":"127.0.0.1","localPort":1080}"#;
+        let config: DynamicForwardConfig = serde_json::from_str(json).unwrap();
+        assert!(!config.enable_udp);
+    }
+
     #[test]
     fn tunnel_store_serde_round_trip() {
         let store = TunnelStore {
@@ -223,6 +282,9 @@ mod tests {
                 }),
                 auto_start: false,
                 reconnect_on_disconnect: false,
+                reconnect_max_attempts: 10,
+                reconnect_initial_backoff_secs: 1,
+                reconnect_max_backoff_secs: 30,
             }],
         };
         let json = serde_json::to_string_pretty(&store).unwrap();
@@ -258,6 +320,21 @@ mod tests {
         assert_eq!(deserialized.stats.bytes_sent, 1024);
     }
 
+    #[test]
+    fn tunnel_metrics_serializes_camel_case() {
+        let metrics = TunnelMetrics {
+            tunnel_id: "tun-1".to_string(),
+            stats: TunnelStats::default(),
+            bytes_sent_per_sec: 512.0,
+            bytes_received_per_sec: 0.0,
+        };
+        let json = serde_json::to_value(&metrics).unwrap();
+        assert_eq!(json["tunnelId"], "tun-1");
+        assert_eq!(json["bytesSentPerSec"], 512.0);
+        assert_eq!(json["bytesReceivedPerSec"], 0.0);
+        assert_eq!(json["stats"]["bytesSent"], 0);
+    }
+
     #[test]
     fn tunnel_state_error_included_when_set() {
         let state = TunnelState {
@@ -298,6 +375,9 @@ mod tests {
         let config: TunnelConfig = serde_json::from_str(json).unwrap();
         assert!(!config.auto_start);
         assert!(!config.reconnect_on_disconnect);
+        assert_eq!(config.reconnect_max_attempts, 10);
+        assert_eq!(config.reconnect_initial_backoff_secs, 1);
+        assert_eq!(config.reconnect_max_backoff_secs, 30);
     }
 
     #[test]
@@ -314,12 +394,17 @@ mod tests {
             }),
             auto_start: false,
             reconnect_on_disconnect: false,
+            reconnect_max_attempts: 10,
+            reconnect_initial_backoff_secs: 1,
+            reconnect_max_backoff_secs: 30,
         };
         let json: serde_json::Value = serde_json::to_value(&config).unwrap();
         // Check camelCase renaming
         assert!(json.get("sshConnectionId").is_some());
         assert!(json.get("tunnelType").is_some());
         assert!(json.get("autoStart").is_some());
+        assert!(json.get("reconnectMaxAttempts").is_some());
+        assert!(json.get("reconnectInitialBackoffSecs").is_some());
         // Check tagged enum format
         let tunnel_type = json.get("tunnelType").unwrap();
         assert_eq!(tunnel_type.get("type").unwrap(), "local");
@@ -1,20 +1,28 @@
 use std::io::{Read, Write};
-use std::net::TcpListener;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, UdpSocket};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use ssh2::Session;
+use termihub_core::files::utils::shell_quote;
 
-use super::config::{DynamicForwardConfig, TunnelStats};
+use super::config::{DynamicForwardConfig, TunnelMetrics, TunnelStats};
 use super::local_forward::ForwarderStats;
 
 /// Manages a dynamic (SOCKS5) forwarding tunnel.
 ///
 /// Binds a local TCP listener as a SOCKS5 proxy. For each incoming connection,
-/// performs the SOCKS5 handshake (CONNECT only, no auth) and then relays
-/// traffic through an SSH `channel_direct_tcpip`.
+/// performs the SOCKS5 handshake (no auth) and then relays traffic through
+/// an SSH `channel_direct_tcpip`.
+///
+/// When `enable_udp` is set, UDP ASSOCIATE is supported too. SSH has no UDP
+/// channel, so datagrams are relayed through a small perl process on the
+/// server, one per destination. Datagrams travel over the relay channel
+/// with a 2-byte length prefix in both directions, so each one reaches the
+/// destination and the client whole and separate. The association ends
+/// when its controlling TCP connection closes.
 pub struct DynamicForwarder {
     shutdown: Arc<AtomicBool>,
     listener_thread: Option<thread::JoinHandle<()>>,
@@ -25,12 +33,62 @@ pub struct DynamicForwarder {
 const SOCKS5_VERSION: u8 = 0x05;
 const SOCKS5_NO_AUTH: u8 = 0x00;
 const SOCKS5_CMD_CONNECT: u8 = 0x01;
+const SOCKS5_CMD_UDP_ASSOCIATE: u8 = 0x03;
 const SOCKS5_ATYP_IPV4: u8 = 0x01;
 const SOCKS5_ATYP_DOMAIN: u8 = 0x03;
+const SOCKS5_ATYP_IPV6: u8 = 0x04;
 const SOCKS5_REP_SUCCESS: u8 = 0x00;
 const SOCKS5_REP_GENERAL_FAILURE: u8 = 0x01;
 const SOCKS5_REP_CMD_NOT_SUPPORTED: u8 = 0x07;
 
+/// Seconds a UDP relay may go without traffic before it is closed.
+const UDP_RELAY_IDLE_SECS: u64 = 30;
+
+/// Perl program relaying length-prefixed datagrams between its stdin/stdout
+/// and a UDP socket connected to `host port`, exiting once it has been idle
+/// for `idle` seconds. Perl is used because it ships with practically every
+/// Unix server, unlike `socat`, and `nc -u` cannot keep datagrams apart.
+const UDP_RELAY_SCRIPT: &str = r#"use IO::Socket::INET;
+my ($host, $port, $idle) = @ARGV;
+my $s = IO::Socket::INET->new(PeerAddr => $host, PeerPort => $port, Proto => "udp") or die "$@\n";
+binmode STDIN;
+binmode STDOUT;
+$| = 1;
+my ($in, $eof) = ("", 0);
+while (1) {
+    my $r = "";
+    vec($r, fileno($s), 1) = 1;
+    vec($r, fileno(STDIN), 1) = 1 unless $eof;
+    last unless select($r, undef, undef, $idle);
+    if (vec($r, fileno($s), 1) && defined $s->recv(my $d, 65535)) {
+        print pack("n/a*", $d);
+    }
+    if (!$eof && vec($r, fileno(STDIN), 1)) {
+        $eof = !sysread(STDIN, $in, 65536, length $in);
+        while (length($in) >= 2 && length($in) >= 2 + unpack("n", $in)) {
+            my $n = unpack("n", $in);
+            $s->send(substr($in, 2, $n));
+            substr($in, 0, 2 + $n, "");
+        }
+    }
+}
+"#;
+
+/// Most UDP relays a UDP association keeps open at once. Each one holds an
+/// SSH channel, which servers limit per connection (`MaxSessions`, 10 by
+/// default), so the least recently used relay makes room for a new one.
+const MAX_UDP_RELAYS: usize = 4;
+
+/// A relay process on the SSH server sending to one destination.
+struct UdpRelay {
+    host: String,
+    port: u16,
+    channel: ssh2::Channel,
+    /// Reply bytes read from the relay that do not form a whole frame yet.
+    pending: Vec<u8>,
+    last_used: Instant,
+}
+
 impl DynamicForwarder {
     /// Start a dynamic SOCKS5 forwarding tunnel.
     pub fn start(
@@ -43,12 +101,13 @@ impl DynamicForwarder {
 
         let shutdown = Arc::new(AtomicBool::new(false));
         let stats = Arc::new(ForwarderStats::new());
+        let enable_udp = config.enable_udp;
 
         let shutdown_clone = Arc::clone(&shutdown);
         let stats_clone = Arc::clone(&stats);
 
         let listener_thread = thread::spawn(move || {
-            Self::accept_loop(listener, session, shutdown_clone, stats_clone);
+            Self::accept_loop(listener, session, shutdown_clone, stats_clone, enable_udp);
         });
 
         Ok(Self {
@@ -63,6 +122,11 @@ impl DynamicForwarder {
         self.stats.to_tunnel_stats()
     }
 
+    /// Get current tunnel statistics with throughput.
+    pub fn get_metrics(&self, tunnel_id: &str) -> TunnelMetrics {
+        self.stats.to_tunnel_metrics(tunnel_id)
+    }
+
     /// Stop the forwarder.
     pub fn stop(&mut self) {
         self.shutdown.store(true, Ordering::Relaxed);
@@ -76,6 +140,7 @@ impl DynamicForwarder {
         session: Arc<Mutex<Session>>,
         shutdown: Arc<AtomicBool>,
         stats: Arc<ForwarderStats>,
+        enable_udp: bool,
     ) {
         while !shutdown.load(Ordering::Relaxed) {
             match listener.accept() {
@@ -87,7 +152,7 @@ impl DynamicForwarder {
                     let stats = Arc::clone(&stats);
 
                     thread::spawn(move || {
-                        Self::handle_socks5(stream, session, &shutdown, &stats);
+                        Self::handle_socks5(stream, session, &shutdown, &stats, enable_udp);
                         stats.decrement_active();
                     });
                 }
@@ -109,7 +174,8 @@ impl DynamicForwarder {
         mut stream: std::net::TcpStream,
         session: Arc<Mutex<Session>>,
         shutdown: &AtomicBool,
-        stats: &ForwarderStats,
+        stats: &Arc<ForwarderStats>,
+        enable_udp: bool,
     ) {
         // Set blocking for handshake
         if stream.set_nonblocking(false).is_err() {
@@ -154,54 +220,33 @@ impl DynamicForwarder {
         if req[0] != SOCKS5_VERSION {
             return;
         }
-        if req[1] != SOCKS5_CMD_CONNECT {
-            // Only CONNECT is supported
-            let _ = Self::send_reply(&mut stream, SOCKS5_REP_CMD_NOT_SUPPORTED);
-            return;
-        }
+        let udp = match req[1] {
+            SOCKS5_CMD_CONNECT => false,
+            SOCKS5_CMD_UDP_ASSOCIATE if enable_udp => true,
+            _ => {
+                let _ = Self::send_reply(&mut stream, SOCKS5_REP_CMD_NOT_SUPPORTED);
+                return;
+            }
+        };
 
         // Parse destination address
-        let (dest_host, dest_port) = match req[3] {
-            SOCKS5_ATYP_IPV4 => {
-                let mut addr = [0u8; 4];
-                if stream.read_exact(&mut addr).is_err() {
-                    return;
-                }
-                let host = format!("{}.{}.{}.{}", addr[0], addr[1], addr[2], addr[3]);
-                let mut port_buf = [0u8; 2];
-                if stream.read_exact(&mut port_buf).is_err() {
-                    return;
-                }
-                let port = u16::from_be_bytes(port_buf);
-                (host, port)
-            }
-            SOCKS5_ATYP_DOMAIN => {
-                let mut len = [0u8; 1];
-                if stream.read_exact(&mut len).is_err() {
-                    return;
-                }
-                let mut domain = vec![0u8; len[0] as usize];
-                if stream.read_exact(&mut domain).is_err() {
-                    return;
-                }
-                let host = match String::from_utf8(domain) {
-                    Ok(h) => h,
-                    Err(_) => return,
-                };
-                let mut port_buf = [0u8; 2];
-                if stream.read_exact(&mut port_buf).is_err() {
-                    return;
-                }
-                let port = u16::from_be_bytes(port_buf);
-                (host, port)
-            }
-            _ => {
-                // IPv6 and other types not supported
+        let (dest_host, dest_port) = match read_address(&mut stream, req[3]) {
+            Ok(Some(addr)) => addr,
+            Ok(None) => {
+                // Unsupported address type
                 let _ = Self::send_reply(&mut stream, SOCKS5_REP_CMD_NOT_SUPPORTED);
                 return;
             }
+            Err(_) => return,
         };
 
+        if udp {
+            // The address is where the client will send from, which it
+            // often does not know yet; datagrams are matched by IP instead.
+            Self::handle_udp_associate(stream, session, shutdown, stats);
+            return;
+        }
+
         // Open SSH channel to destination
         let mut channel = {
             let sess = match session.lock() {
@@ -292,23 +337,363 @@ impl DynamicForwarder {
         let _ = stream.shutdown(std::net::Shutdown::Both);
     }
 
+    /// Relay a UDP association until its controlling TCP connection closes.
+    ///
+    /// Binds a UDP socket on the address the client reached the proxy on,
+    /// reports it in the reply, then relays the datagrams from the client's
+    /// IP on this thread, reusing one relay per destination.
+    fn handle_udp_associate(
+        mut stream: std::net::TcpStream,
+        session: Arc<Mutex<Session>>,
+        shutdown: &AtomicBool,
+        stats: &Arc<ForwarderStats>,
+    ) {
+        let (client_ip, local_ip) = match (stream.peer_addr(), stream.local_addr()) {
+            (Ok(peer), Ok(local)) => (peer.ip(), local.ip()),
+            _ => return,
+        };
+        let socket = match UdpSocket::bind(SocketAddr::new(local_ip, 0)) {
+            Ok(socket) => socket,
+            Err(e) => {
+                tracing::debug!("SOCKS5 UDP bind failed: {}", e);
+                let _ = Self::send_reply(&mut stream, SOCKS5_REP_GENERAL_FAILURE);
+                return;
+            }
+        };
+        let bound = match socket.local_addr() {
+            Ok(addr) => addr,
+            Err(_) => return,
+        };
+        if socket
+            .set_read_timeout(Some(Duration::from_millis(10)))
+            .is_err()
+        {
+            return;
+        }
+        if stream
+            .write_all(&encode_reply(SOCKS5_REP_SUCCESS, bound))
+            .is_err()
+        {
+            return;
+        }
+        if stream.set_nonblocking(true).is_err() {
+            return;
+        }
+
+        let mut relays: Vec<UdpRelay> = Vec::new();
+        let mut client: Option<SocketAddr> = None;
+        let mut buf = [0u8; 65536];
+        let mut reply_buf = vec![0u8; 65536];
+
+        while !shutdown.load(Ordering::Relaxed) {
+            // The association lives as long as the TCP connection.
+            match stream.read(&mut buf) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(_) => break,
+            }
+
+            match socket.recv_from(&mut buf) {
+                Ok((n, from)) => {
+                    let foreign = from.ip() != client_ip || client.is_some_and(|c| c != from);
+                    if !foreign {
+                        client = Some(from);
+                        if let Some(datagram) = parse_udp_datagram(&buf[..n]) {
+                            stats.add_bytes_sent(datagram.payload.len() as u64);
+                            Self::send_to_relay(&session, &mut relays, &datagram);
+                        }
+                    }
+                }
+                Err(ref e)
+                    if e.kind() == std::io::ErrorKind::WouldBlock
+                        || e.kind() == std::io::ErrorKind::TimedOut => {}
+                Err(_) => break,
+            }
+
+            if let Some(client) = client {
+                Self::pump_udp_relays(
+                    &session,
+                    &mut relays,
+                    &socket,
+                    client,
+                    stats,
+                    &mut reply_buf,
+                );
+            }
+        }
+
+        for mut relay in relays {
+            Self::close_channel(&session, &mut relay.channel);
+        }
+        let _ = stream.shutdown(std::net::Shutdown::Both);
+    }
+
+    /// Write a datagram to the relay for its destination, starting one
+    /// (and closing the least recently used at the cap) if there is none.
+    fn send_to_relay(
+        session: &Mutex<Session>,
+        relays: &mut Vec<UdpRelay>,
+        datagram: &UdpDatagram<'_>,
+    ) {
+        let Some(frame) = frame_datagram(datagram.payload) else {
+            return;
+        };
+        let existing = relays
+            .iter()
+            .position(|r| r.host == datagram.host && r.port == datagram.port);
+        let index = match existing {
+            Some(index) => index,
+            None => {
+                if relays.len() >= MAX_UDP_RELAYS {
+                    if let Some(oldest) = relays
+                        .iter()
+                        .enumerate()
+                        .min_by_key(|(_, r)| r.last_used)
+                        .map(|(i, _)| i)
+                    {
+                        let mut evicted = relays.swap_remove(oldest);
+                        Self::close_channel(session, &mut evicted.channel);
+                    }
+                }
+                let opened = {
+                    let sess = match session.lock() {
+                        Ok(s) => s,
+                        Err(_) => return,
+                    };
+                    sess.set_blocking(true);
+                    let opened = Self::open_udp_relay(&sess, &datagram.host, datagram.port);
+                    sess.set_blocking(false);
+                    opened
+                };
+                match opened {
+                    Ok(channel) => relays.push(UdpRelay {
+                        host: datagram.host.clone(),
+                        port: datagram.port,
+                        channel,
+                        pending: Vec::new(),
+                        last_used: Instant::now(),
+                    }),
+                    Err(e) => {
+                        tracing::debug!(
+                            "SOCKS5 UDP relay to {}:{} failed: {}",
+                            datagram.host,
+                            datagram.port,
+                            e
+                        );
+                        return;
+                    }
+                }
+                relays.len() - 1
+            }
+        };
+
+        let relay = &mut relays[index];
+        relay.last_used = Instant::now();
+        let written = match session.lock() {
+            Ok(sess) => {
+                sess.set_blocking(true);
+                let written = relay.channel.write_all(&frame);
+                sess.set_blocking(false);
+                written
+            }
+            Err(_) => return,
+        };
+        if let Err(e) = written {
+            tracing::debug!(
+                "SOCKS5 UDP relay to {}:{} lost: {}",
+                relay.host,
+                relay.port,
+                e
+            );
+            let mut lost = relays.swap_remove(index);
+            Self::close_channel(session, &mut lost.channel);
+        }
+    }
+
+    /// Send the replies every relay has returned to the client, and close
+    /// relays that exited or went idle for [`UDP_RELAY_IDLE_SECS`].
+    fn pump_udp_relays(
+        session: &Mutex<Session>,
+        relays: &mut Vec<UdpRelay>,
+        socket: &UdpSocket,
+        client: SocketAddr,
+        stats: &ForwarderStats,
+        buf: &mut [u8],
+    ) {
+        let idle = Duration::from_secs(UDP_RELAY_IDLE_SECS);
+        relays.retain_mut(|relay| {
+            loop {
+                let read = match session.lock() {
+                    Ok(_sess) => relay.channel.read(buf),
+                    Err(_) => return false,
+                };
+                match read {
+                    Ok(0) => return false,
+                    Ok(n) => {
+                        relay.last_used = Instant::now();
+                        relay.pending.extend_from_slice(&buf[..n]);
+                        while let Some(payload) = take_frame(&mut relay.pending) {
+                            stats.add_bytes_received(payload.len() as u64);
+                            let packet = encode_udp_datagram(&relay.host, relay.port, &payload);
+                            let _ = socket.send_to(&packet, client);
+                        }
+                    }
+                    Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                    Err(_) => return false,
+                }
+            }
+            if relay.last_used.elapsed() < idle {
+                return true;
+            }
+            Self::close_channel(session, &mut relay.channel);
+            false
+        });
+    }
+
+    /// Start the relay to `host:port` on the SSH server.
+    fn open_udp_relay(sess: &Session, host: &str, port: u16) -> std::io::Result<ssh2::Channel> {
+        let mut channel = sess.channel_session()?;
+        channel.exec(&udp_relay_command(host, port))?;
+        Ok(channel)
+    }
+
+    /// Close a relay channel; the relay exits once its stdin is closed and
+    /// it has been idle for [`UDP_RELAY_IDLE_SECS`].
+    fn close_channel(session: &Mutex<Session>, channel: &mut ssh2::Channel) {
+        if let Ok(_sess) = session.lock() {
+            let _ = channel.send_eof();
+            let _ = channel.close();
+        }
+    }
+
     /// Send a SOCKS5 reply with the given status code.
     fn send_reply(stream: &mut std::net::TcpStream, rep: u8) -> std::io::Result<()> {
-        // Reply: VER REP RSV ATYP BND.ADDR BND.PORT
-        let reply = [
-            SOCKS5_VERSION,
-            rep,
-            0x00, // RSV
-            SOCKS5_ATYP_IPV4,
-            0,
-            0,
-            0,
-            0, // BND.ADDR (0.0.0.0)
-            0,
-            0, // BND.PORT (0)
-        ];
-        stream.write_all(&reply)
+        let unspecified = SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0);
+        stream.write_all(&encode_reply(rep, unspecified))
+    }
+}
+
+/// Read a SOCKS5 `ATYP`-tagged address and port.
+///
+/// Returns `Ok(None)` for address types other than IPv4 and domain names.
+fn read_address(reader: &mut impl Read, atyp: u8) -> std::io::Result<Option<(String, u16)>> {
+    let host = match atyp {
+        SOCKS5_ATYP_IPV4 => {
+            let mut addr = [0u8; 4];
+            reader.read_exact(&mut addr)?;
+            Ipv4Addr::from(addr).to_string()
+        }
+        SOCKS5_ATYP_DOMAIN => {
+            let mut len = [0u8; 1];
+            reader.read_exact(&mut len)?;
+            let mut domain = vec![0u8; len[0] as usize];
+            reader.read_exact(&mut domain)?;
+            match String::from_utf8(domain) {
+                Ok(h) => h,
+                Err(_) => return Ok(None),
+            }
+        }
+        _ => return Ok(None),
+    };
+    let mut port_buf = [0u8; 2];
+    reader.read_exact(&mut port_buf)?;
+    Ok(Some((host, u16::from_be_bytes(port_buf))))
+}
+
+/// Encode a SOCKS5 reply: VER REP RSV ATYP BND.ADDR BND.PORT.
+fn encode_reply(rep: u8, bound: SocketAddr) -> Vec<u8> {
+    let mut reply = vec![SOCKS5_VERSION, rep, 0x00];
+    match bound.ip() {
+        IpAddr::V4(ip) => {
+            reply.push(SOCKS5_ATYP_IPV4);
+            reply.extend_from_slice(&ip.octets());
+        }
+        IpAddr::V6(ip) => {
+            reply.push(SOCKS5_ATYP_IPV6);
+            reply.extend_from_slice(&ip.octets());
+        }
+    }
+    reply.extend_from_slice(&bound.port().to_be_bytes());
+    reply
+}
+
+/// A datagram from a SOCKS5 client, with its UDP request header parsed.
+#[derive(Debug, PartialEq)]
+struct UdpDatagram<'a> {
+    host: String,
+    port: u16,
+    payload: &'a [u8],
+}
+
+/// Parse a client datagram: RSV(2) FRAG ATYP DST.ADDR DST.PORT DATA.
+///
+/// Returns `None` for malformed datagrams, unsupported address types and
+/// fragments, which are dropped as RFC 1928 allows.
+fn parse_udp_datagram(packet: &[u8]) -> Option<UdpDatagram<'_>> {
+    if packet.len() < 4 || packet[0] != 0 || packet[1] != 0 || packet[2] != 0 {
+        return None;
+    }
+    let mut rest = &packet[4..];
+    let (host, port) = read_address(&mut rest, packet[3]).ok()??;
+    Some(UdpDatagram {
+        host,
+        port,
+        payload: rest,
+    })
+}
+
+/// Encode a reply datagram from `host:port` for the client.
+fn encode_udp_datagram(host: &str, port: u16, payload: &[u8]) -> Vec<u8> {
+    let mut packet = vec![0x00, 0x00, 0x00];
+    match host.parse::<Ipv4Addr>() {
+        Ok(ip) => {
+            packet.push(SOCKS5_ATYP_IPV4);
+            packet.extend_from_slice(&ip.octets());
+        }
+        Err(_) => {
+            // Domain names longer than 255 bytes cannot come from a request.
+            packet.push(SOCKS5_ATYP_DOMAIN);
+            packet.push(host.len() as u8);
+            packet.extend_from_slice(host.as_bytes());
+        }
     }
+    packet.extend_from_slice(&port.to_be_bytes());
+    packet.extend_from_slice(payload);
+    packet
+}
+
+/// Frame a datagram for the relay channel: its length as a big-endian
+/// `u16`, then the payload. Returns `None` for payloads too long to frame,
+/// which no UDP datagram can carry anyway.
+fn frame_datagram(payload: &[u8]) -> Option<Vec<u8>> {
+    let len = u16::try_from(payload.len()).ok()?;
+    let mut frame = Vec::with_capacity(2 + payload.len());
+    frame.extend_from_slice(&len.to_be_bytes());
+    frame.extend_from_slice(payload);
+    Some(frame)
+}
+
+/// Remove the first whole frame from `pending` and return its payload, or
+/// `None` while the frame is still incomplete.
+fn take_frame(pending: &mut Vec<u8>) -> Option<Vec<u8>> {
+    let header = pending.get(..2)?;
+    let len = u16::from_be_bytes([header[0], header[1]]) as usize;
+    if pending.len() < 2 + len {
+        return None;
+    }
+    let payload = pending[2..2 + len].to_vec();
+    pending.drain(..2 + len);
+    Some(payload)
+}
+
+/// Remote command that runs [`UDP_RELAY_SCRIPT`] for `host:port`.
+fn udp_relay_command(host: &str, port: u16) -> String {
+    format!(
+        "perl -e {} {} {port} {UDP_RELAY_IDLE_SECS}",
+        shell_quote(UDP_RELAY_SCRIPT),
+        shell_quote(host)
+    )
 }
 
 impl Drop for DynamicForwarder {
@@ -316,3 +701,192 @@ impl Drop for DynamicForwarder {
         self.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{
+        connect_local, free_port, tunnel_target_session, UDP_ECHO_PORT,
+    };
+    use super::*;
+    use std::net::Ipv6Addr;
+
+    #[test]
+    fn udp_associate_relays_datagrams_through_ssh() {
+        let Some(session) = tunnel_target_session() else {
+            return;
+        };
+        let config = DynamicForwardConfig {
+            local_host: "127.0.0.1".to_string(),
+            local_port: free_port(),
+            enable_udp: true,
+        };
+        let mut forwarder = DynamicForwarder::start(&config, session).unwrap();
+
+        let mut control = connect_local(config.local_port);
+        control
+            .write_all(&[SOCKS5_VERSION, 1, SOCKS5_NO_AUTH])
+            .unwrap();
+        let mut greeting = [0u8; 2];
+        control.read_exact(&mut greeting).unwrap();
+        assert_eq!(greeting, [SOCKS5_VERSION, SOCKS5_NO_AUTH]);
+
+        // The client does not know its sending address yet: 0.0.0.0:0.
+        control
+            .write_all(&[
+                SOCKS5_VERSION,
+                SOCKS5_CMD_UDP_ASSOCIATE,
+                0,
+                SOCKS5_ATYP_IPV4,
+                0,
+                0,
+                0,
+                0,
+                0,
+                0,
+            ])
+            .unwrap();
+        let mut reply = [0u8; 10];
+        control.read_exact(&mut reply).unwrap();
+        assert_eq!(reply[1], SOCKS5_REP_SUCCESS);
+        let relay = SocketAddr::new(
+            IpAddr::V4(Ipv4Addr::new(reply[4], reply[5], reply[6], reply[7])),
+            u16::from_be_bytes([reply[8], reply[9]]),
+        );
+
+        let client = UdpSocket::bind("127.0.0.1:0").unwrap();
+        client
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        let mut buf = [0u8; 1024];
+        // Both datagrams go through the same relay.
+        for payload in [&b"ping"[..], &b"pong"[..]] {
+            let packet = encode_udp_datagram("127.0.0.1", UDP_ECHO_PORT, payload);
+            client.send_to(&packet, relay).unwrap();
+            let (n, _) = client.recv_from(&mut buf).unwrap();
+            assert_eq!(
+                parse_udp_datagram(&buf[..n]),
+                Some(UdpDatagram {
+                    host: "127.0.0.1".to_string(),
+                    port: UDP_ECHO_PORT,
+                    payload,
+                })
+            );
+        }
+
+        // Back-to-back datagrams come back as separate datagrams.
+        for payload in [&b"one"[..], &b"two"[..]] {
+            let packet = encode_udp_datagram("127.0.0.1", UDP_ECHO_PORT, payload);
+            client.send_to(&packet, relay).unwrap();
+        }
+        for payload in [&b"one"[..], &b"two"[..]] {
+            let (n, _) = client.recv_from(&mut buf).unwrap();
+            assert_eq!(parse_udp_datagram(&buf[..n]).unwrap().payload, payload);
+        }
+
+        let stats = forwarder.get_stats();
+        assert_eq!(stats.bytes_sent, 14);
+        assert_eq!(stats.bytes_received, 14);
+        drop(control);
+        forwarder.stop();
+    }
+
+    #[test]
+    fn parse_udp_datagram_ipv4() {
+        let packet = [0, 0, 0, 1, 10, 0, 0, 53, 0, 53, b'h', b'i'];
+        assert_eq!(
+            parse_udp_datagram(&packet),
+            Some(UdpDatagram {
+                host: "10.0.0.53".to_string(),
+                port: 53,
+                payload: b"hi",
+            })
+        );
+    }
+
+    #[test]
+    fn parse_udp_datagram_domain() {
+        let mut packet = vec![0, 0, 0, 3, 11];
+        packet.extend_from_slice(b"example.com");
+        packet.extend_from_slice(&9091u16.to_be_bytes());
+        packet.extend_from_slice(b"ping");
+        let datagram = parse_udp_datagram(&packet).unwrap();
+        assert_eq!(datagram.host, "example.com");
+        assert_eq!(datagram.port, 9091);
+        assert_eq!(datagram.payload, b"ping");
+    }
+
+    #[test]
+    fn parse_udp_datagram_rejects_fragments_and_bad_headers() {
+        // FRAG != 0
+        assert_eq!(parse_udp_datagram(&[0, 0, 1, 1, 127, 0, 0, 1, 0, 7]), None);
+        // RSV != 0
+        assert_eq!(parse_udp_datagram(&[1, 0, 0, 1, 127, 0, 0, 1, 0, 7]), None);
+        // IPv6 destinations are not supported
+        assert_eq!(parse_udp_datagram(&[0, 0, 0, 4, 0, 0]), None);
+        // Truncated address
+        assert_eq!(parse_udp_datagram(&[0, 0, 0, 1, 127, 0]), None);
+        assert_eq!(parse_udp_datagram(&[0, 0]), None);
+    }
+
+    #[test]
+    fn encode_udp_datagram_round_trips() {
+        for host in ["127.0.0.1", "dns.example"] {
+            let packet = encode_udp_datagram(host, 53, b"answer");
+            let datagram = parse_udp_datagram(&packet).unwrap();
+            assert_eq!(datagram.host, host);
+            assert_eq!(datagram.port, 53);
+            assert_eq!(datagram.payload, b"answer");
+        }
+        assert_eq!(
+            encode_udp_datagram("127.0.0.1", 9091, b"x"),
+            [0, 0, 0, 1, 127, 0, 0, 1, 0x23, 0x83, b'x']
+        );
+    }
+
+    #[test]
+    fn encode_reply_reports_bound_address() {
+        let v4 = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 40000);
+        assert_eq!(
+            encode_reply(SOCKS5_REP_SUCCESS, v4),
+            [5, 0, 0, 1, 127, 0, 0, 1, 0x9c, 0x40]
+        );
+
+        let v6 = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 1080);
+        let reply = encode_reply(SOCKS5_REP_SUCCESS, v6);
+        assert_eq!(reply.len(), 4 + 16 + 2);
+        assert_eq!(reply[3], SOCKS5_ATYP_IPV6);
+        assert_eq!(&reply[20..], &1080u16.to_be_bytes());
+    }
+
+    #[test]
+    fn frames_keep_back_to_back_datagrams_apart() {
+        let mut pending = frame_datagram(b"one").unwrap();
+        pending.extend(frame_datagram(b"").unwrap());
+        pending.extend(frame_datagram(b"three").unwrap());
+        // Half of a fourth frame is still in flight.
+        pending.extend_from_slice(&frame_datagram(b"four").unwrap()[..3]);
+
+        assert_eq!(take_frame(&mut pending).as_deref(), Some(&b"one"[..]));
+        assert_eq!(take_frame(&mut pending).as_deref(), Some(&b""[..]));
+        assert_eq!(take_frame(&mut pending).as_deref(), Some(&b"three"[..]));
+        assert_eq!(take_frame(&mut pending), None);
+        assert_eq!(pending, [0, 4, b'f']);
+
+        pending.extend_from_slice(b"our");
+        assert_eq!(take_frame(&mut pending).as_deref(), Some(&b"four"[..]));
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn frame_datagram_rejects_oversized_payloads() {
+        assert_eq!(frame_datagram(b"hi").unwrap(), [0, 2, b'h', b'i']);
+        assert!(frame_datagram(&vec![0; 65536]).is_none());
+    }
+
+    #[test]
+    fn udp_relay_command_quotes_script_and_host() {
+        let command = udp_relay_command("a'b", 53);
+        assert!(command.starts_with("perl -e 'use IO::Socket::INET;"));
+        assert!(command.ends_with(" 'a'\\''b' 53 30"), "{command}");
+    }
+}
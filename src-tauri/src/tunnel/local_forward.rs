@@ -1,13 +1,17 @@
+use std::collections::VecDeque;
 use std::io::{Read, Write};
 use std::net::TcpListener;
 use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use ssh2::Session;
 
-use super::config::{LocalForwardConfig, TunnelStats};
+use super::config::{LocalForwardConfig, TunnelMetrics, TunnelStats};
+
+/// Time span the throughput estimate averages over.
+const THROUGHPUT_WINDOW: Duration = Duration::from_secs(10);
 
 /// Manages a local port forwarding tunnel.
 ///
@@ -20,11 +24,16 @@ pub struct LocalForwarder {
 }
 
 /// Shared atomic counters for tracking tunnel statistics.
+///
+/// Each forwarder creates its own, so the counters start from zero whenever
+/// a tunnel is (re)started.
 pub struct ForwarderStats {
     bytes_sent: AtomicU64,
     bytes_received: AtomicU64,
     active_connections: AtomicU32,
     total_connections: AtomicU64,
+    /// Only touched when metrics are read, never on the relay path.
+    throughput: Mutex<ThroughputMeter>,
 }
 
 impl ForwarderStats {
@@ -34,6 +43,24 @@ impl ForwarderStats {
             bytes_received: AtomicU64::new(0),
             active_connections: AtomicU32::new(0),
             total_connections: AtomicU64::new(0),
+            throughput: Mutex::new(ThroughputMeter::new(Instant::now())),
+        }
+    }
+
+    /// Current totals plus the throughput since the oldest sample in the
+    /// last [`THROUGHPUT_WINDOW`].
+    pub fn to_tunnel_metrics(&self, tunnel_id: &str) -> TunnelMetrics {
+        let stats = self.to_tunnel_stats();
+        let (bytes_sent_per_sec, bytes_received_per_sec) = self
+            .throughput
+            .lock()
+            .map(|mut meter| meter.record(Instant::now(), stats.bytes_sent, stats.bytes_received))
+            .unwrap_or((0.0, 0.0));
+        TunnelMetrics {
+            tunnel_id: tunnel_id.to_string(),
+            stats,
+            bytes_sent_per_sec,
+            bytes_received_per_sec,
         }
     }
 
@@ -64,6 +91,40 @@ impl ForwarderStats {
     }
 }
 
+/// Rolling byte-rate estimate built from the counter values seen at each
+/// metrics read.
+struct ThroughputMeter {
+    /// `(time, bytes_sent, bytes_received)`, oldest first.
+    samples: VecDeque<(Instant, u64, u64)>,
+}
+
+impl ThroughputMeter {
+    fn new(start: Instant) -> Self {
+        Self {
+            samples: VecDeque::from([(start, 0, 0)]),
+        }
+    }
+
+    /// Add a sample and return the send and receive rates in bytes per
+    /// second since the newest sample at least [`THROUGHPUT_WINDOW`] old, or
+    /// since the oldest one if none is that old yet.
+    fn record(&mut self, now: Instant, sent: u64, received: u64) -> (f64, f64) {
+        self.samples.push_back((now, sent, received));
+        while self.samples.len() > 2 && now.duration_since(self.samples[1].0) >= THROUGHPUT_WINDOW {
+            self.samples.pop_front();
+        }
+        let (since, sent_then, received_then) = self.samples[0];
+        let elapsed = now.duration_since(since).as_secs_f64();
+        if elapsed <= 0.0 {
+            return (0.0, 0.0);
+        }
+        (
+            sent.saturating_sub(sent_then) as f64 / elapsed,
+            received.saturating_sub(received_then) as f64 / elapsed,
+        )
+    }
+}
+
 impl LocalForwarder {
     /// Start a local port forwarding tunnel.
     ///
@@ -109,6 +170,11 @@ impl LocalForwarder {
         self.stats.to_tunnel_stats()
     }
 
+    /// Get current tunnel statistics with throughput.
+    pub fn get_metrics(&self, tunnel_id: &str) -> TunnelMetrics {
+        self.stats.to_tunnel_metrics(tunnel_id)
+    }
+
     /// Stop the forwarder and wait for the listener thread to finish.
     pub fn stop(&mut self) {
         self.shutdown.store(true, Ordering::Relaxed);
@@ -272,3 +338,120 @@ impl Drop for LocalForwarder {
         self.stop();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::test_support::{
+        connect_local, free_port, tunnel_target_session, TCP_ECHO_PORT,
+    };
+    use super::*;
+
+    #[test]
+    fn relays_bytes_through_ssh_and_counts_them() {
+        let Some(session) = tunnel_target_session() else {
+            return;
+        };
+        let config = LocalForwardConfig {
+            local_host: "127.0.0.1".to_string(),
+            local_port: free_port(),
+            remote_host: "127.0.0.1".to_string(),
+            remote_port: TCP_ECHO_PORT,
+        };
+        let mut forwarder = LocalForwarder::start(&config, session).unwrap();
+
+        let mut stream = connect_local(config.local_port);
+        stream
+            .set_read_timeout(Some(Duration::from_secs(10)))
+            .unwrap();
+        let payload = b"hello through the tunnel";
+        stream.write_all(payload).unwrap();
+        let mut echoed = vec![0u8; payload.len()];
+        stream.read_exact(&mut echoed).unwrap();
+        assert_eq!(echoed, payload);
+
+        // The relay counts received bytes after handing them to the client.
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let mut stats = forwarder.get_stats();
+        while stats.bytes_received < payload.len() as u64 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+            stats = forwarder.get_stats();
+        }
+        assert_eq!(stats.bytes_sent, payload.len() as u64);
+        assert_eq!(stats.bytes_received, payload.len() as u64);
+        assert_eq!(stats.active_connections, 1);
+        assert_eq!(stats.total_connections, 1);
+
+        drop(stream);
+        forwarder.stop();
+    }
+
+    #[test]
+    fn counters_match_bytes_relayed_across_connections() {
+        let stats = Arc::new(ForwarderStats::new());
+        let chunk = [0u8; 8192];
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let stats = Arc::clone(&stats);
+                thread::spawn(move || {
+                    stats.increment_active();
+                    for _ in 0..16 {
+                        stats.add_bytes_sent(chunk.len() as u64);
+                        stats.add_bytes_received(100);
+                    }
+                    stats.decrement_active();
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let metrics = stats.to_tunnel_metrics("tun-1");
+        assert_eq!(metrics.tunnel_id, "tun-1");
+        assert_eq!(metrics.stats.bytes_sent, 4 * 16 * 8192);
+        assert_eq!(metrics.stats.bytes_received, 4 * 16 * 100);
+        assert_eq!(metrics.stats.active_connections, 0);
+        assert_eq!(metrics.stats.total_connections, 4);
+    }
+
+    #[test]
+    fn new_stats_start_at_zero() {
+        let stats = ForwarderStats::new().to_tunnel_stats();
+        assert_eq!(stats.bytes_sent, 0);
+        assert_eq!(stats.bytes_received, 0);
+        assert_eq!(stats.active_connections, 0);
+        assert_eq!(stats.total_connections, 0);
+    }
+
+    #[test]
+    fn throughput_averages_since_start() {
+        let start = Instant::now();
+        let mut meter = ThroughputMeter::new(start);
+        let rates = meter.record(start + Duration::from_secs(2), 4000, 1000);
+        assert_eq!(rates, (2000.0, 500.0));
+        let rates = meter.record(start + Duration::from_secs(4), 4000, 3000);
+        assert_eq!(rates, (1000.0, 750.0));
+    }
+
+    #[test]
+    fn throughput_forgets_samples_outside_window() {
+        let start = Instant::now();
+        let mut meter = ThroughputMeter::new(start);
+        meter.record(start + Duration::from_secs(5), 50_000, 0);
+        meter.record(start + Duration::from_secs(20), 50_000, 0);
+        // Idle for the whole window after the burst.
+        let rates = meter.record(start + Duration::from_secs(30), 50_000, 0);
+        assert_eq!(rates, (0.0, 0.0));
+        assert_eq!(meter.samples.len(), 2);
+
+        let rates = meter.record(start + Duration::from_secs(35), 60_000, 5_000);
+        assert_eq!(rates, (10_000.0 / 15.0, 5_000.0 / 15.0));
+    }
+
+    #[test]
+    fn throughput_is_zero_without_elapsed_time() {
+        let start = Instant::now();
+        let mut meter = ThroughputMeter::new(start);
+        assert_eq!(meter.record(start, 100, 100), (0.0, 0.0));
+    }
+}
@@ -4,4 +4,7 @@ pub mod local_forward;
 pub mod remote_forward;
 pub mod session_pool;
 pub mod storage;
+pub mod supervisor;
+#[cfg(test)]
+mod test_support;
 pub mod tunnel_manager;
@@ -4,7 +4,7 @@ use std::thread;
 
 use ssh2::Session;
 
-use super::config::{RemoteForwardConfig, TunnelStats};
+use super::config::{RemoteForwardConfig, TunnelMetrics, TunnelStats};
 use super::local_forward::ForwarderStats;
 
 /// Manages a remote port forwarding tunnel.
@@ -57,6 +57,11 @@ impl RemoteForwarder {
         self.stats.to_tunnel_stats()
     }
 
+    /// Get current tunnel statistics with throughput.
+    pub fn get_metrics(&self, tunnel_id: &str) -> TunnelMetrics {
+        self.stats.to_tunnel_metrics(tunnel_id)
+    }
+
     /// Stop the forwarder and wait for the thread to finish.
     pub fn stop(&mut self) {
         self.shutdown
@@ -7,6 +7,19 @@ use crate::terminal::backend::SshConfig;
 use crate::utils::errors::TerminalError;
 use crate::utils::ssh_auth::connect_and_authenticate;
 
+/// How long the server may take to answer a liveness probe, in ms.
+const PROBE_TIMEOUT_MS: u32 = 10_000;
+
+/// libssh2 errors meaning a probe got no answer: the socket failed or
+/// the timeout passed. Any other error is the server refusing the probe,
+/// which is still an answer.
+const NO_ANSWER_ERRORS: [i32; 4] = [
+    -7,  // LIBSSH2_ERROR_SOCKET_SEND
+    -9,  // LIBSSH2_ERROR_TIMEOUT
+    -13, // LIBSSH2_ERROR_SOCKET_DISCONNECT
+    -43, // LIBSSH2_ERROR_SOCKET_RECV
+];
+
 /// A pooled SSH session with a reference count.
 struct PooledSession {
     session: Arc<Mutex<Session>>,
@@ -60,31 +73,137 @@ impl SshSessionPool {
     /// Release a reference to a pooled session.
     ///
     /// When the reference count reaches zero, the session is dropped
-    /// and the SSH connection is closed.
-    pub fn release(&mut self, connection_id: &str) {
-        if let Some(pooled) = self.sessions.get_mut(connection_id) {
+    /// and the SSH connection is closed. A reference to a session that was
+    /// since [invalidated](Self::invalidate) is already gone.
+    pub fn release(&mut self, connection_id: &str, session: &Arc<Mutex<Session>>) {
+        if let Some(pooled) = self
+            .sessions
+            .get_mut(connection_id)
+            .filter(|pooled| Arc::ptr_eq(&pooled.session, session))
+        {
             pooled.ref_count = pooled.ref_count.saturating_sub(1);
             if pooled.ref_count == 0 {
                 self.sessions.remove(connection_id);
             }
         }
     }
+
+    /// Drop a session whose connection has died, whatever its reference
+    /// count, so the next [`get_or_create`](Self::get_or_create) connects
+    /// afresh.
+    ///
+    /// Does nothing if the pool already holds a different session for the
+    /// connection, e.g. one another tunnel has reconnected meanwhile.
+    pub fn invalidate(&mut self, connection_id: &str, session: &Arc<Mutex<Session>>) {
+        if self
+            .sessions
+            .get(connection_id)
+            .is_some_and(|pooled| Arc::ptr_eq(&pooled.session, session))
+        {
+            self.sessions.remove(connection_id);
+        }
+    }
+}
+
+/// Whether `session`'s connection still works, probed by opening a
+/// channel: the server has to answer the request, if only to refuse it
+/// (e.g. at its session limit). A connection that does not answer within
+/// [`PROBE_TIMEOUT_MS`] counts as dead.
+///
+/// The forwarders are held off while the probe runs, as it switches the
+/// session to blocking mode.
+pub fn is_session_alive(session: &Mutex<Session>) -> bool {
+    let Ok(sess) = session.lock() else {
+        return false;
+    };
+    let was_blocking = sess.is_blocking();
+    let previous_timeout = sess.timeout();
+    sess.set_blocking(true);
+    sess.set_timeout(PROBE_TIMEOUT_MS);
+    let answered = match sess.channel_session() {
+        Ok(mut channel) => {
+            let _ = channel.close();
+            true
+        }
+        Err(e) => !matches!(
+            e.code(),
+            ssh2::ErrorCode::Session(code) if NO_ANSWER_ERRORS.contains(&code)
+        ),
+    };
+    sess.set_timeout(previous_timeout);
+    sess.set_blocking(was_blocking);
+    answered
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn live_session_answers_probe() {
+        let Some(session) = super::super::test_support::tunnel_target_session() else {
+            return;
+        };
+        // Forwarders keep the session non-blocking; the probe restores that.
+        session.lock().unwrap().set_blocking(false);
+        assert!(is_session_alive(&session));
+        assert!(!session.lock().unwrap().is_blocking());
+        assert!(is_session_alive(&session));
+    }
+
     #[test]
     fn pool_new_is_empty() {
         let pool = SshSessionPool::new();
         assert!(pool.sessions.is_empty());
     }
 
+    fn pooled(session: &Arc<Mutex<Session>>) -> PooledSession {
+        PooledSession {
+            session: Arc::clone(session),
+            ref_count: 2,
+        }
+    }
+
+    #[test]
+    fn invalidate_drops_dead_session_regardless_of_refs() {
+        let mut pool = SshSessionPool::new();
+        let session = Arc::new(Mutex::new(Session::new().unwrap()));
+        pool.sessions.insert("conn".to_string(), pooled(&session));
+
+        pool.invalidate("conn", &session);
+
+        assert!(pool.sessions.is_empty());
+    }
+
+    #[test]
+    fn release_ignores_replaced_session() {
+        let mut pool = SshSessionPool::new();
+        let dead = Arc::new(Mutex::new(Session::new().unwrap()));
+        let fresh = Arc::new(Mutex::new(Session::new().unwrap()));
+        pool.sessions.insert("conn".to_string(), pooled(&fresh));
+
+        pool.release("conn", &dead);
+
+        assert_eq!(pool.sessions["conn"].ref_count, 2);
+    }
+
+    #[test]
+    fn invalidate_keeps_replacement_session() {
+        let mut pool = SshSessionPool::new();
+        let dead = Arc::new(Mutex::new(Session::new().unwrap()));
+        let fresh = Arc::new(Mutex::new(Session::new().unwrap()));
+        pool.sessions.insert("conn".to_string(), pooled(&fresh));
+
+        pool.invalidate("conn", &dead);
+
+        assert!(pool.sessions.contains_key("conn"));
+    }
+
     #[test]
     fn release_nonexistent_connection_is_noop() {
         let mut pool = SshSessionPool::new();
-        pool.release("nonexistent");
+        let session = Arc::new(Mutex::new(Session::new().unwrap()));
+        pool.release("nonexistent", &session);
         assert!(pool.sessions.is_empty());
     }
 }
@@ -0,0 +1,280 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use super::config::{TunnelConfig, TunnelStatus};
+
+/// How often an established tunnel's SSH connection is checked.
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often a wait checks whether the tunnel was stopped.
+const CANCEL_POLL: Duration = Duration::from_millis(100);
+
+/// Retry limits for re-establishing a dropped tunnel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    /// Attempts before giving up.
+    pub max_attempts: u32,
+    /// Delay before the first attempt; doubles after each failure.
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between attempts.
+    pub max_backoff: Duration,
+}
+
+impl ReconnectPolicy {
+    /// Policy from a tunnel's reconnect settings.
+    pub fn from_config(config: &TunnelConfig) -> Self {
+        Self {
+            max_attempts: config.reconnect_max_attempts,
+            initial_backoff: Duration::from_secs(config.reconnect_initial_backoff_secs),
+            max_backoff: Duration::from_secs(config.reconnect_max_backoff_secs),
+        }
+    }
+
+    /// Delay before attempt `attempt` (zero-based).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// The SSH connection behind a running tunnel, as seen by [`supervise`].
+///
+/// Implemented by the tunnel manager; tests drive the supervisor with a
+/// scripted mock.
+pub trait TunnelLink {
+    /// Whether the tunnel's SSH connection is still up.
+    fn is_alive(&mut self) -> bool;
+    /// Tear down the forwarder and the dead connection.
+    fn disconnect(&mut self);
+    /// Establish a new connection and restart the forwarder.
+    fn connect(&mut self) -> Result<(), String>;
+}
+
+/// Why [`supervise`] returned.
+#[derive(Debug, PartialEq)]
+pub enum SupervisorExit {
+    /// The tunnel was stopped.
+    Stopped,
+    /// Every reconnect attempt failed.
+    GaveUp,
+}
+
+/// Watch `link` and re-establish it with backoff when its connection drops.
+///
+/// `wait` sleeps for the given delay and returns `false` if the tunnel was
+/// stopped meanwhile, which ends supervision. `emit` reports status changes:
+/// [`TunnelStatus::Reconnecting`] when a drop is detected,
+/// [`TunnelStatus::Connected`] once re-established and
+/// [`TunnelStatus::Error`] on giving up.
+pub fn supervise(
+    link: &mut dyn TunnelLink,
+    policy: &ReconnectPolicy,
+    mut wait: impl FnMut(Duration) -> bool,
+    mut emit: impl FnMut(TunnelStatus, Option<String>),
+) -> SupervisorExit {
+    loop {
+        if !wait(HEALTH_CHECK_INTERVAL) {
+            return SupervisorExit::Stopped;
+        }
+        if link.is_alive() {
+            continue;
+        }
+
+        link.disconnect();
+        emit(
+            TunnelStatus::Reconnecting,
+            Some("SSH connection lost".to_string()),
+        );
+
+        let mut last_error = String::new();
+        let mut reconnected = false;
+        for attempt in 0..policy.max_attempts {
+            if !wait(policy.delay(attempt)) {
+                return SupervisorExit::Stopped;
+            }
+            match link.connect() {
+                Ok(()) => {
+                    reconnected = true;
+                    break;
+                }
+                Err(e) => {
+                    tracing::warn!("Tunnel reconnect attempt {} failed: {}", attempt + 1, e);
+                    last_error = e;
+                }
+            }
+        }
+
+        if !reconnected {
+            emit(
+                TunnelStatus::Error,
+                Some(format!(
+                    "Reconnect failed after {} attempts: {}",
+                    policy.max_attempts, last_error
+                )),
+            );
+            return SupervisorExit::GaveUp;
+        }
+        emit(TunnelStatus::Connected, None);
+    }
+}
+
+/// Sleep for `delay` in short steps, returning `false` early once
+/// `running` is cleared.
+pub fn wait_while_running(running: &AtomicBool, delay: Duration) -> bool {
+    let deadline = Instant::now() + delay;
+    while Instant::now() < deadline {
+        if !running.load(Ordering::SeqCst) {
+            return false;
+        }
+        std::thread::sleep(CANCEL_POLL.min(deadline - Instant::now()));
+    }
+    running.load(Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    /// Link that replays scripted health checks and connect results.
+    #[derive(Default)]
+    struct MockLink {
+        alive: VecDeque<bool>,
+        connects: VecDeque<Result<(), String>>,
+        disconnects: u32,
+    }
+
+    impl TunnelLink for MockLink {
+        fn is_alive(&mut self) -> bool {
+            self.alive.pop_front().unwrap_or(true)
+        }
+
+        fn disconnect(&mut self) {
+            self.disconnects += 1;
+        }
+
+        fn connect(&mut self) -> Result<(), String> {
+            self.connects
+                .pop_front()
+                .unwrap_or_else(|| Err("refused".to_string()))
+        }
+    }
+
+    fn policy(max_attempts: u32) -> ReconnectPolicy {
+        ReconnectPolicy {
+            max_attempts,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(4),
+        }
+    }
+
+    /// Run `supervise`, stopping after `waits` waits, and return the exit,
+    /// the emitted statuses and the requested delays in seconds.
+    fn run(
+        link: &mut MockLink,
+        policy: &ReconnectPolicy,
+        waits: usize,
+    ) -> (SupervisorExit, Vec<TunnelStatus>, Vec<u64>) {
+        let mut delays = Vec::new();
+        let mut statuses = Vec::new();
+        let exit = supervise(
+            link,
+            policy,
+            |delay| {
+                delays.push(delay.as_secs());
+                delays.len() <= waits
+            },
+            |status, _| statuses.push(status),
+        );
+        (exit, statuses, delays)
+    }
+
+    #[test]
+    fn delay_doubles_up_to_max() {
+        let delays: Vec<u64> = (0..5).map(|a| policy(5).delay(a).as_secs()).collect();
+        assert_eq!(delays, vec![1, 2, 4, 4, 4]);
+        assert_eq!(policy(5).delay(u32::MAX), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn healthy_tunnel_only_checks() {
+        let mut link = MockLink::default();
+        let (exit, statuses, delays) = run(&mut link, &policy(3), 3);
+        assert_eq!(exit, SupervisorExit::Stopped);
+        assert!(statuses.is_empty());
+        assert_eq!(delays, vec![5, 5, 5, 5]);
+        assert_eq!(link.disconnects, 0);
+    }
+
+    #[test]
+    fn drop_reconnects_after_backoff() {
+        let mut link = MockLink {
+            alive: VecDeque::from([true, false]),
+            connects: VecDeque::from([Err("refused".to_string()), Ok(())]),
+            ..MockLink::default()
+        };
+        let (exit, statuses, delays) = run(&mut link, &policy(3), 5);
+        assert_eq!(exit, SupervisorExit::Stopped);
+        assert_eq!(
+            statuses,
+            vec![TunnelStatus::Reconnecting, TunnelStatus::Connected]
+        );
+        // Two health checks, two attempts, then back to checking.
+        assert_eq!(delays, vec![5, 5, 1, 2, 5, 5]);
+        assert_eq!(link.disconnects, 1);
+    }
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let mut link = MockLink {
+            alive: VecDeque::from([false]),
+            ..MockLink::default()
+        };
+        let mut last_error = None;
+        let exit = supervise(
+            &mut link,
+            &policy(3),
+            |_| true,
+            |status, error| {
+                if status == TunnelStatus::Error {
+                    last_error = error;
+                }
+            },
+        );
+        assert_eq!(exit, SupervisorExit::GaveUp);
+        assert_eq!(
+            last_error.as_deref(),
+            Some("Reconnect failed after 3 attempts: refused")
+        );
+        assert!(link.connects.is_empty());
+    }
+
+    #[test]
+    fn stop_cancels_reconnect_in_flight() {
+        let mut link = MockLink {
+            alive: VecDeque::from([false]),
+            connects: VecDeque::from([Err("refused".to_string()), Ok(())]),
+            ..MockLink::default()
+        };
+        // Stopped while waiting before the second attempt.
+        let (exit, statuses, delays) = run(&mut link, &policy(5), 2);
+        assert_eq!(exit, SupervisorExit::Stopped);
+        assert_eq!(statuses, vec![TunnelStatus::Reconnecting]);
+        assert_eq!(delays, vec![5, 1, 2]);
+        // The successful connect was never attempted.
+        assert_eq!(link.connects.len(), 1);
+    }
+
+    #[test]
+    fn wait_while_running_returns_early_when_stopped() {
+        let running = AtomicBool::new(false);
+        let start = Instant::now();
+        assert!(!wait_while_running(&running, Duration::from_secs(10)));
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        let running = AtomicBool::new(true);
+        assert!(wait_while_running(&running, Duration::from_millis(1)));
+    }
+}
@@ -0,0 +1,63 @@
+//! Helpers for tunnel tests that run against a real SSH server.
+//!
+//! The server is the `ssh-tunnel-target` container from
+//! `tests/docker/docker-compose.yml`. When it is not running the helpers
+//! print a skip notice and the test returns early, following the
+//! integration tests' runtime skip convention.
+
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use ssh2::Session;
+
+/// SSH port of the tunnel test server on the host.
+const TUNNEL_TARGET_SSH: &str = "127.0.0.1:2207";
+
+/// TCP echo service inside the tunnel test server.
+pub const TCP_ECHO_PORT: u16 = 9090;
+
+/// UDP echo service inside the tunnel test server.
+pub const UDP_ECHO_PORT: u16 = 9091;
+
+/// An authenticated session to the tunnel test server, or `None` when the
+/// container is not reachable.
+pub fn tunnel_target_session() -> Option<Arc<Mutex<Session>>> {
+    let addr = TUNNEL_TARGET_SSH.parse().expect("valid address");
+    let Ok(tcp) = TcpStream::connect_timeout(&addr, Duration::from_secs(2)) else {
+        eprintln!(
+            "SKIPPED: ssh-tunnel-target not reachable on {TUNNEL_TARGET_SSH} \
+             (start with: cd tests/docker && docker compose up -d ssh-tunnel-target)"
+        );
+        return None;
+    };
+    let mut session = Session::new().expect("ssh session");
+    session.set_tcp_stream(tcp);
+    session.handshake().expect("ssh handshake");
+    session
+        .userauth_password("testuser", "testpass")
+        .expect("ssh password auth");
+    Some(Arc::new(Mutex::new(session)))
+}
+
+/// A local port that was free when this was called.
+pub fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .expect("ephemeral port")
+        .port()
+}
+
+/// Connect to a forwarder's local listener, waiting for it to come up.
+pub fn connect_local(port: u16) -> TcpStream {
+    for _ in 0..50 {
+        if let Ok(stream) = TcpStream::connect(("127.0.0.1", port)) {
+            stream
+                .set_read_timeout(Some(Duration::from_secs(10)))
+                .expect("read timeout");
+            return stream;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    panic!("forwarder did not listen on port {port}");
+}
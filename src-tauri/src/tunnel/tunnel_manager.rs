@@ -1,17 +1,22 @@
 use std::collections::HashMap;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
 
 use anyhow::{Context, Result};
+use ssh2::Session;
 use tauri::{AppHandle, Emitter, Manager};
 
 use super::config::{
-    TunnelConfig, TunnelState, TunnelStats, TunnelStatus, TunnelStore, TunnelType,
+    TunnelConfig, TunnelMetrics, TunnelState, TunnelStats, TunnelStatus, TunnelStore, TunnelType,
 };
 use super::dynamic_forward::DynamicForwarder;
 use super::local_forward::LocalForwarder;
 use super::remote_forward::RemoteForwarder;
-use super::session_pool::SshSessionPool;
+use super::session_pool::{is_session_alive, SshSessionPool};
 use super::storage::TunnelStorage;
+use super::supervisor::{
+    supervise, wait_while_running, ReconnectPolicy, SupervisorExit, TunnelLink,
+};
 use crate::connection::manager::ConnectionManager;
 use crate::connection::recovery::RecoveryWarning;
 use crate::utils::errors::TerminalError;
@@ -23,16 +28,29 @@ enum ActiveForwarder {
     Dynamic(DynamicForwarder),
 }
 
+impl ActiveForwarder {
+    fn stop(&mut self) {
+        match self {
+            ActiveForwarder::Local(f) => f.stop(),
+            ActiveForwarder::Remote(f) => f.stop(),
+            ActiveForwarder::Dynamic(f) => f.stop(),
+        }
+    }
+}
+
 /// An active tunnel instance.
 struct ActiveTunnel {
     forwarder: ActiveForwarder,
     ssh_connection_id: String,
+    session: Arc<Mutex<Session>>,
 }
 
 /// Central manager for SSH tunnels.
 ///
 /// Handles CRUD operations on tunnel configurations, starting/stopping tunnels,
-/// and tracking live tunnel state.
+/// and tracking live tunnel state. Tunnels with `reconnect_on_disconnect`
+/// are watched by a supervisor thread that re-establishes them when their
+/// SSH connection drops.
 pub struct TunnelManager {
     tunnel_configs: Mutex<TunnelStore>,
     storage: TunnelStorage,
@@ -40,6 +58,12 @@ pub struct TunnelManager {
     session_pool: Mutex<SshSessionPool>,
     app_handle: AppHandle,
     recovery_warnings: Mutex<Vec<RecoveryWarning>>,
+    /// Running flags of supervised tunnels. Clearing a flag (under this
+    /// lock) cancels the supervisor, including a reconnect in progress.
+    supervisors: Mutex<HashMap<String, Arc<AtomicBool>>>,
+    /// Last status emitted per tunnel, reported for tunnels that are not
+    /// active (e.g. reconnecting or failed).
+    last_statuses: Mutex<HashMap<String, (TunnelStatus, Option<String>)>>,
 }
 
 impl TunnelManager {
@@ -59,6 +83,8 @@ impl TunnelManager {
             session_pool: Mutex::new(SshSessionPool::new()),
             app_handle: app_handle.clone(),
             recovery_warnings: Mutex::new(result.warnings),
+            supervisors: Mutex::new(HashMap::new()),
+            last_statuses: Mutex::new(HashMap::new()),
         })
     }
 
@@ -128,6 +154,10 @@ impl TunnelManager {
             .active_tunnels
             .lock()
             .map_err(|e| TerminalError::TunnelError(format!("Lock error: {}", e)))?;
+        let last_statuses = self
+            .last_statuses
+            .lock()
+            .map_err(|e| TerminalError::TunnelError(format!("Lock error: {}", e)))?;
 
         let states = store
             .tunnels
@@ -146,10 +176,16 @@ impl TunnelManager {
                         stats,
                     }
                 } else {
+                    let (status, error) = match last_statuses.get(&config.id) {
+                        Some((TunnelStatus::Connected, _)) | None => {
+                            (TunnelStatus::Disconnected, None)
+                        }
+                        Some((status, error)) => (status.clone(), error.clone()),
+                    };
                     TunnelState {
                         tunnel_id: config.id.clone(),
-                        status: TunnelStatus::Disconnected,
-                        error: None,
+                        status,
+                        error,
                         stats: TunnelStats::default(),
                     }
                 }
@@ -159,6 +195,40 @@ impl TunnelManager {
         Ok(states)
     }
 
+    /// Get traffic totals and throughput for all tunnels.
+    ///
+    /// Tunnels that are not running report zero.
+    pub fn get_metrics(&self) -> Result<Vec<TunnelMetrics>, TerminalError> {
+        let store = self
+            .tunnel_configs
+            .lock()
+            .map_err(|e| TerminalError::TunnelError(format!("Lock error: {}", e)))?;
+        let active = self
+            .active_tunnels
+            .lock()
+            .map_err(|e| TerminalError::TunnelError(format!("Lock error: {}", e)))?;
+
+        let metrics = store
+            .tunnels
+            .iter()
+            .map(|config| match active.get(&config.id) {
+                Some(tunnel) => match &tunnel.forwarder {
+                    ActiveForwarder::Local(f) => f.get_metrics(&config.id),
+                    ActiveForwarder::Remote(f) => f.get_metrics(&config.id),
+                    ActiveForwarder::Dynamic(f) => f.get_metrics(&config.id),
+                },
+                None => TunnelMetrics {
+                    tunnel_id: config.id.clone(),
+                    stats: TunnelStats::default(),
+                    bytes_sent_per_sec: 0.0,
+                    bytes_received_per_sec: 0.0,
+                },
+            })
+            .collect();
+
+        Ok(metrics)
+    }
+
     /// Start a tunnel by ID.
     pub fn start_tunnel(&self, tunnel_id: &str) -> Result<(), TerminalError> {
         // Get tunnel config
@@ -177,13 +247,17 @@ impl TunnelManager {
                 })?
         };
 
-        // Check if already active
+        // Check if already active (or being reconnected)
         {
             let active = self
                 .active_tunnels
                 .lock()
                 .map_err(|e| TerminalError::TunnelError(format!("Lock error: {}", e)))?;
-            if active.contains_key(tunnel_id) {
+            let supervisors = self
+                .supervisors
+                .lock()
+                .map_err(|e| TerminalError::TunnelError(format!("Lock error: {}", e)))?;
+            if active.contains_key(tunnel_id) || supervisors.contains_key(tunnel_id) {
                 return Err(TerminalError::TunnelError(format!(
                     "Tunnel {} is already active",
                     tunnel_id
@@ -191,9 +265,39 @@ impl TunnelManager {
             }
         }
 
+        // Fail fast on a taken local port instead of after the SSH connect
+        match &config.tunnel_type {
+            TunnelType::Local(c) => check_local_port_available(&c.local_host, c.local_port)?,
+            TunnelType::Dynamic(c) => check_local_port_available(&c.local_host, c.local_port)?,
+            TunnelType::Remote(_) => {}
+        }
+
         // Emit connecting status
         self.emit_status(tunnel_id, TunnelStatus::Connecting, None);
 
+        self.establish(&config, None)?;
+
+        // Emit connected status
+        self.emit_status(tunnel_id, TunnelStatus::Connected, None);
+
+        if config.reconnect_on_disconnect {
+            self.spawn_supervisor(config);
+        }
+
+        tracing::info!("Tunnel {} started", tunnel_id);
+        Ok(())
+    }
+
+    /// Connect to the tunnel's SSH server, start its forwarder and register
+    /// it as active.
+    ///
+    /// With `running`, a tunnel stopped while connecting is torn down again
+    /// instead of being registered.
+    fn establish(
+        &self,
+        config: &TunnelConfig,
+        running: Option<&AtomicBool>,
+    ) -> Result<(), TerminalError> {
         // Look up the SSH connection config
         let ssh_config = self.resolve_ssh_config(&config.ssh_connection_id)?;
 
@@ -207,24 +311,28 @@ impl TunnelManager {
         };
 
         // Start the appropriate forwarder
-        let forwarder = match &config.tunnel_type {
+        let started = match &config.tunnel_type {
             TunnelType::Local(local_config) => {
-                let f = LocalForwarder::start(local_config, session).map_err(|e| {
-                    TerminalError::TunnelError(format!("Failed to start local forwarder: {}", e))
-                })?;
-                ActiveForwarder::Local(f)
+                LocalForwarder::start(local_config, Arc::clone(&session))
+                    .map(ActiveForwarder::Local)
+                    .map_err(|e| format!("Failed to start local forwarder: {}", e))
             }
             TunnelType::Remote(remote_config) => {
-                let f = RemoteForwarder::start(remote_config, session).map_err(|e| {
-                    TerminalError::TunnelError(format!("Failed to start remote forwarder: {}", e))
-                })?;
-                ActiveForwarder::Remote(f)
+                RemoteForwarder::start(remote_config, Arc::clone(&session))
+                    .map(ActiveForwarder::Remote)
+                    .map_err(|e| format!("Failed to start remote forwarder: {}", e))
             }
             TunnelType::Dynamic(dynamic_config) => {
-                let f = DynamicForwarder::start(dynamic_config, session).map_err(|e| {
-                    TerminalError::TunnelError(format!("Failed to start dynamic forwarder: {}", e))
-                })?;
-                ActiveForwarder::Dynamic(f)
+                DynamicForwarder::start(dynamic_config, Arc::clone(&session))
+                    .map(ActiveForwarder::Dynamic)
+                    .map_err(|e| format!("Failed to start dynamic forwarder: {}", e))
+            }
+        };
+        let mut forwarder = match started {
+            Ok(f) => f,
+            Err(e) => {
+                self.release_session(&config.ssh_connection_id, &session);
+                return Err(TerminalError::TunnelError(e));
             }
         };
 
@@ -234,25 +342,83 @@ impl TunnelManager {
                 .active_tunnels
                 .lock()
                 .map_err(|e| TerminalError::TunnelError(format!("Lock error: {}", e)))?;
-            active.insert(
-                tunnel_id.to_string(),
-                ActiveTunnel {
-                    forwarder,
-                    ssh_connection_id: config.ssh_connection_id.clone(),
-                },
-            );
+            if running.is_none_or(|r| r.load(Ordering::SeqCst)) {
+                active.insert(
+                    config.id.clone(),
+                    ActiveTunnel {
+                        forwarder,
+                        ssh_connection_id: config.ssh_connection_id.clone(),
+                        session,
+                    },
+                );
+                return Ok(());
+            }
         }
 
-        // Emit connected status
-        self.emit_status(tunnel_id, TunnelStatus::Connected, None);
+        forwarder.stop();
+        self.release_session(&config.ssh_connection_id, &session);
+        Err(TerminalError::TunnelError(format!(
+            "Tunnel {} was stopped",
+            config.id
+        )))
+    }
 
-        tracing::info!("Tunnel {} started", tunnel_id);
-        Ok(())
+    /// Watch a started tunnel on a background thread and reconnect it when
+    /// its SSH connection drops.
+    fn spawn_supervisor(&self, config: TunnelConfig) {
+        let running = Arc::new(AtomicBool::new(true));
+        match self.supervisors.lock() {
+            Ok(mut supervisors) => {
+                supervisors.insert(config.id.clone(), Arc::clone(&running));
+            }
+            Err(_) => return,
+        }
+
+        let app_handle = self.app_handle.clone();
+        std::thread::spawn(move || {
+            let Some(manager) = app_handle.try_state::<TunnelManager>() else {
+                return;
+            };
+            let policy = ReconnectPolicy::from_config(&config);
+            let mut link = ManagedTunnelLink {
+                manager: &manager,
+                config: &config,
+                running: &running,
+            };
+            let exit = supervise(
+                &mut link,
+                &policy,
+                |delay| wait_while_running(&running, delay),
+                |status, error| manager.emit_supervised(&config.id, &running, status, error),
+            );
+            if exit == SupervisorExit::GaveUp {
+                if let Ok(mut supervisors) = manager.supervisors.lock() {
+                    if supervisors
+                        .get(&config.id)
+                        .is_some_and(|r| Arc::ptr_eq(r, &running))
+                    {
+                        supervisors.remove(&config.id);
+                    }
+                }
+                tracing::warn!("Tunnel {} gave up reconnecting", config.id);
+            }
+        });
     }
 
-    /// Stop an active tunnel by ID.
+    /// Stop an active tunnel by ID, cancelling any reconnect in progress.
     pub fn stop_tunnel(&self, tunnel_id: &str) -> Result<(), TerminalError> {
-        let tunnel = {
+        let supervised = {
+            let mut supervisors = self
+                .supervisors
+                .lock()
+                .map_err(|e| TerminalError::TunnelError(format!("Lock error: {}", e)))?;
+            supervisors
+                .remove(tunnel_id)
+                .inspect(|running| running.store(false, Ordering::SeqCst))
+                .is_some()
+        };
+
+        let mut tunnel = {
             let mut active = self
                 .active_tunnels
                 .lock()
@@ -260,20 +426,14 @@ impl TunnelManager {
             active.remove(tunnel_id)
         };
 
-        if let Some(mut tunnel) = tunnel {
-            match &mut tunnel.forwarder {
-                ActiveForwarder::Local(f) => f.stop(),
-                ActiveForwarder::Remote(f) => f.stop(),
-                ActiveForwarder::Dynamic(f) => f.stop(),
-            }
+        if let Some(tunnel) = tunnel.as_mut() {
+            tunnel.forwarder.stop();
 
             // Release session from pool
-            let mut pool = self
-                .session_pool
-                .lock()
-                .map_err(|e| TerminalError::TunnelError(format!("Lock error: {}", e)))?;
-            pool.release(&tunnel.ssh_connection_id);
+            self.release_session(&tunnel.ssh_connection_id, &tunnel.session);
+        }
 
+        if tunnel.is_some() || supervised {
             // Emit disconnected status
             self.emit_status(tunnel_id, TunnelStatus::Disconnected, None);
 
@@ -285,13 +445,18 @@ impl TunnelManager {
 
     /// Stop all active tunnels (used during app shutdown).
     pub fn stop_all(&self) {
-        let tunnels: Vec<String> = {
+        let mut tunnels: Vec<String> = {
             let active = match self.active_tunnels.lock() {
                 Ok(a) => a,
                 Err(_) => return,
             };
             active.keys().cloned().collect()
         };
+        if let Ok(supervisors) = self.supervisors.lock() {
+            tunnels.extend(supervisors.keys().cloned());
+        }
+        tunnels.sort();
+        tunnels.dedup();
 
         for tunnel_id in tunnels {
             if let Err(e) = self.stop_tunnel(&tunnel_id) {
@@ -350,16 +515,39 @@ impl TunnelManager {
             )));
         }
 
-        serde_json::from_value(conn.config.settings.clone()).map_err(|e| {
-            TerminalError::TunnelError(format!(
-                "Failed to parse SSH config for connection {}: {}",
-                connection_id, e
-            ))
-        })
+        parse_tunnel_ssh_config(connection_id, &conn.config.settings)
+    }
+
+    /// Release a tunnel's reference to its pooled SSH session.
+    fn release_session(&self, connection_id: &str, session: &Arc<Mutex<Session>>) {
+        if let Ok(mut pool) = self.session_pool.lock() {
+            pool.release(connection_id, session);
+        }
+    }
+
+    /// Emit a status from a tunnel's supervisor, unless the tunnel has been
+    /// stopped. Checked under the supervisors lock, so a status can never
+    /// follow the `Disconnected` emitted by [`stop_tunnel`](Self::stop_tunnel).
+    fn emit_supervised(
+        &self,
+        tunnel_id: &str,
+        running: &AtomicBool,
+        status: TunnelStatus,
+        error: Option<String>,
+    ) {
+        let Ok(_supervisors) = self.supervisors.lock() else {
+            return;
+        };
+        if running.load(Ordering::SeqCst) {
+            self.emit_status(tunnel_id, status, error);
+        }
     }
 
     /// Emit a tunnel status change event to the frontend.
     fn emit_status(&self, tunnel_id: &str, status: TunnelStatus, error: Option<String>) {
+        if let Ok(mut last_statuses) = self.last_statuses.lock() {
+            last_statuses.insert(tunnel_id.to_string(), (status.clone(), error.clone()));
+        }
         let state = TunnelState {
             tunnel_id: tunnel_id.to_string(),
             status,
@@ -369,3 +557,133 @@ impl TunnelManager {
         let _ = self.app_handle.emit("tunnel-status-changed", &state);
     }
 }
+
+/// [`TunnelLink`] over a tunnel registered with a [`TunnelManager`].
+struct ManagedTunnelLink<'a> {
+    manager: &'a TunnelManager,
+    config: &'a TunnelConfig,
+    running: &'a AtomicBool,
+}
+
+impl ManagedTunnelLink<'_> {
+    fn session(&self) -> Option<Arc<Mutex<Session>>> {
+        let active = self.manager.active_tunnels.lock().ok()?;
+        active
+            .get(&self.config.id)
+            .map(|tunnel| Arc::clone(&tunnel.session))
+    }
+}
+
+impl TunnelLink for ManagedTunnelLink<'_> {
+    fn is_alive(&mut self) -> bool {
+        // A tunnel that is no longer active was stopped; the cleared running
+        // flag ends supervision.
+        self.session()
+            .is_none_or(|session| is_session_alive(&session))
+    }
+
+    fn disconnect(&mut self) {
+        let tunnel = match self.manager.active_tunnels.lock() {
+            Ok(mut active) => active.remove(&self.config.id),
+            Err(_) => return,
+        };
+        if let Some(mut tunnel) = tunnel {
+            tunnel.forwarder.stop();
+            if let Ok(mut pool) = self.manager.session_pool.lock() {
+                pool.invalidate(&tunnel.ssh_connection_id, &tunnel.session);
+            }
+        }
+    }
+
+    fn connect(&mut self) -> Result<(), String> {
+        self.manager
+            .establish(self.config, Some(self.running))
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Check that `host:port` can be bound locally.
+///
+/// The probe listener is dropped right away so the forwarder can bind the
+/// port itself. Errors other than the port being taken (e.g. an
+/// unresolvable host) are left for the forwarder to report.
+fn check_local_port_available(host: &str, port: u16) -> Result<(), TerminalError> {
+    match std::net::TcpListener::bind((host, port)) {
+        Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+            Err(TerminalError::PortInUse { port })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Parse the saved settings of SSH connection `connection_id`, expanding
+/// placeholders and resolving the host through its `sshConfigFile`.
+fn parse_tunnel_ssh_config(
+    connection_id: &str,
+    settings: &serde_json::Value,
+) -> Result<crate::terminal::backend::SshConfig, TerminalError> {
+    let config: crate::terminal::backend::SshConfig = serde_json::from_value(settings.clone())
+        .map_err(|e| {
+            TerminalError::TunnelError(format!(
+                "Failed to parse SSH config for connection {}: {}",
+                connection_id, e
+            ))
+        })?;
+    config.expand().apply_ssh_config_file().map_err(|e| {
+        TerminalError::TunnelError(format!(
+            "Failed to apply SSH config file for connection {}: {}",
+            connection_id, e
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tunnel_ssh_config_resolves_host_alias() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("config");
+        std::fs::write(
+            &file,
+            "Host work\n    HostName 10.1.2.3\n    Port 2200\n    User deploy\n",
+        )
+        .unwrap();
+        let settings = serde_json::json!({
+            "host": "work",
+            "username": "",
+            "authMethod": "agent",
+            "sshConfigFile": file.to_str().unwrap(),
+        });
+
+        let config = parse_tunnel_ssh_config("conn-1", &settings).unwrap();
+
+        assert_eq!(config.host, "10.1.2.3");
+        assert_eq!(config.port, 2200);
+        assert_eq!(config.username, "deploy");
+    }
+
+    #[test]
+    fn bound_port_reports_port_in_use() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let err = check_local_port_available("127.0.0.1", port).unwrap_err();
+
+        assert!(matches!(err, TerminalError::PortInUse { port: p } if p == port));
+        assert_eq!(
+            err.to_string(),
+            format!("Local port {port} is already in use")
+        );
+    }
+
+    #[test]
+    fn free_port_passes_check() {
+        let port = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap().port()
+        };
+        assert!(check_local_port_available("127.0.0.1", port).is_ok());
+    }
+}
@@ -0,0 +1,422 @@
+//! Launching files in a user-configured external editor.
+//!
+//! An editor is configured as a command template such as `nvim {path}` or
+//! `code --wait {path}`. The template is split on whitespace and `{path}`
+//! is substituted per argument, so paths containing spaces stay a single
+//! argument. Templates without `{path}` get the path appended.
+//!
+//! Remote files are edited through a local temp copy: [`watch_edits`]
+//! uploads the copy each time it changes on disk and once more after the
+//! editor exits.
+
+use std::cell::Cell;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+
+/// Placeholder replaced with the file path in an editor command template.
+pub const PATH_PLACEHOLDER: &str = "{path}";
+
+/// Editors tried, in order, when none is configured. Each blocks until the
+/// file is closed, so edits to remote files are uploaded when done.
+pub const DEFAULT_EDITOR_COMMANDS: &[&str] = &[
+    "code --wait {path}",
+    "subl --wait {path}",
+    "zed --wait {path}",
+    "gvim --nofork {path}",
+    "notepad {path}",
+];
+
+/// How often a temp copy is checked for changes while its editor runs.
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// An editor process exiting sooner than this is assumed to have handed
+/// the file to an already running instance (e.g. `code` without `--wait`),
+/// which still has it open.
+const DETACHED_EDITOR_EXIT: Duration = Duration::from_secs(3);
+
+/// How long a file handed to a detached editor keeps being watched after
+/// its last change.
+const DETACHED_WATCH_IDLE: Duration = Duration::from_secs(30 * 60);
+
+/// Split an editor command template into program and arguments with
+/// `path` substituted.
+pub fn editor_argv(template: &str, path: &str) -> Result<Vec<String>, String> {
+    let mut argv: Vec<String> = template
+        .split_whitespace()
+        .map(|arg| arg.replace(PATH_PLACEHOLDER, path))
+        .collect();
+    if argv.is_empty() {
+        return Err("Editor command is empty".to_string());
+    }
+    if !template.contains(PATH_PLACEHOLDER) {
+        argv.push(path.to_string());
+    }
+    Ok(argv)
+}
+
+/// Pick the editor template to use: the per-connection override, then the
+/// app-wide setting, then the first default editor found on `PATH`.
+pub fn resolve_editor_command(
+    connection: Option<&str>,
+    app: Option<&str>,
+) -> Result<String, String> {
+    let configured = [connection, app]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|t| !t.is_empty());
+    if let Some(template) = configured {
+        return Ok(template.to_string());
+    }
+    DEFAULT_EDITOR_COMMANDS
+        .iter()
+        .find(|t| {
+            t.split_whitespace()
+                .next()
+                .is_some_and(|program| find_on_path(program).is_some())
+        })
+        .map(|t| t.to_string())
+        .ok_or_else(|| "No editor found; configure an editor command".to_string())
+}
+
+/// Locate `program` in the directories of `PATH`.
+fn find_on_path(program: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    let extensions: &[&str] = if cfg!(windows) {
+        &["", ".exe", ".cmd", ".bat"]
+    } else {
+        &[""]
+    };
+    std::env::split_paths(&paths).find_map(|dir| {
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{program}{ext}")))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+/// Build the `Command` for an editor argv. No shell is involved, so the
+/// path always reaches the editor as a plain argument.
+///
+/// The program is looked up on `PATH` first: on Windows, editors are often
+/// installed as `.cmd` shims that `CreateProcessW` cannot resolve by name.
+/// The standard library runs those with their arguments escaped for
+/// `cmd.exe`.
+fn editor_command(argv: &[String]) -> Command {
+    let program = find_on_path(&argv[0]).unwrap_or_else(|| PathBuf::from(&argv[0]));
+    let mut cmd = Command::new(program);
+    cmd.args(&argv[1..]);
+    cmd
+}
+
+/// Launch `path` in the editor described by `template`.
+pub fn spawn_editor(template: &str, path: &str) -> std::io::Result<Child> {
+    let argv = editor_argv(template, path).map_err(std::io::Error::other)?;
+    editor_command(&argv).spawn()
+}
+
+/// Local temp path for editing `remote_path`, unique per edit so that
+/// concurrent edits of same-named files don't collide.
+pub fn edit_temp_path(temp_dir: &Path, remote_path: &str) -> PathBuf {
+    let filename = Path::new(remote_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "untitled".to_string());
+    temp_dir.join(format!("{}-{}", uuid::Uuid::new_v4(), filename))
+}
+
+/// A local copy of a file being edited, tracking its last uploaded content.
+pub struct EditedFile {
+    path: PathBuf,
+    fingerprint: u64,
+}
+
+impl EditedFile {
+    /// Start tracking `path` with its current content as the baseline.
+    pub fn new(path: PathBuf) -> std::io::Result<Self> {
+        let fingerprint = fingerprint(&path)?;
+        Ok(Self { path, fingerprint })
+    }
+
+    /// Path of the local copy.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Whether the content changed since the last call (or since
+    /// creation), updating the baseline if so.
+    pub fn take_change(&mut self) -> std::io::Result<bool> {
+        let current = fingerprint(&self.path)?;
+        let changed = current != self.fingerprint;
+        self.fingerprint = current;
+        Ok(changed)
+    }
+}
+
+fn fingerprint(path: &Path) -> std::io::Result<u64> {
+    let mut hasher = DefaultHasher::new();
+    std::fs::read(path)?.hash(&mut hasher);
+    Ok(hasher.finish())
+}
+
+/// Upload `file` whenever it changes until `editor_running` returns false,
+/// then upload any final change. Returns the number of uploads.
+///
+/// Read errors while polling (e.g. an editor replacing the file via
+/// rename) are retried on the next poll.
+pub fn watch_edits<E>(
+    file: &mut EditedFile,
+    interval: Duration,
+    mut editor_running: impl FnMut() -> bool,
+    mut upload: impl FnMut(&Path) -> Result<(), E>,
+) -> Result<usize, E> {
+    let mut uploads = 0;
+    loop {
+        let running = editor_running();
+        if matches!(file.take_change(), Ok(true)) {
+            upload(file.path())?;
+            uploads += 1;
+        }
+        if !running {
+            return Ok(uploads);
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+/// [`watch_edits`] until no change was made for `idle`, for an editor
+/// that cannot be waited for.
+pub fn watch_until_idle<E>(
+    file: &mut EditedFile,
+    interval: Duration,
+    idle: Duration,
+    mut upload: impl FnMut(&Path) -> Result<(), E>,
+) -> Result<usize, E> {
+    let last_change = Cell::new(Instant::now());
+    watch_edits(
+        file,
+        interval,
+        || last_change.get().elapsed() < idle,
+        |path| {
+            last_change.set(Instant::now());
+            upload(path)
+        },
+    )
+}
+
+/// How an edit watched by [`watch_editor_process`] ended.
+pub struct EditSummary {
+    /// Number of uploads made.
+    pub uploads: usize,
+    /// Whether the editor process exited right away, handing the file to
+    /// another process that may still have it open. Its copy must then be
+    /// kept.
+    pub detached: bool,
+}
+
+/// [`watch_edits`] for a spawned editor process. An editor that exits
+/// right away is assumed to have detached, and the file keeps being
+/// watched until it has not changed for a while.
+pub fn watch_editor_process<E>(
+    mut child: Child,
+    file: &mut EditedFile,
+    mut upload: impl FnMut(&Path) -> Result<(), E>,
+) -> Result<EditSummary, E> {
+    let started = Instant::now();
+    let mut uploads = watch_edits(
+        file,
+        WATCH_INTERVAL,
+        || matches!(child.try_wait(), Ok(None)),
+        &mut upload,
+    )?;
+    let detached = started.elapsed() < DETACHED_EDITOR_EXIT;
+    if detached {
+        uploads += watch_until_idle(file, WATCH_INTERVAL, DETACHED_WATCH_IDLE, upload)?;
+    }
+    Ok(EditSummary { uploads, detached })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(template: &str, path: &str) -> Vec<String> {
+        editor_argv(template, path).unwrap()
+    }
+
+    #[test]
+    fn editor_argv_substitutes_path() {
+        assert_eq!(
+            argv("nvim {path}", "/tmp/a.txt"),
+            vec!["nvim", "/tmp/a.txt"]
+        );
+        assert_eq!(
+            argv("code --remote {path}", "/tmp/a.txt"),
+            vec!["code", "--remote", "/tmp/a.txt"]
+        );
+    }
+
+    #[test]
+    fn editor_argv_keeps_path_with_spaces_as_one_argument() {
+        assert_eq!(
+            argv("subl --wait {path}", "/tmp/my notes.md"),
+            vec!["subl", "--wait", "/tmp/my notes.md"]
+        );
+    }
+
+    #[test]
+    fn editor_argv_substitutes_within_an_argument() {
+        assert_eq!(
+            argv("emacs --file={path}", "/tmp/a.txt"),
+            vec!["emacs", "--file=/tmp/a.txt"]
+        );
+    }
+
+    #[test]
+    fn editor_argv_appends_path_without_placeholder() {
+        assert_eq!(argv("kate", "/tmp/a.txt"), vec!["kate", "/tmp/a.txt"]);
+    }
+
+    #[test]
+    fn editor_argv_rejects_empty_template() {
+        assert!(editor_argv("  ", "/tmp/a.txt").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn editor_command_passes_path_without_a_shell() {
+        let path = "/tmp/a.txt; echo injected $(id)";
+        let output = editor_command(&argv("printf %s {path}", path))
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), path);
+    }
+
+    #[test]
+    fn resolve_editor_command_prefers_connection_then_app() {
+        assert_eq!(
+            resolve_editor_command(Some("nvim {path}"), Some("code {path}")).unwrap(),
+            "nvim {path}"
+        );
+        assert_eq!(
+            resolve_editor_command(Some(" "), Some("code {path}")).unwrap(),
+            "code {path}"
+        );
+        assert_eq!(
+            resolve_editor_command(None, Some("code {path}")).unwrap(),
+            "code {path}"
+        );
+    }
+
+    #[test]
+    fn default_editor_commands_contain_placeholder() {
+        for template in DEFAULT_EDITOR_COMMANDS {
+            assert!(template.contains(PATH_PLACEHOLDER), "{template}");
+        }
+    }
+
+    #[test]
+    fn edit_temp_path_keeps_remote_filename() {
+        let dir = Path::new("/tmp/termihub-edit");
+        let path = edit_temp_path(dir, "/etc/nginx/nginx.conf");
+        assert_eq!(path.parent(), Some(dir));
+        let name = path.file_name().unwrap().to_string_lossy();
+        assert!(name.ends_with("-nginx.conf"), "{name}");
+        assert_ne!(path, edit_temp_path(dir, "/etc/nginx/nginx.conf"));
+        assert!(edit_temp_path(dir, "/")
+            .to_string_lossy()
+            .ends_with("-untitled"));
+    }
+
+    #[test]
+    fn edited_file_reports_each_change_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "one").unwrap();
+        let mut file = EditedFile::new(path.clone()).unwrap();
+
+        assert!(!file.take_change().unwrap());
+        std::fs::write(&path, "two").unwrap();
+        assert!(file.take_change().unwrap());
+        assert!(!file.take_change().unwrap());
+    }
+
+    #[test]
+    fn watch_edits_uploads_changes_while_running_and_after_exit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "original").unwrap();
+        let mut file = EditedFile::new(path.clone()).unwrap();
+
+        // Simulated editor: saves twice while running, once more on exit.
+        let mut polls = 0;
+        let editor_running = || {
+            polls += 1;
+            match polls {
+                1 => std::fs::write(&path, "first save").unwrap(),
+                3 => std::fs::write(&path, "final save").unwrap(),
+                _ => {}
+            }
+            polls < 3
+        };
+        let mut uploaded = Vec::new();
+        let uploads = watch_edits(&mut file, Duration::ZERO, editor_running, |p| {
+            uploaded.push(std::fs::read_to_string(p).unwrap());
+            Ok::<_, String>(())
+        })
+        .unwrap();
+
+        assert_eq!(uploads, 2);
+        assert_eq!(uploaded, vec!["first save", "final save"]);
+    }
+
+    #[test]
+    fn watch_edits_without_changes_uploads_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "unchanged").unwrap();
+        let mut file = EditedFile::new(path).unwrap();
+
+        let uploads = watch_edits(
+            &mut file,
+            Duration::ZERO,
+            || false,
+            |_| Err::<(), _>("should not upload"),
+        )
+        .unwrap();
+        assert_eq!(uploads, 0);
+    }
+
+    #[test]
+    fn watch_until_idle_uploads_pending_change() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "one").unwrap();
+        let mut file = EditedFile::new(path.clone()).unwrap();
+        std::fs::write(&path, "two").unwrap();
+
+        let mut uploaded = Vec::new();
+        let uploads = watch_until_idle(&mut file, Duration::ZERO, Duration::ZERO, |p| {
+            uploaded.push(std::fs::read_to_string(p).unwrap());
+            Ok::<_, String>(())
+        })
+        .unwrap();
+        assert_eq!(uploads, 1);
+        assert_eq!(uploaded, vec!["two"]);
+    }
+
+    #[test]
+    fn watch_edits_stops_on_upload_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "one").unwrap();
+        let mut file = EditedFile::new(path.clone()).unwrap();
+        std::fs::write(&path, "two").unwrap();
+
+        let result = watch_edits(&mut file, Duration::ZERO, || true, |_| Err("offline"));
+        assert_eq!(result, Err("offline"));
+    }
+}
@@ -15,11 +15,9 @@ pub enum TerminalError {
     #[error("Failed to resize terminal: {0}")]
     ResizeFailed(String),
 
-    #[allow(dead_code)]
     #[error("Connection failed: {0}")]
     ConnectionFailed(String),
 
-    #[allow(dead_code)]
     #[error("Serial port error: {0}")]
     SerialError(String),
 
@@ -46,9 +44,15 @@ pub enum TerminalError {
     #[error("Monitoring session not found: {0}")]
     MonitoringSessionNotFound(String),
 
+    #[error("Monitoring failed: {0}")]
+    MonitoringFailed(String),
+
     #[error("Tunnel error: {0}")]
     TunnelError(String),
 
+    #[error("Local port {port} is already in use")]
+    PortInUse { port: u16 },
+
     #[error("Workspace error: {0}")]
     WorkspaceError(String),
 
@@ -58,6 +62,9 @@ pub enum TerminalError {
     #[error("Not found: {0}")]
     NotFound(String),
 
+    #[error("Destination already exists: {0}")]
+    DestinationExists(String),
+
     #[error("Internal error: {0}")]
     InternalError(String),
 
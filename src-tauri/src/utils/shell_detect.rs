@@ -17,3 +17,10 @@ pub fn detect_available_shells() -> Vec<String> {
 pub fn detect_default_shell() -> Option<String> {
     termihub_core::session::shell::detect_default_shell()
 }
+
+/// List installed WSL distributions (empty outside Windows).
+///
+/// Delegates to [`termihub_core::session::shell::list_wsl_distributions()`].
+pub fn list_wsl_distributions() -> Vec<String> {
+    termihub_core::session::shell::list_wsl_distributions()
+}
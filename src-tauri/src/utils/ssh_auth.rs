@@ -1,9 +1,8 @@
-use std::net::TcpStream;
-#[cfg(not(target_os = "windows"))]
-use std::path::Path;
 use std::time::Duration;
 
 use ssh2::Session;
+use termihub_core::backends::ssh::auth::{ensure_agent_not_empty, open_tcp_stream};
+use termihub_core::backends::ssh::host_key::verify_host_key;
 
 use crate::terminal::backend::SshConfig;
 use crate::utils::errors::TerminalError;
@@ -17,8 +16,17 @@ use crate::utils::errors::TerminalError;
 /// This function will be removed once all callers are migrated to use
 /// the core SSH backend.
 pub fn connect_and_authenticate(config: &SshConfig) -> Result<Session, TerminalError> {
-    let addr = format!("{}:{}", config.host, config.port);
-    let tcp = TcpStream::connect(&addr)
+    connect_session(config, false)
+}
+
+/// Like [`connect_and_authenticate`], for a session dedicated to SFTP
+/// transfers: negotiates SSH compression when `transfer_compression` is set.
+pub fn connect_for_transfer(config: &SshConfig) -> Result<Session, TerminalError> {
+    connect_session(config, config.transfer_compression)
+}
+
+fn connect_session(config: &SshConfig, compress: bool) -> Result<Session, TerminalError> {
+    let tcp = open_tcp_stream(config)
         .map_err(|e| TerminalError::SshError(format!("Connection failed: {}", e)))?;
 
     // Bound blocking time on dead connections so monitoring execs don't hold
@@ -31,13 +39,16 @@ pub fn connect_and_authenticate(config: &SshConfig) -> Result<Session, TerminalE
     let mut session = Session::new().map_err(|e| TerminalError::SshError(e.to_string()))?;
 
     session.set_tcp_stream(tcp);
+    session.set_compress(compress);
     session
         .handshake()
         .map_err(|e| TerminalError::SshError(format!("Handshake failed: {}", e)))?;
+    verify_host_key(&session, config).map_err(|e| TerminalError::SshError(e.to_string()))?;
 
     // Authenticate
     match config.auth_method.as_str() {
         "agent" => {
+            ensure_agent_not_empty(&session).map_err(|e| TerminalError::SshError(e.to_string()))?;
             session
                 .userauth_agent(&config.username)
                 .map_err(|e| TerminalError::SshError(format!("Agent auth failed: {}", e)))?;
@@ -88,70 +99,3 @@ pub fn connect_and_authenticate(config: &SshConfig) -> Result<Session, TerminalE
 
     Ok(session)
 }
-
-/// Check whether the SSH agent is running, stopped, or not installed.
-///
-/// - **Windows**: tries to open the `openssh-ssh-agent` named pipe.
-///   `Path::exists()` does not work for named pipes, so we attempt
-///   an actual open — any result other than "not found" means the
-///   agent is running.
-/// - **Unix**: checks if `SSH_AUTH_SOCK` is set and the socket file exists.
-///
-/// Returns `"running"` or `"stopped"`.
-pub fn check_ssh_agent_status() -> String {
-    #[cfg(target_os = "windows")]
-    {
-        use std::fs::OpenOptions;
-        let pipe_path = r"\\.\pipe\openssh-ssh-agent";
-        match OpenOptions::new().read(true).open(pipe_path) {
-            Ok(_) => "running".to_string(),
-            Err(e) if e.kind() == std::io::ErrorKind::NotFound => "stopped".to_string(),
-            // Any other error (e.g. access denied, busy) means the pipe exists
-            Err(_) => "running".to_string(),
-        }
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    {
-        match std::env::var("SSH_AUTH_SOCK") {
-            Ok(sock_path) if !sock_path.is_empty() => {
-                if Path::new(&sock_path).exists() {
-                    "running".to_string()
-                } else {
-                    "stopped".to_string()
-                }
-            }
-            _ => "stopped".to_string(),
-        }
-    }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn check_ssh_agent_status_returns_valid_value() {
-        let status = check_ssh_agent_status();
-        assert!(
-            status == "running" || status == "stopped" || status == "not_installed",
-            "unexpected status: {status}"
-        );
-    }
-
-    #[cfg(not(target_os = "windows"))]
-    #[test]
-    fn check_ssh_agent_status_stopped_when_sock_unset() {
-        // Temporarily remove SSH_AUTH_SOCK to test the "stopped" path
-        let orig = std::env::var("SSH_AUTH_SOCK").ok();
-        std::env::remove_var("SSH_AUTH_SOCK");
-
-        let status = check_ssh_agent_status();
-        assert_eq!(status, "stopped");
-
-        // Restore original value
-        if let Some(val) = orig {
-            std::env::set_var("SSH_AUTH_SOCK", val);
-        }
-    }
-}
@@ -38,16 +38,6 @@ pub fn open_in_vscode(path: &str) -> Result<(), std::io::Error> {
     vscode_command().arg(path).spawn().map(|_| ())
 }
 
-/// Open a file in VS Code and wait for the editor tab to close.
-pub fn open_in_vscode_wait(path: &str) -> Result<(), std::io::Error> {
-    let status = vscode_command().arg("--wait").arg(path).status()?;
-    if status.success() {
-        Ok(())
-    } else {
-        Err(std::io::Error::other("VS Code exited with error"))
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1,4 +1,4 @@
-import { Component, useEffect } from "react";
+import { Component, useEffect, useState } from "react";
 import type { ErrorInfo, ReactNode } from "react";
 import { ActivityBar } from "@/components/ActivityBar";
 import { Sidebar } from "@/components/Sidebar";
@@ -17,6 +17,7 @@ import { useKeyboardShortcuts } from "@/hooks/useKeyboardShortcuts";
 import { useTunnelEvents } from "@/hooks/useTunnelEvents";
 import { useEmbeddedServerEvents } from "@/hooks/useEmbeddedServerEvents";
 import { useCredentialStoreEvents } from "@/hooks/useCredentialStoreEvents";
+import { useAutoConnect } from "@/hooks/useAutoConnect";
 import { useWebviewZoom } from "@/hooks/useWebviewZoom";
 import { useSidebarResize } from "@/hooks/useSidebarResize";
 import { useAppStore } from "@/store/appStore";
@@ -94,6 +95,8 @@ function App() {
   useEmbeddedServerEvents();
   useCredentialStoreEvents();
   useWebviewZoom();
+  const [backendLoaded, setBackendLoaded] = useState(false);
+  useAutoConnect(backendLoaded);
   const loadFromBackend = useAppStore((s) => s.loadFromBackend);
   const checkForUpdates = useAppStore((s) => s.checkForUpdates);
   const settings = useAppStore((s) => s.settings);
@@ -116,6 +119,7 @@ function App() {
   useEffect(() => {
     (async () => {
       await loadFromBackend();
+      setBackendLoaded(true);
       try {
         const cliWorkspaceName = await getCliWorkspace();
         if (cliWorkspaceName) {
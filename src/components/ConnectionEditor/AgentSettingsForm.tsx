@@ -1,6 +1,7 @@
 /**
  * Form for editing agent runtime settings (enable monitoring, file browser,
- * Docker, default shell, starting directory, log level, verbose tracing).
+ * Docker, default shell, starting directory, idle session timeout, log level,
+ * verbose tracing).
  *
  * Shown in the "Agent" tab of the connection editor when editing a remote agent
  * transport config. Fields that benefit from live agent data (shell list) show
@@ -130,6 +131,33 @@ export function AgentSettingsForm({ settings, onChange, capabilities }: AgentSet
             Working directory for new sessions. Leave empty for the shell default.
           </span>
         </label>
+
+        <label className="settings-form__field">
+          <span className="settings-form__label">Idle Session Timeout (seconds)</span>
+          <input
+            type="number"
+            min={0}
+            value={settings.daemonIdleTtlSecs}
+            onChange={(e) => update("daemonIdleTtlSecs", Math.max(0, Number(e.target.value) || 0))}
+          />
+          <span className="settings-form__hint">
+            Close persistent sessions that stay detached and idle this long. 0 keeps them running.
+          </span>
+        </label>
+
+        <label className="settings-form__field">
+          <span className="settings-form__label">Request Timeout (seconds)</span>
+          <input
+            type="number"
+            min={0}
+            value={settings.requestTimeoutSecs}
+            onChange={(e) => update("requestTimeoutSecs", Math.max(0, Number(e.target.value) || 0))}
+          />
+          <span className="settings-form__hint">
+            Fail agent requests that hang longer than this, e.g. on a dead mount. 0 waits
+            indefinitely.
+          </span>
+        </label>
       </div>
 
       <div className="settings-panel__category">
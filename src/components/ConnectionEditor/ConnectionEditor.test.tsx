@@ -397,4 +397,26 @@ describe("ConnectionEditor — unsaved-changes dirty state", () => {
     });
     expect(useAppStore.getState().editorDirtyTabs[TAB_ID]).toBe(false);
   });
+
+  it("shows the stored auto-connect flag and marks the tab dirty when it is toggled", async () => {
+    useAppStore.setState({
+      ...useAppStore.getInitialState(),
+      connections: [{ ...DIRTY_CONN, autoConnect: true }],
+      connectionTypes: [LOCAL_TYPE],
+    });
+    renderEditor(DIRTY_CONN.id);
+    await flushEffects();
+
+    const toggle = container.querySelector(
+      '[data-testid="connection-editor-auto-connect"]'
+    ) as HTMLInputElement;
+    expect(toggle).not.toBeNull();
+    expect(toggle.checked).toBe(true);
+    expect(useAppStore.getState().editorDirtyTabs[TAB_ID]).toBeFalsy();
+
+    await act(async () => {
+      toggle.click();
+    });
+    expect(useAppStore.getState().editorDirtyTabs[TAB_ID]).toBe(true);
+  });
 });
@@ -10,7 +10,7 @@ import {
   TerminalOptions,
   ConnectionEditorMeta,
 } from "@/types/terminal";
-import { listAvailableShells, resolveCredential } from "@/services/api";
+import { listAvailableShells, listWslDistributions, resolveCredential } from "@/services/api";
 import type { ConnectionTypeInfo } from "@/services/api";
 import {
   SavedConnection,
@@ -25,6 +25,7 @@ import {
   findPasswordPromptInfo,
   filterRuntimeOptions,
   filterCredentialFields,
+  applyWslDistributions,
 } from "@/utils/schemaDefaults";
 import { useAvailableRuntimes } from "@/hooks/useAvailableRuntimes";
 import { ConnectionTerminalSettings } from "./ConnectionTerminalSettings";
@@ -284,6 +285,7 @@ export function ConnectionEditor({ tabId, meta, isVisible }: ConnectionEditorPro
   const [sourceFile, setSourceFile] = useState<string | null>(
     existingConnection?.sourceFile ?? null
   );
+  const [autoConnect, setAutoConnect] = useState(existingConnection?.autoConnect ?? false);
 
   // Snapshot initial field values so we can compare against them to detect changes.
   // Using refs (not state) means the snapshot never triggers a re-render.
@@ -314,6 +316,7 @@ export function ConnectionEditor({ tabId, meta, isVisible }: ConnectionEditorPro
   const initialPersistent = useRef(persistent);
   const initialAgentSettings = useRef(agentSettings);
   const initialSourceFile = useRef(sourceFile);
+  const initialAutoConnect = useRef(autoConnect);
 
   useEffect(() => {
     const normalizedConnSettings = {
@@ -328,7 +331,8 @@ export function ConnectionEditor({ tabId, meta, isVisible }: ConnectionEditorPro
       icon !== initialIcon.current ||
       persistent !== initialPersistent.current ||
       JSON.stringify(agentSettings) !== JSON.stringify(initialAgentSettings.current) ||
-      sourceFile !== initialSourceFile.current;
+      sourceFile !== initialSourceFile.current ||
+      autoConnect !== initialAutoConnect.current;
     setEditorDirty(tabId, isDirty);
   }, [
     name,
@@ -339,6 +343,7 @@ export function ConnectionEditor({ tabId, meta, isVisible }: ConnectionEditorPro
     persistent,
     agentSettings,
     sourceFile,
+    autoConnect,
     tabId,
     setEditorDirty,
   ]);
@@ -552,6 +557,7 @@ export function ConnectionEditor({ tabId, meta, isVisible }: ConnectionEditorPro
         terminalOptions: opts,
         icon,
         sourceFile,
+        autoConnect: autoConnect || undefined,
       };
       updateConnection(saved);
 
@@ -571,6 +577,7 @@ export function ConnectionEditor({ tabId, meta, isVisible }: ConnectionEditorPro
         terminalOptions: opts,
         icon,
         sourceFile,
+        autoConnect: autoConnect || undefined,
       };
       addConnection(saved);
       return saved;
@@ -584,6 +591,7 @@ export function ConnectionEditor({ tabId, meta, isVisible }: ConnectionEditorPro
     terminalOptions,
     icon,
     sourceFile,
+    autoConnect,
     existingConnection,
     existingAgent,
     isAgentTransportMode,
@@ -714,6 +722,23 @@ export function ConnectionEditor({ tabId, meta, isVisible }: ConnectionEditorPro
   // Filter Docker runtime options based on what's actually installed
   const { dockerAvailable, podmanAvailable, loading: runtimesLoading } = useAvailableRuntimes();
 
+  // List the installed WSL distributions whenever the WSL type is selected
+  const [wslDistributions, setWslDistributions] = useState<string[]>([]);
+  useEffect(() => {
+    if (selectedType !== "wsl" || isAgentTransportMode) return;
+    let cancelled = false;
+    listWslDistributions()
+      .then((distros) => {
+        if (!cancelled) setWslDistributions(distros);
+      })
+      .catch(() => {
+        if (!cancelled) setWslDistributions([]);
+      });
+    return () => {
+      cancelled = true;
+    };
+  }, [selectedType, isAgentTransportMode]);
+
   const currentSchema = useMemo(() => {
     const base = isAgentTransportMode ? AGENT_SCHEMA : currentTypeInfo?.schema;
     if (!base) return base;
@@ -721,6 +746,9 @@ export function ConnectionEditor({ tabId, meta, isVisible }: ConnectionEditorPro
     if (selectedType === "docker" && !runtimesLoading) {
       schema = filterRuntimeOptions(schema, dockerAvailable, podmanAvailable);
     }
+    if (selectedType === "wsl" && !isAgentTransportMode) {
+      schema = applyWslDistributions(schema, wslDistributions);
+    }
     return filterCredentialFields(schema, credentialStoreStatus?.mode);
   }, [
     isAgentTransportMode,
@@ -729,6 +757,7 @@ export function ConnectionEditor({ tabId, meta, isVisible }: ConnectionEditorPro
     runtimesLoading,
     dockerAvailable,
     podmanAvailable,
+    wslDistributions,
     credentialStoreStatus?.mode,
   ]);
 
@@ -827,6 +856,27 @@ export function ConnectionEditor({ tabId, meta, isVisible }: ConnectionEditorPro
         />
       )}
 
+      {!isAnyAgentMode && (
+        <div className="settings-panel__category">
+          <h3 className="settings-panel__category-title">Startup</h3>
+          <div className="settings-form__field">
+            <span className="settings-form__label">Connect on startup</span>
+            <label className="settings-panel__toggle">
+              <input
+                type="checkbox"
+                checked={autoConnect}
+                onChange={(e) => setAutoConnect(e.target.checked)}
+                data-testid="connection-editor-auto-connect"
+              />
+              <span className="settings-panel__toggle-slider" />
+            </label>
+            <span className="settings-form__hint">
+              Open this connection in a tab when termiHub launches
+            </span>
+          </div>
+        </div>
+      )}
+
       {isAgentDefinitionMode && (
         <div className="settings-panel__category">
           <h3 className="settings-panel__category-title">Session</h3>
@@ -248,6 +248,56 @@ describe("DynamicField", () => {
     });
   });
 
+  describe("multiSelect field", () => {
+    const fallbackField: SettingsField = {
+      key: "authFallback",
+      label: "Fallback Methods",
+      fieldType: {
+        type: "multiSelect",
+        options: [
+          { value: "key", label: "SSH Key" },
+          { value: "password", label: "Password" },
+          { value: "agent", label: "SSH Agent" },
+        ],
+      },
+      required: false,
+    };
+
+    it("lists chosen options in order and offers the rest", () => {
+      renderField(fallbackField, ["agent", "key"], vi.fn());
+      expect(query("field-authFallback-item-0")?.textContent).toBe("SSH Agent");
+      expect(query("field-authFallback-item-1")?.textContent).toBe("SSH Key");
+      const select = query("field-authFallback") as HTMLSelectElement;
+      const optionValues = Array.from(select.options).map((o) => o.value);
+      expect(optionValues).toEqual(["", "password"]);
+    });
+
+    it("appends an option when picked", () => {
+      const onChange = vi.fn();
+      renderField(fallbackField, ["agent"], onChange);
+      const select = query("field-authFallback") as HTMLSelectElement;
+      act(() => {
+        select.value = "password";
+        select.dispatchEvent(new Event("change", { bubbles: true }));
+      });
+      expect(onChange).toHaveBeenCalledWith("authFallback", ["agent", "password"]);
+    });
+
+    it("removes an option on click", () => {
+      const onChange = vi.fn();
+      renderField(fallbackField, ["agent", "password"], onChange);
+      act(() => {
+        (query("field-authFallback-remove-0") as HTMLElement).click();
+      });
+      expect(onChange).toHaveBeenCalledWith("authFallback", ["password"]);
+    });
+
+    it("hides the dropdown when every option is chosen", () => {
+      renderField(fallbackField, ["agent", "password", "key"], vi.fn());
+      expect(query("field-authFallback")).toBeNull();
+    });
+  });
+
   describe("port field", () => {
     it("renders port input with 1-65535 bounds", () => {
       const field: SettingsField = {
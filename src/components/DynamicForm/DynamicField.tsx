@@ -24,7 +24,8 @@ interface DynamicFieldProps {
  * Renders a single settings field based on its `fieldType`.
  *
  * Dispatches to the appropriate input widget (text, password, number,
- * boolean toggle, select, port, file path, key-value list, object list).
+ * boolean toggle, select, multi-select, port, file path, key-value list,
+ * object list).
  * Boolean fields use the toggle-row layout; all others use the column layout.
  */
 export function DynamicField({
@@ -70,6 +71,10 @@ function renderFieldInput(
       return <BooleanField field={field} value={value} onChange={onChange} />;
     case "select":
       return <SelectField field={field} value={value} onChange={onChange} fieldType={fieldType} />;
+    case "multiSelect":
+      return (
+        <MultiSelectField field={field} value={value} onChange={onChange} fieldType={fieldType} />
+      );
     case "port":
       return <PortField field={field} value={value} onChange={onChange} />;
     case "serialPort":
@@ -256,6 +261,66 @@ function SelectField({
   );
 }
 
+/**
+ * Ordered multi-select: chosen options are listed in the order they were
+ * added, and the dropdown below offers the options not yet chosen.
+ */
+function MultiSelectField({
+  field,
+  value,
+  onChange,
+  fieldType,
+}: FieldProps & {
+  fieldType: { type: "multiSelect"; options: { value: string; label: string }[] };
+}) {
+  const selected = (value as string[]) ?? [];
+  const remaining = fieldType.options.filter((opt) => !selected.includes(opt.value));
+  const labelFor = (v: string) => fieldType.options.find((opt) => opt.value === v)?.label ?? v;
+
+  const handleRemove = (index: number) => {
+    onChange(selected.filter((_, i) => i !== index));
+  };
+
+  return (
+    <>
+      <span className="settings-form__label">{field.label}</span>
+      {selected.map((v, index) => (
+        <div key={v} className="settings-form__list-row">
+          <span
+            className="settings-form__list-input"
+            data-testid={`field-${field.key}-item-${index}`}
+          >
+            {labelFor(v)}
+          </span>
+          <button
+            type="button"
+            className="settings-form__list-remove"
+            onClick={() => handleRemove(index)}
+            title="Remove"
+            data-testid={`field-${field.key}-remove-${index}`}
+          >
+            &times;
+          </button>
+        </div>
+      ))}
+      {remaining.length > 0 && (
+        <select
+          value=""
+          onChange={(e) => e.target.value && onChange([...selected, e.target.value])}
+          data-testid={`field-${field.key}`}
+        >
+          <option value="">+ Add</option>
+          {remaining.map((opt) => (
+            <option key={opt.value} value={opt.value}>
+              {opt.label}
+            </option>
+          ))}
+        </select>
+      )}
+    </>
+  );
+}
+
 function PortField({ field, value, onChange }: FieldProps) {
   return (
     <>
@@ -14,6 +14,7 @@ import {
   listLocalSessions,
   listAgentSessions,
   closeTerminal,
+  reattachTerminal,
   closeAgentSession,
   LocalSessionInfo,
   AgentSessionInfo,
@@ -43,6 +44,7 @@ export function OpenConnectionsModal({ open, onOpenChange }: OpenConnectionsModa
   const disconnectSftp = useAppStore((s) => s.disconnectSftp);
   const monitoringHost = useAppStore((s) => s.monitoringHost);
   const disconnectMonitoring = useAppStore((s) => s.disconnectMonitoring);
+  const addTab = useAppStore((s) => s.addTab);
 
   const [localSessions, setLocalSessions] = useState<LocalSessionInfo[]>([]);
   const [agentSessions, setAgentSessions] = useState<AgentSessionsState>({});
@@ -97,15 +99,35 @@ export function OpenConnectionsModal({ open, onOpenChange }: OpenConnectionsModa
     (monitoringHost ? 1 : 0);
 
   const handleKillLocal = async (id: string) => {
-    await closeTerminal(id).catch(() => {});
+    await closeTerminal(id, "kill").catch(() => {});
     setLocalSessions((prev) => prev.filter((s) => s.id !== id));
   };
 
   const handleKillAllLocal = async () => {
-    await Promise.all(localSessions.map((s) => closeTerminal(s.id).catch(() => {})));
+    await Promise.all(localSessions.map((s) => closeTerminal(s.id, "kill").catch(() => {})));
     setLocalSessions([]);
   };
 
+  const handleReattachLocal = async (session: LocalSessionInfo) => {
+    try {
+      await reattachTerminal(session.id);
+    } catch {
+      return;
+    }
+    addTab(
+      session.title,
+      session.connectionType,
+      { type: session.connectionType, config: {} },
+      undefined,
+      undefined,
+      undefined,
+      session.id
+    );
+    setLocalSessions((prev) =>
+      prev.map((s) => (s.id === session.id ? { ...s, detached: false } : s))
+    );
+  };
+
   const handleKillAgent = async (agentId: string) => {
     await disconnectRemoteAgent(agentId);
     setAgentSessions((prev) => {
@@ -183,8 +205,9 @@ export function OpenConnectionsModal({ open, onOpenChange }: OpenConnectionsModa
                     key={s.id}
                     icon={<Terminal size={14} />}
                     title={s.title}
-                    badge={s.alive ? "alive" : "dead"}
+                    badge={!s.alive ? "dead" : s.detached ? "detached" : "alive"}
                     onKill={() => handleKillLocal(s.id)}
+                    onReattach={s.alive && s.detached ? () => handleReattachLocal(s) : undefined}
                   />
                 ))}
               </Section>
@@ -323,16 +346,18 @@ function Section({ title, count, onKillAll, children }: SectionProps) {
   );
 }
 
-type BadgeVariant = "alive" | "dead" | "connected" | "connecting";
+type BadgeVariant = "alive" | "dead" | "detached" | "connected" | "connecting";
 
 interface ConnectionRowProps {
   icon: React.ReactNode;
   title: string;
   badge: BadgeVariant;
   onKill: () => void;
+  /** Shown for sessions kept running after their tab closed. */
+  onReattach?: () => void;
 }
 
-function ConnectionRow({ icon, title, badge, onKill }: ConnectionRowProps) {
+function ConnectionRow({ icon, title, badge, onKill, onReattach }: ConnectionRowProps) {
   return (
     <div className="oc-row">
       <span className="oc-row__icon">{icon}</span>
@@ -340,6 +365,11 @@ function ConnectionRow({ icon, title, badge, onKill }: ConnectionRowProps) {
         {title}
       </span>
       <span className={`oc-row__badge oc-row__badge--${badge}`}>{badge}</span>
+      {onReattach && (
+        <button className="oc-row__reattach" onClick={onReattach}>
+          Reattach
+        </button>
+      )}
       <button className="oc-row__kill" onClick={onKill}>
         Kill
       </button>
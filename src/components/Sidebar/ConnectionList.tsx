@@ -39,7 +39,12 @@ import {
 } from "@/services/api";
 import { frontendLog } from "@/utils/frontendLog";
 import { ConnectionIcon } from "@/utils/connectionIcons";
-import { resolveConnectionCredential } from "@/utils/resolveConnectionCredential";
+import {
+  resolveConnectionCredential,
+  resolveFallbackPassword,
+  usesPasswordFallback,
+} from "@/utils/resolveConnectionCredential";
+import { connectionLaunchConfig } from "@/utils/connectionLaunchConfig";
 import { useSectionResize } from "@/hooks/useSectionResize";
 import { useTreeSelection } from "@/hooks/useTreeSelection";
 import { computeFlatVisibleIds } from "@/utils/computeFlatVisibleIds";
@@ -360,20 +365,22 @@ export function ConnectionList() {
 
   const handleConnect = useCallback(
     async (connection: SavedConnection) => {
-      let config = connection.config;
-      const cfg = config.config as unknown as Record<string, unknown>;
+      let config = connectionLaunchConfig(connection);
+      let cfg = config.config as unknown as Record<string, unknown>;
 
       // Connections with authMethod and password support credential store resolution
       if (cfg.authMethod && cfg.host) {
         const authMethod = cfg.authMethod as string;
         const savePassword = cfg.savePassword as boolean | undefined;
+        const authFallback = cfg.authFallback as string[] | undefined;
+        const passwordFallback = usesPasswordFallback(authMethod, authFallback);
 
         // Before attempting credential resolution, check whether the credential store
         // is locked. If it is, we can't read the stored credential and SSH would fall
         // back to interactive password prompts. Prompt for unlock first and wait —
         // on success the code continues and the credential resolves automatically.
         const needsStoredCredential =
-          authMethod === "password" || (authMethod === "key" && savePassword);
+          authMethod === "password" || (authMethod === "key" && savePassword) || passwordFallback;
         if (needsStoredCredential) {
           const credStatus = useAppStore.getState().credentialStoreStatus;
           if (credStatus?.mode === "master_password" && credStatus?.status === "locked") {
@@ -382,6 +389,31 @@ export function ConnectionList() {
           }
         }
 
+        // The Password fallback method logs in with its own password, as
+        // `password` holds the primary method's secret.
+        if (passwordFallback) {
+          let fallbackPassword = await resolveFallbackPassword(
+            connection.id,
+            authMethod,
+            authFallback
+          );
+          if (fallbackPassword === null) {
+            fallbackPassword = await requestPassword(
+              cfg.host as string,
+              (cfg.username as string) ?? ""
+            );
+            if (fallbackPassword !== null && useAppStore.getState().passwordPromptShouldSave) {
+              await storeCredential(connection.id, "password", fallbackPassword).catch((err) => {
+                frontendLog("connection_list", `Failed to store credential: ${err}`);
+              });
+            }
+          }
+          if (fallbackPassword !== null) {
+            cfg = { ...cfg, fallbackPassword };
+            config = { ...config, config: cfg } as typeof config;
+          }
+        }
+
         // Try to resolve credential from the store first
         const resolution = await resolveConnectionCredential(
           connection.id,
@@ -400,7 +432,7 @@ export function ConnectionList() {
             // Stored credential worked — open tab with existing session
             addTab(
               connection.name,
-              connection.config.type,
+              config.type,
               preConfig,
               undefined,
               undefined,
@@ -420,7 +452,7 @@ export function ConnectionList() {
               // Non-auth failure — let the Terminal component handle the error
               addTab(
                 connection.name,
-                connection.config.type,
+                config.type,
                 config,
                 undefined,
                 undefined,
@@ -449,7 +481,7 @@ export function ConnectionList() {
 
       addTab(
         connection.name,
-        connection.config.type,
+        config.type,
         config,
         undefined,
         undefined,
@@ -20,6 +20,7 @@ vi.mock("@/themes", () => ({
 
 vi.mock("@/services/events", () => ({
   onVscodeEditComplete: vi.fn(() => Promise.resolve(vi.fn())),
+  onEditorEditComplete: vi.fn(() => Promise.resolve(vi.fn())),
 }));
 
 vi.mock("@/services/api", async (importOriginal) => {
@@ -706,6 +707,39 @@ describe("FileBrowser – Copy Name / Copy Path", () => {
     expect(onAction).toHaveBeenCalledWith(fileEntry, "copyName");
   });
 
+  it("triggers externalEditor action for a file but not a directory", () => {
+    const onAction = vi.fn();
+    const renderFor = (entry: typeof fileEntry) =>
+      act(() => {
+        root.render(
+          <FileMenuItems
+            entry={entry}
+            vscodeAvailable={false}
+            onNavigate={vi.fn()}
+            onContextAction={onAction}
+            onPaste={vi.fn()}
+            hasClipboard={false}
+            Item={SimpleItem}
+            Separator={SimpleSeparator}
+            testIdPrefix="file-menu"
+          />
+        );
+      });
+
+    renderFor(dirEntry);
+    expect(container.querySelector('[data-testid="file-menu-external-editor"]')).toBeNull();
+
+    renderFor(fileEntry);
+    const item = container.querySelector(
+      '[data-testid="file-menu-external-editor"]'
+    ) as HTMLElement;
+    act(() => {
+      item.click();
+    });
+
+    expect(onAction).toHaveBeenCalledWith(fileEntry, "externalEditor");
+  });
+
   it("triggers copyPath action when Copy Path is clicked", () => {
     const onAction = vi.fn();
     act(() => {
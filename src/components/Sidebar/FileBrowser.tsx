@@ -27,10 +27,11 @@ import {
   FolderSync,
   Globe,
   Terminal,
+  ExternalLink,
 } from "lucide-react";
 import { useAppStore, getActiveTab } from "@/store/appStore";
 import { useFileBrowser } from "@/hooks/useFileBrowser";
-import { onVscodeEditComplete } from "@/services/events";
+import { onVscodeEditComplete, onEditorEditComplete } from "@/services/events";
 import { getHomeDir, sendInput } from "@/services/api";
 import { FileEntry } from "@/types/connection";
 import type { ShellType } from "@/types/terminal";
@@ -150,6 +151,15 @@ export function FileMenuItems({
           <CodeXml size={14} /> Open in VS Code
         </Item>
       )}
+      {!entry.isDirectory && (
+        <Item
+          className="context-menu__item"
+          onSelect={() => onContextAction(entry, "externalEditor")}
+          data-testid={`${testIdPrefix}-external-editor`}
+        >
+          <ExternalLink size={14} /> Open in External Editor
+        </Item>
+      )}
       <Separator className="context-menu__separator" />
       <Item
         className="context-menu__item"
@@ -675,6 +685,7 @@ export function FileBrowser() {
     deleteEntry,
     renameEntry,
     openInVscode,
+    openInExternalEditor,
     copyEntry,
     cutEntry,
     pasteEntry,
@@ -708,20 +719,21 @@ export function FileBrowser() {
     onConfirm: () => void;
   } | null>(null);
 
-  // Listen for VS Code edit-complete events (remote file re-upload)
+  // Listen for VS Code and external editor edit-complete events (remote file re-upload)
   useEffect(() => {
-    let cleanup: (() => void) | null = null;
-    onVscodeEditComplete((remotePath, success, err) => {
-      if (success) {
-        refresh();
-      } else {
-        console.error(`VS Code edit failed for ${remotePath}:`, err);
-      }
-    }).then((fn) => {
-      cleanup = fn;
-    });
+    const cleanups: (() => void)[] = [];
+    const handleEditComplete =
+      (editor: string) => (remotePath: string, success: boolean, err: string | null) => {
+        if (success) {
+          refresh();
+        } else {
+          console.error(`${editor} edit failed for ${remotePath}:`, err);
+        }
+      };
+    onVscodeEditComplete(handleEditComplete("VS Code")).then((fn) => cleanups.push(fn));
+    onEditorEditComplete(handleEditComplete("External editor")).then((fn) => cleanups.push(fn));
     return () => {
-      if (cleanup) cleanup();
+      cleanups.forEach((fn) => fn());
     };
   }, [refresh]);
 
@@ -763,6 +775,11 @@ export function FileBrowser() {
             console.error("Open in VS Code failed:", err)
           );
           break;
+        case "externalEditor":
+          openInExternalEditor(entry.path).catch((err: unknown) =>
+            console.error("Open in external editor failed:", err)
+          );
+          break;
         case "copy":
           copyEntry([entry]);
           break;
@@ -797,7 +814,17 @@ export function FileBrowser() {
         }
       }
     },
-    [mode, sftpSessionId, downloadFile, openInVscode, copyEntry, cutEntry, renameEntry, deleteEntry]
+    [
+      mode,
+      sftpSessionId,
+      downloadFile,
+      openInVscode,
+      openInExternalEditor,
+      copyEntry,
+      cutEntry,
+      renameEntry,
+      deleteEntry,
+    ]
   );
 
   const handleShareVia = useCallback(
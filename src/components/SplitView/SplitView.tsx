@@ -26,6 +26,7 @@ import {
   ArrowRightLeft,
   Check,
   Palette,
+  Power,
   Stethoscope,
   WifiOff,
   X,
@@ -34,6 +35,8 @@ import { useAppStore } from "@/store/appStore";
 import { PanelNode, LeafPanel, TerminalTab, DropEdge } from "@/types/terminal";
 import { getAllLeaves, findLeafByTab } from "@/utils/panelTree";
 import { isWindows, isMac } from "@/utils/platform";
+import { isRawKeyModeEvent } from "@/utils/rawKeyMode";
+import { closeTerminal } from "@/services/api";
 import { writeText as writeClipboard } from "@tauri-apps/plugin-clipboard-manager";
 import { ConnectionIcon } from "@/utils/connectionIcons";
 import { useTerminalRegistry } from "@/components/Terminal/TerminalRegistry";
@@ -100,7 +103,7 @@ export function SplitView() {
   useEffect(() => {
     if (!zoomedTabId) return;
     const handleKeyDown = (e: KeyboardEvent) => {
-      if (e.key === "Escape") {
+      if (e.key === "Escape" && !isRawKeyModeEvent(e)) {
         e.stopPropagation();
         setZoomedTabId(null);
       }
@@ -531,6 +534,7 @@ function LeafPanelView({ panel, setActivePanel, activeDragTab }: LeafPanelViewPr
 
   const zoomedTabId = useAppStore((s) => s.zoomedTabId);
   const renameTab = useAppStore((s) => s.renameTab);
+  const closeTab = useAppStore((s) => s.closeTab);
   const tabHorizontalScrolling = useAppStore((s) => s.tabHorizontalScrolling);
   const setTabHorizontalScrolling = useAppStore((s) => s.setTabHorizontalScrolling);
   const tabColors = useAppStore((s) => s.tabColors);
@@ -555,6 +559,18 @@ function LeafPanelView({ panel, setActivePanel, activeDragTab }: LeafPanelViewPr
 
   const [colorPickerTabId, setColorPickerTabId] = useState<string | null>(null);
   const [renameTabId, setRenameTabId] = useState<string | null>(null);
+
+  // Ending the session releases its backend even when the cleanup policy
+  // would keep it running after a plain tab close.
+  const endSession = useCallback(
+    (tab: TerminalTab) => {
+      if (tab.sessionId) {
+        closeTerminal(tab.sessionId, "explicit").catch(() => {});
+      }
+      closeTab(tab.id, panel.id);
+    },
+    [closeTab, panel.id]
+  );
   const [contextMenuTabSelection, setContextMenuTabSelection] = useState<string | null>(null);
 
   // Capture selection BEFORE right-click modifies it (xterm auto-selects word on right-click)
@@ -775,6 +791,18 @@ function LeafPanelView({ panel, setActivePanel, activeDragTab }: LeafPanelViewPr
                   >
                     <Palette size={14} /> Set Color...
                   </ContextMenu.Item>
+                  {tab.sessionId && (
+                    <>
+                      <ContextMenu.Separator className="context-menu__separator" />
+                      <ContextMenu.Item
+                        className="context-menu__item"
+                        onSelect={() => endSession(tab)}
+                        data-testid="tab-context-end-session"
+                      >
+                        <Power size={14} /> End Session
+                      </ContextMenu.Item>
+                    </>
+                  )}
                 </ContextMenu.Content>
               </ContextMenu.Portal>
             </ContextMenu.Root>
@@ -32,6 +32,21 @@ function formatKb(kb: number): string {
   return `${(kb / (1024 * 1024)).toFixed(1)} GB`;
 }
 
+/** Memory tooltip: used/total, plus buff/cache and swap when reported. */
+function memoryTooltip(stats: SystemStats): string {
+  const lines = [
+    `Memory: ${formatKb(stats.memoryTotalKb - stats.memoryAvailableKb)} / ${formatKb(stats.memoryTotalKb)}`,
+  ];
+  const cacheKb = (stats.memoryBuffersKb ?? 0) + (stats.memoryCachedKb ?? 0);
+  if (cacheKb > 0) {
+    lines.push(`Buff/cache: ${formatKb(cacheKb)}`);
+  }
+  if (stats.swapTotalKb) {
+    lines.push(`Swap: ${formatKb(stats.swapUsedKb ?? 0)} / ${formatKb(stats.swapTotalKb)}`);
+  }
+  return lines.join("\n");
+}
+
 /** Extract monitoring-relevant connection settings (host, port, username, authMethod, password). */
 function extractMonitoringConfig(config: ConnectionConfig): Record<string, unknown> | null {
   const cfg = config.config;
@@ -462,7 +477,7 @@ function MonitoringStatus() {
           </span>
           <span
             className={`status-bar__item monitoring-status__stat monitoring-status__stat--${severityLevel(monitoringStats.memoryUsedPercent)}`}
-            title={`Memory: ${formatKb(monitoringStats.memoryTotalKb - monitoringStats.memoryAvailableKb)} / ${formatKb(monitoringStats.memoryTotalKb)}`}
+            title={memoryTooltip(monitoringStats)}
             data-testid="monitoring-mem"
           >
             Mem {monitoringStats.memoryUsedPercent.toFixed(0)}%
@@ -542,6 +557,21 @@ function MonitoringDetailDropdown({
                     {stats.loadAverage.map((v) => v.toFixed(2)).join(" ")}
                   </span>
                 </div>
+                {!!stats.processCount && (
+                  <div className="monitoring-menu__row">
+                    <span className="monitoring-menu__label">Processes</span>
+                    <span className="monitoring-menu__value">{stats.processCount}</span>
+                  </div>
+                )}
+                {!!(stats.networkRxBytes || stats.networkTxBytes) && (
+                  <div className="monitoring-menu__row">
+                    <span className="monitoring-menu__label">Network</span>
+                    <span className="monitoring-menu__value">
+                      ↓ {formatKb(Math.round((stats.networkRxBytes ?? 0) / 1024))} ↑{" "}
+                      {formatKb(Math.round((stats.networkTxBytes ?? 0) / 1024))}
+                    </span>
+                  </div>
+                )}
               </div>
               <DropdownMenu.Separator className="monitoring-menu__separator" />
             </>
@@ -105,6 +105,7 @@ vi.mock("@/services/events", () => ({
     init: vi.fn().mockResolvedValue(undefined),
     subscribeOutput: vi.fn(() => vi.fn()),
     subscribeExit: vi.fn(() => vi.fn()),
+    subscribeNotice: vi.fn(() => vi.fn()),
   },
 }));
 
@@ -13,6 +13,7 @@ import { useAppStore } from "@/store/appStore";
 import { getXtermTheme } from "@/themes";
 import { processKeyEvent, isAppShortcut, isChordPending } from "@/services/keybindings";
 import { frontendLog } from "@/utils/frontendLog";
+import { RAW_KEY_MODE_ATTRIBUTE } from "@/utils/rawKeyMode";
 
 const HORIZONTAL_SCROLL_COLS = 500;
 
@@ -138,6 +139,12 @@ export function Terminal({
   // destroying the live xterm and leaving a blank terminal. Using a mount-time
   // ref keeps setupTerminal stable after the initial connect.
   const initialSessionIdRef = useRef(existingSessionId);
+  // Raw key mode sends every keystroke to the session, application shortcuts
+  // included, for TUI programs that need them.
+  const rawKeyMode = config.config?.rawKeyMode === true;
+  // Read by the key handler and the terminal element setup through a ref so
+  // that toggling the setting does not recreate the xterm instance.
+  const rawKeyModeRef = useRef(rawKeyMode);
   const {
     register,
     unregister,
@@ -158,6 +165,11 @@ export function Terminal({
     });
   }, [tabId]);
 
+  useEffect(() => {
+    rawKeyModeRef.current = rawKeyMode;
+    terminalElRef.current?.toggleAttribute(RAW_KEY_MODE_ATTRIBUTE, rawKeyMode);
+  }, [rawKeyMode]);
+
   const setupTerminal = useCallback(
     async (xterm: XTerm, fitAddon: FitAddon, isCanceled: () => boolean) => {
       // Cancel any pending session close from a StrictMode unmount cycle
@@ -236,7 +248,8 @@ export function Terminal({
               resolved = await createTerminal(sessionConfig);
 
               if (isCanceled()) {
-                closeTerminal(resolved);
+                // Never attached to a tab, so nothing could reattach it.
+                closeTerminal(resolved, "kill");
                 return;
               }
               // Success — clear all pre-connect overlay state.
@@ -371,6 +384,12 @@ export function Terminal({
           useAppStore.getState().setTerminalExited(tabId);
         });
 
+        // Show session notices (e.g. features turned off for this session)
+        // as a highlighted line in the terminal
+        const unsubNotice = terminalDispatcher.subscribeNotice(sessionId, (_code, message) => {
+          xterm.writeln(`\x1b[33m[${message}]\x1b[0m`);
+        });
+
         // Send user input to backend
         const onDataDisposable = xterm.onData((data) => {
           lastInputTimeRef.current = Date.now();
@@ -424,6 +443,7 @@ export function Terminal({
         cleanupRef.current = () => {
           unsubOutput();
           unsubExit();
+          unsubNotice();
           // Cancel pending RAF and flush remaining buffered output
           if (rafId !== null) {
             cancelAnimationFrame(rafId);
@@ -469,6 +489,7 @@ export function Terminal({
     const el = document.createElement("div");
     el.style.position = "absolute";
     el.style.inset = "0";
+    el.toggleAttribute(RAW_KEY_MODE_ATTRIBUTE, rawKeyModeRef.current);
     terminalElRef.current = el;
 
     // Park the element so xterm.open() has a DOM parent
@@ -511,6 +532,13 @@ export function Terminal({
         return false;
       }
 
+      // Hand the key to the session without letting the global shortcut
+      // handlers see it.
+      if (rawKeyModeRef.current) {
+        e.stopPropagation();
+        return true;
+      }
+
       // If a chord is pending, block the key from xterm
       if (isChordPending()) {
         return false;
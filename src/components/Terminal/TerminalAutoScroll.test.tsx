@@ -93,6 +93,7 @@ vi.mock("@/services/events", () => ({
       return vi.fn();
     }),
     subscribeExit: vi.fn(() => vi.fn()),
+    subscribeNotice: vi.fn(() => vi.fn()),
   },
 }));
 
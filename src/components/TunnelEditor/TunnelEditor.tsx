@@ -34,6 +34,7 @@ const DEFAULT_REMOTE: RemoteForwardConfig = {
 const DEFAULT_DYNAMIC: DynamicForwardConfig = {
   localHost: "127.0.0.1",
   localPort: 1080,
+  enableUdp: false,
 };
 
 function defaultTunnelType(type: "local" | "remote" | "dynamic"): TunnelType {
@@ -91,7 +92,7 @@ export function TunnelEditor({ tabId, meta, isVisible }: TunnelEditorProps) {
     [tunnelType.type]
   );
 
-  const updateConfig = useCallback((field: string, value: string | number) => {
+  const updateConfig = useCallback((field: string, value: string | number | boolean) => {
     setTunnelType((prev) => {
       switch (prev.type) {
         case "local":
@@ -107,6 +108,8 @@ export function TunnelEditor({ tabId, meta, isVisible }: TunnelEditorProps) {
   const handleSave = useCallback(
     async (andStart: boolean) => {
       const config: TunnelConfig = {
+        // Keep settings the editor does not show, e.g. the reconnect backoff.
+        ...existingTunnel,
         id: existingTunnel?.id ?? `tun-${Date.now()}-${Math.random().toString(36).slice(2, 6)}`,
         name: name || "Untitled Tunnel",
         sshConnectionId,
@@ -345,6 +348,18 @@ export function TunnelEditor({ tabId, meta, isVisible }: TunnelEditorProps) {
                 />
               </div>
             </div>
+            <div className="tunnel-editor__checkbox-row">
+              <input
+                className="tunnel-editor__checkbox"
+                type="checkbox"
+                id={`enable-udp-${tabId}`}
+                checked={tunnelType.config.enableUdp ?? false}
+                onChange={(e) => updateConfig("enableUdp", e.target.checked)}
+              />
+              <label className="tunnel-editor__checkbox-label" htmlFor={`enable-udp-${tabId}`}>
+                Allow UDP (requires <code>nc</code> on the server)
+              </label>
+            </div>
           </>
         )}
 
@@ -0,0 +1,149 @@
+import { describe, it, expect, vi, beforeEach, afterEach } from "vitest";
+import { act, createElement } from "react";
+import { createRoot, Root } from "react-dom/client";
+
+vi.mock("@/services/events", () => ({
+  onConnectionAutoConnect: vi.fn(),
+  onSessionMonitoringStats: vi.fn(),
+}));
+
+vi.mock("@/services/api", () => ({
+  connectAutoConnections: vi.fn(() => Promise.resolve()),
+  sftpOpen: vi.fn(),
+  sftpClose: vi.fn(),
+  sftpListDir: vi.fn(),
+  localListDir: vi.fn(),
+  vscodeAvailable: vi.fn(() => Promise.resolve(false)),
+}));
+
+vi.mock("@/utils/frontendLog", () => ({
+  frontendLog: vi.fn(),
+}));
+
+import { onConnectionAutoConnect } from "@/services/events";
+import { connectAutoConnections } from "@/services/api";
+import { useAppStore } from "@/store/appStore";
+import type { GroupMemberResult, SavedConnection } from "@/types/connection";
+import { useAutoConnect } from "./useAutoConnect";
+
+const mockOnAutoConnect = vi.mocked(onConnectionAutoConnect);
+const mockConnect = vi.mocked(connectAutoConnections);
+
+const DB: SavedConnection = {
+  id: "Work/db",
+  name: "db",
+  config: { type: "ssh", config: { host: "db.example.com" } },
+  folderId: null,
+  terminalOptions: { fontSize: 15 },
+  autoConnect: true,
+};
+
+function HookConsumer({ enabled }: { enabled: boolean }) {
+  useAutoConnect(enabled);
+  return null;
+}
+
+describe("useAutoConnect", () => {
+  let container: HTMLDivElement;
+  let root: Root;
+  let handler: ((result: GroupMemberResult) => void) | undefined;
+  const unlisten = vi.fn();
+  const addTab = vi.fn();
+
+  beforeEach(() => {
+    vi.clearAllMocks();
+    useAppStore.setState({ ...useAppStore.getInitialState(), connections: [DB], addTab });
+
+    mockOnAutoConnect.mockImplementation((cb) => {
+      handler = cb;
+      return Promise.resolve(unlisten);
+    });
+
+    container = document.createElement("div");
+    document.body.appendChild(container);
+    root = createRoot(container);
+  });
+
+  afterEach(() => {
+    act(() => root.unmount());
+    container.remove();
+  });
+
+  it("does nothing until enabled", async () => {
+    await act(async () => {
+      root.render(createElement(HookConsumer, { enabled: false }));
+    });
+
+    expect(mockOnAutoConnect).not.toHaveBeenCalled();
+    expect(mockConnect).not.toHaveBeenCalled();
+  });
+
+  it("starts connecting after the listener is registered", async () => {
+    await act(async () => {
+      root.render(createElement(HookConsumer, { enabled: true }));
+    });
+
+    expect(mockOnAutoConnect).toHaveBeenCalledTimes(1);
+    expect(mockConnect).toHaveBeenCalledTimes(1);
+    expect(mockOnAutoConnect.mock.invocationCallOrder[0]).toBeLessThan(
+      mockConnect.mock.invocationCallOrder[0]
+    );
+  });
+
+  it("opens a tab attached to the connected session", async () => {
+    await act(async () => {
+      root.render(createElement(HookConsumer, { enabled: true }));
+    });
+
+    act(() => handler?.({ connectionId: "Work/db", sessionId: "sess-1" }));
+
+    expect(addTab).toHaveBeenCalledWith(
+      "db",
+      "ssh",
+      DB.config,
+      undefined,
+      undefined,
+      DB.terminalOptions,
+      "sess-1"
+    );
+  });
+
+  it("opens an unconnected tab when the connection failed", async () => {
+    await act(async () => {
+      root.render(createElement(HookConsumer, { enabled: true }));
+    });
+
+    act(() => handler?.({ connectionId: "Work/db", error: "Connection refused" }));
+
+    expect(addTab).toHaveBeenCalledWith(
+      "db",
+      "ssh",
+      DB.config,
+      undefined,
+      undefined,
+      DB.terminalOptions,
+      null
+    );
+  });
+
+  it("ignores results for unknown connections", async () => {
+    await act(async () => {
+      root.render(createElement(HookConsumer, { enabled: true }));
+    });
+
+    act(() => handler?.({ connectionId: "gone", sessionId: "sess-2" }));
+
+    expect(addTab).not.toHaveBeenCalled();
+  });
+
+  it("unsubscribes listener on unmount", async () => {
+    await act(async () => {
+      root.render(createElement(HookConsumer, { enabled: true }));
+    });
+
+    act(() => root.unmount());
+    root = createRoot(container);
+
+    expect(unlisten).toHaveBeenCalledTimes(1);
+  });
+});
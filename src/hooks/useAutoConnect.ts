@@ -0,0 +1,64 @@
+import { useEffect } from "react";
+import { useAppStore } from "@/store/appStore";
+import { connectAutoConnections } from "@/services/api";
+import { onConnectionAutoConnect } from "@/services/events";
+import { GroupMemberResult } from "@/types/connection";
+import { frontendLog } from "@/utils/frontendLog";
+
+/**
+ * Open a tab for a connection opened at startup. A connected session is
+ * attached to the tab; after a failure the tab connects on its own so the
+ * Terminal component shows the error.
+ */
+function openAutoConnectedTab(result: GroupMemberResult): void {
+  const { connections, addTab } = useAppStore.getState();
+  const connection = connections.find((c) => c.id === result.connectionId);
+  if (!connection) {
+    frontendLog("auto_connect", `Unknown auto-connect connection ${result.connectionId}`);
+    return;
+  }
+  if (result.error) {
+    frontendLog("auto_connect", `Failed to auto-connect ${connection.name}: ${result.error}`);
+  }
+  addTab(
+    connection.name,
+    connection.config.type,
+    connection.config,
+    undefined,
+    undefined,
+    connection.terminalOptions,
+    result.sessionId ?? null
+  );
+}
+
+/**
+ * Hook that opens the connections marked `autoConnect` in tabs once
+ * `enabled` is set, i.e. after the saved connections have been loaded.
+ */
+export function useAutoConnect(enabled: boolean): void {
+  useEffect(() => {
+    if (!enabled) return;
+    let unlisten: (() => void) | null = null;
+    let cancelled = false;
+
+    const setup = async () => {
+      const stop = await onConnectionAutoConnect(openAutoConnectedTab);
+      if (cancelled) {
+        stop();
+        return;
+      }
+      unlisten = stop;
+      // Only start connecting once the listener is registered.
+      await connectAutoConnections();
+    };
+
+    setup().catch((err: unknown) => {
+      frontendLog("auto_connect", `Failed to auto-connect: ${err}`);
+    });
+
+    return () => {
+      cancelled = true;
+      unlisten?.();
+    };
+  }, [enabled]);
+}
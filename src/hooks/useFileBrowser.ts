@@ -43,6 +43,7 @@ export function useFileBrowser() {
     deleteEntry: async () => {},
     renameEntry: async () => {},
     openInVscode: async () => {},
+    openInExternalEditor: async () => {},
     copyEntry: () => {},
     cutEntry: () => {},
     pasteEntry: async () => {},
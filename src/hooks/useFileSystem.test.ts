@@ -35,6 +35,7 @@ vi.mock("@/services/api", () => ({
   sftpRename: vi.fn(() => Promise.resolve()),
   sftpWriteFileContent: vi.fn(() => Promise.resolve()),
   vscodeOpenRemote: vi.fn(() => Promise.resolve()),
+  sftpOpenInEditor: vi.fn(() => Promise.resolve()),
 }));
 
 vi.mock("@tauri-apps/plugin-dialog", () => ({
@@ -9,8 +9,10 @@ import {
   sftpRename,
   sftpWriteFileContent,
   vscodeOpenRemote,
+  sftpOpenInEditor,
 } from "@/services/api";
 import { FileEntry } from "@/types/connection";
+import { renameWithOverwritePrompt } from "@/utils/renameWithOverwritePrompt";
 
 /**
  * Hook for SFTP file system operations.
@@ -107,7 +109,10 @@ export function useFileSystem() {
       if (!sftpSessionId) return;
       const parentDir = oldPath.split("/").slice(0, -1).join("/") || "/";
       const newPath = parentDir === "/" ? `/${newName}` : `${parentDir}/${newName}`;
-      await sftpRename(sftpSessionId, oldPath, newPath);
+      await renameWithOverwritePrompt(
+        (overwrite) => sftpRename(sftpSessionId, oldPath, newPath, overwrite),
+        newPath
+      );
       refreshSftp();
     },
     [sftpSessionId, refreshSftp]
@@ -121,6 +126,14 @@ export function useFileSystem() {
     [sftpSessionId]
   );
 
+  const openInExternalEditor = useCallback(
+    async (remotePath: string) => {
+      if (!sftpSessionId) return;
+      await sftpOpenInEditor(sftpSessionId, remotePath);
+    },
+    [sftpSessionId]
+  );
+
   const copyEntry = useCallback(
     (entries: FileEntry[]) => {
       useAppStore.getState().setFileClipboard({
@@ -160,7 +173,10 @@ export function useFileSystem() {
         // sftp→sftp
         if (clipboard.operation === "cut") {
           if (clipboard.sftpSessionId === sftpSessionId) {
-            await sftpRename(sftpSessionId, clipEntry.path, destPath);
+            await renameWithOverwritePrompt(
+              (overwrite) => sftpRename(sftpSessionId, clipEntry.path, destPath, overwrite),
+              destPath
+            );
           } else {
             // Different SFTP session — download to temp then upload
             const tempPath = `/tmp/termihub-paste-${Date.now()}-${clipEntry.name}`;
@@ -205,6 +221,7 @@ export function useFileSystem() {
     deleteEntry,
     renameEntry,
     openInVscode,
+    openInExternalEditor,
     copyEntry,
     cutEntry,
     pasteEntry,
@@ -41,6 +41,7 @@ vi.mock("@/services/api", () => ({
 import { processKeyEvent, cancelChord } from "@/services/keybindings";
 import { useAppStore } from "@/store/appStore";
 import { getAllLeaves } from "@/utils/panelTree";
+import { RAW_KEY_MODE_ATTRIBUTE } from "@/utils/rawKeyMode";
 import { useKeyboardShortcuts } from "./useKeyboardShortcuts";
 
 const mockProcessKeyEvent = vi.mocked(processKeyEvent);
@@ -296,6 +297,43 @@ describe("useKeyboardShortcuts", () => {
     });
   });
 
+  describe("zoom capture", () => {
+    it("toggles zoom on Ctrl+Shift+Enter", () => {
+      act(() => {
+        root.render(createElement(KeyboardHarness));
+      });
+      const toggle = vi.spyOn(useAppStore.getState(), "toggleZoomActiveTab");
+
+      const prevented = fireKey("Enter", { ctrlKey: true, shiftKey: true });
+
+      expect(prevented).toBe(true);
+      expect(toggle).toHaveBeenCalled();
+    });
+
+    it("leaves the key to a terminal in raw key mode", () => {
+      act(() => {
+        root.render(createElement(KeyboardHarness));
+      });
+      const toggle = vi.spyOn(useAppStore.getState(), "toggleZoomActiveTab");
+      const terminal = document.createElement("div");
+      terminal.setAttribute(RAW_KEY_MODE_ATTRIBUTE, "");
+      document.body.appendChild(terminal);
+
+      const event = new KeyboardEvent("keydown", {
+        key: "Enter",
+        ctrlKey: true,
+        shiftKey: true,
+        bubbles: true,
+        cancelable: true,
+      });
+      terminal.dispatchEvent(event);
+      terminal.remove();
+
+      expect(event.defaultPrevented).toBe(false);
+      expect(toggle).not.toHaveBeenCalled();
+    });
+  });
+
   describe("tab groups", () => {
     it("adds a new tab group on new-tab-group", () => {
       act(() => {
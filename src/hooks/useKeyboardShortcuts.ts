@@ -2,6 +2,7 @@ import { useEffect } from "react";
 import { useAppStore } from "@/store/appStore";
 import { getAllLeaves, findAdjacentLeaf, FocusDirection } from "@/utils/panelTree";
 import { processKeyEvent, onChordStateChange, cancelChord } from "@/services/keybindings";
+import { isRawKeyModeEvent } from "@/utils/rawKeyMode";
 
 /**
  * Global keyboard shortcuts for the application.
@@ -15,6 +16,7 @@ export function useKeyboardShortcuts() {
   useEffect(() => {
     const handleZoomCapture = (e: KeyboardEvent) => {
       if (e.key !== "Enter" || !e.shiftKey || (!e.metaKey && !e.ctrlKey) || e.altKey) return;
+      if (isRawKeyModeEvent(e)) return;
       e.preventDefault();
       e.stopPropagation();
       cancelChord();
@@ -34,6 +34,7 @@ vi.mock("@/services/api", () => ({
   localCopyFile: vi.fn(() => Promise.resolve()),
   vscodeAvailable: vi.fn(() => Promise.resolve(false)),
   vscodeOpenLocal: vi.fn(() => Promise.resolve()),
+  openInEditor: vi.fn(() => Promise.resolve()),
   sftpDownload: vi.fn(() => Promise.resolve()),
 }));
 
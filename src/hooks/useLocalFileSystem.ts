@@ -8,9 +8,11 @@ import {
   localWriteFile,
   localCopyFile,
   vscodeOpenLocal,
+  openInEditor,
   sftpDownload,
 } from "@/services/api";
 import { FileEntry } from "@/types/connection";
+import { renameWithOverwritePrompt } from "@/utils/renameWithOverwritePrompt";
 
 /**
  * Hook for local filesystem operations.
@@ -83,7 +85,10 @@ export function useLocalFileSystem() {
     async (oldPath: string, newName: string) => {
       const parentDir = oldPath.split("/").slice(0, -1).join("/") || "/";
       const newPath = parentDir === "/" ? `/${newName}` : `${parentDir}/${newName}`;
-      await localRename(oldPath, newPath);
+      await renameWithOverwritePrompt(
+        (overwrite) => localRename(oldPath, newPath, overwrite),
+        newPath
+      );
       refreshLocal();
     },
     [refreshLocal]
@@ -93,6 +98,10 @@ export function useLocalFileSystem() {
     await vscodeOpenLocal(path);
   }, []);
 
+  const openInExternalEditor = useCallback(async (path: string) => {
+    await openInEditor(path);
+  }, []);
+
   const uploadFileFromPath = useCallback(
     async (localPath: string) => {
       const parts = localPath.replace(/\\/g, "/").split("/");
@@ -153,7 +162,10 @@ export function useLocalFileSystem() {
       if (clipboard.sourceMode === "local") {
         // local→local
         if (clipboard.operation === "cut") {
-          await localRename(clipEntry.path, destPath);
+          await renameWithOverwritePrompt(
+            (overwrite) => localRename(clipEntry.path, destPath, overwrite),
+            destPath
+          );
         } else {
           await localCopyFile(clipEntry.path, destPath, clipEntry.isDirectory);
         }
@@ -191,6 +203,7 @@ export function useLocalFileSystem() {
     deleteEntry,
     renameEntry,
     openInVscode,
+    openInExternalEditor,
     copyEntry,
     cutEntry,
     pasteEntry,
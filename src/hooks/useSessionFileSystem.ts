@@ -9,6 +9,7 @@ import {
   sessionMkdir,
 } from "@/services/api";
 import { FileEntry } from "@/types/connection";
+import { renameWithOverwritePrompt } from "@/utils/renameWithOverwritePrompt";
 
 /**
  * Hook for session-based file system operations.
@@ -120,7 +121,10 @@ export function useSessionFileSystem() {
       if (!sessionFileBrowserId) return;
       const parentDir = oldPath.split("/").slice(0, -1).join("/") || "/";
       const newPath = parentDir === "/" ? `/${newName}` : `${parentDir}/${newName}`;
-      await sessionRenameFile(sessionFileBrowserId, oldPath, newPath);
+      await renameWithOverwritePrompt(
+        (overwrite) => sessionRenameFile(sessionFileBrowserId, oldPath, newPath, overwrite),
+        newPath
+      );
       refreshSession();
     },
     [sessionFileBrowserId, refreshSession]
@@ -130,6 +134,10 @@ export function useSessionFileSystem() {
     // VS Code remote open is not supported for session-based connections.
   }, []);
 
+  const openInExternalEditor = useCallback(async (_remotePath: string) => {
+    // External editing is not supported for session-based connections.
+  }, []);
+
   const copyEntry = useCallback(
     (entries: FileEntry[]) => {
       useAppStore.getState().setFileClipboard({
@@ -170,7 +178,11 @@ export function useSessionFileSystem() {
       if (clipboard.sourceMode === "session") {
         const srcId = clipboard.terminalSessionId;
         if (clipboard.operation === "cut" && srcId === sessionFileBrowserId) {
-          await sessionRenameFile(sessionFileBrowserId, clipEntry.path, destPath);
+          await renameWithOverwritePrompt(
+            (overwrite) =>
+              sessionRenameFile(sessionFileBrowserId, clipEntry.path, destPath, overwrite),
+            destPath
+          );
         } else {
           // Cross-session or copy: read source, write to dest
           const srcSession = srcId ?? sessionFileBrowserId;
@@ -213,6 +225,7 @@ export function useSessionFileSystem() {
     deleteEntry,
     renameEntry,
     openInVscode,
+    openInExternalEditor,
     copyEntry,
     cutEntry,
     pasteEntry,
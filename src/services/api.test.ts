@@ -11,12 +11,33 @@ const mockedInvoke = vi.mocked(invoke);
 import {
   createTerminal,
   createConnection,
+  connectGroup,
+  saveSessionLayout,
+  restoreSessionLayout,
   getConnectionTypes,
   sendInput,
   resizeTerminal,
+  sendLineControl,
+  getSerialModemStatus,
   closeTerminal,
+  reattachTerminal,
+  restartSession,
+  listActiveSessions,
+  startInputRecording,
+  stopInputRecording,
+  replayInput,
+  sendFileToSession,
+  extractSessionText,
+  searchAllSessions,
+  startOutputLog,
+  stopOutputLog,
+  startSessionRecording,
+  stopSessionRecording,
+  pauseSessionOutput,
+  resumeSessionOutput,
   listSerialPorts,
   listAvailableShells,
+  listWslDistributions,
   checkX11Available,
   checkSshAgentStatus,
   loadConnectionsAndFolders,
@@ -29,12 +50,20 @@ import {
   getSettings,
   saveSettings,
   saveExternalFile,
+  addFileBookmark,
+  listFileBookmarks,
+  removeFileBookmark,
   reloadExternalConnections,
   sftpOpen,
   sftpClose,
   sftpListDir,
+  sftpInitialDir,
   sftpDownload,
+  sessionDownloadArchive,
+  compareFiles,
   sftpUpload,
+  sftpDownloadDir,
+  sftpUploadDir,
   sftpMkdir,
   sftpDelete,
   sftpRename,
@@ -50,7 +79,11 @@ import {
   vscodeAvailable,
   vscodeOpenLocal,
   vscodeOpenRemote,
+  openInEditor,
+  sftpOpenInEditor,
   validateSshKey,
+  generateSshKey,
+  sshRunCommand,
   checkDockerAvailable,
   listDockerImages,
   checkPodmanAvailable,
@@ -67,6 +100,9 @@ import {
   switchCredentialStore,
   resolveCredential,
   removeCredential,
+  listConnectionCredentials,
+  exportCredentialsEncrypted,
+  importCredentialsEncrypted,
 } from "./api";
 
 describe("api service", () => {
@@ -101,6 +137,55 @@ describe("api service", () => {
       expect(result).toBe("session-789");
     });
 
+    it("connectGroup invokes with group ID and launch mode", async () => {
+      const results = [
+        { connectionId: "conn-a", sessionId: "session-1" },
+        { connectionId: "conn-b", error: "Connection refused" },
+      ];
+      mockedInvoke.mockResolvedValue(results);
+
+      const result = await connectGroup("group-1", true);
+
+      expect(mockedInvoke).toHaveBeenCalledWith("connect_group", {
+        groupId: "group-1",
+        parallel: true,
+      });
+      expect(result).toEqual(results);
+    });
+
+    it("connectGroup defaults to backend launch mode", async () => {
+      mockedInvoke.mockResolvedValue([]);
+
+      await connectGroup("group-1");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("connect_group", {
+        groupId: "group-1",
+        parallel: null,
+      });
+    });
+
+    it("session layouts save entries and restore by name", async () => {
+      const restored = {
+        sessions: [{ connectionId: "conn-a", sessionId: "session-1", active: true }],
+        missingConnectionIds: ["conn-gone"],
+      };
+      mockedInvoke.mockResolvedValueOnce(undefined).mockResolvedValueOnce(restored);
+      const sessions = [
+        { connectionId: "conn-a", active: true },
+        { connectionId: "conn-gone", active: false },
+      ];
+
+      await saveSessionLayout("Morning", sessions);
+      const result = await restoreSessionLayout("Morning");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("save_session_layout", {
+        name: "Morning",
+        sessions,
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("restore_session_layout", { name: "Morning" });
+      expect(result).toEqual(restored);
+    });
+
     it("getConnectionTypes returns available types", async () => {
       const types = [
         { typeId: "local", displayName: "Local Shell", icon: "terminal", settingsSchema: {} },
@@ -172,6 +257,27 @@ describe("api service", () => {
       });
     });
 
+    it("sendLineControl invokes with session ID and control", async () => {
+      mockedInvoke.mockResolvedValue(undefined);
+
+      await sendLineControl("session-1", { type: "break", durationMs: 250 });
+
+      expect(mockedInvoke).toHaveBeenCalledWith("send_line_control", {
+        sessionId: "session-1",
+        control: { type: "break", durationMs: 250 },
+      });
+    });
+
+    it("getSerialModemStatus invokes with session ID and returns the lines", async () => {
+      const status = { cts: true, dsr: false, dcd: true, ri: false };
+      mockedInvoke.mockResolvedValue(status);
+
+      const result = await getSerialModemStatus("session-1");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("serial_modem_status", { sessionId: "session-1" });
+      expect(result).toEqual(status);
+    });
+
     it("closeTerminal invokes with session ID", async () => {
       mockedInvoke.mockResolvedValue(undefined);
 
@@ -179,6 +285,215 @@ describe("api service", () => {
 
       expect(mockedInvoke).toHaveBeenCalledWith("close_terminal", {
         sessionId: "session-1",
+        trigger: "tabClosed",
+      });
+    });
+
+    it("closeTerminal passes the close trigger", async () => {
+      mockedInvoke.mockResolvedValue(undefined);
+
+      await closeTerminal("session-1", "explicit");
+      await closeTerminal("session-2", "kill");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("close_terminal", {
+        sessionId: "session-1",
+        trigger: "explicit",
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("close_terminal", {
+        sessionId: "session-2",
+        trigger: "kill",
+      });
+    });
+
+    it("reattachTerminal invokes with session ID", async () => {
+      mockedInvoke.mockResolvedValue(undefined);
+
+      await reattachTerminal("session-1");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("reattach_terminal", {
+        sessionId: "session-1",
+      });
+    });
+
+    it("restartSession clears scrollback by default", async () => {
+      mockedInvoke.mockResolvedValue(undefined);
+
+      await restartSession("session-1");
+      await restartSession("session-2", false);
+
+      expect(mockedInvoke).toHaveBeenCalledWith("session_restart", {
+        sessionId: "session-1",
+        clearScrollback: true,
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("session_restart", {
+        sessionId: "session-2",
+        clearScrollback: false,
+      });
+    });
+
+    it("listActiveSessions returns session metadata", async () => {
+      const sessions = [
+        {
+          id: "session-1",
+          type: "local",
+          title: "bash",
+          connectedSince: "2026-01-01T00:00:00+00:00",
+          cols: 120,
+          rows: 40,
+          capabilities: { monitoring: false, fileBrowser: true, resize: true, persistent: false },
+          detached: false,
+        },
+      ];
+      mockedInvoke.mockResolvedValue(sessions);
+
+      const result = await listActiveSessions();
+
+      expect(mockedInvoke).toHaveBeenCalledWith("list_active_sessions");
+      expect(result).toEqual(sessions);
+    });
+
+    it("input recording commands pass session and path", async () => {
+      mockedInvoke.mockResolvedValueOnce(undefined).mockResolvedValueOnce(true);
+
+      await startInputRecording("session-1", "/tmp/input.cast");
+      const stopped = await stopInputRecording("session-1");
+
+      expect(stopped).toBe(true);
+      expect(mockedInvoke).toHaveBeenCalledWith("session_start_input_recording", {
+        sessionId: "session-1",
+        path: "/tmp/input.cast",
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("session_stop_input_recording", {
+        sessionId: "session-1",
+      });
+    });
+
+    it("sendFileToSession invokes session_send_file with protocol and delay", async () => {
+      mockedInvoke.mockResolvedValue(4096);
+
+      const sent = await sendFileToSession("session-1", "/tmp/fw.bin", "xmodem");
+      await sendFileToSession("session-1", "/tmp/cmds.txt", "ascii", 50);
+
+      expect(sent).toBe(4096);
+      expect(mockedInvoke).toHaveBeenCalledWith("session_send_file", {
+        sessionId: "session-1",
+        path: "/tmp/fw.bin",
+        protocol: "xmodem",
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("session_send_file", {
+        sessionId: "session-1",
+        path: "/tmp/cmds.txt",
+        protocol: "ascii",
+        lineDelayMs: 50,
+      });
+    });
+
+    it("replayInput defaults to original speed", async () => {
+      mockedInvoke.mockResolvedValue(3);
+
+      const count = await replayInput("session-1", "/tmp/input.cast");
+      await replayInput("session-1", "/tmp/input.cast", 4);
+
+      expect(count).toBe(3);
+      expect(mockedInvoke).toHaveBeenCalledWith("session_replay_input", {
+        sessionId: "session-1",
+        path: "/tmp/input.cast",
+        speed: 1,
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("session_replay_input", {
+        sessionId: "session-1",
+        path: "/tmp/input.cast",
+        speed: 4,
+      });
+    });
+
+    it("extractSessionText invokes with the row range", async () => {
+      mockedInvoke.mockResolvedValue("line one\nline two");
+
+      const text = await extractSessionText("session-1", 3, 5);
+
+      expect(text).toBe("line one\nline two");
+      expect(mockedInvoke).toHaveBeenCalledWith("session_extract_text", {
+        sessionId: "session-1",
+        start: 3,
+        end: 5,
+      });
+    });
+
+    it("searchAllSessions invokes search_all_sessions", async () => {
+      const results = [{ sessionId: "session-1", matchCount: 2, firstMatchPreview: "error: x" }];
+      mockedInvoke.mockResolvedValue(results);
+
+      expect(await searchAllSessions("error", true)).toEqual(results);
+      expect(mockedInvoke).toHaveBeenCalledWith("search_all_sessions", {
+        needle: "error",
+        caseInsensitive: true,
+      });
+    });
+
+    it("output log commands default to the raw stream", async () => {
+      mockedInvoke
+        .mockResolvedValueOnce(undefined)
+        .mockResolvedValueOnce(undefined)
+        .mockResolvedValueOnce(false);
+
+      await startOutputLog("session-1", "/tmp/out.log");
+      await startOutputLog("session-1", "/tmp/out.log", true);
+      const stopped = await stopOutputLog("session-1");
+
+      expect(stopped).toBe(false);
+      expect(mockedInvoke).toHaveBeenCalledWith("session_start_output_log", {
+        sessionId: "session-1",
+        path: "/tmp/out.log",
+        collapseCarriageReturns: false,
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("session_start_output_log", {
+        sessionId: "session-1",
+        path: "/tmp/out.log",
+        collapseCarriageReturns: true,
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("session_stop_output_log", {
+        sessionId: "session-1",
+      });
+    });
+
+    it("session recording commands leave input out by default", async () => {
+      mockedInvoke
+        .mockResolvedValueOnce(undefined)
+        .mockResolvedValueOnce(undefined)
+        .mockResolvedValueOnce(true);
+
+      await startSessionRecording("session-1", "/tmp/session.cast");
+      await startSessionRecording("session-1", "/tmp/session.cast", true);
+      const stopped = await stopSessionRecording("session-1");
+
+      expect(stopped).toBe(true);
+      expect(mockedInvoke).toHaveBeenCalledWith("session_start_recording", {
+        sessionId: "session-1",
+        path: "/tmp/session.cast",
+        recordInput: false,
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("session_start_recording", {
+        sessionId: "session-1",
+        path: "/tmp/session.cast",
+        recordInput: true,
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("session_stop_recording", {
+        sessionId: "session-1",
+      });
+    });
+
+    it("pause and resume output pass the session ID", async () => {
+      mockedInvoke.mockResolvedValueOnce(true).mockResolvedValueOnce(true);
+
+      expect(await pauseSessionOutput("session-1")).toBe(true);
+      expect(await resumeSessionOutput("session-1")).toBe(true);
+
+      expect(mockedInvoke).toHaveBeenCalledWith("session_pause_output", {
+        sessionId: "session-1",
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("session_resume_output", {
+        sessionId: "session-1",
       });
     });
 
@@ -200,6 +515,15 @@ describe("api service", () => {
       expect(result).toEqual(["zsh", "bash"]);
     });
 
+    it("listWslDistributions returns distribution names", async () => {
+      mockedInvoke.mockResolvedValue(["Ubuntu", "Debian"]);
+
+      const result = await listWslDistributions();
+
+      expect(mockedInvoke).toHaveBeenCalledWith("list_wsl_distributions");
+      expect(result).toEqual(["Ubuntu", "Debian"]);
+    });
+
     it("checkX11Available returns boolean", async () => {
       mockedInvoke.mockResolvedValue(true);
 
@@ -209,13 +533,13 @@ describe("api service", () => {
       expect(result).toBe(true);
     });
 
-    it("checkSshAgentStatus returns status string", async () => {
-      mockedInvoke.mockResolvedValue("running");
+    it("checkSshAgentStatus returns status and key count", async () => {
+      mockedInvoke.mockResolvedValue({ status: "running", keyCount: 2 });
 
       const result = await checkSshAgentStatus();
 
       expect(mockedInvoke).toHaveBeenCalledWith("check_ssh_agent_status");
-      expect(result).toBe("running");
+      expect(result).toEqual({ status: "running", keyCount: 2 });
     });
   });
 
@@ -336,6 +660,29 @@ describe("api service", () => {
       });
     });
 
+    it("file bookmark commands pass path and connection", async () => {
+      const bookmarks = [{ path: "/var/log" }, { path: "/srv", connectionId: "conn-1" }];
+      mockedInvoke.mockResolvedValue(bookmarks);
+
+      await addFileBookmark("/srv", { connectionId: "conn-1", label: "App" });
+      const result = await listFileBookmarks("conn-1");
+      await removeFileBookmark("/var/log");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("add_file_bookmark", {
+        path: "/srv",
+        label: "App",
+        connectionId: "conn-1",
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("list_file_bookmarks", {
+        connectionId: "conn-1",
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("remove_file_bookmark", {
+        path: "/var/log",
+        connectionId: null,
+      });
+      expect(result).toEqual(bookmarks);
+    });
+
     it("reloadExternalConnections returns sources", async () => {
       const sources = [
         { filePath: "/test", name: "Test", folders: [], connections: [], error: null },
@@ -392,6 +739,15 @@ describe("api service", () => {
       expect(mockedInvoke).toHaveBeenCalledWith("sftp_close", { sessionId: "sftp-session-1" });
     });
 
+    it("sftpInitialDir invokes with session ID and returns the resolved path", async () => {
+      mockedInvoke.mockResolvedValue("/home/user/projects");
+
+      const result = await sftpInitialDir("sftp-1");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("sftp_initial_dir", { sessionId: "sftp-1" });
+      expect(result).toBe("/home/user/projects");
+    });
+
     it("sftpListDir invokes with session ID and path", async () => {
       const entries = [
         {
@@ -427,6 +783,47 @@ describe("api service", () => {
       expect(result).toBe(1024);
     });
 
+    it("sessionDownloadArchive invokes with correct params and returns bytes", async () => {
+      mockedInvoke.mockResolvedValue(2048);
+
+      const result = await sessionDownloadArchive(
+        "session-1",
+        "/remote/project",
+        "tar.gz",
+        "/local/project.tar.gz"
+      );
+
+      expect(mockedInvoke).toHaveBeenCalledWith("session_download_archive", {
+        sessionId: "session-1",
+        path: "/remote/project",
+        format: "tar.gz",
+        localPath: "/local/project.tar.gz",
+      });
+      expect(result).toBe(2048);
+    });
+
+    it("sftp transfers pass resume and verify", async () => {
+      mockedInvoke.mockResolvedValue(512);
+
+      await sftpDownload("sftp-1", "/remote/big.iso", "/local/big.iso", true, true);
+      await sftpUpload("sftp-1", "/local/big.iso", "/remote/big.iso", true, false);
+
+      expect(mockedInvoke).toHaveBeenCalledWith("sftp_download", {
+        sessionId: "sftp-1",
+        remotePath: "/remote/big.iso",
+        localPath: "/local/big.iso",
+        resume: true,
+        verify: true,
+      });
+      expect(mockedInvoke).toHaveBeenCalledWith("sftp_upload", {
+        sessionId: "sftp-1",
+        localPath: "/local/big.iso",
+        remotePath: "/remote/big.iso",
+        resume: true,
+        verify: false,
+      });
+    });
+
     it("sftpUpload invokes with correct params and returns bytes", async () => {
       mockedInvoke.mockResolvedValue(2048);
 
@@ -440,6 +837,43 @@ describe("api service", () => {
       expect(result).toBe(2048);
     });
 
+    it("sftpDownloadDir invokes with correct params and returns the summary", async () => {
+      const summary = {
+        succeeded: ["/remote/project/a.txt"],
+        failed: [{ path: "/remote/project/locked", error: "permission denied" }],
+        skipped: [],
+        bytesTransferred: 12,
+      };
+      mockedInvoke.mockResolvedValue(summary);
+
+      const result = await sftpDownloadDir("sftp-1", "/remote/project", "/local/project", true);
+
+      expect(mockedInvoke).toHaveBeenCalledWith("sftp_download_dir", {
+        sessionId: "sftp-1",
+        remotePath: "/remote/project",
+        localPath: "/local/project",
+        followSymlinks: true,
+      });
+      expect(result).toEqual(summary);
+    });
+
+    it("sftpUploadDir invokes with correct params", async () => {
+      mockedInvoke.mockResolvedValue({
+        succeeded: [],
+        failed: [],
+        skipped: [],
+        bytesTransferred: 0,
+      });
+
+      await sftpUploadDir("sftp-1", "/local/project", "/remote/project");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("sftp_upload_dir", {
+        sessionId: "sftp-1",
+        localPath: "/local/project",
+        remotePath: "/remote/project",
+      });
+    });
+
     it("sftpMkdir invokes with session ID and path", async () => {
       mockedInvoke.mockResolvedValue(undefined);
 
@@ -448,6 +882,19 @@ describe("api service", () => {
       expect(mockedInvoke).toHaveBeenCalledWith("sftp_mkdir", {
         sessionId: "sftp-1",
         path: "/remote/newdir",
+        recursive: false,
+      });
+    });
+
+    it("sftpMkdir passes the recursive flag", async () => {
+      mockedInvoke.mockResolvedValue(undefined);
+
+      await sftpMkdir("sftp-1", "/remote/a/b/c", true);
+
+      expect(mockedInvoke).toHaveBeenCalledWith("sftp_mkdir", {
+        sessionId: "sftp-1",
+        path: "/remote/a/b/c",
+        recursive: true,
       });
     });
 
@@ -534,7 +981,21 @@ describe("api service", () => {
 
       await localMkdir("/home/newdir");
 
-      expect(mockedInvoke).toHaveBeenCalledWith("local_mkdir", { path: "/home/newdir" });
+      expect(mockedInvoke).toHaveBeenCalledWith("local_mkdir", {
+        path: "/home/newdir",
+        recursive: false,
+      });
+    });
+
+    it("localMkdir passes the recursive flag", async () => {
+      mockedInvoke.mockResolvedValue(undefined);
+
+      await localMkdir("/home/a/b/c", true);
+
+      expect(mockedInvoke).toHaveBeenCalledWith("local_mkdir", {
+        path: "/home/a/b/c",
+        recursive: true,
+      });
     });
 
     it("localDelete invokes with path and isDirectory flag", async () => {
@@ -578,6 +1039,37 @@ describe("api service", () => {
         content: "new content",
       });
     });
+
+    it("compareFiles passes both paths and the checksum flag", async () => {
+      const comparison = {
+        local: { size: 4, modified: "2024-01-01T00:00:00Z", checksum: "aa" },
+        remote: { size: 4, modified: "2024-01-01T00:00:00Z", checksum: "bb" },
+        differs: true,
+      };
+      mockedInvoke.mockResolvedValue(comparison);
+
+      const result = await compareFiles("/home/a.txt", "session-1", "/srv/a.txt", true);
+
+      expect(mockedInvoke).toHaveBeenCalledWith("session_compare_files", {
+        localPath: "/home/a.txt",
+        sessionId: "session-1",
+        remotePath: "/srv/a.txt",
+        checksum: true,
+      });
+      expect(result).toEqual(comparison);
+    });
+
+    it("localWriteFile passes the newline mode", async () => {
+      mockedInvoke.mockResolvedValue(undefined);
+
+      await localWriteFile("/home/run.sh", "echo hi\r\n", "lf");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("local_write_file", {
+        path: "/home/run.sh",
+        content: "echo hi\r\n",
+        newline: "lf",
+      });
+    });
   });
 
   describe("VS Code integration", () => {
@@ -608,6 +1100,40 @@ describe("api service", () => {
         remotePath: "/remote/file.txt",
       });
     });
+
+    it("openInEditor invokes with path and editor command", async () => {
+      mockedInvoke.mockResolvedValue(undefined);
+
+      await openInEditor("/home/file.txt", "nvim {path}");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("open_in_editor", {
+        path: "/home/file.txt",
+        editorCommand: "nvim {path}",
+      });
+    });
+
+    it("openInEditor passes null without an editor command", async () => {
+      mockedInvoke.mockResolvedValue(undefined);
+
+      await openInEditor("/home/file.txt");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("open_in_editor", {
+        path: "/home/file.txt",
+        editorCommand: null,
+      });
+    });
+
+    it("sftpOpenInEditor invokes with session ID and remote path", async () => {
+      mockedInvoke.mockResolvedValue(undefined);
+
+      await sftpOpenInEditor("sftp-1", "/remote/file.txt");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("sftp_open_in_editor", {
+        sessionId: "sftp-1",
+        remotePath: "/remote/file.txt",
+        editorCommand: null,
+      });
+    });
   });
 
   describe("SSH key validation", () => {
@@ -627,6 +1153,37 @@ describe("api service", () => {
       expect(result).toEqual(validation);
     });
 
+    it("generateSshKey invokes with key options and returns the public key", async () => {
+      mockedInvoke.mockResolvedValue("ssh-ed25519 AAAAC3Nza alice@laptop");
+
+      const result = await generateSshKey("ed25519", "~/.ssh/id_ed25519", {
+        comment: "alice@laptop",
+      });
+
+      expect(mockedInvoke).toHaveBeenCalledWith("generate_ssh_key", {
+        keyType: "ed25519",
+        bits: null,
+        passphrase: null,
+        comment: "alice@laptop",
+        outPath: "~/.ssh/id_ed25519",
+      });
+      expect(result).toBe("ssh-ed25519 AAAAC3Nza alice@laptop");
+    });
+
+    it("sshRunCommand invokes with config and command", async () => {
+      const output = { stdout: "hello\n", stderr: "", exitCode: 7 };
+      mockedInvoke.mockResolvedValue(output);
+      const config = { host: "example.com", port: 22, username: "me", authMethod: "agent" };
+
+      const result = await sshRunCommand(config, "echo hello; exit 7");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("ssh_run_command", {
+        config,
+        command: "echo hello; exit 7",
+      });
+      expect(result).toEqual(output);
+    });
+
     it("validateSshKey returns warning for public key", async () => {
       const validation = {
         status: "warning",
@@ -988,5 +1545,39 @@ describe("api service", () => {
         credentialType: "password",
       });
     });
+
+    it("listConnectionCredentials returns stored credential types", async () => {
+      mockedInvoke.mockResolvedValue(["password"]);
+
+      const result = await listConnectionCredentials("conn-1");
+
+      expect(result).toEqual(["password"]);
+      expect(mockedInvoke).toHaveBeenCalledWith("list_connection_credentials", {
+        connectionId: "conn-1",
+      });
+    });
+
+    it("exportCredentialsEncrypted passes the password", async () => {
+      mockedInvoke.mockResolvedValue('{"version":"1"}');
+
+      const result = await exportCredentialsEncrypted("backup-pw");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("export_credentials_encrypted", {
+        password: "backup-pw",
+      });
+      expect(result).toBe('{"version":"1"}');
+    });
+
+    it("importCredentialsEncrypted returns the imported count", async () => {
+      mockedInvoke.mockResolvedValue(3);
+
+      const result = await importCredentialsEncrypted('{"version":"1"}', "backup-pw");
+
+      expect(mockedInvoke).toHaveBeenCalledWith("import_credentials_encrypted", {
+        json: '{"version":"1"}',
+        password: "backup-pw",
+      });
+      expect(result).toBe(3);
+    });
   });
 });
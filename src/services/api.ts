@@ -5,6 +5,10 @@
 import { invoke } from "@tauri-apps/api/core";
 import {
   SessionId,
+  LineControl,
+  ModemStatus,
+  FileSendProtocol,
+  SessionSearchResult,
   ConnectionConfig,
   RemoteAgentConfig,
   LogEntry,
@@ -17,8 +21,17 @@ import {
   ConnectionFolder,
   ConnectionTypeInfo,
   FileEntry,
+  FileComparison,
+  DirTransferSummary,
+  GrepMatch,
+  GrepOptions,
+  NewlineMode,
   ExternalFileError,
   AppSettings,
+  FileBookmark,
+  GroupMemberResult,
+  LayoutRestoreResult,
+  SessionLayoutEntry,
   AgentCapabilities,
   AgentSettings,
   RecoveryWarning,
@@ -38,6 +51,11 @@ export async function getConnectionTypes(): Promise<ConnectionTypeInfo[]> {
   return await invoke<ConnectionTypeInfo[]>("get_connection_types");
 }
 
+/** Get a JSON Schema document describing a connection type's settings. */
+export async function getConnectionJsonSchema(typeId: string): Promise<Record<string, unknown>> {
+  return await invoke<Record<string, unknown>>("get_connection_json_schema", { typeId });
+}
+
 /** Create a new connection session (type-agnostic). */
 export async function createConnection(
   typeId: string,
@@ -51,6 +69,46 @@ export async function createConnection(
   });
 }
 
+/**
+ * Connect every saved connection in a connection group.
+ *
+ * Members launch sequentially unless `parallel` is true. A failing member
+ * is reported in its result and does not abort the others.
+ */
+export async function connectGroup(
+  groupId: string,
+  parallel?: boolean
+): Promise<GroupMemberResult[]> {
+  return await invoke<GroupMemberResult[]>("connect_group", {
+    groupId,
+    parallel: parallel ?? null,
+  });
+}
+
+/**
+ * Open the saved connections marked `autoConnect`. Each result arrives as a
+ * `connection-auto-connect` event; only the first call per app launch connects.
+ */
+export async function connectAutoConnections(): Promise<void> {
+  await invoke("connect_auto_connections");
+}
+
+/** Save the open sessions (in tab order) as a named layout, replacing any with that name. */
+export async function saveSessionLayout(
+  name: string,
+  sessions: SessionLayoutEntry[]
+): Promise<void> {
+  await invoke("save_session_layout", { name, sessions });
+}
+
+/**
+ * Reopen the sessions of a saved layout. Connections that no longer exist
+ * are skipped and listed in `missingConnectionIds`.
+ */
+export async function restoreSessionLayout(name: string): Promise<LayoutRestoreResult> {
+  return await invoke<LayoutRestoreResult>("restore_session_layout", { name });
+}
+
 /**
  * Create a new terminal session from a ConnectionConfig.
  *
@@ -83,9 +141,47 @@ export async function resizeTerminal(
   await invoke("resize_terminal", { sessionId, cols, rows });
 }
 
-/** Close a terminal session */
-export async function closeTerminal(sessionId: SessionId): Promise<void> {
-  await invoke("close_terminal", { sessionId });
+/** Send a BREAK or set the DTR/RTS line of a serial session */
+export async function sendLineControl(sessionId: SessionId, control: LineControl): Promise<void> {
+  await invoke("send_line_control", { sessionId, control });
+}
+
+/** Read the CTS/DSR/DCD/RI modem status lines of a serial session */
+export async function getSerialModemStatus(sessionId: SessionId): Promise<ModemStatus> {
+  return await invoke<ModemStatus>("serial_modem_status", { sessionId });
+}
+
+/**
+ * What asked for a session to be closed. `tabClosed` and `explicit` are
+ * subject to the session's `cleanupPolicy`; `kill` always releases it.
+ */
+export type CloseTrigger = "tabClosed" | "explicit" | "kill";
+
+/**
+ * Close a terminal session. Closing a tab passes `tabClosed`, so a session
+ * whose `cleanupPolicy` keeps it alive can be reattached later.
+ */
+export async function closeTerminal(
+  sessionId: SessionId,
+  trigger: CloseTrigger = "tabClosed"
+): Promise<void> {
+  await invoke("close_terminal", { sessionId, trigger });
+}
+
+/** Reattach a session that was kept alive after its tab closed. */
+export async function reattachTerminal(sessionId: SessionId): Promise<void> {
+  await invoke("reattach_terminal", { sessionId });
+}
+
+/**
+ * Restart a local or Docker session's shell in place, keeping its session ID.
+ * The scrollback is cleared unless `clearScrollback` is false.
+ */
+export async function restartSession(
+  sessionId: SessionId,
+  clearScrollback = true
+): Promise<void> {
+  await invoke("session_restart", { sessionId, clearScrollback });
 }
 
 /** Info about a local session managed by the desktop. */
@@ -95,6 +191,10 @@ export interface LocalSessionInfo {
   connectionType: string;
   alive: boolean;
   agentId?: string;
+  /** Whether all keystrokes go to the session, bypassing app shortcuts. */
+  rawKeyMode: boolean;
+  /** Whether the session's tab was closed but its cleanup policy kept it running. */
+  detached: boolean;
 }
 
 /** List all active local sessions (includes remote proxy sessions). */
@@ -102,6 +202,145 @@ export async function listLocalSessions(): Promise<LocalSessionInfo[]> {
   return await invoke<LocalSessionInfo[]>("list_local_sessions");
 }
 
+/** Metadata about a live desktop session, for session-switcher UIs. */
+export interface ActiveSessionInfo {
+  id: string;
+  type: string;
+  title: string;
+  /** RFC 3339 timestamp of when the session connected. */
+  connectedSince: string;
+  cols: number;
+  rows: number;
+  capabilities: {
+    monitoring: boolean;
+    fileBrowser: boolean;
+    resize: boolean;
+    persistent: boolean;
+  };
+  /** Whether the tab was closed while the backend was kept alive for reattaching. */
+  detached: boolean;
+  /** Whether all keystrokes go to the session, bypassing app shortcuts. */
+  rawKeyMode: boolean;
+}
+
+/** List live desktop sessions with their metadata, oldest first. */
+export async function listActiveSessions(): Promise<ActiveSessionInfo[]> {
+  return await invoke<ActiveSessionInfo[]>("list_active_sessions");
+}
+
+/** Start recording a session's input (keystrokes) to an asciicast file. */
+export async function startInputRecording(sessionId: string, path: string): Promise<void> {
+  await invoke("session_start_input_recording", { sessionId, path });
+}
+
+/** Stop recording a session's input. Resolves to false if no recording was running. */
+export async function stopInputRecording(sessionId: string): Promise<boolean> {
+  return await invoke<boolean>("session_stop_input_recording", { sessionId });
+}
+
+/**
+ * Replay recorded input into a session. `speed` divides the recorded delays
+ * (2 = twice as fast). Resolves to the number of writes replayed.
+ */
+export async function replayInput(sessionId: string, path: string, speed = 1): Promise<number> {
+  return await invoke<number>("session_replay_input", { sessionId, path, speed });
+}
+
+/**
+ * Send the contents of a local file to a session as input. `ascii` writes the raw
+ * bytes, pausing `lineDelayMs` after each line if given; `xmodem` sends the file to
+ * an XMODEM receiver already waiting in the session. Resolves to the bytes sent.
+ */
+export async function sendFileToSession(
+  sessionId: SessionId,
+  path: string,
+  protocol: FileSendProtocol,
+  lineDelayMs?: number
+): Promise<number> {
+  return await invoke<number>("session_send_file", { sessionId, path, protocol, lineDelayMs });
+}
+
+/**
+ * Plain text of display rows `start` (inclusive) to `end` (exclusive) of a
+ * session's output, with escape sequences removed and wrapped lines joined.
+ */
+export async function extractSessionText(
+  sessionId: string,
+  start: number,
+  end: number
+): Promise<string> {
+  return await invoke<string>("session_extract_text", { sessionId, start, end });
+}
+
+/**
+ * Search the recent output of every open session for `needle`. Returns the
+ * sessions with at least one match, ordered by session ID.
+ */
+export async function searchAllSessions(
+  needle: string,
+  caseInsensitive?: boolean
+): Promise<SessionSearchResult[]> {
+  return await invoke<SessionSearchResult[]>("search_all_sessions", { needle, caseInsensitive });
+}
+
+/**
+ * OS process ID of a session's local child process (the local shell), or
+ * `null` for sessions without one and once the child has exited.
+ */
+export async function sessionPid(sessionId: string): Promise<number | null> {
+  return await invoke<number | null>("session_pid", { sessionId });
+}
+
+/**
+ * Start logging a session's output to a file. With `collapseCarriageReturns`,
+ * lines redrawn with `\r` (progress bars) are logged in their final state only.
+ */
+export async function startOutputLog(
+  sessionId: string,
+  path: string,
+  collapseCarriageReturns = false
+): Promise<void> {
+  await invoke("session_start_output_log", { sessionId, path, collapseCarriageReturns });
+}
+
+/** Stop logging a session's output. Resolves to false if no log was running. */
+export async function stopOutputLog(sessionId: string): Promise<boolean> {
+  return await invoke<boolean>("session_stop_output_log", { sessionId });
+}
+
+/**
+ * Record a session to an asciicast v2 (`.cast`) file playable with asciinema.
+ * Output and resizes are always recorded; input only with `recordInput`.
+ */
+export async function startSessionRecording(
+  sessionId: string,
+  path: string,
+  recordInput = false
+): Promise<void> {
+  await invoke("session_start_recording", { sessionId, path, recordInput });
+}
+
+/** Stop recording a session. Resolves to false if no recording was running. */
+export async function stopSessionRecording(sessionId: string): Promise<boolean> {
+  return await invoke<boolean>("session_stop_recording", { sessionId });
+}
+
+/**
+ * Stop showing a session's output without stopping the remote process.
+ * Output is held until resumed. Resolves to false if already paused.
+ */
+export async function pauseSessionOutput(sessionId: string): Promise<boolean> {
+  return await invoke<boolean>("session_pause_output", { sessionId });
+}
+
+/**
+ * Resume a paused session's output; the held output is delivered first.
+ * Resolves to false if the output was not paused.
+ */
+export async function resumeSessionOutput(sessionId: string): Promise<boolean> {
+  return await invoke<boolean>("session_resume_output", { sessionId });
+}
+
 /** List available serial ports */
 export async function listSerialPorts(): Promise<string[]> {
   return await invoke<string[]>("list_serial_ports");
@@ -112,6 +351,11 @@ export async function listAvailableShells(): Promise<string[]> {
   return await invoke<string[]>("list_available_shells");
 }
 
+/** List installed WSL distributions (empty outside Windows) */
+export async function listWslDistributions(): Promise<string[]> {
+  return await invoke<string[]>("list_wsl_distributions");
+}
+
 /** Detect the user's default shell on this platform */
 export async function getDefaultShell(): Promise<string | null> {
   return await invoke<string | null>("get_default_shell");
@@ -122,9 +366,16 @@ export async function checkX11Available(): Promise<boolean> {
   return await invoke<boolean>("check_x11_available");
 }
 
-/** Check whether the SSH agent is running, stopped, or not installed. */
-export async function checkSshAgentStatus(): Promise<string> {
-  return await invoke<string>("check_ssh_agent_status");
+/** SSH agent availability and number of loaded keys. */
+export interface SshAgentStatus {
+  status: "running" | "stopped";
+  /** Loaded keys; absent when the agent is stopped or could not be queried. */
+  keyCount?: number;
+}
+
+/** Check whether the SSH agent is running and how many keys it holds. */
+export async function checkSshAgentStatus(): Promise<SshAgentStatus> {
+  return await invoke<SshAgentStatus>("check_ssh_agent_status");
 }
 
 /** Result of validating an SSH key file path. */
@@ -139,6 +390,48 @@ export async function validateSshKey(path: string): Promise<SshKeyValidation> {
   return await invoke<SshKeyValidation>("validate_ssh_key", { path });
 }
 
+/** SSH key algorithm for {@link generateSshKey}. */
+export type SshKeyType = "ed25519" | "rsa";
+
+/**
+ * Generate an SSH keypair at `outPath` (public key at `outPath.pub`).
+ *
+ * `bits` applies to RSA only (default 4096). Returns the public key line
+ * for copying to the server's `authorized_keys`.
+ */
+export async function generateSshKey(
+  keyType: SshKeyType,
+  outPath: string,
+  options: { bits?: number; passphrase?: string; comment?: string } = {}
+): Promise<string> {
+  return await invoke<string>("generate_ssh_key", {
+    keyType,
+    bits: options.bits ?? null,
+    passphrase: options.passphrase ?? null,
+    comment: options.comment ?? null,
+    outPath,
+  });
+}
+
+/** Captured result of {@link sshRunCommand}. */
+export interface SshCommandOutput {
+  stdout: string;
+  stderr: string;
+  exitCode: number;
+}
+
+/**
+ * Run a single command over SSH without a PTY and capture its output.
+ * `config` is the SSH connection settings; a dedicated connection is
+ * opened and closed for the command.
+ */
+export async function sshRunCommand(
+  config: Record<string, unknown>,
+  command: string
+): Promise<SshCommandOutput> {
+  return await invoke<SshCommandOutput>("ssh_run_command", { config, command });
+}
+
 /** Check if Docker is available on the local system. */
 export async function checkDockerAvailable(): Promise<boolean> {
   return await invoke<boolean>("check_docker_available");
@@ -285,6 +578,35 @@ export async function saveSettings(settings: AppSettings): Promise<void> {
   await invoke("save_settings", { settings });
 }
 
+/** Switch the active environment profile (null to deactivate) */
+export async function setActiveProfile(name: string | null): Promise<void> {
+  await invoke("set_active_profile", { name });
+}
+
+/** Bookmark a file browser path, globally or for one saved connection */
+export async function addFileBookmark(
+  path: string,
+  options: { label?: string; connectionId?: string } = {}
+): Promise<void> {
+  await invoke("add_file_bookmark", {
+    path,
+    label: options.label ?? null,
+    connectionId: options.connectionId ?? null,
+  });
+}
+
+/** List global file browser bookmarks plus those of the given connection */
+export async function listFileBookmarks(connectionId?: string): Promise<FileBookmark[]> {
+  return await invoke<FileBookmark[]>("list_file_bookmarks", {
+    connectionId: connectionId ?? null,
+  });
+}
+
+/** Remove a file browser bookmark */
+export async function removeFileBookmark(path: string, connectionId?: string): Promise<void> {
+  await invoke("remove_file_bookmark", { path, connectionId: connectionId ?? null });
+}
+
 /** Save an external connection file to disk */
 export async function saveExternalFile(
   filePath: string,
@@ -317,27 +639,103 @@ export async function sftpListDir(sessionId: string, path: string): Promise<File
   return await invoke<FileEntry[]>("sftp_list_dir", { sessionId, path });
 }
 
-/** Download a remote file to a local path. Returns bytes transferred. */
+/** Directory the file browser opens to: the connection's file browser root, or the remote home. */
+export async function sftpInitialDir(sessionId: string): Promise<string> {
+  return await invoke<string>("sftp_initial_dir", { sessionId });
+}
+
+/**
+ * Download a remote file to a local path. Returns bytes transferred.
+ * With `resume`, a partial local file is continued instead of replaced.
+ * With `verify`, the result is checked against a SHA-256 computed on the server.
+ */
 export async function sftpDownload(
   sessionId: string,
   remotePath: string,
-  localPath: string
+  localPath: string,
+  resume?: boolean,
+  verify?: boolean
 ): Promise<number> {
-  return await invoke<number>("sftp_download", { sessionId, remotePath, localPath });
+  return await invoke<number>("sftp_download", {
+    sessionId,
+    remotePath,
+    localPath,
+    resume,
+    verify,
+  });
 }
 
-/** Upload a local file to a remote path. Returns bytes transferred. */
+/**
+ * Upload a local file to a remote path. Returns bytes transferred.
+ * With `resume`, a partial remote file is continued instead of replaced.
+ * With `verify`, the result is checked against a SHA-256 computed on the server.
+ */
 export async function sftpUpload(
   sessionId: string,
   localPath: string,
-  remotePath: string
+  remotePath: string,
+  resume?: boolean,
+  verify?: boolean
 ): Promise<number> {
-  return await invoke<number>("sftp_upload", { sessionId, localPath, remotePath });
+  return await invoke<number>("sftp_upload", {
+    sessionId,
+    localPath,
+    remotePath,
+    resume,
+    verify,
+  });
+}
+
+/**
+ * Download a remote directory tree into a local directory.
+ * Symlinks are skipped unless `followSymlinks` is set. Failed files are listed in the
+ * summary instead of aborting; progress is reported per file through
+ * `sftp-transfer-progress` events.
+ */
+export async function sftpDownloadDir(
+  sessionId: string,
+  remotePath: string,
+  localPath: string,
+  followSymlinks?: boolean
+): Promise<DirTransferSummary> {
+  return await invoke<DirTransferSummary>("sftp_download_dir", {
+    sessionId,
+    remotePath,
+    localPath,
+    followSymlinks,
+  });
 }
 
-/** Create a directory on the remote host. */
-export async function sftpMkdir(sessionId: string, path: string): Promise<void> {
-  await invoke("sftp_mkdir", { sessionId, path });
+/**
+ * Upload a local directory tree into a remote directory.
+ * Symlinks are skipped unless `followSymlinks` is set. Failed files are listed in the
+ * summary instead of aborting; progress is reported per file through
+ * `sftp-transfer-progress` events.
+ */
+export async function sftpUploadDir(
+  sessionId: string,
+  localPath: string,
+  remotePath: string,
+  followSymlinks?: boolean
+): Promise<DirTransferSummary> {
+  return await invoke<DirTransferSummary>("sftp_upload_dir", {
+    sessionId,
+    localPath,
+    remotePath,
+    followSymlinks,
+  });
+}
+
+/**
+ * Create a directory on the remote host.
+ * With `recursive`, missing parent directories are created too.
+ */
+export async function sftpMkdir(
+  sessionId: string,
+  path: string,
+  recursive = false
+): Promise<void> {
+  await invoke("sftp_mkdir", { sessionId, path, recursive });
 }
 
 /** Delete a file or directory on the remote host. */
@@ -349,13 +747,17 @@ export async function sftpDelete(
   await invoke("sftp_delete", { sessionId, path, isDirectory });
 }
 
-/** Rename a file or directory on the remote host. */
+/**
+ * Rename a file or directory on the remote host.
+ * Fails with "Destination already exists" unless `overwrite` is set.
+ */
 export async function sftpRename(
   sessionId: string,
   oldPath: string,
-  newPath: string
+  newPath: string,
+  overwrite = false
 ): Promise<void> {
-  await invoke("sftp_rename", { sessionId, oldPath, newPath });
+  await invoke("sftp_rename", { sessionId, oldPath, newPath, overwrite });
 }
 
 // --- Local filesystem commands ---
@@ -379,9 +781,12 @@ export async function localCopyFile(
   await invoke("local_copy", { srcPath, destPath, isDirectory });
 }
 
-/** Create a directory on the local filesystem. */
-export async function localMkdir(path: string): Promise<void> {
-  await invoke("local_mkdir", { path });
+/**
+ * Create a directory on the local filesystem.
+ * With `recursive`, missing parent directories are created too.
+ */
+export async function localMkdir(path: string, recursive = false): Promise<void> {
+  await invoke("local_mkdir", { path, recursive });
 }
 
 /** Delete a file or directory on the local filesystem. */
@@ -389,9 +794,16 @@ export async function localDelete(path: string, isDirectory: boolean): Promise<v
   await invoke("local_delete", { path, isDirectory });
 }
 
-/** Rename a file or directory on the local filesystem. */
-export async function localRename(oldPath: string, newPath: string): Promise<void> {
-  await invoke("local_rename", { oldPath, newPath });
+/**
+ * Rename a file or directory on the local filesystem.
+ * Fails with "Destination already exists" unless `overwrite` is set.
+ */
+export async function localRename(
+  oldPath: string,
+  newPath: string,
+  overwrite = false
+): Promise<void> {
+  await invoke("local_rename", { oldPath, newPath, overwrite });
 }
 
 /** Read a local file's contents as a UTF-8 string. */
@@ -399,9 +811,13 @@ export async function localReadFile(path: string): Promise<string> {
   return await invoke<string>("local_read_file", { path });
 }
 
-/** Write a string to a local file. */
-export async function localWriteFile(path: string, content: string): Promise<void> {
-  await invoke("local_write_file", { path, content });
+/** Write a string to a local file, optionally normalizing line endings. */
+export async function localWriteFile(
+  path: string,
+  content: string,
+  newline?: NewlineMode
+): Promise<void> {
+  await invoke("local_write_file", { path, content, newline });
 }
 
 /** Read a remote file's contents as a UTF-8 string via SFTP. */
@@ -409,13 +825,14 @@ export async function sftpReadFileContent(sessionId: string, remotePath: string)
   return await invoke<string>("sftp_read_file_content", { sessionId, remotePath });
 }
 
-/** Write a string to a remote file via SFTP. */
+/** Write a string to a remote file via SFTP, optionally normalizing line endings. */
 export async function sftpWriteFileContent(
   sessionId: string,
   remotePath: string,
-  content: string
+  content: string,
+  newline?: NewlineMode
 ): Promise<void> {
-  await invoke("sftp_write_file_content", { sessionId, remotePath, content });
+  await invoke("sftp_write_file_content", { sessionId, remotePath, content, newline });
 }
 
 // --- Session-based file browsing commands ---
@@ -436,9 +853,10 @@ export async function sessionReadFile(sessionId: string, path: string): Promise<
 export async function sessionWriteFile(
   sessionId: string,
   path: string,
-  data: number[]
+  data: number[],
+  newline?: NewlineMode
 ): Promise<void> {
-  await invoke("session_write_file", { sessionId, path, data });
+  await invoke("session_write_file", { sessionId, path, data, newline });
 }
 
 /** Delete a file or directory via a session's file browser capability. */
@@ -446,18 +864,85 @@ export async function sessionDeleteFile(sessionId: string, path: string): Promis
   await invoke("session_delete_file", { sessionId, path });
 }
 
-/** Rename a file or directory via a session's file browser capability. */
+/**
+ * Rename a file or directory via a session's file browser capability.
+ * Fails with "Destination already exists" unless `overwrite` is set.
+ */
 export async function sessionRenameFile(
   sessionId: string,
   oldPath: string,
-  newPath: string
+  newPath: string,
+  overwrite = false
 ): Promise<void> {
-  await invoke("session_rename_file", { sessionId, oldPath, newPath });
+  await invoke("session_rename_file", { sessionId, oldPath, newPath, overwrite });
 }
 
-/** Create a directory via a session's file browser capability. */
-export async function sessionMkdir(sessionId: string, path: string): Promise<void> {
-  await invoke("session_mkdir", { sessionId, path });
+/**
+ * Create a directory via a session's file browser capability.
+ * With `recursive`, missing parent directories are created too.
+ */
+export async function sessionMkdir(
+  sessionId: string,
+  path: string,
+  recursive = false
+): Promise<void> {
+  await invoke("session_mkdir", { sessionId, path, recursive });
+}
+
+/** Archive format for directory downloads. */
+export type ArchiveFormat = "tar.gz" | "zip";
+
+/**
+ * Download a directory as a single archive via a session's file browser.
+ * Returns the number of archive bytes written. Progress is reported through
+ * `sftp-transfer-progress` events with a `total` of 0.
+ */
+export async function sessionDownloadArchive(
+  sessionId: string,
+  path: string,
+  format: ArchiveFormat,
+  localPath: string
+): Promise<number> {
+  return await invoke<number>("session_download_archive", { sessionId, path, format, localPath });
+}
+
+/** Compute the total size in bytes of a file or directory tree via a session's file browser. */
+export async function sessionDirSize(sessionId: string, path: string): Promise<number> {
+  return await invoke<number>("session_dir_size", { sessionId, path });
+}
+
+/** Cancel an in-flight directory size computation for a session. */
+export async function cancelSessionDirSize(sessionId: string): Promise<void> {
+  await invoke("session_cancel_dir_size", { sessionId });
+}
+
+/** Search file contents below a directory via a session's file browser. */
+export async function sessionGrep(
+  sessionId: string,
+  root: string,
+  pattern: string,
+  options?: GrepOptions
+): Promise<GrepMatch[]> {
+  return await invoke<GrepMatch[]>("session_grep", { sessionId, root, pattern, options });
+}
+
+/**
+ * Compare a local file with a file in a session's file browser. With
+ * `checksum`, both files are hashed and their contents decide `differs`;
+ * otherwise size and modification time do.
+ */
+export async function compareFiles(
+  localPath: string,
+  sessionId: string,
+  remotePath: string,
+  checksum?: boolean
+): Promise<FileComparison> {
+  return await invoke<FileComparison>("session_compare_files", {
+    localPath,
+    sessionId,
+    remotePath,
+    checksum,
+  });
 }
 
 // --- VS Code integration ---
@@ -477,6 +962,30 @@ export async function vscodeOpenRemote(sessionId: string, remotePath: string): P
   await invoke("vscode_open_remote", { sessionId, remotePath });
 }
 
+/**
+ * Open a local file in the external editor. `editorCommand` (e.g.
+ * `nvim {path}`) overrides the configured editor.
+ */
+export async function openInEditor(path: string, editorCommand?: string): Promise<void> {
+  await invoke("open_in_editor", { path, editorCommand: editorCommand ?? null });
+}
+
+/**
+ * Open a remote file in the external editor. Saves are uploaded back while
+ * the editor runs; completion is reported via `editor-edit-complete`.
+ */
+export async function sftpOpenInEditor(
+  sessionId: string,
+  remotePath: string,
+  editorCommand?: string
+): Promise<void> {
+  await invoke("sftp_open_in_editor", {
+    sessionId,
+    remotePath,
+    editorCommand: editorCommand ?? null,
+  });
+}
+
 // --- Agent commands ---
 
 /** Info about a remote session on an agent. */
@@ -867,6 +1376,28 @@ export async function removeCredential(
   await invoke("remove_credential", { connectionId, credentialType });
 }
 
+/** List which credential types are stored for a connection, without their values. */
+export async function listConnectionCredentials(
+  connectionId: string
+): Promise<Array<"password" | "key_passphrase">> {
+  return await invoke<Array<"password" | "key_passphrase">>("list_connection_credentials", {
+    connectionId,
+  });
+}
+
+/** Export every stored credential as a backup encrypted with the given password. */
+export async function exportCredentialsEncrypted(password: string): Promise<string> {
+  return await invoke<string>("export_credentials_encrypted", { password });
+}
+
+/** Import credentials from an encrypted backup. Returns the number imported. */
+export async function importCredentialsEncrypted(
+  json: string,
+  password: string
+): Promise<number> {
+  return await invoke<number>("import_credentials_encrypted", { json, password });
+}
+
 // --- Portable mode commands ---
 
 /** Return the current app mode (portable vs. installed) and the data directory path. */
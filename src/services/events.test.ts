@@ -3,7 +3,14 @@ import { listen } from "@tauri-apps/api/event";
 import {
   onTerminalOutput,
   onTerminalExit,
+  onTerminalBell,
+  onSessionClosed,
+  onSshHostFingerprint,
+  onSessionNotice,
+  onConnectionAutoConnect,
   onVscodeEditComplete,
+  onEditorEditComplete,
+  onSftpTransferProgress,
   onLogEntry,
   TerminalOutputDispatcher,
   onCredentialStoreLocked,
@@ -102,6 +109,100 @@ describe("events service", () => {
     });
   });
 
+  describe("onTerminalBell", () => {
+    it("registers listener and passes the session ID", async () => {
+      let capturedHandler: ((event: unknown) => void) | undefined;
+      mockedListen.mockImplementation((_event, handler) => {
+        capturedHandler = handler as (event: unknown) => void;
+        return Promise.resolve(vi.fn());
+      });
+
+      const callback = vi.fn();
+      await onTerminalBell(callback);
+
+      expect(mockedListen).toHaveBeenCalledWith("terminal-bell", expect.any(Function));
+      capturedHandler!({ payload: { session_id: "sess-1" } });
+      expect(callback).toHaveBeenCalledWith("sess-1");
+    });
+  });
+
+  describe("onSessionClosed", () => {
+    it("registers listener and passes the close reason", async () => {
+      let capturedHandler: ((event: unknown) => void) | undefined;
+      mockedListen.mockImplementation((_event, handler) => {
+        capturedHandler = handler as (event: unknown) => void;
+        return Promise.resolve(vi.fn());
+      });
+
+      const callback = vi.fn();
+      await onSessionClosed(callback);
+
+      expect(mockedListen).toHaveBeenCalledWith("session-closed", expect.any(Function));
+      capturedHandler!({ payload: { session_id: "sess-1", reason: "ConnectionLost" } });
+      expect(callback).toHaveBeenCalledWith("sess-1", "ConnectionLost");
+    });
+  });
+
+  describe("onSshHostFingerprint", () => {
+    it("registers listener and passes the key type and fingerprint", async () => {
+      let capturedHandler: ((event: unknown) => void) | undefined;
+      mockedListen.mockImplementation((_event, handler) => {
+        capturedHandler = handler as (event: unknown) => void;
+        return Promise.resolve(vi.fn());
+      });
+
+      const callback = vi.fn();
+      await onSshHostFingerprint(callback);
+
+      expect(mockedListen).toHaveBeenCalledWith("ssh-host-fingerprint", expect.any(Function));
+      capturedHandler!({
+        payload: { session_id: "sess-1", key_type: "ssh-ed25519", fingerprint: "SHA256:abc" },
+      });
+      expect(callback).toHaveBeenCalledWith("sess-1", "ssh-ed25519", "SHA256:abc");
+    });
+  });
+
+  describe("onSessionNotice", () => {
+    it("registers listener and passes the code and message", async () => {
+      let capturedHandler: ((event: unknown) => void) | undefined;
+      mockedListen.mockImplementation((_event, handler) => {
+        capturedHandler = handler as (event: unknown) => void;
+        return Promise.resolve(vi.fn());
+      });
+
+      const callback = vi.fn();
+      await onSessionNotice(callback);
+
+      expect(mockedListen).toHaveBeenCalledWith("session-notice", expect.any(Function));
+      capturedHandler!({
+        payload: { session_id: "sess-1", code: "restrictedShell", message: "restricted" },
+      });
+      expect(callback).toHaveBeenCalledWith("sess-1", "restrictedShell", "restricted");
+    });
+  });
+
+  describe("onConnectionAutoConnect", () => {
+    it("registers listener and passes each connection result", async () => {
+      let capturedHandler: ((event: unknown) => void) | undefined;
+      mockedListen.mockImplementation((_event, handler) => {
+        capturedHandler = handler as (event: unknown) => void;
+        return Promise.resolve(vi.fn());
+      });
+
+      const callback = vi.fn();
+      await onConnectionAutoConnect(callback);
+
+      expect(mockedListen).toHaveBeenCalledWith("connection-auto-connect", expect.any(Function));
+      capturedHandler!({ payload: { connectionId: "Work/db", sessionId: "sess-1" } });
+      capturedHandler!({ payload: { connectionId: "router", error: "Connection refused" } });
+      expect(callback).toHaveBeenNthCalledWith(1, { connectionId: "Work/db", sessionId: "sess-1" });
+      expect(callback).toHaveBeenNthCalledWith(2, {
+        connectionId: "router",
+        error: "Connection refused",
+      });
+    });
+  });
+
   describe("onVscodeEditComplete", () => {
     it("registers listener on vscode-edit-complete event", async () => {
       const unlisten = vi.fn();
@@ -149,6 +250,43 @@ describe("events service", () => {
     });
   });
 
+  describe("onEditorEditComplete", () => {
+    it("calls callback with the payload of editor-edit-complete", async () => {
+      let capturedHandler: ((event: unknown) => void) | undefined;
+      mockedListen.mockImplementation((_event, handler) => {
+        capturedHandler = handler as (event: unknown) => void;
+        return Promise.resolve(vi.fn());
+      });
+
+      const callback = vi.fn();
+      await onEditorEditComplete(callback);
+
+      expect(mockedListen).toHaveBeenCalledWith("editor-edit-complete", expect.any(Function));
+      capturedHandler!({
+        payload: { remotePath: "/remote/file.txt", success: false, error: "Upload failed" },
+      });
+      expect(callback).toHaveBeenCalledWith("/remote/file.txt", false, "Upload failed");
+    });
+  });
+
+  describe("onSftpTransferProgress", () => {
+    it("forwards progress payloads", async () => {
+      let capturedHandler: ((event: unknown) => void) | undefined;
+      mockedListen.mockImplementation((_event, handler) => {
+        capturedHandler = handler as (event: unknown) => void;
+        return Promise.resolve(vi.fn());
+      });
+
+      const callback = vi.fn();
+      await onSftpTransferProgress(callback);
+
+      expect(mockedListen).toHaveBeenCalledWith("sftp-transfer-progress", expect.any(Function));
+      const payload = { sessionId: "sftp-1", path: "/data.bin", transferred: 512, total: 1024 };
+      capturedHandler!({ payload });
+      expect(callback).toHaveBeenCalledWith(payload);
+    });
+  });
+
   describe("onLogEntry", () => {
     it("registers listener on log-entry event", async () => {
       const unlisten = vi.fn();
@@ -196,16 +334,17 @@ describe("events service", () => {
       dispatcher = new TerminalOutputDispatcher();
     });
 
-    it("init registers four global listeners", async () => {
+    it("init registers five global listeners", async () => {
       mockedListen.mockResolvedValue(vi.fn());
 
       await dispatcher.init();
 
-      expect(mockedListen).toHaveBeenCalledTimes(4);
+      expect(mockedListen).toHaveBeenCalledTimes(5);
       expect(mockedListen).toHaveBeenCalledWith("terminal-output", expect.any(Function));
       expect(mockedListen).toHaveBeenCalledWith("terminal-exit", expect.any(Function));
       expect(mockedListen).toHaveBeenCalledWith("remote-state-change", expect.any(Function));
       expect(mockedListen).toHaveBeenCalledWith("agent-state-change", expect.any(Function));
+      expect(mockedListen).toHaveBeenCalledWith("session-notice", expect.any(Function));
     });
 
     it("init is idempotent — second call does nothing", async () => {
@@ -214,7 +353,7 @@ describe("events service", () => {
       await dispatcher.init();
       await dispatcher.init();
 
-      expect(mockedListen).toHaveBeenCalledTimes(4);
+      expect(mockedListen).toHaveBeenCalledTimes(5);
     });
 
     it("routes output events to the correct session callback", async () => {
@@ -280,6 +419,31 @@ describe("events service", () => {
       expect(cb).toHaveBeenCalledWith("connected");
     });
 
+    it("routes session notices and buffers those sent before subscribing", async () => {
+      const handlers: Record<string, (event: unknown) => void> = {};
+      mockedListen.mockImplementation((eventName, handler) => {
+        handlers[eventName as string] = handler as (event: unknown) => void;
+        return Promise.resolve(vi.fn());
+      });
+
+      await dispatcher.init();
+
+      // The notice is emitted while the session connects
+      handlers["session-notice"]({
+        payload: { session_id: "sess-1", code: "restrictedShell", message: "restricted" },
+      });
+
+      const cb = vi.fn();
+      dispatcher.subscribeNotice("sess-1", cb);
+      expect(cb).toHaveBeenCalledWith("restrictedShell", "restricted");
+
+      handlers["session-notice"]({
+        payload: { session_id: "sess-1", code: "baudRateRounded", message: "rounded" },
+      });
+      expect(cb).toHaveBeenCalledTimes(2);
+      expect(cb).toHaveBeenLastCalledWith("baudRateRounded", "rounded");
+    });
+
     it("unsubscribe stops delivery", async () => {
       const handlers: Record<string, (event: unknown) => void> = {};
       mockedListen.mockImplementation((eventName, handler) => {
@@ -430,7 +594,7 @@ describe("events service", () => {
 
       await dispatcher.init();
 
-      expect(mockedListen).toHaveBeenCalledTimes(4);
+      expect(mockedListen).toHaveBeenCalledTimes(5);
     });
 
     it("handles StrictMode race: destroy during pending init cleans up leaked listeners", async () => {
@@ -7,6 +7,7 @@ import { LogEntry } from "@/types/terminal";
 import { TunnelState, TunnelStats } from "@/types/tunnel";
 import { CredentialStoreStatusInfo } from "@/types/credential";
 import { ServerState } from "@/types/embeddedServer";
+import { GroupMemberResult } from "@/types/connection";
 
 interface TerminalOutputPayload {
   session_id: string;
@@ -18,6 +19,30 @@ interface TerminalExitPayload {
   exit_code: number | null;
 }
 
+interface TerminalBellPayload {
+  session_id: string;
+}
+
+/** Why a session closed on its own. */
+export type SessionCloseReason = "ConnectionLost";
+
+interface SessionClosedPayload {
+  session_id: string;
+  reason: SessionCloseReason;
+}
+
+interface SshHostFingerprintPayload {
+  session_id: string;
+  key_type: string;
+  fingerprint: string;
+}
+
+interface SessionNoticePayload {
+  session_id: string;
+  code: string;
+  message: string;
+}
+
 interface RemoteStateChangePayload {
   session_id: string;
   state: string;
@@ -48,6 +73,69 @@ export async function onTerminalExit(
   });
 }
 
+/**
+ * Subscribe to terminal bell events. Bells that arrive in quick
+ * succession are debounced by the backend into a single event.
+ */
+export async function onTerminalBell(callback: (sessionId: string) => void): Promise<UnlistenFn> {
+  return await listen<TerminalBellPayload>("terminal-bell", (event) => {
+    callback(event.payload.session_id);
+  });
+}
+
+/**
+ * Subscribe to session-closed events, emitted just before terminal-exit
+ * when a session closed on its own for a known reason (e.g. the SSH peer
+ * stopped answering keepalives).
+ */
+export async function onSessionClosed(
+  callback: (sessionId: string, reason: SessionCloseReason) => void
+): Promise<UnlistenFn> {
+  return await listen<SessionClosedPayload>("session-closed", (event) => {
+    callback(event.payload.session_id, event.payload.reason);
+  });
+}
+
+/**
+ * Subscribe to ssh-host-fingerprint events, emitted once when an SSH
+ * session connects. The fingerprint is in OpenSSH format (`SHA256:...`)
+ * so it can be compared against `ssh-keygen -lf` on the server.
+ */
+export async function onSshHostFingerprint(
+  callback: (sessionId: string, keyType: string, fingerprint: string) => void
+): Promise<UnlistenFn> {
+  return await listen<SshHostFingerprintPayload>("ssh-host-fingerprint", (event) => {
+    const { session_id, key_type, fingerprint } = event.payload;
+    callback(session_id, key_type, fingerprint);
+  });
+}
+
+/**
+ * Subscribe to session-notice events, emitted once per notice when a
+ * session connects, e.g. `restrictedShell` when monitoring and file
+ * browsing were turned off for an SSH session on a restricted shell.
+ */
+export async function onSessionNotice(
+  callback: (sessionId: string, code: string, message: string) => void
+): Promise<UnlistenFn> {
+  return await listen<SessionNoticePayload>("session-notice", (event) => {
+    const { session_id, code, message } = event.payload;
+    callback(session_id, code, message);
+  });
+}
+
+/**
+ * Subscribe to connection-auto-connect events, emitted at startup once for
+ * each saved connection marked `autoConnect` with its session ID or error.
+ */
+export async function onConnectionAutoConnect(
+  callback: (result: GroupMemberResult) => void
+): Promise<UnlistenFn> {
+  return await listen<GroupMemberResult>("connection-auto-connect", (event) => {
+    callback(event.payload);
+  });
+}
+
 /**
  * Singleton dispatcher that registers one global Tauri listener for each
  * terminal event type and routes events to per-session callbacks via Map
@@ -59,12 +147,16 @@ export class TerminalOutputDispatcher {
   private exitCallbacks = new Map<string, (exitCode: number | null) => void>();
   private remoteStateCallbacks = new Map<string, (state: string) => void>();
   private agentStateCallbacks = new Map<string, (state: string) => void>();
+  private noticeCallbacks = new Map<string, (code: string, message: string) => void>();
   /** Buffer output for sessions whose subscriber hasn't registered yet. */
   private pendingOutput = new Map<string, Uint8Array[]>();
+  /** Notices arrive while the session connects, before its subscriber registers. */
+  private pendingNotices = new Map<string, { code: string; message: string }[]>();
   private unlistenOutput: UnlistenFn | null = null;
   private unlistenExit: UnlistenFn | null = null;
   private unlistenRemoteState: UnlistenFn | null = null;
   private unlistenAgentState: UnlistenFn | null = null;
+  private unlistenNotice: UnlistenFn | null = null;
   private initPromise: Promise<void> | null = null;
   private initGeneration = 0;
 
@@ -169,6 +261,34 @@ export class TerminalOutputDispatcher {
       return;
     }
     this.unlistenAgentState = unlistenAgentState;
+
+    const unlistenNotice = await onSessionNotice((sessionId, code, message) => {
+      const cb = this.noticeCallbacks.get(sessionId);
+      if (cb) {
+        cb(code, message);
+      } else {
+        let buf = this.pendingNotices.get(sessionId);
+        if (!buf) {
+          buf = [];
+          this.pendingNotices.set(sessionId, buf);
+        }
+        buf.push({ code, message });
+      }
+    });
+
+    if (gen !== this.initGeneration) {
+      unlistenNotice();
+      this.unlistenOutput();
+      this.unlistenOutput = null;
+      this.unlistenExit();
+      this.unlistenExit = null;
+      this.unlistenRemoteState();
+      this.unlistenRemoteState = null;
+      this.unlistenAgentState();
+      this.unlistenAgentState = null;
+      return;
+    }
+    this.unlistenNotice = unlistenNotice;
   }
 
   /** Subscribe to output events for a specific session. Returns an unsubscribe function. */
@@ -203,6 +323,28 @@ export class TerminalOutputDispatcher {
     };
   }
 
+  /**
+   * Subscribe to session notices for a specific session, receiving any
+   * that arrived before the subscriber registered. Returns an unsubscribe
+   * function.
+   */
+  subscribeNotice(
+    sessionId: string,
+    callback: (code: string, message: string) => void
+  ): () => void {
+    this.noticeCallbacks.set(sessionId, callback);
+    const buffered = this.pendingNotices.get(sessionId);
+    if (buffered) {
+      this.pendingNotices.delete(sessionId);
+      for (const notice of buffered) {
+        callback(notice.code, notice.message);
+      }
+    }
+    return () => {
+      this.noticeCallbacks.delete(sessionId);
+    };
+  }
+
   /** Subscribe to agent state change events for a specific agent. Returns an unsubscribe function. */
   subscribeAgentState(agentId: string, callback: (state: string) => void): () => void {
     this.agentStateCallbacks.set(agentId, callback);
@@ -230,11 +372,17 @@ export class TerminalOutputDispatcher {
       this.unlistenAgentState();
       this.unlistenAgentState = null;
     }
+    if (this.unlistenNotice) {
+      this.unlistenNotice();
+      this.unlistenNotice = null;
+    }
     this.outputCallbacks.clear();
     this.exitCallbacks.clear();
     this.remoteStateCallbacks.clear();
     this.agentStateCallbacks.clear();
+    this.noticeCallbacks.clear();
     this.pendingOutput.clear();
+    this.pendingNotices.clear();
     this.initPromise = null;
   }
 }
@@ -278,7 +426,7 @@ export async function onAgentDeployProgress(
   });
 }
 
-interface VscodeEditCompletePayload {
+interface EditCompletePayload {
   remotePath: string;
   success: boolean;
   error: string | null;
@@ -288,11 +436,38 @@ interface VscodeEditCompletePayload {
 export async function onVscodeEditComplete(
   callback: (remotePath: string, success: boolean, error: string | null) => void
 ): Promise<UnlistenFn> {
-  return await listen<VscodeEditCompletePayload>("vscode-edit-complete", (event) => {
+  return await listen<EditCompletePayload>("vscode-edit-complete", (event) => {
     callback(event.payload.remotePath, event.payload.success, event.payload.error);
   });
 }
 
+/** Subscribe to external editor edit-complete events (remote file re-upload). */
+export async function onEditorEditComplete(
+  callback: (remotePath: string, success: boolean, error: string | null) => void
+): Promise<UnlistenFn> {
+  return await listen<EditCompletePayload>("editor-edit-complete", (event) => {
+    callback(event.payload.remotePath, event.payload.success, event.payload.error);
+  });
+}
+
+export interface SftpTransferProgress {
+  sessionId: string;
+  path: string;
+  /** Uncompressed bytes at the destination, including a resumed prefix. */
+  transferred: number;
+  /** Size of the source, or 0 when unknown (archive downloads). */
+  total: number;
+}
+
+/** Subscribe to SFTP upload/download progress events. */
+export async function onSftpTransferProgress(
+  callback: (progress: SftpTransferProgress) => void
+): Promise<UnlistenFn> {
+  return await listen<SftpTransferProgress>("sftp-transfer-progress", (event) => {
+    callback(event.payload);
+  });
+}
+
 /** Subscribe to real-time log entry events from the backend. */
 export async function onLogEntry(callback: (entry: LogEntry) => void): Promise<UnlistenFn> {
   return await listen<LogEntry>("log-entry", (event) => {
@@ -12,6 +12,7 @@ import {
   saveTunnel,
   deleteTunnel,
   getTunnelStatuses,
+  getTunnelMetrics,
   startTunnel,
   stopTunnel,
 } from "./tunnelApi";
@@ -92,6 +93,30 @@ describe("tunnelApi", () => {
     });
   });
 
+  describe("getTunnelMetrics", () => {
+    it("invokes get_tunnel_metrics and returns metrics", async () => {
+      const metrics = [
+        {
+          tunnelId: "t-1",
+          stats: {
+            bytesSent: 4096,
+            bytesReceived: 1024,
+            activeConnections: 1,
+            totalConnections: 3,
+          },
+          bytesSentPerSec: 409.6,
+          bytesReceivedPerSec: 102.4,
+        },
+      ];
+      mockedInvoke.mockResolvedValue(metrics);
+
+      const result = await getTunnelMetrics();
+
+      expect(mockedInvoke).toHaveBeenCalledWith("get_tunnel_metrics");
+      expect(result).toEqual(metrics);
+    });
+  });
+
   describe("startTunnel", () => {
     it("invokes start_tunnel with tunnelId", async () => {
       mockedInvoke.mockResolvedValue(undefined);
@@ -3,7 +3,7 @@
  */
 
 import { invoke } from "@tauri-apps/api/core";
-import { TunnelConfig, TunnelState } from "@/types/tunnel";
+import { TunnelConfig, TunnelMetrics, TunnelState } from "@/types/tunnel";
 
 /** Get all saved tunnel configurations. */
 export async function getTunnels(): Promise<TunnelConfig[]> {
@@ -25,6 +25,11 @@ export async function getTunnelStatuses(): Promise<TunnelState[]> {
   return await invoke<TunnelState[]>("get_tunnel_statuses");
 }
 
+/** Get traffic totals and throughput for all tunnels. */
+export async function getTunnelMetrics(): Promise<TunnelMetrics[]> {
+  return await invoke<TunnelMetrics[]>("get_tunnel_metrics");
+}
+
 /** Start a tunnel by ID. */
 export async function startTunnel(tunnelId: string): Promise<void> {
   await invoke("start_tunnel", { tunnelId });
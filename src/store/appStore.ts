@@ -52,6 +52,7 @@ import {
   sftpOpen,
   sftpClose,
   sftpListDir,
+  sftpInitialDir,
   sessionListFiles,
   localListDir,
   vscodeAvailable as checkVscode,
@@ -119,7 +120,11 @@ import {
   captureAllTabGroups,
   getWorkspaceLeaves,
 } from "@/utils/workspaceLayout";
-import { resolveConnectionCredential } from "@/utils/resolveConnectionCredential";
+import {
+  resolveConnectionCredential,
+  resolveFallbackPassword,
+  usesPasswordFallback,
+} from "@/utils/resolveConnectionCredential";
 import { SystemStats } from "@/types/monitoring";
 import { onSessionMonitoringStats } from "@/services/events";
 import { applyTheme, onThemeChange } from "@/themes";
@@ -1968,13 +1973,14 @@ export const useAppStore = create<AppState>((set, get) => {
       set({ sftpLoading: true, sftpError: null });
       try {
         const sessionId = await sftpOpen(config);
-        const homePath = `/home/${config.username as string}`;
         let entries: FileEntry[];
-        let activePath = homePath;
+        let activePath: string;
         try {
-          entries = await sftpListDir(sessionId, homePath);
+          // The configured file browser root, or the remote home
+          activePath = await sftpInitialDir(sessionId);
+          entries = await sftpListDir(sessionId, activePath);
         } catch {
-          // Fall back to root if home dir doesn't exist
+          // Fall back to root if that directory can't be listed
           activePath = "/";
           entries = await sftpListDir(sessionId, "/");
         }
@@ -3194,7 +3200,12 @@ export const useAppStore = create<AppState>((set, get) => {
               const cfg = saved.config.config as Record<string, unknown>;
               const authMethod = cfg.authMethod as string | undefined;
               const savePassword = cfg.savePassword as boolean | undefined;
-              return authMethod === "password" || (authMethod === "key" && savePassword);
+              const authFallback = cfg.authFallback as string[] | undefined;
+              return (
+                authMethod === "password" ||
+                (authMethod === "key" && savePassword) ||
+                (!!authMethod && usesPasswordFallback(authMethod, authFallback))
+              );
             }) || disconnectedAgentsNeedingCreds.length > 0;
           if (needsStoredCredential) {
             const unlocked = await get().requestUnlock();
@@ -3240,12 +3251,23 @@ export const useAppStore = create<AppState>((set, get) => {
             const savePassword = cfg.savePassword as boolean | undefined;
             if (!authMethod) return conn;
             const resolution = await resolveConnectionCredential(conn.id, authMethod, savePassword);
-            if (!resolution.usedStoredCredential || !resolution.password) return conn;
+            const fallbackPassword = await resolveFallbackPassword(
+              conn.id,
+              authMethod,
+              cfg.authFallback as string[] | undefined
+            );
+            const password =
+              resolution.usedStoredCredential && resolution.password ? resolution.password : null;
+            if (password === null && fallbackPassword === null) return conn;
             return {
               ...conn,
               config: {
                 ...conn.config,
-                config: { ...cfg, password: resolution.password },
+                config: {
+                  ...cfg,
+                  ...(password !== null && { password }),
+                  ...(fallbackPassword !== null && { fallbackPassword }),
+                },
               },
             };
           })
@@ -9,6 +9,12 @@ export interface SavedConnection {
   folderId: string | null;
   terminalOptions?: TerminalOptions;
   icon?: string;
+  /** Free-form markdown notes (e.g. runbook steps), max 64 KiB. */
+  notes?: string;
+  /** ID of a saved remote agent to open this connection through. */
+  viaAgent?: string;
+  /** Connect automatically when termiHub launches. */
+  autoConnect?: boolean;
   /** Which external file this connection was loaded from. null = main connections.json. */
   sourceFile?: string | null;
 }
@@ -60,6 +66,10 @@ export interface AgentSettings {
   startingDirectory: string;
   logLevel: "error" | "warn" | "info" | "debug" | "trace";
   verboseTracing: boolean;
+  /** Close detached, idle daemon sessions after this many seconds (0 = never). */
+  daemonIdleTtlSecs: number;
+  /** Fail agent requests that take longer than this many seconds (0 = never). */
+  requestTimeoutSecs: number;
 }
 
 export const DEFAULT_AGENT_SETTINGS: AgentSettings = {
@@ -70,6 +80,8 @@ export const DEFAULT_AGENT_SETTINGS: AgentSettings = {
   startingDirectory: "~",
   logLevel: "info",
   verboseTracing: false,
+  daemonIdleTtlSecs: 0,
+  requestTimeoutSecs: 0,
 };
 
 /** Capabilities reported by a connected remote agent. */
@@ -188,6 +200,75 @@ export interface AppSettings {
   customLanguageGrammars?: CustomLanguageGrammar[];
   experimentalFeaturesEnabled?: boolean;
   updates?: UpdateSettings;
+  /** Named environment profiles; each maps variable names to values for `${var:NAME}`. */
+  profiles?: Record<string, Record<string, string>>;
+  /** Name of the profile used to resolve `${var:NAME}` on connect. */
+  activeProfile?: string;
+  /** Milliseconds to wait after the last terminal resize before applying it (0 = immediately). */
+  resizeDebounceMs?: number;
+  /** Named groups of saved connections launched together via `connectGroup`. */
+  connectionGroups?: ConnectionGroup[];
+  /** File browser bookmarks, global and per connection. */
+  fileBookmarks?: FileBookmark[];
+  /** Saved sets of open sessions, reopened via `restoreSessionLayout`. */
+  sessionLayouts?: SessionLayout[];
+  /** External editor command template, e.g. `nvim {path}`. Unset = first default editor found. */
+  editorCommand?: string;
+}
+
+/** A file browser path saved for quick navigation. */
+export interface FileBookmark {
+  path: string;
+  /** Display name; the path is shown when unset. */
+  label?: string;
+  /** Saved connection the bookmark belongs to; unset for global bookmarks. */
+  connectionId?: string;
+}
+
+/** A named, ordered list of saved connection IDs. */
+export interface ConnectionGroup {
+  id: string;
+  name: string;
+  /** Saved connection IDs, in launch order. */
+  connectionIds: string[];
+}
+
+/** Outcome of launching one member of a connection group. */
+export interface GroupMemberResult {
+  connectionId: string;
+  /** Session ID when the member connected. */
+  sessionId?: string;
+  /** Error message when the member failed to connect. */
+  error?: string;
+}
+
+/** One open session recorded in a session layout. */
+export interface SessionLayoutEntry {
+  /** Saved connection the session was opened from. */
+  connectionId: string;
+  /** Whether the session's tab was the active one. */
+  active: boolean;
+}
+
+/** A named set of open sessions, saved to be reopened together. */
+export interface SessionLayout {
+  name: string;
+  /** Sessions in tab order. */
+  sessions: SessionLayoutEntry[];
+}
+
+/** Outcome of reopening one session of a session layout. */
+export interface RestoredSession extends GroupMemberResult {
+  /** Whether the session's tab was the active one when saved. */
+  active: boolean;
+}
+
+/** Result of restoring a session layout. */
+export interface LayoutRestoreResult {
+  /** Sessions whose connection still exists, in tab order. */
+  sessions: RestoredSession[];
+  /** Saved connections that no longer exist and were skipped. */
+  missingConnectionIds: string[];
 }
 
 /**
@@ -246,3 +327,53 @@ export interface FileEntry {
   modified: string;
   permissions: string | null;
 }
+
+/** Options for a file content search. */
+export interface GrepOptions {
+  caseInsensitive?: boolean;
+  maxResults?: number;
+}
+
+/** A line matched by a file content search. */
+export interface GrepMatch {
+  path: string;
+  /** 1-based line number. */
+  line: number;
+  text: string;
+}
+
+/** Size, modification time and optional checksum of one compared file. */
+export interface FileSummary {
+  size: number;
+  modified: string;
+  /** Hex SHA-256 of the contents, when requested. */
+  checksum: string | null;
+}
+
+/** Result of comparing a local file with a remote one. */
+export interface FileComparison {
+  local: FileSummary;
+  remote: FileSummary;
+  differs: boolean;
+}
+
+/** A file or directory that could not be transferred. */
+export interface TransferFailure {
+  /** Source path of the entry. */
+  path: string;
+  error: string;
+}
+
+/** Outcome of an SFTP directory download or upload. */
+export interface DirTransferSummary {
+  /** Source paths of the files transferred. */
+  succeeded: string[];
+  /** Entries that failed; the rest of the tree was still transferred. */
+  failed: TransferFailure[];
+  /** Symlinks that were not followed, and links back into visited directories. */
+  skipped: string[];
+  bytesTransferred: number;
+}
+
+/** Line-ending normalization applied when writing a file. */
+export type NewlineMode = "preserve" | "lf" | "crlf";
@@ -7,8 +7,20 @@ export interface SystemStats {
   memoryTotalKb: number;
   memoryAvailableKb: number;
   memoryUsedPercent: number;
+  /** Kernel buffers in KB (absent from older agents). */
+  memoryBuffersKb?: number;
+  /** Page cache plus reclaimable slab in KB (absent from older agents). */
+  memoryCachedKb?: number;
+  swapTotalKb?: number;
+  swapUsedKb?: number;
   diskTotalKb: number;
   diskUsedKb: number;
   diskUsedPercent: number;
   osInfo: string;
+  /** Bytes received on non-loopback interfaces since boot (0 unless "net" is collected). */
+  networkRxBytes?: number;
+  /** Bytes transmitted on non-loopback interfaces since boot (0 unless "net" is collected). */
+  networkTxBytes?: number;
+  /** Number of processes (0 unless "processes" is collected). */
+  processCount?: number;
 }
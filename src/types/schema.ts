@@ -43,7 +43,7 @@ export interface Condition {
 /** Kind of path accepted by a FilePath field. */
 export type FilePathKind = "file" | "directory" | "any";
 
-/** An option in a Select dropdown. */
+/** An option in a Select dropdown or MultiSelect list. */
 export interface SelectOption {
   value: string;
   label: string;
@@ -59,6 +59,7 @@ export type FieldType =
   | { type: "number"; min?: number; max?: number }
   | { type: "boolean" }
   | { type: "select"; options: SelectOption[] }
+  | { type: "multiSelect"; options: SelectOption[] }
   | { type: "port" }
   | { type: "serialPort" }
   | { type: "filePath"; kind: FilePathKind }
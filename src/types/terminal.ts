@@ -1,5 +1,31 @@
 export type SessionId = string;
 
+/** Out-of-band control of a serial session's line. */
+export type LineControl =
+  | { type: "break"; durationMs: number }
+  | { type: "setDtr"; level: boolean }
+  | { type: "setRts"; level: boolean };
+
+/** How a file is sent to a session: raw bytes or the XMODEM protocol. */
+export type FileSendProtocol = "ascii" | "xmodem";
+
+/** A session whose recent output contains the text searched for. */
+export interface SessionSearchResult {
+  sessionId: SessionId;
+  /** Number of matches; counting stops at 1000. */
+  matchCount: number;
+  /** Plain text of the line holding the first match. */
+  firstMatchPreview: string;
+}
+
+/** Levels of a serial session's modem status input lines. */
+export interface ModemStatus {
+  cts: boolean;
+  dsr: boolean;
+  dcd: boolean;
+  ri: boolean;
+}
+
 export type ShellType =
   | "zsh"
   | "bash"
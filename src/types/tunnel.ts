@@ -18,6 +18,8 @@ export interface RemoteForwardConfig {
 export interface DynamicForwardConfig {
   localHost: string;
   localPort: number;
+  /** Accept SOCKS5 UDP ASSOCIATE (relayed via `nc -u` on the server). */
+  enableUdp?: boolean;
 }
 
 /** Tagged union of tunnel types matching the Rust TunnelType enum. */
@@ -34,6 +36,12 @@ export interface TunnelConfig {
   tunnelType: TunnelType;
   autoStart: boolean;
   reconnectOnDisconnect: boolean;
+  /** Reconnect attempts before giving up (default 10). */
+  reconnectMaxAttempts?: number;
+  /** Delay before the first reconnect attempt in seconds, doubling per attempt (default 1). */
+  reconnectInitialBackoffSecs?: number;
+  /** Upper bound on the delay between reconnect attempts in seconds (default 30). */
+  reconnectMaxBackoffSecs?: number;
 }
 
 /** Current status of a tunnel. */
@@ -47,6 +55,17 @@ export interface TunnelStats {
   totalConnections: number;
 }
 
+/** Traffic totals and throughput for a tunnel. */
+export interface TunnelMetrics {
+  tunnelId: string;
+  /** Totals since the tunnel was last (re)started. */
+  stats: TunnelStats;
+  /** Rolling send rate estimate in bytes per second. */
+  bytesSentPerSec: number;
+  /** Rolling receive rate estimate in bytes per second. */
+  bytesReceivedPerSec: number;
+}
+
 /** Combined runtime state for a tunnel. */
 export interface TunnelState {
   tunnelId: string;
@@ -0,0 +1,23 @@
+import { describe, it, expect } from "vitest";
+import { connectionLaunchConfig } from "./connectionLaunchConfig";
+import { SavedConnection } from "@/types/connection";
+
+const ssh: SavedConnection = {
+  id: "conn-1",
+  name: "Build box",
+  config: { type: "ssh", config: { host: "10.0.0.5", username: "dev" } },
+  folderId: null,
+};
+
+describe("connectionLaunchConfig", () => {
+  it("returns the config unchanged without viaAgent", () => {
+    expect(connectionLaunchConfig(ssh)).toBe(ssh.config);
+  });
+
+  it("wraps the config as a remote session on the agent", () => {
+    expect(connectionLaunchConfig({ ...ssh, viaAgent: "gateway" })).toEqual({
+      type: "remote-session",
+      config: { host: "10.0.0.5", username: "dev", agentId: "gateway", sessionType: "ssh" },
+    });
+  });
+});
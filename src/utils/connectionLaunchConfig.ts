@@ -0,0 +1,23 @@
+import { SavedConnection } from "@/types/connection";
+import { ConnectionConfig } from "@/types/terminal";
+
+/**
+ * Build the config used to open a saved connection.
+ *
+ * Connections with `viaAgent` set are wrapped as a `remote-session` so the
+ * session is created on that agent and its I/O tunnels through it. Other
+ * connections are returned unchanged.
+ */
+export function connectionLaunchConfig(connection: SavedConnection): ConnectionConfig {
+  if (!connection.viaAgent) {
+    return connection.config;
+  }
+  return {
+    type: "remote-session",
+    config: {
+      ...connection.config.config,
+      agentId: connection.viaAgent,
+      sessionType: connection.config.type,
+    },
+  };
+}
@@ -0,0 +1,35 @@
+import { describe, it, expect, afterEach } from "vitest";
+import { RAW_KEY_MODE_ATTRIBUTE, isRawKeyModeEvent } from "./rawKeyMode";
+
+function keyEventOn(target: EventTarget): KeyboardEvent {
+  let received: KeyboardEvent | null = null;
+  target.addEventListener("keydown", (e) => {
+    received = e as KeyboardEvent;
+  });
+  target.dispatchEvent(new KeyboardEvent("keydown", { key: "Escape", bubbles: true }));
+  return received!;
+}
+
+describe("isRawKeyModeEvent", () => {
+  afterEach(() => {
+    document.body.innerHTML = "";
+  });
+
+  it("detects keys typed inside a raw key mode terminal", () => {
+    const terminal = document.createElement("div");
+    terminal.setAttribute(RAW_KEY_MODE_ATTRIBUTE, "");
+    const textarea = document.createElement("textarea");
+    terminal.appendChild(textarea);
+    document.body.appendChild(terminal);
+
+    expect(isRawKeyModeEvent(keyEventOn(textarea))).toBe(true);
+  });
+
+  it("ignores keys typed elsewhere", () => {
+    const input = document.createElement("input");
+    document.body.appendChild(input);
+
+    expect(isRawKeyModeEvent(keyEventOn(input))).toBe(false);
+    expect(isRawKeyModeEvent(keyEventOn(window))).toBe(false);
+  });
+});
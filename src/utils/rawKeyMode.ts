@@ -0,0 +1,11 @@
+/** Attribute set on the element of a terminal whose session runs in raw key mode. */
+export const RAW_KEY_MODE_ATTRIBUTE = "data-raw-key-mode";
+
+/**
+ * Whether a key event is headed for a terminal in raw key mode. Such a
+ * terminal gets every key, so capture-phase shortcut handlers must leave
+ * the event alone.
+ */
+export function isRawKeyModeEvent(e: KeyboardEvent): boolean {
+  return e.target instanceof Element && e.target.closest(`[${RAW_KEY_MODE_ATTRIBUTE}]`) !== null;
+}
@@ -0,0 +1,51 @@
+import { describe, it, expect, vi } from "vitest";
+import { isDestinationExistsError, renameWithOverwritePrompt } from "./renameWithOverwritePrompt";
+
+describe("isDestinationExistsError", () => {
+  it("matches the backend collision message", () => {
+    expect(isDestinationExistsError("Destination already exists: /tmp/b.txt")).toBe(true);
+    expect(isDestinationExistsError(new Error("Destination already exists: /b"))).toBe(true);
+  });
+
+  it("ignores other errors", () => {
+    expect(isDestinationExistsError("Permission denied: /root")).toBe(false);
+  });
+});
+
+describe("renameWithOverwritePrompt", () => {
+  it("renames without overwrite when there is no collision", async () => {
+    const rename = vi.fn(() => Promise.resolve());
+    const confirm = vi.fn(() => true);
+    expect(await renameWithOverwritePrompt(rename, "/b", confirm)).toBe(true);
+    expect(rename).toHaveBeenCalledTimes(1);
+    expect(rename).toHaveBeenCalledWith(false);
+    expect(confirm).not.toHaveBeenCalled();
+  });
+
+  it("retries with overwrite after the user confirms", async () => {
+    const rename = vi
+      .fn<(overwrite: boolean) => Promise<void>>()
+      .mockRejectedValueOnce("Destination already exists: /b")
+      .mockResolvedValueOnce(undefined);
+    const confirm = vi.fn(() => true);
+    expect(await renameWithOverwritePrompt(rename, "/b", confirm)).toBe(true);
+    expect(rename).toHaveBeenNthCalledWith(2, true);
+  });
+
+  it("leaves the destination alone when the user declines", async () => {
+    const rename = vi
+      .fn<(overwrite: boolean) => Promise<void>>()
+      .mockRejectedValueOnce("Destination already exists: /b");
+    expect(await renameWithOverwritePrompt(rename, "/b", () => false)).toBe(false);
+    expect(rename).toHaveBeenCalledTimes(1);
+  });
+
+  it("rethrows unrelated errors", async () => {
+    const rename = vi
+      .fn<(overwrite: boolean) => Promise<void>>()
+      .mockRejectedValueOnce("Permission denied: /b");
+    await expect(renameWithOverwritePrompt(rename, "/b", () => true)).rejects.toBe(
+      "Permission denied: /b"
+    );
+  });
+});
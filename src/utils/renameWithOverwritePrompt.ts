@@ -0,0 +1,35 @@
+/**
+ * Helpers for renames/moves that may collide with an existing destination.
+ *
+ * The backend refuses to replace an existing destination unless the call
+ * passes `overwrite: true`, and reports the collision as a
+ * "Destination already exists: <path>" error.
+ */
+
+/** Whether an invoke error reports that the rename destination already exists. */
+export function isDestinationExistsError(err: unknown): boolean {
+  const message = err instanceof Error ? err.message : String(err);
+  return message.includes("Destination already exists");
+}
+
+/**
+ * Run `rename` without overwriting; if the destination exists, ask the user
+ * and retry with overwrite enabled.
+ *
+ * Returns `false` when the user declined to replace the destination.
+ */
+export async function renameWithOverwritePrompt(
+  rename: (overwrite: boolean) => Promise<void>,
+  destPath: string,
+  confirm: (message: string) => boolean = (message) => window.confirm(message)
+): Promise<boolean> {
+  try {
+    await rename(false);
+    return true;
+  } catch (err) {
+    if (!isDestinationExistsError(err)) throw err;
+    if (!confirm(`"${destPath}" already exists. Replace it?`)) return false;
+    await rename(true);
+    return true;
+  }
+}
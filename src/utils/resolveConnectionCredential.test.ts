@@ -5,7 +5,11 @@ vi.mock("@/services/api", () => ({
 }));
 
 import { resolveCredential } from "@/services/api";
-import { resolveConnectionCredential } from "./resolveConnectionCredential";
+import {
+  resolveConnectionCredential,
+  resolveFallbackPassword,
+  usesPasswordFallback,
+} from "./resolveConnectionCredential";
 
 const mockedResolveCredential = vi.mocked(resolveCredential);
 
@@ -123,3 +127,40 @@ describe("resolveConnectionCredential", () => {
     });
   });
 });
+
+describe("resolveFallbackPassword", () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it("resolves the stored password when password is a fallback method", async () => {
+    mockedResolveCredential.mockResolvedValue("login-pw");
+
+    const result = await resolveFallbackPassword("conn-1", "key", ["agent", "password"]);
+
+    expect(mockedResolveCredential).toHaveBeenCalledWith("conn-1", "password");
+    expect(result).toBe("login-pw");
+  });
+
+  it("skips the store without a password fallback", async () => {
+    expect(await resolveFallbackPassword("conn-2", "key", ["agent"])).toBeNull();
+    expect(await resolveFallbackPassword("conn-2", "key")).toBeNull();
+    expect(await resolveFallbackPassword("conn-2", "password", ["password"])).toBeNull();
+    expect(mockedResolveCredential).not.toHaveBeenCalled();
+  });
+
+  it("treats empty and failed lookups as not found", async () => {
+    mockedResolveCredential.mockResolvedValue("");
+    expect(await resolveFallbackPassword("conn-3", "agent", ["password"])).toBeNull();
+
+    mockedResolveCredential.mockRejectedValue(new Error("Store locked"));
+    expect(await resolveFallbackPassword("conn-3", "agent", ["password"])).toBeNull();
+  });
+
+  it("detects the password fallback only for other primary methods", () => {
+    expect(usesPasswordFallback("key", ["password"])).toBe(true);
+    expect(usesPasswordFallback("agent", ["key", "password"])).toBe(true);
+    expect(usesPasswordFallback("password", ["password"])).toBe(false);
+    expect(usesPasswordFallback("key", undefined)).toBe(false);
+  });
+});
@@ -65,3 +65,33 @@ export async function resolveConnectionCredential(
   // agent auth or key without savePassword — no credential to resolve
   return { password: null, usedStoredCredential: false, credentialType: "password" };
 }
+
+/**
+ * Whether the Password fallback method is configured for an SSH connection
+ * whose primary method is another one, so it needs a login password besides
+ * the primary method's secret in `password`.
+ */
+export function usesPasswordFallback(authMethod: string, authFallback?: string[]): boolean {
+  return authMethod !== "password" && (authFallback ?? []).includes("password");
+}
+
+/**
+ * Resolve the login password for the Password fallback method from the
+ * credential store, where it is kept as the connection's `"password"`.
+ *
+ * Returns null when the fallback is not configured or nothing usable is
+ * stored. Errors from the store are caught and treated as "not found".
+ */
+export async function resolveFallbackPassword(
+  connectionId: string,
+  authMethod: string,
+  authFallback?: string[]
+): Promise<string | null> {
+  if (!usesPasswordFallback(authMethod, authFallback)) return null;
+  try {
+    const raw = await resolveCredential(connectionId, "password");
+    return raw === "" ? null : raw;
+  } catch {
+    return null;
+  }
+}
@@ -6,6 +6,7 @@ import {
   findPasswordPromptInfo,
   filterRuntimeOptions,
   filterCredentialFields,
+  applyWslDistributions,
 } from "./schemaDefaults";
 
 function textField(key: string, opts: Partial<SettingsField> = {}): SettingsField {
@@ -409,3 +410,55 @@ describe("filterRuntimeOptions", () => {
     expect(imageField).toEqual(RUNTIME_SCHEMA.groups[0].fields[0]);
   });
 });
+
+const WSL_SCHEMA: SettingsSchema = {
+  groups: [
+    {
+      key: "wsl",
+      label: "WSL",
+      fields: [
+        {
+          key: "distribution",
+          label: "Distribution",
+          fieldType: {
+            type: "select",
+            options: [{ value: "Ubuntu", label: "Ubuntu" }],
+          },
+          required: true,
+          default: "Ubuntu",
+        },
+        textField("user"),
+      ],
+    },
+  ],
+};
+
+describe("applyWslDistributions", () => {
+  it("replaces the distribution options with the listed distributions", () => {
+    const result = applyWslDistributions(WSL_SCHEMA, ["Ubuntu", "Debian"]);
+    const distributionField = result.groups[0].fields.find((f) => f.key === "distribution");
+    expect(distributionField?.fieldType).toEqual({
+      type: "select",
+      options: [
+        { value: "Ubuntu", label: "Ubuntu" },
+        { value: "Debian", label: "Debian" },
+      ],
+    });
+  });
+
+  it("keeps the schema options when no distributions are listed", () => {
+    expect(applyWslDistributions(WSL_SCHEMA, [])).toBe(WSL_SCHEMA);
+  });
+
+  it("does not mutate the original schema", () => {
+    const original = JSON.parse(JSON.stringify(WSL_SCHEMA));
+    applyWslDistributions(WSL_SCHEMA, ["Debian"]);
+    expect(WSL_SCHEMA).toEqual(original);
+  });
+
+  it("leaves other fields unchanged", () => {
+    const result = applyWslDistributions(WSL_SCHEMA, ["Debian"]);
+    const userField = result.groups[0].fields.find((f) => f.key === "user");
+    expect(userField).toEqual(WSL_SCHEMA.groups[0].fields[1]);
+  });
+});
@@ -183,3 +183,37 @@ export function filterRuntimeOptions(
     })),
   };
 }
+
+/**
+ * Replace the `distribution` select options in a WSL connection schema with
+ * the distributions currently installed on the system.
+ *
+ * The schema lists the distributions detected when the backend built it;
+ * this keeps the select in step with distributions installed or removed
+ * since. An empty list leaves the schema unchanged.
+ *
+ * Returns a shallow-cloned schema. The original schema is never mutated.
+ */
+export function applyWslDistributions(
+  schema: SettingsSchema,
+  distributions: string[]
+): SettingsSchema {
+  if (distributions.length === 0) return schema;
+
+  return {
+    groups: schema.groups.map((group) => ({
+      ...group,
+      fields: group.fields.map((field) => {
+        if (field.key !== "distribution" || field.fieldType.type !== "select") return field;
+
+        return {
+          ...field,
+          fieldType: {
+            ...field.fieldType,
+            options: distributions.map((d) => ({ value: d, label: d })),
+          },
+        };
+      }),
+    })),
+  };
+}